@@ -9,8 +9,10 @@
 
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use qm_agent::cnf_dnf::{self, OptimizedFor};
+use qm_agent::qm::classic::petrick::{self, PITable2, PetrickScratch};
 use qm_agent::qm::{Enc16, Enc32, Enc64};
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::collections::HashSet;
 use std::hint::black_box;
 
 /// Generate a random CNF formula for benchmarking
@@ -200,6 +202,41 @@ fn bench_minimal_with_pruning(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark clause preprocessing on a Petrick-like CNF with many redundant clauses
+fn bench_clause_reduction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clause_reduction");
+
+    // Minterms with identical PI sets give duplicate clauses, and minterms covered by
+    // a superset of another minterm's PIs give superset clauses
+    let base = generate_random_cnf(16, 8, 3, 42);
+    let mut cnf = Vec::new();
+    for (i, &clause) in base.iter().enumerate() {
+        cnf.push(clause);
+        cnf.push(clause);
+        cnf.push(clause | (1u64 << (i % 16)) | (1u64 << ((i + 5) % 16)));
+    }
+    group.throughput(Throughput::Elements(cnf.len() as u64));
+
+    group.bench_function("raw", |b| {
+        b.iter(|| {
+            cnf_dnf::cnf_to_dnf_minimal::<Enc16>(
+                black_box(&cnf),
+                black_box(16),
+                OptimizedFor::AutoDetect,
+            )
+        });
+    });
+
+    group.bench_function("reduced", |b| {
+        b.iter(|| {
+            let (reduced, _) = cnf_dnf::reduce_cnf(black_box(&cnf));
+            cnf_dnf::cnf_to_dnf_minimal::<Enc16>(&reduced, black_box(16), OptimizedFor::AutoDetect)
+        });
+    });
+
+    group.finish();
+}
+
 /// Benchmark encoding comparison for 64-bit problems
 fn bench_64bit_comparison(c: &mut Criterion) {
     let mut group = c.benchmark_group("64bit_comparison");
@@ -267,13 +304,51 @@ fn bench_conjunction_density(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark Petrick's method on a cyclic core of 20 PIs, with and without reused buffers
+fn bench_petrick_core(c: &mut Criterion) {
+    let mut group = c.benchmark_group("petrick_core");
+
+    // Minterm i is covered by PIs i and i+1 (mod 20), every third also by PI i+7
+    let n_pis = 20u32;
+    let pi_table: PITable2<u32> = (0..n_pis)
+        .map(|i| {
+            let mut pis: HashSet<u32> = [i, (i + 1) % n_pis].into_iter().collect();
+            if i % 3 == 0 {
+                pis.insert((i + 7) % n_pis);
+            }
+            (i, pis)
+        })
+        .collect();
+    group.throughput(Throughput::Elements(pi_table.len() as u64));
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| petrick::petricks_method::<Enc16>(black_box(&pi_table), false));
+    });
+
+    let mut scratch = PetrickScratch::new();
+    group.bench_function("scratch", |b| {
+        b.iter(|| {
+            petrick::petricks_method_with_scratch::<Enc16>(
+                black_box(&pi_table),
+                true,
+                false,
+                &mut scratch,
+            )
+        });
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_encoding_types,
     bench_problem_sizes,
     bench_encoding_variants,
     bench_minimal_with_pruning,
+    bench_clause_reduction,
     bench_64bit_comparison,
     bench_conjunction_density,
+    bench_petrick_core,
 );
 criterion_main!(benches);
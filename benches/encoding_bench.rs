@@ -10,12 +10,16 @@
 // cargo bench --bench encoding_bench -- reduce_minterms_16bit
 // cargo bench --bench encoding_bench -- reduce_minterms_32bit
 // cargo bench --bench encoding_bench -- reduce_minterms_64bit
+// cargo bench --bench encoding_bench -- reduce_minterms_dense
 
 use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
 use qm_agent::qm::classic::{
-    Enc16, Enc32, Enc64, MintermSet, minterm_to_string, minterms_to_string, reduce_minterms,
-    reduce_minterms_classic,
+    Enc16, Enc32, Enc64, MintermSet, ReduceOptions, ReducerChoice, minterm_to_string,
+    minterms_to_string, petrick, reduce_minterms, reduce_minterms_classic, reduce_qm,
 };
+use qm_agent::qm::random::random_function;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
 /// Generate minterms for a given number of variables
 /// This creates a realistic problem with about 40% coverage
@@ -46,7 +50,7 @@ fn bench_reduce_minterms_32bit(c: &mut Criterion) {
         group.bench_with_input(
             BenchmarkId::new("optimized_32bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
-            |b, minterms| b.iter(|| reduce_minterms::<Enc32>(black_box(minterms), false)),
+            |b, minterms| b.iter(|| reduce_minterms::<Enc32>(black_box(minterms), false).unwrap()),
         );
     }
 
@@ -68,13 +72,33 @@ fn bench_reduce_minterms_16bit(c: &mut Criterion) {
         group.bench_with_input(
             BenchmarkId::new("optimized_16bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
-            |b, minterms| b.iter(|| reduce_minterms::<Enc16>(black_box(minterms), false)),
+            |b, minterms| b.iter(|| reduce_minterms::<Enc16>(black_box(minterms), false).unwrap()),
         );
     }
 
     group.finish();
 }
 
+/// Benchmark a dense 16-variable problem, where most pairs merge
+fn bench_reduce_minterms_dense(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reduce_minterms_dense");
+
+    // About 90% of all minterms
+    let minterms: Vec<u32> = (0..1u32 << 16)
+        .filter(|i| (i.wrapping_mul(7919)) % 100 < 90)
+        .collect();
+    let size = minterms.len();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(
+        BenchmarkId::new("optimized_16bit", format!("16_vars_{}_terms", size)),
+        &minterms,
+        |b, minterms| b.iter(|| reduce_minterms::<Enc16>(black_box(minterms), false).unwrap()),
+    );
+
+    group.finish();
+}
+
 /// Benchmark the classic O(n²) algorithm - 32-bit mode
 fn bench_reduce_minterms_classic_32bit(c: &mut Criterion) {
     let mut group = c.benchmark_group("reduce_minterms_classic_32bit");
@@ -89,7 +113,9 @@ fn bench_reduce_minterms_classic_32bit(c: &mut Criterion) {
             BenchmarkId::new("classic_32bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
             |b, minterms| {
-                b.iter(|| reduce_minterms_classic::<Enc32>(black_box(minterms), *n_vars, false))
+                b.iter(|| {
+                    reduce_minterms_classic::<Enc32>(black_box(minterms), *n_vars, false).unwrap()
+                })
             },
         );
     }
@@ -114,7 +140,9 @@ fn bench_reduce_minterms_classic_16bit(c: &mut Criterion) {
             BenchmarkId::new("classic_16bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
             |b, minterms| {
-                b.iter(|| reduce_minterms_classic::<Enc16>(black_box(minterms), *n_vars, false))
+                b.iter(|| {
+                    reduce_minterms_classic::<Enc16>(black_box(minterms), *n_vars, false).unwrap()
+                })
             },
         );
     }
@@ -338,7 +366,7 @@ fn bench_full_reduction_32bit(c: &mut Criterion) {
                     let mut current = minterms.clone();
                     let mut iteration = 0;
                     loop {
-                        let next = reduce_minterms::<Enc32>(black_box(&current), false);
+                        let next = reduce_minterms::<Enc32>(black_box(&current), false).unwrap();
                         iteration += 1;
                         if current == next || iteration > 100 {
                             break;
@@ -354,6 +382,53 @@ fn bench_full_reduction_32bit(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark building the prime implicant coverage (matrix vs. map-based table)
+fn bench_prime_implicant_table(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prime_implicant_table");
+
+    for n_vars in [8, 10, 12].iter() {
+        let minterms: Vec<u32> = generate_minterms(*n_vars)
+            .into_iter()
+            .map(|x| x as u32)
+            .collect();
+        let primes = reduce_qm::<Enc16>(
+            &minterms,
+            *n_vars,
+            ReduceOptions {
+                reducer: ReducerChoice::Bucketed,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let label = format!("{}_vars_{}_pis", n_vars, primes.len());
+
+        group.throughput(Throughput::Elements((primes.len() * minterms.len()) as u64));
+        group.bench_with_input(
+            BenchmarkId::new("coverage_matrix", &label),
+            &(&primes, &minterms),
+            |b, (primes, minterms)| {
+                b.iter(|| {
+                    petrick::create_coverage_matrix::<Enc16>(black_box(primes), black_box(minterms))
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("pi_table", &label),
+            &(&primes, &minterms),
+            |b, (primes, minterms)| {
+                b.iter(|| {
+                    petrick::create_prime_implicant_table::<Enc16>(
+                        black_box(primes),
+                        black_box(minterms),
+                    )
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
 /// Full end-to-end benchmark - 16-bit mode
 fn bench_full_reduction_16bit(c: &mut Criterion) {
     let mut group = c.benchmark_group("full_reduction_16bit");
@@ -378,7 +453,7 @@ fn bench_full_reduction_16bit(c: &mut Criterion) {
                     let mut current = minterms.clone();
                     let mut iteration = 0;
                     loop {
-                        let next = reduce_minterms::<Enc16>(black_box(&current), false);
+                        let next = reduce_minterms::<Enc16>(black_box(&current), false).unwrap();
                         iteration += 1;
                         if current == next || iteration > 100 {
                             break;
@@ -409,7 +484,7 @@ fn bench_reduce_minterms_64bit(c: &mut Criterion) {
         group.bench_with_input(
             BenchmarkId::new("optimized_64bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
-            |b, minterms| b.iter(|| reduce_minterms::<Enc64>(black_box(minterms), false)),
+            |b, minterms| b.iter(|| reduce_minterms::<Enc64>(black_box(minterms), false).unwrap()),
         );
     }
 
@@ -433,7 +508,9 @@ fn bench_reduce_minterms_classic_64bit(c: &mut Criterion) {
             BenchmarkId::new("classic_64bit", format!("{}_vars_{}_terms", n_vars, size)),
             &minterms,
             |b, minterms| {
-                b.iter(|| reduce_minterms_classic::<Enc64>(black_box(minterms), *n_vars, false))
+                b.iter(|| {
+                    reduce_minterms_classic::<Enc64>(black_box(minterms), *n_vars, false).unwrap()
+                })
             },
         );
     }
@@ -568,7 +645,7 @@ fn bench_full_reduction_64bit(c: &mut Criterion) {
                     let mut current = minterms.clone();
                     let mut iteration = 0;
                     loop {
-                        let next = reduce_minterms::<Enc64>(black_box(&current), false);
+                        let next = reduce_minterms::<Enc64>(black_box(&current), false).unwrap();
                         iteration += 1;
                         if current == next || iteration > 100 {
                             break;
@@ -584,11 +661,57 @@ fn bench_full_reduction_64bit(c: &mut Criterion) {
     group.finish();
 }
 
+/// Reduction to the fixed point with each reducer choice, over random functions
+fn bench_reducer_choice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reducer_choice");
+    group.sample_size(20);
+
+    let mut rng = StdRng::seed_from_u64(42);
+    for n_vars in [6, 10, 14] {
+        for density in [0.1, 0.5, 0.9] {
+            let minterms: Vec<u32> = random_function(&mut rng, n_vars, density)
+                .into_iter()
+                .map(|x| x as u32)
+                .collect();
+            let label = format!("{}_vars_{}_terms", n_vars, minterms.len());
+
+            group.throughput(Throughput::Elements(minterms.len() as u64));
+            for reducer in [
+                ReducerChoice::Auto,
+                ReducerChoice::Classic,
+                ReducerChoice::Bucketed,
+                ReducerChoice::EarlyPruning,
+            ] {
+                group.bench_with_input(
+                    BenchmarkId::new(reducer.to_string().replace(' ', "_"), &label),
+                    &minterms,
+                    |b, minterms| {
+                        b.iter(|| {
+                            reduce_qm::<Enc16>(
+                                black_box(minterms),
+                                n_vars,
+                                ReduceOptions {
+                                    reducer,
+                                    ..Default::default()
+                                },
+                            )
+                            .unwrap()
+                        })
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_reduce_minterms_16bit,
     bench_reduce_minterms_32bit,
     bench_reduce_minterms_64bit,
+    bench_reduce_minterms_dense,
     bench_reduce_minterms_classic_16bit,
     bench_reduce_minterms_classic_32bit,
     bench_reduce_minterms_classic_64bit,
@@ -604,8 +727,10 @@ criterion_group!(
     bench_minterm_set_get_16bit,
     bench_minterm_set_get_32bit,
     bench_minterm_set_get_64bit,
+    bench_prime_implicant_table,
     bench_full_reduction_16bit,
     bench_full_reduction_32bit,
     bench_full_reduction_64bit,
+    bench_reducer_choice,
 );
 criterion_main!(benches);
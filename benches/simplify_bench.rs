@@ -0,0 +1,49 @@
+// Benchmark the if-then-else analysis on 14 boolean variables and 12 branches
+//
+// Dead code analysis and the truth table share one enumeration of the 2^14 assignments;
+// compare against an earlier revision with `--save-baseline` / `--baseline`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use qm_agent::simplify::analyzer::build_truth_table;
+use qm_agent::simplify::dead_code::analyze_with_truth_table;
+use qm_agent::simplify::{BoolExpr, BranchSet, analyze_branches};
+use std::hint::black_box;
+
+const N_VARIABLES: usize = 14;
+const N_BRANCHES: usize = 12;
+
+/// Branch `k`: `(v[k] && !v[k+3]) || (v[k+7] && v[k+9])`, indices mod 14
+fn branch_set() -> BranchSet {
+    let var = |i: usize| BoolExpr::var(&format!("v{:02}", i % N_VARIABLES));
+    let mut branch_set = BranchSet::new();
+    for k in 0..N_BRANCHES {
+        let condition = BoolExpr::or(
+            BoolExpr::and(var(k), BoolExpr::negate(var(k + 3))),
+            BoolExpr::and(var(k + 7), var(k + 9)),
+        );
+        branch_set.add_branch(condition, &format!("return {}", k % 4));
+    }
+    branch_set.set_default("return -1");
+    branch_set
+}
+
+fn bench_analysis(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simplify_14vars_12branches");
+    group.sample_size(10);
+    let branches = branch_set();
+
+    group.bench_function("analyze_branches", |b| {
+        b.iter(|| analyze_branches(black_box(&branches)).unwrap())
+    });
+    group.bench_function("build_truth_table", |b| {
+        b.iter(|| build_truth_table(black_box(&branches)).unwrap())
+    });
+    group.bench_function("analyze_with_truth_table", |b| {
+        b.iter(|| analyze_with_truth_table(black_box(&branches)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_analysis);
+criterion_main!(benches);
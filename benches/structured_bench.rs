@@ -0,0 +1,123 @@
+// Benchmark comparing the three reducers on structured functions
+//
+// The other benches use pseudo-random 40%-density functions; the reducers diverge most
+// on structured inputs:
+// 1. Adder carry and comparator: long chains of mergeable pairs
+// 2. Parity: no two minterms are adjacent, every bit-count bucket is compared for nothing
+// 3. Threshold (symmetric): large, evenly filled buckets
+// 4. Sparse decoder: few minterms spread over many variables
+//
+// Before timing, every reducer is run to its fixed point on every input and the prime
+// implicants are compared, so the benchmark doubles as a correctness test.
+//
+// To run these benchmarks:
+// cargo bench --bench structured_bench
+//
+// To run a single family:
+// cargo bench --bench structured_bench -- structured_reducers/classic/parity
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use qm_agent::qm::classic::{
+    Enc16, reduce_minterms, reduce_minterms_classic, reduce_minterms_with_early_pruning,
+};
+use qm_agent::qm::random::{adder_carry, comparator, decoder, parity, threshold};
+use std::hint::black_box;
+
+/// Structured inputs: (family name, number of variables, minterms)
+fn families() -> Vec<(&'static str, usize, Vec<u32>)> {
+    let sparse_lines: Vec<u64> = (0..1u64 << 10).step_by(37).collect();
+    let families = [
+        ("adder_carry", 8, adder_carry(4)),
+        ("adder_carry", 12, adder_carry(6)),
+        ("comparator", 8, comparator(4)),
+        ("comparator", 12, comparator(6)),
+        ("parity", 8, parity(8)),
+        ("parity", 12, parity(12)),
+        ("threshold", 8, threshold(8, 4)),
+        ("threshold", 12, threshold(12, 6)),
+        ("decoder", 11, decoder(10, &sparse_lines)),
+    ];
+    families
+        .into_iter()
+        .map(|(name, n_vars, minterms)| {
+            (
+                name,
+                n_vars,
+                minterms.into_iter().map(|m| m as u32).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Apply `reduce` until nothing merges; the result holds the prime implicants, sorted
+fn fixed_point(minterms: &[u32], reduce: impl Fn(&[u32]) -> Vec<u32>) -> Vec<u32> {
+    let mut current = minterms.to_vec();
+    current.sort_unstable();
+    loop {
+        let mut next = reduce(&current);
+        next.sort_unstable();
+        next.dedup();
+        if next == current {
+            return current;
+        }
+        current = next;
+    }
+}
+
+/// All reducers must find the same prime implicants on every family
+fn assert_reducers_agree() {
+    for (name, n_vars, minterms) in families() {
+        let optimized = fixed_point(&minterms, |m| reduce_minterms::<Enc16>(m, false).unwrap());
+        let classic = fixed_point(&minterms, |m| {
+            reduce_minterms_classic::<Enc16>(m, n_vars, false).unwrap()
+        });
+        let pruning = fixed_point(&minterms, |m| {
+            reduce_minterms_with_early_pruning::<Enc16>(m, false).unwrap()
+        });
+        assert_eq!(optimized, classic, "{} {} vars: classic", name, n_vars);
+        assert_eq!(
+            optimized, pruning,
+            "{} {} vars: early pruning",
+            name, n_vars
+        );
+    }
+}
+
+/// Benchmark one reduction pass of each reducer on each family
+fn bench_structured_reducers(c: &mut Criterion) {
+    assert_reducers_agree();
+
+    let mut group = c.benchmark_group("structured_reducers");
+    for (name, n_vars, minterms) in families() {
+        let id = format!("{}/{}_vars_{}_terms", name, n_vars, minterms.len());
+        group.throughput(Throughput::Elements(minterms.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("optimized", &id),
+            &minterms,
+            |b, minterms| b.iter(|| reduce_minterms::<Enc16>(black_box(minterms), false).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("classic", &id),
+            &minterms,
+            |b, minterms| {
+                b.iter(|| {
+                    reduce_minterms_classic::<Enc16>(black_box(minterms), n_vars, false).unwrap()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("early_pruning", &id),
+            &minterms,
+            |b, minterms| {
+                b.iter(|| {
+                    reduce_minterms_with_early_pruning::<Enc16>(black_box(minterms), false).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_structured_reducers);
+criterion_main!(benches);
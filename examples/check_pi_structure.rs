@@ -34,9 +34,8 @@ fn extract_minterms(branches: &[(&str, &str)]) -> Vec<u64> {
     bs.set_default("return false");
     let table = build_truth_table(&bs).unwrap();
     table
-        .output_groups
-        .get("return true")
-        .cloned()
+        .group("return true")
+        .map(|group| group.minterms.clone())
         .unwrap_or_default()
         .iter()
         .map(|&x| x as u64)
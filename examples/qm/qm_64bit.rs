@@ -2,7 +2,7 @@
 // This allows working with up to 64 Boolean variables
 
 use qm_agent::qm::random::generate_random_minterms;
-use qm_agent::{QMSolver, Enc64};
+use qm_agent::prelude::*;
 
 fn main() {
 
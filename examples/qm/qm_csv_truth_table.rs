@@ -0,0 +1,55 @@
+// Minimize a truth table read from CSV
+//
+// The CSV has one column per input variable and a final output column (0/1/X); the
+// header names the variables. Input combinations without a row are don't-cares.
+//
+// To run:
+// cargo run --example qm_csv_truth_table --features io
+// cargo run --example qm_csv_truth_table --features io -- path/to/table.csv
+
+use qm_agent::qm::io::{read_truth_table_csv, write_cover_csv};
+use qm_agent::{Enc32, QMSolver};
+use std::fs::File;
+
+const DEFAULT_CSV: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/qm/truth_table.csv");
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CSV.to_string());
+    let table = read_truth_table_csv(File::open(&path)?)?;
+
+    println!("=== Minimizing '{}' from {} ===\n", table.output, path);
+    println!("Variables:   {}", table.variables.join(", "));
+    println!("Minterms:    {:?}", table.minterms);
+    println!("Don't-cares: {:?}", table.dont_cares);
+    println!();
+
+    let mut solver =
+        QMSolver::<Enc32>::new_with_variable_names(table.variables.len(), table.variables.clone());
+    solver.set_minterms(table.minterms.clone());
+    solver.set_dont_cares(table.dont_cares.clone());
+    let result = solver.solve();
+
+    println!("{} = {}\n", table.output, result.minimized_expression);
+
+    println!("Cover (CSV):");
+    print!("{}", write_cover_csv(&table.variables, &result.cover));
+    println!();
+
+    let cover: Vec<serde_json::Value> = result
+        .cover
+        .iter()
+        .map(|&(values, dc)| serde_json::json!({ "values": values, "dont_care_mask": dc }))
+        .collect();
+    let json = serde_json::json!({
+        "output": table.output,
+        "variables": table.variables,
+        "expression": result.minimized_expression,
+        "cover": cover,
+    });
+    println!("Cover (JSON):");
+    println!("{}", serde_json::to_string_pretty(&json)?);
+
+    Ok(())
+}
@@ -17,7 +17,7 @@
 // Expected result: X0X0 X1X1 XX10 1XX0
 // Which means: (B'D') + (BD) + (CD') + (AD')
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Quine-McCluskey Example: Has Essential Prime Implicants ===\n");
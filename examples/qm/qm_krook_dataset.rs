@@ -10,7 +10,7 @@
 // - WM: Workforce Motivation
 // - LP: Labor Productivity
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Quine-McCluskey Example: Krook QCA Dataset ===\n");
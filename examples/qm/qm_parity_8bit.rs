@@ -6,7 +6,7 @@
 // This creates a large truth table (256 entries) and demonstrates
 // the algorithm's ability to handle complex Boolean functions.
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Quine-McCluskey Example: 8-bit Parity Function ===\n");
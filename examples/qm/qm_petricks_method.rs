@@ -18,7 +18,7 @@
 // Expected result: 0XX0 0X1X X10X 10XX
 // Which means: (A'D') + (A'C) + (BC') + (AB')
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Quine-McCluskey Example: Needs Petrick's Method ===\n");
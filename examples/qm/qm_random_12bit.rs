@@ -6,7 +6,7 @@
 // This example uses 550 random minterms (25 * 22) from 4096 possible inputs
 
 use qm_agent::qm::random::generate_random_minterms;
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("12-bit Encoding Example with 12 variables");
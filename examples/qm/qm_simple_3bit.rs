@@ -13,7 +13,7 @@
 // This represents the Boolean function: f(A,B,C) = Σ(3,4,5,6,7)
 // Output is 1 when the decimal value > 2
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Simple 3-bit Quine-McCluskey Example ===\n");
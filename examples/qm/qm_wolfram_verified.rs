@@ -17,7 +17,7 @@
 // Also equivalent to:
 // 00X1 X011 X101 10X0
 
-use qm_agent::{QMSolver, Enc32};
+use qm_agent::prelude::*;
 
 fn main() {
     println!("=== Quine-McCluskey Example: Wolfram Alpha Verified ===\n");
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qm_agent::simplify::parse_bool_expr;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        // Errors are fine, panics and stack overflows are not
+        let _ = parse_bool_expr(input);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qm_agent::parse_minterms;
+
+// The minterm lists of the natural-language input (`Σ(...)`, `d(...)`, "minimize minterms ...")
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = parse_minterms(input);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qm_agent::qm::{Enc32, parse_pla};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(pla) = parse_pla::<Enc32>(input) {
+            let _ = pla.minterms();
+            let _ = pla.dont_cares();
+        }
+    }
+});
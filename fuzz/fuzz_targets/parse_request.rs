@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qm_agent::qm::parse::parse_request_with;
+
+// The natural-language input of `minimize`: function notation, the "minimize minterms ..."
+// sentence, truth tables, expressions, inline PLA and JSON; never read as a file path
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = parse_request_with(input, &|_| None);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qm_agent::qm::parse_sop;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(expr) = parse_sop(input, &[]) {
+            let _ = expr.evaluate(0);
+            let _ = expr.evaluate(u64::MAX);
+        }
+    }
+});
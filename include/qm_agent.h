@@ -0,0 +1,70 @@
+#ifndef QM_AGENT_H
+#define QM_AGENT_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Success
+#define QM_OK 0
+
+// A required pointer argument was null
+#define QM_ERR_NULL_POINTER 1
+
+// An input string is not valid UTF-8
+#define QM_ERR_INVALID_UTF8 2
+
+// More variables than the minimizer supports (64)
+#define QM_ERR_TOO_MANY_VARIABLES 3
+
+// The request was rejected; `*out_json` holds the error message
+#define QM_ERR_INVALID_INPUT 4
+
+// An internal error (a panic) was caught at the boundary
+#define QM_ERR_PANIC 5
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Minimize the function with `len` minterms and `dlen` don't-cares over `variables` inputs
+//
+// Writes the [`QMResult`](crate::QMResult) as JSON to `*out_json`. `dont_cares` may be
+// null when `dlen` is 0.
+//
+// # Safety
+//
+// `minterms` must point to `len` readable `u64`s (or be null when `len` is 0), likewise
+// `dont_cares` and `dlen`; `out_json` must be a valid pointer to write a `char *` to.
+int32_t qm_minimize(const uint64_t *minterms,
+                    size_t len,
+                    const uint64_t *dont_cares,
+                    size_t dlen,
+                    uint32_t variables,
+                    char **out_json);
+
+// Run [`simplify_from_json`](crate::agent_api::simplify_from_json) on the NUL-terminated
+// request `input`, writing the response JSON to `*out_json`
+//
+// # Safety
+//
+// `input` must be a valid NUL-terminated string and `out_json` a valid pointer to write a
+// `char *` to.
+int32_t qm_simplify_json(const char *input, char **out_json);
+
+// Release a string returned by this library; null is ignored
+//
+// # Safety
+//
+// `s` must be null or a string returned through `out_json`, not freed before.
+void qm_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* QM_AGENT_H */
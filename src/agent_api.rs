@@ -15,12 +15,31 @@
 //! - Coverage analysis
 //! - Optimization suggestions
 
+use crate::simplify::analyzer::collect_variables;
 use crate::simplify::{
-    BranchSet, SimplificationResult, VariableType, analyze_branches, format_bool_expr,
-    parse_bool_expr, simplify_branches,
+    BoolExpr, BranchSet, OutputComparison, SimplificationResult, VariableType, analyze_branches,
+    build_truth_table_with_comparisons, format_bool_expr, format_bool_expr_python, parse_bool_expr,
+    simplify_branches,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// Default cap on the number of minterms listed per group in the debug truth table
+const DEFAULT_DEBUG_MAX_ROWS: usize = 256;
+
+/// Version of the response envelope emitted to versioned requests
+pub const API_VERSION: u32 = 2;
+
+/// Features this build supports, advertised in versioned responses
+pub const CAPABILITIES: &[&str] = &[
+    "integer_comparisons",
+    "protected_branches",
+    "side_effects",
+    "debug_truth_table",
+    "output_comparison",
+    "patch",
+    "visualization",
+];
 
 /// Main request structure from Claude
 #[derive(Debug, Deserialize)]
@@ -39,6 +58,20 @@ pub struct SimplificationRequest {
     /// Optional context about the code
     #[serde(default)]
     pub context: RequestContext,
+
+    /// Envelope version the caller speaks; without any version field a v1 response is sent
+    #[serde(default)]
+    pub api_version: Option<u32>,
+
+    /// Oldest API version the caller accepts; newer than [`API_VERSION`] is an error
+    #[serde(default)]
+    pub min_api_version: Option<u32>,
+}
+
+impl SimplificationRequest {
+    fn is_versioned(&self) -> bool {
+        self.api_version.is_some() || self.min_api_version.is_some()
+    }
 }
 
 /// Variable specification
@@ -85,6 +118,11 @@ pub struct BranchSpec {
     /// Output value or action (e.g., "return 1", "action_a()")
     pub output: String,
 
+    /// Expected execution frequency; the heaviest output is emitted first, or as the else
+    /// branch when there is no default and every input is covered
+    #[serde(default)]
+    pub weight: Option<f64>,
+
     /// Optional metadata about this branch
     #[serde(default)]
     pub metadata: BranchMetadata,
@@ -102,6 +140,62 @@ pub struct BranchMetadata {
 
     /// Original source code
     pub source: Option<String>,
+
+    /// Keep this branch verbatim (non-functional reasons: logging, metrics, rollout)
+    #[serde(default)]
+    pub protected: bool,
+
+    /// Human comment on the branch, carried over to the generated code; defaults to the
+    /// trailing `// ...` or `# ...` comments of `source`
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+impl BranchMetadata {
+    /// Must the optimizer keep this branch as written (protected or with side effects)?
+    fn keeps_verbatim(&self) -> bool {
+        self.protected || self.has_side_effects
+    }
+
+    /// `comment`, or else the trailing comments of the lines of `source`
+    fn comments(&self) -> Vec<String> {
+        if let Some(comment) = &self.comment {
+            return comment.lines().map(|line| line.trim().to_string()).collect();
+        }
+        self.source
+            .iter()
+            .flat_map(|source| source.lines())
+            .filter_map(trailing_comment)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Text of a `// ...` comment, or of a `# ...` comment after whitespace, ending `line`
+/// outside string literals
+fn trailing_comment(line: &str) -> Option<&str> {
+    let bytes = line.as_bytes();
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, &byte) in bytes.iter().enumerate() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if byte == b'\\' => escaped = true,
+            Some(q) if byte == q => quote = None,
+            Some(_) => {}
+            None => match byte {
+                b'"' | b'\'' => quote = Some(byte),
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    return Some(line[i + 2..].trim()).filter(|c| !c.is_empty());
+                }
+                b'#' if i > 0 && bytes[i - 1].is_ascii_whitespace() => {
+                    return Some(line[i + 1..].trim()).filter(|c| !c.is_empty());
+                }
+                _ => {}
+            },
+        }
+    }
+    None
 }
 
 /// Request context
@@ -119,18 +213,77 @@ pub struct RequestContext {
     #[serde(default)]
     pub style: Option<String>,
 
-    /// Whether this code was already analyzed by QM agent (skip re-analysis)
+    /// Whether this code was already analyzed by QM agent: the branches are parsed and
+    /// counted, but neither simplified nor analyzed
     #[serde(default)]
     pub already_analyzed: bool,
 
     /// Original source code (for including in suggestions when changes are made)
     #[serde(default)]
     pub original_code: Option<String>,
+
+    /// Include the derived truth table in the response (for auditing)
+    #[serde(default)]
+    pub debug: bool,
+
+    /// Maximum number of minterms listed per group in the debug truth table
+    #[serde(default)]
+    pub debug_max_rows: Option<usize>,
+
+    /// When two outputs are the same: "exact" (default), "ignore_whitespace" or "ignore_case"
+    #[serde(default)]
+    pub output_comparison: Option<String>,
+
+    /// Indentation and brace placement of generated code
+    #[serde(default)]
+    pub codegen_style: CodegenStyle,
+
+    /// Include coverage diagrams of the original branches in the response
+    #[serde(default)]
+    pub visualize: bool,
+}
+
+/// Layout of generated code, honored by every language generator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodegenStyle {
+    /// One level of indentation (default: a tab)
+    pub indent: String,
+    /// `if a {` (default) rather than the opening brace on a line of its own
+    pub brace_on_same_line: bool,
+}
+
+impl Default for CodegenStyle {
+    fn default() -> Self {
+        Self {
+            indent: "\t".to_string(),
+            brace_on_same_line: true,
+        }
+    }
+}
+
+impl RequestContext {
+    fn output_comparison(&self) -> Result<OutputComparison, String> {
+        match self.output_comparison.as_deref() {
+            None | Some("exact") => Ok(OutputComparison::Exact),
+            Some("ignore_whitespace") => Ok(OutputComparison::IgnoreWhitespace),
+            Some("ignore_case") => Ok(OutputComparison::IgnoreCase),
+            Some(other) => Err(format!("Unknown output comparison: {}", other)),
+        }
+    }
 }
 
 /// Main response structure to Claude
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimplificationResponse {
+    /// Envelope version (absent in v1 responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<u32>,
+
+    /// Features supported by this build (absent in v1 responses)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+
     /// Simplified branches
     pub simplified_branches: Vec<SimplifiedBranch>,
 
@@ -142,6 +295,40 @@ pub struct SimplificationResponse {
 
     /// Original complexity metrics
     pub metrics: ComplexityMetrics,
+
+    /// Derived truth table (only when `context.debug` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truth_table: Option<TruthTableDump>,
+
+    /// Coverage diagrams (only when `context.visualize` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visualization: Option<Visualization>,
+}
+
+/// Coverage of the original branches as diagrams: a node per branch and the default,
+/// dashed edges counting overlapping input combinations, dead branches in red
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Visualization {
+    /// Graphviz source (`dot -Tsvg`)
+    pub dot: String,
+    /// Mermaid flowchart source
+    pub mermaid: String,
+}
+
+/// Truth table the simplification was derived from
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TruthTableDump {
+    /// Variable order: bit `i` of a minterm is `variables[i]`
+    pub variables: Vec<String>,
+
+    /// Minterms per output value
+    pub outputs: BTreeMap<String, Vec<u64>>,
+
+    /// Input combinations not covered by any branch (no default)
+    pub dont_cares: Vec<u64>,
+
+    /// Whether any list was cut off at `debug_max_rows`
+    pub truncated: bool,
 }
 
 /// A simplified branch
@@ -156,9 +343,27 @@ pub struct SimplifiedBranch {
     /// Which original branches this combines
     pub original_lines: Vec<usize>,
 
+    /// Comments of the combined branches, deduplicated, one per line; emitted above the
+    /// branch's output in the generated code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
     /// Is this the else/default clause?
     #[serde(default)]
     pub is_default: bool,
+
+    /// Was this branch kept verbatim because it is protected?
+    #[serde(default)]
+    pub protected: bool,
+
+    /// Input combinations on which this is the first branch to hold (integers counted by
+    /// their domain size)
+    #[serde(default)]
+    pub combinations_covered: u64,
+
+    /// `combinations_covered` as a fraction of all input combinations
+    #[serde(default)]
+    pub coverage_fraction: f64,
 }
 
 /// Analysis results
@@ -170,8 +375,9 @@ pub struct AnalysisResult {
     /// Coverage gaps (untested conditions)
     pub coverage_gaps: Vec<String>,
 
-    /// Total coverage percentage
-    pub coverage_percent: f64,
+    /// Total coverage percentage (absent when the analysis was skipped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_percent: Option<f64>,
 
     /// Overlapping conditions
     pub overlaps: Vec<OverlapWarning>,
@@ -191,6 +397,14 @@ pub struct DeadCodeWarning {
 
     /// Which branches already cover this
     pub covered_by: Vec<usize>,
+
+    /// Branch is protected and was kept anyway (informational)
+    #[serde(default)]
+    pub protected: bool,
+
+    /// Explanation, e.g. the conflicting comparisons of a contradiction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
 }
 
 /// Overlap warning
@@ -204,7 +418,7 @@ pub struct OverlapWarning {
 /// Code suggestion
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Suggestion {
-    /// Suggestion type: "simplification", "dead_code", "coverage"
+    /// Suggestion type: "simplification", "dead_code", "coverage", "unused_variable", ...
     pub kind: String,
 
     /// Human-readable message
@@ -215,88 +429,206 @@ pub struct Suggestion {
 
     /// Which lines this affects
     pub lines: Vec<usize>,
+
+    /// Unified diff against `context.original_code` (only for a simplification, when every
+    /// branch has a `metadata.line`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<String>,
 }
 
 /// Complexity metrics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ComplexityMetrics {
     pub original_branches: usize,
-    pub simplified_branches: usize,
-    pub complexity_reduction: f64,
+    /// Absent when the simplification was skipped, as is `complexity_reduction`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simplified_branches: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complexity_reduction: Option<f64>,
     pub variables_used: Vec<String>,
 }
 
+/// Structured error for a request this build cannot serve
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiVersionError {
+    /// Always "unsupported_api_version"
+    pub error: String,
+    pub message: String,
+    pub requested_min_api_version: u32,
+    pub api_version: u32,
+    pub capabilities: Vec<String>,
+}
+
+fn capabilities() -> Vec<String> {
+    CAPABILITIES.iter().map(|c| c.to_string()).collect()
+}
+
 /// Main entry point: simplify from JSON string
+///
+/// A request whose `min_api_version` is newer than [`API_VERSION`] is rejected before any
+/// work is done; the error string is a JSON-serialized [`ApiVersionError`].
 pub fn simplify_from_json(json: &str) -> Result<String, String> {
     let request: SimplificationRequest =
         serde_json::from_str(json).map_err(|e| format!("JSON parse error: {}", e))?;
 
-    let response = process_request(request)?;
+    if let Some(min_version) = request.min_api_version
+        && min_version > API_VERSION
+    {
+        let error = ApiVersionError {
+            error: "unsupported_api_version".to_string(),
+            message: format!(
+                "Request needs API version {} or newer; this build supports version {}",
+                min_version, API_VERSION
+            ),
+            requested_min_api_version: min_version,
+            api_version: API_VERSION,
+            capabilities: capabilities(),
+        };
+        return Err(serde_json::to_string_pretty(&error)
+            .map_err(|e| format!("JSON serialize error: {}", e))?);
+    }
+
+    let versioned = request.is_versioned();
+    let mut response = process_request(request)?;
+    if versioned {
+        response.api_version = Some(API_VERSION);
+        response.capabilities = capabilities();
+    }
 
     serde_json::to_string_pretty(&response).map_err(|e| format!("JSON serialize error: {}", e))
 }
 
 /// Process a simplification request
 fn process_request(request: SimplificationRequest) -> Result<SimplificationResponse, String> {
-    // Check if code was already analyzed - skip re-analysis
+    let branch_set = build_branch_set(&request)?;
+
+    // Already analyzed: echo the request's metrics, without simplifying or analyzing
     if request.context.already_analyzed {
         return Ok(SimplificationResponse {
+            api_version: None,
+            capabilities: vec![],
             simplified_branches: vec![],
             analysis: AnalysisResult {
                 dead_code: vec![],
                 coverage_gaps: vec![],
-                coverage_percent: 100.0,
+                coverage_percent: None,
                 overlaps: vec![],
             },
             suggestions: vec![Suggestion {
-                kind: "already_analyzed".to_string(),
-                message: "Code was already analyzed by QM agent. Skipping re-analysis.".to_string(),
+                kind: "skipped_reanalysis".to_string(),
+                message:
+                    "Code was already analyzed by QM agent; skipped simplification and analysis"
+                        .to_string(),
                 code: None,
                 lines: vec![],
+                patch: None,
             }],
             metrics: ComplexityMetrics {
-                original_branches: 0,
-                simplified_branches: 0,
-                complexity_reduction: 0.0,
-                variables_used: vec![],
+                original_branches: branch_set.branches.len(),
+                simplified_branches: None,
+                complexity_reduction: None,
+                variables_used: collect_variables(&branch_set),
             },
+            truth_table: None,
+            visualization: None,
         });
     }
 
-    // Convert to internal BranchSet
-    let mut branch_set = BranchSet::new();
+    // Run simplification
+    let result = simplify_branches(&branch_set)?;
 
-    // Register variables
-    for (name, spec) in &request.variables {
-        let var_type = spec.to_variable_type()?;
-        match var_type {
-            VariableType::Boolean => branch_set.declare_bool(name),
-            VariableType::Integer { min, max } => branch_set.declare_int(name, min, max),
+    // Run analysis
+    let analysis = analyze_branches(&branch_set)?;
+
+    // Build response
+    let truth_table = if request.context.debug {
+        let max_rows = request
+            .context
+            .debug_max_rows
+            .unwrap_or(DEFAULT_DEBUG_MAX_ROWS);
+        Some(dump_truth_table(&branch_set, max_rows)?)
+    } else {
+        None
+    };
+
+    let visualization = request.context.visualize.then(|| {
+        let labels = branch_labels(&request);
+        Visualization {
+            dot: analysis.to_dot(&labels),
+            mermaid: analysis.to_mermaid(&labels),
         }
-    }
+    });
+
+    let mut response = build_response(request, result, analysis)?;
+    response.truth_table = truth_table;
+    response.visualization = visualization;
+
+    Ok(response)
+}
 
-    // Parse and add branches
+/// The internal [`BranchSet`] of a request; parses every condition
+fn build_branch_set(request: &SimplificationRequest) -> Result<BranchSet, String> {
+    let mut builder = BranchSet::builder();
+    for (name, spec) in &request.variables {
+        builder = builder.variable(name, spec.to_variable_type()?);
+    }
     for branch_spec in &request.branches {
-        let condition = parse_bool_expr(&branch_spec.condition)
-            .map_err(|e| format!("Failed to parse '{}': {}", branch_spec.condition, e))?;
-        branch_set.add_branch(condition, &branch_spec.output);
+        // A side-effecting output must run exactly where it was written: never merged
+        // with an equal output elsewhere, never dropped as dead code
+        builder = if branch_spec.metadata.keeps_verbatim() {
+            builder.protected_branch(&branch_spec.condition, &branch_spec.output)
+        } else {
+            builder.branch(&branch_spec.condition, &branch_spec.output)
+        };
+        if let Some(weight) = branch_spec.weight {
+            builder = builder.weight(weight);
+        }
     }
-
-    // Set default if provided
     if let Some(ref default) = request.default {
-        branch_set.set_default(default);
+        builder = builder.default(default);
     }
+    builder
+        .output_comparison(request.context.output_comparison()?)
+        .build()
+}
 
-    // Run simplification
-    let result = simplify_branches(&branch_set)?;
-
-    // Run analysis
-    let analysis = analyze_branches(&branch_set)?;
+/// `condition => output` per branch, then `else => default` when there is a default
+fn branch_labels(request: &SimplificationRequest) -> Vec<String> {
+    request
+        .branches
+        .iter()
+        .map(|b| format!("{} => {}", b.condition, b.output))
+        .chain(request.default.iter().map(|d| format!("else => {}", d)))
+        .collect()
+}
 
-    // Build response
-    let response = build_response(request, result, analysis)?;
+/// Export the truth table of a branch set, capping each minterm list at `max_rows`
+fn dump_truth_table(branch_set: &BranchSet, max_rows: usize) -> Result<TruthTableDump, String> {
+    let table = build_truth_table_with_comparisons(branch_set)?;
+    let mut truncated = false;
+    let mut cap = |mut minterms: Vec<u64>| {
+        if minterms.len() > max_rows {
+            minterms.truncate(max_rows);
+            truncated = true;
+        }
+        minterms
+    };
 
-    Ok(response)
+    let outputs = table
+        .sorted_output_groups()
+        .into_iter()
+        .map(|(output, minterms)| (output, cap(minterms)))
+        .collect();
+    let mut dont_cares = table.dont_cares.clone();
+    dont_cares.sort_unstable();
+    let dont_cares = cap(dont_cares);
+
+    Ok(TruthTableDump {
+        variables: table.variables,
+        outputs,
+        dont_cares,
+        truncated,
+    })
 }
 
 /// Build the response structure
@@ -306,30 +638,65 @@ fn build_response(
     analysis: crate::simplify::SimplificationAnalysis,
 ) -> Result<SimplificationResponse, String> {
     // Convert simplified branches
+    let comparison = request.context.output_comparison()?;
+    let coverage = |position: usize| {
+        let covered = result
+            .combinations_covered
+            .get(position)
+            .copied()
+            .unwrap_or(0);
+        let fraction = if result.total_combinations == 0 {
+            0.0
+        } else {
+            covered as f64 / result.total_combinations as f64
+        };
+        (covered, fraction)
+    };
     let mut simplified_branches = Vec::new();
-    for (condition, output) in &result.simplified_conditions {
-        let condition_str = format_bool_expr(condition);
-        let is_default = condition_str == "true" || condition_str == "1";
+    for (position, (condition, output)) in result.simplified_conditions.iter().enumerate() {
+        let (combinations_covered, coverage_fraction) = coverage(position);
+        // Protected branches are emitted exactly as written
+        if let Some(&(_, branch_index)) = result
+            .protected_branches
+            .iter()
+            .find(|(pos, _)| *pos == position)
+        {
+            let spec = &request.branches[branch_index];
+            simplified_branches.push(SimplifiedBranch {
+                condition: spec.condition.clone(),
+                output: spec.output.clone(),
+                original_lines: spec.metadata.line.into_iter().collect(),
+                comment: merge_comments([spec]),
+                is_default: false,
+                protected: true,
+                combinations_covered,
+                coverage_fraction,
+            });
+            continue;
+        }
+
+        let condition_str = format_bool_expr(&condition.normalize());
+        let is_default = *condition == BoolExpr::Const(true);
 
-        // Find which original lines this came from
-        let original_lines: Vec<usize> = request
+        // The original branches this came from
+        let merged: Vec<&BranchSpec> = request
             .branches
             .iter()
-            .enumerate()
-            .filter_map(|(_i, b)| {
-                if b.output == *output {
-                    b.metadata.line
-                } else {
-                    None
-                }
+            .filter(|b| {
+                comparison.key(&b.output) == comparison.key(output) && !b.metadata.keeps_verbatim()
             })
             .collect();
+        let original_lines: Vec<usize> = merged.iter().filter_map(|b| b.metadata.line).collect();
 
         simplified_branches.push(SimplifiedBranch {
             condition: condition_str,
             output: output.clone(),
             original_lines,
+            comment: merge_comments(merged),
             is_default,
+            protected: false,
+            combinations_covered,
+            coverage_fraction,
         });
     }
 
@@ -348,6 +715,8 @@ fn build_response(
                 line,
                 reason: format!("{:?}", db.reason),
                 covered_by: db.covered_by.clone(),
+                protected: db.protected,
+                detail: db.detail.clone(),
             }
         })
         .collect();
@@ -378,7 +747,7 @@ fn build_response(
     let analysis_result = AnalysisResult {
         dead_code,
         coverage_gaps,
-        coverage_percent: analysis.total_coverage_percent,
+        coverage_percent: Some(analysis.total_coverage_percent),
         overlaps,
     };
 
@@ -389,16 +758,20 @@ fn build_response(
     // Calculate metrics
     let metrics = ComplexityMetrics {
         original_branches: result.original_branch_count,
-        simplified_branches: result.simplified_branch_count,
-        complexity_reduction: result.complexity_reduction(),
+        simplified_branches: Some(result.simplified_branch_count),
+        complexity_reduction: Some(result.complexity_reduction()),
         variables_used: result.variables.clone(),
     };
 
     Ok(SimplificationResponse {
+        api_version: None,
+        capabilities: vec![],
         simplified_branches,
         analysis: analysis_result,
         suggestions,
         metrics,
+        truth_table: None,
+        visualization: None,
     })
 }
 
@@ -419,7 +792,9 @@ fn generate_suggestions(
             simplified,
             language,
             request.context.original_code.as_deref(),
+            &request.context.codegen_style,
         );
+        let patch = generate_patch(request, analysis, simplified, language);
 
         suggestions.push(Suggestion {
             kind: "simplification".to_string(),
@@ -434,10 +809,13 @@ fn generate_suggestions(
                 .iter()
                 .flat_map(|b| b.original_lines.clone())
                 .collect(),
+            patch,
         });
     } else if result.complexity_reduction() == 0.0
         && analysis.dead_code.is_empty()
         && analysis.overlaps.is_empty()
+        && result.analysis.redundant_with_default.is_empty()
+        && result.unreferenced_variables.is_empty()
     {
         // No simplification possible and no issues found
         suggestions.push(Suggestion {
@@ -445,44 +823,167 @@ fn generate_suggestions(
             message: "No simplification possible. The logic is already optimal.".to_string(),
             code: None,
             lines: vec![],
+            patch: None,
+        });
+    }
+
+    if let Some(output) = &result.weighted_default {
+        let comparison = request.context.output_comparison().unwrap_or_default();
+        suggestions.push(Suggestion {
+            kind: "weighted_default".to_string(),
+            message: format!("Made '{}' the default branch based on weights", output),
+            code: None,
+            lines: request
+                .branches
+                .iter()
+                .filter(|b| comparison.key(&b.output) == comparison.key(output))
+                .filter_map(|b| b.metadata.line)
+                .collect(),
+            patch: None,
         });
     }
 
     // Dead code warnings
     for warning in &analysis.dead_code {
+        if warning.reason == "ConflictingDuplicate" {
+            let first = warning.covered_by.first().copied().unwrap_or(0);
+            let output = |index: usize| {
+                request
+                    .branches
+                    .get(index)
+                    .map_or("", |b| b.output.as_str())
+            };
+            let first_line = request.branches.get(first).and_then(|b| b.metadata.line);
+            suggestions.push(Suggestion {
+                kind: "duplicate_condition_different_output".to_string(),
+                message: format!(
+                    "Branches {} and {} have the same condition but different outputs ('{}' vs '{}'); \
+                     branch {} can never run. Check whether a variable that distinguishes them was left out.",
+                    first,
+                    warning.branch_index,
+                    output(first),
+                    output(warning.branch_index),
+                    warning.branch_index
+                ),
+                code: None,
+                lines: first_line.into_iter().chain(warning.line).collect(),
+                patch: None,
+            });
+            continue;
+        }
+        if warning.protected {
+            suggestions.push(Suggestion {
+                kind: "protected_redundant".to_string(),
+                message: format!(
+                    "Branch at line {:?} is redundant ({}) but protected; kept as-is",
+                    warning.line, warning.reason
+                ),
+                code: None,
+                lines: warning.line.into_iter().collect(),
+                patch: None,
+            });
+            continue;
+        }
+        let reason = match &warning.detail {
+            Some(detail) => format!("{}: {}", warning.reason, detail),
+            None => warning.reason.clone(),
+        };
         suggestions.push(Suggestion {
             kind: "dead_code".to_string(),
             message: format!(
                 "Branch at line {:?} is unreachable ({})",
-                warning.line, warning.reason
+                warning.line, reason
             ),
             code: None,
             lines: warning.line.into_iter().collect(),
+            patch: None,
+        });
+    }
+
+    // Explicit branches that only repeat what the default does
+    for &index in &result.analysis.redundant_with_default {
+        let line = request.branches.get(index).and_then(|b| b.metadata.line);
+        suggestions.push(Suggestion {
+            kind: "redundant_with_default".to_string(),
+            message: match line {
+                Some(line) => format!("Branch at line {} is redundant with the default", line),
+                None => format!("Branch {} is redundant with the default", index),
+            },
+            code: None,
+            lines: line.into_iter().collect(),
+            patch: None,
+        });
+    }
+
+    // Inputs the simplified branches never read
+    for var in &result.unreferenced_variables {
+        suggestions.push(Suggestion {
+            kind: "unused_variable".to_string(),
+            message: format!(
+                "Input '{}' does not affect the outcome and can be removed",
+                var
+            ),
+            code: None,
+            lines: vec![],
+            patch: None,
+        });
+    }
+
+    // Side-effecting branches are kept apart from branches with the same output
+    for branch in request
+        .branches
+        .iter()
+        .filter(|b| b.metadata.has_side_effects)
+    {
+        suggestions.push(Suggestion {
+            kind: "side_effects".to_string(),
+            message: format!(
+                "Branch at line {:?} has side effects ('{}'); kept as a separate branch",
+                branch.metadata.line, branch.output
+            ),
+            code: None,
+            lines: branch.metadata.line.into_iter().collect(),
+            patch: None,
         });
     }
 
     // Coverage warnings
-    if !analysis.coverage_gaps.is_empty() {
+    if !analysis.coverage_gaps.is_empty()
+        && let Some(coverage_percent) = analysis.coverage_percent
+    {
         suggestions.push(Suggestion {
             kind: "coverage".to_string(),
             message: format!(
                 "Missing {} test cases. Coverage: {:.1}%",
                 analysis.coverage_gaps.len(),
-                analysis.coverage_percent
+                coverage_percent
             ),
             code: None,
             lines: vec![],
+            patch: None,
         });
     }
 
     suggestions
 }
 
+/// Comments of `branches` in order, each once, joined by newlines
+fn merge_comments<'a>(branches: impl IntoIterator<Item = &'a BranchSpec>) -> Option<String> {
+    let mut comments: Vec<String> = Vec::new();
+    for comment in branches.into_iter().flat_map(|b| b.metadata.comments()) {
+        if !comment.is_empty() && !comments.contains(&comment) {
+            comments.push(comment);
+        }
+    }
+    (!comments.is_empty()).then(|| comments.join("\n"))
+}
+
 /// Generate code in target language
 fn generate_code(
     branches: &[SimplifiedBranch],
     language: &str,
     original_code: Option<&str>,
+    style: &CodegenStyle,
 ) -> String {
     let mut result = String::new();
 
@@ -490,19 +991,18 @@ fn generate_code(
     if let Some(original) = original_code {
         result.push_str(&comment_out_code(original, language));
         result.push_str("\n");
-        let comment = match language {
-            "python" => "# QM-AGENT-SIMPLIFIED\n",
-            _ => "// QM-AGENT-SIMPLIFIED\n",
-        };
-        result.push_str(comment);
+        result.push_str(&format!(
+            "{}QM-AGENT-SIMPLIFIED\n",
+            comment_prefix(language)
+        ));
     }
 
     // Generate new code
     let new_code = match language {
-        "go" => generate_go_code(branches),
-        "rust" => generate_rust_code(branches),
-        "cpp" | "c++" => generate_cpp_code(branches),
-        "python" => generate_python_code(branches),
+        "go" => generate_go_code(branches, style),
+        "rust" => generate_rust_code(branches, style),
+        "cpp" | "c++" => generate_cpp_code(branches, style),
+        "python" => generate_python_code(branches, style),
         _ => generate_generic_code(branches),
     };
 
@@ -510,12 +1010,27 @@ fn generate_code(
     result
 }
 
-/// Comment out code based on language
-fn comment_out_code(code: &str, language: &str) -> String {
-    let comment_prefix = match language {
+/// Line comment marker of `language`
+fn comment_prefix(language: &str) -> &'static str {
+    match language {
         "python" => "# ",
         _ => "// ", // C-style for Go, Rust, C++, etc.
-    };
+    }
+}
+
+/// The comment of `branch` as comment lines starting with `prefix`
+fn comment_lines(branch: &SimplifiedBranch, prefix: &str) -> String {
+    branch
+        .comment
+        .iter()
+        .flat_map(|comment| comment.lines())
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+/// Comment out code based on language
+fn comment_out_code(code: &str, language: &str) -> String {
+    let comment_prefix = comment_prefix(language);
 
     let mut result = String::from(&format!("{}QM-AGENT-ORIGINAL:\n", comment_prefix));
     for line in code.lines() {
@@ -524,94 +1039,305 @@ fn comment_out_code(code: &str, language: &str) -> String {
     result
 }
 
-fn generate_go_code(branches: &[SimplifiedBranch]) -> String {
-    let mut code = String::new();
-    for (i, branch) in branches.iter().enumerate() {
-        if branch.is_default {
-            code.push_str(&format!("{}\n", branch.output));
-        } else if i == 0 {
-            code.push_str(&format!(
-                "if {} {{\n\t{}\n}}\n",
-                branch.condition, branch.output
-            ));
-        } else {
-            code.push_str(&format!(
-                "else if {} {{\n\t{}\n}}\n",
-                branch.condition, branch.output
-            ));
+/// Unified diff turning `context.original_code` into the simplified code
+///
+/// The first line of `original_code` is taken to be the line of the first branch; the
+/// ladder ends as found by [`ladder_len`], and code after it is left alone. Dead branches
+/// (from their line up to the next branch) are commented out below the simplified ladder,
+/// which is indented like the original first line. `None` when the code or a branch line
+/// is missing, or a line lies outside the code.
+fn generate_patch(
+    request: &SimplificationRequest,
+    analysis: &AnalysisResult,
+    simplified: &[SimplifiedBranch],
+    language: &str,
+) -> Option<String> {
+    let original: Vec<&str> = request.context.original_code.as_deref()?.lines().collect();
+    let lines: Vec<usize> = request
+        .branches
+        .iter()
+        .map(|b| b.metadata.line)
+        .collect::<Option<_>>()?;
+    let first_line = *lines.iter().min()?;
+    let mut starts: Vec<usize> = lines.iter().map(|line| line - first_line).collect();
+    if starts.iter().any(|&start| start >= original.len()) {
+        return None;
+    }
+    starts.sort_unstable();
+    starts.dedup();
+
+    let indent = &original[0][..original[0].len() - original[0].trim_start().len()];
+    let original = &original[..ladder_len(&original, *starts.last()?, indent)];
+    let mut replacement: Vec<String> =
+        generate_code(simplified, language, None, &request.context.codegen_style)
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", indent, line)
+                }
+            })
+            .collect();
+
+    let prefix = comment_prefix(language);
+    for warning in analysis.dead_code.iter().filter(|w| !w.protected) {
+        let Some(line) = warning.line else { continue };
+        let start = line - first_line;
+        let end = starts
+            .iter()
+            .copied()
+            .find(|&s| s > start)
+            .unwrap_or(original.len());
+        replacement.push(format!("{}{}QM-AGENT-DEAD: line {}", indent, prefix, line));
+        for code in &original[start..end] {
+            let code = code.strip_prefix(indent).unwrap_or(code);
+            replacement.push(format!("{}{}{}", indent, prefix, code));
         }
     }
-    code
+
+    let replacement: Vec<&str> = replacement.iter().map(String::as_str).collect();
+    let patch = unified_diff(original, &replacement, first_line);
+    (!patch.is_empty()).then_some(patch)
 }
 
-fn generate_rust_code(branches: &[SimplifiedBranch]) -> String {
-    let mut code = String::new();
-    for (i, branch) in branches.iter().enumerate() {
-        if branch.is_default {
-            if i > 0 {
-                code.push_str("else {\n\t");
-            }
-            code.push_str(&format!("{}\n", branch.output));
-            if i > 0 {
-                code.push_str("}\n");
+/// Lines of the if-ladder at the start of `code`, whose last branch starts at line `last`
+///
+/// The ladder runs up to the first line at its indentation, or less, that continues
+/// neither a branch (`else`, `elif`, `} else`) nor closes the ladder: a closing `}` is
+/// still part of it, other code is not. Blank lines before that code are left out.
+fn ladder_len(code: &[&str], last: usize, indent: &str) -> usize {
+    let mut end = code.len();
+    for (i, line) in code.iter().enumerate().skip(last + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty()
+            || line.len() - trimmed.len() > indent.len()
+            || trimmed.starts_with("else")
+            || trimmed.starts_with("elif")
+        {
+            continue;
+        }
+        match trimmed.strip_prefix('}') {
+            Some(rest) if rest.trim_start().starts_with("else") => continue,
+            Some(_) => return i + 1,
+            None => {
+                end = i;
+                break;
             }
-        } else if i == 0 {
-            code.push_str(&format!(
-                "if {} {{\n\t{}\n}}\n",
-                branch.condition, branch.output
-            ));
+        }
+    }
+    while end > last + 1 && code[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+/// Lines of context around each change in [`unified_diff`]
+const DIFF_CONTEXT: usize = 3;
+
+/// Unified diff from `old` to `new`, whose first lines are line `first_line`; empty when
+/// they are equal
+fn unified_diff(old: &[&str], new: &[&str], first_line: usize) -> String {
+    // lcs[i][j]: longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // (marker, line, lines of old before it, lines of new before it)
+    let mut ops: Vec<(char, &str, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', old[i], i, j));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', old[i], i, j));
+            i += 1;
         } else {
-            code.push_str(&format!(
-                "else if {} {{\n\t{}\n}}\n",
-                branch.condition, branch.output
-            ));
+            ops.push(('+', new[j], i, j));
+            j += 1;
         }
     }
-    code
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+    // Changes closer than twice the context share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &k in &changes {
+        let start = k.saturating_sub(DIFF_CONTEXT);
+        let end = (k + 1 + DIFF_CONTEXT).min(ops.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = String::from("--- original\n+++ simplified\n");
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        // An empty range is numbered by the line before it
+        let header_start = |before: usize, len: usize| {
+            if len == 0 {
+                (first_line + before).saturating_sub(1)
+            } else {
+                first_line + before
+            }
+        };
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            header_start(hunk[0].2, old_len),
+            old_len,
+            header_start(hunk[0].3, new_len),
+            new_len
+        ));
+        for (marker, line, _, _) in hunk {
+            diff.push_str(&format!("{}{}\n", marker, line));
+        }
+    }
+    diff
 }
 
-fn generate_cpp_code(branches: &[SimplifiedBranch]) -> String {
+/// Every line of `body` one level deeper, keeping the lines' relative indentation
+fn indent_block(body: &str, indent: &str) -> String {
+    let common = body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    body.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                "\n".to_string()
+            } else {
+                let line = line.get(common..).unwrap_or(line.trim_start());
+                format!("{}{}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+/// if / else if / else chain for languages with braces
+///
+/// `condition` renders the test of a branch, `statement` one line of its output.
+fn generate_brace_code(
+    branches: &[SimplifiedBranch],
+    style: &CodegenStyle,
+    condition: impl Fn(&str) -> String,
+    statement: impl Fn(&str) -> String,
+) -> String {
+    let open = if style.brace_on_same_line {
+        " {\n"
+    } else {
+        "\n{\n"
+    };
+    let close_before_else = if style.brace_on_same_line {
+        "} "
+    } else {
+        "}\n"
+    };
+    let body = |output: &str| {
+        let statements: Vec<String> = output.lines().map(&statement).collect();
+        indent_block(&statements.join("\n"), &style.indent)
+    };
+
     let mut code = String::new();
     for (i, branch) in branches.iter().enumerate() {
-        if branch.is_default {
-            if i > 0 {
-                code.push_str("else {\n\t");
-            }
-            code.push_str(&format!("{};\n", branch.output));
-            if i > 0 {
-                code.push_str("}\n");
+        let is_last = i + 1 == branches.len();
+        if branch.is_default && i == 0 {
+            code.push_str(&comment_lines(branch, "// "));
+            code.push_str(&indent_block(&branch.output, ""));
+            continue;
+        }
+        if i > 0 {
+            code.push_str("else");
+            if !branch.is_default {
+                code.push(' ');
             }
-        } else if i == 0 {
-            code.push_str(&format!(
-                "if ({}) {{\n\t{};\n}}\n",
-                branch.condition, branch.output
-            ));
-        } else {
-            code.push_str(&format!(
-                "else if ({}) {{\n\t{};\n}}\n",
-                branch.condition, branch.output
-            ));
         }
+        if !branch.is_default {
+            code.push_str("if ");
+            code.push_str(&condition(&branch.condition));
+        }
+        code.push_str(open);
+        // Inside the block: Go allows nothing between `}` and `else`
+        code.push_str(&indent_block(&comment_lines(branch, "// "), &style.indent));
+        code.push_str(&body(&branch.output));
+        code.push_str(if is_last { "}\n" } else { close_before_else });
     }
     code
 }
 
-fn generate_python_code(branches: &[SimplifiedBranch]) -> String {
+/// Go requires `} else {` on one line, so [`CodegenStyle::brace_on_same_line`] is ignored
+fn generate_go_code(branches: &[SimplifiedBranch], style: &CodegenStyle) -> String {
+    let style = CodegenStyle {
+        brace_on_same_line: true,
+        ..style.clone()
+    };
+    generate_brace_code(branches, &style, |c| c.to_string(), |s| s.to_string())
+}
+
+fn generate_rust_code(branches: &[SimplifiedBranch], style: &CodegenStyle) -> String {
+    generate_brace_code(branches, style, |c| c.to_string(), |s| s.to_string())
+}
+
+fn generate_cpp_code(branches: &[SimplifiedBranch], style: &CodegenStyle) -> String {
+    generate_brace_code(
+        branches,
+        style,
+        |c| format!("({})", c),
+        |s| {
+            // Statements end with ';' unless the output already has one or opens a block
+            let trimmed = s.trim_end();
+            if trimmed.is_empty() || trimmed.ends_with([';', '{', '}']) {
+                s.to_string()
+            } else {
+                format!("{};", s)
+            }
+        },
+    )
+}
+
+/// `condition` in Python syntax; one that does not parse (e.g. a protected branch kept as
+/// written) is left as it is
+fn python_condition(condition: &str) -> String {
+    parse_bool_expr(condition).map_or_else(
+        |_| condition.to_string(),
+        |expr| format_bool_expr_python(&expr),
+    )
+}
+
+fn generate_python_code(branches: &[SimplifiedBranch], style: &CodegenStyle) -> String {
     let mut code = String::new();
     for (i, branch) in branches.iter().enumerate() {
+        let comment = comment_lines(branch, "# ");
         if branch.is_default {
             if i > 0 {
-                code.push_str(&format!("else:\n\t{}\n", branch.output));
+                code.push_str("else:\n");
+                code.push_str(&indent_block(&comment, &style.indent));
+                code.push_str(&indent_block(&branch.output, &style.indent));
             } else {
-                code.push_str(&format!("{}\n", branch.output));
+                code.push_str(&comment);
+                code.push_str(&indent_block(&branch.output, ""));
             }
-        } else if i == 0 {
-            code.push_str(&format!("if {}:\n\t{}\n", branch.condition, branch.output));
         } else {
-            code.push_str(&format!(
-                "elif {}:\n\t{}\n",
-                branch.condition, branch.output
-            ));
+            let keyword = if i == 0 { "if" } else { "elif" };
+            let condition = python_condition(&branch.condition);
+            code.push_str(&format!("{} {}:\n", keyword, condition));
+            code.push_str(&indent_block(&comment, &style.indent));
+            code.push_str(&indent_block(&branch.output, &style.indent));
         }
     }
     code
@@ -620,6 +1346,7 @@ fn generate_python_code(branches: &[SimplifiedBranch]) -> String {
 fn generate_generic_code(branches: &[SimplifiedBranch]) -> String {
     let mut code = String::new();
     for branch in branches {
+        code.push_str(&comment_lines(branch, "// "));
         if branch.is_default {
             code.push_str(&format!("default: {}\n", branch.output));
         } else {
@@ -701,19 +1428,151 @@ mod tests {
                 condition: "a".to_string(),
                 output: "return 1".to_string(),
                 original_lines: vec![10],
+                comment: None,
                 is_default: false,
+                protected: false,
+                combinations_covered: 0,
+                coverage_fraction: 0.0,
             },
             SimplifiedBranch {
                 condition: "true".to_string(),
                 output: "return 0".to_string(),
                 original_lines: vec![15],
+                comment: None,
                 is_default: true,
+                protected: false,
+                combinations_covered: 0,
+                coverage_fraction: 0.0,
             },
         ];
 
-        let code = generate_go_code(&branches);
+        let code = generate_go_code(&branches, &CodegenStyle::default());
         assert!(code.contains("if a {"));
         assert!(code.contains("return 1"));
         assert!(code.contains("return 0"));
     }
+
+    /// Compare with `tests/golden/<name>`; regenerate with `UPDATE_GOLDEN=1`
+    fn check_golden(name: &str, actual: &str) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, actual).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e));
+        assert_eq!(actual, expected, "output differs from {}", path.display());
+    }
+
+    /// if / else if / else with two-line outputs; the default holds the nested `default`
+    fn two_line_branches(default: &str) -> Vec<SimplifiedBranch> {
+        let branch = |condition: &str, output: &str, is_default| SimplifiedBranch {
+            condition: condition.to_string(),
+            output: output.to_string(),
+            original_lines: vec![],
+            comment: None,
+            is_default,
+            protected: false,
+            combinations_covered: 0,
+            coverage_fraction: 0.0,
+        };
+        vec![
+            branch("a && b", "log(x)\nreturn 1", false),
+            branch("!a", "return 2", false),
+            branch("true", default, true),
+        ]
+    }
+
+    const GO_LOOP: &str = "for _, x := range xs {\n    f(x)\n}\nreturn 0";
+    const RUST_LOOP: &str = "for x in xs {\n    f(x);\n}\nreturn 0";
+    const CPP_LOOP: &str = "for (int x : xs) {\n    f(x);\n}\nreturn 0";
+    const PYTHON_LOOP: &str = "for x in xs:\n    f(x)\nreturn 0";
+
+    #[test]
+    fn test_codegen_golden() {
+        let style = CodegenStyle::default();
+        let go = generate_go_code(&two_line_branches(GO_LOOP), &style);
+        check_golden("codegen_go.txt", &go);
+        let rust = generate_rust_code(&two_line_branches(RUST_LOOP), &style);
+        check_golden("codegen_rust.txt", &rust);
+        let cpp = generate_cpp_code(&two_line_branches(CPP_LOOP), &style);
+        check_golden("codegen_cpp.txt", &cpp);
+        let python = generate_python_code(&two_line_branches(PYTHON_LOOP), &style);
+        check_golden("codegen_python.txt", &python);
+    }
+
+    #[test]
+    fn test_codegen_style_golden() {
+        let style = CodegenStyle {
+            indent: "    ".to_string(),
+            brace_on_same_line: false,
+        };
+        let cpp = generate_cpp_code(&two_line_branches(CPP_LOOP), &style);
+        check_golden("codegen_cpp_allman.txt", &cpp);
+        let python = generate_python_code(&two_line_branches(PYTHON_LOOP), &style);
+        check_golden("codegen_python_spaces.txt", &python);
+
+        // Go has no choice of brace placement
+        let branches = two_line_branches(GO_LOOP);
+        assert_eq!(
+            generate_go_code(&branches, &style),
+            generate_go_code(
+                &branches,
+                &CodegenStyle {
+                    brace_on_same_line: true,
+                    ..style.clone()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff(&["a", "b"], &["a", "b"], 1), "");
+        // An insertion is numbered by the line before it
+        assert_eq!(
+            unified_diff(&["a"], &["x", "a"], 5),
+            "--- original\n+++ simplified\n@@ -5,1 +5,2 @@\n+x\n a\n"
+        );
+        assert_eq!(
+            unified_diff(&[], &["x"], 5),
+            "--- original\n+++ simplified\n@@ -4,0 +5,1 @@\n+x\n"
+        );
+
+        // Changes far apart get a hunk each, with three lines of context
+        let old: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let mut new = old.clone();
+        new[1] = "one";
+        new[18] = "eighteen";
+        let diff = unified_diff(&old, &new, 1);
+        let headers: Vec<&str> = diff.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,5 @@", "@@ -16,5 +16,5 @@"]);
+    }
+
+    #[test]
+    fn test_codegen_style_from_request() {
+        let json = r#"{
+            "variables": {"a": "boolean", "b": "boolean"},
+            "branches": [
+                {"condition": "a && b", "output": "return 1"},
+                {"condition": "a && !b", "output": "return 1"},
+                {"condition": "!a", "output": "return 2"}
+            ],
+            "context": {"language": "rust", "codegen_style": {"indent": "  "}}
+        }"#;
+        let request: SimplificationRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.context.codegen_style.indent, "  ");
+        assert!(request.context.codegen_style.brace_on_same_line);
+
+        let response: SimplificationResponse =
+            serde_json::from_str(&simplify_from_json(json).unwrap()).unwrap();
+        let code = response
+            .suggestions
+            .iter()
+            .find_map(|s| s.code.clone())
+            .unwrap();
+        assert!(code.contains("if a {\n  return 1\n} else"), "{}", code);
+    }
 }
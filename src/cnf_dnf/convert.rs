@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
+use super::dnf_set::DnfSet;
 use super::error::CnfDnfError;
 use super::optimized_for::OptimizedFor;
+use super::progress::{ClauseProgress, NoProgress, ProgressSink};
 use super::utils::test_bit;
+use super::wide::{WideClause, cnf_to_dnf_wide};
 use crate::qm::encoding::MintermEncoding;
 
 /// Convert CNF to DNF with encoding-aware optimization selection
@@ -18,50 +21,77 @@ use crate::qm::encoding::MintermEncoding;
 /// * `cnf` - CNF formula as vector of disjunctions (bit vectors)
 /// * `n_bits` - Number of Boolean variables
 ///
+/// Duplicate clauses and clauses that are a superset of another clause are removed before
+/// the conversion (see [`reduce_cnf`]); progress events count the remaining clauses.
+///
+/// # Errors
+/// An empty clause (no variable in `0..n_bits`) can never be true:
+/// [`CnfDnfError::Unsatisfiable`] names the first one.
+///
 /// # Examples
 /// ```
-/// use qm_agent::cnf_dnf::{self, OptimizedFor};
-/// use qm_agent::qm::Enc64;
+/// use qm_agent::prelude::*;
 ///
 /// let cnf = vec![0b1010u64, 0b1100u64];
 ///
 /// // Auto-detect optimization (recommended)
-/// let dnf1 = cnf_dnf::cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::AutoDetect);
+/// let dnf1 = cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::AutoDetect);
 ///
 /// // Force X64 scalar for testing
-/// let dnf2 = cnf_dnf::cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::X64);
+/// let dnf2 = cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::X64);
 ///
 /// // Force AVX512 for testing
-/// let dnf3 = cnf_dnf::cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::Avx512_64bits);
+/// let dnf3 = cnf_to_dnf::<Enc64>(&cnf, 4, OptimizedFor::Avx512_64bits);
 /// ```
 pub fn cnf_to_dnf<E: MintermEncoding>(
     cnf: &[u64],
     n_bits: usize,
     of: OptimizedFor,
+) -> Result<Vec<u64>, CnfDnfError> {
+    cnf_to_dnf_with_progress::<E, _>(cnf, n_bits, of, &mut NoProgress)
+}
+
+/// [`cnf_to_dnf`] reporting a [`ClauseProgress`] to `progress` after every clause
+pub fn cnf_to_dnf_with_progress<E: MintermEncoding, P: ProgressSink>(
+    cnf: &[u64],
+    n_bits: usize,
+    of: OptimizedFor,
+    progress: &mut P,
 ) -> Result<Vec<u64>, CnfDnfError> {
     validate_parameters::<E>(n_bits, of)?;
-    let result_dnf = cnf_to_dnf_impl(cnf, n_bits, of.resolve(n_bits));
+    let cnf = prepare_cnf(cnf, n_bits)?;
+    let mut result_dnf = cnf_to_dnf_impl(&cnf, n_bits, of.resolve(n_bits), progress);
+    sort_canonical(&mut result_dnf);
 
-    if false {
-        println!("cnf_to_dnf {}", result_dnf.len());
-    }
+    log::trace!("cnf_to_dnf {}", result_dnf.len());
 
     Ok(result_dnf)
 }
 
+/// The products with the fewest literals; clauses are handled as in [`cnf_to_dnf`]
 pub fn cnf_to_dnf_minimal<E: MintermEncoding>(
     cnf: &[u64],
     n_bits: usize,
     of: OptimizedFor,
+) -> Result<Vec<u64>, CnfDnfError> {
+    cnf_to_dnf_minimal_with_progress::<E, _>(cnf, n_bits, of, &mut NoProgress)
+}
+
+/// [`cnf_to_dnf_minimal`] reporting a [`ClauseProgress`] to `progress` after every clause
+pub fn cnf_to_dnf_minimal_with_progress<E: MintermEncoding, P: ProgressSink>(
+    cnf: &[u64],
+    n_bits: usize,
+    of: OptimizedFor,
+    progress: &mut P,
 ) -> Result<Vec<u64>, CnfDnfError> {
     validate_parameters::<E>(n_bits, of)?;
-    let result_dnf = cnf_to_dnf_minimal_method1(cnf, n_bits, of.resolve(n_bits));
+    let cnf = prepare_cnf(cnf, n_bits)?;
+    let result_dnf = cnf_to_dnf_minimal_method1(&cnf, n_bits, of.resolve(n_bits), progress);
 
     let size_before = result_dnf.len();
-    let result = filter_to_minimal(result_dnf);
-    if false {
-        println!("cnf_to_dnf_minimal {} to {}", size_before, result.len());
-    }
+    let mut result = filter_to_minimal(result_dnf);
+    sort_canonical(&mut result);
+    log::trace!("cnf_to_dnf_minimal {} to {}", size_before, result.len());
     Ok(result)
 }
 
@@ -72,20 +102,84 @@ pub fn cnf_to_dnf_minimal_reference<E: MintermEncoding>(
     of: OptimizedFor,
 ) -> Result<Vec<u64>, CnfDnfError> {
     validate_parameters::<E>(n_bits, of)?;
-    let result_dnf = cnf_to_dnf_impl(cnf, n_bits, of.resolve(n_bits));
+    let cnf = prepare_cnf(cnf, n_bits)?;
+    let result_dnf = cnf_to_dnf_impl(&cnf, n_bits, of.resolve(n_bits), &mut NoProgress);
 
     let size_before = result_dnf.len();
-    let result = filter_to_minimal(result_dnf);
-    if false {
-        println!(
-            "cnf_to_dnf_minimal_reference {} to {}",
-            size_before,
-            result.len()
-        );
-    }
+    let mut result = filter_to_minimal(result_dnf);
+    sort_canonical(&mut result);
+    log::trace!(
+        "cnf_to_dnf_minimal_reference {} to {}",
+        size_before,
+        result.len()
+    );
     Ok(result)
 }
 
+/// Canonical order of DNF terms: ascending number of literals, then ascending value
+///
+/// All conversion functions return their terms in this order, so the first term is
+/// always the same smallest product regardless of clause order or optimization level.
+pub fn sort_canonical(dnf: &mut [u64]) {
+    dnf.sort_unstable_by_key(|&term| (term.count_ones(), term));
+}
+
+/// Number of clauses removed by [`reduce_cnf`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CnfReduction {
+    pub duplicates_removed: usize,
+    pub supersets_removed: usize,
+}
+
+impl CnfReduction {
+    pub fn total_removed(&self) -> usize {
+        self.duplicates_removed + self.supersets_removed
+    }
+}
+
+/// Remove duplicate clauses and clauses that are a superset of another clause
+///
+/// A superset clause is implied by its subset, so the DNF of the reduced CNF is the same.
+/// Kept clauses stay in the order of their first occurrence.
+pub fn reduce_cnf(cnf: &[u64]) -> (Vec<u64>, CnfReduction) {
+    let mut seen = std::collections::HashSet::with_capacity(cnf.len());
+    let unique: Vec<u64> = cnf.iter().copied().filter(|&c| seen.insert(c)).collect();
+
+    // Any subset of a clause has fewer literals, so a single pass in size order suffices
+    let mut by_size: Vec<usize> = (0..unique.len()).collect();
+    by_size.sort_by_key(|&i| unique[i].count_ones());
+
+    let mut kept_clauses: Vec<u64> = Vec::with_capacity(unique.len());
+    let mut keep = vec![false; unique.len()];
+    for i in by_size {
+        let clause = unique[i];
+        if !kept_clauses.iter().any(|&smaller| smaller & !clause == 0) {
+            kept_clauses.push(clause);
+            keep[i] = true;
+        }
+    }
+
+    let reduced: Vec<u64> = unique
+        .iter()
+        .zip(&keep)
+        .filter(|&(_, &k)| k)
+        .map(|(&c, _)| c)
+        .collect();
+    let reduction = CnfReduction {
+        duplicates_removed: cnf.len() - unique.len(),
+        supersets_removed: unique.len() - reduced.len(),
+    };
+    (reduced, reduction)
+}
+
+/// Reject empty clauses, then drop duplicate and superset clauses
+fn prepare_cnf(cnf: &[u64], n_bits: usize) -> Result<Vec<u64>, CnfDnfError> {
+    if let Some(clause_index) = cnf.iter().position(|&c| count_literals(c, n_bits) == 0) {
+        return Err(CnfDnfError::Unsatisfiable { clause_index });
+    }
+    Ok(reduce_cnf(cnf).0)
+}
+
 /// Validate encoding capacity and optimization level
 fn validate_parameters<E: MintermEncoding>(
     n_bits: usize,
@@ -131,11 +225,17 @@ fn filter_to_minimal(dnf: Vec<u64>) -> Vec<u64> {
 }
 
 /// Private implementation of CNF to DNF conversion
-fn cnf_to_dnf_impl(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> Vec<u64> {
+fn cnf_to_dnf_impl<P: ProgressSink>(
+    cnf: &[u64],
+    n_bits: usize,
+    of: OptimizedFor,
+    progress: &mut P,
+) -> Vec<u64> {
     let mut result_dnf: Vec<u64> = Vec::new();
     let mut first = true;
+    let mut literals_processed = 0;
 
-    for &disj_val in cnf {
+    for (clause_index, &disj_val) in cnf.iter().enumerate() {
         if first {
             first = false;
             for i in 0..n_bits {
@@ -144,103 +244,60 @@ fn cnf_to_dnf_impl(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> Vec<u64> {
                 }
             }
         } else {
-            let mut result_dnf_next: Vec<u64> = Vec::new();
+            let mut result_dnf_next = DnfSet::new(of, n_bits);
 
             for pos in 0..n_bits {
                 if test_bit(disj_val, pos) {
                     let x = 1u64 << pos;
 
                     for &y in &result_dnf {
-                        let z = x | y;
-
-                        let (index_to_delete, add_z) = run_optimized(of, &result_dnf_next, z);
-
-                        if add_z {
-                            // In-place O(n) filtering with BitVec - no allocations!
-                            if !index_to_delete.is_empty() {
-                                // Build deletion bitset - O(m) where m = index_to_delete.len()
-                                let mut to_delete = vec![false; result_dnf_next.len()];
-                                for &idx in &index_to_delete {
-                                    to_delete[idx] = true;
-                                }
-
-                                // Single-pass in-place compaction - O(n)
-                                let len = result_dnf_next.len();
-                                let mut write_idx = 0;
-                                for read_idx in 0..len {
-                                    if !to_delete[read_idx] {
-                                        if write_idx != read_idx {
-                                            result_dnf_next[write_idx] = result_dnf_next[read_idx];
-                                        }
-                                        write_idx += 1;
-                                    }
-                                }
-                                result_dnf_next.truncate(write_idx);
-                            }
-                            result_dnf_next.push(z);
-                        }
+                        result_dnf_next.insert(x | y);
                     }
                 }
             }
 
-            result_dnf = result_dnf_next;
+            result_dnf = result_dnf_next.into_vec();
         }
+
+        literals_processed += count_literals(disj_val, n_bits);
+        progress.clause_done(ClauseProgress {
+            clause_index,
+            n_clauses: cnf.len(),
+            literals_processed,
+            dnf_size: result_dnf.len(),
+            pruned: 0,
+        });
     }
 
     result_dnf
 }
 
-/// Run the appropriate optimization based on the OptimizedFor setting
-fn run_optimized(of: OptimizedFor, result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    match of {
-        OptimizedFor::AutoDetect => {
-            unreachable!(
-                "AutoDetect should be resolved to a concrete optimization level before reaching this point"
-            )
-        }
-        OptimizedFor::X64 => optimized_for_x64(result_dnf_next, z),
-        #[cfg(target_arch = "x86_64")]
-        OptimizedFor::Avx512_64bits => super::simd::run_avx512_64bits(result_dnf_next, z),
-        #[cfg(target_arch = "x86_64")]
-        OptimizedFor::Avx512_32bits => super::simd::run_avx512_32bits(result_dnf_next, z),
-        #[cfg(target_arch = "x86_64")]
-        OptimizedFor::Avx512_16bits => super::simd::run_avx512_16bits(result_dnf_next, z),
-        #[cfg(target_arch = "x86_64")]
-        OptimizedFor::Avx512_8bits => super::simd::run_avx512_8bits(result_dnf_next, z),
-        #[cfg(target_arch = "x86_64")]
-        OptimizedFor::Avx2_64bits => super::simd::run_avx2_64bits(result_dnf_next, z),
-    }
-}
-
-/// Check if we should add z, and return indices to delete (scalar version)
-pub(crate) fn optimized_for_x64(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    let mut index_to_delete = Vec::new();
-
-    for (index, &q) in result_dnf_next.iter().enumerate() {
-        let p = z | q;
-
-        if p == z {
-            // z is subsumed under q: no need to add z
-            return (Vec::new(), false);
-        }
-
-        if p == q {
-            // q is subsumed under z: add z and remove q
-            index_to_delete.push(index);
-        }
-    }
-
-    (index_to_delete, true)
+/// Number of variables `0..n_bits` in `clause`
+#[inline]
+fn count_literals(clause: u64, n_bits: usize) -> usize {
+    let mask = if n_bits >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << n_bits) - 1
+    };
+    (clause & mask).count_ones() as usize
 }
 
 /// Convert CNF to DNF with early pruning optimization, the results contain at least the smallest DNF
 /// with the smallest number of literals. This is not guaranteed to be only the minimal DNF
-fn cnf_to_dnf_minimal_method1(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> Vec<u64> {
+fn cnf_to_dnf_minimal_method1<P: ProgressSink>(
+    cnf: &[u64],
+    n_bits: usize,
+    of: OptimizedFor,
+    progress: &mut P,
+) -> Vec<u64> {
     let n_disjunctions = cnf.len();
     let mut n_disjunction_done = 0;
     let mut result_dnf: Vec<u64> = Vec::new();
+    let mut literals_processed = 0;
 
     for &disj_val in cnf {
+        let mut pruned = 0;
         if n_disjunction_done == 0 {
             for pos in 0..n_bits {
                 if test_bit(disj_val, pos) {
@@ -248,7 +305,7 @@ fn cnf_to_dnf_minimal_method1(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> V
                 }
             }
         } else {
-            let mut result_dnf_next: Vec<u64> = Vec::new();
+            let mut result_dnf_next = DnfSet::new(of, n_bits);
             let mut smallest_cnf_size = i32::MAX;
             let mut max_size = 0;
 
@@ -268,42 +325,28 @@ fn cnf_to_dnf_minimal_method1(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> V
                         }
 
                         let consider_z = max_size >= conjunction_size;
+                        if !consider_z {
+                            pruned += 1;
+                        }
 
                         if consider_z {
-                            let (index_to_delete, add_z) = run_optimized(of, &result_dnf_next, z);
-
-                            if add_z {
-                                // In-place O(n) filtering with BitVec
-                                if !index_to_delete.is_empty() {
-                                    // Build deletion bitset - O(m)
-                                    let len = result_dnf_next.len();
-                                    let mut to_delete = vec![false; len];
-                                    for &idx in &index_to_delete {
-                                        to_delete[idx] = true;
-                                    }
-
-                                    // Single-pass in-place compaction - O(n)
-                                    let mut write_idx = 0;
-                                    for read_idx in 0..len {
-                                        if !to_delete[read_idx] {
-                                            if write_idx != read_idx {
-                                                result_dnf_next[write_idx] =
-                                                    result_dnf_next[read_idx];
-                                            }
-                                            write_idx += 1;
-                                        }
-                                    }
-                                    result_dnf_next.truncate(write_idx);
-                                }
-                                result_dnf_next.push(z);
-                            }
+                            result_dnf_next.insert(z);
                         }
                     }
                 }
             }
 
-            result_dnf = result_dnf_next;
+            result_dnf = result_dnf_next.into_vec();
         }
+
+        literals_processed += count_literals(disj_val, n_bits);
+        progress.clause_done(ClauseProgress {
+            clause_index: n_disjunction_done,
+            n_clauses: n_disjunctions,
+            literals_processed,
+            dnf_size: result_dnf.len(),
+            pruned,
+        });
         n_disjunction_done += 1;
     }
 
@@ -311,7 +354,16 @@ fn cnf_to_dnf_minimal_method1(cnf: &[u64], n_bits: usize, of: OptimizedFor) -> V
 }
 
 /// Convert CNF with string variable names to DNF
+///
+/// Variables are numbered in order of first appearance, and the products are returned
+/// in the canonical order (see [`sort_canonical`]) of that numbering. More than 64
+/// variables are handled by [`cnf_to_dnf_wide`]. Duplicate and superset clauses are
+/// dropped and an empty clause is [`CnfDnfError::Unsatisfiable`], as in [`cnf_to_dnf`].
 pub fn cnf_to_dnf_with_names(cnf: &[Vec<String>]) -> Result<Vec<Vec<String>>, CnfDnfError> {
+    if let Some(clause_index) = cnf.iter().position(|clause| clause.is_empty()) {
+        return Err(CnfDnfError::Unsatisfiable { clause_index });
+    }
+
     // Create translations
     let mut translation1: HashMap<String, usize> = HashMap::new();
     let mut translation2: HashMap<usize, String> = HashMap::new();
@@ -328,7 +380,25 @@ pub fn cnf_to_dnf_with_names(cnf: &[Vec<String>]) -> Result<Vec<Vec<String>>, Cn
     }
 
     if n_variables > 64 {
-        return Err(CnfDnfError::TooManyVariables { n_variables });
+        let mut cnf_wide: Vec<WideClause> = cnf
+            .iter()
+            .map(|conjunction| {
+                WideClause::from_bits(n_variables, conjunction.iter().map(|v| translation1[v]))
+            })
+            .collect();
+        // Same reduction as `reduce_cnf`: a subset clause has fewer literals
+        cnf_wide.sort_by(|a, b| (a.count_ones(), a).cmp(&(b.count_ones(), b)));
+        cnf_wide.dedup();
+        let mut reduced: Vec<WideClause> = Vec::with_capacity(cnf_wide.len());
+        for clause in cnf_wide {
+            if !reduced.iter().any(|smaller| smaller.is_subset_of(&clause)) {
+                reduced.push(clause);
+            }
+        }
+        return Ok(cnf_to_dnf_wide(&reduced, n_variables)
+            .iter()
+            .map(|term| term.ones().map(|pos| translation2[&pos].clone()).collect())
+            .collect());
     }
 
     // Translate CNF to u64
@@ -371,6 +441,94 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
 
+    #[test]
+    fn test_reduce_cnf_removes_duplicates_and_supersets() {
+        // (0|1) & (0|1) & (0|1|2) & (3) & (2|3) & (1|2)
+        let cnf: Vec<u64> = vec![0b0011, 0b0011, 0b0111, 0b1000, 0b1100, 0b0110];
+        let (reduced, reduction) = reduce_cnf(&cnf);
+
+        assert_eq!(reduced, vec![0b0011, 0b1000, 0b0110]);
+        assert_eq!(reduction.duplicates_removed, 1);
+        assert_eq!(reduction.supersets_removed, 2);
+
+        let dnf = |cnf: &[u64]| {
+            let mut dnf =
+                cnf_to_dnf_minimal::<crate::qm::Enc16>(cnf, 4, OptimizedFor::AutoDetect).unwrap();
+            dnf.sort_unstable();
+            dnf
+        };
+        assert_eq!(dnf(&reduced), dnf(&cnf));
+    }
+
+    #[test]
+    fn test_empty_clause_is_unsatisfiable() {
+        let unsat = Err(CnfDnfError::Unsatisfiable { clause_index: 1 });
+        let cnf: Vec<u64> = vec![0b011, 0, 0b110];
+        assert_eq!(
+            cnf_to_dnf::<crate::qm::Enc16>(&cnf, 3, OptimizedFor::X64),
+            unsat
+        );
+        assert_eq!(
+            cnf_to_dnf_minimal::<crate::qm::Enc16>(&cnf, 3, OptimizedFor::X64),
+            unsat
+        );
+        // Variables outside 0..n_bits do not count
+        let cnf: Vec<u64> = vec![0b011, 0b1000];
+        assert_eq!(
+            cnf_to_dnf::<crate::qm::Enc16>(&cnf, 3, OptimizedFor::AutoDetect),
+            unsat
+        );
+
+        let names = |clause: &[&str]| clause.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            cnf_to_dnf_with_names(&[names(&["A"]), names(&[])]),
+            Err(CnfDnfError::Unsatisfiable { clause_index: 1 })
+        );
+        let wide: Vec<Vec<String>> = (0..70)
+            .map(|i| names(&[&format!("x{}", i)]))
+            .chain([names(&[])])
+            .collect();
+        assert_eq!(
+            cnf_to_dnf_with_names(&wide),
+            Err(CnfDnfError::Unsatisfiable { clause_index: 70 })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_and_superset_clauses_are_dropped() {
+        // (0|1) & (0|1) & (0|1|2) & (1|2): only (0|1) & (1|2) is expanded
+        let cnf: Vec<u64> = vec![0b011, 0b011, 0b111, 0b110];
+        let mut n_clauses = Vec::new();
+        let dnf = cnf_to_dnf_with_progress::<crate::qm::Enc16, _>(
+            &cnf,
+            3,
+            OptimizedFor::X64,
+            &mut |e: ClauseProgress| n_clauses.push(e.n_clauses),
+        )
+        .unwrap();
+        assert_eq!(dnf, vec![0b010, 0b101]);
+        assert_eq!(n_clauses, vec![2, 2]);
+
+        let names = |clause: &[&str]| clause.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let named = [
+            names(&["A", "B"]),
+            names(&["B", "A"]),
+            names(&["A", "B", "C"]),
+        ];
+        assert_eq!(
+            cnf_to_dnf_with_names(&named).unwrap(),
+            vec![names(&["A"]), names(&["B"])]
+        );
+        // The wide path reduces the same way
+        let wide: Vec<Vec<String>> = (0..70)
+            .map(|i| names(&["a", &format!("x{}", i)]))
+            .chain([names(&["a", "b"]), names(&["b", "a"])])
+            .collect();
+        let dnf = cnf_to_dnf_with_names(&wide).unwrap();
+        assert_eq!(dnf[0], names(&["a"]));
+        assert_eq!(dnf.len(), 2);
+    }
+
     #[test]
     fn test_cnf_to_dnf_simple() {
         // CNF = (1|2) & (3|4)
@@ -490,4 +648,69 @@ mod tests {
             assert_eq!(term.count_ones(), first_size);
         }
     }
+
+    #[test]
+    fn test_cnf_to_dnf_with_names_beyond_64_variables() {
+        // (v0|v1) & (v2|v3) & (v4|v5) & (v0|v1|...|v69)
+        let cnf: Vec<Vec<String>> = (0..3)
+            .map(|i| vec![format!("v{}", 2 * i), format!("v{}", 2 * i + 1)])
+            .chain([(0..70).map(|i| format!("v{}", i)).collect()])
+            .collect();
+        let dnf = cnf_to_dnf_with_names(&cnf).unwrap();
+
+        // The wide clause absorbs nothing; the three pairs give 8 products
+        assert_eq!(dnf.len(), 8);
+        assert_eq!(dnf[0], vec!["v0", "v2", "v4"]);
+    }
+
+    #[test]
+    fn test_progress_events() {
+        let event =
+            |clause_index, n_clauses, literals_processed, dnf_size, pruned| ClauseProgress {
+                clause_index,
+                n_clauses,
+                literals_processed,
+                dnf_size,
+                pruned,
+            };
+
+        // (0|1) & (1|2) & (0|2): {0},{1} -> {1},{0,2} -> {0,1},{0,2},{1,2}
+        let cnf: Vec<u64> = vec![0b011, 0b110, 0b101];
+        let mut events = Vec::new();
+        let dnf =
+            cnf_to_dnf_with_progress::<crate::qm::Enc16, _>(&cnf, 3, OptimizedFor::X64, &mut |e| {
+                events.push(e)
+            })
+            .unwrap();
+        assert_eq!(dnf, vec![0b011, 0b101, 0b110]);
+        assert_eq!(
+            events,
+            vec![
+                event(0, 3, 2, 2, 0),
+                event(1, 3, 4, 2, 0),
+                event(2, 3, 6, 3, 0)
+            ]
+        );
+
+        // (0|1) & (0|2) & (0|3) & (0|4): the last clause prunes 0123 and 1234
+        let cnf: Vec<u64> = vec![0b00011, 0b00101, 0b01001, 0b10001];
+        let mut events = Vec::new();
+        let dnf = cnf_to_dnf_minimal_with_progress::<crate::qm::Enc16, _>(
+            &cnf,
+            5,
+            OptimizedFor::X64,
+            &mut |e| events.push(e),
+        )
+        .unwrap();
+        assert_eq!(dnf, vec![0b00001]);
+        assert_eq!(
+            events,
+            vec![
+                event(0, 4, 2, 2, 0),
+                event(1, 4, 4, 2, 0),
+                event(2, 4, 6, 2, 0),
+                event(3, 4, 8, 1, 2)
+            ]
+        );
+    }
 }
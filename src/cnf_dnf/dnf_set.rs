@@ -0,0 +1,230 @@
+//! DNF terms in which no term subsumes another
+//!
+//! A term is the bitmask of its (positive) literals, and `q` subsumes `z` when the literals
+//! of `q` are a subset of those of `z` (`q | z == z`): `q` alone already covers `z`.
+
+use super::optimized_for::OptimizedFor;
+
+/// What [`DnfSet::insert`] did with a term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertOutcome {
+    /// The term was added; it neither subsumes nor is subsumed by a term of the set
+    Added,
+    /// The term was added and replaced this many terms it subsumes
+    SubsumedExisting(usize),
+    /// A term of the set (possibly an equal one) subsumes the term; the set is unchanged
+    WasSubsumed,
+}
+
+/// Set of DNF terms kept free of subsumption as terms are inserted
+///
+/// Terms are ordered by literal count: only terms with at most as many literals can
+/// subsume a new term, and only terms with more literals can be subsumed by it, so each
+/// insertion scans two disjoint ranges. The scans run on the subsumption kernel of an
+/// [`OptimizedFor`] level (scalar or SIMD).
+#[derive(Debug, Clone)]
+pub struct DnfSet {
+    /// Ascending literal count
+    terms: Vec<u64>,
+    kernel: OptimizedFor,
+}
+
+impl DnfSet {
+    /// Empty set using the kernel of `of`; `AutoDetect` is resolved for `n_bits` variables
+    pub fn new(of: OptimizedFor, n_bits: usize) -> Self {
+        Self {
+            terms: Vec::new(),
+            kernel: of.resolve(n_bits),
+        }
+    }
+
+    /// Add `term` unless a term of the set subsumes it, removing the terms it subsumes
+    pub fn insert(&mut self, term: u64) -> InsertOutcome {
+        let size = term.count_ones();
+        let larger = self.terms.partition_point(|t| t.count_ones() <= size);
+
+        // A term of equal size subsumes `term` only if it is equal, and is then caught here
+        let (_, add) = run_kernel(self.kernel, &self.terms[..larger], term);
+        if !add {
+            return InsertOutcome::WasSubsumed;
+        }
+        let (index_to_delete, _) = run_kernel(self.kernel, &self.terms[larger..], term);
+
+        let removed = index_to_delete.len();
+        if removed > 0 {
+            // Deletion bitset over the larger terms, then one in-place compaction pass
+            let mut to_delete = vec![false; self.terms.len() - larger];
+            for &idx in &index_to_delete {
+                to_delete[idx] = true;
+            }
+            let mut write_idx = larger;
+            for read_idx in larger..self.terms.len() {
+                if !to_delete[read_idx - larger] {
+                    self.terms[write_idx] = self.terms[read_idx];
+                    write_idx += 1;
+                }
+            }
+            self.terms.truncate(write_idx);
+        }
+        self.terms.insert(larger, term);
+
+        if removed > 0 {
+            InsertOutcome::SubsumedExisting(removed)
+        } else {
+            InsertOutcome::Added
+        }
+    }
+
+    /// The terms by ascending literal count
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.terms.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// The terms by ascending literal count
+    pub fn into_vec(self) -> Vec<u64> {
+        self.terms
+    }
+}
+
+/// Indices of the terms subsumed by `z`, and whether `z` is to be added (no term
+/// subsumes it); the indices are only meaningful when `z` is added
+fn run_kernel(of: OptimizedFor, terms: &[u64], z: u64) -> (Vec<usize>, bool) {
+    match of {
+        OptimizedFor::AutoDetect => {
+            unreachable!(
+                "AutoDetect should be resolved to a concrete optimization level before reaching this point"
+            )
+        }
+        OptimizedFor::X64 => optimized_for_x64(terms, z),
+        #[cfg(target_arch = "x86_64")]
+        OptimizedFor::Avx512_64bits => super::simd::run_avx512_64bits(terms, z),
+        #[cfg(target_arch = "x86_64")]
+        OptimizedFor::Avx512_32bits => super::simd::run_avx512_32bits(terms, z),
+        #[cfg(target_arch = "x86_64")]
+        OptimizedFor::Avx512_16bits => super::simd::run_avx512_16bits(terms, z),
+        #[cfg(target_arch = "x86_64")]
+        OptimizedFor::Avx512_8bits => super::simd::run_avx512_8bits(terms, z),
+        #[cfg(target_arch = "x86_64")]
+        OptimizedFor::Avx2_64bits => super::simd::run_avx2_64bits(terms, z),
+        // SIMD levels exist only on x86_64; elsewhere they run the scalar code
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => optimized_for_x64(terms, z),
+    }
+}
+
+/// Check if we should add z, and return indices to delete (scalar version)
+pub(crate) fn optimized_for_x64(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
+    let mut index_to_delete = Vec::new();
+
+    for (index, &q) in result_dnf_next.iter().enumerate() {
+        let p = z | q;
+
+        if p == z {
+            // z is subsumed under q: no need to add z
+            return (Vec::new(), false);
+        }
+
+        if p == q {
+            // q is subsumed under z: add z and remove q
+            index_to_delete.push(index);
+        }
+    }
+
+    (index_to_delete, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_seeded;
+    use crate::qm::random::test_seed;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    /// The terms of `inserted` that no other (or no earlier equal) term subsumes
+    fn brute_force(inserted: &[u64]) -> Vec<u64> {
+        let mut kept: Vec<u64> = inserted
+            .iter()
+            .enumerate()
+            .filter(|&(i, &z)| {
+                inserted
+                    .iter()
+                    .enumerate()
+                    .all(|(j, &q)| j == i || q | z != z || (q == z && j > i))
+            })
+            .map(|(_, &z)| z)
+            .collect();
+        kept.sort_unstable();
+        kept
+    }
+
+    #[test]
+    fn test_insert_outcomes() {
+        let mut set = DnfSet::new(OptimizedFor::X64, 4);
+        assert_eq!(set.insert(0b0111), InsertOutcome::Added);
+        assert_eq!(set.insert(0b1011), InsertOutcome::Added);
+        assert_eq!(set.insert(0b0011), InsertOutcome::SubsumedExisting(2));
+        assert_eq!(set.insert(0b0011), InsertOutcome::WasSubsumed);
+        assert_eq!(set.insert(0b1111), InsertOutcome::WasSubsumed);
+        assert_eq!(set.insert(0b0100), InsertOutcome::Added);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0b0100, 0b0011]);
+        assert_eq!(set.len(), 2);
+        // The empty product subsumes everything
+        assert_eq!(set.insert(0), InsertOutcome::SubsumedExisting(2));
+        assert_eq!(set.into_vec(), vec![0]);
+    }
+
+    #[test]
+    fn test_matches_brute_force_filter() {
+        let seed = test_seed(11);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for of in [
+            OptimizedFor::X64,
+            OptimizedFor::Avx2_64bits,
+            OptimizedFor::Avx512_64bits,
+            OptimizedFor::Avx512_8bits,
+        ] {
+            for _ in 0..100 {
+                let n_bits = rng.random_range(1..=8);
+                let inserted: Vec<u64> = (0..rng.random_range(0..60))
+                    .map(|_| rng.random_range(0..1u64 << n_bits))
+                    .collect();
+
+                let mut set = DnfSet::new(of, n_bits);
+                for &term in &inserted {
+                    let before = set.len();
+                    match set.insert(term) {
+                        InsertOutcome::Added => assert_eq_seeded!(seed, set.len(), before + 1),
+                        InsertOutcome::SubsumedExisting(n) => {
+                            assert_eq_seeded!(seed, set.len() + n, before + 1)
+                        }
+                        InsertOutcome::WasSubsumed => assert_eq_seeded!(seed, set.len(), before),
+                    }
+                }
+
+                let terms: Vec<u64> = set.iter().collect();
+                for (i, &z) in terms.iter().enumerate() {
+                    for (j, &q) in terms.iter().enumerate() {
+                        assert_eq_seeded!(seed, i != j && q | z == z, false);
+                    }
+                }
+                assert!(
+                    terms
+                        .windows(2)
+                        .all(|w| w[0].count_ones() <= w[1].count_ones())
+                );
+
+                let mut sorted = set.into_vec();
+                sorted.sort_unstable();
+                assert_eq_seeded!(seed, sorted, brute_force(&inserted));
+            }
+        }
+    }
+}
@@ -13,6 +13,8 @@ pub enum CnfDnfError {
     },
     /// The number of variables exceeds the maximum supported (64)
     TooManyVariables { n_variables: usize },
+    /// Clause `clause_index` has no literals, so the CNF is unsatisfiable
+    Unsatisfiable { clause_index: usize },
 }
 
 impl fmt::Display for CnfDnfError {
@@ -43,8 +45,58 @@ impl fmt::Display for CnfDnfError {
                     n_variables
                 )
             }
+            CnfDnfError::Unsatisfiable { clause_index } => {
+                write!(
+                    f,
+                    "clause {} is empty; the CNF is unsatisfiable",
+                    clause_index
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for CnfDnfError {}
+
+/// Why a string is not a CNF or DNF in the format of
+/// [`cnf_to_string`](super::cnf_to_string) / [`dnf_to_string`](super::dnf_to_string)
+///
+/// Positions are byte offsets into the string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Something other than `expected` (or the end of the string) at `position`
+    Unexpected {
+        position: usize,
+        expected: &'static str,
+    },
+    /// A variable index of 64 or more at `position`
+    VariableOutOfRange { position: usize },
+    /// `variable` a second time in the same term, at `position`
+    DuplicateVariable { position: usize, variable: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unexpected { position, expected } => {
+                write!(f, "expected {} at position {}", expected, position)
+            }
+            ParseError::VariableOutOfRange { position } => {
+                write!(
+                    f,
+                    "variable at position {} is out of range (0..=63)",
+                    position
+                )
+            }
+            ParseError::DuplicateVariable { position, variable } => {
+                write!(
+                    f,
+                    "variable {} appears twice in a term (position {})",
+                    variable, position
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
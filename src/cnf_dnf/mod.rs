@@ -4,21 +4,32 @@
 // conversion with SIMD optimizations for x86_64 platforms.
 
 pub mod convert;
+pub mod dnf_set; // Subsumption-free term sets
 pub mod error; // Error types
 pub mod optimized_for; // Optimization level selection
+pub mod progress; // Per-clause progress events
 pub mod utils; // Utility functions (string conversions) // Main conversion logic and algorithms
+pub mod wide; // Clauses over more than 64 variables
 
 #[cfg(target_arch = "x86_64")]
 pub mod simd; // SIMD-optimized implementations (AVX2, AVX512)
 
 // Re-export main types and functions for convenience
 pub use convert::{
+    CnfReduction,
     // Encoding-aware APIs with const generic optimization selection
     cnf_to_dnf,
     cnf_to_dnf_minimal,
     cnf_to_dnf_minimal_reference,
+    cnf_to_dnf_minimal_with_progress,
+    cnf_to_dnf_with_progress,
     cnf_to_dnf_with_names,
+    reduce_cnf,
+    sort_canonical,
 };
-pub use error::CnfDnfError;
+pub use dnf_set::{DnfSet, InsertOutcome};
+pub use error::{CnfDnfError, ParseError};
 pub use optimized_for::OptimizedFor;
-pub use utils::{cnf_to_string, dnf_to_string};
+pub use progress::{ClauseProgress, NoProgress, ProgressSink};
+pub use utils::{cnf_to_string, dnf_to_string, parse_cnf_string, parse_dnf_string};
+pub use wide::{WideClause, cnf_to_dnf_minimal_wide, cnf_to_dnf_wide};
@@ -179,3 +179,24 @@ impl fmt::Display for OptimizedFor {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// Log (at debug level, once per kernel) that a SIMD kernel runs its scalar fallback
+///
+/// Called from the hot paths of the SIMD wrappers; without a debug logger this is a single
+/// level check.
+pub(crate) fn debug_scalar_fallback(kernel: &'static str) {
+    use std::sync::Mutex;
+    static LOGGED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    if !log::log_enabled!(log::Level::Debug) {
+        return;
+    }
+    let mut logged = LOGGED.lock().unwrap_or_else(|e| e.into_inner());
+    if !logged.contains(&kernel) {
+        logged.push(kernel);
+        log::debug!(
+            "{}: required SIMD instructions not available; using the scalar fallback",
+            kernel
+        );
+    }
+}
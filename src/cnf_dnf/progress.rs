@@ -0,0 +1,43 @@
+// Progress reporting for CNF to DNF conversion
+//
+// The conversion loop is generic over the sink, so the default `NoProgress` sink is
+// compiled away entirely.
+
+/// State after multiplying in one clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClauseProgress {
+    /// Index of the clause just processed
+    pub clause_index: usize,
+    /// Number of clauses in the CNF
+    pub n_clauses: usize,
+    /// Literals in clauses `0..=clause_index`
+    pub literals_processed: usize,
+    /// Number of products in the DNF so far
+    pub dnf_size: usize,
+    /// Candidate products of this clause dropped because they cannot become a smallest
+    /// product (always 0 for the full conversion)
+    pub pruned: usize,
+}
+
+/// Receives a [`ClauseProgress`] event after every clause
+///
+/// Implemented by [`NoProgress`] and by any `FnMut(ClauseProgress)` closure.
+pub trait ProgressSink {
+    fn clause_done(&mut self, progress: ClauseProgress);
+}
+
+/// Sink that ignores all events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    #[inline(always)]
+    fn clause_done(&mut self, _progress: ClauseProgress) {}
+}
+
+impl<F: FnMut(ClauseProgress)> ProgressSink for F {
+    #[inline]
+    fn clause_done(&mut self, progress: ClauseProgress) {
+        self(progress)
+    }
+}
@@ -241,7 +241,8 @@ pub fn run_avx512_64bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool)
     if is_x86_feature_detected!("avx512f") {
         unsafe { optimized_for_avx512_epi64_internal(result_dnf_next, z) }
     } else {
-        super::convert::optimized_for_x64(result_dnf_next, z)
+        super::optimized_for::debug_scalar_fallback("run_avx512_64bits");
+        super::dnf_set::optimized_for_x64(result_dnf_next, z)
     }
 }
 
@@ -253,7 +254,8 @@ pub fn run_avx512_32bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool)
         let result_u32: Vec<u32> = result_dnf_next.iter().map(|&x| x as u32).collect();
         unsafe { optimized_for_avx512_epi32_internal(&result_u32, z as u32) }
     } else {
-        super::convert::optimized_for_x64(result_dnf_next, z)
+        super::optimized_for::debug_scalar_fallback("run_avx512_32bits");
+        super::dnf_set::optimized_for_x64(result_dnf_next, z)
     }
 }
 
@@ -265,7 +267,8 @@ pub fn run_avx512_16bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool)
         let result_u16: Vec<u16> = result_dnf_next.iter().map(|&x| x as u16).collect();
         unsafe { optimized_for_avx512_epi16_internal(&result_u16, z as u16) }
     } else {
-        super::convert::optimized_for_x64(result_dnf_next, z)
+        super::optimized_for::debug_scalar_fallback("run_avx512_16bits");
+        super::dnf_set::optimized_for_x64(result_dnf_next, z)
     }
 }
 
@@ -275,7 +278,8 @@ pub fn run_avx512_8bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
     if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512bw") {
         unsafe { optimized_for_avx512_epi8(result_dnf_next, z) }
     } else {
-        super::convert::optimized_for_x64(result_dnf_next, z)
+        super::optimized_for::debug_scalar_fallback("run_avx512_8bits");
+        super::dnf_set::optimized_for_x64(result_dnf_next, z)
     }
 }
 
@@ -286,34 +290,40 @@ pub fn run_avx2_64bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
         // No type conversion needed - u64 is processed natively
         unsafe { optimized_for_avx2_epi64_internal(result_dnf_next, z) }
     } else {
-        super::convert::optimized_for_x64(result_dnf_next, z)
+        super::optimized_for::debug_scalar_fallback("run_avx2_64bits");
+        super::dnf_set::optimized_for_x64(result_dnf_next, z)
     }
 }
 
 // Fallback implementations for non-x86_64 architectures
 #[cfg(not(target_arch = "x86_64"))]
 pub fn run_avx512_64bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    super::convert::optimized_for_x64(result_dnf_next, z)
+    super::optimized_for::debug_scalar_fallback("run_avx512_64bits");
+    super::dnf_set::optimized_for_x64(result_dnf_next, z)
 }
 
 #[cfg(not(target_arch = "x86_64"))]
 pub fn run_avx512_32bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    super::convert::optimized_for_x64(result_dnf_next, z)
+    super::optimized_for::debug_scalar_fallback("run_avx512_32bits");
+    super::dnf_set::optimized_for_x64(result_dnf_next, z)
 }
 
 #[cfg(not(target_arch = "x86_64"))]
 pub fn run_avx512_16bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    super::convert::optimized_for_x64(result_dnf_next, z)
+    super::optimized_for::debug_scalar_fallback("run_avx512_16bits");
+    super::dnf_set::optimized_for_x64(result_dnf_next, z)
 }
 
 #[cfg(not(target_arch = "x86_64"))]
 pub fn run_avx512_8bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    super::convert::optimized_for_x64(result_dnf_next, z)
+    super::optimized_for::debug_scalar_fallback("run_avx512_8bits");
+    super::dnf_set::optimized_for_x64(result_dnf_next, z)
 }
 
 #[cfg(not(target_arch = "x86_64"))]
 pub fn run_avx2_64bits(result_dnf_next: &[u64], z: u64) -> (Vec<usize>, bool) {
-    super::convert::optimized_for_x64(result_dnf_next, z)
+    super::optimized_for::debug_scalar_fallback("run_avx2_64bits");
+    super::dnf_set::optimized_for_x64(result_dnf_next, z)
 }
 
 #[cfg(test)]
@@ -1,9 +1,19 @@
+use super::error::ParseError;
+
 /// Convert CNF to string representation
+///
+/// Clauses are written in ascending order, each in parentheses with the indices of its
+/// variables in ascending order joined by `|`, and the clauses joined by ` & `:
+/// `(0|2) & (1)`. An empty clause is `()`, an empty CNF the empty string.
+/// [`parse_cnf_string`] reads it back.
 pub fn cnf_to_string(cnf: &[u64]) -> String {
     to_string(cnf, true)
 }
 
 /// Convert DNF to string representation
+///
+/// As [`cnf_to_string`] with the operators swapped: `(0&2) | (1)`. [`parse_dnf_string`]
+/// reads it back.
 pub fn dnf_to_string(dnf: &[u64]) -> String {
     to_string(dnf, false)
 }
@@ -43,8 +53,232 @@ fn to_string(terms: &[u64], is_cnf: bool) -> String {
     result
 }
 
+/// Parse a CNF in the format of [`cnf_to_string`]
+///
+/// Whitespace between tokens is ignored, and clauses and variables may come in any order;
+/// the clauses are returned in the order of the string. A variable may appear only once
+/// per clause.
+pub fn parse_cnf_string(cnf: &str) -> Result<Vec<u64>, ParseError> {
+    parse(cnf, true)
+}
+
+/// Parse a DNF in the format of [`dnf_to_string`], as [`parse_cnf_string`]
+pub fn parse_dnf_string(dnf: &str) -> Result<Vec<u64>, ParseError> {
+    parse(dnf, false)
+}
+
+/// Parse CNF or DNF
+fn parse(text: &str, is_cnf: bool) -> Result<Vec<u64>, ParseError> {
+    let (outer, inner) = if is_cnf { ('&', '|') } else { ('|', '&') };
+    let (expected_outer, expected_inner) = if is_cnf {
+        ("'&'", "'|' or ')'")
+    } else {
+        ("'|'", "'&' or ')'")
+    };
+    let mut scanner = Scanner { text, position: 0 };
+    let mut terms = Vec::new();
+
+    if scanner.at_end() {
+        return Ok(terms);
+    }
+    loop {
+        scanner.expect('(', "'('")?;
+        let mut term = 0u64;
+        if !scanner.eat(')') {
+            loop {
+                let (position, variable) = scanner.variable()?;
+                if test_bit(term, variable) {
+                    return Err(ParseError::DuplicateVariable { position, variable });
+                }
+                term |= 1 << variable;
+                if scanner.eat(')') {
+                    break;
+                }
+                scanner.expect(inner, expected_inner)?;
+            }
+        }
+        terms.push(term);
+
+        if scanner.at_end() {
+            return Ok(terms);
+        }
+        scanner.expect(outer, expected_outer)?;
+    }
+}
+
+/// Position in the string being parsed
+struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Scanner<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.position == self.text.len()
+    }
+
+    /// Consume `c` if it is the next token
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.text[self.position..].starts_with(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(ParseError::Unexpected {
+                position: self.position,
+                expected,
+            })
+        }
+    }
+
+    /// A variable index, and where it starts
+    fn variable(&mut self) -> Result<(usize, usize), ParseError> {
+        self.skip_whitespace();
+        let start = self.position;
+        let digits = self.text[start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Err(ParseError::Unexpected {
+                position: start,
+                expected: "a variable",
+            });
+        }
+        self.position += digits;
+        match self.text[start..self.position].parse::<usize>() {
+            Ok(variable) if variable < u64::BITS as usize => Ok((start, variable)),
+            _ => Err(ParseError::VariableOutOfRange { position: start }),
+        }
+    }
+}
+
 /// Test if a bit is set at a given position
 #[inline]
 pub fn test_bit(data: u64, pos: usize) -> bool {
     (data >> pos) & 1 == 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_formatted_strings() {
+        assert_eq!(cnf_to_string(&[0b10, 0b101]), "(1) & (0|2)");
+        assert_eq!(parse_cnf_string("(1) & (0|2)"), Ok(vec![0b10, 0b101]));
+        assert_eq!(dnf_to_string(&[0b101, 0b10]), "(1) | (0&2)");
+        assert_eq!(parse_dnf_string("(1) | (0&2)"), Ok(vec![0b10, 0b101]));
+
+        // No terms, and a single empty term
+        assert_eq!(parse_cnf_string(""), Ok(vec![]));
+        assert_eq!(parse_dnf_string("()"), Ok(vec![0]));
+        assert_eq!(
+            parse_cnf_string(&cnf_to_string(&[1 << 63])),
+            Ok(vec![1 << 63])
+        );
+    }
+
+    #[test]
+    fn test_parse_is_lenient_on_whitespace_and_order() {
+        assert_eq!(parse_cnf_string(" ( 2 | 0 )&(1) "), Ok(vec![0b101, 0b10]));
+        assert_eq!(parse_dnf_string("(3&1)|()"), Ok(vec![0b1010, 0]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // A DNF is not a CNF (unless every term has a single variable)
+        assert_eq!(
+            parse_cnf_string("(0&1)"),
+            Err(ParseError::Unexpected {
+                position: 2,
+                expected: "'|' or ')'"
+            })
+        );
+        assert_eq!(
+            parse_dnf_string("(0) & (1)"),
+            Err(ParseError::Unexpected {
+                position: 4,
+                expected: "'|'"
+            })
+        );
+        assert_eq!(
+            parse_cnf_string("(0)(1)"),
+            Err(ParseError::Unexpected {
+                position: 3,
+                expected: "'&'"
+            })
+        );
+        assert_eq!(
+            parse_cnf_string("(0|)"),
+            Err(ParseError::Unexpected {
+                position: 3,
+                expected: "a variable"
+            })
+        );
+        assert_eq!(
+            parse_cnf_string("(0"),
+            Err(ParseError::Unexpected {
+                position: 2,
+                expected: "'|' or ')'"
+            })
+        );
+        assert_eq!(
+            parse_cnf_string("0|1"),
+            Err(ParseError::Unexpected {
+                position: 0,
+                expected: "'('"
+            })
+        );
+        assert_eq!(
+            parse_dnf_string("(1&64)"),
+            Err(ParseError::VariableOutOfRange { position: 3 })
+        );
+        assert_eq!(
+            parse_dnf_string("(99999999999999999999999)"),
+            Err(ParseError::VariableOutOfRange { position: 1 })
+        );
+        assert_eq!(
+            parse_dnf_string("(1&2&1)"),
+            Err(ParseError::DuplicateVariable {
+                position: 5,
+                variable: 1
+            })
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_cnf_round_trip(cnf in prop::collection::vec(any::<u64>(), 0..8)) {
+            let text = cnf_to_string(&cnf);
+            let mut sorted = cnf.clone();
+            sorted.sort_unstable();
+            prop_assert_eq!(parse_cnf_string(&text), Ok(sorted.clone()));
+            prop_assert_eq!(cnf_to_string(&sorted), text);
+        }
+
+        #[test]
+        fn prop_dnf_round_trip(dnf in prop::collection::vec(any::<u64>(), 0..8)) {
+            let text = dnf_to_string(&dnf);
+            let mut sorted = dnf.clone();
+            sorted.sort_unstable();
+            prop_assert_eq!(parse_dnf_string(&text), Ok(sorted.clone()));
+            prop_assert_eq!(dnf_to_string(&sorted), text);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// CNF to DNF conversion for more than 64 variables
+//
+// Same algorithm as `convert`, with every clause and product stored as a vector of u64
+// words instead of a single u64. Subsumption is tested word by word (scalar).
+
+use std::cmp::Ordering;
+
+/// A clause (or product term) over any number of variables: bit `i` is variable `i`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WideClause {
+    words: Vec<u64>,
+}
+
+impl WideClause {
+    /// Empty clause with room for `n_bits` variables
+    pub fn new(n_bits: usize) -> Self {
+        Self {
+            words: vec![0; n_bits.div_ceil(64)],
+        }
+    }
+
+    /// Clause with the given variables set
+    pub fn from_bits(n_bits: usize, bits: impl IntoIterator<Item = usize>) -> Self {
+        let mut clause = Self::new(n_bits);
+        for bit in bits {
+            clause.set_bit(bit);
+        }
+        clause
+    }
+
+    /// Clause from a u64 clause of the narrow representation
+    pub fn from_u64(value: u64, n_bits: usize) -> Self {
+        let mut clause = Self::new(n_bits.max(1));
+        clause.words[0] = value;
+        clause
+    }
+
+    /// The clause as a u64, `None` if it uses a variable beyond 63
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.words.split_first() {
+            None => Some(0),
+            Some((&low, high)) => high.iter().all(|&w| w == 0).then_some(low),
+        }
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    #[inline]
+    pub fn test_bit(&self, pos: usize) -> bool {
+        self.words
+            .get(pos / 64)
+            .is_some_and(|&w| (w >> (pos % 64)) & 1 == 1)
+    }
+
+    #[inline]
+    pub fn set_bit(&mut self, pos: usize) {
+        if pos / 64 >= self.words.len() {
+            self.words.resize(pos / 64 + 1, 0);
+        }
+        self.words[pos / 64] |= 1u64 << (pos % 64);
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    /// Positions of the set bits, ascending
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &w)| {
+            (0..64)
+                .filter(move |&b| (w >> b) & 1 == 1)
+                .map(move |b| i * 64 + b)
+        })
+    }
+
+    /// Is every variable of `self` also in `other`?
+    #[inline]
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(i, &w)| w & !other.words.get(i).copied().unwrap_or(0) == 0)
+    }
+}
+
+impl Ord for WideClause {
+    /// Numeric order, as if the words were one big unsigned integer
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.words.len().max(other.words.len());
+        (0..len)
+            .rev()
+            .map(|i| {
+                let a = self.words.get(i).copied().unwrap_or(0);
+                let b = other.words.get(i).copied().unwrap_or(0);
+                a.cmp(&b)
+            })
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for WideClause {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Canonical order of DNF terms, as [`sort_canonical`](super::convert::sort_canonical)
+pub fn sort_canonical_wide(dnf: &mut [WideClause]) {
+    dnf.sort_unstable_by(|a, b| (a.count_ones(), a).cmp(&(b.count_ones(), b)));
+}
+
+/// Convert CNF to DNF for any number of variables
+///
+/// Wide counterpart of [`cnf_to_dnf`](super::convert::cnf_to_dnf); the products are
+/// returned in canonical order.
+pub fn cnf_to_dnf_wide(cnf: &[WideClause], n_bits: usize) -> Vec<WideClause> {
+    let mut result = expand(cnf, n_bits, false);
+    sort_canonical_wide(&mut result);
+    result
+}
+
+/// The products with the fewest literals, for any number of variables
+///
+/// Wide counterpart of [`cnf_to_dnf_minimal`](super::convert::cnf_to_dnf_minimal).
+pub fn cnf_to_dnf_minimal_wide(cnf: &[WideClause], n_bits: usize) -> Vec<WideClause> {
+    let result = expand(cnf, n_bits, true);
+    let smallest = result.iter().map(WideClause::count_ones).min();
+    let mut result: Vec<WideClause> = result
+        .into_iter()
+        .filter(|term| Some(term.count_ones()) == smallest)
+        .collect();
+    sort_canonical_wide(&mut result);
+    result
+}
+
+/// Multiply out the clauses, keeping only products that are not subsumed
+///
+/// With `prune`, products that can no longer become a smallest product are dropped early
+/// (the results then contain at least all smallest products).
+fn expand(cnf: &[WideClause], n_bits: usize, prune: bool) -> Vec<WideClause> {
+    let mut result_dnf: Vec<WideClause> = Vec::new();
+
+    for (n_done, disjunction) in cnf.iter().enumerate() {
+        let positions: Vec<usize> = disjunction.ones().filter(|&pos| pos < n_bits).collect();
+        if n_done == 0 {
+            result_dnf = positions
+                .iter()
+                .map(|&pos| WideClause::from_bits(n_bits, [pos]))
+                .collect();
+            continue;
+        }
+
+        let mut result_dnf_next: Vec<WideClause> = Vec::new();
+        let mut smallest_size = u32::MAX;
+        let mut max_size = 0;
+
+        for &pos in &positions {
+            for y in &result_dnf {
+                let mut z = y.clone();
+                z.set_bit(pos);
+
+                if prune {
+                    let size = z.count_ones();
+                    if size < smallest_size {
+                        smallest_size = size;
+                        max_size = size + (cnf.len() - n_done) as u32;
+                    }
+                    if size > max_size {
+                        continue;
+                    }
+                }
+
+                // z is subsumed under some q: no need to add z
+                if result_dnf_next.iter().any(|q| q.is_subset_of(&z)) {
+                    continue;
+                }
+                // q is subsumed under z: remove q
+                result_dnf_next.retain(|q| !z.is_subset_of(q));
+                result_dnf_next.push(z);
+            }
+        }
+
+        result_dnf = result_dnf_next;
+    }
+
+    result_dnf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::convert::{cnf_to_dnf, cnf_to_dnf_minimal};
+    use super::super::optimized_for::OptimizedFor;
+    use super::*;
+    use crate::qm::Enc64;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_cnf(rng: &mut StdRng, n_bits: usize) -> Vec<u64> {
+        (0..rng.random_range(1..8))
+            .map(|_| {
+                (0..rng.random_range(1..4))
+                    .fold(0u64, |c, _| c | 1u64 << rng.random_range(0..n_bits))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_wide_matches_u64_path() {
+        for seed in 0..30u64 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let n_bits = rng.random_range(2..=64);
+            let cnf = random_cnf(&mut rng, n_bits);
+            let wide: Vec<WideClause> = cnf
+                .iter()
+                .map(|&c| WideClause::from_u64(c, n_bits))
+                .collect();
+            let narrow = |dnf: Vec<WideClause>| -> Vec<u64> {
+                dnf.iter().map(|t| t.to_u64().unwrap()).collect()
+            };
+
+            assert_eq!(
+                narrow(cnf_to_dnf_wide(&wide, n_bits)),
+                cnf_to_dnf::<Enc64>(&cnf, n_bits, OptimizedFor::X64).unwrap(),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                narrow(cnf_to_dnf_minimal_wide(&wide, n_bits)),
+                cnf_to_dnf_minimal::<Enc64>(&cnf, n_bits, OptimizedFor::X64).unwrap(),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_100_variables() {
+        // (0|70) & (70|99) & (1|99) = (1&70) | (0&99) | (70&99)
+        let n_bits = 100;
+        let cnf = vec![
+            WideClause::from_bits(n_bits, [0, 70]),
+            WideClause::from_bits(n_bits, [70, 99]),
+            WideClause::from_bits(n_bits, [1, 99]),
+        ];
+        let dnf = cnf_to_dnf_wide(&cnf, n_bits);
+        let products: Vec<Vec<usize>> = dnf.iter().map(|t| t.ones().collect()).collect();
+        assert_eq!(products, vec![vec![1, 70], vec![0, 99], vec![70, 99]]);
+        assert!(dnf.iter().all(|t| t.to_u64().is_none()));
+
+        // All products are smallest
+        assert_eq!(cnf_to_dnf_minimal_wide(&cnf, n_bits), dnf);
+    }
+}
@@ -0,0 +1,249 @@
+//! C ABI for calling the minimizer in-process (feature `ffi`)
+//!
+//! Every function returns one of the `QM_*` status codes. On success the JSON result is
+//! written to `*out_json`; on [`QM_ERR_INVALID_INPUT`] `*out_json` holds the error message
+//! instead. Either way the string is owned by the caller and must be released with
+//! [`qm_free_string`]. Panics never cross the boundary: they are reported as
+//! [`QM_ERR_PANIC`].
+//!
+//! The header `include/qm_agent.h` is generated with
+//! `cbindgen --config cbindgen.toml --output include/qm_agent.h`; build the library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib` (or `staticlib`).
+
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Success
+pub const QM_OK: i32 = 0;
+/// A required pointer argument was null
+pub const QM_ERR_NULL_POINTER: i32 = 1;
+/// An input string is not valid UTF-8
+pub const QM_ERR_INVALID_UTF8: i32 = 2;
+/// More variables than the minimizer supports (64)
+pub const QM_ERR_TOO_MANY_VARIABLES: i32 = 3;
+/// The request was rejected; `*out_json` holds the error message
+pub const QM_ERR_INVALID_INPUT: i32 = 4;
+/// An internal error (a panic) was caught at the boundary
+pub const QM_ERR_PANIC: i32 = 5;
+
+/// Most variables [`qm_minimize`] accepts
+const MAX_VARIABLES: u32 = 64;
+
+/// Minimize the function with `len` minterms and `dlen` don't-cares over `variables` inputs
+///
+/// Writes the [`QMResult`](crate::QMResult) as JSON to `*out_json`. `dont_cares` may be
+/// null when `dlen` is 0.
+///
+/// # Safety
+///
+/// `minterms` must point to `len` readable `u64`s (or be null when `len` is 0), likewise
+/// `dont_cares` and `dlen`; `out_json` must be a valid pointer to write a `char *` to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn qm_minimize(
+    minterms: *const u64,
+    len: usize,
+    dont_cares: *const u64,
+    dlen: usize,
+    variables: u32,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        return QM_ERR_NULL_POINTER;
+    }
+    // SAFETY: checked non-null above; the caller guarantees it is writable
+    unsafe { *out_json = ptr::null_mut() };
+    if variables > MAX_VARIABLES {
+        return QM_ERR_TOO_MANY_VARIABLES;
+    }
+    // SAFETY: the caller guarantees `len` / `dlen` readable elements behind non-null pointers
+    let (Some(minterms), Some(dont_cares)) = (unsafe { slice_arg(minterms, len) }, unsafe {
+        slice_arg(dont_cares, dlen)
+    }) else {
+        return QM_ERR_NULL_POINTER;
+    };
+
+    let result = guard(|| {
+        if let Some(&m) = minterms
+            .iter()
+            .chain(dont_cares)
+            .find(|&&m| variables < 64 && m >> variables != 0)
+        {
+            return (
+                QM_ERR_INVALID_INPUT,
+                format!("minterm {} does not fit in {} variables", m, variables),
+            );
+        }
+        let result = crate::minimize_function(minterms, Some(dont_cares), variables as usize);
+        match serde_json::to_string(&result) {
+            Ok(json) => (QM_OK, json),
+            Err(e) => (QM_ERR_INVALID_INPUT, format!("JSON serialize error: {}", e)),
+        }
+    });
+    // SAFETY: checked non-null above
+    unsafe { write_result(result, out_json) }
+}
+
+/// Run [`simplify_from_json`](crate::agent_api::simplify_from_json) on the NUL-terminated
+/// request `input`, writing the response JSON to `*out_json`
+///
+/// # Safety
+///
+/// `input` must be a valid NUL-terminated string and `out_json` a valid pointer to write a
+/// `char *` to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn qm_simplify_json(input: *const c_char, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() || input.is_null() {
+        return QM_ERR_NULL_POINTER;
+    }
+    // SAFETY: checked non-null above; the caller guarantees it is writable
+    unsafe { *out_json = ptr::null_mut() };
+    // SAFETY: the caller guarantees a NUL-terminated string
+    let Ok(input) = unsafe { CStr::from_ptr(input) }.to_str() else {
+        return QM_ERR_INVALID_UTF8;
+    };
+
+    let result = guard(|| match crate::agent_api::simplify_from_json(input) {
+        Ok(json) => (QM_OK, json),
+        Err(message) => (QM_ERR_INVALID_INPUT, message),
+    });
+    // SAFETY: checked non-null above
+    unsafe { write_result(result, out_json) }
+}
+
+/// Release a string returned by this library; null is ignored
+///
+/// # Safety
+///
+/// `s` must be null or a string returned through `out_json`, not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn qm_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the string was created by `CString::into_raw` in `write_result`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// `len` elements at `data`; `None` for a null pointer with a non-zero length
+unsafe fn slice_arg<'a>(data: *const u64, len: usize) -> Option<&'a [u64]> {
+    if len == 0 {
+        Some(&[])
+    } else if data.is_null() {
+        None
+    } else {
+        // SAFETY: forwarded from the caller of the `extern "C"` function
+        Some(unsafe { std::slice::from_raw_parts(data, len) })
+    }
+}
+
+/// Run `f`, turning a panic into [`QM_ERR_PANIC`]
+fn guard(f: impl FnOnce() -> (i32, String)) -> Option<(i32, String)> {
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// Hand the message of `result` to the caller; returns its status code
+unsafe fn write_result(result: Option<(i32, String)>, out_json: *mut *mut c_char) -> i32 {
+    let Some((status, text)) = result else {
+        return QM_ERR_PANIC;
+    };
+    // JSON escapes NUL; an error message could in principle contain one
+    let text = CString::new(text.replace('\0', "\\u0000")).expect("NUL bytes were replaced");
+    // SAFETY: forwarded from the caller of the `extern "C"` function
+    unsafe { *out_json = text.into_raw() };
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take ownership of a returned string
+    fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null());
+        let text = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { qm_free_string(s) };
+        text
+    }
+
+    #[test]
+    fn test_minimize_through_c_abi() {
+        let minterms = [1u64, 3, 7];
+        let dont_cares = [2u64, 4];
+        let mut out = ptr::null_mut();
+        let status = unsafe {
+            qm_minimize(
+                minterms.as_ptr(),
+                minterms.len(),
+                dont_cares.as_ptr(),
+                dont_cares.len(),
+                3,
+                &mut out,
+            )
+        };
+        assert_eq!(status, QM_OK);
+        let result: crate::QMResult = serde_json::from_str(&take(out)).unwrap();
+        assert_eq!(result.minterms, vec![1, 3, 7]);
+
+        // No don't-cares: a null pointer is fine
+        let status = unsafe { qm_minimize(minterms.as_ptr(), 3, ptr::null(), 0, 3, &mut out) };
+        assert_eq!(status, QM_OK);
+        take(out);
+    }
+
+    #[test]
+    fn test_minimize_error_codes() {
+        let minterms = [1u64];
+        let mut out = ptr::null_mut();
+        let status = unsafe { qm_minimize(minterms.as_ptr(), 1, ptr::null(), 0, 65, &mut out) };
+        assert_eq!(status, QM_ERR_TOO_MANY_VARIABLES);
+        assert!(out.is_null());
+
+        let status = unsafe { qm_minimize(ptr::null(), 1, ptr::null(), 0, 3, &mut out) };
+        assert_eq!(status, QM_ERR_NULL_POINTER);
+
+        let status = unsafe { qm_minimize(minterms.as_ptr(), 1, ptr::null(), 0, 0, &mut out) };
+        assert_eq!(status, QM_ERR_INVALID_INPUT);
+        assert!(take(out).contains("does not fit in 0 variables"));
+    }
+
+    #[test]
+    fn test_simplify_through_c_abi() {
+        let request = CString::new(
+            r#"{"variables": {"a": "boolean", "b": "boolean"},
+                "branches": [{"condition": "a && b", "output": "1"},
+                             {"condition": "a && !b", "output": "1"}],
+                "default": "0"}"#,
+        )
+        .unwrap();
+        let mut out = ptr::null_mut();
+        let status = unsafe { qm_simplify_json(request.as_ptr(), &mut out) };
+        assert_eq!(status, QM_OK);
+        let response: serde_json::Value = serde_json::from_str(&take(out)).unwrap();
+        assert_eq!(response["simplified_branches"][0]["condition"], "a");
+
+        let invalid = CString::new(r#"{"branches": 1}"#).unwrap();
+        let status = unsafe { qm_simplify_json(invalid.as_ptr(), &mut out) };
+        assert_eq!(status, QM_ERR_INVALID_INPUT);
+        assert!(take(out).contains("JSON parse error"));
+    }
+
+    #[test]
+    fn test_simplify_invalid_utf8() {
+        let bytes = CString::new(vec![b'{', 0xff, b'}']).unwrap();
+        let mut out = ptr::null_mut();
+        let status = unsafe { qm_simplify_json(bytes.as_ptr(), &mut out) };
+        assert_eq!(status, QM_ERR_INVALID_UTF8);
+        assert!(out.is_null());
+
+        let status = unsafe { qm_simplify_json(ptr::null(), &mut out) };
+        assert_eq!(status, QM_ERR_NULL_POINTER);
+    }
+
+    #[test]
+    fn test_panic_is_caught() {
+        assert_eq!(
+            unsafe { write_result(guard(|| panic!("boom")), &mut ptr::null_mut()) },
+            QM_ERR_PANIC
+        );
+    }
+}
@@ -2,20 +2,33 @@
 //!
 //! A Rust library for Boolean function minimization using the Quine-McCluskey
 //! algorithm with Petrick's method.
+//!
+//! `use qm_agent::prelude::*;` brings in the solver, the encodings, CNF to DNF conversion
+//! and the if-then-else simplifier; see [`prelude`].
 
 #![feature(adt_const_params)]
 #![allow(incomplete_features)]
 
 pub mod agent_api;
 pub mod cnf_dnf; // CNF to DNF conversion with SIMD
+#[cfg(feature = "ffi")]
+pub mod ffi; // C ABI for in-process callers
+pub mod prelude; // One-line import of the common surface
+#[cfg(feature = "python")]
+pub mod python; // Python extension module
 pub mod qm; // Quine-McCluskey algorithm and solver
 pub mod simplify; // If-then-else simplification // JSON API for Claude integration
+#[cfg(feature = "wasm")]
+pub mod wasm; // JavaScript bindings
 
 // Re-export the main types
 pub use qm::PetricksMethod;
-pub use qm::{BitState, Implicant, QuineMcCluskey};
+pub use qm::{BitState, Implicant, QuineMcCluskey, TracedImplicant};
 pub use qm::{Enc16, Enc32, Enc64, MintermEncoding};
-pub use qm::{QMResult, QMSolver, SolveMethod};
+pub use qm::{BestForm, BestFormResult, BoundedResult, CoverStrategy, Objective, Polarity, QMResult, QMSolver, SolveMethod, TieBreak};
+pub use qm::{BuildError, QMSolverBuilder, SolveError, VariableNamesError};
+pub use qm::{FactorStyle, FactoredExpression};
+pub use qm::{MultiOutputTable, minimize_truth_table_multi};
 
 /// Convenience function to minimize a Boolean function (up to 64 variables)
 ///
@@ -23,6 +36,11 @@ pub use qm::{QMResult, QMSolver, SolveMethod};
 /// - Enc16 (u32 storage) for up to 16 variables
 /// - Enc32 (u64 storage) for up to 32 variables
 /// - Enc64 (u128 storage) for up to 64 variables
+///
+/// `minterms` and `dont_cares` may be unsorted and contain repeats; they are sorted and
+/// deduplicated before solving, so `original_minterms` and `cost_original` count each
+/// minterm once. The number of repeats dropped is in
+/// [`duplicates_removed`](QMResult::duplicates_removed).
 pub fn minimize_function(
     minterms: &[u64],
     dont_cares: Option<&[u64]>,
@@ -80,21 +98,173 @@ pub fn generate_variable_names(count: usize) -> Vec<String> {
         .collect()
 }
 
-/// Parse a minterm string like "1,3,7,15"
-pub fn parse_minterms(input: &str) -> Result<Vec<u64>, std::num::ParseIntError> {
-    input.split(',').map(|s| s.trim().parse()).collect()
+/// A token of a minterm list that is not a valid literal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintermParseError {
+    /// Position of the token in the comma-separated list (0-based)
+    pub index: usize,
+    pub token: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for MintermParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid minterm '{}' at index {}: {}",
+            self.token, self.index, self.message
+        )
+    }
+}
+
+impl std::error::Error for MintermParseError {}
+
+/// Parse a single minterm literal: decimal (`45`), hex (`0x2D`) or binary (`0b101101`)
+///
+/// Underscores may separate digits (`0b10_1101`).
+pub fn parse_minterm(token: &str) -> Result<u64, String> {
+    let (digits, radix) = match token.get(..2) {
+        Some("0x" | "0X") => (&token[2..], 16),
+        Some("0b" | "0B") => (&token[2..], 2),
+        _ => (token, 10),
+    };
+    if digits.starts_with('_') || digits.ends_with('_') {
+        return Err("'_' may only separate digits".to_string());
+    }
+    let digits = digits.replace('_', "");
+    if digits.is_empty() {
+        return Err("missing digits".to_string());
+    }
+    u64::from_str_radix(&digits, radix).map_err(|e| e.to_string())
+}
+
+/// Parse a minterm string like "1,3,7,15" (or "0x1, 0b11, 7, 0xF")
+pub fn parse_minterms(input: &str) -> Result<Vec<u64>, MintermParseError> {
+    input
+        .split(',')
+        .map(str::trim)
+        .enumerate()
+        .map(|(index, token)| {
+            parse_minterm(token).map_err(|message| MintermParseError {
+                index,
+                token: token.to_string(),
+                message,
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prelude_reexports_the_module_paths() {
+        // The module paths keep working and name the same items as the prelude
+        fn same<T>(_: T, _: T) {}
+        same(
+            prelude::QMSolver::<prelude::Enc16>::new(1),
+            qm::qm_solver::QMSolver::<qm::encoding::Enc16>::new(1),
+        );
+        same(prelude::BranchSet::new(), simplify::types::BranchSet::new());
+        same(
+            prelude::OptimizedFor::AutoDetect,
+            cnf_dnf::optimized_for::OptimizedFor::AutoDetect,
+        );
+        let _: fn(&[u64], Option<&[u64]>, usize) -> QMResult = prelude::minimize_function;
+    }
+
     #[test]
     fn test_minimize_simple() {
         let result = minimize_function(&[1, 3], None, 2);
         assert!(!result.minimized_expression.is_empty());
     }
 
+    #[test]
+    fn test_minimize_zero_and_one_variables() {
+        assert_eq!(minimize_function(&[], None, 0).minimized_expression, "0");
+        assert_eq!(minimize_function(&[0], None, 0).minimized_expression, "1");
+        assert_eq!(minimize_function(&[1], None, 1).minimized_expression, "A");
+        assert_eq!(minimize_function(&[0], Some(&[1]), 1).minimized_expression, "1");
+        assert!(generate_variable_names(0).is_empty());
+    }
+
+    #[test]
+    fn test_duplicates_and_unsorted_input() {
+        let clean = minimize_function(&[1, 3, 7], Some(&[5]), 3);
+        let messy = minimize_function(&[3, 1, 3, 7, 1], Some(&[5, 5]), 3);
+        assert_eq!(messy.minterms, vec![1, 3, 7]);
+        assert_eq!(messy.cost_original, 9);
+        assert_eq!(messy.duplicates_removed, 3);
+        assert_eq!(clean.duplicates_removed, 0);
+        assert_eq!(
+            QMResult {
+                duplicates_removed: 0,
+                ..messy
+            },
+            clean
+        );
+    }
+
+    #[test]
+    fn test_used_dont_cares() {
+        // Σ(1,3) + d(5,6,7): the cover A absorbs 5 and 7, but not 6
+        let result = minimize_function(&[1, 3], Some(&[5, 6, 7]), 3);
+        assert_eq!(result.minimized_expression, "A");
+        assert_eq!(result.used_dont_cares, vec![5, 7]);
+        assert_eq!(result.unused_dont_cares, vec![6]);
+
+        let result = minimize_function(&[1, 3], None, 3);
+        assert!(result.used_dont_cares.is_empty());
+        assert!(result.unused_dont_cares.is_empty());
+    }
+
+    #[test]
+    fn test_completed_on_set() {
+        let result = minimize_function(&[1, 3], Some(&[5, 6, 7]), 3);
+        assert_eq!(result.completed_on_set(), vec![1, 3, 5, 7]);
+        let mut lazy: Vec<u64> = result.completed_on_set_iter().collect();
+        lazy.sort_unstable();
+        assert_eq!(lazy, result.completed_on_set());
+    }
+
+    #[test]
+    fn test_completed_on_set_minimizes_no_worse() {
+        let cases: [(&[u64], &[u64], usize); 4] = [
+            (&[1, 3], &[5, 6, 7], 3),
+            (&[0, 2, 5, 7], &[1, 8, 10, 13], 4),
+            (&[4, 8, 10, 11, 12, 15], &[9, 14], 4),
+            (&[0, 1, 2, 5, 6, 7], &[3], 3),
+        ];
+        for (minterms, dont_cares, variables) in cases {
+            let result = minimize_function(minterms, Some(dont_cares), variables);
+            let completed = result.completed_on_set();
+            assert!(minterms.iter().all(|m| completed.contains(m)));
+
+            let again = minimize_function(&completed, None, variables);
+            assert!(again.cost_minimized <= result.cost_minimized);
+        }
+    }
+
+    #[test]
+    fn test_test_vectors_lie_in_on_set() {
+        // Σ(0,2,5,7) + d(1,8,10,13) over 4 variables
+        let result = minimize_function(&[0, 2, 5, 7], Some(&[1, 8, 10, 13]), 4);
+        let on_set = result.completed_on_set();
+        let vectors = result.test_vectors(4, 42);
+
+        for index in 0..result.cover.len() {
+            assert!(vectors.iter().any(|tv| tv.implicant == index));
+        }
+        for tv in &vectors {
+            let (values, dc_mask) = result.cover[tv.implicant];
+            assert_eq!(tv.vector & !dc_mask, values, "outside implicant {}", tv.implicant);
+            assert!(on_set.contains(&tv.vector), "{} is in the off-set", tv.vector);
+            assert!(tv.expected);
+        }
+        assert_eq!(vectors, result.test_vectors(4, 42));
+    }
+
     #[test]
     fn test_generate_variable_names() {
         let names = generate_variable_names(4);
@@ -107,6 +277,36 @@ mod tests {
         assert_eq!(minterms, vec![1, 3, 7, 15]);
     }
 
+    #[test]
+    fn test_parse_minterms_radixes() {
+        let minterms = parse_minterms("45, 0x2D, 0b10_1101, 0X2d, 4_5").unwrap();
+        assert_eq!(minterms, vec![45; 5]);
+
+        // Round trip through every radix
+        let values = [0u64, 1, 6, 255, 0x3F, 0xDEAD_BEEF, u64::MAX];
+        let text = values
+            .iter()
+            .flat_map(|v| [format!("{}", v), format!("{:#x}", v), format!("{:#b}", v)])
+            .collect::<Vec<_>>()
+            .join(",");
+        let parsed = parse_minterms(&text).unwrap();
+        let expected: Vec<u64> = values.iter().flat_map(|&v| [v, v, v]).collect();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_parse_minterms_errors() {
+        let error = parse_minterms("1, 0x1G, 3").unwrap_err();
+        assert_eq!((error.index, error.token.as_str()), (1, "0x1G"));
+        assert!(error.to_string().contains("'0x1G' at index 1"));
+
+        for (input, index) in [("0b102", 0), ("1,0x", 1), ("1,2,_3", 2), ("0b1_", 0), ("1,,2", 1)] {
+            assert_eq!(parse_minterms(input).unwrap_err().index, index, "{}", input);
+        }
+        // One past u64::MAX
+        assert!(parse_minterms("0x1_0000_0000_0000_0000").is_err());
+    }
+
     #[test]
     fn test_minimize_64_variables() {
         // Test with 40 variables (should use Enc64)
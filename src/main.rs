@@ -1,11 +1,22 @@
 use anyhow::{Result, anyhow};
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use qm_agent::agent_api;
+use qm_agent::qm::codegen::RustFnStyle;
+use qm_agent::qm::cubes::{covers_equivalent, cube_from_mask};
+use qm_agent::qm::examples::{
+    COMMAND_EXAMPLES, CommandExample, EXAMPLES, INTERACTIVE_COMMANDS, LIBRARY_EXAMPLES,
+    SIMPLIFY_EXAMPLES, SIMPLIFY_FEATURES,
+};
+use qm_agent::qm::parse::{ParseError, ParsedRequest, parse_output_table, parse_request};
+use qm_agent::qm::{Implicant, MultiOutputTable, Pla, parse_sop, shared_terms};
+use qm_agent::simplify::{DEFAULT_MAX_TRUTH_TABLE_ROWS, TruthTable};
+use qm_agent::{BestForm, Enc32, PetricksMethod, minimize_truth_table_multi};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::collections::HashSet;
 use std::fs;
 use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Quine-McCluskey Boolean minimization agent for Claude
 #[derive(Parser)]
@@ -20,21 +31,13 @@ struct Cli {
 enum Commands {
     /// Minimize a Boolean function
     Minimize {
-        /// Input: JSON file path, inline JSON, or natural language
+        /// Input: JSON file path, inline JSON, natural language, a Boolean expression, or
+        /// stdin (use "-")
         #[arg(short, long)]
         input: String,
 
-        /// Output format
-        #[arg(short, long, default_value = "human")]
-        format: OutputFormat,
-
-        /// Show step-by-step solution
-        #[arg(long)]
-        show_steps: bool,
-
-        /// Include Product of Sums form
-        #[arg(long)]
-        include_pos: bool,
+        #[command(flatten)]
+        options: MinimizeOptions,
     },
     /// Simplify if-then-else conditions (for Claude integration)
     Simplify {
@@ -46,6 +49,43 @@ enum Commands {
         #[arg(long)]
         pretty: bool,
     },
+    /// Print the truth table of a function without minimizing it
+    TruthTable {
+        /// Input: any format accepted by `minimize`, or an expression such as "A'B + AC"
+        #[arg(short, long)]
+        input: String,
+
+        /// Table format
+        #[arg(short, long, default_value = "text")]
+        format: TableFormat,
+
+        #[command(flatten)]
+        table_limit: TableLimit,
+    },
+    /// Check whether two minimized expressions are equivalent
+    Equivalent {
+        /// First expression, e.g. "A'B + AB"
+        #[arg(short)]
+        a: String,
+
+        /// Second expression, e.g. "B"
+        #[arg(short)]
+        b: String,
+
+        /// Variable order (comma separated); defaults to order of appearance
+        #[arg(long, value_delimiter = ',')]
+        variables: Vec<String>,
+    },
+    /// Compare the covers of two minimize results (JSON)
+    Diff {
+        /// First result, as written by `minimize -f json`
+        #[arg(short)]
+        a: PathBuf,
+
+        /// Second result
+        #[arg(short)]
+        b: PathBuf,
+    },
     /// Interactive mode for complex queries
     Interactive,
     /// Show usage examples
@@ -58,41 +98,145 @@ enum OutputFormat {
     Human,
     Table,
     Steps,
+    RustFn,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct QMRequest {
-    minterms: Vec<u32>,
-    dont_cares: Option<Vec<u32>>,
-    variables: usize,
-    variable_names: Option<Vec<String>>, // A, B, C, etc.
-    format: Option<String>,
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum TableFormat {
+    Text,
+    Csv,
+    Markdown,
+}
+
+/// Options of `minimize`
+#[derive(Args)]
+struct MinimizeOptions {
+    /// Output format
+    #[arg(short, long, default_value = "human")]
+    format: OutputFormat,
+
+    /// Write the formatted result to this file (human-readable report goes to stderr)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Show step-by-step solution
+    #[arg(long)]
+    show_steps: bool,
+
+    /// Also minimize the product of sums and report the form with fewer literals
+    #[arg(long, alias = "include-pos")]
+    best_form: bool,
+
+    /// Print reduction statistics (combining iterations) to stderr
+    #[arg(long)]
+    stats: bool,
+
+    /// Verify the result is equivalent to the input cover (PLA input only)
+    #[arg(long)]
+    check: bool,
+
+    /// Write the prime implicant covering problem to this file as DIMACS CNF, for an
+    /// external SAT solver (variable i selects the prime implicant of comment line i)
+    #[arg(long)]
+    export_cover_dimacs: Option<PathBuf>,
+
+    /// Outputs of a PLA to minimize: `all` (the default for several outputs) or
+    /// comma-separated names from `.ob`
+    #[arg(long, value_delimiter = ',')]
+    outputs: Option<Vec<String>>,
+
+    #[command(flatten)]
+    table_limit: TableLimit,
+}
+
+/// How many rows of a truth table to print
+#[derive(Args, Copy, Clone)]
+struct TableLimit {
+    /// Print at most this many truth table rows (`minimize` leaves a larger table out)
+    #[arg(long, default_value_t = DEFAULT_MAX_TRUTH_TABLE_ROWS)]
+    max_rows: u64,
+
+    /// Print every row of the truth table, however large
+    #[arg(long)]
+    full_table: bool,
 }
 
+impl Default for TableLimit {
+    fn default() -> Self {
+        Self {
+            max_rows: DEFAULT_MAX_TRUTH_TABLE_ROWS,
+            full_table: false,
+        }
+    }
+}
+
+impl TableLimit {
+    fn max_rows(self) -> u64 {
+        if self.full_table {
+            u64::MAX
+        } else {
+            self.max_rows
+        }
+    }
+}
+
+/// `minimize -f json` output: the library's [`QMResult`](qm_agent::QMResult) fields plus a
+/// few CLI-only ones (see `qm_agent::qm::schema`)
 #[derive(Debug, Serialize)]
 struct QMResponse {
-    original_minterms: Vec<u32>,
+    #[serde(flatten)]
+    minimization: qm_agent::QMResult,
     dont_cares: Vec<u32>,
-    minimized_sop: String,
     minimized_pos: Option<String>,
-    prime_implicants: Vec<String>,
-    essential_prime_implicants: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_form: Option<BestFormSummary>,
     cost_reduction: Option<f64>,
     truth_table: Option<String>,
-    steps: Option<Vec<String>>, // For educational purposes
+}
+
+/// `minimize -f json` output for several outputs
+#[derive(Debug, Serialize)]
+struct MultiOutputResponse {
+    outputs: Vec<OutputResponse>,
+    /// Terms in the covers of more than one output
+    shared_terms: Vec<SharedTermSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputResponse {
+    name: String,
+    #[serde(flatten)]
+    minimization: qm_agent::QMResult,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedTermSummary {
+    term: String,
+    outputs: Vec<String>,
+}
+
+/// `--best-form`: which form has fewer literals, and both literal counts
+#[derive(Debug, Serialize)]
+struct BestFormSummary {
+    /// `sop` or `pos`
+    form: &'static str,
+    sop_literals: usize,
+    pos_literals: usize,
 }
 
 fn main() {
     let cli = Cli::parse();
 
     let result = match cli.command {
-        Commands::Minimize {
+        Commands::Minimize { input, options } => handle_minimize(&input, &options),
+        Commands::Simplify { input, pretty } => handle_simplify(input.as_deref(), pretty),
+        Commands::TruthTable {
             input,
             format,
-            show_steps,
-            include_pos,
-        } => handle_minimize(&input, format, show_steps, include_pos),
-        Commands::Simplify { input, pretty } => handle_simplify(input.as_deref(), pretty),
+            table_limit,
+        } => handle_truth_table(&input, format, table_limit),
+        Commands::Equivalent { a, b, variables } => handle_equivalent(&a, &b, &variables),
+        Commands::Diff { a, b } => handle_diff(&a, &b),
         Commands::Interactive => handle_interactive(),
         Commands::Examples => handle_examples(),
     };
@@ -103,189 +247,294 @@ fn main() {
     }
 }
 
-fn handle_minimize(
-    input: &str,
-    format: OutputFormat,
-    show_steps: bool,
-    include_pos: bool,
-) -> Result<()> {
+fn handle_minimize(input: &str, options: &MinimizeOptions) -> Result<()> {
+    let MinimizeOptions {
+        format,
+        show_steps,
+        best_form,
+        stats,
+        check,
+        table_limit,
+        ..
+    } = *options;
+    let output = options.output.as_deref();
+    let export_cover_dimacs = options.export_cover_dimacs.as_deref();
+    let outputs = options.outputs.as_deref();
+
     // Parse input in various formats
-    let request = parse_input(input)?;
+    let input = read_input(input, &mut io::stdin())?;
+    match parse_output_table(&input)? {
+        Some(table) if outputs.is_some() || table.outputs.len() > 1 => {
+            if check || best_form || stats || export_cover_dimacs.is_some() {
+                return Err(anyhow!(
+                    "--check, --best-form, --stats and --export-cover-dimacs need a single-output function"
+                ));
+            }
+            return minimize_outputs(&table, outputs, format, output, show_steps);
+        }
+        None if outputs.is_some() => {
+            return Err(anyhow!("--outputs needs PLA input"));
+        }
+        _ => {}
+    }
+    let request = parse_request(&input)?;
+    print_notes(&request);
+    if check && request.pla.is_none() {
+        return Err(anyhow!("--check needs PLA input with a cover"));
+    }
 
     // Run Quine-McCluskey algorithm
-    let result = run_quine_mccluskey(&request, show_steps, include_pos)?;
+    let (result, cover_problem) =
+        run_quine_mccluskey(&request, show_steps, best_form, table_limit)?;
 
-    // Output in requested format
+    // Output in requested format; with an output file only the result goes to the file
+    let mut out = create_output(output)?;
     match format {
-        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
-        OutputFormat::Human => print_human_readable(&result),
-        OutputFormat::Table => print_table_format(&result),
-        OutputFormat::Steps => print_steps(&result),
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&result)?)?,
+        OutputFormat::Human if output.is_some() => {
+            write_human_readable(&mut io::stderr(), &result)?;
+            writeln!(out, "{}", result.minimization.minimized_expression)?;
+        }
+        OutputFormat::Human => write_human_readable(&mut out, &result)?,
+        OutputFormat::Table => write_table_format(&mut out, &result)?,
+        OutputFormat::Steps => write_steps(&mut out, &request, &result)?,
+        OutputFormat::RustFn => {
+            let style = RustFnStyle {
+                parameter_names: request
+                    .variable_names
+                    .iter()
+                    .flatten()
+                    .map(|name| name.trim().to_string())
+                    .collect(),
+                ..RustFnStyle::default()
+            };
+            write!(out, "{}", result.minimization.to_rust_fn("f", &style)?)?
+        }
+    }
+    out.flush()?;
+
+    if stats {
+        print_stats(&request);
+    }
+
+    if let Some(path) = export_cover_dimacs {
+        fs::write(path, cover_problem.to_dimacs())
+            .map_err(|e| anyhow!("Cannot write '{}': {}", path.display(), e))?;
+    }
+
+    if let Some(ref pla) = request.pla {
+        print_pla_stats(pla, &result);
+        if check {
+            check_pla_cover(pla, &result)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// `output`, or stdout without one
+fn create_output(output: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(path) => Box::new(
+            fs::File::create(path)
+                .map_err(|e| anyhow!("Cannot create '{}': {}", path.display(), e))?,
+        ),
+        None => Box::new(io::stdout()),
+    })
+}
+
+/// Minimize the `selection` of the outputs of `table` (all of them for `None` or `all`)
+/// and report the terms their covers share
+fn minimize_outputs(
+    table: &MultiOutputTable,
+    selection: Option<&[String]>,
+    format: OutputFormat,
+    output: Option<&Path>,
+    show_steps: bool,
+) -> Result<()> {
+    let selected;
+    let table = match selection {
+        Some(names) if names != ["all"] => {
+            selected = table.select(names).map_err(|e| anyhow!(e))?;
+            &selected
+        }
+        _ => table,
+    };
+    let mut results = minimize_truth_table_multi(table);
+    if !show_steps {
+        results
+            .iter_mut()
+            .for_each(|result| result.solution_steps.clear());
     }
+    let shared = shared_terms(table, &results);
 
+    let mut out = create_output(output)?;
+    match format {
+        OutputFormat::Json => {
+            let response = MultiOutputResponse {
+                outputs: table
+                    .outputs
+                    .iter()
+                    .zip(results)
+                    .map(|(column, minimization)| OutputResponse {
+                        name: column.name.clone(),
+                        minimization,
+                    })
+                    .collect(),
+                shared_terms: shared
+                    .into_iter()
+                    .map(|term| SharedTermSummary {
+                        term: term.expression,
+                        outputs: term.outputs,
+                    })
+                    .collect(),
+            };
+            writeln!(out, "{}", serde_json::to_string_pretty(&response)?)?
+        }
+        OutputFormat::Human => {
+            writeln!(out, "🔍 Quine-McCluskey Boolean Minimization Result")?;
+            writeln!(out, "════════════════════════════════════════════")?;
+            writeln!(out, "\n📊 Inputs: {}", table.variables.join(", "))?;
+            writeln!(out, "\n✨ Minimized Outputs (SOP):")?;
+            for (column, result) in table.outputs.iter().zip(&results) {
+                writeln!(out, "   {} = {}", column.name, result.minimized_expression)?;
+            }
+            writeln!(out, "\n🔗 Shared Terms:")?;
+            if shared.is_empty() {
+                writeln!(out, "   • None")?;
+            }
+            for term in &shared {
+                writeln!(out, "   • {}: {}", term.expression, term.outputs.join(", "))?;
+            }
+            for (column, result) in table.outputs.iter().zip(&results) {
+                if !result.solution_steps.is_empty() {
+                    writeln!(out, "\n📝 Solution Steps ({}):", column.name)?;
+                    for (i, step) in result.solution_steps.iter().enumerate() {
+                        writeln!(out, "   {}. {}", i + 1, step)?;
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "The outputs of a PLA can only be written as human or json"
+            ));
+        }
+    }
+    out.flush()?;
     Ok(())
 }
 
-fn parse_input(input: &str) -> Result<QMRequest> {
-    // Try parsing as file path first
-    if let Ok(file_content) = fs::read_to_string(input)
-        && let Ok(request) = serde_json::from_str::<QMRequest>(&file_content)
-    {
-        return Ok(request);
-    }
-
-    // Try parsing as inline JSON
-    if let Ok(request) = serde_json::from_str::<QMRequest>(input) {
-        return Ok(request);
-    }
-
-    // Parse natural language formats
-    parse_natural_input(input)
-}
-
-fn parse_natural_input(input: &str) -> Result<QMRequest> {
-    let input = input.trim();
-
-    // Pattern 1: f(A,B,C) = Σ(1,3,7) + d(2,4)
-    let sigma_pattern =
-        Regex::new(r"f\(([A-Z,\s]+)\)\s*=\s*Σ\(([0-9,\s]+)\)(?:\s*\+\s*d\(([0-9,\s]*)\))?")?;
-    if let Some(caps) = sigma_pattern.captures(input) {
-        let variables: Vec<String> = caps[1].split(',').map(|s| s.trim().to_string()).collect();
-        let minterms: Vec<u32> = caps[2]
-            .split(',')
-            .map(|s| s.trim().parse())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| anyhow!("Failed to parse minterm: {}", e))?;
-        let dont_cares: Option<Vec<u32>> = caps
-            .get(3)
-            .map(|m| -> Result<Vec<u32>> {
-                m.as_str()
-                    .split(',')
-                    .filter(|s| !s.trim().is_empty())
-                    .map(|s| s.trim().parse())
-                    .collect::<Result<Vec<_>, _>>()
-                    .map_err(|e| anyhow!("Failed to parse don't care term: {}", e))
-            })
-            .transpose()?;
-
-        return Ok(QMRequest {
-            minterms,
-            dont_cares,
-            variables: variables.len(),
-            variable_names: Some(variables),
-            format: None,
-        });
-    }
-
-    // Pattern 2: "minimize minterms 1,3,7 with 3 variables"
-    let simple_pattern =
-        Regex::new(r"minimize\s+minterms?\s+([0-9,\s]+)\s+with\s+(\d+)\s+variables?")?;
-    if let Some(caps) = simple_pattern.captures(input) {
-        let minterms: Vec<u32> = caps[1]
-            .split(',')
-            .map(|s| s.trim().parse())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| anyhow!("Failed to parse minterm: {}", e))?;
-        let variables: usize = caps[2].parse()?;
-
-        return Ok(QMRequest {
-            minterms,
-            dont_cares: None,
-            variables,
-            variable_names: None,
-            format: None,
-        });
-    }
-
-    // Pattern 3: Truth table format "truth table: 00110110"
-    let tt_pattern = Regex::new(r"truth\s+table:\s*([01]+)")?;
-    if let Some(caps) = tt_pattern.captures(input) {
-        let truth_table = &caps[1];
-        let variables = (truth_table.len() as f64).log2() as usize;
-        let minterms: Vec<u32> = truth_table
-            .chars()
-            .enumerate()
-            .filter_map(|(i, c)| if c == '1' { Some(i as u32) } else { None })
-            .collect();
-
-        return Ok(QMRequest {
-            minterms,
-            dont_cares: None,
-            variables,
-            variable_names: None,
-            format: None,
-        });
-    }
-
-    Err(anyhow!(
-        "Could not parse input format. Supported formats:\n\
-        - JSON: {{\"minterms\": [1,3,7], \"variables\": 3}}\n\
-        - Function notation: f(A,B,C) = Σ(1,3,7)\n\
-        - With don't cares: f(A,B,C) = Σ(1,3,7) + d(2,4)\n\
-        - Simple: minimize minterms 1,3,7 with 3 variables\n\
-        - Truth table: truth table: 00110110"
-    ))
+/// How the input was read where it was not explicit; on stderr, so JSON output stays clean
+fn print_notes(request: &ParsedRequest) {
+    for note in &request.notes {
+        eprintln!("Note: {}", note);
+    }
+}
+
+/// Resolve the `-i` argument: `-` reads the whole request from `stdin`, anything else is
+/// passed on to [`parse_input`] unchanged
+fn read_input(input: &str, stdin: &mut dyn Read) -> Result<String> {
+    if input != "-" {
+        return Ok(input.to_string());
+    }
+    let mut buffer = String::new();
+    stdin.read_to_string(&mut buffer)?;
+    Ok(buffer)
 }
 
+/// Minimize the function of `request`; also gives the covering problem the cover was chosen
+/// from, for `--export-cover-dimacs`
 fn run_quine_mccluskey(
-    request: &QMRequest,
+    request: &ParsedRequest,
     show_steps: bool,
-    include_pos: bool,
-) -> Result<QMResponse> {
+    best_form: bool,
+    table_limit: TableLimit,
+) -> Result<(QMResponse, PetricksMethod<Enc32>)> {
     let empty_dont_cares = vec![];
     let dont_cares = request.dont_cares.as_ref().unwrap_or(&empty_dont_cares);
-    let variable_names = request.variable_names.as_ref().cloned().unwrap_or_else(|| {
-        (0..request.variables)
-            .map(|i| ((b'A' + i as u8) as char).to_string())
-            .collect()
-    });
+    let variable_names = variable_names(request);
 
     // Use the actual QM implementation
-    let (minimized_sop, prime_implicants_formatted, essential_pis_formatted, steps) =
-        integrate_your_qm_solver(
-            &request.minterms,
-            dont_cares,
-            request.variables,
-            &variable_names,
-            show_steps,
-        );
-
-    let minimized_pos = if include_pos {
-        Some(convert_to_pos(&minimized_sop))
+    let solver = integrate_your_qm_solver(
+        &request.minterms,
+        dont_cares,
+        request.variables,
+        &variable_names,
+    )?;
+    let (sop, cover_problem) = solver.solve_with_cover_problem();
+    let (mut minimization, minimized_pos, best_form) = if best_form {
+        let best = solver.try_best_form_of(sop)?;
+        let summary = BestFormSummary {
+            form: match best.best {
+                BestForm::Sop => "sop",
+                BestForm::Pos => "pos",
+            },
+            sop_literals: best.sop_cost,
+            pos_literals: best.pos_cost,
+        };
+        (best.sop, Some(best.pos_expression), Some(summary))
+    } else {
+        (sop, None, None)
+    };
+    if !show_steps {
+        minimization.solution_steps.clear();
+    }
+    // A table over the row limit is left out rather than cut off
+    let rows = TruthTable::row_count(request.variables).map_err(|e| anyhow!(e))?;
+    let truth_table = if rows <= table_limit.max_rows() {
+        Some(function_truth_table(request, u64::MAX)?.to_text())
     } else {
         None
     };
 
-    Ok(QMResponse {
-        original_minterms: request.minterms.clone(),
-        dont_cares: dont_cares.clone(),
-        minimized_sop,
+    let mut dont_cares = dont_cares.clone();
+    dont_cares.sort_unstable();
+    dont_cares.dedup();
+    let cost_reduction = calculate_cost_reduction(minimization.minterms.len(), request.variables);
+
+    let response = QMResponse {
+        minimization,
+        dont_cares,
         minimized_pos,
-        prime_implicants: prime_implicants_formatted,
-        essential_prime_implicants: essential_pis_formatted,
-        cost_reduction: Some(calculate_cost_reduction(
-            &request.minterms,
-            request.variables,
-        )),
-        truth_table: Some(generate_truth_table(
-            &request.minterms,
-            dont_cares,
-            request.variables,
-        )),
-        steps,
-    })
+        best_form,
+        cost_reduction: Some(cost_reduction),
+        truth_table,
+    };
+    Ok((response, cover_problem))
+}
+
+/// Truth table of the function described by `request`, the first `max_rows` rows
+fn function_truth_table(request: &ParsedRequest, max_rows: u64) -> Result<TruthTable> {
+    let to_u64 = |minterms: &[u32]| -> Vec<u64> { minterms.iter().map(|&m| m as u64).collect() };
+    TruthTable::from_function_truncated(
+        variable_names(request),
+        &to_u64(&request.minterms),
+        &to_u64(request.dont_cares.as_deref().unwrap_or(&[])),
+        max_rows,
+    )
+    .map_err(|e| anyhow!(e))
+}
+
+/// Variable names from the request, trimmed, or A, B, C, ... (A is bit 0)
+fn variable_names(request: &ParsedRequest) -> Vec<String> {
+    match &request.variable_names {
+        Some(names) => names.iter().map(|name| name.trim().to_string()).collect(),
+        None => (0..request.variables)
+            .map(|i| ((b'A' + i as u8) as char).to_string())
+            .collect(),
+    }
 }
 
 fn integrate_your_qm_solver(
     minterms: &[u32],
     dont_cares: &[u32],
     variables: usize,
-    _variable_names: &[String],
-    show_steps: bool,
-) -> (String, Vec<String>, Vec<String>, Option<Vec<String>>) {
-    use qm_agent::{Enc32, QMSolver};
+    variable_names: &[String],
+) -> Result<qm_agent::QMSolver<Enc32>> {
+    use qm_agent::QMSolver;
 
-    let mut solver = QMSolver::<Enc32>::new(variables);
+    let mut solver = QMSolver::<Enc32>::with_variable_names(variables, variable_names.to_vec())?;
 
     // Convert u32 to u64 for Enc32
     let minterms_u64: Vec<u64> = minterms.iter().map(|&x| x as u64).collect();
@@ -293,32 +542,13 @@ fn integrate_your_qm_solver(
 
     solver.set_minterms(minterms_u64);
     solver.set_dont_cares(dont_cares_u64);
-
-    let result = solver.solve();
-
-    let steps = if show_steps {
-        Some(result.solution_steps)
-    } else {
-        None
-    };
-
-    (
-        result.minimized_expression,
-        result.prime_implicants,
-        result.essential_prime_implicants,
-        steps,
-    )
-}
-
-fn convert_to_pos(sop_expression: &str) -> String {
-    // Placeholder - implement De Morgan's laws conversion if needed
-    format!("({})", sop_expression.replace(" + ", ")("))
+    Ok(solver)
 }
 
-fn calculate_cost_reduction(minterms: &[u32], variables: usize) -> f64 {
+fn calculate_cost_reduction(n_minterms: usize, variables: usize) -> f64 {
     // Simple cost calculation - replace with your actual cost analysis
-    let original_cost = minterms.len() * variables;
-    let minimized_cost = (minterms.len() as f64 * 0.6) as usize; // Placeholder
+    let original_cost = n_minterms * variables;
+    let minimized_cost = (n_minterms as f64 * 0.6) as usize; // Placeholder
 
     if original_cost > 0 {
         ((original_cost - minimized_cost) as f64 / original_cost as f64) * 100.0
@@ -327,103 +557,237 @@ fn calculate_cost_reduction(minterms: &[u32], variables: usize) -> f64 {
     }
 }
 
-fn generate_truth_table(minterms: &[u32], dont_cares: &[u32], variables: usize) -> String {
-    let total_rows = 1 << variables;
-    let minterm_set: HashSet<u32> = minterms.iter().copied().collect();
-    let dont_care_set: HashSet<u32> = dont_cares.iter().copied().collect();
-
-    let mut table = String::new();
-
-    // Header
-    for i in 0..variables {
-        table.push_str(&format!("{} ", ((b'A' + i as u8) as char)));
+fn write_human_readable(out: &mut dyn Write, result: &QMResponse) -> io::Result<()> {
+    writeln!(out, "🔍 Quine-McCluskey Boolean Minimization Result")?;
+    writeln!(out, "════════════════════════════════════════════")?;
+
+    writeln!(out, "\n📊 Input:")?;
+    writeln!(out, "   Minterms: {:?}", result.minimization.minterms)?;
+    if result.minimization.duplicates_removed > 0 {
+        writeln!(
+            out,
+            "   Duplicates removed: {}",
+            result.minimization.duplicates_removed
+        )?;
     }
-    table.push_str("| F\n");
-    table.push_str(&"-".repeat(variables * 2 + 4));
-    table.push('\n');
-
-    // Rows
-    for i in 0..total_rows {
-        for j in (0..variables).rev() {
-            table.push_str(&format!("{} ", (i >> j) & 1));
-        }
-        table.push_str("| ");
-
-        if minterm_set.contains(&i) {
-            table.push('1');
-        } else if dont_care_set.contains(&i) {
-            table.push('X');
-        } else {
-            table.push('0');
-        }
-        table.push('\n');
-    }
-
-    table
-}
-
-fn print_human_readable(result: &QMResponse) {
-    println!("🔍 Quine-McCluskey Boolean Minimization Result");
-    println!("════════════════════════════════════════════");
-
-    println!("\n📊 Input:");
-    println!("   Minterms: {:?}", result.original_minterms);
     if !result.dont_cares.is_empty() {
-        println!("   Don't cares: {:?}", result.dont_cares);
+        writeln!(out, "   Don't cares: {:?}", result.dont_cares)?;
+        writeln!(
+            out,
+            "   Don't-cares assigned to 1: {} of {}",
+            result.minimization.used_dont_cares.len(),
+            result.minimization.used_dont_cares.len() + result.minimization.unused_dont_cares.len()
+        )?;
     }
 
-    println!("\n✨ Minimized Expression (SOP):");
-    println!("   F = {}", result.minimized_sop);
+    writeln!(out, "\n✨ Minimized Expression (SOP):")?;
+    writeln!(out, "   F = {}", result.minimization.minimized_expression)?;
 
     if let Some(ref pos) = result.minimized_pos {
-        println!("\n✨ Minimized Expression (POS):");
-        println!("   F = {}", pos);
+        writeln!(out, "\n✨ Minimized Expression (POS):")?;
+        writeln!(out, "   F = {}", pos)?;
+    }
+    if let Some(ref best) = result.best_form {
+        writeln!(
+            out,
+            "   Best form: {} ({} literals in SOP, {} in POS)",
+            best.form.to_uppercase(),
+            best.sop_literals,
+            best.pos_literals
+        )?;
     }
 
-    println!("\n🎯 Prime Implicants:");
-    for pi in &result.prime_implicants {
-        println!("   • {}", pi);
+    writeln!(out, "\n🎯 Prime Implicants:")?;
+    for pi in &result.minimization.prime_implicants {
+        writeln!(out, "   • {}", pi)?;
     }
 
-    println!("\n⭐ Essential Prime Implicants:");
-    if result.essential_prime_implicants.is_empty() {
-        println!("   • None (no prime implicant uniquely covers any minterm)");
+    writeln!(out, "\n⭐ Essential Prime Implicants:")?;
+    if result.minimization.essential_prime_implicants.is_empty() {
+        writeln!(
+            out,
+            "   • None (no prime implicant uniquely covers any minterm)"
+        )?;
     } else {
-        for epi in &result.essential_prime_implicants {
-            println!("   • {}", epi);
+        for epi in &result.minimization.essential_prime_implicants {
+            writeln!(out, "   • {}", epi)?;
+        }
+        if result.minimization.essential_only_cover {
+            writeln!(out, "   (cover completed by essential implicants alone)")?;
         }
     }
 
     if let Some(cost) = result.cost_reduction {
-        println!("\n💰 Cost Reduction: {:.1}%", cost);
+        writeln!(out, "\n💰 Cost Reduction: {:.1}%", cost)?;
     }
 
-    if let Some(ref steps) = result.steps {
-        println!("\n📝 Solution Steps:");
+    let steps = &result.minimization.solution_steps;
+    if !steps.is_empty() {
+        writeln!(out, "\n📝 Solution Steps:")?;
         for (i, step) in steps.iter().enumerate() {
-            println!("   {}. {}", i + 1, step);
+            writeln!(out, "   {}. {}", i + 1, step)?;
         }
     }
+    Ok(())
 }
 
-fn print_table_format(result: &QMResponse) {
+fn write_table_format(out: &mut dyn Write, result: &QMResponse) -> io::Result<()> {
     if let Some(ref truth_table) = result.truth_table {
-        println!("Truth Table:");
-        println!("{}", truth_table);
+        writeln!(out, "Truth Table:")?;
+        writeln!(out, "{}", truth_table)?;
     }
 
-    println!("\nMinimized Expression: {}", result.minimized_sop);
+    writeln!(
+        out,
+        "\nMinimized Expression: {}",
+        result.minimization.minimized_expression
+    )?;
+    Ok(())
 }
 
-fn print_steps(result: &QMResponse) {
-    if let Some(ref steps) = result.steps {
-        println!("Quine-McCluskey Solution Steps:");
-        println!("===============================");
+fn write_steps(
+    out: &mut dyn Write,
+    request: &ParsedRequest,
+    result: &QMResponse,
+) -> io::Result<()> {
+    use qm_agent::qm::render::render_steps;
+    use qm_agent::{Enc32, MintermEncoding};
+
+    writeln!(out, "Quine-McCluskey Solution Steps:")?;
+    writeln!(out, "===============================")?;
+
+    if request.variables <= Enc32::MAX_VARS {
+        let minterms = &result.minimization.minterms;
+        let dont_cares: Vec<u64> = result.dont_cares.iter().map(|&m| m as u64).collect();
+        write!(
+            out,
+            "{}",
+            render_steps::<Enc32>(
+                minterms,
+                &dont_cares,
+                request.variables,
+                &variable_names(request)
+            )
+        )?;
+    }
+
+    let steps = &result.minimization.solution_steps;
+    if !steps.is_empty() {
+        writeln!(out, "\nSolver log:")?;
         for (i, step) in steps.iter().enumerate() {
-            println!("{}. {}", i + 1, step);
+            writeln!(out, "{}. {}", i + 1, step)?;
         }
+    }
+
+    writeln!(
+        out,
+        "\nMinimized Expression: {}",
+        result.minimization.minimized_expression
+    )?;
+    Ok(())
+}
+
+fn print_stats(request: &ParsedRequest) {
+    use qm_agent::qm::classic::{ReduceOptions, reduce_qm};
+    use qm_agent::{Enc32, MintermEncoding};
+
+    if request.variables > Enc32::MAX_VARS {
+        eprintln!(
+            "Statistics: not available for more than {} variables",
+            Enc32::MAX_VARS
+        );
+        return;
+    }
+
+    let terms: Vec<u64> = request
+        .minterms
+        .iter()
+        .chain(request.dont_cares.iter().flatten())
+        .map(|&m| m as u64)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut passes = Vec::new();
+    let mut record = |iteration, before, after, estimate, reducer| {
+        passes.push((iteration, before, after, estimate, reducer))
+    };
+    let primes = match reduce_qm::<Enc32>(
+        &terms,
+        request.variables,
+        ReduceOptions {
+            on_iteration: Some(&mut record),
+            ..Default::default()
+        },
+    ) {
+        Ok(primes) => primes,
+        Err(e) => {
+            eprintln!("Statistics: not available: {}", e);
+            return;
+        }
+    };
+
+    eprintln!("Statistics:");
+    eprintln!("  Iterations: {}", passes.len());
+    for (iteration, before, after, estimate, reducer) in &passes {
+        eprintln!(
+            "    pass {}: {} -> {} terms ({} pair comparisons, {})",
+            iteration + 1,
+            before,
+            after,
+            estimate,
+            reducer
+        );
+    }
+    eprintln!("  Prime implicants: {}", primes.len());
+}
+
+/// Espresso-style summary of the input cover against the minimized cover (stderr)
+fn print_pla_stats(pla: &Pla<Enc32>, result: &QMResponse) {
+    let terms_in = pla.on_set.len();
+    let literals_in = pla.literal_count();
+    let terms_out = result.minimization.cover.len();
+    let literals_out: usize = result
+        .minimization
+        .cover
+        .iter()
+        .map(|&(_, dc_mask)| pla.n_inputs - dc_mask.count_ones() as usize)
+        .sum();
+
+    let change = |before: usize, after: usize| {
+        if before == 0 {
+            0.0
+        } else {
+            (after as f64 - before as f64) / before as f64 * 100.0
+        }
+    };
+    eprintln!(
+        "PLA: in {} terms, {} literals -> out {} terms, {} literals ({:+.1}% terms, {:+.1}% literals)",
+        terms_in,
+        literals_in,
+        terms_out,
+        literals_out,
+        change(terms_in, terms_out),
+        change(literals_in, literals_out)
+    );
+}
+
+/// Verify the minimized cover against the input cover, modulo the don't-care set
+fn check_pla_cover(pla: &Pla<Enc32>, result: &QMResponse) -> Result<()> {
+    let on_set: Vec<u64> = pla.on_set.iter().map(Implicant::to_cube).collect();
+    let dc_set: Vec<u64> = pla.dc_set.iter().map(Implicant::to_cube).collect();
+    let output: Vec<u64> = result
+        .minimization
+        .cover
+        .iter()
+        .map(|&(values, dc_mask)| cube_from_mask::<Enc32>(values, dc_mask))
+        .collect();
+
+    if covers_equivalent::<Enc32>(&on_set, &output, &dc_set, pla.n_inputs) {
+        eprintln!("✅ Output cover is equivalent to the input cover");
+        Ok(())
     } else {
-        println!("No step-by-step information available. Use --show-steps flag.");
+        Err(anyhow!("Output cover is not equivalent to the input cover"))
     }
 }
 
@@ -431,11 +795,9 @@ fn handle_interactive() -> Result<()> {
     println!("🚀 QM Agent Interactive Mode");
     println!("============================");
     println!("Enter Boolean functions in various formats:");
-    println!("• JSON: {{\"minterms\": [1,3,7], \"variables\": 3}}");
-    println!("• Function: f(A,B,C) = Σ(1,3,7)");
-    println!("• With don't cares: f(A,B,C) = Σ(1,3,7) + d(2,4)");
-    println!("• Simple: minimize minterms 1,3,7 with 3 variables");
-    println!("• Truth table: truth table: 00110110");
+    for example in EXAMPLES {
+        println!("• {}: {}", example.name, example.input);
+    }
     println!("• Type 'help' for more options, 'quit' to exit\n");
 
     loop {
@@ -451,15 +813,18 @@ fn handle_interactive() -> Result<()> {
             "help" => print_interactive_help(),
             "examples" => print_examples(),
             "" => continue,
-            _ => match parse_input(input) {
-                Ok(request) => match run_quine_mccluskey(&request, false, false) {
-                    Ok(result) => {
-                        println!();
-                        print_human_readable(&result);
-                        println!();
+            _ => match parse_request(input) {
+                Ok(request) => {
+                    match run_quine_mccluskey(&request, false, false, TableLimit::default()) {
+                        Ok((result, _)) => {
+                            println!();
+                            print_notes(&request);
+                            write_human_readable(&mut io::stdout(), &result)?;
+                            println!();
+                        }
+                        Err(e) => eprintln!("❌ Error processing: {}", e),
                     }
-                    Err(e) => eprintln!("❌ Error processing: {}", e),
-                },
+                }
                 Err(e) => eprintln!("❌ Parse error: {}", e),
             },
         }
@@ -471,9 +836,9 @@ fn handle_interactive() -> Result<()> {
 
 fn print_interactive_help() {
     println!("\n📚 Interactive Mode Commands:");
-    println!("• help - Show this help");
-    println!("• examples - Show usage examples");
-    println!("• quit/exit - Exit interactive mode");
+    for (command, description) in INTERACTIVE_COMMANDS {
+        println!("• {} - {}", command, description);
+    }
     println!("• Any valid input format to minimize\n");
 }
 
@@ -489,70 +854,171 @@ fn print_examples() {
     println!("\n🔹 Boolean Minimization (Quine-McCluskey):");
     println!("─────────────────────────────────────────");
 
-    println!("\n1. Function notation:");
-    println!("   qm-agent minimize -i 'f(A,B,C) = Σ(1,3,7)'");
-    println!("   → Minimizes f(A,B,C) with minterms 1, 3, 7");
-
-    println!("\n2. With don't cares:");
-    println!("   qm-agent minimize -i 'f(A,B,C) = Σ(1,3,7) + d(2,4)'");
-    println!("   → Uses don't care terms 2, 4 for better minimization");
-
-    println!("\n3. Simple format:");
-    println!("   qm-agent minimize -i 'minimize minterms 1,3,7 with 3 variables'");
-    println!("   → Plain English input");
-
-    println!("\n4. JSON format:");
-    println!("   qm-agent minimize -i '{{\"minterms\": [1,3,7], \"variables\": 3}}'");
-    println!("   → Structured input for automation");
+    let mut number = 0;
+    for example in EXAMPLES {
+        number += 1;
+        println!("\n{}. {}:", number, example.name);
+        println!("   qm-agent minimize -i '{}'", example.input);
+        println!("   → {}", example.description);
+    }
+    for example in COMMAND_EXAMPLES {
+        number += 1;
+        print_command_example(number, example);
+    }
 
-    println!("\n5. Truth table:");
-    println!("   qm-agent minimize -i 'truth table: 00110110'");
-    println!("   → Direct truth table input (8 bits = 3 variables)");
+    println!("\n🔹 If-Then-Else Simplification (Claude Integration):");
+    println!("──────────────────────────────────────────────────");
+    println!("\nSimplify conditional logic via JSON API:");
+    for feature in SIMPLIFY_FEATURES {
+        println!("• {}", feature);
+    }
+    for example in SIMPLIFY_EXAMPLES {
+        number += 1;
+        print_command_example(number, example);
+    }
 
-    println!("\n6. From file:");
-    println!("   qm-agent minimize -i input.json");
-    println!("   → Read from JSON file");
+    println!("\nRun library examples:");
+    for name in LIBRARY_EXAMPLES {
+        println!("   cargo run --example {}", name);
+    }
 
-    println!("\n7. Show step-by-step solution:");
-    println!("   qm-agent minimize -i 'f(A,B) = Σ(1,3)' --show-steps");
-    println!("   → Educational mode with detailed steps");
+    println!("\nSee examples/agent/ directory for JSON API samples.");
+}
 
-    println!("\n8. Different output formats:");
-    println!("   qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f json");
-    println!("   qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f table");
-    println!("   qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f steps");
-    println!("   → Format: human (default), json, table, steps");
+fn print_command_example(number: usize, example: &CommandExample) {
+    println!("\n{}. {}:", number, example.title);
+    for command in example.commands {
+        println!("   {}", command);
+    }
+    if !example.description.is_empty() {
+        println!("   → {}", example.description);
+    }
+}
 
-    println!("\n9. Interactive mode:");
-    println!("   qm-agent interactive");
-    println!("   → REPL for iterative problem solving");
+fn handle_truth_table(input: &str, format: TableFormat, table_limit: TableLimit) -> Result<()> {
+    let input = read_input(input, &mut io::stdin())?;
+    let table = parse_truth_table_input(&input, table_limit.max_rows())?;
+    let rendered = match format {
+        TableFormat::Text => table.to_text(),
+        TableFormat::Csv => {
+            // Keep the output plain CSV
+            if let Some(notice) = table.truncation_notice() {
+                eprintln!("{}", notice);
+            }
+            table.to_csv()
+        }
+        TableFormat::Markdown => table.to_markdown(),
+    };
+    print!("{}", rendered);
+    Ok(())
+}
 
-    println!("\n🔹 If-Then-Else Simplification (Claude Integration):");
-    println!("──────────────────────────────────────────────────");
-    println!("\nSimplify conditional logic via JSON API:");
-    println!("• Boolean expression minimization");
-    println!("• Dead code detection");
-    println!("• Coverage analysis");
-    println!("• Integer comparison operators (==, <, >, <=, >=, !=)");
-    println!("• Multi-language code generation (Go, Rust, C++, Python)");
+/// First `max_rows` rows of the truth table of a `minimize` request, or else of a
+/// sum-of-products expression
+fn parse_truth_table_input(input: &str, max_rows: u64) -> Result<TruthTable> {
+    let request = match parse_request(input) {
+        Ok(request) => request,
+        Err(spec_error) => {
+            return expression_truth_table(input, max_rows).map_err(|_| match spec_error {
+                ParseError::UnknownFormat => anyhow!("{}\n- Expression: A'B + AC", spec_error),
+                other => other.into(),
+            });
+        }
+    };
+    print_notes(&request);
+    function_truth_table(&request, max_rows)
+}
 
-    println!("\n10. From JSON file:");
-    println!("   qm-agent simplify -i examples/agent/simple.json");
+/// Truth table of an expression such as `A'B + AC`, optionally written as `f(A,B,C) = ...`
+/// to fix the variable order; the first `max_rows` rows
+fn expression_truth_table(input: &str, max_rows: u64) -> Result<TruthTable> {
+    let prefix = Regex::new(r"^\s*f\(([^)]*)\)\s*=\s*(.*)$")?;
+    let (names, expression) = match prefix.captures(input) {
+        Some(caps) => (
+            caps[1].split(',').map(|s| s.trim().to_string()).collect(),
+            caps.get(2).map_or("", |m| m.as_str()),
+        ),
+        None => (Vec::new(), input),
+    };
+    let expr = parse_sop(expression, &names).map_err(|e| anyhow!("{}", e))?;
+
+    // Only the rows shown are evaluated
+    let rows = TruthTable::row_count(expr.variables.len()).map_err(|e| anyhow!(e))?;
+    let minterms: Vec<u64> = (0..rows.min(max_rows))
+        .filter(|&m| expr.evaluate(m))
+        .collect();
+    TruthTable::from_function_truncated(expr.variables.clone(), &minterms, &[], max_rows)
+        .map_err(|e| anyhow!(e))
+}
 
-    println!("\n11. From stdin:");
-    println!("   echo '{{...}}' | qm-agent simplify");
+fn handle_equivalent(expr_a: &str, expr_b: &str, variables: &[String]) -> Result<()> {
+    let report = qm_agent::qm::expressions_equivalent(expr_a, expr_b, variables)
+        .map_err(|e| anyhow!("Invalid expression: {}", e))?;
+
+    match report.counterexample {
+        None => {
+            println!("✅ Expressions are equivalent");
+            println!(
+                "   Checked all {} assignments of {}",
+                1u64 << report.variables.len(),
+                report.variables.join(", ")
+            );
+            Ok(())
+        }
+        Some(counterexample) => {
+            let assignment: Vec<String> = counterexample
+                .assignment
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, u8::from(*value)))
+                .collect();
+            println!("❌ Expressions differ");
+            println!("   Counterexample: {}", assignment.join(", "));
+            println!("   a = {}", u8::from(counterexample.value_a));
+            println!("   b = {}", u8::from(counterexample.value_b));
+            Err(anyhow!("Expressions are not equivalent"))
+        }
+    }
+}
 
-    println!("\n12. Inline JSON:");
+fn handle_diff(path_a: &Path, path_b: &Path) -> Result<()> {
+    let read_result = |path: &Path| -> Result<qm_agent::QMResult> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read '{}': {}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow!("'{}' is not a minimize result: {}", path.display(), e))
+    };
+    let result_a = read_result(path_a)?;
+    let result_b = read_result(path_b)?;
+    let diff = qm_agent::qm::diff_covers(&result_a, &result_b)?;
+
+    let print_terms = |label: &str, terms: &[(u64, u64)]| {
+        for &term in terms {
+            println!(
+                "   {} {}",
+                label,
+                qm_agent::qm::diff::term_pattern(term, diff.n_variables)
+            );
+        }
+    };
+    println!("a: {}", result_a.minimized_expression);
+    println!("b: {}", result_b.minimized_expression);
     println!(
-        "   qm-agent simplify -i '{{\"variables\": {{\"a\": \"boolean\"}}, \"branches\": [...]}}'"
+        "Terms: {} unchanged, {} only in a, {} only in b",
+        diff.unchanged.len(),
+        diff.only_in_a.len(),
+        diff.only_in_b.len()
     );
+    print_terms("=", &diff.unchanged);
+    print_terms("-", &diff.only_in_a);
+    print_terms("+", &diff.only_in_b);
 
-    println!("\nRun library examples:");
-    println!("   cargo run --example simplify_if_then_else");
-    println!("   cargo run --example dead_code_detection");
-    println!("   cargo run --example comparison_operators");
-
-    println!("\nSee examples/agent/ directory for JSON API samples.");
+    if diff.equivalent {
+        println!("✅ Covers are equivalent");
+        Ok(())
+    } else {
+        println!("❌ Covers implement different functions");
+        Err(anyhow!("Covers are not equivalent"))
+    }
 }
 
 fn handle_simplify(input: Option<&str>, _pretty: bool) -> Result<()> {
@@ -584,3 +1050,44 @@ fn handle_simplify(input: Option<&str>, _pretty: bool) -> Result<()> {
         Err(e) => Err(anyhow!("Simplification error: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_input_from_stdin() {
+        let mut stdin = Cursor::new("{\"minterms\": [1, 3], \"variables\": 2}\n");
+        let input = read_input("-", &mut stdin).unwrap();
+        let request = parse_request(&input).unwrap();
+        assert_eq!(request.minterms, vec![1, 3]);
+        assert_eq!(request.variables, 2);
+    }
+
+    #[test]
+    fn test_read_input_passes_other_arguments_through() {
+        // The reader is not touched unless the argument is "-"
+        let mut stdin = Cursor::new("ignored");
+        let input = read_input("f(A,B) = Σ(1,3)", &mut stdin).unwrap();
+        assert_eq!(input, "f(A,B) = Σ(1,3)");
+        assert_eq!(stdin.position(), 0);
+    }
+
+    #[test]
+    fn test_best_form_keeps_the_sum_of_products() {
+        // (A + B)(C + D): four literals as a product of sums, eight as a sum of products
+        let request = parse_request("f(A,B,C,D) = Σ(5,6,7,9,10,11,13,14,15)").unwrap();
+        let (response, _) =
+            run_quine_mccluskey(&request, false, true, TableLimit::default()).unwrap();
+        let summary = response.best_form.unwrap();
+        assert_eq!(
+            (summary.form, summary.sop_literals, summary.pos_literals),
+            ("pos", 8, 4)
+        );
+        assert_eq!(response.minimized_pos.as_deref(), Some("(A + B)(C + D)"));
+        // The minimization is still the sum of products, not the complement
+        assert!(!response.minimization.complemented);
+        assert_eq!(response.minimization.literal_cost(), 8);
+    }
+}
@@ -0,0 +1,14 @@
+//! The commonly used types and functions, importable in one line
+//!
+//! ```
+//! use qm_agent::prelude::*;
+//!
+//! let mut solver = QMSolver::<Enc16>::new(2);
+//! solver.set_minterms(vec![1, 3]);
+//! assert_eq!(solver.solve().minimized_expression, "A");
+//! ```
+
+pub use crate::cnf_dnf::{OptimizedFor, cnf_to_dnf};
+pub use crate::minimize_function;
+pub use crate::qm::{Enc16, Enc32, Enc64, MintermEncoding, QMResult, QMSolver};
+pub use crate::simplify::{BoolExpr, BranchSet, simplify_branches};
@@ -0,0 +1,106 @@
+//! Python bindings (feature `python`)
+//!
+//! Build and install the extension module into the active virtualenv with
+//! `maturin develop --release` (see `pyproject.toml`), then `import qm_agent`.
+//! The functions are thin wrappers over [`minimize_function`](crate::minimize_function)
+//! and [`simplify_from_json`](crate::agent_api::simplify_from_json); the solve runs
+//! without holding the GIL.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Most variables [`minimize`] accepts
+const MAX_VARIABLES: usize = 64;
+
+/// Minimize the function given by `minterms` and `dont_cares` over `variables` inputs
+///
+/// Returns the [`QMResult`](crate::QMResult) as a `dict` with the field names of the JSON
+/// output (`minimized_sop`, `prime_implicants`, ...). Raises `ValueError` for more than 64
+/// variables or a minterm that does not fit.
+#[pyfunction]
+#[pyo3(signature = (minterms, dont_cares, variables))]
+pub fn minimize<'py>(
+    py: Python<'py>,
+    minterms: Vec<u64>,
+    dont_cares: Option<Vec<u64>>,
+    variables: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let dont_cares = dont_cares.unwrap_or_default();
+    let json = py
+        .allow_threads(|| minimize_to_json(&minterms, &dont_cares, variables))
+        .map_err(PyValueError::new_err)?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Run [`simplify_from_json`](crate::agent_api::simplify_from_json): the request and the
+/// response are the JSON strings of `qm-agent simplify`. Raises `ValueError` when the
+/// request is rejected.
+#[pyfunction(name = "simplify")]
+pub fn simplify_json(py: Python<'_>, request_json: &str) -> PyResult<String> {
+    py.allow_threads(|| crate::agent_api::simplify_from_json(request_json))
+        .map_err(PyValueError::new_err)
+}
+
+/// The `qm_agent` extension module
+#[pymodule]
+fn qm_agent(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(minimize, m)?)?;
+    m.add_function(wrap_pyfunction!(simplify_json, m)?)?;
+    Ok(())
+}
+
+/// The JSON of the minimized function, or the message of the `ValueError` to raise
+fn minimize_to_json(
+    minterms: &[u64],
+    dont_cares: &[u64],
+    variables: usize,
+) -> Result<String, String> {
+    if variables > MAX_VARIABLES {
+        return Err(format!(
+            "too many variables ({}); maximum supported is {}",
+            variables, MAX_VARIABLES
+        ));
+    }
+    if let Some(&m) = minterms
+        .iter()
+        .chain(dont_cares)
+        .find(|&&m| variables < 64 && m >> variables != 0)
+    {
+        return Err(format!(
+            "minterm {} does not fit in {} variables",
+            m, variables
+        ));
+    }
+    let result = crate::minimize_function(minterms, Some(dont_cares), variables);
+    serde_json::to_string(&result).map_err(|e| format!("JSON serialize error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_to_json() {
+        let json = minimize_to_json(&[1, 3], &[], 2).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["minimized_sop"], "A");
+
+        // Don't-cares take part as in the library
+        let json = minimize_to_json(&[1, 3], &[5, 7], 3).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["minimized_sop"], "A");
+
+        assert_eq!(
+            minimize_to_json(&[4], &[], 2).unwrap_err(),
+            "minterm 4 does not fit in 2 variables"
+        );
+        assert_eq!(
+            minimize_to_json(&[], &[8], 3).unwrap_err(),
+            "minterm 8 does not fit in 3 variables"
+        );
+        assert_eq!(
+            minimize_to_json(&[1], &[], 65).unwrap_err(),
+            "too many variables (65); maximum supported is 64"
+        );
+    }
+}
@@ -7,7 +7,10 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crate::cnf_dnf::{self, OptimizedFor};
 
+use super::simd_coverage::CoverageMatrix;
+
 // Re-export encoding types for backward compatibility
+use super::encoding::EncodingError;
 pub use super::encoding::{BitOps, Enc16, Enc32, Enc64, MintermEncoding};
 pub use super::minterm_set::MintermSet;
 
@@ -67,7 +70,7 @@ pub fn minterms_to_strings<E: MintermEncoding>(
     minterms: &[E::Value],
 ) -> Vec<String> {
     if number_vars > E::MAX_VARS {
-        eprintln!("ERROR: max number of vars is {}", E::MAX_VARS);
+        log::error!("max number of vars is {}", E::MAX_VARS);
         return Vec::new();
     }
 
@@ -98,15 +101,38 @@ pub fn is_gray_code<E: MintermEncoding>(a: E::Value, b: E::Value) -> bool {
 ///   both the value type (u32 or u64) and the don't-care offset
 #[inline]
 pub fn replace_complements<E: MintermEncoding>(a: E::Value, b: E::Value) -> E::Value {
+    // The differing bit must be a data bit, or its don't-care copy lands outside the word
+    debug_assert!(
+        (a ^ b) >> E::DK_OFFSET == E::Value::zero(),
+        "replace_complements: terms differ outside the data part (too many variables?)"
+    );
     a | b | ((a ^ b) << E::DK_OFFSET)
 }
 
+/// Check that no term has a don't-care bit without its data bit
+///
+/// Merging only ever sets a don't-care bit together with its data bit, so such a bit is
+/// data that spilled into the don't-care region: more variables than the encoding holds.
+fn validate_terms<E: MintermEncoding>(terms: &[E::Value]) -> Result<(), EncodingError> {
+    match terms
+        .iter()
+        .position(|&m| (m >> E::DK_OFFSET) & !m != E::Value::zero())
+    {
+        Some(index) => Err(EncodingError::StrayDontCare { index }),
+        None => Ok(()),
+    }
+}
+
 /// Reduce minterms using classic O(n²) algorithm
+///
+/// Fails when `n_variables` exceeds the encoding or a term has bits outside the data part.
 pub fn reduce_minterms_classic<E: MintermEncoding>(
     minterms: &[E::Value],
     n_variables: usize,
     show_info: bool,
-) -> Vec<E::Value> {
+) -> Result<Vec<E::Value>, EncodingError> {
+    E::validate(n_variables)?;
+    validate_terms::<E>(minterms)?;
     let mut total_comparisons = 0u64;
     let max = minterms.len();
     let mut checked = vec![false; max];
@@ -125,20 +151,18 @@ pub fn reduce_minterms_classic<E: MintermEncoding>(
                 checked[j] = true;
                 let new_mt = replace_complements::<E>(term_i, term_j);
 
-                if false {
-                    println!(
-                        "INFO: 09f28d3a: term_i: {}",
-                        minterm_to_string::<E>(n_variables, term_i)
-                    );
-                    println!(
-                        "INFO: 2d17146f: term_j: {}",
-                        minterm_to_string::<E>(n_variables, term_j)
-                    );
-                    println!(
-                        "INFO: 313a49ea: new_mt: {}",
-                        minterm_to_string::<E>(n_variables, new_mt)
-                    );
-                }
+                log::trace!(
+                    "09f28d3a: term_i: {}",
+                    minterm_to_string::<E>(n_variables, term_i)
+                );
+                log::trace!(
+                    "2d17146f: term_j: {}",
+                    minterm_to_string::<E>(n_variables, term_j)
+                );
+                log::trace!(
+                    "313a49ea: new_mt: {}",
+                    minterm_to_string::<E>(n_variables, new_mt)
+                );
 
                 new_minterms.insert(new_mt);
             }
@@ -146,14 +170,14 @@ pub fn reduce_minterms_classic<E: MintermEncoding>(
     }
 
     if show_info {
-        println!("INFO: 393bb38d: total_comparisons = {}", total_comparisons);
+        log::info!("393bb38d: total_comparisons = {}", total_comparisons);
     }
 
     for i in 0..max {
         if !checked[i] {
             if show_info {
-                println!(
-                    "INFO: 6dc50c80: adding existing minterm: {}",
+                log::info!(
+                    "6dc50c80: adding existing minterm: {}",
                     minterm_to_string::<E>(n_variables, minterms[i])
                 );
             }
@@ -161,19 +185,25 @@ pub fn reduce_minterms_classic<E: MintermEncoding>(
         }
     }
 
-    new_minterms.into_iter().collect()
+    Ok(new_minterms.into_iter().collect())
 }
 
 /// Reduce minterms using an optimized algorithm
+///
+/// Without a variable count, fails only on data that spilled into the don't-care region
+/// (see [`EncodingError::StrayDontCare`]).
 pub fn reduce_minterms<E: MintermEncoding>(
     minterms: &[E::Value],
     show_info: bool,
-) -> Vec<E::Value> {
+) -> Result<Vec<E::Value>, EncodingError> {
+    validate_terms::<E>(minterms)?;
     let mut total_comparisons = 0u64;
     let mut set = MintermSet::<E>::new();
     set.add_all(minterms);
 
-    let mut new_minterms = BTreeSet::new();
+    // Merged terms are deduplicated once at the end; a BTreeSet insert per merge dominated
+    // dense reductions
+    let mut new_minterms = Vec::with_capacity(minterms.len());
     let max_bit_count = set.get_max_bit_count();
 
     let mut checked_x: Vec<Vec<bool>> = Vec::new();
@@ -191,8 +221,8 @@ pub fn reduce_minterms<E: MintermEncoding>(
         total_comparisons += (max_i * max_j) as u64;
 
         if show_info {
-            println!(
-                "INFO: 413d6ad8: max_i = {max_i}; max_j = {max_j}; total_comparisons = {total_comparisons}"
+            log::info!(
+                "413d6ad8: max_i = {max_i}; max_j = {max_j}; total_comparisons = {total_comparisons}"
             );
         }
 
@@ -207,32 +237,34 @@ pub fn reduce_minterms<E: MintermEncoding>(
                     checked_x[bit_count + 1][j] = true;
                     let new_mt = replace_complements::<E>(term_i, term_j);
 
-                    if false {
-                        println!(
-                            "INFO: 09f28d3a: term_i: {}",
-                            minterm_to_string::<E>(3, term_i)
-                        );
-                        println!(
-                            "INFO: 2d17146f: term_j: {}",
-                            minterm_to_string::<E>(3, term_j)
-                        );
-                        println!(
-                            "INFO: 313a49ea: new_mt: {}",
-                            minterm_to_string::<E>(3, new_mt)
-                        );
-                    }
+                    log::trace!(
+                        "09f28d3a: term_i: {}",
+                        minterm_to_string::<E>(E::MAX_VARS, term_i)
+                    );
+                    log::trace!(
+                        "2d17146f: term_j: {}",
+                        minterm_to_string::<E>(E::MAX_VARS, term_j)
+                    );
+                    log::trace!(
+                        "313a49ea: new_mt: {}",
+                        minterm_to_string::<E>(E::MAX_VARS, new_mt)
+                    );
 
-                    new_minterms.insert(new_mt);
+                    new_minterms.push(new_mt);
                 }
             }
         }
     }
 
     if show_info {
-        println!("INFO: 393bb38d: total_comparisons = {}", total_comparisons);
+        log::info!("393bb38d: total_comparisons = {}", total_comparisons);
     }
 
-    let mut result: Vec<E::Value> = new_minterms.into_iter().collect();
+    // Sorted and deduplicated, exactly as the BTreeSet used to produce them; `reduce_qm`
+    // compares successive passes to detect the fixed point
+    new_minterms.sort_unstable();
+    new_minterms.dedup();
+    let mut result = new_minterms;
 
     for bit_count in 0..=max_bit_count {
         let checked_i = &checked_x[bit_count];
@@ -245,14 +277,15 @@ pub fn reduce_minterms<E: MintermEncoding>(
         }
     }
 
-    result
+    Ok(result)
 }
 
 /// Reduce minterms using an optimized algorithm with early pruning
 pub fn reduce_minterms_with_early_pruning<E: MintermEncoding>(
     minterms: &[E::Value],
     _show_info: bool,
-) -> Vec<E::Value> {
+) -> Result<Vec<E::Value>, EncodingError> {
+    validate_terms::<E>(minterms)?;
     let mut set = MintermSet::<E>::new();
     for &minterm in minterms {
         set.add(minterm);
@@ -321,7 +354,7 @@ pub fn reduce_minterms_with_early_pruning<E: MintermEncoding>(
         }
     }
 
-    new_minterms.into_iter().collect()
+    Ok(new_minterms.into_iter().collect())
 }
 
 pub mod petrick {
@@ -385,37 +418,88 @@ pub mod petrick {
         result
     }
 
+    /// Coverage of `minterms` (columns) by `prime_implicants` (rows)
+    ///
+    /// Each prime implicant is reduced once to its fixed values and don't-care mask, so the
+    /// inner loop is a single mask-and-compare per minterm.
+    pub fn create_coverage_matrix<E: MintermEncoding>(
+        prime_implicants: &[E::Value],
+        minterms: &[E::Value],
+    ) -> CoverageMatrix {
+        let data_mask = (E::Value::one() << E::DK_OFFSET) - E::Value::one();
+        let mut matrix = CoverageMatrix::new(prime_implicants.len(), minterms.len());
+
+        for (row, &pi) in prime_implicants.iter().enumerate() {
+            let mask = (pi >> E::DK_OFFSET) & data_mask;
+            let value = pi & data_mask & !mask;
+            for (col, &mt) in minterms.iter().enumerate() {
+                if mt & !mask == value {
+                    matrix.set(row, col, true);
+                }
+            }
+        }
+
+        matrix
+    }
+
     /// Create prime implicant table
     pub fn create_prime_implicant_table<E: MintermEncoding>(
         prime_implicants: &[E::Value],
         minterms: &[E::Value],
     ) -> PITable1<E::Value> {
-        // DATA_MASK needs to match the encoding's value type
-        let data_mask = if E::DK_OFFSET == 16 {
-            E::Value::from_u64(0xFFFF)
-        } else {
-            E::Value::from_u64(0xFFFF_FFFF)
-        };
-
+        let matrix = create_coverage_matrix::<E>(prime_implicants, minterms);
         let mut results = PITable1::new();
 
-        for &pi in prime_implicants {
-            // Shift to get don't-care bits
-            let dont_know = E::Value::from_u64(pi.to_u64() >> E::DK_OFFSET);
-            let q = (data_mask & pi) | dont_know;
-
-            let mut set = HashSet::new();
-            for &mt in minterms {
-                if (mt | dont_know) == q {
-                    set.insert(mt);
-                }
-            }
+        for (row, &pi) in prime_implicants.iter().enumerate() {
+            let set = minterms
+                .iter()
+                .enumerate()
+                .filter(|&(col, _)| matrix.get(row, col))
+                .map(|(_, &mt)| mt)
+                .collect();
             results.insert(pi, set);
         }
 
         results
     }
 
+    /// Primary essential prime implicants and the table of the remaining minterms
+    ///
+    /// Same result as `identify_primary_essential_pi2(&convert(&create_prime_implicant_table(..)))`,
+    /// but read off the coverage matrix, so only minterms not covered by an essential prime
+    /// implicant are ever put into a table. `prime_implicants` must not contain duplicates.
+    pub fn reduced_core_table<E: MintermEncoding>(
+        prime_implicants: &[E::Value],
+        minterms: &[E::Value],
+        matrix: &CoverageMatrix,
+    ) -> (PITable2<E::Value>, Vec<E::Value>) {
+        let covering =
+            |col: usize| (0..prime_implicants.len()).filter(move |&row| matrix.get(row, col));
+
+        let mut essential_rows = BTreeSet::new();
+        for col in 0..minterms.len() {
+            let mut rows = covering(col);
+            if let (Some(row), None) = (rows.next(), rows.next()) {
+                essential_rows.insert(row);
+            }
+        }
+
+        let mut pi_table = PITable2::new();
+        for (col, &mt) in minterms.iter().enumerate() {
+            let rows: Vec<usize> = covering(col).collect();
+            if rows.is_empty() || rows.iter().any(|row| essential_rows.contains(row)) {
+                continue;
+            }
+            pi_table.insert(mt, rows.iter().map(|&row| prime_implicants[row]).collect());
+        }
+
+        let essential = essential_rows
+            .into_iter()
+            .map(|row| prime_implicants[row])
+            .collect();
+        (pi_table, essential)
+    }
+
     /// Convert PITable1 to string
     pub fn to_string_pi_table1<E: MintermEncoding>(
         pi_table1: &PITable1<E::Value>,
@@ -595,50 +679,129 @@ pub mod petrick {
         result
     }
 
+    /// Size of the CNF handed to cnf_to_dnf by [`petricks_method_with_report`]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct PetrickReport {
+        pub n_prime_implicants: usize,
+        pub clauses: usize, // One per remaining minterm, before preprocessing
+        pub reduction: cnf_dnf::CnfReduction,
+    }
+
+    /// Buffers reused across calls of [`petricks_method_with_scratch`]
+    #[derive(Debug, Clone, Default)]
+    pub struct PetrickScratch<V> {
+        pis: Vec<V>, // Unique PIs, ascending: the CNF variable of a PI is its index
+    }
+
+    impl<V> PetrickScratch<V> {
+        pub fn new() -> Self {
+            Self { pis: Vec::new() }
+        }
+    }
+
     /// Petrick's method using CNF to DNF conversion
     ///
-    /// Note: This method is limited to at most 64 prime implicants due to the
-    /// u64-based CNF representation. Automatically selects optimization based on encoding type.
+    /// Up to 64 prime implicants use the u64-based CNF representation (with an optimization
+    /// selected by encoding type); more use [`WideClause`](cnf_dnf::WideClause)s.
     pub fn petricks_method<E: MintermEncoding>(
         pi_table2: &PITable2<E::Value>,
         show_info: bool,
     ) -> Vec<Vec<E::Value>> {
-        // Create translation maps
-        let mut translation1: HashMap<E::Value, usize> = HashMap::new();
-        let mut translation2: HashMap<usize, E::Value> = HashMap::new();
-        let mut variable_id = 0;
-
-        for pi_set in pi_table2.values() {
-            for &pi in pi_set {
-                if !translation1.contains_key(&pi) {
-                    translation1.insert(pi, variable_id);
-                    translation2.insert(variable_id, pi);
-                    variable_id += 1;
-                }
-            }
-        }
+        petricks_method_with_report::<E>(pi_table2, true, show_info).0
+    }
+
+    /// Petrick's method, also reporting how many CNF clauses were removed
+    ///
+    /// With `preprocess_cnf`, duplicate clauses (minterms with identical PI sets) and
+    /// superset clauses are removed before the CNF to DNF expansion.
+    ///
+    /// # Panics
+    /// Panics if a minterm of the table has no prime implicant: its CNF clause is empty,
+    /// which is a covering bug in the caller rather than an unsatisfiable problem.
+    pub fn petricks_method_with_report<E: MintermEncoding>(
+        pi_table2: &PITable2<E::Value>,
+        preprocess_cnf: bool,
+        show_info: bool,
+    ) -> (Vec<Vec<E::Value>>, PetrickReport) {
+        petricks_method_with_scratch::<E>(
+            pi_table2,
+            preprocess_cnf,
+            show_info,
+            &mut PetrickScratch::new(),
+        )
+    }
 
-        let n_variables = variable_id;
+    /// [`petricks_method_with_report`] with caller-owned buffers, for callers that run
+    /// Petrick's method many times
+    pub fn petricks_method_with_scratch<E: MintermEncoding>(
+        pi_table2: &PITable2<E::Value>,
+        preprocess_cnf: bool,
+        show_info: bool,
+        scratch: &mut PetrickScratch<E::Value>,
+    ) -> (Vec<Vec<E::Value>>, PetrickReport) {
+        // Number the PIs in ascending order, so the CNF does not depend on HashSet
+        // iteration order
+        let pis = &mut scratch.pis;
+        pis.clear();
+        pis.extend(pi_table2.values().flatten().copied());
+        pis.sort_unstable();
+        pis.dedup();
+        let pis = &*pis;
+        let variable = |pi: &E::Value| pis.binary_search(pi).expect("PI is in the table");
+
+        let n_variables = pis.len();
         if n_variables > 64 {
-            eprintln!(
-                "ERROR: too many prime implicants ({}) for cnf_to_dnf (max 64)",
-                n_variables
-            );
-            return Vec::new();
+            // Wide clauses: one word per 64 prime implicants (no CNF preprocessing)
+            let cnf: Vec<cnf_dnf::WideClause> = pi_table2
+                .values()
+                .map(|pi_set| {
+                    cnf_dnf::WideClause::from_bits(n_variables, pi_set.iter().map(variable))
+                })
+                .collect();
+            if let Some(clause_index) = cnf.iter().position(|c| c.count_ones() == 0) {
+                panic!(
+                    "Petrick CNF clause {} is empty: a minterm is covered by no prime implicant",
+                    clause_index
+                );
+            }
+            let report = PetrickReport {
+                n_prime_implicants: n_variables,
+                clauses: cnf.len(),
+                reduction: cnf_dnf::CnfReduction::default(),
+            };
+            let result = cnf_dnf::cnf_to_dnf_minimal_wide(&cnf, n_variables)
+                .iter()
+                .map(|conj| conj.ones().map(|i| pis[i]).collect())
+                .collect();
+            return (result, report);
         }
 
-        // Convert PI table to CNF (limited to u64 representation)
-        let mut cnf: Vec<u64> = Vec::new();
-        for pi_set in pi_table2.values() {
-            let mut disjunction = 0u64;
-            for &pi in pi_set {
-                disjunction |= 1u64 << translation1[&pi];
-            }
-            cnf.push(disjunction);
+        // Convert PI table to CNF (u64 fast path)
+        let mut cnf: Vec<u64> = pi_table2
+            .values()
+            .map(|pi_set| {
+                pi_set
+                    .iter()
+                    .fold(0u64, |disjunction, pi| disjunction | 1u64 << variable(pi))
+            })
+            .collect();
+
+        let mut report = PetrickReport {
+            n_prime_implicants: n_variables,
+            clauses: cnf.len(),
+            reduction: cnf_dnf::CnfReduction::default(),
+        };
+        if preprocess_cnf {
+            (cnf, report.reduction) = cnf_dnf::reduce_cnf(&cnf);
         }
 
         if show_info {
-            println!("CNF = {}", cnf_dnf::cnf_to_string(&cnf));
+            log::info!(
+                "CNF = {} ({} duplicate and {} superset clauses removed)",
+                cnf_dnf::cnf_to_string(&cnf),
+                report.reduction.duplicates_removed,
+                report.reduction.supersets_removed
+            );
         }
 
         // Convert CNF to DNF using encoding-aware API
@@ -662,122 +825,214 @@ pub mod petrick {
                 OptimizedFor::AutoDetect,
             )
         }
-        .expect("CNF to DNF conversion failed");
+        .unwrap_or_else(|e| match e {
+            // Every remaining minterm must be covered by some prime implicant
+            cnf_dnf::CnfDnfError::Unsatisfiable { clause_index } => panic!(
+                "Petrick CNF clause {} is empty: a minterm is covered by no prime implicant",
+                clause_index
+            ),
+            other => panic!("CNF to DNF conversion failed: {}", other),
+        });
 
         if show_info {
-            println!("DNF = {}", cnf_dnf::dnf_to_string(&smallest_conjunctions));
+            log::info!("DNF = {}", cnf_dnf::dnf_to_string(&smallest_conjunctions));
         }
 
         // Translate the smallest conjunctions back
-        let mut result = Vec::new();
-        for conj in smallest_conjunctions {
-            let mut x = Vec::new();
-            for i in 0..64 {
-                if (conj >> i) & 1 == 1
-                    && let Some(&pi) = translation2.get(&i)
-                {
-                    x.push(pi);
-                }
-            }
-            result.push(x);
-        }
+        let result = smallest_conjunctions
+            .iter()
+            .map(|&conj| {
+                (0..n_variables)
+                    .filter(|&i| (conj >> i) & 1 == 1)
+                    .map(|i| pis[i])
+                    .collect()
+            })
+            .collect();
+
+        (result, report)
+    }
 
-        result
+    /// Number of literals in a prime implicant
+    fn literal_count<E: MintermEncoding>(pi: E::Value, n_bits: usize) -> usize {
+        (0..n_bits)
+            .filter(|&i| !pi.get_bit(i + E::DK_OFFSET))
+            .count()
+    }
+
+    /// Product chosen by [`select_product`], with the products it was chosen over
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ProductChoice<V> {
+        pub product: Vec<V>,   // Sorted
+        pub literals: usize,   // Summed over the PIs of `product`
+        pub runner_ups: usize, // Other products with as few PIs
+        pub tied: usize,       // Runner-ups with as few literals too, passed over on order
+    }
+
+    /// Cheapest product of Petrick's method: fewest PIs, then fewest literals, then the
+    /// first in `products`
+    ///
+    /// [`petricks_method`] returns the products in canonical DNF order (see
+    /// [`sort_canonical`](cnf_dnf::sort_canonical)), so the choice is deterministic.
+    pub fn select_product<E: MintermEncoding>(
+        products: &[Vec<E::Value>],
+        n_bits: usize,
+    ) -> Option<ProductChoice<E::Value>> {
+        let costs: Vec<(usize, usize)> = products
+            .iter()
+            .map(|product| {
+                let literals = product
+                    .iter()
+                    .map(|&pi| literal_count::<E>(pi, n_bits))
+                    .sum();
+                (product.len(), literals)
+            })
+            .collect();
+        // The first of the cheapest
+        let best = (0..products.len()).min_by_key(|&i| costs[i])?;
+        let (size, literals) = costs[best];
+        let mut product = products[best].clone();
+        product.sort_unstable();
+        Some(ProductChoice {
+            product,
+            literals,
+            runner_ups: costs.iter().filter(|cost| cost.0 == size).count() - 1,
+            tied: costs.iter().filter(|&&cost| cost == costs[best]).count() - 1,
+        })
+    }
+
+    /// The product of [`select_product`]
+    pub fn cheapest_product<E: MintermEncoding>(
+        products: &[Vec<E::Value>],
+        n_bits: usize,
+    ) -> Option<Vec<E::Value>> {
+        select_product::<E>(products, n_bits).map(|choice| choice.product)
     }
 
     /// Petrick simplification
     ///
-    /// Automatically selects optimization based on encoding type.
+    /// Automatically selects optimization based on encoding type. Fails when `n_bits`
+    /// exceeds the encoding or a term has bits outside the data part.
     pub fn petrick_simplify<E: MintermEncoding>(
         prime_implicants: &[E::Value],
         minterms: &[E::Value],
         n_bits: usize,
         use_petrick_cnf2dnf: bool,
         show_info: bool,
-    ) -> Vec<E::Value> {
-        // 1. Create prime implicant table
-        let pi_table1 = create_prime_implicant_table::<E>(prime_implicants, minterms);
+    ) -> Result<Vec<E::Value>, EncodingError> {
+        petrick_simplify_with_scratch::<E>(
+            prime_implicants,
+            minterms,
+            n_bits,
+            use_petrick_cnf2dnf,
+            show_info,
+            &mut PetrickScratch::new(),
+        )
+    }
+
+    /// [`petrick_simplify`] with caller-owned buffers for Petrick's method
+    pub fn petrick_simplify_with_scratch<E: MintermEncoding>(
+        prime_implicants: &[E::Value],
+        minterms: &[E::Value],
+        n_bits: usize,
+        use_petrick_cnf2dnf: bool,
+        show_info: bool,
+        scratch: &mut PetrickScratch<E::Value>,
+    ) -> Result<Vec<E::Value>, EncodingError> {
+        E::validate(n_bits)?;
+        validate_terms::<E>(prime_implicants)?;
+        validate_terms::<E>(minterms)?;
+
+        // 1. Create the coverage matrix (the PI table is only materialized for display)
+        let mut prime_implicants = prime_implicants.to_vec();
+        prime_implicants.sort_unstable();
+        prime_implicants.dedup();
+        let matrix = create_coverage_matrix::<E>(&prime_implicants, minterms);
         if show_info {
-            println!("1] created PI table: number of PIs = {}", pi_table1.len());
-            println!("{}", to_string_pi_table1::<E>(&pi_table1, n_bits));
+            let pi_table1 = create_prime_implicant_table::<E>(&prime_implicants, minterms);
+            log::info!("1] created PI table: number of PIs = {}", pi_table1.len());
+            log::info!("{}", to_string_pi_table1::<E>(&pi_table1, n_bits));
         }
 
         // 2. Identify primary essential prime implicants
         let (pi_table2, primary_essential_pi) =
-            identify_primary_essential_pi2::<E>(&convert::<E>(&pi_table1));
+            reduced_core_table::<E>(&prime_implicants, minterms, &matrix);
         if show_info {
-            println!(
+            log::info!(
                 "2] identified primary essential PIs: number of essential PIs = {}; number of remaining PIs = {}",
                 primary_essential_pi.len(),
                 pi_table2.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table2, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table2, n_bits));
         }
 
         // 3. Row dominance
         let pi_table3 = row_dominance::<E>(&pi_table2);
         if show_info {
-            println!(
+            log::info!(
                 "3] reduced based on row dominance: number of PIs remaining = {}",
                 pi_table3.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table3, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table3, n_bits));
         }
 
         // 4. Column dominance
         let pi_table4 = column_dominance::<E>(&pi_table3);
         if show_info {
-            println!(
+            log::info!(
                 "4] reduced based on column dominance: number of PIs remaining = {}",
                 pi_table4.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table4, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table4, n_bits));
         }
 
         // 5. Identify secondary essential prime implicants
         let (pi_table5, secondary_essential_pi) = identify_primary_essential_pi2::<E>(&pi_table4);
         if show_info {
-            println!(
+            log::info!(
                 "5] identified secondary essential PIs: number of essential PIs = {}; number of remaining PIs = {}",
                 secondary_essential_pi.len(),
                 pi_table5.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table5, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table5, n_bits));
         }
 
         // 6. Row dominance
         let pi_table6 = row_dominance::<E>(&pi_table5);
         if show_info {
-            println!(
+            log::info!(
                 "6] reduced based on row dominance: number of PIs remaining = {}",
                 pi_table6.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table6, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table6, n_bits));
         }
 
         // 7. Column dominance
         let pi_table7 = column_dominance::<E>(&pi_table6);
         if show_info {
-            println!(
+            log::info!(
                 "7] reduced based on column dominance: number of PIs remaining = {}",
                 pi_table7.len()
             );
-            println!("{}", to_string_pi_table2::<E>(&pi_table7, n_bits));
+            log::info!("{}", to_string_pi_table2::<E>(&pi_table7, n_bits));
         }
 
         let mut essential_pi = Vec::new();
 
         if !pi_table7.is_empty() {
             if use_petrick_cnf2dnf {
-                let pi_vector_petricks = petricks_method::<E>(&pi_table7, show_info);
-                if !pi_vector_petricks.is_empty() {
-                    essential_pi.extend_from_slice(&pi_vector_petricks[0]);
-                }
-                if show_info {
-                    println!(
-                        "8] reduce with Petricks method: number essential PIs = {}",
-                        essential_pi.len()
-                    );
+                let (pi_vector_petricks, _) =
+                    petricks_method_with_scratch::<E>(&pi_table7, true, show_info, scratch);
+                if let Some(choice) = select_product::<E>(&pi_vector_petricks, n_bits) {
+                    if show_info {
+                        log::info!(
+                            "8] reduce with Petricks method: number essential PIs = {}; {} literals; {} runner-up products ({} with as few literals)",
+                            choice.product.len(),
+                            choice.literals,
+                            choice.runner_ups,
+                            choice.tied
+                        );
+                    }
+                    essential_pi.extend(choice.product);
                 }
             } else {
                 let mut pi_set = BTreeSet::new();
@@ -792,8 +1047,8 @@ pub mod petrick {
 
         for &pi in &primary_essential_pi {
             if show_info {
-                println!(
-                    "INFO: b650c460: adding primary essential PI to result: {}",
+                log::info!(
+                    "b650c460: adding primary essential PI to result: {}",
                     minterm_to_string::<E>(n_bits, pi)
                 );
             }
@@ -802,8 +1057,8 @@ pub mod petrick {
 
         for &pi in &secondary_essential_pi {
             if show_info {
-                println!(
-                    "INFO: e2c83d65: adding secondary essential PI to result: {}",
+                log::info!(
+                    "e2c83d65: adding secondary essential PI to result: {}",
                     minterm_to_string::<E>(n_bits, pi)
                 );
             }
@@ -811,46 +1066,129 @@ pub mod petrick {
         }
 
         if show_info {
-            println!(
-                "INFO: 6b723975: simplify removed {} from (initially) {} PIs",
+            log::info!(
+                "6b723975: simplify removed {} from (initially) {} PIs",
                 prime_implicants.len() - essential_pi.len(),
                 prime_implicants.len()
             );
         }
 
-        essential_pi
+        Ok(essential_pi)
+    }
+}
+
+/// Estimated pair comparisons up to which [`ReducerChoice::Auto`] runs the classic reducer
+///
+/// Below this the classic reducer's all-pairs loop is cheaper than sorting the terms into
+/// bit-count buckets first. In `benches/results/comparison.txt` (16-bit, one pass over
+/// random functions) classic takes 0.4x the time of bucketed on 7 terms over 4 variables
+/// and 1.26x on 103 terms over 8 variables (2.35x on 411 terms over 10). Interpolating puts
+/// the crossover near 60 terms over 8 variables, which estimate about 600 pairs.
+pub const AUTO_CLASSIC_MAX_PAIRS: u64 = 512;
+
+/// Reducer that [`reduce_qm`] runs for each combining pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReducerChoice {
+    /// Decided per pass from the [`MintermSet::estimated_pair_comparisons`] of its terms:
+    /// [`Classic`](Self::Classic) up to [`AUTO_CLASSIC_MAX_PAIRS`], else
+    /// [`Bucketed`](Self::Bucketed)
+    #[default]
+    Auto,
+    /// [`reduce_minterms_classic`]: every pair of terms
+    Classic,
+    /// [`reduce_minterms`]: pairs of terms in adjacent bit-count buckets
+    Bucketed,
+    /// [`reduce_minterms_with_early_pruning`]
+    EarlyPruning,
+}
+
+impl ReducerChoice {
+    /// The reducer to run on terms with `estimated_comparisons` candidate pairs; never `Auto`
+    pub fn resolve(self, estimated_comparisons: u64) -> Self {
+        match self {
+            ReducerChoice::Auto if estimated_comparisons <= AUTO_CLASSIC_MAX_PAIRS => {
+                ReducerChoice::Classic
+            }
+            ReducerChoice::Auto => ReducerChoice::Bucketed,
+            fixed => fixed,
+        }
     }
 }
 
+/// The former `use_classic_method` flag: `true` is `Classic`, `false` is `Bucketed`
+impl From<bool> for ReducerChoice {
+    fn from(use_classic_method: bool) -> Self {
+        if use_classic_method {
+            ReducerChoice::Classic
+        } else {
+            ReducerChoice::Bucketed
+        }
+    }
+}
+
+impl std::fmt::Display for ReducerChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReducerChoice::Auto => "auto",
+            ReducerChoice::Classic => "classic",
+            ReducerChoice::Bucketed => "bucketed",
+            ReducerChoice::EarlyPruning => "early pruning",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Options of [`reduce_qm`]; the default reduces to a fixed point with [`ReducerChoice::Auto`]
+/// and returns the prime implicants without Petrick's method
+#[derive(Default)]
+pub struct ReduceOptions<'a> {
+    pub reducer: ReducerChoice,
+    /// Run Petrick's method on the prime implicants and return a minimum cover
+    pub use_petrick_simplify: bool,
+    /// Let Petrick's method expand the CNF with `cnf_to_dnf` rather than by multiplication
+    pub use_petrick_cnf2dnf: bool,
+    /// CNF to DNF optimization; `None` uses the encoding's recommended variant. Only used
+    /// with `use_petrick_cnf2dnf`
+    pub optimized_for: Option<OptimizedFor>,
+    /// Cap on the number of combining passes; after it a warning is printed and the (not
+    /// fully reduced) terms of the last pass are used
+    pub max_iterations: Option<usize>,
+    /// Called after every pass with
+    /// `(iteration, before_len, after_len, estimated_comparisons, reducer)`, where the
+    /// estimate is [`MintermSet::estimated_pair_comparisons`] of the terms going into the
+    /// pass and `reducer` the one that ran it (resolved, see [`ReducerChoice::resolve`])
+    pub on_iteration: Option<&'a mut dyn FnMut(usize, usize, usize, u64, ReducerChoice)>,
+    pub show_info: bool,
+}
+
 /// Main Quine-McCluskey reduction function
 ///
-/// If `of` is None, uses the encoding's recommended OptimizedFor variant.
-/// The OptimizedFor parameter is only used when Petrick's method with CNF-to-DNF is enabled.
+/// The reduction loops until a fixed point, or until `options.max_iterations` passes.
+/// Fails when `n_variables` exceeds the encoding or a term has bits outside the data part.
 pub fn reduce_qm<E: MintermEncoding>(
     minterms_input: &[E::Value],
     n_variables: usize,
-    use_classic_method: bool,
-    use_petrick_simplify: bool,
-    use_petrick_cnf2dnf: bool,
-    of: Option<OptimizedFor>,
-    show_info: bool,
-) -> Vec<E::Value> {
-    // Validate encoding compatibility
-    if n_variables > E::MAX_VARS {
-        eprintln!(
-            "ERROR: n_variables ({}) exceeds encoding maximum ({})",
-            n_variables,
-            E::MAX_VARS
-        );
-        return Vec::new();
-    }
+    options: ReduceOptions<'_>,
+) -> Result<Vec<E::Value>, EncodingError> {
+    let ReduceOptions {
+        reducer,
+        use_petrick_simplify,
+        use_petrick_cnf2dnf,
+        optimized_for,
+        max_iterations,
+        mut on_iteration,
+        show_info,
+    } = options;
+
+    E::validate(n_variables)?;
+    validate_terms::<E>(minterms_input)?;
 
     // Validate OptimizedFor if provided
-    if let Some(optimized_for) = of
+    if let Some(optimized_for) = optimized_for
         && !E::is_compatible_with(optimized_for)
     {
-        eprintln!(
-            "WARNING: OptimizedFor {:?} (max {} bits) may be incompatible with {} variables",
+        log::warn!(
+            "OptimizedFor {:?} (max {} bits) may be incompatible with {} variables",
             optimized_for,
             optimized_for.max_bits(),
             n_variables
@@ -861,26 +1199,63 @@ pub fn reduce_qm<E: MintermEncoding>(
     let mut fixed_point = false;
 
     while !fixed_point {
-        let next_minterms = if use_classic_method {
-            reduce_minterms_classic::<E>(&minterms, n_variables, show_info)
-        } else {
-            reduce_minterms::<E>(&minterms, show_info)
+        if max_iterations.is_some_and(|max| iteration >= max) {
+            log::warn!(
+                "reduce_qm: no fixed point after {} iterations; using {} partially reduced terms",
+                iteration,
+                minterms.len()
+            );
+            break;
+        }
+
+        let estimated_comparisons =
+            if on_iteration.is_some() || show_info || reducer == ReducerChoice::Auto {
+                MintermSet::<E>::estimated_pair_comparisons_of(&minterms)
+            } else {
+                0
+            };
+        let pass_reducer = reducer.resolve(estimated_comparisons);
+        if show_info {
+            log::info!(
+                "5c0e7a12: reduce_qm: iteration {iteration}; estimated pair comparisons {estimated_comparisons}; reducer {pass_reducer}"
+            );
+        }
+
+        let next_minterms = match pass_reducer {
+            ReducerChoice::Classic => {
+                reduce_minterms_classic::<E>(&minterms, n_variables, show_info)?
+            }
+            ReducerChoice::EarlyPruning => {
+                reduce_minterms_with_early_pruning::<E>(&minterms, show_info)?
+            }
+            ReducerChoice::Auto | ReducerChoice::Bucketed => {
+                reduce_minterms::<E>(&minterms, show_info)?
+            }
         };
 
         fixed_point = minterms == next_minterms;
+        if let Some(callback) = on_iteration.as_mut() {
+            callback(
+                iteration,
+                minterms.len(),
+                next_minterms.len(),
+                estimated_comparisons,
+                pass_reducer,
+            );
+        }
 
         if show_info {
-            println!(
-                "INFO: 361a49a4: reduce_qm: iteration {iteration}; minterms {}; next minterms {}",
+            log::info!(
+                "361a49a4: reduce_qm: iteration {iteration}; minterms {}; next minterms {}",
                 minterms.len(),
                 next_minterms.len()
             );
-            println!(
-                "INFO: 49ecfd1e: old minterms = {}",
+            log::info!(
+                "49ecfd1e: old minterms = {}",
                 minterms_to_string::<E>(n_variables, &minterms)
             );
-            println!(
-                "INFO: ed11b7c0: new minterms = {}",
+            log::info!(
+                "ed11b7c0: new minterms = {}",
                 minterms_to_string::<E>(n_variables, &next_minterms)
             );
         }
@@ -898,13 +1273,384 @@ pub fn reduce_qm<E: MintermEncoding>(
             show_info,
         )
     } else {
-        minterms
+        Ok(minterms)
     }
 }
 
+/// [`reduce_qm`] with the former `use_classic_method` flag (see
+/// [`ReducerChoice::from`]) and a callback without the reducer
+///
+/// As before, invalid input is logged and gives no terms.
+#[deprecated(note = "use `reduce_qm` with a `ReducerChoice`")]
+#[allow(clippy::too_many_arguments)]
+pub fn reduce_qm_with_classic_flag<E: MintermEncoding>(
+    minterms_input: &[E::Value],
+    n_variables: usize,
+    use_classic_method: bool,
+    use_petrick_simplify: bool,
+    use_petrick_cnf2dnf: bool,
+    of: Option<OptimizedFor>,
+    max_iterations: Option<usize>,
+    mut on_iteration: Option<&mut dyn FnMut(usize, usize, usize, u64)>,
+    show_info: bool,
+) -> Vec<E::Value> {
+    let has_callback = on_iteration.is_some();
+    let mut forward =
+        |iteration: usize, before: usize, after: usize, estimate: u64, _: ReducerChoice| {
+            if let Some(callback) = on_iteration.as_mut() {
+                callback(iteration, before, after, estimate);
+            }
+        };
+    let forward: Option<&mut dyn FnMut(usize, usize, usize, u64, ReducerChoice)> = if has_callback {
+        Some(&mut forward)
+    } else {
+        None
+    };
+    reduce_qm::<E>(
+        minterms_input,
+        n_variables,
+        ReduceOptions {
+            reducer: ReducerChoice::from(use_classic_method),
+            use_petrick_simplify,
+            use_petrick_cnf2dnf,
+            optimized_for: of,
+            max_iterations,
+            on_iteration: forward,
+            show_info,
+        },
+    )
+    .unwrap_or_else(|e| {
+        log::error!("{}", e);
+        Vec::new()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CAPTURED: RefCell<Vec<(log::Level, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Records log messages of the calling thread, so parallel tests do not interfere
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED.with(|c| {
+                c.borrow_mut()
+                    .push((record.level(), record.args().to_string()))
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger;
+
+    /// Warnings and errors logged by this thread while running `f`
+    fn capture_warnings(f: impl FnOnce()) -> Vec<(log::Level, String)> {
+        // Other tests may have installed the logger already
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Warn);
+        CAPTURED.with(|c| c.borrow_mut().clear());
+        f();
+        CAPTURED.with(|c| c.take())
+    }
+
+    #[test]
+    fn test_incompatible_optimized_for_warns_once() {
+        let minterms: Vec<u32> = vec![0, 1, 2, 5, 6, 7];
+        let reduce = |of| {
+            reduce_qm::<Enc16>(
+                &minterms,
+                3,
+                ReduceOptions {
+                    reducer: ReducerChoice::Bucketed,
+                    use_petrick_simplify: true,
+                    use_petrick_cnf2dnf: true,
+                    optimized_for: Some(of),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        };
+
+        let logs = capture_warnings(|| reduce(OptimizedFor::Avx512_8bits));
+        let warnings: Vec<&(log::Level, String)> = logs
+            .iter()
+            .filter(|(level, message)| {
+                *level == log::Level::Warn && message.contains("may be incompatible")
+            })
+            .collect();
+        assert_eq!(warnings.len(), 1, "{:?}", logs);
+        assert!(warnings[0].1.contains("Avx512_8bits"));
+
+        assert!(capture_warnings(|| reduce(OptimizedFor::Avx512_16bits)).is_empty());
+    }
+
+    #[test]
+    fn test_cheapest_product() {
+        // 3 variables: 1X0 has 2 literals, XX1 has 1, 0X1 has 2
+        let pi_1x0 = replace_complements::<Enc16>(0b100, 0b110);
+        let pi_xx1 = replace_complements::<Enc16>(
+            replace_complements::<Enc16>(0b001, 0b011),
+            replace_complements::<Enc16>(0b101, 0b111),
+        );
+        let pi_0x1 = replace_complements::<Enc16>(0b001, 0b011);
+
+        let products = vec![vec![pi_0x1, pi_1x0], vec![pi_1x0, pi_xx1], vec![pi_0x1]];
+        assert_eq!(
+            petrick::cheapest_product::<Enc16>(&products, 3),
+            Some(vec![pi_0x1])
+        );
+
+        // Same number of PIs: fewer literals wins, independent of position
+        let products = vec![vec![pi_0x1, pi_1x0], vec![pi_xx1, pi_1x0]];
+        let mut expected = vec![pi_xx1, pi_1x0];
+        expected.sort_unstable();
+        assert_eq!(
+            petrick::cheapest_product::<Enc16>(&products, 3),
+            Some(expected)
+        );
+        assert_eq!(petrick::cheapest_product::<Enc16>(&[], 3), None);
+    }
+
+    #[test]
+    fn test_select_product_prefers_fewer_literals() {
+        // Two covers of two PIs each: 0X1 + 1X0 (4 literals) and XX1 + 1X0 (3 literals)
+        let pi_1x0 = replace_complements::<Enc16>(0b100, 0b110);
+        let pi_xx1 = replace_complements::<Enc16>(
+            replace_complements::<Enc16>(0b001, 0b011),
+            replace_complements::<Enc16>(0b101, 0b111),
+        );
+        let pi_0x1 = replace_complements::<Enc16>(0b001, 0b011);
+        let mut cheaper = vec![pi_xx1, pi_1x0];
+        cheaper.sort_unstable();
+
+        for products in [
+            vec![vec![pi_0x1, pi_1x0], vec![pi_xx1, pi_1x0]],
+            vec![vec![pi_xx1, pi_1x0], vec![pi_0x1, pi_1x0]],
+        ] {
+            let choice = petrick::select_product::<Enc16>(&products, 3).unwrap();
+            assert_eq!(choice.product, cheaper);
+            assert_eq!((choice.literals, choice.runner_ups, choice.tied), (3, 1, 0));
+        }
+
+        // Equal in PIs and literals: the first product, in canonical order
+        let products = vec![vec![pi_0x1], vec![pi_1x0]];
+        let choice = petrick::select_product::<Enc16>(&products, 3).unwrap();
+        assert_eq!(choice.product, vec![pi_0x1]);
+        assert_eq!((choice.runner_ups, choice.tied), (1, 1));
+    }
+
+    #[test]
+    fn test_petrick_simplify_selects_fewer_literals() {
+        // Minterms 1 and 3 are covered alike by XX1 and 0X1: one PI either way, and XX1
+        // has one literal less
+        let pi_xx1 = replace_complements::<Enc16>(
+            replace_complements::<Enc16>(0b001, 0b011),
+            replace_complements::<Enc16>(0b101, 0b111),
+        );
+        let pi_0x1 = replace_complements::<Enc16>(0b001, 0b011);
+        for primes in [[pi_xx1, pi_0x1], [pi_0x1, pi_xx1]] {
+            let cover =
+                petrick::petrick_simplify::<Enc16>(&primes, &[0b001, 0b011], 3, true, false)
+                    .unwrap();
+            assert_eq!(cover, vec![pi_xx1]);
+        }
+    }
+
+    #[test]
+    fn test_petrick_cnf_preprocessing_keeps_result() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seed};
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let normalize = |mut covers: Vec<Vec<u32>>| {
+            for cover in &mut covers {
+                cover.sort_unstable();
+            }
+            covers.sort();
+            covers
+        };
+
+        let seed = test_seed(7);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut removed = 0;
+        for _ in 0..20 {
+            let minterms: Vec<u32> = random_function(&mut rng, 6, 0.5)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+            let primes = reduce_qm::<Enc16>(
+                &minterms,
+                6,
+                ReduceOptions {
+                    reducer: ReducerChoice::Bucketed,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let pi_table1 = petrick::create_prime_implicant_table::<Enc16>(&primes, &minterms);
+            let (pi_table2, _) = petrick::identify_primary_essential_pi2::<Enc16>(
+                &petrick::convert::<Enc16>(&pi_table1),
+            );
+            if pi_table2.is_empty() {
+                continue;
+            }
+
+            let (with, report) =
+                petrick::petricks_method_with_report::<Enc16>(&pi_table2, true, false);
+            let (without, _) =
+                petrick::petricks_method_with_report::<Enc16>(&pi_table2, false, false);
+            assert_eq_seeded!(seed, normalize(with), normalize(without));
+            assert_eq_seeded!(seed, report.clauses, pi_table2.len());
+            removed += report.reduction.total_removed();
+        }
+        assert!(removed > 0, "expected some redundant clauses");
+    }
+
+    #[test]
+    #[should_panic(expected = "covered by no prime implicant")]
+    fn test_petrick_empty_clause_panics() {
+        // Minterm 3 lost its only prime implicant: a covering bug, not an unsatisfiable CNF
+        let mut pi_table2 = petrick::PITable2::new();
+        pi_table2.insert(1u32, HashSet::from([0x10001u32]));
+        pi_table2.insert(3u32, HashSet::new());
+        petrick::petricks_method::<Enc16>(&pi_table2, false);
+    }
+
+    #[test]
+    fn test_petrick_scratch_reuse_matches_fresh() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seed};
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let seed = test_seed(11);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut scratch = petrick::PetrickScratch::new();
+        for _ in 0..20 {
+            let minterms: Vec<u32> = random_function(&mut rng, 6, 0.5)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+            let primes = reduce_qm::<Enc16>(
+                &minterms,
+                6,
+                ReduceOptions {
+                    reducer: ReducerChoice::Bucketed,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let pi_table1 = petrick::create_prime_implicant_table::<Enc16>(&primes, &minterms);
+            let pi_table2 = petrick::convert::<Enc16>(&pi_table1);
+
+            // Same products in the same order, whatever the buffer held before
+            let fresh = petrick::petricks_method_with_report::<Enc16>(&pi_table2, true, false);
+            let reused = petrick::petricks_method_with_scratch::<Enc16>(
+                &pi_table2,
+                true,
+                false,
+                &mut scratch,
+            );
+            assert_eq_seeded!(seed, fresh, reused);
+        }
+    }
+
+    /// `create_prime_implicant_table` as it was before the coverage matrix (exact for Enc16
+    /// and Enc32 only)
+    fn pi_table_reference<E: MintermEncoding>(
+        prime_implicants: &[E::Value],
+        minterms: &[E::Value],
+    ) -> petrick::PITable1<E::Value> {
+        let data_mask = if E::DK_OFFSET == 16 {
+            E::Value::from_u64(0xFFFF)
+        } else {
+            E::Value::from_u64(0xFFFF_FFFF)
+        };
+        let mut results = petrick::PITable1::new();
+        for &pi in prime_implicants {
+            let dont_know = E::Value::from_u64(pi.to_u64() >> E::DK_OFFSET);
+            let q = (data_mask & pi) | dont_know;
+            let set = minterms
+                .iter()
+                .copied()
+                .filter(|&mt| (mt | dont_know) == q)
+                .collect();
+            results.insert(pi, set);
+        }
+        results
+    }
+
+    #[test]
+    fn test_coverage_matrix_matches_pi_tables() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seed};
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let seed = test_seed(11);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..30 {
+            let n_variables = rng.random_range(2..=8);
+            let minterms: Vec<u32> = random_function(&mut rng, n_variables, 0.4)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+            let primes = reduce_qm::<Enc16>(
+                &minterms,
+                n_variables,
+                ReduceOptions {
+                    reducer: ReducerChoice::Bucketed,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let matrix = petrick::create_coverage_matrix::<Enc16>(&primes, &minterms);
+            let reference = pi_table_reference::<Enc16>(&primes, &minterms);
+            for (row, pi) in primes.iter().enumerate() {
+                for (col, mt) in minterms.iter().enumerate() {
+                    assert_eq_seeded!(seed, matrix.get(row, col), reference[pi].contains(mt));
+                }
+            }
+            assert_eq_seeded!(
+                seed,
+                petrick::create_prime_implicant_table::<Enc16>(&primes, &minterms),
+                reference
+            );
+
+            let (expected_table, mut expected_essential) =
+                petrick::identify_primary_essential_pi2::<Enc16>(&petrick::convert::<Enc16>(
+                    &reference,
+                ));
+            let (table, mut essential) =
+                petrick::reduced_core_table::<Enc16>(&primes, &minterms, &matrix);
+            expected_essential.sort_unstable();
+            essential.sort_unstable();
+            assert_eq_seeded!(seed, table, expected_table);
+            assert_eq_seeded!(seed, essential, expected_essential);
+        }
+    }
+
+    #[test]
+    fn test_coverage_matrix_wide_encoding() {
+        // Variable 40 is beyond the old 32-bit DATA_MASK of Enc64
+        let bit40 = 1u128 << 40;
+        let pi = replace_complements::<Enc64>(bit40, bit40 | 1); // x0 free
+        let minterms = vec![bit40, bit40 | 1, 1, 0];
+        let matrix = petrick::create_coverage_matrix::<Enc64>(&[pi], &minterms);
+        let row: Vec<bool> = (0..minterms.len()).map(|col| matrix.get(0, col)).collect();
+        assert_eq!(row, vec![true, true, false, false]);
+    }
 
     #[test]
     fn test_is_gray_code_32bit() {
@@ -948,6 +1694,33 @@ mod tests {
         assert_eq!(set.get_max_bit_count(), 2);
     }
 
+    #[test]
+    fn test_minterm_set_histogram() {
+        let mut set = MintermSet::<Enc16>::new();
+        // Weights: 0 -> {0}, 1 -> {1, 2, 4}, 3 -> {7, 11}
+        set.add_all(&[0, 1, 2, 4, 7, 11]);
+        assert_eq!(set.histogram(), vec![(0, 1), (1, 3), (3, 2)]);
+        assert_eq!(set.densest_bucket(), Some((1, 3)));
+        // Only 0-1 are adjacent non-empty buckets; the empty weight-2 bucket cuts 1-3
+        assert_eq!(set.estimated_pair_comparisons(), 3);
+
+        set.add_all(&[3, 5, 6]);
+        // 1*3 + 3*3 + 3*2
+        assert_eq!(set.estimated_pair_comparisons(), 18);
+        assert_eq!(
+            MintermSet::<Enc16>::estimated_pair_comparisons_of(&[0, 1, 2, 4, 7, 11, 3, 5, 6]),
+            18
+        );
+        // Ties go to the lowest bit count
+        assert_eq!(set.densest_bucket(), Some((1, 3)));
+
+        let empty = MintermSet::<Enc16>::new();
+        assert!(empty.histogram().is_empty());
+        assert_eq!(empty.densest_bucket(), None);
+        assert_eq!(empty.estimated_pair_comparisons(), 0);
+        assert_eq!(MintermSet::<Enc16>::estimated_pair_comparisons_of(&[]), 0);
+    }
+
     #[test]
     fn test_replace_complements_32bit() {
         let result_32 = replace_complements::<Enc32>(0b0110u64, 0b0111u64);
@@ -989,6 +1762,110 @@ mod tests {
         assert_ne!(result_64, 0);
     }
 
+    /// `reduce_minterms` as it was with a BTreeSet of merged terms
+    fn reduce_minterms_btreeset<E: MintermEncoding>(minterms: &[E::Value]) -> Vec<E::Value> {
+        let mut set = MintermSet::<E>::new();
+        set.add_all(minterms);
+        let max_bit_count = set.get_max_bit_count();
+        let mut checked: Vec<Vec<bool>> = (0..=max_bit_count)
+            .map(|bc| vec![false; set.get(bc).len()])
+            .collect();
+        let mut new_minterms = BTreeSet::new();
+        for bit_count in 0..max_bit_count {
+            for (i, &term_i) in set.get(bit_count).iter().enumerate() {
+                for (j, &term_j) in set.get(bit_count + 1).iter().enumerate() {
+                    if is_gray_code::<E>(term_i, term_j) {
+                        checked[bit_count][i] = true;
+                        checked[bit_count + 1][j] = true;
+                        new_minterms.insert(replace_complements::<E>(term_i, term_j));
+                    }
+                }
+            }
+        }
+        let mut result: Vec<E::Value> = new_minterms.into_iter().collect();
+        for bit_count in 0..=max_bit_count {
+            for (i, &term) in set.get(bit_count).iter().enumerate() {
+                if !checked[bit_count][i] {
+                    result.push(term);
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_reduce_minterms_matches_btreeset_version() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seeds};
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        for seed in test_seeds(20) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let n_variables = rng.random_range(3..=10);
+            let density = rng.random_range(0.1..0.95);
+            let minterms: Vec<u32> = random_function(&mut rng, n_variables, density)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+
+            // Every pass of the fixed-point iteration must agree, including the order
+            let mut current = minterms;
+            loop {
+                let expected = reduce_minterms_btreeset::<Enc16>(&current);
+                let next = reduce_minterms::<Enc16>(&current, false).unwrap();
+                assert_eq_seeded!(seed, next, expected);
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_bucketed_pass_matches_classic_pass() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seeds};
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        for seed in test_seeds(20) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let density = rng.random_range(0.1..0.9);
+            let minterms: Vec<u32> = random_function(&mut rng, 8, density)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+
+            // Later passes mix terms with different don't-care masks; each single pass of
+            // the bucketed reducer must find exactly the merges of the O(n²) one
+            let mut current = minterms;
+            loop {
+                let mut expected = reduce_minterms_classic::<Enc16>(&current, 8, false).unwrap();
+                let mut next = reduce_minterms::<Enc16>(&current, false).unwrap();
+                expected.sort_unstable();
+                next.sort_unstable();
+                assert_eq_seeded!(seed, next, expected);
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_minterm_set_combinable_terms_are_adjacent() {
+        let mut set = MintermSet::<Enc16>::new();
+        // 0X1 (data 011, dc 010) and 1X1 (data 111, dc 010): one data bit apart. The shared
+        // don't-care sets the same data and mask bits in both, so their weights differ by one
+        let pi_0x1 = replace_complements::<Enc16>(0b001, 0b011);
+        let pi_1x1 = replace_complements::<Enc16>(0b101, 0b111);
+        set.add_all(&[pi_0x1, pi_1x1]);
+        assert_eq!(set.histogram(), vec![(3, 1), (4, 1)]);
+    }
+
     #[test]
     fn test_both_modes() {
         // Test that both 16-bit and 32-bit modes work correctly
@@ -996,11 +1873,11 @@ mod tests {
         let minterms_16: Vec<u32> = vec![0b001, 0b010, 0b110, 0b111];
 
         // 32-bit mode
-        let result_32 = reduce_minterms::<Enc32>(&minterms_32, false);
+        let result_32 = reduce_minterms::<Enc32>(&minterms_32, false).unwrap();
         assert!(!result_32.is_empty());
 
         // 16-bit mode
-        let result_16 = reduce_minterms::<Enc16>(&minterms_16, false);
+        let result_16 = reduce_minterms::<Enc16>(&minterms_16, false).unwrap();
         assert!(!result_16.is_empty());
 
         // Results should be the same for small problems
@@ -1050,16 +1927,228 @@ mod tests {
         // Test that reduce_qm rejects too many variables
         let minterms: Vec<u32> = vec![1, 3];
         let result = reduce_qm::<Enc16>(
-            &minterms, 20, // Exceeds MAX_VARS for Encoding16 (16)
-            false, false, false, None, false,
+            &minterms,
+            20, // Exceeds MAX_VARS for Encoding16 (16)
+            ReduceOptions {
+                reducer: ReducerChoice::Bucketed,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            result,
+            Err(EncodingError::TooManyVariables {
+                n_variables: 20,
+                max_vars: 16
+            })
         );
-        assert!(result.is_empty()); // Should return empty due to validation failure
 
         // Test that reduce_qm accepts valid variable count
         let result = reduce_qm::<Enc16>(
-            &minterms, 8, // Within MAX_VARS for Encoding16
-            false, false, false, None, false,
-        );
+            &minterms,
+            8, // Within MAX_VARS for Encoding16
+            ReduceOptions {
+                reducer: ReducerChoice::Bucketed,
+                ..Default::default()
+            },
+        )
+        .unwrap();
         assert!(!result.is_empty()); // Should succeed
     }
+
+    #[test]
+    fn test_entry_points_reject_17_variables_under_enc16() {
+        let too_many = EncodingError::TooManyVariables {
+            n_variables: 17,
+            max_vars: 16,
+        };
+        assert_eq!(Enc16::validate(17), Err(too_many.clone()));
+        assert!(Enc16::validate(16).is_ok());
+
+        // Bit 16 is the first don't-care bit under Enc16
+        let minterms: Vec<u32> = vec![0, 1 << 16];
+        assert_eq!(
+            reduce_minterms_classic::<Enc16>(&minterms, 17, false),
+            Err(too_many.clone())
+        );
+        assert_eq!(
+            petrick::petrick_simplify::<Enc16>(&minterms, &minterms, 17, false, false),
+            Err(too_many)
+        );
+    }
+
+    #[test]
+    fn test_reducers_reject_data_in_dk_region() {
+        // Without a variable count, a 17th variable shows up as a stray don't-care bit
+        let minterms: Vec<u32> = vec![0, 1 << 16];
+        let stray = Err(EncodingError::StrayDontCare { index: 1 });
+        assert_eq!(reduce_minterms::<Enc16>(&minterms, false), stray);
+        assert_eq!(
+            reduce_minterms_with_early_pruning::<Enc16>(&minterms, false),
+            stray
+        );
+        assert_eq!(
+            reduce_minterms_classic::<Enc16>(&minterms, 16, false),
+            stray
+        );
+        assert_eq!(
+            reduce_qm::<Enc16>(&minterms, 16, ReduceOptions::default()),
+            stray
+        );
+
+        // A don't-care bit with its data bit is a merged term
+        let merged = replace_complements::<Enc16>(0b0, 0b1);
+        assert!(reduce_minterms::<Enc16>(&[merged], false).is_ok());
+    }
+
+    #[test]
+    fn test_reduce_qm_iteration_callback() {
+        // Σ(0..8) over 3 variables: 8 minterms -> 12 pairs -> 6 quads -> XXX -> fixed point
+        let minterms: Vec<u32> = (0..8).collect();
+        let mut calls = Vec::new();
+        let mut record = |iteration, before, after, estimate, _| {
+            calls.push((iteration, before, after, estimate))
+        };
+
+        let result = reduce_qm::<Enc16>(
+            &minterms,
+            3,
+            ReduceOptions {
+                reducer: ReducerChoice::Bucketed,
+                on_iteration: Some(&mut record),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        // Buckets 1, 3, 3, 1 -> 3 + 9 + 3 candidate pairs in the first pass
+        assert_eq!(calls.first(), Some(&(0, 8, 12, 15)));
+        let &(last_iteration, before, after, _) = calls.last().unwrap();
+        assert_eq!((before, after), (1, 1));
+        assert_eq!(last_iteration, calls.len() - 1);
+    }
+
+    #[test]
+    fn test_reduce_qm_max_iterations_cap() {
+        let minterms: Vec<u32> = (0..8).collect();
+        let mut n_calls = 0;
+        let mut count = |_, _, _, _, _| n_calls += 1;
+
+        let result = reduce_qm::<Enc16>(
+            &minterms,
+            3,
+            ReduceOptions {
+                reducer: ReducerChoice::Bucketed,
+                max_iterations: Some(1),
+                on_iteration: Some(&mut count),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(n_calls, 1);
+        // Best effort: the terms after one pass, not the single prime implicant
+        assert!(result.len() > 1);
+    }
+
+    #[test]
+    fn test_reducer_choice_resolve() {
+        assert_eq!(ReducerChoice::default(), ReducerChoice::Auto);
+        assert_eq!(
+            ReducerChoice::Auto.resolve(AUTO_CLASSIC_MAX_PAIRS),
+            ReducerChoice::Classic
+        );
+        assert_eq!(
+            ReducerChoice::Auto.resolve(AUTO_CLASSIC_MAX_PAIRS + 1),
+            ReducerChoice::Bucketed
+        );
+        assert_eq!(
+            ReducerChoice::EarlyPruning.resolve(0),
+            ReducerChoice::EarlyPruning
+        );
+        assert_eq!(ReducerChoice::from(true), ReducerChoice::Classic);
+        assert_eq!(ReducerChoice::from(false), ReducerChoice::Bucketed);
+    }
+
+    #[test]
+    fn test_reduce_qm_records_auto_choice() {
+        // Σ(0..256) over 8 variables: 1024 + ... candidate pairs in the first pass, none in
+        // the last (a single term)
+        let minterms: Vec<u32> = (0..256).collect();
+        let mut reducers = Vec::new();
+        let mut record = |_, _, _, _, reducer| reducers.push(reducer);
+        let result = reduce_qm::<Enc16>(
+            &minterms,
+            8,
+            ReduceOptions {
+                on_iteration: Some(&mut record),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(reducers.first(), Some(&ReducerChoice::Bucketed));
+        assert_eq!(reducers.last(), Some(&ReducerChoice::Classic));
+        assert!(!reducers.contains(&ReducerChoice::Auto));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_reduce_qm_with_classic_flag() {
+        let minterms: Vec<u32> = (0..8).collect();
+        let mut n_calls = 0;
+        let mut count = |_, _, _, _| n_calls += 1;
+        let result = reduce_qm_with_classic_flag::<Enc16>(
+            &minterms,
+            3,
+            true,
+            false,
+            false,
+            None,
+            None,
+            Some(&mut count),
+            false,
+        );
+        assert_eq!(result.len(), 1);
+        assert!(n_calls > 1);
+    }
+
+    #[test]
+    fn test_reducer_choices_find_the_same_primes() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::{random_function, test_seed};
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let seed = test_seed(13);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..30 {
+            let n_variables = rng.random_range(1..=9);
+            let density = rng.random_range(0.1..0.9);
+            let minterms: Vec<u32> = random_function(&mut rng, n_variables, density)
+                .into_iter()
+                .map(|m| m as u32)
+                .collect();
+            let primes = |reducer| {
+                let mut primes = reduce_qm::<Enc16>(
+                    &minterms,
+                    n_variables,
+                    ReduceOptions {
+                        reducer,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+                primes.sort_unstable();
+                primes
+            };
+
+            let expected = primes(ReducerChoice::Bucketed);
+            for reducer in [
+                ReducerChoice::Auto,
+                ReducerChoice::Classic,
+                ReducerChoice::EarlyPruning,
+            ] {
+                assert_eq_seeded!(seed, primes(reducer), expected, "{}", reducer);
+            }
+        }
+    }
 }
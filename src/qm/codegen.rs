@@ -0,0 +1,360 @@
+//! Source code for a minimized function, for code generators that embed the result
+
+use super::qm_result::QMResult;
+use std::fmt;
+
+/// Shape of the function written by [`QMResult::to_rust_fn`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustFnStyle {
+    /// Written before `fn`, e.g. `pub` or `pub(crate)`; empty for a private function
+    pub visibility: String,
+    /// Parameter `i` is variable `i`; when empty: `a`, `b`, `c`, ..., or `x0`, `x1`, ...
+    /// for more than 26 variables. Names are written in snake case, see
+    /// [`rust_identifier`]
+    pub parameter_names: Vec<String>,
+}
+
+impl Default for RustFnStyle {
+    fn default() -> Self {
+        Self {
+            visibility: "pub".to_string(),
+            parameter_names: Vec::new(),
+        }
+    }
+}
+
+impl RustFnStyle {
+    /// Name of the parameter of variable `i` out of `n_variables`
+    fn parameter(&self, i: usize, n_variables: usize) -> String {
+        match self.parameter_names.get(i) {
+            Some(name) => name.clone(),
+            None if n_variables <= 26 => ((b'a' + i as u8) as char).to_string(),
+            None => format!("x{}", i),
+        }
+    }
+}
+
+/// Why [`QMResult::to_rust_fn`] cannot write a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RustFnError {
+    /// A function or parameter name with characters outside ASCII
+    NonAscii { name: String },
+    /// A function or parameter name without letters or digits
+    Empty { name: String },
+    /// Two parameter names written as the same identifier, e.g. `Ready` and `ready`
+    Duplicate {
+        first: String,
+        second: String,
+        identifier: String,
+    },
+}
+
+impl fmt::Display for RustFnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RustFnError::NonAscii { name } => {
+                write!(f, "name '{}' is not an ASCII identifier", name)
+            }
+            RustFnError::Empty { name } => {
+                write!(f, "name '{}' has no letters or digits", name)
+            }
+            RustFnError::Duplicate {
+                first,
+                second,
+                identifier,
+            } => write!(
+                f,
+                "names '{}' and '{}' are both written as '{}'",
+                first, second, identifier
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RustFnError {}
+
+/// Keywords of the 2024 edition, strict and reserved
+const RUST_KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// `name` as a snake-case Rust identifier: ASCII letters lowercased with an underscore
+/// at each lower-to-upper case step, every run of other characters one underscore, a
+/// leading digit prefixed with an underscore and keywords written raw (`r#type`), or
+/// with a trailing underscore where a raw identifier is not allowed (`self_`)
+///
+/// ```
+/// use qm_agent::qm::codegen::rust_identifier;
+///
+/// assert_eq!(rust_identifier("busReady").unwrap(), "bus_ready");
+/// assert_eq!(rust_identifier("Bus ready").unwrap(), "bus_ready");
+/// assert_eq!(rust_identifier("type").unwrap(), "r#type");
+/// assert!(rust_identifier("größe").is_err());
+/// ```
+pub fn rust_identifier(name: &str) -> Result<String, RustFnError> {
+    if !name.is_ascii() {
+        return Err(RustFnError::NonAscii {
+            name: name.to_string(),
+        });
+    }
+    let mut identifier = String::new();
+    let mut previous = ' ';
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase()
+                && (previous.is_ascii_lowercase() || previous.is_ascii_digit())
+            {
+                identifier.push('_');
+            }
+            identifier.push(c.to_ascii_lowercase());
+        } else if !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+        previous = c;
+    }
+    let identifier = identifier.trim_matches('_');
+    if identifier.is_empty() {
+        return Err(RustFnError::Empty {
+            name: name.to_string(),
+        });
+    }
+    Ok(if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", identifier)
+    } else if ["crate", "self", "super"].contains(&identifier) {
+        format!("{}_", identifier)
+    } else if RUST_KEYWORDS.contains(&identifier) {
+        format!("r#{}", identifier)
+    } else {
+        identifier.to_string()
+    })
+}
+
+impl QMResult {
+    /// The minimized function as a Rust function `name` over one `bool` per variable
+    ///
+    /// ```
+    /// use qm_agent::prelude::*;
+    /// use qm_agent::qm::codegen::RustFnStyle;
+    ///
+    /// let mut solver = QMSolver::<Enc16>::new(2);
+    /// solver.set_minterms(vec![1]);
+    /// assert_eq!(
+    ///     solver.solve().to_rust_fn("f", &RustFnStyle::default()).unwrap(),
+    ///     "pub fn f(a: bool, b: bool) -> bool {\n    a && !b\n}\n"
+    /// );
+    /// ```
+    ///
+    /// The body follows the order of [`cover`](QMResult::cover); an empty cover is `false`
+    /// and a term without literals `true`. A [`complemented`](QMResult::complemented)
+    /// result is negated, so the function still computes the original function. The
+    /// function and parameter names go through [`rust_identifier`]; names that are not
+    /// ASCII or that end up as the same parameter are an error.
+    pub fn to_rust_fn(&self, name: &str, style: &RustFnStyle) -> Result<String, RustFnError> {
+        let n = self.n_variables;
+        let mut identifiers: Vec<String> = Vec::with_capacity(n);
+        for i in 0..n {
+            let parameter = style.parameter(i, n);
+            let identifier = rust_identifier(&parameter)?;
+            if let Some(first) = identifiers.iter().position(|other| *other == identifier) {
+                return Err(RustFnError::Duplicate {
+                    first: style.parameter(first, n),
+                    second: parameter,
+                    identifier,
+                });
+            }
+            identifiers.push(identifier);
+        }
+        let parameters: Vec<String> = identifiers
+            .iter()
+            .map(|identifier| format!("{}: bool", identifier))
+            .collect();
+        let terms: Vec<Vec<String>> = self
+            .cover
+            .iter()
+            .map(|&(values, dc_mask)| {
+                (0..n)
+                    .filter(|&i| dc_mask >> i & 1 == 0)
+                    .map(|i| {
+                        if values >> i & 1 == 1 {
+                            identifiers[i].clone()
+                        } else {
+                            format!("!{}", identifiers[i])
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let body = if terms.is_empty() {
+            (self.complemented).to_string()
+        } else if terms.iter().any(Vec::is_empty) {
+            (!self.complemented).to_string()
+        } else if self.complemented && terms.len() == 1 && terms[0].len() == 1 {
+            // A single literal is negated in place rather than written as `!(!a)`
+            match terms[0][0].strip_prefix('!') {
+                Some(plain) => plain.to_string(),
+                None => format!("!{}", terms[0][0]),
+            }
+        } else {
+            let sum = if terms.len() == 1 {
+                terms[0].join(" && ")
+            } else {
+                terms
+                    .iter()
+                    .map(|term| match term.len() {
+                        1 => term[0].clone(),
+                        _ => format!("({})", term.join(" && ")),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" || ")
+            };
+            if self.complemented {
+                format!("!({})", sum)
+            } else {
+                sum
+            }
+        };
+
+        let visibility = if style.visibility.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", style.visibility)
+        };
+        Ok(format!(
+            "{}fn {}({}) -> bool {{\n    {}\n}}\n",
+            visibility,
+            rust_identifier(name)?,
+            parameters.join(", "),
+            body
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::{Enc16, Enc32, QMSolver};
+
+    fn solve(n: usize, minterms: &[u32]) -> QMResult {
+        let mut solver = QMSolver::<Enc16>::new(n);
+        solver.set_minterms(minterms.to_vec());
+        solver.solve()
+    }
+
+    #[test]
+    fn test_rust_fn_text() {
+        let style = RustFnStyle {
+            visibility: "pub(crate)".to_string(),
+            parameter_names: ["ready", "busy"].iter().map(|s| s.to_string()).collect(),
+        };
+        assert_eq!(
+            solve(2, &[1]).to_rust_fn("can_start", &style).unwrap(),
+            "pub(crate) fn can_start(ready: bool, busy: bool) -> bool {\n    ready && !busy\n}\n"
+        );
+
+        // Constant functions, and no variables at all
+        let private = RustFnStyle {
+            visibility: String::new(),
+            ..RustFnStyle::default()
+        };
+        assert_eq!(
+            solve(2, &[]).to_rust_fn("never", &private).unwrap(),
+            "fn never(a: bool, b: bool) -> bool {\n    false\n}\n"
+        );
+        assert_eq!(
+            solve(2, &[0, 1, 2, 3])
+                .to_rust_fn("always", &private)
+                .unwrap(),
+            "fn always(a: bool, b: bool) -> bool {\n    true\n}\n"
+        );
+
+        // Terms in cover order, parenthesized when they have several literals
+        let mut result = solve(3, &[]);
+        result.cover = vec![(0b001, 0b100), (0b100, 0b011)];
+        assert_eq!(
+            result.to_rust_fn("f", &RustFnStyle::default()).unwrap(),
+            "pub fn f(a: bool, b: bool, c: bool) -> bool {\n    (a && !b) || c\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rust_fn_names_are_identifiers() {
+        let identifier = |name: &str| rust_identifier(name).unwrap();
+        assert_eq!(identifier("A"), "a");
+        assert_eq!(identifier("busReady"), "bus_ready");
+        assert_eq!(identifier("HTTPStatus2xx"), "httpstatus2xx");
+        assert_eq!(identifier(" bus  ready-now "), "bus_ready_now");
+        assert_eq!(identifier("2nd"), "_2nd");
+        assert_eq!(identifier("type"), "r#type");
+        assert_eq!(identifier("Self"), "self_");
+        assert_eq!(
+            rust_identifier("größe"),
+            Err(RustFnError::NonAscii {
+                name: "größe".to_string()
+            })
+        );
+        assert_eq!(
+            rust_identifier("--"),
+            Err(RustFnError::Empty {
+                name: "--".to_string()
+            })
+        );
+
+        // Upper-case variable names, a keyword and a space
+        let style = |names: &[&str]| RustFnStyle {
+            parameter_names: names.iter().map(|s| s.to_string()).collect(),
+            ..RustFnStyle::default()
+        };
+        assert_eq!(
+            solve(3, &[7])
+                .to_rust_fn("Is Ready", &style(&["A", "match", "Bus Ready"]))
+                .unwrap(),
+            "pub fn is_ready(a: bool, r#match: bool, bus_ready: bool) -> bool {\n    \
+             a && r#match && bus_ready\n}\n"
+        );
+        let error = solve(2, &[1])
+            .to_rust_fn("f", &style(&["Ready", "ready"]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "names 'Ready' and 'ready' are both written as 'ready'"
+        );
+        assert!(solve(1, &[1]).to_rust_fn("größe", &style(&["a"])).is_err());
+    }
+
+    #[test]
+    fn test_rust_fn_complement_and_many_variables() {
+        // The off-set of Σ(1,3) over two variables is A'; negated back to A
+        let mut solver = QMSolver::<Enc16>::new(2);
+        solver.set_minterms(vec![1, 3]);
+        assert_eq!(
+            solver
+                .solve_complement()
+                .to_rust_fn("f", &RustFnStyle::default())
+                .unwrap(),
+            "pub fn f(a: bool, b: bool) -> bool {\n    a\n}\n"
+        );
+
+        let mut solver = QMSolver::<Enc32>::new(27);
+        solver.set_minterms(vec![1 << 26]);
+        let code = solver
+            .solve()
+            .to_rust_fn("wide", &RustFnStyle::default())
+            .unwrap();
+        assert!(
+            code.starts_with("pub fn wide(x0: bool, x1: bool, "),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(", x26: bool) -> bool {\n    !x0 && !x1 && "),
+            "{}",
+            code
+        );
+        assert!(code.ends_with(" && x26\n}\n"), "{}", code);
+    }
+}
@@ -0,0 +1,486 @@
+//! Cube algebra on classic-encoded implicants
+//!
+//! A cube is an `E::Value` in the classic encoding (see [`classic`](super::classic)): data
+//! bits at `0..n`, don't-care mask at `E::DK_OFFSET`. Data bits under the don't-care mask
+//! are ignored on input and set to 1 on output, as [`replace_complements`] does. A minterm
+//! is a cube without don't-cares.
+//!
+//! [`replace_complements`]: super::classic::replace_complements
+
+use super::encoding::{BitOps, MintermEncoding};
+use super::implicant::Implicant;
+
+/// Don't-care mask of `cube`, shifted down to the data positions
+#[inline]
+fn dc_mask<E: MintermEncoding>(cube: E::Value) -> E::Value {
+    cube >> E::DK_OFFSET
+}
+
+/// Mask of the data positions `0..DK_OFFSET`
+#[inline]
+fn data_mask<E: MintermEncoding>() -> E::Value {
+    (E::Value::one() << E::DK_OFFSET) - E::Value::one()
+}
+
+/// Canonical cube from data bits and a don't-care mask (both at the data positions)
+#[inline]
+fn make_cube<E: MintermEncoding>(data: E::Value, dc: E::Value) -> E::Value {
+    let dc = dc & data_mask::<E>();
+    (data & data_mask::<E>()) | dc | (dc << E::DK_OFFSET)
+}
+
+/// Cube from a value and a don't-care mask, e.g. an entry of `QMResult::cover`
+pub fn cube_from_mask<E: MintermEncoding>(values: u64, dc_mask: u64) -> E::Value {
+    make_cube::<E>(E::Value::from_u64(values), E::Value::from_u64(dc_mask))
+}
+
+/// Cube with every variable in `0..n_variables` free (the constant 1)
+pub fn universe<E: MintermEncoding>(n_variables: usize) -> E::Value {
+    let free = (0..n_variables).fold(E::Value::zero(), |v, i| v.set_bit(i));
+    make_cube::<E>(free, free)
+}
+
+/// Intersection of two cubes, `None` when they are disjoint
+pub fn cube_intersect<E: MintermEncoding>(a: E::Value, b: E::Value) -> Option<E::Value> {
+    let (dc_a, dc_b) = (dc_mask::<E>(a), dc_mask::<E>(b));
+    let both_fixed = !dc_a & !dc_b & data_mask::<E>();
+    if (a ^ b) & both_fixed != E::Value::zero() {
+        return None;
+    }
+    let data = (a & !dc_a) | (b & !dc_b);
+    Some(make_cube::<E>(data, dc_a & dc_b))
+}
+
+/// Does cube `a` contain cube `b` (every point of `b` is a point of `a`)?
+pub fn cube_contains<E: MintermEncoding>(a: E::Value, b: E::Value) -> bool {
+    let (dc_a, dc_b) = (dc_mask::<E>(a), dc_mask::<E>(b));
+    let fixed_a = !dc_a & data_mask::<E>();
+    // `b` must be fixed wherever `a` is, and to the same values
+    (dc_b & fixed_a) == E::Value::zero() && ((a ^ b) & fixed_a) == E::Value::zero()
+}
+
+/// `a # b`: disjoint cubes covering the points of `a` that are not in `b`
+pub fn cube_sharp<E: MintermEncoding>(
+    a: E::Value,
+    b: E::Value,
+    n_variables: usize,
+) -> Vec<E::Value> {
+    if cube_intersect::<E>(a, b).is_none() {
+        return vec![a];
+    }
+    let (dc_a, dc_b) = (dc_mask::<E>(a), dc_mask::<E>(b));
+    let mut result = Vec::new();
+    let mut data = a & !dc_a & data_mask::<E>();
+    let mut dc = dc_a;
+    for i in (0..n_variables).filter(|&i| dc_a.get_bit(i) && !dc_b.get_bit(i)) {
+        // Split on variable `i`: the half opposite to `b` is outside `b`
+        dc = dc & !(E::Value::one() << i);
+        let inside = data | (b & (E::Value::one() << i));
+        let outside = inside ^ (E::Value::one() << i);
+        result.push(make_cube::<E>(outside, dc));
+        data = inside;
+    }
+    result
+}
+
+/// Points (minterms) of `cube`, ascending
+pub fn cube_points<E: MintermEncoding>(cube: E::Value) -> Vec<E::Value> {
+    let free = dc_mask::<E>(cube) & data_mask::<E>();
+    let fixed = cube & !free & data_mask::<E>();
+    // Every subset of the free variables, from the full set down to the empty set
+    let mut points = Vec::new();
+    let mut subset = free;
+    loop {
+        points.push(fixed | subset);
+        if subset == E::Value::zero() {
+            break;
+        }
+        subset = (subset - E::Value::one()) & free;
+    }
+    points.reverse();
+    points
+}
+
+/// Disjoint cover of the complement (off-set) of `cover` over `n_variables` variables
+pub fn cover_complement<E: MintermEncoding>(
+    cover: &[E::Value],
+    n_variables: usize,
+) -> Vec<E::Value> {
+    debug_assert!(n_variables <= E::MAX_VARS);
+    cover
+        .iter()
+        .fold(vec![universe::<E>(n_variables)], |remaining, &cube| {
+            remaining
+                .into_iter()
+                .flat_map(|piece| cube_sharp::<E>(piece, cube, n_variables))
+                .collect()
+        })
+}
+
+/// Is every point of `cubes` a point of `cover`?
+pub fn cover_contains<E: MintermEncoding>(
+    cover: &[E::Value],
+    cubes: &[E::Value],
+    n_variables: usize,
+) -> bool {
+    let outside = cover_complement::<E>(cover, n_variables);
+    !cubes.iter().any(|&cube| {
+        outside
+            .iter()
+            .any(|&o| cube_intersect::<E>(cube, o).is_some())
+    })
+}
+
+/// Do covers `a` and `b` agree on every point outside `dont_cares`?
+pub fn covers_equivalent<E: MintermEncoding>(
+    a: &[E::Value],
+    b: &[E::Value],
+    dont_cares: &[E::Value],
+    n_variables: usize,
+) -> bool {
+    // Every point of `x` must lie in `y` or in the don't-cares
+    let within = |x: &[E::Value], y: &[E::Value]| {
+        let allowed: Vec<E::Value> = y.iter().chain(dont_cares).copied().collect();
+        cover_contains::<E>(&allowed, x, n_variables)
+    };
+    within(a, b) && within(b, a)
+}
+
+/// Consensus of `a` and `b`: when they conflict in exactly one variable, the cube of the
+/// literals of both without that variable, which lies in their union
+pub fn cube_consensus<E: MintermEncoding>(a: E::Value, b: E::Value) -> Option<E::Value> {
+    let (dc_a, dc_b) = (dc_mask::<E>(a), dc_mask::<E>(b));
+    let conflict = (a ^ b) & !dc_a & !dc_b & data_mask::<E>();
+    if conflict.count_ones() != 1 {
+        return None;
+    }
+    let data = ((a & !dc_a) | (b & !dc_b)) & !conflict;
+    Some(make_cube::<E>(data, (dc_a & dc_b) | conflict))
+}
+
+/// Prime implicants of the union of `cover`, ascending, by iterated consensus
+///
+/// Works on the cubes themselves, so wide cubes cost no more than minterms.
+pub fn cover_primes<E: MintermEncoding>(cover: &[E::Value]) -> Vec<E::Value> {
+    let mut primes: Vec<E::Value> = Vec::new();
+    let mut pending: Vec<E::Value> = cover
+        .iter()
+        .map(|&cube| make_cube::<E>(cube, dc_mask::<E>(cube)))
+        .collect();
+    while let Some(cube) = pending.pop() {
+        if primes.iter().any(|&p| cube_contains::<E>(p, cube)) {
+            continue;
+        }
+        // The consensus terms of an absorbed cube lie in `cube` or in one of its own
+        primes.retain(|&p| !cube_contains::<E>(cube, p));
+        pending.extend(primes.iter().filter_map(|&p| cube_consensus::<E>(p, cube)));
+        primes.push(cube);
+    }
+    primes.sort_unstable();
+    primes
+}
+
+/// The cubes of `primes` that cover a point of `on_set` no other prime (or don't-care)
+/// covers
+pub fn essential_cubes<E: MintermEncoding>(
+    primes: &[E::Value],
+    on_set: &[E::Value],
+    dont_cares: &[E::Value],
+    n_variables: usize,
+) -> Vec<E::Value> {
+    (0..primes.len())
+        .filter(|&i| !redundant::<E>(primes, i, on_set, dont_cares, n_variables))
+        .map(|i| primes[i])
+        .collect()
+}
+
+/// A subset of `primes` that still covers `on_set` (given `dont_cares`) and from which no
+/// cube can be dropped; the cubes with the most literals are dropped first
+///
+/// Irredundant is not minimum, but needs no point of `on_set` to be listed.
+pub fn irredundant_cover<E: MintermEncoding>(
+    primes: &[E::Value],
+    on_set: &[E::Value],
+    dont_cares: &[E::Value],
+    n_variables: usize,
+) -> Vec<E::Value> {
+    let mut cover = primes.to_vec();
+    cover.sort_by_key(|&cube| dc_mask::<E>(cube).count_ones());
+    let mut i = 0;
+    while i < cover.len() {
+        if redundant::<E>(&cover, i, on_set, dont_cares, n_variables) {
+            cover.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    cover.sort_unstable();
+    cover
+}
+
+/// Are the points of `on_set` in `cover[i]` covered by the rest of `cover` and `dont_cares`?
+fn redundant<E: MintermEncoding>(
+    cover: &[E::Value],
+    i: usize,
+    on_set: &[E::Value],
+    dont_cares: &[E::Value],
+    n_variables: usize,
+) -> bool {
+    let own: Vec<E::Value> = on_set
+        .iter()
+        .filter_map(|&cube| cube_intersect::<E>(cube, cover[i]))
+        .collect();
+    let rest: Vec<E::Value> = cover[..i]
+        .iter()
+        .chain(&cover[i + 1..])
+        .chain(dont_cares)
+        .copied()
+        .collect();
+    cover_contains::<E>(&rest, &own, n_variables)
+}
+
+/// How cube `a` relates to cube `b` as sets of points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeRelation {
+    Equal,
+    /// `a` strictly contains `b`
+    AContainsB,
+    /// `b` strictly contains `a`
+    BContainsA,
+    /// Common points, but neither contains the other
+    Overlap,
+    Disjoint,
+}
+
+/// Relation of cube `a` to cube `b`
+pub fn cube_relation<E: MintermEncoding>(a: E::Value, b: E::Value) -> CubeRelation {
+    match (cube_contains::<E>(a, b), cube_contains::<E>(b, a)) {
+        (true, true) => CubeRelation::Equal,
+        (true, false) => CubeRelation::AContainsB,
+        (false, true) => CubeRelation::BContainsA,
+        (false, false) if cube_intersect::<E>(a, b).is_some() => CubeRelation::Overlap,
+        (false, false) => CubeRelation::Disjoint,
+    }
+}
+
+/// `matrix[i][j]`: relation of `pis_a[i]` to `pis_b[j]` as cubes, e.g. of the prime
+/// implicants of two functions over the same variables
+///
+/// Only the variable states count: data bits under a don't-care and `covered_minterms`
+/// are ignored.
+pub fn intersection_matrix<E: MintermEncoding>(
+    pis_a: &[Implicant<E>],
+    pis_b: &[Implicant<E>],
+) -> Vec<Vec<CubeRelation>> {
+    let cubes_b: Vec<E::Value> = pis_b.iter().map(Implicant::to_cube).collect();
+    pis_a
+        .iter()
+        .map(|a| {
+            let cube_a = a.to_cube();
+            cubes_b
+                .iter()
+                .map(|&cube_b| cube_relation::<E>(cube_a, cube_b))
+                .collect()
+        })
+        .collect()
+}
+
+/// Index pairs `(i, j)` with `pis_a[i]` and `pis_b[j]` the same cube, ascending: the
+/// product terms the two functions can share
+pub fn shared_cubes<E: MintermEncoding>(
+    pis_a: &[Implicant<E>],
+    pis_b: &[Implicant<E>],
+) -> Vec<(usize, usize)> {
+    intersection_matrix(pis_a, pis_b)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.into_iter()
+                .enumerate()
+                .filter(|(_, relation)| *relation == CubeRelation::Equal)
+                .map(move |(j, _)| (i, j))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::classic::replace_complements;
+    use crate::qm::encoding::Enc16;
+    use proptest::prelude::*;
+
+    const N: usize = 6;
+
+    /// Points of a cube over `N` variables
+    fn points(cube: u32) -> Vec<u32> {
+        (0..1u32 << N)
+            .filter(|&m| cube_contains::<Enc16>(cube, m))
+            .collect()
+    }
+
+    /// A random cube: each variable is 0, 1 or free
+    fn cube() -> impl Strategy<Value = u32> {
+        prop::collection::vec(0u8..3, N).prop_map(|states| {
+            let (data, dc) = states
+                .iter()
+                .enumerate()
+                .fold((0u32, 0u32), |(data, dc), (i, &s)| match s {
+                    0 => (data, dc),
+                    1 => (data | 1 << i, dc),
+                    _ => (data, dc | 1 << i),
+                });
+            make_cube::<Enc16>(data, dc)
+        })
+    }
+
+    #[test]
+    fn test_cube_basics() {
+        // x1 (bit 1 fixed to 1, the rest free) and x0' x1
+        let x1 = make_cube::<Enc16>(0b10, 0b11_1101);
+        let x0n_x1 = make_cube::<Enc16>(0b10, 0b11_1100);
+        assert!(cube_contains::<Enc16>(x1, x0n_x1));
+        assert!(!cube_contains::<Enc16>(x0n_x1, x1));
+        assert_eq!(cube_intersect::<Enc16>(x1, x0n_x1), Some(x0n_x1));
+
+        // Pairs merged by the tabular method are cubes too
+        let merged = replace_complements::<Enc16>(0b000100, 0b000110);
+        assert_eq!(points(merged), vec![0b000100, 0b000110]);
+        assert!(cube_contains::<Enc16>(merged, 0b000110));
+        assert_eq!(points(universe::<Enc16>(N)).len(), 64);
+        assert_eq!(cube_points::<Enc16>(x0n_x1), points(x0n_x1));
+    }
+
+    #[test]
+    fn test_intersection_matrix_of_two_functions() {
+        use crate::qm::quine_mccluskey::QuineMcCluskey;
+
+        // F = Σ(0,1,3) has PIs 00X and 0X1; G = Σ(1,3,6,7) has 0X1, 11X and X11
+        let pis = |minterms: Vec<u32>| {
+            let mut qm = QuineMcCluskey::<Enc16>::new(3);
+            qm.set_minterms(minterms);
+            let mut pis: Vec<Implicant<Enc16>> = qm.prime_implicants_iter().collect();
+            pis.sort_by_key(Implicant::to_pattern);
+            pis
+        };
+        let (f, g) = (pis(vec![0, 1, 3]), pis(vec![1, 3, 6, 7]));
+        let patterns = |pis: &[Implicant<Enc16>]| -> Vec<String> {
+            pis.iter().map(Implicant::to_pattern).collect()
+        };
+        assert_eq!(patterns(&f), vec!["00X", "0X1"]);
+        assert_eq!(patterns(&g), vec!["0X1", "11X", "X11"]);
+
+        use CubeRelation::*;
+        assert_eq!(
+            intersection_matrix(&f, &g),
+            vec![
+                vec![Overlap, Disjoint, Disjoint],
+                vec![Equal, Disjoint, Overlap]
+            ]
+        );
+        assert_eq!(shared_cubes(&f, &g), vec![(1, 0)]);
+        assert_eq!(shared_cubes(&g, &f), vec![(0, 1)]);
+
+        // Containment either way; a pattern's data bits under `X` do not matter
+        let cube = |pattern: &str| Implicant::<Enc16>::from_pattern(pattern).unwrap();
+        let (wide, narrow) = (vec![cube("0XX")], vec![cube("01X")]);
+        assert_eq!(intersection_matrix(&wide, &narrow), vec![vec![AContainsB]]);
+        assert_eq!(intersection_matrix(&narrow, &wide), vec![vec![BContainsA]]);
+        let mut cleared = cube("0XX");
+        cleared.bits = 0b011_000;
+        assert_eq!(shared_cubes(&wide, &[cleared]), vec![(0, 0)]);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_intersect_is_pointwise_and(a in cube(), b in cube()) {
+            let expected: Vec<u32> =
+                points(a).into_iter().filter(|m| points(b).contains(m)).collect();
+            match cube_intersect::<Enc16>(a, b) {
+                Some(c) => prop_assert_eq!(points(c), expected),
+                None => prop_assert!(expected.is_empty()),
+            }
+        }
+
+        #[test]
+        fn prop_contains_is_subset(a in cube(), b in cube()) {
+            let subset = points(b).iter().all(|m| points(a).contains(m));
+            prop_assert_eq!(cube_contains::<Enc16>(a, b), subset);
+        }
+
+        #[test]
+        fn prop_complement_is_involutive(cover in prop::collection::vec(cube(), 0..4)) {
+            let complement = cover_complement::<Enc16>(&cover, N);
+            let twice = cover_complement::<Enc16>(&complement, N);
+            prop_assert!(covers_equivalent::<Enc16>(&cover, &twice, &[], N));
+            // A cover differs from its complement unless nothing is left to disagree on
+            prop_assert!(!covers_equivalent::<Enc16>(&cover, &complement, &[], N));
+        }
+
+        #[test]
+        fn prop_complement_covers_off_set(cover in prop::collection::vec(cube(), 0..5)) {
+            let complement = cover_complement::<Enc16>(&cover, N);
+            for m in 0..1u32 << N {
+                let in_cover = cover.iter().any(|&c| cube_contains::<Enc16>(c, m));
+                let hits = complement
+                    .iter()
+                    .filter(|&&c| cube_contains::<Enc16>(c, m))
+                    .count();
+                // Exactly the off-set, and the cubes are disjoint
+                prop_assert_eq!(hits, if in_cover { 0 } else { 1 });
+            }
+        }
+
+        #[test]
+        fn prop_cover_primes_are_the_primes(cover in prop::collection::vec(cube(), 0..5)) {
+            let union: Vec<u32> = (0..1u32 << N)
+                .filter(|&m| cover.iter().any(|&c| cube_contains::<Enc16>(c, m)))
+                .collect();
+            let implicant = |c: u32| points(c).iter().all(|m| union.contains(m));
+            // Every cube over `N` variables, each variable 0, 1 or free
+            let all_cubes = (0..3u32.pow(N as u32)).map(|code| {
+                let (data, dc) = (0..N).fold((0, 0), |(data, dc), i| {
+                    match code / 3u32.pow(i as u32) % 3 {
+                        0 => (data, dc),
+                        1 => (data | 1 << i, dc),
+                        _ => (data, dc | 1 << i),
+                    }
+                });
+                make_cube::<Enc16>(data, dc)
+            });
+            let implicants: Vec<u32> = all_cubes.filter(|&c| implicant(c)).collect();
+            let mut expected: Vec<u32> = implicants
+                .iter()
+                .copied()
+                .filter(|&c| !implicants.iter().any(|&d| d != c && cube_contains::<Enc16>(d, c)))
+                .collect();
+            expected.sort_unstable();
+            prop_assert_eq!(cover_primes::<Enc16>(&cover), expected);
+        }
+
+        #[test]
+        fn prop_irredundant_cover(
+            on_set in prop::collection::vec(cube(), 0..4),
+            dont_cares in prop::collection::vec(cube(), 0..3),
+        ) {
+            let care: Vec<u32> = on_set.iter().chain(&dont_cares).copied().collect();
+            let primes = cover_primes::<Enc16>(&care);
+            let cover = irredundant_cover::<Enc16>(&primes, &on_set, &dont_cares, N);
+            let with_dont_cares = |cubes: &[u32]| -> Vec<u32> {
+                cubes.iter().chain(&dont_cares).copied().collect()
+            };
+            prop_assert!(cover_contains::<Enc16>(&with_dont_cares(&cover), &on_set, N));
+            prop_assert!(cover_contains::<Enc16>(&care, &cover, N));
+            // Dropping any cube uncovers part of the on-set
+            for i in 0..cover.len() {
+                let mut fewer = cover.clone();
+                fewer.remove(i);
+                prop_assert!(!cover_contains::<Enc16>(&with_dont_cares(&fewer), &on_set, N));
+            }
+            // The essential cubes are in every cover from `primes`
+            for essential in essential_cubes::<Enc16>(&primes, &on_set, &dont_cares, N) {
+                prop_assert!(cover.contains(&essential));
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Semantic comparison of two minimization results
+//!
+//! Terms are compared as cubes, so a cover whose terms merely moved is unchanged, and the
+//! covers are checked for functional equivalence with the cube algebra of [`cubes`](super::cubes).
+
+use super::cubes::{cover_complement, covers_equivalent, cube_from_mask};
+use super::encoding::Enc64;
+use super::qm_result::QMResult;
+use std::fmt;
+
+/// Differences between the covers of two [`QMResult`]s
+///
+/// Terms are `(values, don't-care mask)` pairs as in [`QMResult::cover`], with the values
+/// of free variables cleared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverDiff {
+    /// Number of variables the terms are over
+    pub n_variables: usize,
+    /// Terms of both covers, in the order of `a`
+    pub unchanged: Vec<(u64, u64)>,
+    /// Terms only in `a`, in the order of `a`
+    pub only_in_a: Vec<(u64, u64)>,
+    /// Terms only in `b`, in the order of `b`
+    pub only_in_b: Vec<(u64, u64)>,
+    /// Both covers implement the same function on every point that is a don't-care of
+    /// neither result
+    pub equivalent: bool,
+}
+
+impl CoverDiff {
+    /// No term was added or removed
+    pub fn is_identical(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty()
+    }
+}
+
+/// Why two results cannot be compared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffError {
+    /// Result `a` or `b` has minterms but no `cover`, e.g. JSON written before the cover
+    /// was serialized
+    MissingCover { result: &'static str },
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffError::MissingCover { result } => write!(
+                f,
+                "result {} has no cover to compare (written by an older version?)",
+                result
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiffError {}
+
+/// Compare the covers of `a` and `b`
+///
+/// A point that either result treats as a don't-care cannot tell the covers apart. A
+/// complemented result (see `QMSolver::solve_complement`) is complemented back before the
+/// equivalence check; its terms are still compared as given.
+///
+/// An empty cover is the constant 0, so a result with minterms but without cover is
+/// rejected rather than compared as that.
+pub fn diff_covers(a: &QMResult, b: &QMResult) -> Result<CoverDiff, DiffError> {
+    for (result, name) in [(a, "a"), (b, "b")] {
+        if result.cover.is_empty() && !result.minterms.is_empty() {
+            return Err(DiffError::MissingCover { result: name });
+        }
+    }
+    let n_variables = a.n_variables.max(b.n_variables);
+    let terms_a = normalized(&a.cover);
+    let terms_b = normalized(&b.cover);
+
+    let (unchanged, only_in_a): (Vec<_>, Vec<_>) =
+        terms_a.iter().partition(|term| terms_b.contains(term));
+    let only_in_b: Vec<_> = terms_b
+        .iter()
+        .filter(|term| !terms_a.contains(term))
+        .copied()
+        .collect();
+
+    Ok(CoverDiff {
+        n_variables,
+        unchanged,
+        only_in_a,
+        only_in_b,
+        equivalent: functions_equivalent(a, b, n_variables),
+    })
+}
+
+/// Term of a diff as a pattern, most significant variable first and `X` for don't-cares
+/// (as [`Implicant::to_pattern`](super::Implicant::to_pattern))
+pub fn term_pattern((values, dc_mask): (u64, u64), n_variables: usize) -> String {
+    (0..n_variables)
+        .rev()
+        .map(|i| match ((dc_mask >> i) & 1, (values >> i) & 1) {
+            (1, _) => 'X',
+            (_, 1) => '1',
+            _ => '0',
+        })
+        .collect()
+}
+
+/// Cover terms with the values of free variables cleared, without duplicates
+fn normalized(cover: &[(u64, u64)]) -> Vec<(u64, u64)> {
+    let mut terms: Vec<(u64, u64)> = Vec::with_capacity(cover.len());
+    for &(values, dc_mask) in cover {
+        let term = (values & !dc_mask, dc_mask);
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Do the functions implemented by `a` and `b` agree outside both don't-care sets?
+fn functions_equivalent(a: &QMResult, b: &QMResult, n_variables: usize) -> bool {
+    let function = |result: &QMResult| {
+        let cubes: Vec<u128> = result
+            .cover
+            .iter()
+            .map(|&(values, dc_mask)| cube_from_mask::<Enc64>(values, dc_mask))
+            .collect();
+        if result.complemented {
+            cover_complement::<Enc64>(&cubes, n_variables)
+        } else {
+            cubes
+        }
+    };
+    let dont_cares: Vec<u128> = [a, b]
+        .iter()
+        .flat_map(|r| r.used_dont_cares.iter().chain(&r.unused_dont_cares))
+        .map(|&m| cube_from_mask::<Enc64>(m, 0))
+        .collect();
+    covers_equivalent::<Enc64>(&function(a), &function(b), &dont_cares, n_variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::{Enc32, QMSolver};
+
+    fn solve(minterms: &[u64], dont_cares: &[u64]) -> QMResult {
+        let mut solver = QMSolver::<Enc32>::new(3);
+        solver.set_minterms(minterms.to_vec());
+        solver.set_dont_cares(dont_cares.to_vec());
+        solver.solve()
+    }
+
+    #[test]
+    fn test_equivalent_covers_with_different_terms() {
+        // Σ(0,1,2,5,6,7) has two minimal covers: B'C' + A'B + AC and A'C' + AB' + BC
+        let mut a = solve(&[0, 1, 2, 5, 6, 7], &[]);
+        let mut b = a.clone();
+        a.cover = vec![(0b000, 0b001), (0b010, 0b100), (0b101, 0b010)];
+        b.cover = vec![(0b000, 0b010), (0b001, 0b100), (0b110, 0b001)];
+
+        let diff = diff_covers(&a, &b).unwrap();
+        assert!(diff.equivalent);
+        assert!(diff.unchanged.is_empty());
+        assert_eq!(diff.only_in_a.len(), 3);
+        assert_eq!(diff.only_in_b.len(), 3);
+
+        // Reordered terms, and values under the don't-care mask, are no change at all
+        let mut reordered = a.clone();
+        reordered.cover = vec![(0b111, 0b010), (0b001, 0b001), (0b010, 0b100)];
+        let diff = diff_covers(&a, &reordered).unwrap();
+        assert!(diff.is_identical() && diff.equivalent);
+        assert_eq!(term_pattern(diff.unchanged[0], 3), "00X");
+    }
+
+    #[test]
+    fn test_different_functions() {
+        // Making 4 a don't-care lets the cover drop it: equivalent modulo that don't-care
+        let a = solve(&[1, 3, 4, 7], &[]);
+        let b = solve(&[1, 3, 7], &[4]);
+        assert!(diff_covers(&a, &b).unwrap().equivalent);
+
+        // Dropping minterm 4 altogether changes the function
+        let c = solve(&[1, 3, 7], &[]);
+        let diff = diff_covers(&a, &c).unwrap();
+        assert!(!diff.equivalent);
+        assert!(!diff.is_identical());
+    }
+
+    #[test]
+    fn test_complemented_result() {
+        let minterms = [1, 3, 4, 7];
+        let a = solve(&minterms, &[]);
+        let mut solver = QMSolver::<Enc32>::new(3);
+        solver.set_minterms(minterms.to_vec());
+        let complement = solver.solve_complement();
+        assert!(complement.complemented);
+        assert!(diff_covers(&a, &complement).unwrap().equivalent);
+    }
+
+    #[test]
+    fn test_missing_cover() {
+        // Older JSON has no cover: it must not compare as the constant 0
+        let a = solve(&[1, 3], &[]);
+        let mut old = a.clone();
+        old.cover.clear();
+        assert_eq!(
+            diff_covers(&a, &old),
+            Err(DiffError::MissingCover { result: "b" })
+        );
+        assert_eq!(
+            diff_covers(&old, &a),
+            Err(DiffError::MissingCover { result: "a" })
+        );
+
+        // Without minterms the empty cover is the function
+        let zero = solve(&[], &[]);
+        assert!(zero.cover.is_empty());
+        assert!(diff_covers(&zero, &zero).unwrap().equivalent);
+        assert!(!diff_covers(&zero, &a).unwrap().equivalent);
+    }
+}
@@ -172,6 +172,18 @@ pub trait MintermEncoding: Copy + fmt::Debug {
     /// Get the recommended SIMD optimization strategy for this encoding width.
     fn recommended_optimized_for() -> OptimizedFor;
 
+    /// Check that `n_variables` fits the data part (and so never spills into the
+    /// don't-care region); every public reduction entry point calls this.
+    fn validate(n_variables: usize) -> Result<(), EncodingError> {
+        if n_variables > Self::MAX_VARS {
+            return Err(EncodingError::TooManyVariables {
+                n_variables,
+                max_vars: Self::MAX_VARS,
+            });
+        }
+        Ok(())
+    }
+
     /// Check if an OptimizedFor variant is compatible with this encoding's variable limit.
     /// Returns true if the variant can handle the encoding's `MAX_VARS`.
     fn is_compatible_with(of: OptimizedFor) -> bool {
@@ -191,12 +203,49 @@ pub trait MintermEncoding: Copy + fmt::Debug {
     ) -> Vec<(usize, usize)>;
 }
 
+/// Errors from [`MintermEncoding::validate`] and the input checks of the classic reducers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    /// More variables than the data part of the encoding holds
+    TooManyVariables { n_variables: usize, max_vars: usize },
+    /// Term `index` has a don't-care bit without its data bit: data that spilled into the
+    /// don't-care region
+    StrayDontCare { index: usize },
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::TooManyVariables {
+                n_variables,
+                max_vars,
+            } => write!(
+                f,
+                "n_variables ({}) exceeds encoding maximum ({})",
+                n_variables, max_vars
+            ),
+            EncodingError::StrayDontCare { index } => write!(
+                f,
+                "term {} has bits outside the data part (too many variables?)",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+// The data part and the don't-care region (from `DK_OFFSET`) must both fit the storage word
+const _: () = assert!(Enc16::MAX_VARS <= Enc16::DK_OFFSET && 2 * Enc16::DK_OFFSET <= 32);
+const _: () = assert!(Enc32::MAX_VARS <= Enc32::DK_OFFSET && 2 * Enc32::DK_OFFSET <= 64);
+const _: () = assert!(Enc64::MAX_VARS <= Enc64::DK_OFFSET && 2 * Enc64::DK_OFFSET <= 128);
+
 /// 16-bit encoding: uses u32 values, supports up to 16 variables.
 ///
 /// The `DK_OFFSET` is 16, meaning data bits occupy positions 0-15
 /// and don't-care mask occupies positions 16-31 in the raw encoding.
 /// Recommended SIMD optimization: `Avx512_16bits`.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Enc16;
 
 impl MintermEncoding for Enc16 {
@@ -224,7 +273,7 @@ impl MintermEncoding for Enc16 {
 /// The `DK_OFFSET` is 32, meaning data bits occupy positions 0-31
 /// and don't-care mask occupies positions 32-63 in the raw encoding.
 /// Recommended SIMD optimization: `Avx512_32bits`.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Enc32;
 
 impl MintermEncoding for Enc32 {
@@ -252,7 +301,7 @@ impl MintermEncoding for Enc32 {
 /// The `DK_OFFSET` is 64, meaning data bits occupy positions 0-63
 /// and don't-care mask occupies positions 64-127 in the raw encoding.
 /// Recommended SIMD optimization: `Avx512_64bits`.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Enc64;
 
 impl MintermEncoding for Enc64 {
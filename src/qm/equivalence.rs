@@ -0,0 +1,134 @@
+//! Equivalence checking between two sum-of-products expressions
+//!
+//! Both expressions are parsed with [`parse_sop`] over the union of their variables and
+//! their truth tables are compared exhaustively.
+
+use super::expression::{ExprError, parse_sop};
+
+/// Maximum number of variables for the exhaustive comparison (2^20 rows)
+pub const MAX_EQUIVALENCE_VARIABLES: usize = 20;
+
+/// An assignment on which the two expressions disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    /// Value of every variable, in the order of [`EquivalenceReport::variables`]
+    pub assignment: Vec<(String, bool)>,
+    pub value_a: bool,
+    pub value_b: bool,
+}
+
+/// Outcome of [`expressions_equivalent`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquivalenceReport {
+    /// Union of the variables of both expressions (bit `i` of a minterm is `variables[i]`)
+    pub variables: Vec<String>,
+    /// First assignment (lowest minterm) on which the expressions differ
+    pub counterexample: Option<Counterexample>,
+}
+
+impl EquivalenceReport {
+    pub fn is_equivalent(&self) -> bool {
+        self.counterexample.is_none()
+    }
+}
+
+/// Check whether two expressions compute the same function
+///
+/// `variables` fixes the order (and bit positions) of known names, and may be empty;
+/// variables appearing in only one of the expressions are still part of the comparison.
+pub fn expressions_equivalent(
+    expr_a: &str,
+    expr_b: &str,
+    variables: &[String],
+) -> Result<EquivalenceReport, ExprError> {
+    let a = parse_sop(expr_a, variables)?;
+    let b = parse_sop(expr_b, &a.variables)?;
+    // `a.variables` is a prefix of `b.variables`, so both agree on bit positions
+    let variables = b.variables.clone();
+
+    if variables.len() > MAX_EQUIVALENCE_VARIABLES {
+        return Err(ExprError::TooManyVariables {
+            n_variables: variables.len(),
+            max_vars: MAX_EQUIVALENCE_VARIABLES,
+        });
+    }
+
+    let counterexample = (0..1u64 << variables.len())
+        .find(|&m| a.evaluate(m) != b.evaluate(m))
+        .map(|m| Counterexample {
+            assignment: variables
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), (m >> i) & 1 == 1))
+                .collect(),
+            value_a: a.evaluate(m),
+            value_b: b.evaluate(m),
+        });
+
+    Ok(EquivalenceReport {
+        variables,
+        counterexample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_pair() {
+        let report = expressions_equivalent("A'B + AB", "B", &[]).unwrap();
+        assert!(report.is_equivalent());
+        assert_eq!(report.variables, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn test_inequivalent_pair_counterexample() {
+        let report = expressions_equivalent("AB + A'C", "AB + C", &[]).unwrap();
+        let counterexample = report.counterexample.expect("should differ");
+
+        // The counterexample must really separate the two expressions
+        let value = |name: &str| {
+            counterexample
+                .assignment
+                .iter()
+                .find(|(n, _)| n == name)
+                .unwrap()
+                .1
+        };
+        let (a, b, c) = (value("A"), value("B"), value("C"));
+        assert_eq!(counterexample.value_a, (a && b) || (!a && c));
+        assert_eq!(counterexample.value_b, (a && b) || c);
+        assert_ne!(counterexample.value_a, counterexample.value_b);
+        assert!(a && !b && c);
+    }
+
+    #[test]
+    fn test_mismatched_variables_use_union() {
+        // D only appears on one side; D + D' is a tautology so the functions still match
+        let report = expressions_equivalent("A", "AD + AD'", &[]).unwrap();
+        assert!(report.is_equivalent());
+        assert_eq!(report.variables, vec!["A", "D"]);
+
+        // ... but A on its own differs from A + D
+        let report = expressions_equivalent("A", "A + D", &[]).unwrap();
+        let counterexample = report.counterexample.unwrap();
+        assert_eq!(
+            counterexample.assignment,
+            vec![("A".to_string(), false), ("D".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn test_too_many_variables() {
+        let expr: Vec<String> = (0..21).map(|i| format!("x{}", i)).collect();
+        let result = expressions_equivalent(&expr.join(" + "), "1", &[]);
+        assert!(matches!(
+            result,
+            Err(ExprError::TooManyVariables {
+                n_variables: 21,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! Usage examples and interactive help of the `qm-agent` binary, as data
+//!
+//! The CLI only renders these; keeping them in the library lets the tests check that every
+//! example input is still accepted by the parser.
+
+/// A `minimize` input in one of the accepted formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    /// Short name of the format, e.g. `Function notation`
+    pub name: &'static str,
+    /// The input, as passed to `qm-agent minimize -i`
+    pub input: &'static str,
+    pub description: &'static str,
+}
+
+/// A command line of the CLI with what it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandExample {
+    /// Heading in the examples listing
+    pub title: &'static str,
+    /// Command lines; a `minimize` or `truth-table` input is quoted with `'`
+    pub commands: &'static [&'static str],
+    pub description: &'static str,
+}
+
+/// One example per input format of `minimize`
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        name: "JSON",
+        input: r#"{"minterms": [1,3,7], "variables": 3}"#,
+        description: "Structured input for automation",
+    },
+    Example {
+        name: "Function notation",
+        input: "f(A,B,C) = Σ(1,3,7)",
+        description: "Minimizes f(A,B,C) with minterms 1, 3, 7",
+    },
+    Example {
+        name: "With don't cares",
+        input: "f(A,B,C) = Σ(1,3,7) + d(2,4)",
+        description: "Uses don't care terms 2, 4 for better minimization",
+    },
+    Example {
+        name: "ASCII function notation",
+        input: "f(a,b,c) = sum(1,3,7) + dc(2,4)",
+        description: "Also m(...), Σm(...) and d(...), X(...), in any case",
+    },
+    Example {
+        name: "Simple",
+        input: "minimize minterms 1,3,7 with 3 variables",
+        description: "Plain English input",
+    },
+    Example {
+        name: "Simple with don't cares",
+        input: "minimize minterms 1,3,7 with don't cares 2,4 and 3 variables",
+        description: "Also dc or x terms; without a variable count it is inferred",
+    },
+    Example {
+        name: "Truth table",
+        input: "truth table: 00110110",
+        description: "Direct truth table input (8 bits = 3 variables)",
+    },
+    Example {
+        name: "Boolean expression",
+        input: "(!a && b) || c",
+        description: "A condition pasted from C code",
+    },
+    Example {
+        name: "Python expression",
+        input: "not a and b or c",
+        description: "A condition pasted from Python code",
+    },
+];
+
+/// Command lines beyond the input formats of [`EXAMPLES`]
+pub const COMMAND_EXAMPLES: &[CommandExample] = &[
+    CommandExample {
+        title: "Truth table without minimizing",
+        commands: &["qm-agent truth-table -i 'f(A,B,C) = Σ(1,3,7) + d(2)' --format markdown"],
+        description: "Print the table (text, csv, markdown)",
+    },
+    CommandExample {
+        title: "From file",
+        commands: &["qm-agent minimize -i input.json"],
+        description: "Read from JSON file",
+    },
+    CommandExample {
+        title: "Show step-by-step solution",
+        commands: &["qm-agent minimize -i 'f(A,B) = Σ(1,3)' --show-steps"],
+        description: "Educational mode with detailed steps",
+    },
+    CommandExample {
+        title: "Different output formats",
+        commands: &[
+            "qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f json",
+            "qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f table",
+            "qm-agent minimize -i 'f(A,B) = Σ(1,3)' -f steps",
+        ],
+        description: "Format: human (default), json, table, steps",
+    },
+    CommandExample {
+        title: "Compare two results",
+        commands: &["qm-agent diff -a before.json -b after.json"],
+        description: "Added/removed terms and whether the covers are equivalent",
+    },
+    CommandExample {
+        title: "Interactive mode",
+        commands: &["qm-agent interactive"],
+        description: "REPL for iterative problem solving",
+    },
+];
+
+/// Features of the `simplify` command
+pub const SIMPLIFY_FEATURES: &[&str] = &[
+    "Boolean expression minimization",
+    "Dead code detection",
+    "Coverage analysis",
+    "Integer comparison operators (==, <, >, <=, >=, !=)",
+    "Multi-language code generation (Go, Rust, C++, Python)",
+];
+
+/// Command lines of the `simplify` command
+pub const SIMPLIFY_EXAMPLES: &[CommandExample] = &[
+    CommandExample {
+        title: "From JSON file",
+        commands: &["qm-agent simplify -i examples/agent/simple.json"],
+        description: "",
+    },
+    CommandExample {
+        title: "From stdin",
+        commands: &["echo '{...}' | qm-agent simplify"],
+        description: "",
+    },
+    CommandExample {
+        title: "Inline JSON",
+        commands: &[r#"qm-agent simplify -i '{"variables": {"a": "boolean"}, "branches": [...]}'"#],
+        description: "",
+    },
+];
+
+/// Library examples, run with `cargo run --example NAME`
+pub const LIBRARY_EXAMPLES: &[&str] = &[
+    "simplify_if_then_else",
+    "dead_code_detection",
+    "comparison_operators",
+];
+
+/// Commands of the interactive mode besides an input to minimize
+pub const INTERACTIVE_COMMANDS: &[(&str, &str)] = &[
+    ("help", "Show this help"),
+    ("examples", "Show usage examples"),
+    ("quit/exit", "Exit interactive mode"),
+];
+
+/// The quoted `-i` argument of a `minimize` or `truth-table` command line
+pub fn quoted_input(command: &str) -> Option<&str> {
+    if !command.contains(" minimize ") && !command.contains(" truth-table ") {
+        return None;
+    }
+    let (_, rest) = command.split_once(" -i '")?;
+    rest.split_once('\'').map(|(input, _)| input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_inputs() {
+        let inputs: Vec<&str> = COMMAND_EXAMPLES
+            .iter()
+            .flat_map(|example| example.commands.iter())
+            .filter_map(|command| quoted_input(command))
+            .collect();
+        assert_eq!(inputs.len(), 5);
+        assert_eq!(inputs[0], "f(A,B,C) = Σ(1,3,7) + d(2)");
+        assert_eq!(quoted_input("qm-agent minimize -i input.json"), None);
+        assert_eq!(quoted_input(SIMPLIFY_EXAMPLES[2].commands[0]), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Sum-of-products expression parsing
+//!
+//! Parses minimized expressions in the notation produced by [`QMSolver`](super::QMSolver),
+//! e.g. `"A'B + AC"`, back into a form that can be evaluated. Accepted syntax:
+//! - terms separated by `+`; literals inside a term separated by nothing, whitespace, `*`, `&` or `·`
+//! - negation as postfix `'` or prefix `!` / `~`
+//! - constants `1` (tautology) and `0` (contradiction)
+//!
+//! Variable names are matched longest-first against the known names; any other name is a
+//! single letter followed by optional digits (`A`, `x1`) and is appended to the variable list.
+
+use std::fmt;
+
+/// Errors that can occur while parsing or evaluating an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    /// Unexpected character or missing operand at `position` (char index)
+    Parse { position: usize, message: String },
+    /// The expression (or the union of variables) has more variables than supported
+    TooManyVariables { n_variables: usize, max_vars: usize },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Parse { position, message } => {
+                write!(f, "parse error at position {}: {}", position, message)
+            }
+            ExprError::TooManyVariables {
+                n_variables,
+                max_vars,
+            } => {
+                write!(
+                    f,
+                    "too many variables ({}); maximum supported is {}",
+                    n_variables, max_vars
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// A parsed sum-of-products expression
+///
+/// Each term is a list of `(variable index, positive)` literals; an empty term is `1`,
+/// an empty term list is `0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SopExpression {
+    pub variables: Vec<String>,
+    pub terms: Vec<Vec<(usize, bool)>>,
+}
+
+impl SopExpression {
+    /// Evaluate with bit `i` of `assignment` as the value of `variables[i]`
+    ///
+    /// Variables from index 64 on are 0.
+    pub fn evaluate(&self, assignment: u64) -> bool {
+        self.terms.iter().any(|term| {
+            term.iter().all(|&(var, positive)| {
+                let value = u32::try_from(var)
+                    .ok()
+                    .and_then(|var| assignment.checked_shr(var))
+                    .unwrap_or(0);
+                (value & 1 == 1) == positive
+            })
+        })
+    }
+}
+
+/// Parse a sum-of-products expression such as `"A'B + AC"`
+///
+/// `variables` gives the known names (and their bit positions); names not in the list are
+/// appended in order of first appearance.
+pub fn parse_sop(input: &str, variables: &[String]) -> Result<SopExpression, ExprError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut variables = variables.to_vec();
+    let mut terms = Vec::new();
+    let mut term: Vec<(usize, bool)> = Vec::new();
+    let mut term_has_content = false;
+    let mut term_is_zero = false;
+    let mut negate_next = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        match ch {
+            c if c.is_whitespace() || c == '*' || c == '&' || c == '·' => i += 1,
+            '+' => {
+                if negate_next {
+                    return Err(ExprError::Parse {
+                        position: i,
+                        message: "negation without a variable".to_string(),
+                    });
+                }
+                finish_term(&mut terms, &mut term, term_has_content, term_is_zero, i)?;
+                term_has_content = false;
+                term_is_zero = false;
+                i += 1;
+            }
+            '!' | '~' => {
+                negate_next = !negate_next;
+                i += 1;
+            }
+            '0' | '1' if !negate_next => {
+                term_has_content = true;
+                term_is_zero |= ch == '0';
+                i += 1;
+            }
+            c if c.is_alphabetic() => {
+                // A fresh name is one letter plus trailing digits; a known name wins if it
+                // is at least as long (so `x10` is not read as `x1` followed by `0`)
+                let fresh_len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+                let remaining = &chars[i..];
+                let known = variables
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, name)| (idx, name.chars().count()))
+                    .filter(|&(idx, len)| {
+                        len <= remaining.len()
+                            && variables[idx].chars().eq(remaining[..len].iter().copied())
+                    })
+                    .max_by_key(|&(_, len)| len)
+                    .filter(|&(_, len)| len >= fresh_len);
+
+                let (var, len) = match known {
+                    Some(found) => found,
+                    None => {
+                        variables.push(chars[i..i + fresh_len].iter().collect());
+                        (variables.len() - 1, fresh_len)
+                    }
+                };
+                i += len;
+
+                let mut positive = !negate_next;
+                negate_next = false;
+                while i < chars.len() && chars[i] == '\'' {
+                    positive = !positive;
+                    i += 1;
+                }
+
+                term.push((var, positive));
+                term_has_content = true;
+            }
+            _ => {
+                return Err(ExprError::Parse {
+                    position: i,
+                    message: format!("unexpected character '{}'", ch),
+                });
+            }
+        }
+    }
+
+    if negate_next {
+        return Err(ExprError::Parse {
+            position: chars.len(),
+            message: "negation without a variable".to_string(),
+        });
+    }
+    finish_term(
+        &mut terms,
+        &mut term,
+        term_has_content,
+        term_is_zero,
+        chars.len(),
+    )?;
+
+    // `x x'` can never be true
+    terms.retain(|term| {
+        !term
+            .iter()
+            .any(|&(var, positive)| term.contains(&(var, !positive)))
+    });
+
+    Ok(SopExpression { variables, terms })
+}
+
+/// Close the current term; a term containing `0` is dropped
+fn finish_term(
+    terms: &mut Vec<Vec<(usize, bool)>>,
+    term: &mut Vec<(usize, bool)>,
+    has_content: bool,
+    is_zero: bool,
+    position: usize,
+) -> Result<(), ExprError> {
+    if !has_content {
+        return Err(ExprError::Parse {
+            position,
+            message: "expected a term".to_string(),
+        });
+    }
+    let term = std::mem::take(term);
+    if !is_zero {
+        terms.push(term);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_qm_notation() {
+        let expr = parse_sop("A'B + AC", &names(&["A", "B", "C"])).unwrap();
+        assert_eq!(
+            expr.terms,
+            vec![vec![(0, false), (1, true)], vec![(0, true), (2, true)]]
+        );
+        assert!(expr.evaluate(0b010)); // A=0, B=1
+        assert!(!expr.evaluate(0b001)); // A=1, C=0
+    }
+
+    #[test]
+    fn test_parse_discovers_variables() {
+        let expr = parse_sop("!x1 * x2 + x10", &[]).unwrap();
+        assert_eq!(expr.variables, names(&["x1", "x2", "x10"]));
+        assert_eq!(
+            expr.terms,
+            vec![vec![(0, false), (1, true)], vec![(2, true)]]
+        );
+    }
+
+    #[test]
+    fn test_parse_constants() {
+        let one = parse_sop("1", &[]).unwrap();
+        assert!(one.evaluate(0));
+        let zero = parse_sop("0", &[]).unwrap();
+        assert!(zero.terms.is_empty());
+        assert!(!zero.evaluate(0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_sop("A + ", &[]),
+            Err(ExprError::Parse { position: 4, .. })
+        ));
+        assert!(matches!(
+            parse_sop("A # B", &[]),
+            Err(ExprError::Parse { position: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_many_variables() {
+        // Regression input from fuzzing: evaluating variable 64 and up used to overflow the shift
+        let input: Vec<String> = (0..70).map(|i| format!("x{}", i)).collect();
+        let expr = parse_sop(&input.join(" + "), &[]).unwrap();
+        assert_eq!(expr.variables.len(), 70);
+        assert!(!expr.evaluate(0));
+        let last = parse_sop("x69'", &expr.variables).unwrap();
+        assert!(last.evaluate(u64::MAX));
+    }
+}
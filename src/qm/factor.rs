@@ -0,0 +1,338 @@
+//! Algebraic factoring of a sum-of-products into a multi-level form
+//!
+//! Two SOPs with the same literal count can differ a lot once factored, so the factored
+//! literal count and depth give a rough multi-level view of a result. Only algebraic
+//! division by cubes and literals is done (no kernels or Boolean division): the result is
+//! equivalent to the SOP, but not necessarily the best factored form.
+
+use std::cmp::Reverse;
+
+use super::expression::SopExpression;
+
+/// How far [`factor_sop`] goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FactorStyle {
+    /// Pull out the cube common to all terms only: `ABC + ABD` → `AB(C + D)`
+    CommonCube,
+    /// Repeatedly pull out the literal shared by the most terms, and the common cube of
+    /// every subexpression: `AB + AC + D` → `A(B + C) + D`
+    #[default]
+    CommonLiteral,
+}
+
+/// A factored expression with its cost metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FactoredExpression {
+    /// The factored form, in the notation of the minimized expression (e.g. `A(B + C') + D`)
+    pub expression: String,
+    /// Literals of the sum-of-products
+    pub literals_before: usize,
+    /// Literals of the factored form
+    pub literals_after: usize,
+    /// Levels of AND/OR operations; a literal has depth 0, a product 1, a sum of products 2
+    pub depth: usize,
+}
+
+/// Factor `sop` in the given style
+pub fn factor_sop(sop: &SopExpression, style: FactorStyle) -> FactoredExpression {
+    let tree = factor(&sop.terms, style);
+    FactoredExpression {
+        expression: tree.render(&sop.variables, false),
+        literals_before: sop.terms.iter().map(Vec::len).sum(),
+        literals_after: tree.literals(),
+        depth: tree.depth(),
+    }
+}
+
+type Literal = (usize, bool);
+
+/// Factored form as a tree; `And` and `Or` have at least two children and no child of
+/// their own kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Factored {
+    Const(bool),
+    Literal(Literal),
+    And(Vec<Factored>),
+    Or(Vec<Factored>),
+}
+
+impl Factored {
+    fn and(factors: Vec<Factored>) -> Factored {
+        Self::combine(factors, true)
+    }
+
+    fn or(summands: Vec<Factored>) -> Factored {
+        Self::combine(summands, false)
+    }
+
+    /// Flattened conjunction (`is_and`) or disjunction of `children`
+    fn combine(children: Vec<Factored>, is_and: bool) -> Factored {
+        let mut flat = Vec::with_capacity(children.len());
+        for child in children {
+            match child {
+                Factored::And(grand) if is_and => flat.extend(grand),
+                Factored::Or(grand) if !is_and => flat.extend(grand),
+                // The neutral element drops out, the absorbing one takes over
+                Factored::Const(value) if value == is_and => {}
+                Factored::Const(value) => return Factored::Const(value),
+                other => flat.push(other),
+            }
+        }
+        match flat.len() {
+            0 => Factored::Const(is_and),
+            1 => flat.pop().expect("one child"),
+            _ if is_and => Factored::And(flat),
+            _ => Factored::Or(flat),
+        }
+    }
+
+    fn literals(&self) -> usize {
+        match self {
+            Factored::Const(_) => 0,
+            Factored::Literal(_) => 1,
+            Factored::And(children) | Factored::Or(children) => {
+                children.iter().map(Factored::literals).sum()
+            }
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Factored::Const(_) | Factored::Literal(_) => 0,
+            Factored::And(children) | Factored::Or(children) => {
+                1 + children.iter().map(Factored::depth).max().unwrap_or(0)
+            }
+        }
+    }
+
+    /// Products are juxtaposed and sums joined with ` + `; a sum inside a product is
+    /// parenthesized
+    fn render(&self, names: &[String], in_product: bool) -> String {
+        match self {
+            Factored::Const(value) => if *value { "1" } else { "0" }.to_string(),
+            Factored::Literal((var, positive)) => {
+                format!("{}{}", names[*var], if *positive { "" } else { "'" })
+            }
+            Factored::And(children) => children
+                .iter()
+                .map(|child| child.render(names, true))
+                .collect(),
+            Factored::Or(children) => {
+                let sum = children
+                    .iter()
+                    .map(|child| child.render(names, false))
+                    .collect::<Vec<_>>()
+                    .join(" + ");
+                if in_product {
+                    format!("({})", sum)
+                } else {
+                    sum
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn evaluate(&self, assignment: u64) -> bool {
+        match self {
+            Factored::Const(value) => *value,
+            Factored::Literal((var, positive)) => ((assignment >> var) & 1 == 1) == *positive,
+            Factored::And(children) => children.iter().all(|c| c.evaluate(assignment)),
+            Factored::Or(children) => children.iter().any(|c| c.evaluate(assignment)),
+        }
+    }
+}
+
+/// Factored form of the sum of `terms`
+fn factor(terms: &[Vec<Literal>], style: FactorStyle) -> Factored {
+    if terms.is_empty() {
+        return Factored::Const(false);
+    }
+    if terms.iter().any(Vec::is_empty) {
+        return Factored::Const(true);
+    }
+    if terms.len() == 1 {
+        return product(&terms[0]);
+    }
+
+    let cube: Vec<Literal> = terms[0]
+        .iter()
+        .copied()
+        .filter(|literal| terms[1..].iter().all(|term| term.contains(literal)))
+        .collect();
+    if !cube.is_empty() {
+        let quotient: Vec<Vec<Literal>> = terms
+            .iter()
+            .map(|term| term.iter().copied().filter(|l| !cube.contains(l)).collect())
+            .collect();
+        let quotient = match style {
+            FactorStyle::CommonCube => sum_of_products(&quotient),
+            FactorStyle::CommonLiteral => factor(&quotient, style),
+        };
+        return Factored::and(vec![product(&cube), quotient]);
+    }
+
+    match style {
+        FactorStyle::CommonCube => sum_of_products(terms),
+        FactorStyle::CommonLiteral => match most_shared_literal(terms) {
+            // The terms with the literal have it as a common cube, so both sides shrink
+            Some(literal) => {
+                let (with, without): (Vec<Vec<Literal>>, Vec<Vec<Literal>>) = terms
+                    .iter()
+                    .cloned()
+                    .partition(|term| term.contains(&literal));
+                Factored::or(vec![factor(&with, style), factor(&without, style)])
+            }
+            None => sum_of_products(terms),
+        },
+    }
+}
+
+/// Literal in the most terms (at least two), the first one in term order on a tie
+fn most_shared_literal(terms: &[Vec<Literal>]) -> Option<Literal> {
+    let mut counts: Vec<(Literal, usize)> = Vec::new();
+    for &literal in terms.iter().flatten() {
+        match counts.iter_mut().find(|(l, _)| *l == literal) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((literal, 1)),
+        }
+    }
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, count))| *count >= 2)
+        .min_by_key(|&(position, &(_, count))| (Reverse(count), position))
+        .map(|(_, &(literal, _))| literal)
+}
+
+fn product(term: &[Literal]) -> Factored {
+    Factored::and(
+        term.iter()
+            .map(|&literal| Factored::Literal(literal))
+            .collect(),
+    )
+}
+
+fn sum_of_products(terms: &[Vec<Literal>]) -> Factored {
+    Factored::or(terms.iter().map(|term| product(term)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::expression::parse_sop;
+
+    fn factored(sop: &str, style: FactorStyle) -> FactoredExpression {
+        factor_sop(&parse_sop(sop, &[]).unwrap(), style)
+    }
+
+    #[test]
+    fn test_common_literal_saves_literals() {
+        let result = factored("AB + AC + D", FactorStyle::CommonLiteral);
+        assert_eq!(result.expression, "A(B + C) + D");
+        assert_eq!((result.literals_before, result.literals_after), (5, 4));
+        assert_eq!(result.depth, 3);
+
+        // ac + ad + bc + bd = (a + b)(c + d) needs kernels; literal factoring gets 6
+        let result = factored("AC + AD + BC + BD", FactorStyle::CommonLiteral);
+        assert_eq!(result.expression, "A(C + D) + B(C + D)");
+        assert_eq!((result.literals_before, result.literals_after), (8, 6));
+
+        let result = factored("A'B'C + A'B'D' + A'E + F", FactorStyle::CommonLiteral);
+        assert_eq!(result.expression, "A'(B'(C + D') + E) + F");
+        assert_eq!((result.literals_before, result.literals_after), (9, 6));
+        assert_eq!(result.depth, 5);
+    }
+
+    #[test]
+    fn test_common_cube_only() {
+        let result = factored("ABC + ABD", FactorStyle::CommonCube);
+        assert_eq!(result.expression, "AB(C + D)");
+        assert_eq!((result.literals_before, result.literals_after), (6, 4));
+        assert_eq!(result.depth, 2);
+
+        // No cube common to all terms: the SOP stays as it is
+        let result = factored("AB + AC + D", FactorStyle::CommonCube);
+        assert_eq!(result.expression, "AB + AC + D");
+        assert_eq!(result.literals_after, 5);
+        assert_eq!(result.depth, 2);
+    }
+
+    #[test]
+    fn test_trivial_expressions() {
+        for (sop, expression, depth) in [
+            ("0", "0", 0),
+            ("1", "1", 0),
+            ("A'", "A'", 0),
+            ("AB", "AB", 1),
+        ] {
+            let result = factored(sop, FactorStyle::CommonLiteral);
+            assert_eq!(
+                (result.expression.as_str(), result.depth),
+                (expression, depth)
+            );
+            assert_eq!(result.literals_before, result.literals_after);
+        }
+        // Absorbed terms: A + AB is A
+        assert_eq!(factored("A + AB", FactorStyle::CommonCube).expression, "A");
+    }
+
+    #[test]
+    fn test_factored_minimization_result() {
+        use crate::qm::{Enc16, QMSolver};
+
+        // AB + AC + D over 4 variables (A = bit 0)
+        let minterms: Vec<u32> = (0..16)
+            .filter(|m| (m & 0b0011 == 0b0011) || (m & 0b0101 == 0b0101) || (m & 0b1000 != 0))
+            .collect();
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(minterms);
+        let result = solver.solve();
+        let factored = result.factored_expression(FactorStyle::default());
+        assert_eq!((factored.literals_before, factored.literals_after), (5, 4));
+        assert_eq!(factored.depth, 3);
+
+        // Built from the cover, not from the formatted expression
+        let mut edited = result.clone();
+        edited.minimized_expression = "A + ?".to_string();
+        assert_eq!(edited.factored_expression(FactorStyle::default()), factored);
+    }
+
+    #[test]
+    fn test_factoring_preserves_the_function() {
+        use crate::qm::random::test_seed;
+        use crate::{assert_eq_seeded, assert_seeded};
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let seed = test_seed(7);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..200 {
+            let terms: Vec<Vec<Literal>> = (0..rng.random_range(0..6))
+                .map(|_| {
+                    // Each variable is absent, negated or plain
+                    (0..5)
+                        .filter_map(|var| match rng.random_range(0..5) {
+                            0 => Some((var, false)),
+                            1 => Some((var, true)),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .collect();
+            let sop = SopExpression {
+                variables: ["A", "B", "C", "D", "E"].map(String::from).to_vec(),
+                terms,
+            };
+            for style in [FactorStyle::CommonCube, FactorStyle::CommonLiteral] {
+                let tree = factor(&sop.terms, style);
+                assert_seeded!(
+                    seed,
+                    tree.literals() <= sop.terms.iter().map(Vec::len).sum()
+                );
+                for assignment in 0..32 {
+                    assert_eq_seeded!(seed, tree.evaluate(assignment), sop.evaluate(assignment));
+                }
+            }
+        }
+    }
+}
@@ -20,6 +20,7 @@ pub fn find_gray_code_pairs_avx512_u32(
     const LANES: usize = 16; // ZMM holds 16x u32
 
     if !is_x86_feature_detected!("avx512f") || !is_x86_feature_detected!("avx512vpopcntdq") {
+        crate::cnf_dnf::optimized_for::debug_scalar_fallback("find_gray_code_pairs_avx512_u32");
         return find_gray_code_pairs_ref(group1_indices, group2_indices, raw_encodings);
     }
 
@@ -84,6 +85,7 @@ pub fn find_gray_code_pairs_avx512_u64(
     const LANES: usize = 8; // ZMM holds 8x u64
 
     if !is_x86_feature_detected!("avx512f") || !is_x86_feature_detected!("avx512vpopcntdq") {
+        crate::cnf_dnf::optimized_for::debug_scalar_fallback("find_gray_code_pairs_avx512_u64");
         // Fallback to scalar
         return find_gray_code_pairs_ref(group1_indices, group2_indices, raw_encodings);
     }
@@ -160,6 +162,7 @@ pub fn find_gray_code_pairs_avx512_u128(
     const LANES: usize = 4; // Process 4x u128 as 8x u64
 
     if !is_x86_feature_detected!("avx512f") || !is_x86_feature_detected!("avx512vpopcntdq") {
+        crate::cnf_dnf::optimized_for::debug_scalar_fallback("find_gray_code_pairs_avx512_u128");
         return find_gray_code_pairs_ref(group1_indices, group2_indices, raw_encodings);
     }
 
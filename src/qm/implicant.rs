@@ -2,9 +2,12 @@
 //!
 //! Uses packed E::Value for all bit state storage (One/Zero/DontCare per variable).
 
+use super::cubes::cube_points;
 use super::encoding::{BitOps, MintermEncoding};
 use crate::qm::quine_mccluskey::validate_prime_implicant;
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::collections::HashSet;
+use std::fmt;
 
 /// State of a bit in an implicant: Zero, One, or DontCare
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,6 +17,57 @@ pub enum BitState {
     DontCare,
 }
 
+/// Errors that can occur while parsing an implicant pattern such as `"1X0-"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// Character other than `0`, `1`, `X`, `x` or `-` at `position` (char index)
+    InvalidCharacter { position: usize, character: char },
+    /// More characters than the encoding has variables
+    TooManyVariables { n_variables: usize, max_vars: usize },
+    /// More don't-cares than [`MAX_PATTERN_DONT_CARES`], whose points would not fit in memory
+    TooManyDontCares {
+        n_dont_cares: usize,
+        max_dont_cares: usize,
+    },
+}
+
+/// Most don't-cares in a pattern for [`Implicant::from_pattern`], which lists every point
+/// of the cube (2^24 of them at this bound)
+pub const MAX_PATTERN_DONT_CARES: usize = 24;
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "invalid pattern character '{}' at position {}",
+                character, position
+            ),
+            PatternError::TooManyVariables {
+                n_variables,
+                max_vars,
+            } => write!(
+                f,
+                "pattern has {} variables; maximum supported is {}",
+                n_variables, max_vars
+            ),
+            PatternError::TooManyDontCares {
+                n_dont_cares,
+                max_dont_cares,
+            } => write!(
+                f,
+                "pattern has {} don't-cares; maximum supported is {}",
+                n_dont_cares, max_dont_cares
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
 /// An implicant in the Quine-McCluskey algorithm.
 ///
 /// An implicant represents a cube (a product term) in the Karnaugh map /
@@ -25,7 +79,10 @@ pub enum BitState {
 /// Raw encoding layout stored in `bits`:
 ///   - Data bits: lower `n_variables` positions (1 = One, 0 = Zero)
 ///   - Don't-care bits: upper `n_variables` positions (1 = DontCare, 0 = One/Zero)
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Displayed as its pattern (`1X0`, see [`to_pattern`](Self::to_pattern)); the `Debug`
+/// form adds the number of literals.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Implicant<E: MintermEncoding> {
     pub bits: E::Value,
     pub n_variables: usize,
@@ -37,6 +94,11 @@ impl<E: MintermEncoding> Implicant<E> {
     /// Create from a single minterm (no dont-care bits).
     #[inline]
     pub fn from_minterm(minterm: E::Value, n_variables: usize) -> Self {
+        debug_assert!(
+            n_variables <= E::MAX_VARS && minterm >> n_variables == E::Value::zero(),
+            "minterm does not fit in {} variables",
+            n_variables
+        );
         Self {
             bits: minterm,
             n_variables,
@@ -48,6 +110,71 @@ impl<E: MintermEncoding> Implicant<E> {
         }
     }
 
+    /// Parse a pattern such as `"1X0-"`, most significant variable first (the order of
+    /// [`minterm_to_string`](super::classic::minterm_to_string))
+    ///
+    /// `X`, `x` and `-` mark a don't-care; `covered_minterms` is filled with every point of
+    /// the cube.
+    pub fn from_pattern(pattern: &str) -> Result<Self, PatternError> {
+        let n_variables = pattern.chars().count();
+        if n_variables > E::MAX_VARS {
+            return Err(PatternError::TooManyVariables {
+                n_variables,
+                max_vars: E::MAX_VARS,
+            });
+        }
+
+        let mut minterm = E::Value::zero();
+        let mut free = Vec::new();
+        for (position, character) in pattern.chars().enumerate() {
+            let bit = n_variables - 1 - position;
+            match character {
+                '1' => minterm = minterm.set_bit(bit),
+                '0' => {}
+                'X' | 'x' | '-' => free.push(bit),
+                _ => {
+                    return Err(PatternError::InvalidCharacter {
+                        position,
+                        character,
+                    });
+                }
+            }
+        }
+
+        if free.len() > MAX_PATTERN_DONT_CARES {
+            return Err(PatternError::TooManyDontCares {
+                n_dont_cares: free.len(),
+                max_dont_cares: MAX_PATTERN_DONT_CARES,
+            });
+        }
+
+        // Same raw layout as merged implicants: data and don't-care bit set for a free variable
+        let raw = free
+            .iter()
+            .fold(minterm, |raw, &i| raw.set_bit(i).set_bit(i + n_variables));
+        Ok(Self::from_raw_with_points(raw, n_variables))
+    }
+
+    /// This implicant as a pattern such as `"1X0"`, most significant variable first; the
+    /// inverse of [`from_pattern`](Self::from_pattern)
+    pub fn to_pattern(&self) -> String {
+        (0..self.n_variables)
+            .rev()
+            .map(|i| match self.get_bit(i) {
+                BitState::One => '1',
+                BitState::Zero => '0',
+                BitState::DontCare => 'X',
+            })
+            .collect()
+    }
+
+    /// Number of variables that are not don't-cares
+    pub fn literal_count(&self) -> usize {
+        (0..self.n_variables)
+            .filter(|&i| self.get_bit(i) != BitState::DontCare)
+            .count()
+    }
+
     /// Get an iterator over the minterms covered by this implicant.
     #[inline]
     pub fn covered_minterms_iter(&self) -> impl Iterator<Item = &E::Value> {
@@ -79,8 +206,65 @@ impl<E: MintermEncoding> Implicant<E> {
         if self.covered_minterms.contains(&minterm) {
             return true;
         }
+        // The dc half sits above the data bits and is not part of the minterm
         let mask = self.get_dc_mask();
-        (self.bits & !mask) == (minterm & !mask)
+        let data = self.bits ^ (mask << self.n_variables);
+        (data & !mask) == (minterm & !mask)
+    }
+
+    /// Representative input vectors of this cube, e.g. to drive a simulation.
+    ///
+    /// Returns the minimal vector (don't-cares = 0), the maximal vector (don't-cares = 1)
+    /// and `k` random vectors inside the cube, in that order with duplicates removed.
+    pub fn sample_vectors(&self, k: usize, seed: u64) -> Vec<E::Value> {
+        let mut minimal = E::Value::zero();
+        let mut free = Vec::new();
+        for i in 0..self.n_variables {
+            match self.get_bit(i) {
+                BitState::One => minimal = minimal.set_bit(i),
+                BitState::Zero => {}
+                BitState::DontCare => free.push(i),
+            }
+        }
+        let maximal = free.iter().fold(minimal, |v, &i| v.set_bit(i));
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let random = (0..k).map(|_| {
+            free.iter()
+                .filter(|_| rng.random::<bool>())
+                .fold(minimal, |v, &i| v.set_bit(i))
+        });
+
+        let mut seen = HashSet::new();
+        [minimal, maximal]
+            .into_iter()
+            .chain(random)
+            .filter(|v| seen.insert(*v))
+            .collect()
+    }
+
+    /// This implicant as a classic-encoded cube (don't-care mask at `E::DK_OFFSET`), the
+    /// form used by [`cubes`](super::cubes)
+    pub fn to_cube(&self) -> E::Value {
+        (0..self.n_variables).fold(E::Value::zero(), |cube, i| match self.get_bit(i) {
+            BitState::One => cube.set_bit(i),
+            BitState::Zero => cube,
+            BitState::DontCare => cube.set_bit(i).set_bit(i + E::DK_OFFSET),
+        })
+    }
+
+    /// Inverse of [`to_cube`](Self::to_cube), with no covered minterms listed
+    pub(crate) fn from_cube(cube: E::Value, n_variables: usize) -> Self {
+        let raw = (0..n_variables).fold(E::Value::zero(), |raw, i| {
+            if cube.get_bit(i + E::DK_OFFSET) {
+                raw.set_bit(i).set_bit(i + n_variables)
+            } else if cube.get_bit(i) {
+                raw.set_bit(i)
+            } else {
+                raw
+            }
+        });
+        Self::from_raw_encoding(raw, n_variables)
     }
 
     #[inline]
@@ -103,6 +287,15 @@ impl<E: MintermEncoding> Implicant<E> {
         result
     }
 
+    /// Create from a raw encoding, with every point of the cube as covered minterm
+    ///
+    /// The caller bounds the number of don't-cares: there are 2^n points for n of them.
+    pub(crate) fn from_raw_with_points(raw: E::Value, n_variables: usize) -> Self {
+        let mut implicant = Self::from_raw_encoding(raw, n_variables);
+        implicant.covered_minterms = cube_points::<E>(implicant.to_cube()).into_iter().collect();
+        implicant
+    }
+
     /// Create from raw encoding (internal use only).
     pub(crate) fn from_raw_encoding(raw: E::Value, n_variables: usize) -> Self {
         Self {
@@ -113,10 +306,27 @@ impl<E: MintermEncoding> Implicant<E> {
     }
 }
 
+impl<E: MintermEncoding> fmt::Display for Implicant<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pattern())
+    }
+}
+
+impl<E: MintermEncoding> fmt::Debug for Implicant<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Implicant")
+            .field("pattern", &format_args!("{}", self))
+            .field("literals", &self.literal_count())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::qm::encoding::Enc32;
+    use crate::qm::classic::minterm_to_string;
+    use crate::qm::encoding::{Enc16, Enc32, Enc64};
+    use proptest::prelude::*;
 
     #[test]
     fn is_gray_code() {
@@ -139,6 +349,40 @@ mod tests {
         assert!(Implicant::<Enc32>::is_gray_code(0, 1u64 << 7));
     }
 
+    #[test]
+    fn covers_minterm_without_covered_list() {
+        // 1X0X over 4 variables, given with and without data bits under the don't-cares
+        for raw in [0b0101_1000, 0b0101_1101] {
+            let implicant = Implicant::<Enc32>::from_raw_encoding(raw, 4);
+            let covered: Vec<u64> = (0..16).filter(|&m| implicant.covers_minterm(m)).collect();
+            assert_eq!(covered, vec![0b1000, 0b1001, 0b1100, 0b1101]);
+        }
+
+        // The universal cube over one variable
+        let implicant = Implicant::<Enc16>::from_raw_encoding(0b11, 1);
+        assert!(implicant.covers_minterm(0) && implicant.covers_minterm(1));
+    }
+
+    #[test]
+    fn sample_vectors_stay_inside_cube() {
+        // 1X0X over 4 variables (bit 0 = A): A and C free, B = 0, D = 1
+        let raw = 0b0101_1000;
+        let implicant = Implicant::<Enc32>::from_raw_encoding(raw, 4);
+
+        let vectors = implicant.sample_vectors(16, 7);
+        assert_eq!(vectors[0], 0b1000);
+        assert_eq!(vectors[1], 0b1101);
+        assert!(vectors.len() <= 4); // the cube has only four points
+        assert!(vectors.iter().all(|&v| v & !0b0101 == 0b1000));
+
+        // Same seed, same vectors
+        assert_eq!(vectors, implicant.sample_vectors(16, 7));
+
+        // A single minterm has a single vector
+        let minterm = Implicant::<Enc32>::from_minterm(0b0110, 4);
+        assert_eq!(minterm.sample_vectors(3, 0), vec![0b0110]);
+    }
+
     #[test]
     fn replace_complements() {
         {
@@ -160,4 +404,126 @@ mod tests {
             assert_eq!(Implicant::<Enc32>::replace_complements(a, b, 8), c);
         }
     }
+
+    #[test]
+    fn pattern_round_trip() {
+        // 1X0X over 4 variables, MSB first: D = 1, C free, B = 0, A free
+        let implicant = Implicant::<Enc32>::from_pattern("1X0-").unwrap();
+        assert_eq!(
+            implicant,
+            Implicant::<Enc32>::from_raw_encoding(0b0101_1101, 4).with_points()
+        );
+        assert_eq!(implicant.to_pattern(), "1X0X");
+        assert_eq!(implicant.covered_minterms.len(), 4);
+
+        // Same order as the classic string form
+        let minterm = Implicant::<Enc16>::from_pattern("0110").unwrap();
+        assert_eq!(minterm, Implicant::<Enc16>::from_minterm(0b0110, 4));
+        assert_eq!(minterm.to_pattern(), minterm_to_string::<Enc16>(4, 0b0110));
+
+        assert_eq!(
+            Implicant::<Enc16>::from_pattern("01a"),
+            Err(PatternError::InvalidCharacter {
+                position: 2,
+                character: 'a'
+            })
+        );
+        assert_eq!(
+            Implicant::<Enc16>::from_pattern(&"0".repeat(17)),
+            Err(PatternError::TooManyVariables {
+                n_variables: 17,
+                max_vars: 16
+            })
+        );
+        assert_eq!(
+            Implicant::<Enc16>::from_pattern("").unwrap().to_pattern(),
+            ""
+        );
+
+        // All 64 variables free would be 2^64 points
+        assert_eq!(
+            Implicant::<Enc64>::from_pattern(&"X".repeat(64)),
+            Err(PatternError::TooManyDontCares {
+                n_dont_cares: 64,
+                max_dont_cares: MAX_PATTERN_DONT_CARES
+            })
+        );
+        let free = Implicant::<Enc16>::from_pattern(&"X".repeat(16)).unwrap();
+        assert_eq!(free.covered_minterms.len(), 1 << 16);
+    }
+
+    #[test]
+    fn display_and_debug_show_the_pattern() {
+        fn check<E: MintermEncoding>() {
+            let implicant = Implicant::<E>::from_pattern("1X0").unwrap();
+            assert_eq!(implicant.to_string(), "1X0");
+            assert_eq!(implicant.literal_count(), 2);
+            assert_eq!(
+                format!("{:?}", implicant),
+                "Implicant { pattern: 1X0, literals: 2 }"
+            );
+            assert_eq!(
+                format!("{:?}", vec![Implicant::<E>::from_minterm(E::Value::zero(), 2)]),
+                "[Implicant { pattern: 00, literals: 2 }]"
+            );
+
+            // All variables of the encoding, most significant first
+            let widest = format!("1{}X", "0".repeat(E::MAX_VARS - 2));
+            let implicant = Implicant::<E>::from_pattern(&widest).unwrap();
+            assert_eq!(implicant.to_string(), widest);
+            assert_eq!(implicant.literal_count(), E::MAX_VARS - 1);
+        }
+        check::<Enc16>();
+        check::<Enc32>();
+        check::<Enc64>();
+
+        assert_eq!(Implicant::<Enc16>::from_pattern("").unwrap().to_string(), "");
+    }
+
+    impl<E: MintermEncoding> Implicant<E> {
+        /// Fill `covered_minterms` with every point of the cube
+        fn with_points(mut self) -> Self {
+            self.covered_minterms = cube_points::<E>(self.to_cube()).into_iter().collect();
+            self
+        }
+    }
+
+    /// A random implicant over up to `max_vars` variables, with at most 8 don't-cares
+    fn random_implicant<E: MintermEncoding>(
+        max_vars: usize,
+    ) -> impl Strategy<Value = Implicant<E>> {
+        prop::collection::vec(0u8..3, 0..=max_vars).prop_map(|states| {
+            let n = states.len();
+            let mut n_free = 0;
+            let raw = states
+                .iter()
+                .enumerate()
+                .fold(E::Value::zero(), |raw, (i, &s)| match s {
+                    1 => raw.set_bit(i),
+                    2 if n_free < 8 => {
+                        n_free += 1;
+                        raw.set_bit(i).set_bit(i + n)
+                    }
+                    _ => raw,
+                });
+            Implicant::<E>::from_raw_encoding(raw, n).with_points()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_pattern_round_trip_enc16(implicant in random_implicant::<Enc16>(16)) {
+            prop_assert_eq!(Implicant::from_pattern(&implicant.to_pattern()), Ok(implicant));
+        }
+
+        #[test]
+        fn prop_pattern_round_trip_enc32(implicant in random_implicant::<Enc32>(32)) {
+            prop_assert_eq!(Implicant::from_pattern(&implicant.to_pattern()), Ok(implicant));
+        }
+
+        #[test]
+        fn prop_pattern_round_trip_enc64(implicant in random_implicant::<Enc64>(64)) {
+            prop_assert_eq!(Implicant::from_pattern(&implicant.to_pattern()), Ok(implicant));
+        }
+    }
 }
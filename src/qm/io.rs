@@ -0,0 +1,340 @@
+//! Truth tables in CSV form (`io` feature)
+//!
+//! A truth table has one column per input variable followed by one output column (or
+//! several, see [`parse_truth_table_csv_outputs`]), and a header row naming them. Input cells are `0` / `1`; output cells are `1`, `0`, or `X` /
+//! `-` for a don't-care. Input column `i` is bit `i` of the minterm, the same layout as
+//! [`TruthTable::to_csv`](crate::simplify::types::TruthTable::to_csv) writes. Input combinations
+//! without a row are don't-cares. Fields may be quoted (`"enable, active"`, `""` escapes a
+//! quote).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+
+use super::multi_output::{MultiOutputTable, OutputColumn};
+
+/// Maximum number of input columns (missing rows are enumerated, 2^20 combinations)
+pub const MAX_CSV_VARIABLES: usize = 20;
+
+/// Errors that can occur while reading a CSV truth table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    /// The input could not be read
+    Io(String),
+    /// No header row
+    Empty,
+    /// Malformed header (fewer than two columns, unterminated quote, ...)
+    Header(String),
+    /// Malformed data row at `line` (1-based)
+    Row { line: usize, message: String },
+    /// More input columns than supported
+    TooManyVariables { n_variables: usize, max_vars: usize },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(message) => write!(f, "cannot read CSV: {}", message),
+            CsvError::Empty => write!(f, "CSV has no header row"),
+            CsvError::Header(message) => write!(f, "CSV header: {}", message),
+            CsvError::Row { line, message } => write!(f, "CSV line {}: {}", line, message),
+            CsvError::TooManyVariables {
+                n_variables,
+                max_vars,
+            } => write!(
+                f,
+                "CSV has {} input columns; maximum supported is {}",
+                n_variables, max_vars
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// A single-output function read from a CSV truth table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvTruthTable {
+    /// Input column names (variable `i` is bit `i`)
+    pub variables: Vec<String>,
+    /// Name of the output column
+    pub output: String,
+    /// Rows with output `1` (sorted)
+    pub minterms: Vec<u64>,
+    /// Rows with output `X` / `-` and combinations without a row (sorted)
+    pub dont_cares: Vec<u64>,
+}
+
+/// Read a CSV truth table
+pub fn read_truth_table_csv<R: Read>(mut reader: R) -> Result<CsvTruthTable, CsvError> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|e| CsvError::Io(e.to_string()))?;
+    parse_truth_table_csv(&text)
+}
+
+/// Parse a CSV truth table from a string
+pub fn parse_truth_table_csv(text: &str) -> Result<CsvTruthTable, CsvError> {
+    let mut table = parse_truth_table_csv_outputs(text, 1)?;
+    let output = table.outputs.remove(0);
+    Ok(CsvTruthTable {
+        variables: table.variables,
+        output: output.name,
+        minterms: output.minterms,
+        dont_cares: output.dont_cares,
+    })
+}
+
+/// Parse a CSV truth table whose last `n_outputs` columns are outputs
+///
+/// Input combinations without a row are don't-cares of every output.
+pub fn parse_truth_table_csv_outputs(
+    text: &str,
+    n_outputs: usize,
+) -> Result<MultiOutputTable, CsvError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines.next().ok_or(CsvError::Empty)?;
+    let mut variables = split_fields(header).map_err(CsvError::Header)?;
+    if n_outputs == 0 || variables.len() <= n_outputs {
+        return Err(CsvError::Header(format!(
+            "expected at least one input column and {} output column{}",
+            n_outputs.max(1),
+            if n_outputs > 1 { "s" } else { "" }
+        )));
+    }
+    let output_names = variables.split_off(variables.len() - n_outputs);
+    if variables.len() > MAX_CSV_VARIABLES {
+        return Err(CsvError::TooManyVariables {
+            n_variables: variables.len(),
+            max_vars: MAX_CSV_VARIABLES,
+        });
+    }
+
+    // minterm -> (outputs, line they were defined on)
+    let mut rows: BTreeMap<u64, (Vec<Option<bool>>, usize)> = BTreeMap::new();
+    for (line, text) in lines {
+        let row_error = |message: String| CsvError::Row { line, message };
+        let fields = split_fields(text).map_err(row_error)?;
+        if fields.len() != variables.len() + n_outputs {
+            return Err(row_error(format!(
+                "expected {} fields, found {}",
+                variables.len() + n_outputs,
+                fields.len()
+            )));
+        }
+
+        let mut minterm = 0u64;
+        for (i, cell) in fields[..variables.len()].iter().enumerate() {
+            match cell.as_str() {
+                "1" => minterm |= 1 << i,
+                "0" => {}
+                other => {
+                    return Err(row_error(format!(
+                        "invalid value '{}' for input '{}'",
+                        other, variables[i]
+                    )));
+                }
+            }
+        }
+        let values = fields[variables.len()..]
+            .iter()
+            .map(|cell| match cell.as_str() {
+                "1" => Ok(Some(true)),
+                "0" => Ok(Some(false)),
+                "X" | "x" | "-" => Ok(None),
+                other => Err(row_error(format!("invalid output value '{}'", other))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some((previous, previous_line)) = rows.get(&minterm) {
+            if *previous != values {
+                return Err(row_error(format!(
+                    "conflicts with line {} for the same inputs",
+                    previous_line
+                )));
+            }
+            continue;
+        }
+        rows.insert(minterm, (values, line));
+    }
+
+    let outputs = output_names
+        .into_iter()
+        .enumerate()
+        .map(|(k, name)| OutputColumn {
+            name,
+            minterms: rows
+                .iter()
+                .filter(|(_, (values, _))| values[k] == Some(true))
+                .map(|(&m, _)| m)
+                .collect(),
+            dont_cares: (0..1u64 << variables.len())
+                .filter(|m| rows.get(m).is_none_or(|(values, _)| values[k].is_none()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(MultiOutputTable { variables, outputs })
+}
+
+/// Write a cover as CSV: one row per implicant, `0` / `1` / `-` per variable
+///
+/// `cover` holds `(values, don't-care mask)` pairs as in
+/// [`QMResult::cover`](crate::qm::QMResult::cover).
+pub fn write_cover_csv(variables: &[String], cover: &[(u64, u64)]) -> String {
+    let mut csv = variables
+        .iter()
+        .map(|name| quote_field(name))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+    for &(values, dc) in cover {
+        let cells: Vec<&str> = (0..variables.len())
+            .map(|i| match ((dc >> i) & 1, (values >> i) & 1) {
+                (1, _) => "-",
+                (_, 1) => "1",
+                _ => "0",
+            })
+            .collect();
+        csv.push_str(&cells.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Split one CSV line into trimmed fields, honouring double quotes
+fn split_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+fn quote_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_truth_table() {
+        let csv =
+            "a,b,c,out\n0,0,0,1\n1,0,0,1\n0,1,0,0\n1,1,0,X\n0,0,1,1\n1,0,1,0\n0,1,1,0\n1,1,1,-\n";
+        let table = parse_truth_table_csv(csv).unwrap();
+        assert_eq!(table.variables, vec!["a", "b", "c"]);
+        assert_eq!(table.output, "out");
+        // Column i is bit i: "1,0,0" = 1, "0,0,1" = 4
+        assert_eq!(table.minterms, vec![0, 1, 4]);
+        assert_eq!(table.dont_cares, vec![3, 7]);
+    }
+
+    #[test]
+    fn test_quoted_header_and_missing_rows() {
+        let csv = "\"enable, active\",\"say \"\"hi\"\"\",result\r\n1,1,1\r\n0,1,0\r\n";
+        let table = parse_truth_table_csv(csv).unwrap();
+        assert_eq!(table.variables, vec!["enable, active", "say \"hi\""]);
+        assert_eq!(table.minterms, vec![3]);
+        // Rows 0 and 1 are not listed
+        assert_eq!(table.dont_cares, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_malformed_files() {
+        assert_eq!(parse_truth_table_csv(""), Err(CsvError::Empty));
+        assert!(matches!(
+            parse_truth_table_csv("out\n1\n"),
+            Err(CsvError::Header(_))
+        ));
+        assert!(matches!(
+            parse_truth_table_csv("\"a,b,out\n"),
+            Err(CsvError::Header(_))
+        ));
+        assert!(matches!(
+            parse_truth_table_csv("a,b,out\n0,1\n"),
+            Err(CsvError::Row { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_truth_table_csv("a,b,out\n0,1,1\n0,2,1\n"),
+            Err(CsvError::Row { line: 3, .. })
+        ));
+        assert!(matches!(
+            parse_truth_table_csv("a,b,out\n0,1,yes\n"),
+            Err(CsvError::Row { line: 2, .. })
+        ));
+        // The same inputs twice with different outputs
+        assert!(matches!(
+            parse_truth_table_csv("a,b,out\n0,1,1\n1,1,0\n0,1,0\n"),
+            Err(CsvError::Row { line: 4, .. })
+        ));
+        let wide: Vec<String> = (0..21).map(|i| format!("x{}", i)).collect();
+        assert!(matches!(
+            parse_truth_table_csv(&format!("{},out\n", wide.join(","))),
+            Err(CsvError::TooManyVariables {
+                n_variables: 21,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_outputs() {
+        // sum and carry of a half adder, and a don't-care row for the carry
+        let csv = "a,b,sum,carry\n0,0,0,0\n1,0,1,0\n0,1,1,-\n1,1,0,1\n";
+        let table = parse_truth_table_csv_outputs(csv, 2).unwrap();
+        assert_eq!(table.variables, vec!["a", "b"]);
+        assert_eq!(table.output_names(), vec!["sum", "carry"]);
+        assert_eq!(table.outputs[0].minterms, vec![1, 2]);
+        assert!(table.outputs[0].dont_cares.is_empty());
+        assert_eq!(table.outputs[1].minterms, vec![3]);
+        assert_eq!(table.outputs[1].dont_cares, vec![2]);
+
+        assert!(matches!(
+            parse_truth_table_csv_outputs("a,b\n", 2),
+            Err(CsvError::Header(_))
+        ));
+        assert!(matches!(
+            parse_truth_table_csv_outputs("a,f,g\n0,1\n", 2),
+            Err(CsvError::Row { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_cover_csv() {
+        let names = vec!["a".to_string(), "b, c".to_string()];
+        let csv = write_cover_csv(&names, &[(0b01, 0b10), (0b10, 0b00)]);
+        assert_eq!(csv, "a,\"b, c\"\n1,-\n0,1\n");
+    }
+}
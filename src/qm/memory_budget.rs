@@ -0,0 +1,96 @@
+//! Memory budget of a solve
+//!
+//! The solvers report the size of their working set at each phase boundary; the budget
+//! keeps the peak and fails with [`SolveError::ResourceLimit`] once a cap is exceeded.
+
+use std::fmt;
+
+/// Why [`QMSolver::try_solve`](super::QMSolver::try_solve) gave up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The memory tracked at the end of `phase` exceeds the cap of
+    /// [`QMSolver::set_max_memory`](super::QMSolver::set_max_memory)
+    ResourceLimit {
+        phase: String,
+        tracked_bytes: usize,
+        limit_bytes: usize,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::ResourceLimit {
+                phase,
+                tracked_bytes,
+                limit_bytes,
+            } => write!(
+                f,
+                "memory limit of {} bytes exceeded in phase '{}' ({} bytes tracked)",
+                limit_bytes, phase, tracked_bytes
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Working-set sizes reported at phase boundaries, checked against an optional cap
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MemoryBudget {
+    limit: Option<usize>,
+    peak: usize,
+}
+
+impl MemoryBudget {
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        Self { limit, peak: 0 }
+    }
+
+    pub(crate) fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub(crate) fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Record `bytes` in use at the end of `phase`
+    pub(crate) fn track(&mut self, phase: &str, bytes: usize) -> Result<(), SolveError> {
+        self.peak = self.peak.max(bytes);
+        match self.limit {
+            Some(limit) if bytes > limit => Err(SolveError::ResourceLimit {
+                phase: phase.to_string(),
+                tracked_bytes: bytes,
+                limit_bytes: limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_keeps_peak_and_reports_the_phase() {
+        let mut memory = MemoryBudget::new(Some(100));
+        assert_eq!(memory.track("input", 40), Ok(()));
+        assert_eq!(memory.track("primes", 90), Ok(()));
+        assert_eq!(memory.track("cover", 10), Ok(()));
+        assert_eq!(memory.peak(), 90);
+        assert_eq!(
+            memory.track("coverage matrix", 120),
+            Err(SolveError::ResourceLimit {
+                phase: "coverage matrix".to_string(),
+                tracked_bytes: 120,
+                limit_bytes: 100,
+            })
+        );
+        assert_eq!(memory.peak(), 120);
+
+        let mut unlimited = MemoryBudget::new(None);
+        assert_eq!(unlimited.track("input", usize::MAX), Ok(()));
+    }
+}
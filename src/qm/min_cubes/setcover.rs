@@ -15,6 +15,7 @@
 //! skeletonized but not yet wired into the hot loop.
 
 use super::primes::PrimeCube;
+use crate::qm::quine_mccluskey::start_timer;
 use std::time::Instant;
 
 // ============================================================================
@@ -37,6 +38,11 @@ pub struct SetCoverSolution {
     pub time_ns: u128,
 }
 
+/// Nanoseconds since `start`; 0 without a clock
+fn elapsed_ns(start: Option<Instant>) -> u128 {
+    start.map_or(0, |start| start.elapsed().as_nanos())
+}
+
 /// Trait for set cover solver implementations.
 ///
 /// A set cover solver takes a list of prime implicants and their coverage
@@ -92,7 +98,7 @@ impl SetCoverSolver for BnBSolver {
     }
 
     fn solve(&self, pis: &[PrimeCube], minterms: &[u64]) -> SetCoverSolution {
-        let start = Instant::now();
+        let start = start_timer();
 
         let n = minterms.len();
         if n == 0 || pis.is_empty() || n > 64 {
@@ -100,7 +106,7 @@ impl SetCoverSolver for BnBSolver {
                 num_selected: 0,
                 selected_indices: Vec::new(),
                 solver_name: self.name().to_string(),
-                time_ns: start.elapsed().as_nanos(),
+                time_ns: elapsed_ns(start),
             };
         }
 
@@ -132,7 +138,7 @@ impl SetCoverSolver for BnBSolver {
             num_selected: best_solution.len(),
             selected_indices: best_solution,
             solver_name: self.name().to_string(),
-            time_ns: start.elapsed().as_nanos(),
+            time_ns: elapsed_ns(start),
         }
     }
 
@@ -351,7 +357,7 @@ impl SetCoverSolver for LagrangianSolver {
     }
 
     fn solve(&self, pis: &[PrimeCube], minterms: &[u64]) -> SetCoverSolution {
-        let start = Instant::now();
+        let start = start_timer();
         let n_mts = minterms.len();
         let n_pis = pis.len();
 
@@ -360,7 +366,7 @@ impl SetCoverSolver for LagrangianSolver {
                 num_selected: 0,
                 selected_indices: Vec::new(),
                 solver_name: self.name().to_string(),
-                time_ns: start.elapsed().as_nanos(),
+                time_ns: elapsed_ns(start),
             };
         }
 
@@ -459,7 +465,7 @@ impl SetCoverSolver for LagrangianSolver {
             num_selected: indices.len(),
             selected_indices: indices,
             solver_name: self.name().to_string(),
-            time_ns: start.elapsed().as_nanos(),
+            time_ns: elapsed_ns(start),
         }
     }
 
@@ -506,14 +512,14 @@ impl SetCoverSolver for SCPSolver {
     }
 
     fn solve(&self, pis: &[PrimeCube], minterms: &[u64]) -> SetCoverSolution {
-        let start = Instant::now();
+        let start = start_timer();
 
         if minterms.is_empty() || pis.is_empty() {
             return SetCoverSolution {
                 num_selected: 0,
                 selected_indices: Vec::new(),
                 solver_name: self.name().to_string(),
-                time_ns: start.elapsed().as_nanos(),
+                time_ns: elapsed_ns(start),
             };
         }
 
@@ -549,7 +555,7 @@ impl SetCoverSolver for SCPSolver {
             num_selected: best_solution.len(),
             selected_indices: best_solution,
             solver_name: self.name().to_string(),
-            time_ns: start.elapsed().as_nanos(),
+            time_ns: elapsed_ns(start),
         }
     }
 
@@ -577,9 +583,9 @@ impl SCPSolver {
         covered: u64,
         best_size: &mut usize,
         best_sol: &mut Vec<usize>,
-        start_time: Instant,
+        start_time: Option<Instant>,
     ) {
-        if start_time.elapsed().as_nanos() > self.timeout_ns {
+        if elapsed_ns(start_time) > self.timeout_ns {
             return;
         }
 
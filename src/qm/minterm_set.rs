@@ -55,6 +55,52 @@ impl<E: MintermEncoding> MintermSet<E> {
     pub fn get_max_bit_count(&self) -> usize {
         self.max_bit_count
     }
+
+    /// Non-empty buckets as `(bit_count, len)`, in ascending bit count
+    pub fn histogram(&self) -> Vec<(usize, usize)> {
+        self.data[..=self.max_bit_count]
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| !bucket.is_empty())
+            .map(|(bit_count, bucket)| (bit_count, bucket.len()))
+            .collect()
+    }
+
+    /// The largest bucket as `(bit_count, len)` (lowest bit count on ties); `None` when empty
+    pub fn densest_bucket(&self) -> Option<(usize, usize)> {
+        self.histogram()
+            .into_iter()
+            .rev()
+            .max_by_key(|&(_, len)| len)
+    }
+
+    /// Number of candidate pairs one reduction pass compares: the sum of
+    /// `len(k) * len(k + 1)` over adjacent buckets
+    pub fn estimated_pair_comparisons(&self) -> u64 {
+        adjacent_products(self.data[..=self.max_bit_count].iter().map(Vec::len))
+    }
+
+    /// [`estimated_pair_comparisons`](Self::estimated_pair_comparisons) of the set holding
+    /// `values`, from the bucket sizes alone: the values are not copied
+    pub fn estimated_pair_comparisons_of(values: &[E::Value]) -> u64 {
+        let mut lens = vec![0usize; E::BUCKET_WIDTH];
+        for value in values {
+            lens[value.count_ones() as usize] += 1;
+        }
+        adjacent_products(lens)
+    }
+}
+
+/// Sum of `len(k) * len(k + 1)` over the bucket sizes in ascending bit count
+fn adjacent_products(lens: impl IntoIterator<Item = usize>) -> u64 {
+    let mut previous = 0u64;
+    let mut sum = 0;
+    for len in lens {
+        let len = len as u64;
+        sum += previous * len;
+        previous = len;
+    }
+    sum
 }
 
 impl<E: MintermEncoding> Default for MintermSet<E> {
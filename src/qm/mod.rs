@@ -10,17 +10,33 @@
 //! - [`implicant`] - BitState enum and Implicant struct
 //! - [`quine_mccluskey`] - QuineMcCluskey algorithm implementation
 //! - [`petricks_method`] - Petrick's method for minimal cover selection
+//! - [`memory_budget`] - Memory cap of a solve and the SolveError it fails with
 //!
 //! **High-Level Interface:**
 //! - [`qm_solver`] - QMSolver orchestration
+//! - [`qm_solver_builder`] - QMSolverBuilder, validated solver configuration
 //! - [`qm_result`] - QMResult output type
+//! - [`schema`] - JSON Schema of the serialized QMResult
+//! - [`expression`] - Parsing sum-of-products expressions like `A'B + AC`
+//! - [`factor`] - Algebraic factoring of the minimized expression into a multi-level form
+//! - [`codegen`] - The minimized function as Rust source
+//! - [`equivalence`] - Exhaustive equivalence check between two expressions
+//! - [`diff`] - Term-by-term and functional comparison of two results
+//! - [`symmetry`] - Detection of interchangeable variables
+//! - [`parse`] - Parsing minimize requests (JSON, Σ notation, truth tables, ...)
+//! - [`pla`] - Reading Berkeley PLA files
+//! - [`multi_output`] - Minimizing the outputs of a multi-output truth table with shared inputs
+//! - [`examples`] - Usage examples and interactive help of the CLI
+//! - `io` - Reading CSV truth tables and writing covers (`io` feature)
 //!
 //! **Encoding and Data Structures:**
 //! - [`encoding`] - BitOps trait, MintermEncoding trait, Encoding16/32/64
 //! - [`minterm_set`] - MintermSet data structure
+//! - [`cubes`] - Cube intersection, containment, sharp, complement, consensus and relations
 //!
 //! **Testing and Utilities:**
 //! - [`random`] - Random minterm generation for testing and benchmarking
+//! - [`render`] - Grouping tables, combining tables and PI charts for step-by-step output
 //!
 //! **C++ Compatibility:**
 //! - [`classic`] - C++ API-compatible functions and utilities
@@ -28,20 +44,37 @@
 // Core algorithm modules
 pub mod gray_code;
 pub mod implicant;
+pub mod memory_budget;
 pub mod petricks_method;
 pub mod quine_mccluskey;
 pub mod simd_coverage;
 
 // High-level interface
+pub mod codegen;
+pub mod diff;
+pub mod equivalence;
+pub mod examples;
+pub mod expression;
+pub mod factor;
+#[cfg(feature = "io")]
+pub mod io;
+pub mod multi_output;
+pub mod parse;
+pub mod pla;
 pub mod qm_result;
 pub mod qm_solver;
+pub mod qm_solver_builder;
+pub mod schema;
+pub mod symmetry;
 
 // Encoding and data structures
+pub mod cubes;
 pub mod encoding;
 pub mod minterm_set;
 
 // Testing and utilities
 pub mod random;
+pub mod render;
 
 // C++ compatibility and utilities
 pub mod classic;
@@ -56,19 +89,33 @@ pub use min_cubes::setcover::{
 };
 
 // Re-export main types for convenience
-pub use implicant::{BitState, Implicant};
+pub use diff::{CoverDiff, DiffError, diff_covers};
+pub use equivalence::{Counterexample, EquivalenceReport, expressions_equivalent};
+pub use expression::{ExprError, SopExpression, parse_sop};
+pub use factor::{FactorStyle, FactoredExpression, factor_sop};
+pub use implicant::{BitState, Implicant, MAX_PATTERN_DONT_CARES, PatternError};
+pub use memory_budget::SolveError;
 pub use petricks_method::PetricksMethod;
-pub use qm_result::QMResult;
-pub use qm_solver::SolveMethod;
+pub use multi_output::{
+    MultiOutputTable, OutputColumn, SharedTerm, minimize_truth_table_multi, shared_terms,
+};
+pub use pla::{Pla, PlaError, PlaOutputs, parse_pla, parse_pla_outputs};
+pub use qm_result::{BestForm, BestFormResult, BitVec, BoundedResult, QMResult, TestVector};
+pub use qm_solver::{
+    CoverStrategy, Objective, Polarity, SolveMethod, TieBreak, VariableNameIssue,
+    VariableNamesError, normalize_variable_names,
+};
 pub use qm_solver::QMSolver;
-pub use quine_mccluskey::QuineMcCluskey;
+pub use qm_solver_builder::{BuildError, QMSolverBuilder};
+pub use quine_mccluskey::{PrimeImplicantsIter, QuineMcCluskey, TracedImplicant};
 pub use simd_coverage::CoverageMatrix;
 
 // Re-export encoding types
-pub use encoding::{BitOps, Enc16, Enc32, Enc64, MintermEncoding};
+pub use encoding::{BitOps, Enc16, Enc32, Enc64, EncodingError, MintermEncoding};
 pub use minterm_set::MintermSet;
 
 // Re-export classic algorithm functions for backward compatibility
 pub use classic::{
-    reduce_minterms, reduce_minterms_classic, reduce_minterms_with_early_pruning, reduce_qm,
+    AUTO_CLASSIC_MAX_PAIRS, ReduceOptions, ReducerChoice, reduce_minterms,
+    reduce_minterms_classic, reduce_minterms_with_early_pruning, reduce_qm,
 };
@@ -0,0 +1,246 @@
+//! Functions with several outputs over shared inputs
+//!
+//! A [`MultiOutputTable`] holds the on-set and don't-care set of every output over one set of
+//! named inputs, read from a multi-output PLA ([`MultiOutputTable::from_pla`]) or a CSV truth
+//! table with several output columns (`io` feature). [`minimize_truth_table_multi`]
+//! minimizes every output with the same variable names, and [`shared_terms`] lists the
+//! product terms that more than one of the resulting covers use.
+//!
+//! The outputs are minimized one at a time: a term is shared when the minimum covers of
+//! several outputs happen to contain it, not because sharing it would save a gate.
+
+use super::encoding::{Enc32, MintermEncoding};
+use super::pla::PlaOutputs;
+use super::qm_result::QMResult;
+use super::qm_solver::QMSolver;
+
+/// Most inputs of a [`MultiOutputTable`] that can be minimized
+pub const MAX_MULTI_OUTPUT_VARIABLES: usize = Enc32::MAX_VARS;
+
+/// One output of a [`MultiOutputTable`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputColumn {
+    pub name: String,
+    /// Input combinations with output `1` (sorted)
+    pub minterms: Vec<u64>,
+    /// Input combinations where the output does not matter (sorted)
+    pub dont_cares: Vec<u64>,
+}
+
+/// Several outputs over the same inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiOutputTable {
+    /// Input names (variable `i` is bit `i`)
+    pub variables: Vec<String>,
+    pub outputs: Vec<OutputColumn>,
+}
+
+impl MultiOutputTable {
+    /// The outputs of a PLA; inputs without `.ilb` are named A, B, C, ..., outputs without
+    /// `.ob` f0, f1, ...
+    pub fn from_pla<E: MintermEncoding>(pla: &PlaOutputs<E>) -> Self {
+        let n_inputs = pla.outputs.first().map_or(0, |output| output.n_inputs);
+        let variables = pla
+            .outputs
+            .first()
+            .and_then(|output| output.input_labels.clone())
+            .unwrap_or_else(|| crate::generate_variable_names(n_inputs));
+        let outputs = pla
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| OutputColumn {
+                name: pla
+                    .output_labels
+                    .as_ref()
+                    .map_or_else(|| format!("f{}", i), |labels| labels[i].clone()),
+                minterms: output.minterms(),
+                dont_cares: output.dont_cares(),
+            })
+            .collect();
+        Self { variables, outputs }
+    }
+
+    /// Only the outputs named in `names`, in that order
+    pub fn select(&self, names: &[String]) -> Result<Self, String> {
+        let outputs = names
+            .iter()
+            .map(|name| {
+                self.outputs
+                    .iter()
+                    .find(|output| output.name == *name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        format!(
+                            "no output '{}'; the outputs are {}",
+                            name,
+                            self.output_names().join(", ")
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            variables: self.variables.clone(),
+            outputs,
+        })
+    }
+
+    pub fn output_names(&self) -> Vec<&str> {
+        self.outputs
+            .iter()
+            .map(|output| output.name.as_str())
+            .collect()
+    }
+}
+
+/// A product term used by the covers of several outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedTerm {
+    /// `(values, don't-care mask)` as in [`QMResult::cover`], free variables cleared
+    pub term: (u64, u64),
+    /// The term as a product of literals, e.g. `AB'`
+    pub expression: String,
+    /// Names of the outputs whose cover contains the term, in table order
+    pub outputs: Vec<String>,
+}
+
+/// Minimize every output of `table`, with the table's variable names
+///
+/// The results are in the order of `table.outputs`.
+///
+/// # Panics
+/// When the table has more than [`MAX_MULTI_OUTPUT_VARIABLES`] inputs.
+pub fn minimize_truth_table_multi(table: &MultiOutputTable) -> Vec<QMResult> {
+    let n_variables = table.variables.len();
+    assert!(
+        n_variables <= MAX_MULTI_OUTPUT_VARIABLES,
+        "{} inputs; at most {} are supported",
+        n_variables,
+        MAX_MULTI_OUTPUT_VARIABLES
+    );
+    table
+        .outputs
+        .iter()
+        .map(|output| {
+            let mut solver =
+                QMSolver::<Enc32>::new_with_variable_names(n_variables, table.variables.clone());
+            solver.set_minterms(output.minterms.clone());
+            solver.set_dont_cares(output.dont_cares.clone());
+            solver.solve()
+        })
+        .collect()
+}
+
+/// Terms in the covers of more than one output, in order of first use
+///
+/// `results` are those of [`minimize_truth_table_multi`] for `table`.
+pub fn shared_terms(table: &MultiOutputTable, results: &[QMResult]) -> Vec<SharedTerm> {
+    let mut terms: Vec<SharedTerm> = Vec::new();
+    for (output, result) in table.outputs.iter().zip(results) {
+        for &(values, dc_mask) in &result.cover {
+            let term = (values & !dc_mask, dc_mask);
+            match terms.iter_mut().find(|shared| shared.term == term) {
+                Some(shared) if shared.outputs.last() != Some(&output.name) => {
+                    shared.outputs.push(output.name.clone())
+                }
+                Some(_) => {}
+                None => terms.push(SharedTerm {
+                    term,
+                    expression: term_expression(term, &table.variables),
+                    outputs: vec![output.name.clone()],
+                }),
+            }
+        }
+    }
+    terms.retain(|shared| shared.outputs.len() > 1);
+    terms
+}
+
+/// `term` as a product of literals in variable order, `1` without literals
+fn term_expression((values, dc_mask): (u64, u64), variables: &[String]) -> String {
+    let literals: String = variables
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| (dc_mask >> i) & 1 == 0)
+        .map(|(i, name)| {
+            if (values >> i) & 1 == 1 {
+                name.clone()
+            } else {
+                format!("{}'", name)
+            }
+        })
+        .collect();
+    if literals.is_empty() {
+        "1".to_string()
+    } else {
+        literals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::pla::parse_pla_outputs;
+
+    /// f0 = AB + C, f1 = AB + A'C' over A, B, C (column `i` is variable `i`)
+    const SHARED_AB: &str = "\
+.i 3
+.o 2
+.ilb A B C
+.ob f0 f1
+11- 11
+--1 10
+0-0 01
+.e
+";
+
+    fn table() -> MultiOutputTable {
+        MultiOutputTable::from_pla(&parse_pla_outputs::<Enc32>(SHARED_AB).unwrap())
+    }
+
+    #[test]
+    fn test_from_pla() {
+        let table = table();
+        assert_eq!(table.variables, vec!["A", "B", "C"]);
+        assert_eq!(table.output_names(), vec!["f0", "f1"]);
+        assert_eq!(table.outputs[0].minterms, vec![3, 4, 5, 6, 7]);
+        assert_eq!(table.outputs[1].minterms, vec![0, 2, 3, 7]);
+
+        // Without .ilb and .ob
+        let unnamed = parse_pla_outputs::<Enc32>(".i 2\n.o 2\n1- 10\n").unwrap();
+        let unnamed = MultiOutputTable::from_pla(&unnamed);
+        assert_eq!(unnamed.variables, vec!["A", "B"]);
+        assert_eq!(unnamed.output_names(), vec!["f0", "f1"]);
+        assert!(unnamed.outputs[1].minterms.is_empty());
+    }
+
+    #[test]
+    fn test_minimize_shares_the_common_term() {
+        let table = table();
+        let results = minimize_truth_table_multi(&table);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].cover.len(), 2);
+        assert_eq!(results[1].cover.len(), 2);
+        assert_eq!(results[1].minterms, vec![0, 2, 3, 7]);
+
+        let shared = shared_terms(&table, &results);
+        assert_eq!(
+            shared,
+            vec![SharedTerm {
+                term: (0b011, 0b100),
+                expression: "AB".to_string(),
+                outputs: vec!["f0".to_string(), "f1".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let table = table();
+        let f1 = table.select(&["f1".to_string()]).unwrap();
+        assert_eq!(f1.output_names(), vec!["f1"]);
+        assert_eq!(f1.variables, table.variables);
+        assert!(shared_terms(&f1, &minimize_truth_table_multi(&f1)).is_empty());
+        assert!(table.select(&["carry".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,889 @@
+//! Parsing `minimize` requests in the formats accepted by the `qm-agent` CLI
+//!
+//! [`parse_request`] tries, in order: a file path (JSON or PLA), inline PLA, inline JSON,
+//! function notation `f(A,B,C) = Σ(1,3,7) + d(2,4)` (also `sum(...)`, `dc(...)`, ..., or
+//! `Π(...)` for maxterms), the sentence `minimize minterms 1,3,7 with don't cares 2,4 and 3
+//! variables`, a truth table `truth table: 00110110`, and a C- or Python-style Boolean
+//! expression.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Enc32;
+use super::examples::EXAMPLES;
+use super::multi_output::MultiOutputTable;
+use super::pla::{Pla, PlaError, is_pla, parse_pla, parse_pla_outputs};
+use crate::simplify::BoolExpr;
+use crate::simplify::analyzer::{evaluate, extract_variables};
+use crate::simplify::parse_bool_expr;
+
+/// Most variables of a function given as an expression or as maxterms, which are expanded
+/// into all input combinations
+pub const MAX_EXPANDED_VARIABLES: usize = 16;
+
+/// A function to minimize, over at most 32 variables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedRequest {
+    pub minterms: Vec<u32>,
+    pub dont_cares: Option<Vec<u32>>,
+    pub variables: usize,
+    /// A, B, C, ... when not given
+    pub variable_names: Option<Vec<String>>,
+    pub format: Option<String>,
+    /// Input cover, when read from a PLA file
+    #[serde(skip)]
+    pub pla: Option<Pla<Enc32>>,
+    /// How the input was read where it was not explicit, e.g. an inferred variable count
+    #[serde(skip)]
+    pub notes: Vec<String>,
+}
+
+impl ParsedRequest {
+    fn new(minterms: Vec<u32>, variables: usize) -> Self {
+        Self {
+            minterms,
+            dont_cares: None,
+            variables,
+            variable_names: None,
+            format: None,
+            pla: None,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Every minterm and don't-care must be an assignment of `self.variables` inputs
+    pub fn check_minterm_range(&self) -> Result<(), ParseError> {
+        let limit = 1u64 << self.variables.min(63);
+        let dont_cares = self.dont_cares.iter().flatten();
+        match self
+            .minterms
+            .iter()
+            .chain(dont_cares)
+            .find(|&&m| u64::from(m) >= limit)
+        {
+            Some(&minterm) => Err(ParseError::MintermOutOfRange {
+                minterm,
+                variables: self.variables,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why an input is not a valid request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input matches none of the accepted formats
+    UnknownFormat,
+    /// An entry of a minterm (`dont_care: false`) or don't-care list is not a 32-bit number
+    InvalidMinterm {
+        dont_care: bool,
+        message: String,
+    },
+    /// The variable count of the sentence form is not a number
+    InvalidVariableCount(String),
+    /// The sentence form has the variable count or the don't-cares twice
+    RepeatedClause(&'static str),
+    /// A truth table whose length is not a power of two
+    TruthTableLength(usize),
+    /// An expression or maxterm list over more than [`MAX_EXPANDED_VARIABLES`] variables
+    TooManyVariables(usize),
+    /// An expression with integer comparisons, which `simplify` handles
+    Comparisons,
+    /// Function notation with two term lists (`Σ(1) + sum(3)`, `Σ(1) + Π(2)`) or two
+    /// don't-care lists, in the spellings of the first and second one
+    MixedNotation {
+        first: String,
+        second: String,
+    },
+    MintermOutOfRange {
+        minterm: u32,
+        variables: usize,
+    },
+    Pla(PlaError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownFormat => {
+                write!(f, "Could not parse input format. Supported formats:")?;
+                for example in EXAMPLES {
+                    write!(f, "\n- {}: {}", example.name, example.input)?;
+                }
+                Ok(())
+            }
+            ParseError::InvalidMinterm { dont_care, message } => write!(
+                f,
+                "Failed to parse {}: {}",
+                if *dont_care {
+                    "don't care term"
+                } else {
+                    "minterm"
+                },
+                message
+            ),
+            ParseError::InvalidVariableCount(message) => {
+                write!(f, "Invalid number of variables: {}", message)
+            }
+            ParseError::RepeatedClause(clause) => write!(f, "The {} are given twice", clause),
+            ParseError::TruthTableLength(rows) => write!(
+                f,
+                "Truth table has {} rows; expected a power of two (1, 2, 4, 8, ...)",
+                rows
+            ),
+            ParseError::TooManyVariables(variables) => write!(
+                f,
+                "Truth table for {} variables is too large (maximum {})",
+                variables, MAX_EXPANDED_VARIABLES
+            ),
+            ParseError::Comparisons => write!(
+                f,
+                "Integer comparisons are not supported by minimize; use the simplify command"
+            ),
+            ParseError::MixedNotation { first, second } => write!(
+                f,
+                "Function notation has both '{}(...)' and '{}(...)'; use one minterm (or \
+                 maxterm) list and at most one don't-care list, in one spelling each, \
+                 e.g. f(A,B,C) = Σ(1,3,7) + d(2,4) or f(a,b,c) = sum(1,3,7) + dc(2,4)",
+                first, second
+            ),
+            ParseError::MintermOutOfRange { minterm, variables } => write!(
+                f,
+                "Minterm {} ({:#x}) is out of range for {} variables (maximum {})",
+                minterm,
+                minterm,
+                variables,
+                (1u64 << (*variables).min(63)) - 1
+            ),
+            ParseError::Pla(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<PlaError> for ParseError {
+    fn from(e: PlaError) -> Self {
+        ParseError::Pla(e)
+    }
+}
+
+/// Parse a request, reading `input` as a file when it names one
+///
+/// The minterms are checked against the variable count.
+pub fn parse_request(input: &str) -> Result<ParsedRequest, ParseError> {
+    parse_request_with(input, &|path| std::fs::read_to_string(path).ok())
+}
+
+/// [`parse_request`] with `read_file` returning the contents of a path, `None` if there
+/// is no such file
+pub fn parse_request_with(
+    input: &str,
+    read_file: &dyn Fn(&Path) -> Option<String>,
+) -> Result<ParsedRequest, ParseError> {
+    let request = parse_unchecked(input, read_file)?;
+    request.check_minterm_range()?;
+    Ok(request)
+}
+
+fn parse_unchecked(
+    input: &str,
+    read_file: &dyn Fn(&Path) -> Option<String>,
+) -> Result<ParsedRequest, ParseError> {
+    // A file path first (JSON or PLA); other contents leave the input to the inline forms
+    if let Some(file_content) = read_file(Path::new(input)) {
+        if let Ok(request) = serde_json::from_str::<ParsedRequest>(&file_content) {
+            return Ok(request);
+        }
+        if is_pla(&file_content) {
+            return parse_pla_request(&file_content);
+        }
+    }
+
+    // Inline (or piped) PLA
+    if is_pla(input) {
+        return parse_pla_request(input);
+    }
+
+    if let Ok(request) = serde_json::from_str::<ParsedRequest>(input) {
+        return Ok(request);
+    }
+
+    parse_natural(input)
+}
+
+/// The outputs of a PLA given as a file path or inline, `None` for any other input
+///
+/// Unlike [`parse_request`], this accepts PLAs with several outputs (`.o N`).
+pub fn parse_output_table(input: &str) -> Result<Option<MultiOutputTable>, ParseError> {
+    parse_output_table_with(input, &|path| std::fs::read_to_string(path).ok())
+}
+
+/// [`parse_output_table`] with `read_file` as in [`parse_request_with`]
+pub fn parse_output_table_with(
+    input: &str,
+    read_file: &dyn Fn(&Path) -> Option<String>,
+) -> Result<Option<MultiOutputTable>, ParseError> {
+    let text = match read_file(Path::new(input)) {
+        Some(file_content) if is_pla(&file_content) => file_content,
+        _ if is_pla(input) => input.to_string(),
+        _ => return Ok(None),
+    };
+    let pla = parse_pla_outputs::<Enc32>(&text)?;
+    Ok(Some(MultiOutputTable::from_pla(&pla)))
+}
+
+fn parse_pla_request(text: &str) -> Result<ParsedRequest, ParseError> {
+    let pla = parse_pla::<Enc32>(text)?;
+    let to_u32 = |minterms: Vec<u64>| minterms.into_iter().map(|m| m as u32).collect();
+    Ok(ParsedRequest {
+        minterms: to_u32(pla.minterms()),
+        dont_cares: Some(to_u32(pla.dont_cares())),
+        variables: pla.n_inputs,
+        variable_names: pla.input_labels.clone(),
+        format: None,
+        pla: Some(pla),
+        notes: Vec::new(),
+    })
+}
+
+/// `f(A,B,C) = ` and the lists of function notation after it
+static HEADER_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)[fF]\(([^()=]*)\)\s*=\s*(.*)$").expect("valid header pattern")
+});
+
+/// One list of function notation, in any of its spellings (case-insensitive): minterms
+/// `Σ(...)`, `Σm(...)`, `sum(...)`, `m(...)`, maxterms `Π(...)`, don't-cares `d(...)`,
+/// `dc(...)`, `X(...)`
+static LIST_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(Σm?|Π|sum|m|dc|d|x)\s*\(([^()]*)\)").expect("valid list pattern")
+});
+
+/// Comma-separated numbers; malformed ones such as `0b12` are rejected when parsed
+const SENTENCE_LIST: &str = r"[0-9][0-9a-zA-Z_]*(?:\s*,\s*[0-9][0-9a-zA-Z_]*)*";
+
+/// `minimize minterms 1,3,7` and the clauses after it
+static SENTENCE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)minimize\s+minterms?\s+({})(.*)$",
+        SENTENCE_LIST
+    ))
+    .expect("valid sentence pattern")
+});
+
+/// One clause of the sentence: `with 3 variables`, or don't-cares `and don't cares 2,4`
+/// (also `dont care`, `dc`, `x terms`)
+static SENTENCE_CLAUSE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^\s*,?\s*(?:(?:with|and)\s+)?(?:(\d+)\s+variables?|(?:don['’]?t[\s-]*cares?|dc|x[\s-]*terms?)\s+({}))",
+        SENTENCE_LIST
+    ))
+    .expect("valid sentence clause pattern")
+});
+
+/// `truth table: 00110110`
+static TRUTH_TABLE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"truth\s+table:\s*([01]+)").expect("valid truth table pattern"));
+
+fn parse_natural(input: &str) -> Result<ParsedRequest, ParseError> {
+    let input = input.trim();
+
+    if let Some(request) = parse_function_notation(input)? {
+        return Ok(request);
+    }
+
+    if let Some(request) = parse_sentence(input)? {
+        return Ok(request);
+    }
+
+    if let Some(caps) = TRUTH_TABLE_PATTERN.captures(input) {
+        let truth_table = &caps[1];
+        // One output per row: "1" is a constant over 0 variables, "01" is F = A
+        if !truth_table.len().is_power_of_two() {
+            return Err(ParseError::TruthTableLength(truth_table.len()));
+        }
+        let variables = truth_table.len().trailing_zeros() as usize;
+        let minterms: Vec<u32> = truth_table
+            .chars()
+            .enumerate()
+            .filter_map(|(i, c)| if c == '1' { Some(i as u32) } else { None })
+            .collect();
+        return Ok(ParsedRequest::new(minterms, variables));
+    }
+
+    // A condition pasted from code, "(!a && b) || c" or "not a and b or c"
+    if let Some(request) = parse_expression(input)? {
+        return Ok(request);
+    }
+
+    Err(ParseError::UnknownFormat)
+}
+
+/// `minimize minterms 1,3,7 with don't cares 2,4 and 3 variables`, `None` if `input` is
+/// not such a sentence
+///
+/// Without a variable count, the fewest variables for the largest minterm or don't-care
+/// are used, with a note saying so.
+fn parse_sentence(input: &str) -> Result<Option<ParsedRequest>, ParseError> {
+    let Some(caps) = SENTENCE_PATTERN.captures(input) else {
+        return Ok(None);
+    };
+    let minterms = parse_minterm_list(&caps[1], false)?;
+
+    let mut variables = None;
+    let mut dont_cares = None;
+    let mut rest = caps.get(2).map_or("", |m| m.as_str());
+    while let Some(clause) = SENTENCE_CLAUSE_PATTERN.captures(rest) {
+        if let Some(count) = clause.get(1) {
+            let count: usize = count
+                .as_str()
+                .parse()
+                .map_err(|e: std::num::ParseIntError| {
+                    ParseError::InvalidVariableCount(e.to_string())
+                })?;
+            if variables.replace(count).is_some() {
+                return Err(ParseError::RepeatedClause("variable counts"));
+            }
+        }
+        if let Some(list) = clause.get(2) {
+            let list = parse_minterm_list(list.as_str(), true)?;
+            if dont_cares.replace(list).is_some() {
+                return Err(ParseError::RepeatedClause("don't cares"));
+            }
+        }
+        rest = &rest[clause[0].len()..];
+    }
+    // Anything else after the minterms could change the function, so it is not ignored
+    if !rest
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+        .is_empty()
+    {
+        return Ok(None);
+    }
+
+    let mut notes = Vec::new();
+    let variables = variables.unwrap_or_else(|| {
+        let largest = minterms.iter().chain(dont_cares.iter().flatten()).max();
+        let largest = largest.copied().unwrap_or(0);
+        let variables = ((u32::BITS - largest.leading_zeros()) as usize).max(1);
+        notes.push(format!(
+            "No variable count given; using {} variables, the fewest for the largest value {}",
+            variables, largest
+        ));
+        variables
+    });
+    let mut request = ParsedRequest::new(minterms, variables);
+    request.dont_cares = dont_cares;
+    request.notes = notes;
+    Ok(Some(request))
+}
+
+/// `f(A,B,C) = Σ(1,3,7) + d(2,4)` in any spelling, `None` if `input` is not function
+/// notation
+///
+/// The lists may come in any order, but there is one minterm or maxterm list and at most
+/// one don't-care list. Text after the last list is ignored.
+fn parse_function_notation(input: &str) -> Result<Option<ParsedRequest>, ParseError> {
+    let Some(caps) = HEADER_PATTERN.captures(input) else {
+        return Ok(None);
+    };
+    let header = caps[1].trim();
+    let variables: Vec<String> = if header.is_empty() {
+        Vec::new()
+    } else {
+        header.split(',').map(|s| s.trim().to_string()).collect()
+    };
+    // Letters of any script, as in Rust identifiers
+    let is_name = |name: &String| {
+        name.starts_with(char::is_alphabetic)
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    if !variables.iter().all(is_name) {
+        return Ok(None);
+    }
+
+    // Spelling and values of each list
+    let mut terms: Option<(&str, &str)> = None;
+    let mut dont_cares: Option<(&str, &str)> = None;
+    let mut rest = caps.get(2).map_or("", |m| m.as_str()).trim_start();
+    while let Some(list) = LIST_PATTERN.captures(rest) {
+        let (spelling, values) = (list.get(1).unwrap().as_str(), list.get(2).unwrap().as_str());
+        let is_dont_care = matches!(spelling.to_lowercase().as_str(), "d" | "dc" | "x");
+        let slot = if is_dont_care {
+            &mut dont_cares
+        } else {
+            &mut terms
+        };
+        if let Some((first, _)) = slot {
+            return Err(ParseError::MixedNotation {
+                first: first.to_string(),
+                second: spelling.to_string(),
+            });
+        }
+        *slot = Some((spelling, values));
+
+        rest = rest[list.get(0).unwrap().end()..].trim_start();
+        match rest.strip_prefix('+') {
+            Some(next) => rest = next.trim_start(),
+            None => break,
+        }
+    }
+    let Some((spelling, values)) = terms else {
+        return Ok(None);
+    };
+
+    let mut request = ParsedRequest::new(parse_minterm_list(values, false)?, variables.len());
+    request.dont_cares = match dont_cares {
+        Some((_, values)) if values.trim().is_empty() => Some(Vec::new()),
+        Some((_, values)) => Some(parse_minterm_list(values, true)?),
+        None => None,
+    };
+    request.variable_names = Some(variables);
+    // `(?i)` also lets the lowercase `π` through
+    if spelling.to_uppercase() == "Π" {
+        complement_maxterms(&mut request)?;
+    }
+    Ok(Some(request))
+}
+
+/// Replace the maxterms in `request.minterms` by the minterms: the input combinations that
+/// are neither a maxterm nor a don't-care
+fn complement_maxterms(request: &mut ParsedRequest) -> Result<(), ParseError> {
+    if request.variables > MAX_EXPANDED_VARIABLES {
+        return Err(ParseError::TooManyVariables(request.variables));
+    }
+    request.check_minterm_range()?;
+    let dont_cares = request.dont_cares.as_deref().unwrap_or(&[]);
+    request.minterms = (0..1u32 << request.variables)
+        .filter(|m| !request.minterms.contains(m) && !dont_cares.contains(m))
+        .collect();
+    Ok(())
+}
+
+/// Function of a C- or Python-style Boolean expression, `None` if `input` is not one
+///
+/// The variables are the names in the expression, sorted; variable `i` is bit `i` of a
+/// minterm. A lone name or constant is not taken as an expression.
+fn parse_expression(input: &str) -> Result<Option<ParsedRequest>, ParseError> {
+    let expr = match parse_bool_expr(input) {
+        Ok(expr) if !matches!(expr, BoolExpr::Var(_) | BoolExpr::Const(_)) => expr,
+        _ => return Ok(None),
+    };
+    if expr.has_comparisons() {
+        return Err(ParseError::Comparisons);
+    }
+    let mut variables: Vec<String> = extract_variables(&expr).into_iter().collect();
+    variables.sort();
+    if variables.len() > MAX_EXPANDED_VARIABLES {
+        return Err(ParseError::TooManyVariables(variables.len()));
+    }
+
+    let minterms: Vec<u32> = (0..1u32 << variables.len())
+        .filter(|&m| {
+            let assignment = variables
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), (m >> i) & 1 == 1))
+                .collect();
+            evaluate(&expr, &assignment)
+        })
+        .collect();
+
+    let mut request = ParsedRequest::new(minterms, variables.len());
+    request.variable_names = Some(variables);
+    Ok(Some(request))
+}
+
+/// Comma-separated minterms in decimal, hex (`0x3F`) or binary (`0b101`)
+fn parse_minterm_list(list: &str, dont_care: bool) -> Result<Vec<u32>, ParseError> {
+    let invalid = |message: String| ParseError::InvalidMinterm { dont_care, message };
+    let values = crate::parse_minterms(list).map_err(|e| invalid(e.to_string()))?;
+    values
+        .into_iter()
+        .map(|m| {
+            u32::try_from(m)
+                .map_err(|_| invalid(format!("minterm {:#x} does not fit in 32 bits", m)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> ParsedRequest {
+        parse_request_with(input, &|_| None).unwrap_or_else(|e| panic!("'{}': {}", input, e))
+    }
+
+    fn error(input: &str) -> ParseError {
+        parse_request_with(input, &|_| None).unwrap_err()
+    }
+
+    #[test]
+    fn test_json() {
+        let request = parse(r#"{"minterms": [1, 3], "dont_cares": [2], "variables": 2}"#);
+        assert_eq!(request.minterms, vec![1, 3]);
+        assert_eq!(request.dont_cares, Some(vec![2]));
+        assert_eq!((request.variables, request.variable_names), (2, None));
+
+        let request = parse(r#"{"minterms": [0], "variables": 1, "variable_names": ["x"]}"#);
+        assert_eq!(request.variable_names, Some(vec!["x".to_string()]));
+        assert_eq!(request.dont_cares, None);
+    }
+
+    #[test]
+    fn test_function_notation() {
+        let request = parse("f(A,B,C) = Σ(1,3,7) + d(2,4)");
+        assert_eq!(request.minterms, vec![1, 3, 7]);
+        assert_eq!(request.dont_cares, Some(vec![2, 4]));
+        assert_eq!(request.variables, 3);
+        assert_eq!(
+            request.variable_names,
+            Some(vec!["A".to_string(), "B".to_string(), "C".to_string()])
+        );
+
+        assert_eq!(parse("f(A,B) = Σ(1,3)").dont_cares, None);
+        assert_eq!(parse("f(A,B) = Σ(1,3) + d()").dont_cares, Some(vec![]));
+        assert_eq!(parse("f(A,B) = Σ(0x1, 0b11)").minterms, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_function_notation_whitespace() {
+        let request = parse("  f( A , B ,C )=Σ( 1 ,3 )  +  d( 2 ) \n");
+        assert_eq!(request.minterms, vec![1, 3]);
+        assert_eq!(request.dont_cares, Some(vec![2]));
+        assert_eq!(
+            request.variable_names,
+            Some(vec!["A".to_string(), "B".to_string(), "C".to_string()])
+        );
+        assert_eq!(parse("f(A,B)\t=\tΣ(1,\n3)").minterms, vec![1, 3]);
+
+        // Text after the lists is ignored
+        let request = parse("f(A,B,C) = Σ(1,3) + d(2) please");
+        assert_eq!(request.minterms, vec![1, 3]);
+        assert_eq!(request.dont_cares, Some(vec![2]));
+        assert_eq!(parse("f(A,B) = sum(1,3).").minterms, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_maxterm_notation() {
+        // Π(0,2) + d(1) over A, B: 0 at 0 and 2, free at 1
+        let request = parse("f(A,B) = Π(0,2) + d(1)");
+        assert_eq!(request.minterms, vec![3]);
+        assert_eq!(request.dont_cares, Some(vec![1]));
+        assert_eq!(
+            parse("f(A,B,C) = Π(0)").minterms,
+            (1..8).collect::<Vec<_>>()
+        );
+        assert_eq!(parse("f(A,B) = π(0,2) + d(1)").minterms, vec![3]);
+        assert_eq!(
+            error("f(A,B) = Π(4)"),
+            ParseError::MintermOutOfRange {
+                minterm: 4,
+                variables: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_function_notation_preferred_over_expression() {
+        let request = parse("f(A,B,C) = Σ(1,3,7) + d(2)");
+        assert_eq!(request.minterms, vec![1, 3, 7]);
+        assert_eq!(request.dont_cares, Some(vec![2]));
+    }
+
+    #[test]
+    fn test_ascii_spellings() {
+        let expected = parse("f(A,B,C) = Σ(1,3,7) + d(2,4)");
+        for input in [
+            "f(A,B,C) = sum(1,3,7) + d(2,4)",
+            "f(A,B,C) = Σm(1,3,7) + d(2,4)",
+            "f(A,B,C) = m(1,3,7) + dc(2,4)",
+            "f(A,B,C) = SUM(1,3,7) + X(2,4)",
+            "f(A,B,C) = M (1,3,7) + DC (2,4)",
+            "f(A,B,C) = sum(1,3,7) + x(2,4)",
+            "f(A,B,C) = d(2,4) + sum(1,3,7)",
+        ] {
+            let request = parse(input);
+            assert_eq!(request.minterms, expected.minterms, "{}", input);
+            assert_eq!(request.dont_cares, expected.dont_cares, "{}", input);
+            assert_eq!(request.variable_names, expected.variable_names, "{}", input);
+        }
+        assert_eq!(parse("f(A,B) = sum(0x1, 0b11)").minterms, vec![1, 3]);
+        assert_eq!(error("f(A,B,C) = S(1,3,7)"), ParseError::UnknownFormat);
+    }
+
+    #[test]
+    fn test_lowercase_variable_names() {
+        let request = parse("f(a, b, c_2) = sum(1,3,7)");
+        assert_eq!(
+            request.variable_names,
+            Some(vec!["a".to_string(), "b".to_string(), "c_2".to_string()])
+        );
+        assert_eq!(parse("f() = Σ(0)").variables, 0);
+        assert_eq!(
+            parse("f(größe, β) = Σ(1)").variable_names,
+            Some(vec!["größe".to_string(), "β".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_mixed_spellings_are_rejected() {
+        for (input, first, second) in [
+            ("f(A,B) = Σ(1) + sum(3)", "Σ", "sum"),
+            ("f(A,B) = m(1) + Π(2)", "m", "Π"),
+            ("f(A,B) = sum(1) + d(2) + dc(0)", "d", "dc"),
+        ] {
+            let error = error(input);
+            assert_eq!(
+                error,
+                ParseError::MixedNotation {
+                    first: first.to_string(),
+                    second: second.to_string()
+                }
+            );
+            let message = error.to_string();
+            assert!(
+                message.contains(&format!("'{}(...)'", second)),
+                "{}",
+                message
+            );
+            assert!(
+                message.contains("at most one don't-care list"),
+                "{}",
+                message
+            );
+        }
+        // A don't-care list alone is not a function
+        assert_eq!(error("f(A,B) = d(1)"), ParseError::UnknownFormat);
+    }
+
+    #[test]
+    fn test_sentence() {
+        let request = parse("minimize minterms 1,3,7 with 3 variables");
+        assert_eq!((request.minterms, request.variables), (vec![1, 3, 7], 3));
+        assert_eq!(
+            parse("minimize  minterm 5  with 4 variable").minterms,
+            vec![5]
+        );
+        assert_eq!(
+            parse("minimize minterms 0x1, 0b10 with 2 variables").minterms,
+            vec![1, 2]
+        );
+        assert!(matches!(
+            error("minimize minterms 1 with 99999999999999999999999 variables"),
+            ParseError::InvalidVariableCount(_)
+        ));
+    }
+
+    #[test]
+    fn test_sentence_dont_cares() {
+        for input in [
+            "minimize minterms 1,3,7 with don't cares 2,4 and 3 variables",
+            "minimize minterms 1,3,7 with 3 variables and don't cares 2,4",
+            "Minimize minterms 1, 3, 7 and dont care 2, 4 with 3 variables.",
+            "minimize minterms 1,3,7 with don’t-cares 2,4, with 3 variables",
+            "minimize minterms 1,3,7 with dc 2,4 and 3 variables",
+            "minimize minterms 1,3,7 with x terms 0x2,0b100 with 3 variables",
+        ] {
+            let request = parse(input);
+            assert_eq!(request.minterms, vec![1, 3, 7], "{}", input);
+            assert_eq!(request.dont_cares, Some(vec![2, 4]), "{}", input);
+            assert_eq!(request.variables, 3, "{}", input);
+            assert!(request.notes.is_empty(), "{}", input);
+        }
+        assert_eq!(
+            parse("minimize minterms 1,3 with 2 variables").dont_cares,
+            None
+        );
+
+        assert!(matches!(
+            error("minimize minterms 1 with dc 2, 0b12 and 2 variables"),
+            ParseError::InvalidMinterm {
+                dont_care: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            error("minimize minterms 1 with dc 2 and dc 3 with 2 variables"),
+            ParseError::RepeatedClause("don't cares")
+        );
+        assert_eq!(
+            error("minimize minterms 1 with 2 variables and 3 variables"),
+            ParseError::RepeatedClause("variable counts")
+        );
+        // An unknown clause is not dropped silently
+        assert_eq!(
+            error("minimize minterms 1,3 with 2 variables ignoring 2"),
+            ParseError::UnknownFormat
+        );
+        assert!(matches!(
+            error("minimize minterms 1 with dc 4 and 2 variables"),
+            ParseError::MintermOutOfRange { minterm: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn test_sentence_infers_variable_count() {
+        let request = parse("minimize minterms 1,3,7");
+        assert_eq!(request.variables, 3);
+        assert_eq!(
+            request.notes,
+            vec!["No variable count given; using 3 variables, the fewest for the largest value 7"]
+        );
+        // Don't-cares count, and a power of two needs one more variable
+        let request = parse("minimize minterms 1 with don't cares 8");
+        assert_eq!((request.variables, request.dont_cares), (4, Some(vec![8])));
+        assert_eq!(parse("minimize minterm 0").variables, 1);
+        assert_eq!(parse("minimize minterms 0, 1").variables, 1);
+    }
+
+    #[test]
+    fn test_truth_table() {
+        let request = parse("truth table: 00110110");
+        assert_eq!((request.minterms, request.variables), (vec![2, 3, 5, 6], 3));
+        assert_eq!(parse("truth table:01").minterms, vec![1]);
+        assert_eq!(parse("truth   table:  1").variables, 0);
+        assert_eq!(error("truth table: 011"), ParseError::TruthTableLength(3));
+    }
+
+    #[test]
+    fn test_expressions() {
+        let c_style = parse("(!a && b) || c");
+        let python = parse("not a and b or c");
+        assert_eq!(c_style.minterms, vec![2, 4, 5, 6, 7]);
+        assert_eq!(python.minterms, c_style.minterms);
+        assert_eq!(
+            c_style.variable_names,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        assert_eq!(error("x > 2 && a"), ParseError::Comparisons);
+        // A lone name is not an expression
+        assert_eq!(error("a"), ParseError::UnknownFormat);
+
+        let wide = (0..17)
+            .map(|i| format!("v{}", i))
+            .collect::<Vec<_>>()
+            .join(" && ");
+        assert_eq!(error(&wide), ParseError::TooManyVariables(17));
+    }
+
+    #[test]
+    fn test_pla() {
+        let request = parse(".i 2\n.o 1\n1- 1\n01 -\n.e\n");
+        assert_eq!(request.minterms, vec![1, 3]);
+        assert_eq!(request.dont_cares, Some(vec![2]));
+        assert!(request.pla.is_some());
+        assert!(matches!(error(".i 2\n.o 1\n1 1\n.e\n"), ParseError::Pla(_)));
+    }
+
+    #[test]
+    fn test_output_table() {
+        let read_file = |path: &Path| match path.to_str() {
+            Some("adder.pla") => Some(".i 2\n.o 2\n.ob s c\n10 10\n01 10\n11 01\n".to_string()),
+            _ => None,
+        };
+        let table = parse_output_table_with("adder.pla", &read_file)
+            .unwrap()
+            .unwrap();
+        assert_eq!(table.output_names(), vec!["s", "c"]);
+        assert_eq!(table.outputs[0].minterms, vec![1, 2]);
+        assert!(parse_output_table(".i 1\n.o 2\n1 11\n").unwrap().is_some());
+        assert!(parse_output_table("f(A,B) = Σ(1,3)").unwrap().is_none());
+        // parse_request reads single-output PLAs only
+        assert!(matches!(
+            parse_request_with("adder.pla", &read_file),
+            Err(ParseError::Pla(PlaError::UnsupportedOutputs {
+                n_outputs: 2
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_files() {
+        let read_file = |path: &Path| match path.to_str() {
+            Some("f.json") => Some(r#"{"minterms": [2], "variables": 2}"#.to_string()),
+            Some("f.pla") => Some(".i 1\n.o 1\n1 1\n.e\n".to_string()),
+            Some("notes.txt") => Some("neither JSON nor PLA".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            parse_request_with("f.json", &read_file).unwrap().minterms,
+            vec![2]
+        );
+        assert_eq!(
+            parse_request_with("f.pla", &read_file).unwrap().minterms,
+            vec![1]
+        );
+        // Other file contents leave the path to the inline formats, which reject it
+        assert_eq!(
+            parse_request_with("notes.txt", &read_file).unwrap_err(),
+            ParseError::UnknownFormat
+        );
+    }
+
+    #[test]
+    fn test_minterm_errors() {
+        let message = error("f(A,B) = Σ(1, 0b12)").to_string();
+        assert!(
+            message.starts_with("Failed to parse minterm"),
+            "{}",
+            message
+        );
+        assert!(message.contains("'0b12' at index 1"), "{}", message);
+        let message = error("f(A,B) = Σ(1) + d(0x1_0000_0000)").to_string();
+        assert!(
+            message.starts_with("Failed to parse don't care term"),
+            "{}",
+            message
+        );
+        assert!(message.contains("does not fit in 32 bits"), "{}", message);
+    }
+
+    #[test]
+    fn test_minterm_range() {
+        let error = error("minimize minterms 0x1, 0xFFFF_FFFF with 8 variables");
+        assert_eq!(
+            error.to_string(),
+            "Minterm 4294967295 (0xffffffff) is out of range for 8 variables (maximum 255)"
+        );
+        assert!(matches!(
+            parse_request_with(
+                r#"{"minterms": [1], "dont_cares": [4], "variables": 2}"#,
+                &|_| { None }
+            ),
+            Err(ParseError::MintermOutOfRange { minterm: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_format_lists_the_examples() {
+        let message = error("hello").to_string();
+        assert!(message.starts_with("Could not parse input format"));
+        for example in EXAMPLES {
+            assert!(message.contains(example.input), "{}", example.input);
+        }
+    }
+
+    #[test]
+    fn test_examples_parse() {
+        use super::super::examples::{COMMAND_EXAMPLES, quoted_input};
+
+        // The inputs of the format examples, and the quoted inputs of the command lines
+        let commands = COMMAND_EXAMPLES
+            .iter()
+            .flat_map(|example| example.commands.iter());
+        let inputs = EXAMPLES
+            .iter()
+            .map(|example| example.input)
+            .chain(commands.filter_map(|command| quoted_input(command)));
+        for input in inputs {
+            parse(input);
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use super::encoding::MintermEncoding;
 use super::implicant::Implicant;
 use super::simd_coverage;
+use crate::cnf_dnf::CnfReduction;
+use std::collections::HashSet;
+use std::time::Instant;
 
 pub struct PetricksMethod<E: MintermEncoding> {
     prime_implicants: Vec<Implicant<E>>,
@@ -96,8 +99,11 @@ impl<E: MintermEncoding> PetricksMethod<E> {
 
             if covers_new {
                 selected.push(pi.clone());
-                for &minterm in &pi.covered_minterms {
-                    covered_minterms.insert(minterm);
+                // Only count required minterms; `covered_minterms` also holds don't-cares
+                for (mt_idx, &minterm) in self.minterms.iter().enumerate() {
+                    if coverage_matrix.get(pi_idx, mt_idx) {
+                        covered_minterms.insert(minterm);
+                    }
                 }
             }
 
@@ -106,7 +112,7 @@ impl<E: MintermEncoding> PetricksMethod<E> {
             }
         }
 
-        selected
+        self.remove_redundant(selected)
     }
 
     /// Original scalar implementation
@@ -125,8 +131,11 @@ impl<E: MintermEncoding> PetricksMethod<E> {
 
             if covers_new {
                 selected.push(pi.clone());
-                for &minterm in &pi.covered_minterms {
-                    covered_minterms.insert(minterm);
+                // Only count required minterms; `covered_minterms` also holds don't-cares
+                for &minterm in &self.minterms {
+                    if pi.covers_minterm(minterm) {
+                        covered_minterms.insert(minterm);
+                    }
                 }
             }
 
@@ -135,9 +144,202 @@ impl<E: MintermEncoding> PetricksMethod<E> {
             }
         }
 
+        self.remove_redundant(selected)
+    }
+
+    /// Drop selected implicants whose minterms are all covered by the other selections.
+    ///
+    /// Greedy selection in PI order can pick an implicant that later picks make
+    /// redundant; earlier picks are tried first.
+    fn remove_redundant(&self, mut selected: Vec<Implicant<E>>) -> Vec<Implicant<E>> {
+        let mut i = 0;
+        while i < selected.len() {
+            let redundant = self
+                .minterms
+                .iter()
+                .filter(|&&minterm| selected[i].covers_minterm(minterm))
+                .all(|&minterm| {
+                    selected
+                        .iter()
+                        .enumerate()
+                        .any(|(j, other)| j != i && other.covers_minterm(minterm))
+                });
+            if redundant {
+                selected.remove(i);
+            } else {
+                i += 1;
+            }
+        }
         selected
     }
 
+    /// All covers with the smallest possible number of prime implicants.
+    ///
+    /// Exact branch and bound: the uncovered minterm with the fewest candidates is branched
+    /// on first, and the greedy cover bounds the search. Covers are returned in PI order.
+    pub fn find_minimum_covers(&self) -> Vec<Vec<Implicant<E>>> {
+        self.find_minimum_covers_until(None).0
+    }
+
+    /// [`find_minimum_covers`](Self::find_minimum_covers), giving up at `deadline`
+    ///
+    /// Returns the covers, whether the search was cut short, and how many minterm clauses
+    /// were dropped before it. A cut-short search returns the smallest covers found so far,
+    /// or the greedy cover when it found none.
+    pub fn find_minimum_covers_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> (Vec<Vec<Implicant<E>>>, bool, CnfReduction) {
+        let (candidates, reduction) = self.reduced_candidates();
+
+        let greedy = self.find_minimal_cover();
+        let mut search = CoverSearch {
+            candidates: &candidates,
+            deadline,
+            timed_out: false,
+            best: greedy.len(),
+            found: Vec::new(),
+        };
+        search.run(&mut Vec::new());
+        if search.found.is_empty() {
+            return (vec![greedy], search.timed_out, reduction);
+        }
+
+        let mut found = search.found;
+        found.sort();
+        found.dedup();
+        let covers = found
+            .into_iter()
+            .map(|cover| {
+                cover
+                    .into_iter()
+                    .map(|i| self.prime_implicants[i].clone())
+                    .collect()
+            })
+            .collect();
+        (covers, search.timed_out, reduction)
+    }
+
+    /// Per minterm that some prime implicant covers, the indices of those prime implicants
+    fn candidates(&self) -> Vec<Vec<usize>> {
+        self.minterms
+            .iter()
+            .map(|&minterm| {
+                (0..self.prime_implicants.len())
+                    .filter(|&i| self.prime_implicants[i].covers_minterm(minterm))
+                    .collect()
+            })
+            .filter(|c: &Vec<usize>| !c.is_empty())
+            .collect()
+    }
+
+    /// [`candidates`](Self::candidates) without duplicate clauses (minterms covered by the
+    /// same prime implicants) and clauses that are a superset of another
+    ///
+    /// A superset clause is implied by its subset, so the covers are the same; as in
+    /// [`reduce_cnf`](crate::cnf_dnf::reduce_cnf), kept clauses stay in their order.
+    fn reduced_candidates(&self) -> (Vec<Vec<usize>>, CnfReduction) {
+        let clauses = self.candidates();
+        let mut seen = HashSet::with_capacity(clauses.len());
+        let unique: Vec<&Vec<usize>> = clauses.iter().filter(|&c| seen.insert(c)).collect();
+
+        // Any subset of a clause is shorter, so a single pass in size order suffices
+        let mut by_size: Vec<usize> = (0..unique.len()).collect();
+        by_size.sort_by_key(|&i| unique[i].len());
+        let mut kept_clauses: Vec<&Vec<usize>> = Vec::with_capacity(unique.len());
+        let mut keep = vec![false; unique.len()];
+        for i in by_size {
+            let clause = unique[i];
+            let implied = kept_clauses
+                .iter()
+                .any(|smaller| smaller.iter().all(|pi| clause.binary_search(pi).is_ok()));
+            if !implied {
+                kept_clauses.push(clause);
+                keep[i] = true;
+            }
+        }
+
+        let reduced: Vec<Vec<usize>> = unique
+            .iter()
+            .zip(&keep)
+            .filter(|&(_, &k)| k)
+            .map(|(&c, _)| c.clone())
+            .collect();
+        let reduction = CnfReduction {
+            duplicates_removed: clauses.len() - unique.len(),
+            supersets_removed: unique.len() - reduced.len(),
+        };
+        (reduced, reduction)
+    }
+
+    /// The covering problem in DIMACS CNF, for an external SAT solver
+    ///
+    /// Variable `i + 1` selects prime implicant `i`, whose pattern a comment line gives;
+    /// every minterm is a clause of the prime implicants covering it. Minterms that no prime
+    /// implicant covers are left out, as in [`find_minimum_covers`](Self::find_minimum_covers).
+    /// [`from_model`](Self::from_model) reads a model back as a cover.
+    pub fn to_dimacs(&self) -> String {
+        let clauses = self.candidates();
+        let mut dimacs = self.variable_comments();
+        dimacs.push_str(&format!(
+            "p cnf {} {}\n",
+            self.prime_implicants.len(),
+            clauses.len()
+        ));
+        for clause in &clauses {
+            dimacs.push_str(&dimacs_clause("", clause));
+        }
+        dimacs
+    }
+
+    /// [`to_dimacs`](Self::to_dimacs) as weighted CNF, for a MaxSAT solver
+    ///
+    /// The covering clauses are hard; a soft clause of weight 1 per prime implicant
+    /// penalizes selecting it, so an optimal model is a cover with the fewest prime
+    /// implicants.
+    pub fn to_wcnf(&self) -> String {
+        let clauses = self.candidates();
+        let n_variables = self.prime_implicants.len();
+        // More than all soft clauses together
+        let top = n_variables + 1;
+        let mut wcnf = self.variable_comments();
+        wcnf.push_str(&format!(
+            "p wcnf {} {} {}\n",
+            n_variables,
+            clauses.len() + n_variables,
+            top
+        ));
+        for clause in &clauses {
+            wcnf.push_str(&dimacs_clause(&format!("{} ", top), clause));
+        }
+        for i in 1..=n_variables {
+            wcnf.push_str(&format!("1 -{} 0\n", i));
+        }
+        wcnf
+    }
+
+    /// The prime implicants selected by a model of [`to_dimacs`](Self::to_dimacs) or
+    /// [`to_wcnf`](Self::to_wcnf), in PI order
+    ///
+    /// `assignment[i]` is the value of variable `i + 1`; variables past its end are false.
+    pub fn from_model(&self, assignment: &[bool]) -> Vec<Implicant<E>> {
+        self.prime_implicants
+            .iter()
+            .zip(assignment)
+            .filter(|&(_, &selected)| selected)
+            .map(|(pi, _)| pi.clone())
+            .collect()
+    }
+
+    /// `c <variable> <pattern>` per prime implicant
+    fn variable_comments(&self) -> String {
+        self.prime_implicants
+            .iter()
+            .enumerate()
+            .map(|(i, pi)| format!("c {} {}\n", i + 1, pi.to_pattern()))
+            .collect()
+    }
+
     /// Generate a product-of-sums expression from the prime implicant coverage.
     ///
     /// Currently returns a placeholder string. Full implementation would convert
@@ -146,3 +348,128 @@ impl<E: MintermEncoding> PetricksMethod<E> {
         "Dummy POS expression".to_string()
     }
 }
+
+/// A clause line over prime implicant indices (variable `i + 1`), after `prefix`
+fn dimacs_clause(prefix: &str, pis: &[usize]) -> String {
+    let literals: Vec<String> = pis.iter().map(|i| (i + 1).to_string()).collect();
+    format!("{}{} 0\n", prefix, literals.join(" "))
+}
+
+/// State of the branch and bound of [`PetricksMethod::find_minimum_covers_until`]
+struct CoverSearch<'a> {
+    /// Per minterm, the indices of the prime implicants covering it
+    candidates: &'a [Vec<usize>],
+    deadline: Option<Instant>,
+    timed_out: bool,
+    best: usize,
+    found: Vec<Vec<usize>>,
+}
+
+impl CoverSearch<'_> {
+    fn run(&mut self, chosen: &mut Vec<usize>) {
+        if self.timed_out
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.timed_out = true;
+            return;
+        }
+        let uncovered = self
+            .candidates
+            .iter()
+            .filter(|c| !c.iter().any(|i| chosen.contains(i)))
+            .min_by_key(|c| c.len());
+
+        let Some(options) = uncovered else {
+            if chosen.len() < self.best {
+                self.best = chosen.len();
+                self.found.clear();
+            }
+            let mut cover = chosen.clone();
+            cover.sort_unstable();
+            self.found.push(cover);
+            return;
+        };
+        if chosen.len() >= self.best {
+            return;
+        }
+        for &i in options {
+            chosen.push(i);
+            self.run(chosen);
+            chosen.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::encoding::Enc16;
+
+    /// Σ(0,1,2,5,6,7) over 3 variables: six prime implicants of two minterms each
+    fn cyclic_chart() -> PetricksMethod<Enc16> {
+        let primes: Vec<Implicant<Enc16>> = ["00X", "0X0", "X01", "X10", "1X1", "11X"]
+            .iter()
+            .map(|pattern| Implicant::from_pattern(pattern).unwrap())
+            .collect();
+        PetricksMethod::new(&primes, &[0, 1, 2, 5, 6, 7])
+    }
+
+    #[test]
+    fn test_to_dimacs() {
+        let dimacs = cyclic_chart().to_dimacs();
+        assert_eq!(
+            dimacs,
+            "c 1 00X\nc 2 0X0\nc 3 X01\nc 4 X10\nc 5 1X1\nc 6 11X\n\
+             p cnf 6 6\n1 2 0\n1 3 0\n2 4 0\n3 5 0\n4 6 0\n5 6 0\n"
+        );
+    }
+
+    #[test]
+    fn test_to_wcnf() {
+        let wcnf = cyclic_chart().to_wcnf();
+        assert!(wcnf.contains("p wcnf 6 12 7\n7 1 2 0\n"), "{}", wcnf);
+        assert!(wcnf.ends_with("7 5 6 0\n1 -1 0\n1 -2 0\n1 -3 0\n1 -4 0\n1 -5 0\n1 -6 0\n"));
+    }
+
+    #[test]
+    fn test_from_model_is_a_cover() {
+        let chart = cyclic_chart();
+        // By hand: 1 covers 0 and 1, 4 covers 2 and 6, 5 covers 5 and 7
+        let model = [true, false, false, true, true, false];
+        let cover = chart.from_model(&model);
+        let patterns: Vec<String> = cover.iter().map(Implicant::to_pattern).collect();
+        assert_eq!(patterns, vec!["00X", "X10", "1X1"]);
+        for minterm in [0, 1, 2, 5, 6, 7] {
+            assert!(
+                cover.iter().any(|pi| pi.covers_minterm(minterm)),
+                "{}",
+                minterm
+            );
+        }
+        // As small as the exact search gets
+        assert_eq!(cover.len(), chart.find_minimum_covers()[0].len());
+
+        // A short assignment leaves the remaining variables false
+        assert_eq!(chart.from_model(&[true]).len(), 1);
+    }
+
+    #[test]
+    fn test_minimum_covers_drop_implied_clauses() {
+        let (_, _, reduction) = cyclic_chart().find_minimum_covers_until(None);
+        assert_eq!(reduction, CnfReduction::default());
+
+        // Minterm 0 is covered by both implicants, 1 (twice) and 4 by one each
+        let primes: Vec<Implicant<Enc16>> = ["00X", "X00"]
+            .iter()
+            .map(|pattern| Implicant::from_pattern(pattern).unwrap())
+            .collect();
+        let chart = PetricksMethod::new(&primes, &[0, 1, 4, 1]);
+        let (covers, timed_out, reduction) = chart.find_minimum_covers_until(None);
+        assert_eq!(covers, vec![primes]);
+        assert!(!timed_out);
+        assert_eq!(reduction.duplicates_removed, 1);
+        assert_eq!(reduction.supersets_removed, 1);
+    }
+}
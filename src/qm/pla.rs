@@ -0,0 +1,401 @@
+//! Berkeley PLA input (as read by espresso)
+//!
+//! Only the subset needed to describe the functions is supported:
+//! - `.i N` (required), `.o N`, `.ilb names...`, `.ob names...`, `.p`, `.type f|fd`,
+//!   `.e` / `.end`
+//! - product terms `01-1 10`: input column `i` is variable `i` (bit `i`), `-` is a
+//!   don't-care; per output column, `1` adds the cube to the on-set of that output, `-` (or
+//!   `2`) to its don't-care set, `0` / `~` is ignored
+//! - `#` starts a comment
+//!
+//! [`parse_pla`] reads a single-output PLA, [`parse_pla_outputs`] one with any number of
+//! outputs.
+//!
+//! A product term may have at most [`MAX_TERM_DONT_CARES`] don't-care inputs.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::encoding::{BitOps, MintermEncoding};
+use super::implicant::{BitState, Implicant, PatternError};
+
+/// Most don't-care inputs in one product term (every term is expanded into its minterms)
+pub const MAX_TERM_DONT_CARES: usize = 20;
+
+/// Errors that can occur while reading a PLA file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaError {
+    /// Malformed directive or product term at `line` (1-based)
+    Parse { line: usize, message: String },
+    /// More inputs than the encoding supports
+    TooManyInputs { n_inputs: usize, max_vars: usize },
+    /// A single-output PLA was expected
+    UnsupportedOutputs { n_outputs: usize },
+}
+
+impl fmt::Display for PlaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaError::Parse { line, message } => write!(f, "PLA line {}: {}", line, message),
+            PlaError::TooManyInputs { n_inputs, max_vars } => write!(
+                f,
+                "PLA has {} inputs; maximum supported is {}",
+                n_inputs, max_vars
+            ),
+            PlaError::UnsupportedOutputs { n_outputs } => write!(
+                f,
+                "PLA has {} outputs; expected a single output",
+                n_outputs
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlaError {}
+
+/// A single-output function read from a PLA file
+#[derive(Debug, Clone)]
+pub struct Pla<E: MintermEncoding> {
+    pub n_inputs: usize,
+    /// Names from `.ilb`, if given
+    pub input_labels: Option<Vec<String>>,
+    /// Product terms with output `1`
+    pub on_set: Vec<Implicant<E>>,
+    /// Product terms with output `-`
+    pub dc_set: Vec<Implicant<E>>,
+}
+
+impl<E: MintermEncoding> Pla<E> {
+    /// Minterms of the on-set cover (sorted, deduplicated)
+    pub fn minterms(&self) -> Vec<u64> {
+        expand(&self.on_set)
+    }
+
+    /// Don't-care minterms that are not also in the on-set (sorted, deduplicated)
+    pub fn dont_cares(&self) -> Vec<u64> {
+        let on_set: BTreeSet<u64> = self.minterms().into_iter().collect();
+        expand(&self.dc_set)
+            .into_iter()
+            .filter(|m| !on_set.contains(m))
+            .collect()
+    }
+
+    /// Number of literals in the on-set cover
+    pub fn literal_count(&self) -> usize {
+        self.on_set
+            .iter()
+            .map(|imp| {
+                (0..self.n_inputs)
+                    .filter(|&i| imp.get_bit(i) != BitState::DontCare)
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// Does `text` look like a PLA file (first directive is `.i`)?
+pub fn is_pla(text: &str) -> bool {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.starts_with(".i ") || line.starts_with(".i\t"))
+}
+
+/// A function with several outputs read from a PLA file; the outputs share the inputs
+#[derive(Debug, Clone)]
+pub struct PlaOutputs<E: MintermEncoding> {
+    /// Names from `.ob`, if given
+    pub output_labels: Option<Vec<String>>,
+    /// One single-output function per output column, in column order
+    pub outputs: Vec<Pla<E>>,
+}
+
+/// Parse a single-output PLA
+pub fn parse_pla<E: MintermEncoding>(text: &str) -> Result<Pla<E>, PlaError> {
+    let mut pla = parse_pla_outputs::<E>(text)?;
+    if pla.outputs.len() != 1 {
+        return Err(PlaError::UnsupportedOutputs {
+            n_outputs: pla.outputs.len(),
+        });
+    }
+    Ok(pla.outputs.remove(0))
+}
+
+/// The (on-set, don't-care set) of one output column
+type OutputSets<E> = (Vec<Implicant<E>>, Vec<Implicant<E>>);
+
+/// Parse a PLA with any number of outputs (`.o 1` when not given)
+pub fn parse_pla_outputs<E: MintermEncoding>(text: &str) -> Result<PlaOutputs<E>, PlaError> {
+    let mut n_inputs: Option<usize> = None;
+    let mut n_outputs: usize = 1;
+    let mut input_labels: Option<Vec<String>> = None;
+    let mut output_labels: Option<Vec<String>> = None;
+    let mut sets: Vec<OutputSets<E>> = vec![Default::default()];
+    let mut seen_term = false;
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let parse_error = |message: String| PlaError::Parse {
+            line: line_number,
+            message,
+        };
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let first = fields.next().unwrap_or("");
+        if let Some(directive) = first.strip_prefix('.') {
+            let value = fields.next();
+            match directive {
+                "i" => {
+                    let n = value
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .ok_or_else(|| parse_error("'.i' needs a number".to_string()))?;
+                    if n > E::MAX_VARS {
+                        return Err(PlaError::TooManyInputs {
+                            n_inputs: n,
+                            max_vars: E::MAX_VARS,
+                        });
+                    }
+                    n_inputs = Some(n);
+                }
+                "o" => {
+                    let n = value
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| parse_error("'.o' needs a positive number".to_string()))?;
+                    if seen_term {
+                        return Err(parse_error("'.o' after the product terms".to_string()));
+                    }
+                    n_outputs = n;
+                    sets = vec![Default::default(); n];
+                }
+                "ilb" => input_labels = Some(directive_names(value, fields)),
+                "ob" => output_labels = Some(directive_names(value, fields)),
+                "type" => {
+                    if !matches!(value, Some("f") | Some("fd")) {
+                        return Err(parse_error(format!(
+                            "unsupported PLA type '{}'",
+                            value.unwrap_or("")
+                        )));
+                    }
+                }
+                "e" | "end" => break,
+                "p" => {}
+                _ => return Err(parse_error(format!("unknown directive '.{}'", directive))),
+            }
+            continue;
+        }
+
+        let n = n_inputs.ok_or_else(|| parse_error("product term before '.i'".to_string()))?;
+        // Input and output part, either separated by whitespace or written as one token
+        let (inputs, output) = match fields.next() {
+            Some(output) => (first, output),
+            None if first.chars().count() == n + n_outputs => {
+                first.split_at(first.char_indices().nth(n).map_or(0, |(i, _)| i))
+            }
+            None => return Err(parse_error("missing output column".to_string())),
+        };
+        if output.chars().count() != n_outputs {
+            return Err(parse_error(format!(
+                "expected {} output columns, found {}",
+                n_outputs,
+                output.chars().count()
+            )));
+        }
+        if inputs.chars().count() != n {
+            return Err(parse_error(format!(
+                "expected {} input columns, found {}",
+                n,
+                inputs.chars().count()
+            )));
+        }
+
+        let n_free = inputs
+            .chars()
+            .filter(|c| matches!(c, '-' | 'x' | 'X'))
+            .count();
+        if n_free > MAX_TERM_DONT_CARES {
+            return Err(parse_error(format!(
+                "term has {} don't-care inputs; at most {} are supported",
+                n_free, MAX_TERM_DONT_CARES
+            )));
+        }
+        let term = parse_term::<E>(inputs).map_err(parse_error)?;
+        for (value, (on_set, dc_set)) in output.chars().zip(&mut sets) {
+            match value {
+                '1' | '4' => on_set.push(term.clone()),
+                '-' | '2' => dc_set.push(term.clone()),
+                '0' | '~' => {}
+                other => return Err(parse_error(format!("invalid output '{}'", other))),
+            }
+        }
+        seen_term = true;
+    }
+
+    let n_inputs = n_inputs.ok_or_else(|| PlaError::Parse {
+        line: 0,
+        message: "missing '.i' directive".to_string(),
+    })?;
+    for (directive, labels, expected, what) in [
+        ("ilb", &input_labels, n_inputs, "inputs"),
+        ("ob", &output_labels, n_outputs, "outputs"),
+    ] {
+        let found = labels.as_ref().map_or(expected, Vec::len);
+        if found != expected {
+            return Err(PlaError::Parse {
+                line: 0,
+                message: format!(
+                    "'.{}' lists {} names for {} {}",
+                    directive, found, expected, what
+                ),
+            });
+        }
+    }
+
+    let outputs = sets
+        .into_iter()
+        .map(|(on_set, dc_set)| Pla {
+            n_inputs,
+            input_labels: input_labels.clone(),
+            on_set,
+            dc_set,
+        })
+        .collect();
+    Ok(PlaOutputs {
+        output_labels,
+        outputs,
+    })
+}
+
+/// The names of an `.ilb` or `.ob` directive
+fn directive_names<'a>(first: Option<&'a str>, rest: impl Iterator<Item = &'a str>) -> Vec<String> {
+    first
+        .into_iter()
+        .chain(rest)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Implicant for an input pattern such as `01-1` (column `i` is bit `i`)
+fn parse_term<E: MintermEncoding>(pattern: &str) -> Result<Implicant<E>, String> {
+    // Implicant patterns are most significant variable first
+    let reversed: String = pattern.chars().rev().collect();
+    Implicant::from_pattern(&reversed).map_err(|e| match e {
+        PatternError::InvalidCharacter { character, .. } => {
+            format!("invalid input character '{}'", character)
+        }
+        other => other.to_string(),
+    })
+}
+
+/// All minterms covered by `cover`, sorted and deduplicated
+fn expand<E: MintermEncoding>(cover: &[Implicant<E>]) -> Vec<u64> {
+    cover
+        .iter()
+        .flat_map(|imp| imp.covered_minterms_iter().map(|&m| m.to_u64()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::encoding::{Enc16, Enc32};
+
+    const REDUNDANT: &str = "\
+# A'B' + AC with redundant terms
+.i 3
+.o 1
+.ilb a b c
+000 1
+001 1
+00- 1
+1-1 1
+111 1
+011 -
+.e
+";
+
+    #[test]
+    fn test_parse_pla_cover() {
+        assert!(is_pla(REDUNDANT));
+        let pla = parse_pla::<Enc16>(REDUNDANT).unwrap();
+        assert_eq!(pla.n_inputs, 3);
+        assert_eq!(
+            pla.input_labels,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        assert_eq!(pla.on_set.len(), 5);
+        assert_eq!(pla.literal_count(), 13);
+
+        // Column i is bit i: "00-" = {0b000, 0b100}, "1-1" = {0b101, 0b111}
+        assert_eq!(pla.minterms(), vec![0, 4, 5, 7]);
+        // "011" = a=0, b=1, c=1
+        assert_eq!(pla.dont_cares(), vec![6]);
+        assert_eq!(pla.on_set[2].get_bit(2), BitState::DontCare);
+        // Implicant patterns are MSB first, PLA columns LSB first
+        assert_eq!(pla.on_set[2], Implicant::from_pattern("X00").unwrap());
+        assert_eq!(pla.on_set[3].to_pattern(), "1X1");
+        assert_eq!(pla.dc_set[0].to_pattern(), "110");
+    }
+
+    #[test]
+    fn test_parse_pla_outputs() {
+        let text = ".i 3\n.o 2\n.ob sum carry\n11- 11\n0-1 1-\n1-0 01\n.e\n";
+        let pla = parse_pla_outputs::<Enc16>(text).unwrap();
+        assert_eq!(
+            pla.output_labels,
+            Some(vec!["sum".to_string(), "carry".to_string()])
+        );
+        assert_eq!(pla.outputs.len(), 2);
+        assert_eq!(pla.outputs[0].minterms(), vec![3, 4, 6, 7]);
+        assert_eq!(pla.outputs[1].minterms(), vec![1, 3, 7]);
+        assert_eq!(pla.outputs[1].dont_cares(), vec![4, 6]);
+        // A single output is what parse_pla reads
+        assert!(matches!(
+            parse_pla::<Enc16>(text),
+            Err(PlaError::UnsupportedOutputs { n_outputs: 2 })
+        ));
+        assert!(matches!(
+            parse_pla_outputs::<Enc16>(".i 1\n.o 2\n1 1\n"),
+            Err(PlaError::Parse { line: 3, .. })
+        ));
+        assert!(matches!(
+            parse_pla_outputs::<Enc16>(".i 1\n.o 2\n.ob f\n"),
+            Err(PlaError::Parse { line: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_pla_errors() {
+        assert!(!is_pla("f(A,B) = Σ(1,3)"));
+        assert!(matches!(
+            parse_pla::<Enc16>(".i 2\n.o 2\n"),
+            Err(PlaError::UnsupportedOutputs { n_outputs: 2 })
+        ));
+        assert!(matches!(
+            parse_pla::<Enc16>(".i 2\n.o 1\n0a 1\n"),
+            Err(PlaError::Parse { line: 3, .. })
+        ));
+        // Regression input from fuzzing: a multi-byte character used to split off mid-char
+        assert!(matches!(
+            parse_pla::<Enc16>(".i 1\nΣ"),
+            Err(PlaError::Parse { line: 2, .. })
+        ));
+        // Regression input from fuzzing: a 32-input don't-care term expanded to 2^32 minterms
+        let wide = format!(".i 32\n{} 1\n", "-".repeat(32));
+        assert!(matches!(
+            parse_pla::<Enc32>(&wide),
+            Err(PlaError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_pla::<Enc16>(".i 20\n"),
+            Err(PlaError::TooManyInputs { n_inputs: 20, .. })
+        ));
+    }
+}
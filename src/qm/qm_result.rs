@@ -1,18 +1,438 @@
 //! QMResult: Result type for Quine-McCluskey minimization
 
-/// Result of Quine-McCluskey minimization
+use super::encoding::{BitOps, Enc64};
+use super::expression::SopExpression;
+use super::factor::{FactorStyle, FactoredExpression, factor_sop};
+use super::implicant::Implicant;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::rc::Rc;
+
+/// An input vector inside one of the selected implicants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVector {
+    /// Index of the implicant in [`QMResult::cover`]
+    pub implicant: usize,
+    /// Input assignment (bit `i` is variable `i`)
+    pub vector: u64,
+    /// Expected function output (always `true`: the vector lies in the cover)
+    pub expected: bool,
+}
+
+/// Fixed-length sequence of bits, packed 64 to a word (bit `i` of word `w` is bit `64w + i`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bit `index`; panics when out of range
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit {} of {}", index, self.len);
+        (self.words[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    /// Number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|index| self.get(index))
+    }
+
+    /// The packed words; bits past `len` are zero
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+/// Best cover with a bounded number of terms, see [`QMSolver::solve_bounded`]
+///
+/// [`QMSolver::solve_bounded`]: super::QMSolver::solve_bounded
 #[derive(Debug, Clone, PartialEq)]
+pub struct BoundedResult {
+    /// Selected implicants as `(values, don't-care mask)`, at most the term budget
+    pub cover: Vec<(u64, u64)>,
+    /// The selected implicants as a sum-of-products expression
+    pub expression: String,
+    /// Minterms no selected implicant covers (ascending)
+    pub uncovered_minterms: Vec<u64>,
+    /// Fraction of the (distinct) minterms covered by `cover` (1.0 without minterms)
+    pub covered_fraction: f64,
+}
+
+/// The cheaper of the two-level forms chosen by [`QMSolver::solve_best_form`]
+///
+/// [`QMSolver::solve_best_form`]: super::QMSolver::solve_best_form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BestForm {
+    /// The sum of products has at most as many literals as the product of sums
+    Sop,
+    /// The product of sums has fewer literals
+    Pos,
+}
+
+/// Both minimized forms, the [`BestForm`] among them and their literal counts
+#[derive(Debug, Clone)]
+pub struct BestFormResult {
+    pub best: BestForm,
+    /// The chosen form, e.g. `AC + BD` or `(A + B)(C + D)`
+    pub expression: String,
+    /// The product of sums, also when the sum of products was chosen
+    pub pos_expression: String,
+    /// Literals of the minimum sum of products
+    pub sop_cost: usize,
+    /// Literals of the minimum product of sums
+    pub pos_cost: usize,
+    /// The minimized function, also when the product of sums was chosen
+    pub sop: QMResult,
+    /// The minimized complement ([`complemented`](QMResult::complemented)), each of its
+    /// terms inverted into a clause of `pos_expression`
+    pub complement: QMResult,
+}
+
+/// Result of Quine-McCluskey minimization
+///
+/// The serialized field names are stable (they are what the CLI's JSON output has always
+/// used); [`result_schema`](super::schema::result_schema) describes the shape. Fields
+/// added later default when absent, so older documents still deserialize.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QMResult {
     /// The minimized sum-of-products expression (e.g. "A'B + AC")
+    #[serde(rename = "minimized_sop", alias = "minimized_expression")]
     pub minimized_expression: String,
     /// All prime implicants found, as formatted strings
     pub prime_implicants: Vec<String>,
     /// Essential prime implicants that must appear in any minimal cover
     pub essential_prime_implicants: Vec<String>,
     /// Step-by-step description of the minimization process
+    #[serde(
+        rename = "steps",
+        alias = "solution_steps",
+        default,
+        serialize_with = "empty_as_null",
+        deserialize_with = "null_as_empty"
+    )]
     pub solution_steps: Vec<String>,
     /// Original cost = number of minterms × number of variables
+    #[serde(default)]
     pub cost_original: usize,
     /// Minimized cost = number of selected prime implicants × 2
+    #[serde(default)]
     pub cost_minimized: usize,
+    /// Minterms the cover had to cover (sorted, deduplicated)
+    #[serde(rename = "original_minterms", alias = "minterms")]
+    pub minterms: Vec<u64>,
+    /// Don't-cares covered by the selected implicants (assigned 1 in the implemented function)
+    #[serde(default)]
+    pub used_dont_cares: Vec<u64>,
+    /// Don't-cares not covered by the selected implicants (assigned 0)
+    #[serde(default)]
+    pub unused_dont_cares: Vec<u64>,
+    /// Number of input variables
+    #[serde(default)]
+    pub n_variables: usize,
+    /// Selected implicants as `(values, don't-care mask)`, in expression order
+    #[serde(default)]
+    pub cover: Vec<(u64, u64)>,
+    /// Consensus terms added to `cover` to remove static-1 hazards (see `Objective::HazardFree`)
+    #[serde(default)]
+    pub hazard_terms: Vec<String>,
+    /// Variables referenced by the minimized expression, in variable order
+    #[serde(default)]
+    pub variables_used: Vec<String>,
+    /// Bit indices of the input variables some term of `cover` fixes (ascending); the
+    /// function does not depend on the others
+    #[serde(default)]
+    pub referenced_variables: Vec<usize>,
+    /// The essential prime implicants alone cover every minterm (no cyclic core)
+    #[serde(default)]
+    pub essential_only_cover: bool,
+    /// Fraction of the minterms covered by the essential prime implicants (1.0 without minterms)
+    #[serde(default)]
+    pub essential_cover_fraction: f64,
+    /// Petrick's method was run to resolve the minterms the essentials leave uncovered
+    #[serde(default)]
+    pub petrick_invoked: bool,
+    /// Covers with as few prime implicants as `cover` that Petrick's method found besides
+    /// it, whatever their literal count (0 when it did not run; `None` when it ran
+    /// greedily and did not enumerate the minimum covers)
+    #[serde(default)]
+    pub petrick_runner_ups: Option<usize>,
+    /// Minterm clauses Petrick's method dropped before its search: duplicates (minterms
+    /// covered by the same prime implicants) and supersets of another clause
+    #[serde(default)]
+    pub petrick_clauses_removed: usize,
+    /// Per essential prime implicant (index into `essential_prime_implicants`), the minterms
+    /// that no other prime implicant covers (ascending)
+    #[serde(default)]
+    pub essentiality_witnesses: Vec<(usize, Vec<u64>)>,
+    /// The cover is of the complement: `minterms` is the off-set of the original function
+    /// (see `QMSolver::solve_complement`)
+    #[serde(default)]
+    pub complemented: bool,
+    /// Classes of interchangeable variables (bit indices, see [`symmetry::detect`](super::symmetry::detect));
+    /// only filled in when requested with `QMSolver::set_report_symmetry`
+    #[serde(default)]
+    pub symmetry_classes: Vec<Vec<usize>>,
+    /// Most bytes tracked at a phase boundary of the solve (see `QMSolver::set_max_memory`)
+    #[serde(default)]
+    pub peak_memory_bytes: usize,
+    /// Repeated minterms and don't-cares dropped from the input (see `QMSolver::set_minterms`)
+    #[serde(default)]
+    pub duplicates_removed: usize,
+}
+
+/// No steps as `null`, as the CLI has always written them when none were requested
+fn empty_as_null<S: Serializer>(steps: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+    if steps.is_empty() {
+        serializer.serialize_none()
+    } else {
+        steps.serialize(serializer)
+    }
+}
+
+/// `null` (no steps requested) as an empty list
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl QMResult {
+    /// Literals of the cover: the variables each selected implicant fixes, summed
+    pub fn literal_cost(&self) -> usize {
+        self.cover
+            .iter()
+            .map(|&(_, dc_mask)| self.n_variables - dc_mask.count_ones() as usize)
+            .sum()
+    }
+
+    /// On-set of the implemented function: the minterms plus the absorbed don't-cares
+    ///
+    /// Prime implicants are built from minterms and don't-cares only, so the cover never
+    /// reaches into the off-set; this is exactly the set of assignments the cover evaluates
+    /// to 1. Suitable as input for a second minimization or an equivalence check.
+    pub fn completed_on_set(&self) -> Vec<u64> {
+        let mut on_set: Vec<u64> = self.completed_on_set_iter().collect();
+        on_set.sort_unstable();
+        on_set
+    }
+
+    /// Lazy variant of [`completed_on_set`](Self::completed_on_set), term by term
+    ///
+    /// Enumerates the assignments of each cover term in turn, skipping those an earlier
+    /// term already produced, so the on-set itself is never stored; the order is that of
+    /// the cover, not ascending. A term with `k` free variables yields up to `2^k` assignments, so for
+    /// functions with many variables the on-set can be huge.
+    pub fn completed_on_set_iter(&self) -> impl Iterator<Item = u64> + '_ {
+        // Shared with the iterator of every term, which checks the terms before it
+        let terms: Rc<[(u64, u64)]> = self.care_terms().collect();
+        let free = self.variable_mask();
+        (0..terms.len()).flat_map(move |i| {
+            let (values, care) = terms[i];
+            let terms = Rc::clone(&terms);
+            cube_points(values, free & !care)
+                .filter(move |&point| !terms[..i].iter().any(|&(v, c)| point & c == v))
+        })
+    }
+
+    /// Test vectors for every selected implicant, see [`Implicant::sample_vectors`]
+    ///
+    /// Each implicant contributes its minimal and maximal vector plus `per_term` random
+    /// vectors; implicant `i` is sampled with `seed + i`, so results are reproducible.
+    pub fn test_vectors(&self, per_term: usize, seed: u64) -> Vec<TestVector> {
+        let n = self.n_variables;
+        self.cover
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &(values, dc_mask))| {
+                let raw = values as u128 | ((dc_mask as u128) << n);
+                Implicant::<Enc64>::from_raw_encoding(raw, n)
+                    .sample_vectors(per_term, seed.wrapping_add(index as u64))
+                    .into_iter()
+                    .map(move |v| TestVector {
+                        implicant: index,
+                        vector: v.to_u64(),
+                        expected: true,
+                    })
+            })
+            .collect()
+    }
+
+    /// Value of the minimized function on `input` (bit `i` is variable `i`)
+    ///
+    /// True when `input` lies in one of the [`cover`](Self::cover) terms; a complemented
+    /// result covers the off-set, so there the answer is negated. Bits from `n_variables`
+    /// on are ignored.
+    pub fn evaluate(&self, input: u64) -> bool {
+        self.care_terms()
+            .any(|(values, care)| input & care == values)
+            != self.complemented
+    }
+
+    /// [`evaluate`](Self::evaluate) on every input; bit `i` of the result is `inputs[i]`
+    ///
+    /// Works on 64 inputs at a time with one pass per term, which the compiler can
+    /// vectorize.
+    pub fn evaluate_many(&self, inputs: &[u64]) -> BitVec {
+        let terms: Vec<(u64, u64)> = self.care_terms().collect();
+        let words = inputs
+            .chunks(64)
+            .map(|chunk| {
+                let mut hits = [0u64; 64];
+                for &(values, care) in &terms {
+                    for (hit, &input) in hits.iter_mut().zip(chunk) {
+                        *hit |= (input & care == values) as u64;
+                    }
+                }
+                let word = hits
+                    .iter()
+                    .enumerate()
+                    .fold(0, |word, (i, &hit)| word | hit << i);
+                if self.complemented {
+                    !word & (u64::MAX >> (64 - chunk.len()))
+                } else {
+                    word
+                }
+            })
+            .collect();
+        BitVec {
+            words,
+            len: inputs.len(),
+        }
+    }
+
+    /// One bit per variable of the function
+    fn variable_mask(&self) -> u64 {
+        if self.n_variables >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.n_variables) - 1
+        }
+    }
+
+    /// Cover terms as `(values, care mask)`, restricted to the variables of the function
+    fn care_terms(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let variables = self.variable_mask();
+        self.cover.iter().map(move |&(values, dc_mask)| {
+            let care = !dc_mask & variables;
+            (values & care, care)
+        })
+    }
+
+    /// Multi-level view of the minimized cover: its factored form with the literal counts
+    /// before and after factoring and the resulting depth (see [`factor_sop`])
+    ///
+    /// Literals refer to [`variables_used`](Self::variables_used), the names of the
+    /// [`referenced_variables`](Self::referenced_variables).
+    pub fn factored_expression(&self, style: FactorStyle) -> FactoredExpression {
+        let terms = self
+            .cover
+            .iter()
+            .map(|&(values, dc_mask)| {
+                self.referenced_variables
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &bit)| dc_mask >> bit & 1 == 0)
+                    .map(|(var, &bit)| (var, values >> bit & 1 == 1))
+                    .collect()
+            })
+            .collect();
+        let sop = SopExpression {
+            variables: self.variables_used.clone(),
+            terms,
+        };
+        factor_sop(&sop, style)
+    }
+}
+
+/// The assignments `values | sub` for every subset `sub` of `free`, lazily
+fn cube_points(values: u64, free: u64) -> impl Iterator<Item = u64> {
+    let mut next = Some(0u64);
+    std::iter::from_fn(move || {
+        let sub = next?;
+        next = (sub != free).then(|| sub.wrapping_sub(free) & free);
+        Some(values | sub)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qm::{Enc16, QMSolver};
+
+    fn solve(n: usize, minterms: &[u32], dont_cares: &[u32]) -> QMSolver<Enc16> {
+        let mut solver = QMSolver::<Enc16>::new(n);
+        solver.set_minterms(minterms.to_vec());
+        solver.set_dont_cares(dont_cares.to_vec());
+        solver
+    }
+
+    #[test]
+    fn test_evaluate_matches_on_set() {
+        let functions: [(usize, &[u32], &[u32]); 5] = [
+            (3, &[1, 3, 7], &[]),
+            (3, &[1, 3], &[5, 7]),
+            (4, &[0, 1, 2, 5, 6, 7, 8, 9, 10, 14], &[]),
+            (4, &[4, 8, 10, 11, 12, 15], &[9, 14]),
+            (7, &[0, 5, 17, 33, 64, 100, 127], &[1, 4, 101]),
+        ];
+        for (n, minterms, dont_cares) in functions {
+            let result = solve(n, minterms, dont_cares).solve();
+            let on_set = result.completed_on_set();
+            let rows: Vec<u64> = (0..1u64 << n).collect();
+            let many = result.evaluate_many(&rows);
+            assert_eq!(many.len(), rows.len());
+            for &row in &rows {
+                assert_eq!(result.evaluate(row), on_set.contains(&row), "row {}", row);
+                assert_eq!(many.get(row as usize), on_set.contains(&row));
+            }
+            assert_eq!(many.count_ones(), on_set.len());
+        }
+    }
+
+    #[test]
+    fn test_evaluate_complement_and_edge_cases() {
+        let solver = solve(3, &[1, 3, 4, 7], &[2]);
+        let (result, complement) = (solver.solve(), solver.solve_complement());
+        // Both describe the same function outside the don't-care
+        let rows: Vec<u64> = (0..8).filter(|&m| m != 2).collect();
+        assert_eq!(result.evaluate_many(&rows), complement.evaluate_many(&rows));
+        // Bits past the inputs, and past the last variable, are ignored
+        assert_eq!(complement.evaluate_many(&rows).as_words(), &[0b100_1110]);
+        assert_eq!(result.evaluate(7 | 1 << 40), result.evaluate(7));
+
+        // A chunk boundary in the middle of the inputs
+        let inputs: Vec<u64> = (0..100).map(|i| (i * 5) % 8).collect();
+        let many = result.evaluate_many(&inputs);
+        assert!(many.iter().eq(inputs.iter().map(|&m| result.evaluate(m))));
+        assert!(result.evaluate_many(&[]).is_empty());
+
+        // Constant functions
+        assert!(!solve(2, &[], &[]).solve().evaluate(3));
+        assert!(solve(2, &[0, 1, 2, 3], &[]).solve().evaluate(2));
+    }
+
+    #[test]
+    fn test_completed_on_set_iter_is_lazy_per_term() {
+        // A + B: 3 lies in both terms but is produced once
+        let result = solve(2, &[1, 2, 3], &[]).solve();
+        assert_eq!(result.cover.len(), 2);
+        let mut points: Vec<u64> = result.completed_on_set_iter().collect();
+        assert_eq!(points.len(), 3);
+        points.sort_unstable();
+        assert_eq!(points, vec![1, 2, 3]);
+        assert_eq!(points, result.completed_on_set());
+
+        // 40 free variables: only the assignments taken are enumerated
+        let first: Vec<u64> = super::cube_points(1, 0xff_ffff_ffff << 8).take(3).collect();
+        assert_eq!(first, vec![1, 1 | 1 << 8, 1 | 2 << 8]);
+    }
 }
@@ -1,13 +1,28 @@
 //! QMSolver: High-level solver interface for Quine-McCluskey minimization
 
+use super::classic::replace_complements;
+use super::cubes::{
+    cover_complement, cover_primes, cube_contains, cube_intersect, essential_cubes,
+    irredundant_cover,
+};
 use super::encoding::{BitOps, MintermEncoding};
 use super::implicant::{BitState, Implicant};
+use super::memory_budget::{MemoryBudget, SolveError};
 use super::min_cubes::{
     TruthTable, find_prime_implicants, populate_covered_minterms_u64, prime_cubes_to_implicants,
 };
 use super::petricks_method::PetricksMethod;
-use super::qm_result::QMResult;
-use super::quine_mccluskey::QuineMcCluskey;
+use super::qm_result::{BestForm, BestFormResult, BoundedResult, QMResult};
+use super::quine_mccluskey::{
+    QuineMcCluskey, essential_with_witnesses, implicant_bytes, start_timer,
+};
+use super::simd_coverage::CoverageMatrix;
+use super::symmetry;
+use crate::cnf_dnf::CnfReduction;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 /// Algorithm selection for QM minimization
 #[derive(Debug, Clone, Copy, Default)]
@@ -19,14 +34,214 @@ pub enum SolveMethod {
     MinCubes,
 }
 
+/// What the selected cover has to achieve
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Objective {
+    #[default]
+    /// Fewest prime implicants, then fewest literals (see [`TieBreak`])
+    MinimumCover,
+    /// Minimum cover plus consensus terms, so that every pair of adjacent minterms is
+    /// covered by a single product term (no static-1 hazards)
+    HazardFree,
+    /// Among the covers with the fewest prime implicants, the one referencing the fewest
+    /// distinct variables (ties broken by literal count)
+    FewestVariables,
+}
+
+/// How the cover is chosen from the prime implicants when the essentials are not enough
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoverStrategy {
+    #[default]
+    /// Prime implicants in order, each taken when it covers a new minterm (fast, usually
+    /// but not always minimum)
+    Greedy,
+    /// Branch and bound over all covers: always a minimum number of prime implicants
+    Exact,
+}
+
+/// Which cover is reported when several have the minimum number of prime implicants and
+/// of literals
+///
+/// Applies where every minimum cover is enumerated: [`CoverStrategy::Exact`] and
+/// [`Objective::FewestVariables`] (among the covers with the fewest variables and
+/// literals). Each policy falls back to the lexicographic order of the patterns, so the
+/// choice does not depend on the order in which the search finds the covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    #[default]
+    /// The cover whose sorted patterns (`"1X0"`, see [`Implicant::to_pattern`]) compare
+    /// smallest
+    LexicographicSmallestPattern,
+    /// The cover with the fewest literals; the covers left all have the fewest, so this
+    /// is the lexicographic order
+    FewestLiterals,
+    /// The cover whose non-essential prime implicants cover the most minterms that the
+    /// essentials cover as well
+    MostSharedWithEssentials,
+    /// The first cover the search finds (the order of the prime implicants)
+    FirstFound,
+}
+
+/// Electrical polarity of an input signal, applied only when formatting expressions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Polarity {
+    #[default]
+    /// The variable is true when the signal is high
+    ActiveHigh,
+    /// The variable is true when the signal is low (e.g. `nRESET`): a literal of the
+    /// variable is written complemented and vice versa
+    ActiveLow,
+}
+
+/// One problem with a list of variable names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableNameIssue {
+    /// The number of names differs from the number of variables
+    Count { n_variables: usize, n_names: usize },
+    /// Name `index` is empty or only whitespace
+    Empty { index: usize },
+    /// Name `index` repeats name `first`
+    Duplicate {
+        name: String,
+        first: usize,
+        index: usize,
+    },
+    /// Name `index` contains `'` or `!`, which would read as a complemented literal
+    ComplementMark { name: String, index: usize },
+}
+
+impl fmt::Display for VariableNameIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableNameIssue::Count {
+                n_variables,
+                n_names,
+            } => write!(f, "{} names given for {} variables", n_names, n_variables),
+            VariableNameIssue::Empty { index } => write!(f, "name {} is empty", index),
+            VariableNameIssue::Duplicate { name, first, index } => {
+                write!(f, "name {} '{}' repeats name {}", index, name, first)
+            }
+            VariableNameIssue::ComplementMark { name, index } => {
+                write!(f, "name {} '{}' contains ' or !", index, name)
+            }
+        }
+    }
+}
+
+/// Every problem found by [`normalize_variable_names`], in the order of the names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableNamesError {
+    pub issues: Vec<VariableNameIssue>,
+}
+
+impl fmt::Display for VariableNamesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid variable names: ")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VariableNamesError {}
+
+/// `names` with surrounding whitespace trimmed, if they name `n_variables` variables
+///
+/// The names must be non-empty, unique and free of the complement marks `'` and `!`; all
+/// violations are reported together.
+pub fn normalize_variable_names(
+    n_variables: usize,
+    names: &[String],
+) -> Result<Vec<String>, VariableNamesError> {
+    let names: Vec<String> = names.iter().map(|name| name.trim().to_string()).collect();
+    let mut issues = Vec::new();
+    if names.len() != n_variables {
+        issues.push(VariableNameIssue::Count {
+            n_variables,
+            n_names: names.len(),
+        });
+    }
+    for (index, name) in names.iter().enumerate() {
+        if name.is_empty() {
+            issues.push(VariableNameIssue::Empty { index });
+        } else if let Some(first) = names[..index].iter().position(|n| n == name) {
+            issues.push(VariableNameIssue::Duplicate {
+                name: name.clone(),
+                first,
+                index,
+            });
+        }
+        if name.contains(['\'', '!']) {
+            issues.push(VariableNameIssue::ComplementMark {
+                name: name.clone(),
+                index,
+            });
+        }
+    }
+    if issues.is_empty() {
+        Ok(names)
+    } else {
+        Err(VariableNamesError { issues })
+    }
+}
+
+/// Cover picked by `QMSolver::select_cover`, and how it was found
+struct CoverSelection<E: MintermEncoding> {
+    cover: Vec<Implicant<E>>,
+    petrick_invoked: bool,
+    /// Other minimum covers the exact search found; `None` for a greedy cover
+    runner_ups: Option<usize>,
+    /// The exact search stopped at the deadline
+    timed_out: bool,
+    /// Minterm clauses dropped before the exact search
+    clause_reduction: CnfReduction,
+}
+
+impl<E: MintermEncoding> CoverSelection<E> {
+    fn essentials(essentials: &[Implicant<E>]) -> Self {
+        Self {
+            cover: essentials.to_vec(),
+            petrick_invoked: false,
+            runner_ups: Some(0),
+            timed_out: false,
+            clause_reduction: CnfReduction::default(),
+        }
+    }
+}
+
+/// Covered minterms per implicant assumed by [`QMSolver::estimate_memory`]
+const ESTIMATED_COVERED_PER_IMPLICANT: usize = 4;
+
+/// Partial covers kept per step by [`QMSolver::solve_bounded`]
+const BOUNDED_BEAM_WIDTH: usize = 16;
+
+/// Up to this many variables [`QMSolver::solve_complement`] enumerates the off-set; above
+/// it the off-set is derived with cube algebra
+const COMPLEMENT_ENUMERATION_LIMIT: usize = 26;
+
 /// High-level solver for Quine-McCluskey Boolean minimization
 pub struct QMSolver<E: MintermEncoding> {
     variables: usize,
     minterms: Vec<E::Value>,
     dont_cares: Vec<E::Value>,
     variable_names: Vec<String>,
+    polarities: Vec<Polarity>,
     logging_on: bool,
     method: SolveMethod,
+    objective: Objective,
+    cover_strategy: CoverStrategy,
+    tie_break: TieBreak,
+    timeout: Option<Duration>,
+    report_symmetry: bool,
+    max_memory_bytes: Option<usize>,
+    /// Entries [`set_minterms`](Self::set_minterms) and
+    /// [`set_dont_cares`](Self::set_dont_cares) dropped as repeats
+    duplicate_minterms: usize,
+    duplicate_dont_cares: usize,
 }
 
 impl<E: MintermEncoding> QMSolver<E> {
@@ -40,20 +255,45 @@ impl<E: MintermEncoding> QMSolver<E> {
         Self::new_with_variable_names(variables, variable_names)
     }
 
+    /// Create a new solver with custom variable names, checked and trimmed by
+    /// [`normalize_variable_names`]
+    pub fn with_variable_names(
+        variables: usize,
+        names: Vec<String>,
+    ) -> Result<Self, VariableNamesError> {
+        let names = normalize_variable_names(variables, &names)?;
+        Ok(Self::new_with_variable_names(variables, names))
+    }
+
     /// Create a new solver with custom variable names.
     ///
-    /// The number of variables must match the length of `names`.
+    /// The number of variables must match the length of `names`; see
+    /// [`with_variable_names`](Self::with_variable_names) for a checked version.
     pub fn new_with_variable_names(variables: usize, names: Vec<String>) -> Self {
         Self {
             variables,
             minterms: Vec::with_capacity(0),
             dont_cares: Vec::with_capacity(0),
             variable_names: names,
+            polarities: vec![Polarity::ActiveHigh; variables],
             logging_on: false,
             method: SolveMethod::QM,
+            objective: Objective::MinimumCover,
+            cover_strategy: CoverStrategy::Greedy,
+            tie_break: TieBreak::default(),
+            timeout: None,
+            report_symmetry: false,
+            max_memory_bytes: None,
+            duplicate_minterms: 0,
+            duplicate_dont_cares: 0,
         }
     }
 
+    /// Start configuring a solver; see [`QMSolverBuilder`](super::QMSolverBuilder)
+    pub fn builder() -> super::QMSolverBuilder<E> {
+        super::QMSolverBuilder::new()
+    }
+
     pub fn set_logging(&mut self, logging_on: bool) {
         self.logging_on = logging_on;
     }
@@ -62,47 +302,245 @@ impl<E: MintermEncoding> QMSolver<E> {
         self.method = method;
     }
 
+    pub fn set_objective(&mut self, objective: Objective) {
+        self.objective = objective;
+    }
+
+    pub fn set_cover_strategy(&mut self, strategy: CoverStrategy) {
+        self.cover_strategy = strategy;
+    }
+
+    /// Choose the reported cover among several minimum covers (see [`TieBreak`])
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    /// Stop the exact cover search after `timeout`, keeping the best cover found so far
+    ///
+    /// Applies to [`CoverStrategy::Exact`] and [`Objective::FewestVariables`]; a search that
+    /// was cut short is noted in the solution steps. Without a clock (wasm32-unknown-unknown)
+    /// the search runs to the end.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Abort [`try_solve`](Self::try_solve) when the memory tracked at a phase boundary
+    /// (input, each merge pass, coverage matrix) exceeds `max_bytes`
+    ///
+    /// Only the main working sets are counted (their length times the element size), so
+    /// the process uses somewhat more.
+    pub fn set_max_memory(&mut self, max_bytes: Option<usize>) {
+        self.max_memory_bytes = max_bytes;
+    }
+
+    /// Rough upper estimate of the bytes [`solve`](Self::solve) tracks, before solving
+    ///
+    /// Implicants are estimated at `k·(3/2)^n` for `k` minterms and don't-cares over `n`
+    /// variables, which is `3^n` (every cube) for the full function and capped there; the
+    /// coverage matrix has a bit per implicant and minterm.
+    pub fn estimate_memory(&self) -> usize {
+        let value_bytes = std::mem::size_of::<E::Value>();
+        let terms = self.minterms.len() + self.dont_cares.len();
+        let n = self.variables.min(i32::MAX as usize) as i32;
+        let implicants = (terms as f64 * 1.5f64.powi(n)).min(3f64.powi(n));
+        let implicant_bytes = (std::mem::size_of::<Implicant<E>>()
+            + ESTIMATED_COVERED_PER_IMPLICANT * value_bytes) as f64;
+        let matrix_bytes = implicants * self.minterms.len() as f64 / 8.0;
+        let input_bytes = (terms * value_bytes) as f64;
+        // Saturates at usize::MAX
+        (input_bytes + implicants * implicant_bytes + matrix_bytes) as usize
+    }
+
+    /// Report the classes of interchangeable variables in
+    /// [`symmetry_classes`](QMResult::symmetry_classes)
+    ///
+    /// Detection costs `O(n² · minterms)`; the minimization itself is unchanged.
+    pub fn set_report_symmetry(&mut self, report: bool) {
+        self.report_symmetry = report;
+    }
+
+    /// Declare the polarity of variable `index`
+    ///
+    /// Only the formatted expressions change; the minimization and the structured
+    /// [`cover`](QMResult::cover) stay in terms of the logical variable.
+    pub fn set_variable_polarity(&mut self, index: usize, polarity: Polarity) {
+        self.polarities[index] = polarity;
+    }
+
     /// Set the minterms that must be covered by the minimization.
+    ///
+    /// The list may be unsorted and repeat minterms: it is sorted and deduplicated, and the
+    /// number of repeats dropped is reported in
+    /// [`duplicates_removed`](QMResult::duplicates_removed).
     pub fn set_minterms(&mut self, minterms: Vec<E::Value>) {
-        self.minterms = minterms;
+        (self.minterms, self.duplicate_minterms) = sorted_unique(minterms);
     }
 
     /// Set the don't-care minterms that may be used during minimization
     /// but do not need to be covered in the final expression.
+    ///
+    /// Normalized as in [`set_minterms`](Self::set_minterms).
     pub fn set_dont_cares(&mut self, dont_cares: Vec<E::Value>) {
-        self.dont_cares = dont_cares;
+        (self.dont_cares, self.duplicate_dont_cares) = sorted_unique(dont_cares);
     }
 
     /// Default solve using Classic method.
+    ///
+    /// # Panics
+    /// When the cap of [`set_max_memory`](Self::set_max_memory) is exceeded; use
+    /// [`try_solve`](Self::try_solve) to get the error instead.
     pub fn solve(&self) -> QMResult {
-        match self.method {
-            SolveMethod::QM => self.solve_classic(),
-            SolveMethod::MinCubes => self.solve_min_cubes_internal(),
+        self.try_solve().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// [`solve`](Self::solve), failing with [`SolveError::ResourceLimit`] when the memory
+    /// cap is exceeded
+    pub fn try_solve(&self) -> Result<QMResult, SolveError> {
+        self.try_solve_with_primes().map(|(result, _)| result)
+    }
+
+    /// [`solve`](Self::solve) and its [`cover_problem`](Self::cover_problem), from a single
+    /// generation of the prime implicants
+    ///
+    /// # Panics
+    /// As [`solve`](Self::solve).
+    pub fn solve_with_cover_problem(&self) -> (QMResult, PetricksMethod<E>) {
+        let (result, primes) = self
+            .try_solve_with_primes()
+            .unwrap_or_else(|e| panic!("{}", e));
+        (result, PetricksMethod::new(&primes, &self.minterms))
+    }
+
+    /// [`try_solve`](Self::try_solve) and the prime implicants the cover was chosen from
+    fn try_solve_with_primes(&self) -> Result<(QMResult, Vec<Implicant<E>>), SolveError> {
+        let deadline = self.deadline();
+        let mut memory = MemoryBudget::new(self.max_memory_bytes);
+        memory.track("input", self.input_bytes())?;
+        let (mut result, primes) = match self.method {
+            // The min-cubes truth table needs a condition; a constant function is left to QM
+            SolveMethod::MinCubes if self.variables > 0 => {
+                self.solve_min_cubes_internal(deadline, &mut memory)?
+            }
+            _ => self.solve_classic(deadline, &mut memory)?,
+        };
+        if self.report_symmetry {
+            self.add_symmetry_classes(&mut result);
+        }
+        result.peak_memory_bytes = memory.peak();
+        Ok((result, primes))
+    }
+
+    fn input_bytes(&self) -> usize {
+        (self.minterms.len() + self.dont_cares.len()) * std::mem::size_of::<E::Value>()
+    }
+
+    /// Track the coverage matrix over `primes` that Petrick's method builds (none when the
+    /// essentials cover everything)
+    fn track_coverage_matrix(
+        &self,
+        memory: &mut MemoryBudget,
+        primes: &[Implicant<E>],
+        essential_cover_fraction: f64,
+    ) -> Result<(), SolveError> {
+        if essential_cover_fraction == 1.0 {
+            return Ok(());
         }
+        let matrix_bytes = primes.len() * self.minterms.len().div_ceil(8);
+        memory.track(
+            "coverage matrix",
+            self.input_bytes() + implicant_bytes(primes) + matrix_bytes,
+        )
     }
 
-    fn solve_classic(&self) -> QMResult {
+    /// Fill in the symmetry classes of the function, noting the non-trivial ones in the steps
+    fn add_symmetry_classes(&self, result: &mut QMResult) {
+        let to_u64 = |values: &[E::Value]| values.iter().map(|m| m.to_u64()).collect::<Vec<_>>();
+        let classes = symmetry::detect(
+            &to_u64(&self.minterms),
+            &to_u64(&self.dont_cares),
+            self.variables,
+        );
+        for class in classes.iter().filter(|class| class.len() > 1) {
+            let names: Vec<&str> = class
+                .iter()
+                .map(|&i| self.variable_names[i].as_str())
+                .collect();
+            result
+                .solution_steps
+                .push(format!("Symmetric variables: {{{}}}", names.join(", ")));
+        }
+        result.symmetry_classes = classes;
+    }
+
+    fn solve_classic(
+        &self,
+        deadline: Option<Instant>,
+        memory: &mut MemoryBudget,
+    ) -> Result<(QMResult, Vec<Implicant<E>>), SolveError> {
         let mut qm = QuineMcCluskey::<E>::new(self.variables);
         qm.set_logging_on(self.logging_on);
         qm.set_minterms(self.minterms.clone());
         qm.set_dont_cares(self.dont_cares.clone());
+        qm.set_max_memory(self.max_memory_bytes);
 
-        let (essential_pis, prime_implicants) = qm.find_essential_prime_implicants();
-        let petricks = PetricksMethod::<E>::new(&prime_implicants, &self.minterms);
-        let minimal_cover = petricks.find_minimal_cover();
+        let (prime_implicants, essential_pis, witnesses) =
+            qm.find_essential_prime_implicants_with_witnesses();
+        if let Some(e) = qm.memory_error() {
+            return Err(e.clone());
+        }
+        memory.track("prime implicants", qm.peak_memory_bytes())?;
+        let essential_cover_fraction = self.essential_cover_fraction(&essential_pis);
+        self.track_coverage_matrix(memory, &prime_implicants, essential_cover_fraction)?;
+        let selection = self.select_cover(
+            &prime_implicants,
+            &essential_pis,
+            essential_cover_fraction,
+            deadline,
+        );
+        let mut minimal_cover = selection.cover;
+        let hazard_terms = self.add_hazard_terms(&prime_implicants, &mut minimal_cover);
         let minimized_expression = self.format_expression(&minimal_cover);
+        let (used_dont_cares, unused_dont_cares) = self.split_dont_cares(&minimal_cover);
 
-        QMResult {
+        let result = QMResult {
             minimized_expression,
             prime_implicants: self.format_implicants(&prime_implicants),
             essential_prime_implicants: self.format_implicants(&essential_pis),
-            solution_steps: qm.get_solution_steps().to_vec(),
+            solution_steps: [
+                qm.get_solution_steps().to_vec(),
+                self.witness_steps(&essential_pis, &witnesses),
+                Self::timeout_steps(selection.timed_out),
+            ]
+            .concat(),
             cost_original: self.calculate_original_cost(),
             cost_minimized: minimal_cover.len() * 2,
-        }
+            minterms: self.sorted_minterms(),
+            used_dont_cares,
+            unused_dont_cares,
+            n_variables: self.variables,
+            cover: self.cover_cubes(&minimal_cover),
+            hazard_terms: self.format_implicants(&hazard_terms),
+            variables_used: self.variables_used(&minimal_cover),
+            referenced_variables: self.referenced_variables(&minimal_cover),
+            essential_only_cover: !selection.petrick_invoked,
+            essential_cover_fraction,
+            petrick_invoked: selection.petrick_invoked,
+            petrick_runner_ups: selection.runner_ups,
+            petrick_clauses_removed: selection.clause_reduction.total_removed(),
+            essentiality_witnesses: Self::witness_values(&witnesses),
+            complemented: false,
+            symmetry_classes: Vec::new(),
+            peak_memory_bytes: 0,
+            duplicates_removed: self.duplicate_minterms + self.duplicate_dont_cares,
+        };
+        Ok((result, prime_implicants))
     }
 
-    fn solve_min_cubes_internal(&self) -> QMResult {
+    fn solve_min_cubes_internal(
+        &self,
+        deadline: Option<Instant>,
+        memory: &mut MemoryBudget,
+    ) -> Result<(QMResult, Vec<Implicant<E>>), SolveError> {
         // 1. Build truth table from minterms + dont-cares
         let n_conds = self.variables;
         let minterm_bits: Vec<u64> = self.minterms.iter().map(|m| m.to_u64() as u64).collect();
@@ -125,18 +563,26 @@ impl<E: MintermEncoding> QMSolver<E> {
             .cloned()
             .collect();
         populate_covered_minterms_u64(&mut pis, &all_true, n_conds);
+        memory.track(
+            "prime implicants",
+            self.input_bytes() + implicant_bytes(&pis),
+        )?;
 
         // 5. Find essential prime implicants
-        let essential_pis = find_essential_pis(&pis, &self.minterms);
+        let (essential_pis, witnesses) = essential_with_witnesses(&pis, &self.minterms);
+        let essential_cover_fraction = self.essential_cover_fraction(&essential_pis);
+        self.track_coverage_matrix(memory, &pis, essential_cover_fraction)?;
 
-        // 6. Petrick's method for minimal cover
-        let petricks = PetricksMethod::<E>::new(&pis, &self.minterms);
-        let minimal_cover = petricks.find_minimal_cover();
+        // 6. Petrick's method for minimal cover (unless the essentials already cover it)
+        let selection = self.select_cover(&pis, &essential_pis, essential_cover_fraction, deadline);
+        let mut minimal_cover = selection.cover;
+        let hazard_terms = self.add_hazard_terms(&pis, &mut minimal_cover);
 
         // 7. Format result
         let minimized_expression = self.format_expression(&minimal_cover);
+        let (used_dont_cares, unused_dont_cares) = self.split_dont_cares(&minimal_cover);
 
-        let steps = vec![
+        let mut steps = vec![
             format!(
                 "Step 1: Built truth table with {} positive, {} negative rows",
                 tt.pos_rows, tt.neg_rows
@@ -146,20 +592,358 @@ impl<E: MintermEncoding> QMSolver<E> {
                 "Step 3: Identified {} essential prime implicants",
                 essential_pis.len()
             ),
-            format!(
-                "Step 4: Petrick's method selected {} PIs for minimal cover",
-                minimal_cover.len()
-            ),
         ];
+        steps.extend(self.witness_steps(&essential_pis, &witnesses));
+        steps.push(format!(
+            "Step 4: Petrick's method selected {} PIs for minimal cover",
+            minimal_cover.len()
+        ));
+        steps.extend(Self::timeout_steps(selection.timed_out));
 
-        QMResult {
+        let result = QMResult {
             minimized_expression,
             prime_implicants: self.format_implicants(&pis),
             essential_prime_implicants: self.format_implicants(&essential_pis),
             solution_steps: steps,
             cost_original: self.calculate_original_cost(),
             cost_minimized: minimal_cover.len() * 2,
+            minterms: self.sorted_minterms(),
+            used_dont_cares,
+            unused_dont_cares,
+            n_variables: self.variables,
+            cover: self.cover_cubes(&minimal_cover),
+            hazard_terms: self.format_implicants(&hazard_terms),
+            variables_used: self.variables_used(&minimal_cover),
+            referenced_variables: self.referenced_variables(&minimal_cover),
+            essential_only_cover: !selection.petrick_invoked,
+            essential_cover_fraction,
+            petrick_invoked: selection.petrick_invoked,
+            petrick_runner_ups: selection.runner_ups,
+            petrick_clauses_removed: selection.clause_reduction.total_removed(),
+            essentiality_witnesses: Self::witness_values(&witnesses),
+            complemented: false,
+            symmetry_classes: Vec::new(),
+            peak_memory_bytes: 0,
+            duplicates_removed: self.duplicate_minterms + self.duplicate_dont_cares,
+        };
+        Ok((result, pis))
+    }
+
+    /// The covering step of [`solve`](Self::solve): every prime implicant, and the minterms
+    /// to cover
+    ///
+    /// For solving it outside, e.g. with [`PetricksMethod::to_dimacs`].
+    pub fn cover_problem(&self) -> PetricksMethod<E> {
+        let mut qm = QuineMcCluskey::<E>::new(self.variables);
+        qm.set_minterms(self.minterms.clone());
+        qm.set_dont_cares(self.dont_cares.clone());
+        let primes: Vec<Implicant<E>> = qm.prime_implicants_iter().collect();
+        PetricksMethod::new(&primes, &self.minterms)
+    }
+
+    /// Best cover using at most `k` terms
+    ///
+    /// When a minimum cover fits the budget it is returned (the one of [`solve`](Self::solve)
+    /// if that is small enough, else an exact minimum cover); the prime implicants are
+    /// generated once for both. The exact search stops at the [timeout](Self::set_timeout)
+    /// and keeps a cover it found if that fits. Otherwise a beam search over the prime
+    /// implicants picks the `k` terms that cover the most minterms (ties broken by fewer
+    /// literals); the minterms left out are reported.
+    pub fn solve_bounded(&self, k: usize) -> BoundedResult {
+        let mut qm = QuineMcCluskey::<E>::new(self.variables);
+        qm.set_minterms(self.minterms.clone());
+        qm.set_dont_cares(self.dont_cares.clone());
+        let (primes, essentials) = qm.find_essential_prime_implicants();
+
+        // The cover `solve` picks, from the same prime implicants
+        let deadline = self.deadline();
+        let essential_cover_fraction = self.essential_cover_fraction(&essentials);
+        let mut cover = self
+            .select_cover(&primes, &essentials, essential_cover_fraction, deadline)
+            .cover;
+        self.add_hazard_terms(&primes, &mut cover);
+        if cover.len() <= k {
+            return BoundedResult {
+                cover: self.cover_cubes(&cover),
+                expression: self.format_expression(&cover),
+                uncovered_minterms: Vec::new(),
+                covered_fraction: 1.0,
+            };
+        }
+
+        // The greedy cover of `solve` may exceed a budget that an exact minimum cover meets
+        let (covers, _, _) =
+            PetricksMethod::<E>::new(&primes, &self.minterms).find_minimum_covers_until(deadline);
+        if let Some(minimum) = covers.into_iter().next().filter(|cover| cover.len() <= k) {
+            let cover: Vec<Implicant<E>> = primes
+                .iter()
+                .filter(|pi| minimum.iter().any(|m| m.bits == pi.bits))
+                .cloned()
+                .collect();
+            return BoundedResult {
+                cover: self.cover_cubes(&cover),
+                expression: self.format_expression(&cover),
+                uncovered_minterms: Vec::new(),
+                covered_fraction: 1.0,
+            };
+        }
+
+        let minterms = self.sorted_minterms();
+        let mut matrix = CoverageMatrix::new(primes.len(), minterms.len());
+        for (row, pi) in primes.iter().enumerate() {
+            let cube = pi.to_cube();
+            for (col, &m) in minterms.iter().enumerate() {
+                matrix.set(row, col, cube_contains::<E>(cube, E::Value::from_u64(m)));
+            }
+        }
+        let literals: Vec<usize> = primes.iter().map(Implicant::literal_count).collect();
+        let chosen = bounded_beam_search(&matrix, &literals, k);
+        let cover: Vec<Implicant<E>> = chosen.iter().map(|&row| primes[row].clone()).collect();
+        let uncovered_minterms: Vec<u64> = minterms
+            .iter()
+            .enumerate()
+            .filter(|&(col, _)| !chosen.iter().any(|&row| matrix.get(row, col)))
+            .map(|(_, &m)| m)
+            .collect();
+        let covered_fraction = if minterms.is_empty() {
+            1.0
+        } else {
+            (minterms.len() - uncovered_minterms.len()) as f64 / minterms.len() as f64
+        };
+
+        BoundedResult {
+            cover: self.cover_cubes(&cover),
+            expression: self.format_expression(&cover),
+            uncovered_minterms,
+            covered_fraction,
+        }
+    }
+
+    /// Minimized cover of the complement of the function
+    ///
+    /// The off-set (every assignment that is neither a minterm nor a don't-care) is
+    /// minimized with the same don't-cares and method. The result is flagged
+    /// [`complemented`](QMResult::complemented) and its `minterms` are the off-set.
+    ///
+    /// Above [`COMPLEMENT_ENUMERATION_LIMIT`] variables the off-set is never listed: see
+    /// [`solve_complement_by_cubes`](Self::solve_complement_by_cubes).
+    ///
+    /// # Panics
+    /// When the cap of [`set_max_memory`](Self::set_max_memory) is exceeded; use
+    /// [`try_solve_complement`](Self::try_solve_complement) to get the error instead.
+    pub fn solve_complement(&self) -> QMResult {
+        self.try_solve_complement()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// [`solve_complement`](Self::solve_complement), failing with
+    /// [`SolveError::ResourceLimit`] when the memory cap is exceeded
+    pub fn try_solve_complement(&self) -> Result<QMResult, SolveError> {
+        if self.variables > COMPLEMENT_ENUMERATION_LIMIT {
+            return Ok(self.solve_complement_by_cubes());
+        }
+        let off_set = self.off_set_by_enumeration();
+        let complement = Self {
+            variables: self.variables,
+            minterms: off_set,
+            dont_cares: self.dont_cares.clone(),
+            variable_names: self.variable_names.clone(),
+            polarities: self.polarities.clone(),
+            logging_on: self.logging_on,
+            method: self.method,
+            objective: self.objective,
+            cover_strategy: self.cover_strategy,
+            tie_break: self.tie_break,
+            timeout: self.timeout,
+            report_symmetry: self.report_symmetry,
+            max_memory_bytes: self.max_memory_bytes,
+            duplicate_minterms: self.duplicate_minterms,
+            duplicate_dont_cares: self.duplicate_dont_cares,
+        };
+        let mut result = complement.try_solve()?;
+        result.complemented = true;
+        Ok(result)
+    }
+
+    /// Sum of products or product of sums, whichever has fewer literals (the sum of
+    /// products on a tie)
+    ///
+    /// The product of sums is the minimized complement (see
+    /// [`solve_complement`](Self::solve_complement), with the same don't-cares) with each
+    /// term inverted into a clause.
+    ///
+    /// # Panics
+    /// When the cap of [`set_max_memory`](Self::set_max_memory) is exceeded; use
+    /// [`try_solve_best_form`](Self::try_solve_best_form) to get the error instead.
+    pub fn solve_best_form(&self) -> BestFormResult {
+        self.try_solve_best_form()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// [`solve_best_form`](Self::solve_best_form), failing with
+    /// [`SolveError::ResourceLimit`] when the memory cap is exceeded by either solve
+    pub fn try_solve_best_form(&self) -> Result<BestFormResult, SolveError> {
+        self.try_best_form_of(self.try_solve()?)
+    }
+
+    /// [`try_solve_best_form`](Self::try_solve_best_form) with `sop`, the result of
+    /// [`solve`](Self::solve), already at hand: only the complement is minimized
+    pub fn try_best_form_of(&self, sop: QMResult) -> Result<BestFormResult, SolveError> {
+        let complement = self.try_solve_complement()?;
+        let sop_cost = sop.literal_cost();
+        let pos_cost = complement.literal_cost();
+        let pos_expression = self.format_product_of_sums(&complement.cover);
+        let (best, expression) = if pos_cost < sop_cost {
+            (BestForm::Pos, pos_expression.clone())
+        } else {
+            (BestForm::Sop, sop.minimized_expression.clone())
+        };
+        Ok(BestFormResult {
+            best,
+            expression,
+            pos_expression,
+            sop_cost,
+            pos_cost,
+            sop,
+            complement,
+        })
+    }
+
+    /// Product of sums of the function from a cover of its complement: each term becomes
+    /// the clause of its complemented literals, e.g. `A'B'` becomes `(A + B)` and `C'`
+    /// becomes `C`; the clauses are sorted, as the cover comes in no particular order
+    fn format_product_of_sums(&self, complement_cover: &[(u64, u64)]) -> String {
+        let mut clauses = Vec::new();
+        for &(values, dc_mask) in complement_cover {
+            let literals: Vec<String> = (0..self.variables)
+                .filter(|i| (dc_mask >> i) & 1 == 0)
+                .map(|i| {
+                    let active_low = self.polarities.get(i) == Some(&Polarity::ActiveLow);
+                    let plain = ((values >> i) & 1 == 0) != active_low;
+                    format!("{}{}", self.variable_names[i], if plain { "" } else { "'" })
+                })
+                .collect();
+            // A term without literals: the complement is 1, so the function is 0
+            if literals.is_empty() {
+                return "0".to_string();
+            }
+            if literals.len() == 1 {
+                clauses.push(literals[0].clone());
+            } else {
+                clauses.push(format!("({})", literals.join(" + ")));
+            }
+        }
+        if clauses.is_empty() {
+            return "1".to_string();
+        }
+        clauses.sort_unstable();
+        clauses.concat()
+    }
+
+    /// Off-set in ascending order, by testing every assignment
+    fn off_set_by_enumeration(&self) -> Vec<E::Value> {
+        let on_or_dc: HashSet<u64> = self
+            .minterms
+            .iter()
+            .chain(&self.dont_cares)
+            .map(|m| m.to_u64())
+            .collect();
+        (0..1u64 << self.variables)
+            .filter(|m| !on_or_dc.contains(m))
+            .map(E::Value::from_u64)
+            .collect()
+    }
+
+    /// The complement solve with the off-set kept as cubes throughout: the complement of
+    /// the minterms and don't-cares, its prime implicants by iterated consensus and an
+    /// irredundant (not necessarily minimum) cover of them
+    ///
+    /// No point of the off-set is listed, so `minterms` is empty and
+    /// `essential_cover_fraction` is counted from cube sizes. Hazard terms and symmetry
+    /// classes are not computed.
+    fn solve_complement_by_cubes(&self) -> QMResult {
+        let on_or_dc: Vec<E::Value> = self
+            .minterms
+            .iter()
+            .chain(&self.dont_cares)
+            .copied()
+            .collect();
+        let off_set = cover_complement::<E>(&on_or_dc, self.variables);
+        let off_or_dc: Vec<E::Value> = off_set.iter().chain(&self.dont_cares).copied().collect();
+        // Primes inside the don't-cares never help to cover the off-set
+        let primes: Vec<E::Value> = cover_primes::<E>(&off_or_dc)
+            .into_iter()
+            .filter(|&p| off_set.iter().any(|&o| cube_intersect::<E>(p, o).is_some()))
+            .collect();
+        let essentials = essential_cubes::<E>(&primes, &off_set, &self.dont_cares, self.variables);
+        let cover = irredundant_cover::<E>(&primes, &off_set, &self.dont_cares, self.variables);
+
+        let implicants = |cubes: &[E::Value]| -> Vec<Implicant<E>> {
+            cubes
+                .iter()
+                .map(|&cube| Implicant::from_cube(cube, self.variables))
+                .collect()
+        };
+        let minimal_cover = implicants(&cover);
+        let (used_dont_cares, unused_dont_cares) = self.split_dont_cares(&minimal_cover);
+        let essential_only_cover = cover == essentials;
+
+        QMResult {
+            minimized_expression: self.format_expression(&minimal_cover),
+            prime_implicants: self.format_implicants(&implicants(&primes)),
+            essential_prime_implicants: self.format_implicants(&implicants(&essentials)),
+            solution_steps: vec![
+                format!("Off-set as {} disjoint cubes", off_set.len()),
+                format!("{} prime implicants by iterated consensus", primes.len()),
+                format!("Irredundant cover of {} terms", cover.len()),
+            ],
+            // Each off-set cube counts as one term over all variables
+            cost_original: off_set.len() * self.variables,
+            cost_minimized: minimal_cover.len() * 2,
+            minterms: Vec::new(),
+            used_dont_cares,
+            unused_dont_cares,
+            n_variables: self.variables,
+            cover: self.cover_cubes(&minimal_cover),
+            hazard_terms: Vec::new(),
+            variables_used: self.variables_used(&minimal_cover),
+            referenced_variables: self.referenced_variables(&minimal_cover),
+            essential_only_cover,
+            essential_cover_fraction: if essential_only_cover {
+                1.0
+            } else {
+                self.cube_cover_fraction(&essentials, &off_set)
+            },
+            petrick_invoked: false,
+            petrick_runner_ups: Some(0),
+            petrick_clauses_removed: 0,
+            essentiality_witnesses: Vec::new(),
+            complemented: true,
+            symmetry_classes: Vec::new(),
+            peak_memory_bytes: 0,
+            duplicates_removed: self.duplicate_minterms + self.duplicate_dont_cares,
+        }
+    }
+
+    /// Fraction of the points of the disjoint cubes `on_set` that lie in `cover`
+    fn cube_cover_fraction(&self, cover: &[E::Value], on_set: &[E::Value]) -> f64 {
+        let size = |cube: E::Value| ((cube >> E::DK_OFFSET).count_ones() as f64).exp2();
+        let total: f64 = on_set.iter().map(|&cube| size(cube)).sum();
+        if total == 0.0 {
+            return 1.0;
         }
+        // Both sides are disjoint, so are their intersections
+        let outside = cover_complement::<E>(cover, self.variables);
+        let uncovered: f64 = on_set
+            .iter()
+            .flat_map(|&o| {
+                outside
+                    .iter()
+                    .filter_map(move |&c| cube_intersect::<E>(o, c))
+            })
+            .map(size)
+            .sum();
+        1.0 - uncovered / total
     }
 
     fn format_expression(&self, implicants: &[Implicant<E>]) -> String {
@@ -175,11 +959,10 @@ impl<E: MintermEncoding> QMSolver<E> {
 
     fn format_single_implicant(&self, implicant: &Implicant<E>) -> String {
         let mut result = String::new();
-        for i in 0..self.variables {
-            match implicant.get_bit(i) {
-                BitState::Zero => result.push_str(&format!("{}'", self.variable_names[i])),
-                BitState::One => result.push_str(&self.variable_names[i]),
-                BitState::DontCare => {}
+        for (i, plain) in self.literals(implicant) {
+            result.push_str(&self.variable_names[i]);
+            if !plain {
+                result.push('\'');
             }
         }
         if result.is_empty() {
@@ -189,6 +972,62 @@ impl<E: MintermEncoding> QMSolver<E> {
         }
     }
 
+    /// A cover (e.g. [`QMResult::cover`]) as a Verilog expression such as
+    /// `(~a & b) | (a & c)`, with the variable polarities applied
+    pub fn format_verilog(&self, cover: &[(u64, u64)]) -> String {
+        if cover.is_empty() {
+            return "1'b0".to_string();
+        }
+        let terms: Vec<String> = cover
+            .iter()
+            .map(|&(values, dc_mask)| {
+                let raw = (0..self.variables).fold(E::Value::zero(), |raw, i| {
+                    if (dc_mask >> i) & 1 == 1 {
+                        raw.set_bit(i).set_bit(i + self.variables)
+                    } else if (values >> i) & 1 == 1 {
+                        raw.set_bit(i)
+                    } else {
+                        raw
+                    }
+                });
+                let implicant = Implicant::<E>::from_raw_encoding(raw, self.variables);
+                let literals: Vec<String> = self
+                    .literals(&implicant)
+                    .map(|(i, plain)| {
+                        let name = &self.variable_names[i];
+                        if plain {
+                            name.clone()
+                        } else {
+                            format!("~{}", name)
+                        }
+                    })
+                    .collect();
+                match literals.len() {
+                    0 => "1'b1".to_string(),
+                    1 => literals[0].clone(),
+                    _ => format!("({})", literals.join(" & ")),
+                }
+            })
+            .collect();
+        terms.join(" | ")
+    }
+
+    /// The literals of `implicant` as `(variable, written without complement)`, with the
+    /// polarity of active-low variables applied
+    fn literals<'a>(
+        &'a self,
+        implicant: &'a Implicant<E>,
+    ) -> impl Iterator<Item = (usize, bool)> + 'a {
+        (0..self.variables).filter_map(move |i| {
+            let active_low = self.polarities.get(i) == Some(&Polarity::ActiveLow);
+            match implicant.get_bit(i) {
+                BitState::One => Some((i, !active_low)),
+                BitState::Zero => Some((i, active_low)),
+                BitState::DontCare => None,
+            }
+        })
+    }
+
     fn format_implicants(&self, implicants: &[Implicant<E>]) -> Vec<String> {
         implicants
             .iter()
@@ -196,46 +1035,1046 @@ impl<E: MintermEncoding> QMSolver<E> {
             .collect()
     }
 
+    fn sorted_minterms(&self) -> Vec<u64> {
+        let mut minterms: Vec<u64> = self.minterms.iter().map(|&m| m.to_u64()).collect();
+        minterms.sort_unstable();
+        minterms.dedup();
+        minterms
+    }
+
+    /// Split the don't-cares into those covered by `cover` and the rest (both sorted)
+    fn split_dont_cares(&self, cover: &[Implicant<E>]) -> (Vec<u64>, Vec<u64>) {
+        let mut dont_cares: Vec<E::Value> = self.dont_cares.clone();
+        dont_cares.sort_unstable();
+        dont_cares.dedup();
+
+        let (used, unused): (Vec<E::Value>, Vec<E::Value>) = dont_cares
+            .into_iter()
+            .partition(|&dc| cover.iter().any(|imp| imp.covers_minterm(dc)));
+        (
+            used.into_iter().map(BitOps::to_u64).collect(),
+            unused.into_iter().map(BitOps::to_u64).collect(),
+        )
+    }
+
+    /// Fraction of the (distinct) minterms covered by `essentials`; 1.0 without minterms
+    fn essential_cover_fraction(&self, essentials: &[Implicant<E>]) -> f64 {
+        let minterms: HashSet<E::Value> = self.minterms.iter().copied().collect();
+        if minterms.is_empty() {
+            return 1.0;
+        }
+        let cubes: Vec<E::Value> = essentials.iter().map(Implicant::to_cube).collect();
+        let covered = minterms
+            .iter()
+            .filter(|&&m| cubes.iter().any(|&c| cube_contains::<E>(c, m)))
+            .count();
+        covered as f64 / minterms.len() as f64
+    }
+
+    /// Cover chosen according to the objective, and how it was found
+    ///
+    /// When the essential prime implicants cover every minterm (`essential_cover_fraction`
+    /// is 1) they are the unique minimum cover, and Petrick's method is skipped. The
+    /// runner-ups are all other covers with the minimum number of prime implicants, before
+    /// the objective, the literal count or the [`TieBreak`] narrows them down; the greedy
+    /// cover has none.
+    fn select_cover(
+        &self,
+        primes: &[Implicant<E>],
+        essentials: &[Implicant<E>],
+        essential_cover_fraction: f64,
+        deadline: Option<Instant>,
+    ) -> CoverSelection<E> {
+        if essential_cover_fraction == 1.0 {
+            return CoverSelection::essentials(essentials);
+        }
+        let petricks = PetricksMethod::<E>::new(primes, &self.minterms);
+        if self.objective != Objective::FewestVariables
+            && self.cover_strategy == CoverStrategy::Greedy
+        {
+            return CoverSelection {
+                cover: petricks.find_minimal_cover(),
+                petrick_invoked: true,
+                runner_ups: None,
+                timed_out: false,
+                clause_reduction: CnfReduction::default(),
+            };
+        }
+        let (covers, timed_out, clause_reduction) = petricks.find_minimum_covers_until(deadline);
+        let runner_ups = covers.len().saturating_sub(1);
+        let key = |cover: &[Implicant<E>]| {
+            let used = match self.objective {
+                Objective::FewestVariables => self.referenced_variables(cover).len(),
+                _ => 0,
+            };
+            let literals: usize = cover.iter().map(Implicant::literal_count).sum();
+            (used, literals)
+        };
+        let best = covers.iter().map(|cover| key(cover)).min();
+        let covers = covers
+            .into_iter()
+            .filter(|cover| Some(key(cover)) == best)
+            .collect();
+        CoverSelection {
+            cover: self.break_tie(covers, essentials),
+            petrick_invoked: true,
+            runner_ups: Some(runner_ups),
+            timed_out,
+            clause_reduction,
+        }
+    }
+
+    /// The cover of `covers` chosen by the [`TieBreak`] policy
+    fn break_tie(
+        &self,
+        covers: Vec<Vec<Implicant<E>>>,
+        essentials: &[Implicant<E>],
+    ) -> Vec<Implicant<E>> {
+        let patterns = |cover: &[Implicant<E>]| {
+            let mut patterns: Vec<String> = cover.iter().map(Implicant::to_pattern).collect();
+            patterns.sort_unstable();
+            patterns
+        };
+        let mut covers = covers.into_iter();
+        let chosen = match self.tie_break {
+            TieBreak::FirstFound => covers.next(),
+            TieBreak::LexicographicSmallestPattern => covers.min_by_key(|cover| patterns(cover)),
+            TieBreak::FewestLiterals => covers.min_by_key(|cover| {
+                let literals: usize = cover.iter().map(Implicant::literal_count).sum();
+                (literals, patterns(cover))
+            }),
+            TieBreak::MostSharedWithEssentials => covers.min_by_key(|cover| {
+                let shared = self.shared_with_essentials(cover, essentials);
+                (Reverse(shared), patterns(cover))
+            }),
+        };
+        chosen.unwrap_or_default()
+    }
+
+    /// Minterms covered both by an essential and by a non-essential implicant of `cover`
+    fn shared_with_essentials(&self, cover: &[Implicant<E>], essentials: &[Implicant<E>]) -> usize {
+        let essential_cubes: Vec<E::Value> = essentials.iter().map(Implicant::to_cube).collect();
+        let others: Vec<&Implicant<E>> = cover
+            .iter()
+            .filter(|imp| !essentials.iter().any(|e| e.bits == imp.bits))
+            .collect();
+        self.minterms
+            .iter()
+            .filter(|&&m| essential_cubes.iter().any(|&c| cube_contains::<E>(c, m)))
+            .filter(|&&m| others.iter().any(|imp| imp.covers_minterm(m)))
+            .count()
+    }
+
+    /// When the cover search has to stop, if a timeout is set and there is a clock
+    fn deadline(&self) -> Option<Instant> {
+        Some(start_timer()? + self.timeout?)
+    }
+
+    fn timeout_steps(timed_out: bool) -> Vec<String> {
+        if timed_out {
+            vec!["Cover search stopped at the timeout; the cover may not be minimum".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// One line per essential implicant naming the minterms only it covers
+    fn witness_steps(
+        &self,
+        essentials: &[Implicant<E>],
+        witnesses: &[Vec<E::Value>],
+    ) -> Vec<String> {
+        essentials
+            .iter()
+            .zip(witnesses)
+            .map(|(imp, witness)| {
+                let list: Vec<String> = witness.iter().map(|m| m.to_u64().to_string()).collect();
+                format!(
+                    "   {} is essential: the only prime implicant covering {} {}",
+                    self.format_single_implicant(imp),
+                    if witness.len() == 1 {
+                        "minterm"
+                    } else {
+                        "minterms"
+                    },
+                    list.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    fn witness_values(witnesses: &[Vec<E::Value>]) -> Vec<(usize, Vec<u64>)> {
+        witnesses
+            .iter()
+            .enumerate()
+            .map(|(i, witness)| (i, witness.iter().map(|m| m.to_u64()).collect()))
+            .collect()
+    }
+
+    /// Indices of the variables that appear as a literal in `cover` (ascending)
+    fn referenced_variables(&self, cover: &[Implicant<E>]) -> Vec<usize> {
+        (0..self.variables)
+            .filter(|&i| cover.iter().any(|imp| imp.get_bit(i) != BitState::DontCare))
+            .collect()
+    }
+
+    fn variables_used(&self, cover: &[Implicant<E>]) -> Vec<String> {
+        self.referenced_variables(cover)
+            .into_iter()
+            .map(|i| self.variable_names[i].clone())
+            .collect()
+    }
+
+    /// With [`Objective::HazardFree`], extend `cover` with prime implicants until every pair
+    /// of adjacent minterms lies in a single term; returns the added terms
+    ///
+    /// Such a prime implicant always exists: the pair itself is an implicant, so some prime
+    /// implicant contains it. The largest one (fewest literals) is taken.
+    fn add_hazard_terms(
+        &self,
+        primes: &[Implicant<E>],
+        cover: &mut Vec<Implicant<E>>,
+    ) -> Vec<Implicant<E>> {
+        let mut added = Vec::new();
+        if self.objective != Objective::HazardFree {
+            return added;
+        }
+
+        let minterms: HashSet<E::Value> = self.minterms.iter().copied().collect();
+        let mut sorted: Vec<E::Value> = minterms.iter().copied().collect();
+        sorted.sort_unstable();
+        let mut cover_cubes: Vec<E::Value> = cover.iter().map(Implicant::to_cube).collect();
+
+        for &m in &sorted {
+            for bit in (0..self.variables).filter(|&bit| !m.get_bit(bit)) {
+                let neighbour = m.set_bit(bit);
+                if !minterms.contains(&neighbour) {
+                    continue;
+                }
+                let pair = replace_complements::<E>(m, neighbour);
+                if cover_cubes.iter().any(|&c| cube_contains::<E>(c, pair)) {
+                    continue;
+                }
+                let consensus = primes
+                    .iter()
+                    .filter(|imp| cube_contains::<E>(imp.to_cube(), pair))
+                    .max_by_key(|imp| {
+                        (0..self.variables)
+                            .filter(|&i| imp.get_bit(i) == BitState::DontCare)
+                            .count()
+                    })
+                    .expect("adjacent minterms are always covered by a prime implicant");
+                cover_cubes.push(consensus.to_cube());
+                cover.push(consensus.clone());
+                added.push(consensus.clone());
+            }
+        }
+        added
+    }
+
+    /// `(values, don't-care mask)` of each implicant
+    fn cover_cubes(&self, cover: &[Implicant<E>]) -> Vec<(u64, u64)> {
+        cover
+            .iter()
+            .map(|imp| {
+                (0..self.variables).fold((0u64, 0u64), |(values, dc), i| match imp.get_bit(i) {
+                    BitState::One => (values | (1 << i), dc),
+                    BitState::Zero => (values, dc),
+                    BitState::DontCare => (values, dc | (1 << i)),
+                })
+            })
+            .collect()
+    }
+
     fn calculate_original_cost(&self) -> usize {
         self.minterms.len() * self.variables
     }
 }
 
-/// Find essential prime implicants — those that uniquely cover at least one minterm.
+/// `values` sorted and deduplicated, and the number of entries dropped
+fn sorted_unique<T: Ord>(mut values: Vec<T>) -> (Vec<T>, usize) {
+    let len = values.len();
+    values.sort_unstable();
+    values.dedup();
+    let removed = len - values.len();
+    (values, removed)
+}
+
+/// Rows of `matrix` (at most `k`) covering the most columns, found by beam search
 ///
-/// A prime implicant is essential if there exists at least one minterm that it
-/// covers exclusively (no other prime implicant covers that minterm).
-/// Essential prime implicants MUST be included in any minimal cover.
-fn find_essential_pis<E: MintermEncoding>(
-    pis: &[Implicant<E>],
-    minterms: &[E::Value],
-) -> Vec<Implicant<E>> {
-    // Build coverage map: minterm -> list of PIs that cover it
-    let mut coverage_map: std::collections::HashMap<E::Value, Vec<usize>> =
-        std::collections::HashMap::new();
-    for minterm in minterms {
-        for (pi_idx, pi) in pis.iter().enumerate() {
-            if pi.covers_minterm(*minterm) {
-                coverage_map.entry(*minterm).or_default().push(pi_idx);
+/// Each step extends every kept partial cover by one row; the [`BOUNDED_BEAM_WIDTH`] best
+/// extensions (most columns covered, then fewest literals) survive. Returned rows ascend.
+fn bounded_beam_search(matrix: &CoverageMatrix, literals: &[usize], k: usize) -> Vec<usize> {
+    // (rows, columns covered, literal count)
+    type State = (Vec<usize>, Vec<bool>, usize);
+    let score = |state: &State| {
+        let covered = state.1.iter().filter(|&&c| c).count();
+        (std::cmp::Reverse(covered), state.2)
+    };
+
+    let mut beam: Vec<State> = vec![(Vec::new(), vec![false; matrix.num_cols()], 0)];
+    let mut best = beam[0].clone();
+    for _ in 0..k {
+        let mut next: Vec<State> = Vec::new();
+        for (rows, covered, cost) in &beam {
+            for row in (0..matrix.num_rows()).filter(|row| !rows.contains(row)) {
+                let mut grown = covered.clone();
+                let mut gain = 0;
+                for (col, c) in grown.iter_mut().enumerate() {
+                    if !*c && matrix.get(row, col) {
+                        *c = true;
+                        gain += 1;
+                    }
+                }
+                if gain == 0 {
+                    continue;
+                }
+                let mut rows = rows.clone();
+                rows.push(row);
+                rows.sort_unstable();
+                if next.iter().all(|state| state.0 != rows) {
+                    next.push((rows, grown, cost + literals[row]));
+                }
             }
         }
+        if next.is_empty() {
+            break;
+        }
+        next.sort_by_key(|state| (score(state), state.0.clone()));
+        next.truncate(BOUNDED_BEAM_WIDTH);
+        if score(&next[0]) < score(&best) {
+            best = next[0].clone();
+        }
+        beam = next;
     }
+    best.0
+}
 
-    let mut essential_indices = std::collections::HashSet::new();
-    for (_minterm, covering_pis) in &coverage_map {
-        if covering_pis.len() == 1 {
-            essential_indices.insert(covering_pis[0]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::encoding::{Enc16, Enc32};
+
+    fn solve_hazard_free(minterms: &[u32], variables: usize) -> QMResult {
+        let mut solver = QMSolver::<Enc16>::new(variables);
+        solver.set_minterms(minterms.to_vec());
+        solver.set_objective(Objective::HazardFree);
+        solver.solve()
+    }
+
+    /// Every pair of adjacent minterms must lie in a single term of the cover
+    fn assert_hazard_free(result: &QMResult, minterms: &[u32]) {
+        for &a in minterms {
+            for &b in minterms.iter().filter(|&&b| (a ^ b).count_ones() == 1) {
+                let (a, b) = (a as u64, b as u64);
+                assert!(
+                    result
+                        .cover
+                        .iter()
+                        .any(|&(values, dc)| a & !dc == values && b & !dc == values),
+                    "{} and {} are not covered by a single term in {}",
+                    a,
+                    b,
+                    result.minimized_expression
+                );
+            }
         }
     }
 
-    pis.iter()
-        .enumerate()
-        .filter_map(|(idx, pi)| {
-            if essential_indices.contains(&idx) {
-                Some(pi.clone())
-            } else {
-                None
+    #[test]
+    fn test_hazard_free_adds_consensus_term() {
+        // F = AC' + BC has a static-1 hazard between 3 and 7; the consensus term AB fixes it
+        let minterms = [1, 3, 6, 7];
+        let result = solve_hazard_free(&minterms, 3);
+        assert_eq!(result.hazard_terms, vec!["AB"]);
+        assert!(result.minimized_expression.ends_with(" + AB"));
+        assert_hazard_free(&result, &minterms);
+    }
+
+    #[test]
+    fn test_hazard_free_textbook_example() {
+        let minterms = [1, 3, 5, 7, 8, 9, 12, 13];
+        let result = solve_hazard_free(&minterms, 4);
+        assert!(!result.hazard_terms.is_empty());
+        assert_hazard_free(&result, &minterms);
+
+        // The minimum cover alone is not hazard free
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(minterms.to_vec());
+        let minimum = solver.solve();
+        assert!(minimum.hazard_terms.is_empty());
+        assert_eq!(
+            minimum.cover.len() + result.hazard_terms.len(),
+            result.cover.len()
+        );
+    }
+
+    #[test]
+    fn test_fewest_variables_objective() {
+        // On-set {1, 2}, don't-cares {0, 5, 6}: every minimum cover has two terms and four
+        // literals, but only AB' + A'B avoids C
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![1, 2]);
+        solver.set_dont_cares(vec![0, 5, 6]);
+        solver.set_objective(Objective::FewestVariables);
+        let result = solver.solve();
+
+        assert_eq!(result.variables_used, vec!["A", "B"]);
+        assert_eq!(result.cover.len(), 2);
+        let mut terms: Vec<&str> = result.minimized_expression.split(" + ").collect();
+        terms.sort_unstable();
+        assert_eq!(terms, vec!["A'B", "AB'"]);
+    }
+
+    #[test]
+    fn test_variables_used_reported_for_minimum_cover() {
+        // Σ(1, 3) over A, B: the function is A
+        let mut solver = QMSolver::<Enc16>::new(2);
+        solver.set_minterms(vec![1, 3]);
+        let result = solver.solve();
+        assert_eq!(result.minimized_expression, "A");
+        assert_eq!(result.variables_used, vec!["A"]);
+        assert_eq!(result.referenced_variables, vec![0]);
+    }
+
+    #[test]
+    fn test_referenced_variables_skip_an_irrelevant_input() {
+        // f = A'C + AB' over A, B, C, D: every minterm comes with both values of D
+        let f = |m: u32| (m & 1 == 0 && m & 4 != 0) || (m & 1 != 0 && m & 2 == 0);
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms((0..16).filter(|&m| f(m)).collect());
+        let result = solver.solve();
+        assert_eq!(result.referenced_variables, vec![0, 1, 2]);
+        assert_eq!(result.variables_used, vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_essential_only_cover() {
+        // Σ(1, 3, 5, 6, 7) = A + BC; both terms are essential
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![1, 3, 5, 6, 7]);
+        let result = solver.solve();
+
+        assert!(result.essential_only_cover);
+        assert!(!result.petrick_invoked);
+        assert_eq!(result.petrick_runner_ups, Some(0));
+        assert_eq!(result.essential_cover_fraction, 1.0);
+        assert_eq!(result.cover.len(), 2);
+    }
+
+    #[test]
+    fn test_cyclic_core_needs_petrick() {
+        // Σ(0, 1, 5, 7, 8, 10, 14, 15): every minterm is covered by two prime implicants
+        for method in [SolveMethod::QM, SolveMethod::MinCubes] {
+            let mut solver = QMSolver::<Enc16>::new(4);
+            solver.set_method(method);
+            solver.set_minterms(vec![0, 1, 5, 7, 8, 10, 14, 15]);
+            let result = solver.solve();
+
+            assert!(result.essential_prime_implicants.is_empty());
+            assert!(!result.essential_only_cover);
+            assert!(result.petrick_invoked);
+            assert_eq!(result.essential_cover_fraction, 0.0);
+            // The greedy cover does not enumerate the other minimum covers
+            assert_eq!(result.petrick_runner_ups, None);
+
+            // Two covers of four terms: the chosen one and a runner-up
+            solver.set_cover_strategy(CoverStrategy::Exact);
+            let result = solver.solve();
+            assert_eq!(result.cover.len(), 4);
+            assert_eq!(result.petrick_runner_ups, Some(1));
+        }
+    }
+
+    #[test]
+    fn test_petrick_drops_duplicate_clauses() {
+        // The cyclic core Σ(0, 1, 2, 5, 6, 7) plus 19 and 27, which only 1X011 covers: their
+        // clauses are the same
+        let mut solver = QMSolver::<Enc16>::new(5);
+        solver.set_cover_strategy(CoverStrategy::Exact);
+        solver.set_minterms(vec![0, 1, 2, 5, 6, 7, 19, 27]);
+        let result = solver.solve();
+        assert!(result.petrick_invoked);
+        assert_eq!(result.cover.len(), 4);
+        assert_eq!(result.petrick_clauses_removed, 1);
+
+        solver.set_cover_strategy(CoverStrategy::Greedy);
+        assert_eq!(solver.solve().petrick_clauses_removed, 0);
+    }
+
+    #[test]
+    fn test_cover_problem_matches_solve() {
+        // Σ(0, 1, 5, 7, 8, 10, 14, 15): eight prime implicants in a cycle
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 5, 7, 8, 10, 14, 15]);
+        let problem = solver.cover_problem();
+        assert!(problem.to_dimacs().contains("p cnf 8 8\n"));
+
+        let cover = problem.find_minimum_covers().remove(0);
+        assert_eq!(cover.len(), solver.solve().cover.len());
+
+        // The same problem from the prime implicants of the solve
+        let (result, reused) = solver.solve_with_cover_problem();
+        assert_eq!(reused.to_dimacs(), problem.to_dimacs());
+        assert_eq!(result.cover, solver.solve().cover);
+    }
+
+    #[test]
+    fn test_solve_bounded_at_minimum_is_exact() {
+        // Σ(1, 3, 5, 6, 7) = A + BC
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![1, 3, 5, 6, 7]);
+        let exact = solver.solve();
+        let bounded = solver.solve_bounded(exact.cover.len());
+
+        assert_eq!(bounded.cover, exact.cover);
+        assert_eq!(bounded.expression, exact.minimized_expression);
+        assert!(bounded.uncovered_minterms.is_empty());
+        assert_eq!(bounded.covered_fraction, 1.0);
+
+        // A cyclic core with minimum covers of 4 terms (the greedy cover may need 5)
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 5, 7, 8, 10, 14, 15]);
+        let bounded = solver.solve_bounded(4);
+        assert_eq!(bounded.cover.len(), 4);
+        assert!(bounded.uncovered_minterms.is_empty());
+        assert_eq!(bounded.covered_fraction, 1.0);
+
+        // The cover `solve` picks under the solver's settings
+        for tie_break in [TieBreak::FirstFound, TieBreak::MostSharedWithEssentials] {
+            solver.set_cover_strategy(CoverStrategy::Exact);
+            solver.set_tie_break(tie_break);
+            let exact = solver.solve();
+            let bounded = solver.solve_bounded(4);
+            assert_eq!(bounded.cover, exact.cover);
+            assert_eq!(bounded.expression, exact.minimized_expression);
+        }
+    }
+
+    #[test]
+    fn test_solve_bounded_below_minimum() {
+        // Σ(0, 1, 6) = B'C' + A'BC: one term keeps the larger cube and drops minterm 6
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![0, 1, 6]);
+        let bounded = solver.solve_bounded(1);
+
+        assert_eq!(bounded.cover, vec![(0b000, 0b001)]);
+        assert_eq!(bounded.expression, "B'C'");
+        assert_eq!(bounded.uncovered_minterms, vec![6]);
+        assert!((bounded.covered_fraction - 2.0 / 3.0).abs() < 1e-12);
+
+        // Σ(1, 3, 5, 6, 7) = A + BC: A alone leaves out 6
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![1, 3, 5, 6, 7]);
+        let bounded = solver.solve_bounded(1);
+        assert_eq!(bounded.expression, "A");
+        assert_eq!(bounded.uncovered_minterms, vec![6]);
+        assert_eq!(bounded.covered_fraction, 0.8);
+
+        let empty = solver.solve_bounded(0);
+        assert!(empty.cover.is_empty());
+        assert_eq!(empty.uncovered_minterms, vec![1, 3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_solve_bounded_past_timeout() {
+        // With no time for the exact search, the beam search still fills the budget
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 5, 7, 8, 10, 14, 15]);
+        solver.set_timeout(Some(Duration::ZERO));
+        for k in 1..=4 {
+            let bounded = solver.solve_bounded(k);
+            assert!(bounded.cover.len() <= k);
+            assert_eq!(
+                bounded.uncovered_minterms.is_empty(),
+                bounded.covered_fraction == 1.0
+            );
+        }
+        assert_eq!(solver.solve_bounded(4).covered_fraction, 1.0);
+    }
+
+    #[test]
+    fn test_essentiality_witnesses() {
+        // Σ(0, 1, 2, 5, 6, 7, 8, 9, 10, 14) = B'C' + A'B + ACD'
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 2, 5, 6, 7, 8, 9, 10, 14]);
+        let result = solver.solve();
+
+        let mut witnesses: Vec<(&str, Vec<u64>)> = result
+            .essentiality_witnesses
+            .iter()
+            .map(|(i, minterms)| {
+                (
+                    result.essential_prime_implicants[*i].as_str(),
+                    minterms.clone(),
+                )
+            })
+            .collect();
+        witnesses.sort();
+        // 14 = A'BCD is only in A'B, 9 = AB'C'D only in B'C'
+        assert_eq!(witnesses, vec![("A'B", vec![14]), ("B'C'", vec![9])]);
+        assert!(result.solution_steps.contains(
+            &"   A'B is essential: the only prime implicant covering minterm 14".to_string()
+        ));
+    }
+
+    #[test]
+    fn test_active_low_variable_formatting() {
+        // Σ(2, 5, 6, 7) = A'B + AC, with A the active-low signal nRESET
+        let names = ["nRESET", "B", "C"].iter().map(|s| s.to_string()).collect();
+        let mut solver = QMSolver::<Enc16>::new_with_variable_names(3, names);
+        solver.set_minterms(vec![2, 5, 6, 7]);
+        let sorted = |mut terms: Vec<String>| {
+            terms.sort();
+            terms
+        };
+
+        let result = solver.solve();
+        let verilog = solver.format_verilog(&result.cover);
+        let verilog_terms = sorted(verilog.split(" | ").map(String::from).collect());
+        assert_eq!(verilog_terms, vec!["(nRESET & C)", "(~nRESET & B)"]);
+
+        solver.set_variable_polarity(0, Polarity::ActiveLow);
+        let low = solver.solve();
+        assert_eq!(
+            sorted(low.prime_implicants.clone()),
+            vec!["BC", "nRESET'C", "nRESETB"]
+        );
+        let terms = sorted(
+            low.minimized_expression
+                .split(" + ")
+                .map(String::from)
+                .collect(),
+        );
+        assert_eq!(terms, vec!["nRESET'C", "nRESETB"]);
+        let verilog = solver.format_verilog(&low.cover);
+        let verilog_terms = sorted(verilog.split(" | ").map(String::from).collect());
+        assert_eq!(verilog_terms, vec!["(nRESET & B)", "(~nRESET & C)"]);
+
+        // The structured cover stays logical
+        assert_eq!(sorted_cover(&low.cover), sorted_cover(&result.cover));
+        assert!(low.cover.contains(&(0b010, 0b100)));
+        assert_eq!(solver.format_verilog(&[]), "1'b0");
+        assert_eq!(solver.format_verilog(&[(0, 0b111)]), "1'b1");
+    }
+
+    fn sorted_cover(cover: &[(u64, u64)]) -> Vec<(u64, u64)> {
+        let mut cover = cover.to_vec();
+        cover.sort_unstable();
+        cover
+    }
+
+    #[test]
+    fn test_solve_complement_partitions_rows() {
+        use crate::qm::random::test_seed;
+        use crate::{assert_eq_seeded, assert_seeded};
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let seed = test_seed(3);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for n in 0..=5usize {
+            for _ in 0..10 {
+                // Each row is a minterm, a don't-care or in the off-set
+                let states: Vec<u8> = (0..1u64 << n).map(|_| rng.random_range(0..3)).collect();
+                let rows_with = |state: u8| -> Vec<u32> {
+                    (0..states.len() as u32)
+                        .filter(|&m| states[m as usize] == state)
+                        .collect()
+                };
+                let mut solver = QMSolver::<Enc16>::new(n);
+                solver.set_minterms(rows_with(1));
+                solver.set_dont_cares(rows_with(2));
+
+                let result = solver.solve();
+                let complement = solver.solve_complement();
+                assert_seeded!(seed, !result.complemented);
+                assert_seeded!(seed, complement.complemented);
+                let off_set: Vec<u64> = rows_with(0).into_iter().map(u64::from).collect();
+                assert_eq_seeded!(seed, complement.minterms, off_set);
+                let by_cubes = solver.solve_complement_by_cubes();
+                assert_seeded!(seed, by_cubes.complemented);
+
+                // Exactly one of the two covers holds on every row that is not a don't-care,
+                // so both results evaluate to the same function there
+                for m in (0..1u64 << n).filter(|&m| states[m as usize] != 2) {
+                    assert_eq_seeded!(
+                        seed,
+                        result.evaluate(m),
+                        complement.evaluate(m),
+                        "n {} row {}",
+                        n,
+                        m
+                    );
+                    assert_eq_seeded!(seed, result.evaluate(m), by_cubes.evaluate(m));
+                }
             }
-        })
-        .collect()
+        }
+    }
+
+    #[test]
+    fn test_solve_complement_keeps_wide_off_set_as_cubes() {
+        // Σ(0) + d(1) over 30 variables: the off-set has 2^30 - 2 points, its cover is
+        // every variable but the first
+        let names = (0..30).map(|i| format!("x{}", i)).collect();
+        let mut solver = QMSolver::<Enc32>::new_with_variable_names(30, names);
+        solver.set_minterms(vec![0]);
+        solver.set_dont_cares(vec![1]);
+
+        let complement = solver.solve_complement();
+        assert!(complement.complemented);
+        assert!(complement.minterms.is_empty());
+        let cover = sorted_cover(&complement.cover);
+        let expected: Vec<(u64, u64)> = (1..30)
+            .map(|i| (1 << i, ((1 << 30) - 1) & !(1 << i)))
+            .collect();
+        assert_eq!(cover, sorted_cover(&expected));
+        assert!(complement.essential_only_cover);
+        assert_eq!(complement.used_dont_cares, Vec::<u64>::new());
+        assert!(complement.evaluate(0) && complement.evaluate(1));
+        assert!(!complement.evaluate(2) && !complement.evaluate(1 << 29));
+    }
+
+    #[test]
+    fn test_tie_break_policies() {
+        // Σ(0,1,2,5,6,7) is cyclic with two minimum covers; the reported one never varies
+        let cyclic = |tie_break| {
+            let mut solver = QMSolver::<Enc16>::new(3);
+            solver.set_minterms(vec![0, 1, 2, 5, 6, 7]);
+            solver.set_cover_strategy(CoverStrategy::Exact);
+            solver.set_tie_break(tie_break);
+            solver.solve().cover
+        };
+        let first = cyclic(TieBreak::default());
+        // 00X, 1X1 and X10 sort before 0X0, 11X and X01
+        assert_eq!(
+            sorted_cover(&first),
+            vec![(0b000, 0b001), (0b010, 0b100), (0b101, 0b010)]
+        );
+        for _ in 0..100 {
+            assert_eq!(cyclic(TieBreak::default()), first);
+        }
+
+        // Σ(1) + d(0,3,5,7): 00X and XX1 each cover minterm 1 on their own
+        let tied = |tie_break| {
+            let mut solver = QMSolver::<Enc16>::new(3);
+            solver.set_minterms(vec![1]);
+            solver.set_dont_cares(vec![0, 3, 5, 7]);
+            solver.set_cover_strategy(CoverStrategy::Exact);
+            solver.set_tie_break(tie_break);
+            solver.solve()
+        };
+        // XX1 has fewer literals than 00X, whichever policy breaks the tie after that
+        for tie_break in [
+            TieBreak::LexicographicSmallestPattern,
+            TieBreak::FewestLiterals,
+            TieBreak::MostSharedWithEssentials,
+            TieBreak::FirstFound,
+        ] {
+            let result = tied(tie_break);
+            assert_eq!(result.cover, vec![(0b001, 0b110)], "{:?}", tie_break);
+            assert_eq!(result.petrick_runner_ups, Some(1));
+        }
+    }
+
+    #[test]
+    fn test_minimum_covers_ranked_by_literals() {
+        // Σ(0, 1, 5, 7, 9, 11, 12, 13, 14) has two covers of five terms, with 15 and 14
+        // literals; the first compares smaller by pattern
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 5, 7, 9, 11, 12, 13, 14]);
+        solver.set_cover_strategy(CoverStrategy::Exact);
+        for tie_break in [TieBreak::LexicographicSmallestPattern, TieBreak::FirstFound] {
+            solver.set_tie_break(tie_break);
+            let result = solver.solve();
+            assert_eq!(result.cover.len(), 5);
+            assert_eq!(result.literal_cost(), 14, "{:?}", tie_break);
+            assert_eq!(result.petrick_runner_ups, Some(1));
+        }
+    }
+
+    #[test]
+    fn test_zero_and_one_variable_functions() {
+        let cases: [(usize, &[u32], &str); 7] = [
+            (0, &[], "0"),
+            (0, &[0], "1"),
+            (1, &[], "0"),
+            (1, &[0], "A'"),
+            (1, &[1], "A"),
+            (1, &[0, 1], "1"),
+            (2, &[0, 1, 2, 3], "1"),
+        ];
+        let complement_of = |expression: &str| match expression {
+            "0" => "1".to_string(),
+            "1" => "0".to_string(),
+            "A" => "A'".to_string(),
+            _ => "A".to_string(),
+        };
+        for (n, minterms, expected) in cases {
+            for method in [SolveMethod::QM, SolveMethod::MinCubes] {
+                for objective in [
+                    Objective::MinimumCover,
+                    Objective::HazardFree,
+                    Objective::FewestVariables,
+                ] {
+                    let mut solver = QMSolver::<Enc16>::new(n);
+                    solver.set_minterms(minterms.to_vec());
+                    solver.set_method(method);
+                    solver.set_objective(objective);
+                    let context = format!("n {} {:?} {:?} {:?}", n, minterms, method, objective);
+
+                    let result = solver.solve();
+                    assert_eq!(result.minimized_expression, expected, "{}", context);
+                    for m in 0..1u64 << n {
+                        assert_eq!(result.evaluate(m), minterms.contains(&(m as u32)));
+                    }
+                    assert_eq!(
+                        solver.solve_complement().minimized_expression,
+                        complement_of(expected),
+                        "{}",
+                        context
+                    );
+                    assert_eq!(solver.solve_bounded(1).expression, expected, "{}", context);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_report_symmetry() {
+        use crate::qm::random::threshold;
+
+        // Majority of three: every variable is interchangeable with the others
+        let majority: Vec<u32> = threshold(3, 2).into_iter().map(|m| m as u32).collect();
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(majority);
+        assert!(solver.solve().symmetry_classes.is_empty());
+
+        solver.set_report_symmetry(true);
+        for method in [SolveMethod::QM, SolveMethod::MinCubes] {
+            solver.set_method(method);
+            let result = solver.solve();
+            assert_eq!(result.symmetry_classes, vec![vec![0, 1, 2]]);
+            assert!(
+                result
+                    .solution_steps
+                    .contains(&"Symmetric variables: {A, B, C}".to_string())
+            );
+        }
+        // The off-set has the same symmetries
+        assert_eq!(
+            solver.solve_complement().symmetry_classes,
+            vec![vec![0, 1, 2]]
+        );
+    }
+
+    /// Phase named by the error of `try_solve` with `limit`, `None` if it succeeds
+    fn failing_phase(solver: &mut QMSolver<Enc16>, limit: usize) -> Option<String> {
+        solver.set_max_memory(Some(limit));
+        match solver.try_solve() {
+            Ok(_) => None,
+            Err(SolveError::ResourceLimit {
+                phase,
+                tracked_bytes,
+                limit_bytes,
+            }) => {
+                assert!(tracked_bytes > limit_bytes);
+                Some(phase)
+            }
+        }
+    }
+
+    #[test]
+    fn test_memory_limit_names_the_phase() {
+        // A cyclic core: Petrick's method (and its coverage matrix) is needed
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![0, 1, 2, 5, 6, 7]);
+        let peak = solver.solve().peak_memory_bytes;
+        assert!(peak > 0);
+
+        assert_eq!(failing_phase(&mut solver, 1).as_deref(), Some("input"));
+        let input = 6 * std::mem::size_of::<u32>();
+        assert_eq!(
+            failing_phase(&mut solver, input).as_deref(),
+            Some("prime implicants, pass 1")
+        );
+        let phases: HashSet<String> = (0..peak)
+            .filter_map(|limit| failing_phase(&mut solver, limit))
+            .collect();
+        assert!(phases.contains("coverage matrix"), "{:?}", phases);
+        assert_eq!(failing_phase(&mut solver, peak), None);
+
+        solver.set_method(SolveMethod::MinCubes);
+        assert_eq!(
+            failing_phase(&mut solver, input).as_deref(),
+            Some("prime implicants")
+        );
+
+        let error = QMSolver::<Enc16>::builder()
+            .variables(2)
+            .minterms(vec![1, 2])
+            .max_memory_bytes(4)
+            .build()
+            .unwrap()
+            .try_solve()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "memory limit of 4 bytes exceeded in phase 'input' (8 bytes tracked)"
+        );
+    }
+
+    #[test]
+    fn test_complement_and_best_form_return_the_memory_error() {
+        let mut solver = QMSolver::<Enc16>::new(3);
+        solver.set_minterms(vec![0, 1, 2, 5, 6, 7]);
+        solver.set_max_memory(Some(1));
+        let phase = |error: SolveError| match error {
+            SolveError::ResourceLimit { phase, .. } => phase,
+        };
+        assert_eq!(phase(solver.try_solve_complement().unwrap_err()), "input");
+        assert_eq!(phase(solver.try_solve_best_form().unwrap_err()), "input");
+
+        solver.set_max_memory(None);
+        let complement = solver.try_solve_complement().unwrap();
+        assert!(complement.complemented);
+        assert_eq!(complement.minterms, solver.solve_complement().minterms);
+        assert_eq!(
+            solver.try_solve_best_form().unwrap().expression,
+            solver.solve_best_form().expression
+        );
+    }
+
+    #[test]
+    fn test_estimate_memory_bounds_the_full_function() {
+        let mut previous = 0;
+        for variables in 1..=8 {
+            let mut solver = QMSolver::<Enc16>::new(variables);
+            solver.set_minterms((0..1u32 << variables).collect());
+            let estimate = solver.estimate_memory();
+            assert!(
+                estimate >= solver.solve().peak_memory_bytes,
+                "{}",
+                variables
+            );
+            assert!(estimate > previous);
+            previous = estimate;
+        }
+        assert_eq!(QMSolver::<Enc16>::new(4).estimate_memory(), 0);
+    }
+
+    #[test]
+    fn test_best_form() {
+        // (A + B)(C + D): four literals as a product of sums, AC + AD + BC + BD has eight
+        let product: Vec<u32> = (0..16)
+            .filter(|m| (m & 0b0011 != 0) && (m & 0b1100 != 0))
+            .collect();
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(product);
+        let best = solver.solve_best_form();
+        assert_eq!((best.sop_cost, best.pos_cost), (8, 4));
+        assert_eq!(best.expression, "(A + B)(C + D)");
+        assert_eq!(best.best, BestForm::Pos);
+        assert!(best.complement.complemented);
+        // The sum of products comes along, as solved on its own
+        assert!(!best.sop.complemented);
+        assert_eq!(best.sop.cover, solver.solve().cover);
+        assert_eq!(best.sop.literal_cost(), 8);
+
+        // AB + CD is cheaper as it is; the product of sums is still given
+        let sum: Vec<u32> = (0..16)
+            .filter(|m| (m & 0b0011 == 0b0011) || (m & 0b1100 == 0b1100))
+            .collect();
+        solver.set_minterms(sum);
+        let best = solver.solve_best_form();
+        assert_eq!((best.sop_cost, best.pos_cost), (4, 8));
+        assert_eq!(best.best, BestForm::Sop);
+        assert_eq!(best.expression, best.sop.minimized_expression);
+        let mut terms: Vec<&str> = best.expression.split(" + ").collect();
+        terms.sort_unstable();
+        assert_eq!(terms, ["AB", "CD"]);
+        assert_eq!(best.pos_expression, "(A + C)(A + D)(B + C)(B + D)");
+
+        // Both forms use the don't-cares: with 15 free, F = A + B + C + D is one clause
+        // and four terms, a tie that goes to the sum of products
+        let mostly_ones: Vec<u32> = (1..15).collect();
+        solver.set_minterms(mostly_ones);
+        solver.set_dont_cares(vec![15]);
+        let best = solver.solve_best_form();
+        assert_eq!((best.sop_cost, best.pos_cost), (4, 4));
+        assert_eq!(best.pos_expression, "(A + B + C + D)");
+        assert_eq!(best.best, BestForm::Sop);
+    }
+
+    #[test]
+    fn test_best_form_of_constants() {
+        let mut solver = QMSolver::<Enc16>::new(2);
+        assert_eq!(solver.solve_best_form().pos_expression, "0");
+        solver.set_minterms(vec![0, 1, 2, 3]);
+        let best = solver.solve_best_form();
+        assert_eq!(
+            (best.expression.as_str(), best.pos_expression.as_str()),
+            ("1", "1")
+        );
+        assert_eq!((best.sop_cost, best.pos_cost), (0, 0));
+    }
+
+    #[test]
+    fn test_variable_name_validation() {
+        let names = |list: &[&str]| -> Vec<String> { list.iter().map(|s| s.to_string()).collect() };
+        let issues = |n: usize, list: &[&str]| {
+            normalize_variable_names(n, &names(list))
+                .unwrap_err()
+                .issues
+        };
+
+        assert_eq!(
+            issues(3, &["A", "B"]),
+            vec![VariableNameIssue::Count {
+                n_variables: 3,
+                n_names: 2
+            }]
+        );
+        assert_eq!(
+            issues(2, &["A", "  "]),
+            vec![VariableNameIssue::Empty { index: 1 }]
+        );
+        // Compared after trimming
+        assert_eq!(
+            issues(3, &["x", "y", " x "]),
+            vec![VariableNameIssue::Duplicate {
+                name: "x".to_string(),
+                first: 0,
+                index: 2
+            }]
+        );
+        assert_eq!(
+            issues(2, &["a'", "!b"]),
+            vec![
+                VariableNameIssue::ComplementMark {
+                    name: "a'".to_string(),
+                    index: 0
+                },
+                VariableNameIssue::ComplementMark {
+                    name: "!b".to_string(),
+                    index: 1
+                }
+            ]
+        );
+
+        // All violations at once
+        let error = QMSolver::<Enc16>::with_variable_names(2, names(&["a", "a", "", "b!"]))
+            .err()
+            .unwrap();
+        assert_eq!(error.issues.len(), 4);
+        assert_eq!(
+            error.to_string(),
+            "invalid variable names: 4 names given for 2 variables; name 1 'a' repeats name 0; \
+             name 2 is empty; name 3 'b!' contains ' or !"
+        );
+
+        let mut solver =
+            QMSolver::<Enc16>::with_variable_names(3, names(&[" α ", "größe", "変数"])).unwrap();
+        solver.set_minterms(vec![1, 3]);
+        assert_eq!(solver.solve().minimized_expression, "α変数'");
+    }
 }
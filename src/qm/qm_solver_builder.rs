@@ -0,0 +1,357 @@
+//! QMSolverBuilder: configure a [`QMSolver`] and validate the configuration in one step
+
+use std::fmt;
+use std::time::Duration;
+
+use super::encoding::{BitOps, MintermEncoding};
+use super::qm_solver::{
+    CoverStrategy, Objective, QMSolver, TieBreak, VariableNamesError, normalize_variable_names,
+};
+
+/// Errors that can occur when building a [`QMSolver`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// Neither [`variables`](QMSolverBuilder::variables) nor
+    /// [`variable_names`](QMSolverBuilder::variable_names) was given
+    MissingVariables,
+    /// More variables than the encoding supports
+    TooManyVariables { n_variables: usize, max_vars: usize },
+    /// The variable names do not fit the variables (see [`normalize_variable_names`])
+    VariableNames(VariableNamesError),
+    /// A minterm (or don't-care) is `2^n_variables` or larger
+    MintermOutOfRange {
+        minterm: u64,
+        n_variables: usize,
+        dont_care: bool,
+    },
+    /// A minterm is also listed as a don't-care
+    MintermIsDontCare { minterm: u64 },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingVariables => {
+                write!(f, "the number of variables (or their names) is required")
+            }
+            BuildError::TooManyVariables {
+                n_variables,
+                max_vars,
+            } => write!(
+                f,
+                "too many variables ({}); maximum supported is {}",
+                n_variables, max_vars
+            ),
+            BuildError::VariableNames(e) => write!(f, "{}", e),
+            BuildError::MintermOutOfRange {
+                minterm,
+                n_variables,
+                dont_care,
+            } => write!(
+                f,
+                "{} {} does not fit in {} variables",
+                if *dont_care { "don't-care" } else { "minterm" },
+                minterm,
+                n_variables
+            ),
+            BuildError::MintermIsDontCare { minterm } => {
+                write!(f, "minterm {} is also a don't-care", minterm)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Fluent configuration of a [`QMSolver`]; [`build`](Self::build) checks it as a whole
+///
+/// ```
+/// use qm_agent::Objective;
+/// use qm_agent::prelude::*;
+///
+/// let solver = QMSolver::<Enc16>::builder()
+///     .variables(3)
+///     .minterms(vec![1, 3, 7])
+///     .dont_cares(vec![5])
+///     .objective(Objective::FewestVariables)
+///     .build()
+///     .unwrap();
+/// assert_eq!(solver.solve().minimized_expression, "A");
+/// ```
+#[derive(Debug, Clone)]
+pub struct QMSolverBuilder<E: MintermEncoding> {
+    variables: Option<usize>,
+    variable_names: Option<Vec<String>>,
+    minterms: Vec<E::Value>,
+    dont_cares: Vec<E::Value>,
+    objective: Objective,
+    cover_strategy: CoverStrategy,
+    tie_break: TieBreak,
+    timeout: Option<Duration>,
+    report_symmetry: bool,
+    max_memory_bytes: Option<usize>,
+}
+
+impl<E: MintermEncoding> Default for QMSolverBuilder<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: MintermEncoding> QMSolverBuilder<E> {
+    pub fn new() -> Self {
+        Self {
+            variables: None,
+            variable_names: None,
+            minterms: Vec::new(),
+            dont_cares: Vec::new(),
+            objective: Objective::default(),
+            cover_strategy: CoverStrategy::default(),
+            tie_break: TieBreak::default(),
+            timeout: None,
+            report_symmetry: false,
+            max_memory_bytes: None,
+        }
+    }
+
+    /// Number of variables; may be left out when names are given
+    pub fn variables(mut self, n: usize) -> Self {
+        self.variables = Some(n);
+        self
+    }
+
+    /// Variable names, `names[i]` for bit `i` (default: A, B, C, ...)
+    pub fn variable_names(mut self, names: Vec<String>) -> Self {
+        self.variable_names = Some(names);
+        self
+    }
+
+    pub fn minterms(mut self, minterms: Vec<E::Value>) -> Self {
+        self.minterms = minterms;
+        self
+    }
+
+    pub fn dont_cares(mut self, dont_cares: Vec<E::Value>) -> Self {
+        self.dont_cares = dont_cares;
+        self
+    }
+
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    pub fn cover_strategy(mut self, strategy: CoverStrategy) -> Self {
+        self.cover_strategy = strategy;
+        self
+    }
+
+    /// Which of several minimum covers is reported (see [`TieBreak`])
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Time budget of the exact cover search (see [`QMSolver::set_timeout`])
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Report the symmetry classes (see [`QMSolver::set_report_symmetry`])
+    pub fn report_symmetry(mut self, report: bool) -> Self {
+        self.report_symmetry = report;
+        self
+    }
+
+    /// Memory cap of the solve (see [`QMSolver::set_max_memory`])
+    pub fn max_memory_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_memory_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Validate the configuration and create the solver
+    pub fn build(self) -> Result<QMSolver<E>, BuildError> {
+        let n_variables = match (self.variables, &self.variable_names) {
+            (Some(n), _) => n,
+            (None, Some(names)) => names.len(),
+            (None, None) => return Err(BuildError::MissingVariables),
+        };
+        if n_variables > E::MAX_VARS {
+            return Err(BuildError::TooManyVariables {
+                n_variables,
+                max_vars: E::MAX_VARS,
+            });
+        }
+        let variable_names = match &self.variable_names {
+            Some(names) => Some(
+                normalize_variable_names(n_variables, names).map_err(BuildError::VariableNames)?,
+            ),
+            None => None,
+        };
+
+        let out_of_range = |m: &E::Value| *m >> n_variables != E::Value::zero();
+        if let Some(m) = self.minterms.iter().find(|m| out_of_range(m)) {
+            return Err(BuildError::MintermOutOfRange {
+                minterm: m.to_u64(),
+                n_variables,
+                dont_care: false,
+            });
+        }
+        if let Some(m) = self.dont_cares.iter().find(|m| out_of_range(m)) {
+            return Err(BuildError::MintermOutOfRange {
+                minterm: m.to_u64(),
+                n_variables,
+                dont_care: true,
+            });
+        }
+        if let Some(m) = self.minterms.iter().find(|m| self.dont_cares.contains(m)) {
+            return Err(BuildError::MintermIsDontCare {
+                minterm: m.to_u64(),
+            });
+        }
+
+        let mut solver = match variable_names {
+            Some(names) => QMSolver::new_with_variable_names(n_variables, names),
+            None => QMSolver::new(n_variables),
+        };
+        solver.set_minterms(self.minterms);
+        solver.set_dont_cares(self.dont_cares);
+        solver.set_objective(self.objective);
+        solver.set_cover_strategy(self.cover_strategy);
+        solver.set_tie_break(self.tie_break);
+        solver.set_timeout(self.timeout);
+        solver.set_report_symmetry(self.report_symmetry);
+        solver.set_max_memory(self.max_memory_bytes);
+        Ok(solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::{Enc16, Enc64, VariableNameIssue};
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let builder = || QMSolver::<Enc16>::builder();
+        assert_eq!(
+            builder().minterms(vec![1]).build().err(),
+            Some(BuildError::MissingVariables)
+        );
+        assert_eq!(
+            builder().variables(17).build().err(),
+            Some(BuildError::TooManyVariables {
+                n_variables: 17,
+                max_vars: 16
+            })
+        );
+        assert_eq!(
+            builder()
+                .variables(3)
+                .variable_names(names(&["x", "y"]))
+                .build()
+                .err(),
+            Some(BuildError::VariableNames(VariableNamesError {
+                issues: vec![VariableNameIssue::Count {
+                    n_variables: 3,
+                    n_names: 2
+                }]
+            }))
+        );
+        assert_eq!(
+            builder().variables(3).minterms(vec![1, 8]).build().err(),
+            Some(BuildError::MintermOutOfRange {
+                minterm: 8,
+                n_variables: 3,
+                dont_care: false
+            })
+        );
+        assert_eq!(
+            builder().variables(3).dont_cares(vec![9]).build().err(),
+            Some(BuildError::MintermOutOfRange {
+                minterm: 9,
+                n_variables: 3,
+                dont_care: true
+            })
+        );
+        assert_eq!(
+            builder()
+                .variables(3)
+                .minterms(vec![1, 3])
+                .dont_cares(vec![3])
+                .build()
+                .err(),
+            Some(BuildError::MintermIsDontCare { minterm: 3 })
+        );
+        // All 64 variables of Enc64: every minterm fits
+        assert!(
+            QMSolver::<Enc64>::builder()
+                .variables(64)
+                .minterms(vec![u64::MAX as u128])
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_names_give_the_variable_count() {
+        let solver = QMSolver::<Enc16>::builder()
+            .variable_names(names(&["x", "y"]))
+            .minterms(vec![1, 3])
+            .build()
+            .unwrap();
+        assert_eq!(solver.solve().minimized_expression, "x");
+    }
+
+    #[test]
+    fn test_options_reach_solve() {
+        // Σ(0,1,2,5,6,7) is cyclic: no essentials, minimum covers have 3 terms
+        let cyclic = || {
+            QMSolver::<Enc16>::builder()
+                .variables(3)
+                .minterms(vec![0, 1, 2, 5, 6, 7])
+        };
+
+        let exact = cyclic()
+            .cover_strategy(CoverStrategy::Exact)
+            .build()
+            .unwrap()
+            .solve();
+        assert!(exact.petrick_invoked);
+        assert_eq!(exact.cover.len(), 3);
+
+        // A zero budget stops the exact search at once and says so
+        let timed_out = cyclic()
+            .cover_strategy(CoverStrategy::Exact)
+            .timeout(Duration::ZERO)
+            .build()
+            .unwrap()
+            .solve();
+        assert!(
+            timed_out
+                .solution_steps
+                .iter()
+                .any(|step| step.contains("stopped at the timeout"))
+        );
+        let untimed = cyclic().build().unwrap().solve();
+        assert!(
+            !untimed
+                .solution_steps
+                .iter()
+                .any(|step| step.contains("timeout"))
+        );
+
+        // The objective is passed on as well
+        let hazard_free = cyclic()
+            .objective(Objective::HazardFree)
+            .build()
+            .unwrap()
+            .solve();
+        assert!(!hazard_free.hazard_terms.is_empty());
+    }
+}
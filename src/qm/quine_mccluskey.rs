@@ -1,14 +1,26 @@
 //! QuineMcCluskey: Core implementation of the Quine-McCluskey algorithm
 
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use std::arch::asm;
 use super::encoding::{BitOps, MintermEncoding};
 use super::implicant::Implicant;
+use super::memory_budget::{MemoryBudget, SolveError};
 
 
+/// Break into the debugger (x86 only; a no-op elsewhere)
 pub fn int3() {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     unsafe { asm!("int3"); }
 }
 
+/// Start of a timed section; `None` where there is no clock (wasm32-unknown-unknown)
+pub(crate) fn start_timer() -> Option<std::time::Instant> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return Some(std::time::Instant::now());
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return None;
+}
+
 
 /// Core Quine-McCluskey algorithm implementation
 pub struct QuineMcCluskey<E: MintermEncoding> {
@@ -18,6 +30,37 @@ pub struct QuineMcCluskey<E: MintermEncoding> {
     dont_cares: Vec<E::Value>,
     solution_steps: Vec<String>,
     logging_on: bool,
+    memory: MemoryBudget,
+    memory_error: Option<SolveError>,
+}
+
+/// A prime implicant with the combining passes that produced it
+///
+/// Passes are numbered from 1; the minterms and don't-cares enter pass 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedImplicant<E: MintermEncoding> {
+    pub implicant: Implicant<E>,
+    /// Pass in which the implicant found nothing to merge with
+    pub prime_at_pass: usize,
+}
+
+impl<E: MintermEncoding> TracedImplicant<E> {
+    /// Merging steps behind the implicant: its number of don't-care positions, which is
+    /// also the pass whose merges produced it (0 for a minterm or don't-care)
+    pub fn order(&self) -> usize {
+        (self.implicant.bits >> self.implicant.n_variables).count_ones() as usize
+    }
+}
+
+/// Tracked bytes of `implicants`: the implicants and their covered-minterm sets
+pub(crate) fn implicant_bytes<E: MintermEncoding>(implicants: &[Implicant<E>]) -> usize {
+    implicants
+        .iter()
+        .map(|imp| {
+            std::mem::size_of::<Implicant<E>>()
+                + imp.covered_minterms.len() * std::mem::size_of::<E::Value>()
+        })
+        .sum()
 }
 
 impl<E: MintermEncoding> QuineMcCluskey<E> {
@@ -34,9 +77,29 @@ impl<E: MintermEncoding> QuineMcCluskey<E> {
             dont_cares: Vec::with_capacity(0),
             solution_steps: Vec::with_capacity(0),
             logging_on: false,
+            memory: MemoryBudget::new(None),
+            memory_error: None,
         }
     }
 
+    /// Stop merging after the pass at which the tracked implicants exceed `max_bytes`
+    ///
+    /// The prime implicants are then incomplete; [`memory_error`](Self::memory_error) says
+    /// at which pass the limit was hit.
+    pub fn set_max_memory(&mut self, max_bytes: Option<usize>) {
+        self.memory = MemoryBudget::new(max_bytes);
+    }
+
+    /// Why the last search for prime implicants stopped early, if it did
+    pub fn memory_error(&self) -> Option<&SolveError> {
+        self.memory_error.as_ref()
+    }
+
+    /// Most bytes tracked after any merge pass of the last search
+    pub fn peak_memory_bytes(&self) -> usize {
+        self.memory.peak()
+    }
+
     pub fn set_logging_on(&mut self, logging_on: bool) {
         self.logging_on = logging_on;
     }
@@ -64,127 +127,59 @@ impl<E: MintermEncoding> QuineMcCluskey<E> {
     ///
     /// Clears the solution steps before starting and logs each processing level.
     fn find_prime_implicants(&mut self) -> Vec<Implicant<E>> {
+        self.prime_implicants_iter().collect()
+    }
+
+    /// All prime implicants, each with the pass that produced it and the pass at which it
+    /// became prime
+    ///
+    /// Records the solution steps and respects the memory limit as the search for
+    /// essential prime implicants does.
+    pub fn find_traced_prime_implicants(&mut self) -> Vec<TracedImplicant<E>> {
+        self.traced_prime_implicants_iter().collect()
+    }
+
+    /// The prime implicants, yielded pass by pass as they are finalized
+    ///
+    /// Only the implicants of the current pass are held in memory besides the ones not yet
+    /// yielded, so callers can filter or consume the prime implicants incrementally. The
+    /// order is deterministic: by pass, then by raw encoding.
+    pub fn prime_implicants_iter(&mut self) -> impl Iterator<Item = Implicant<E>> + '_ {
+        self.traced_prime_implicants_iter().map(|traced| traced.implicant)
+    }
+
+    /// As [`prime_implicants_iter`](Self::prime_implicants_iter), with the passes of
+    /// [`find_traced_prime_implicants`](Self::find_traced_prime_implicants)
+    pub fn traced_prime_implicants_iter(&mut self) -> PrimeImplicantsIter<'_, E> {
         self.solution_steps.clear();
+        self.memory = MemoryBudget::new(self.memory.limit());
+        self.memory_error = None;
         self.solution_steps.push(format!(
             "Step 1: Initial minterms: {} terms",
             self.minterms.len()
         ));
 
-        let mut all_terms: Vec<E::Value> = self.minterms.clone();
-        all_terms.extend(&self.dont_cares);
-
-        let mut current_level: Vec<Implicant<E>> = all_terms
+        let current_level: Vec<E::Value> = self
+            .minterms
             .iter()
-            .map(|&term| Implicant::from_minterm(term, self.variables))
+            .chain(&self.dont_cares)
+            .copied()
             .collect();
-        
-        let mut prime_implicants: Vec<Implicant<E>> = Vec::new();
-        let mut order = 1;
-
-        while !current_level.is_empty() {
-            let msg = format!(
-                "Step {order}: Processing {order}-order implicants (#{})",
-                current_level.len()
-            );
-            if self.logging_on {
-                println!("{msg}");
-            }
-            self.solution_steps.push(msg);
-
-            let mut next_level: Vec<Implicant<E>> = Vec::new();
-            let mut used = vec![false; current_level.len()];
-
-            // Use Hamming bit-count grouping with fast raw encoding operations
-            // Two implicants can only combine if they differ by exactly 1 bit,
-            // So we only need to compare groups[k] with groups[k+1]
-            use std::collections::HashMap;
-            let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
-            
-            let raw_encodings: Vec<E::Value> = current_level.iter().map(|i| i.bits).collect::<Vec<_>>();
-
-            // Group by Hamming weight using fast pop-count on raw encoding
-            for (idx, &raw_value) in raw_encodings.iter().enumerate() {
-                let data = raw_value & self.mask;
-                let ones_count = data.count_ones() as usize;
-                groups.entry(ones_count).or_insert_with(Vec::new).push(idx);
-            }
-
-            // Use HashMap for deduplication while tracking covered_minterms
-            use std::collections::HashMap as DedupeMap;
-            use std::collections::HashSet;
-            let mut next_level_map: DedupeMap<E::Value, HashSet<E::Value>> = DedupeMap::new();
-
-            // Only compare adjacent Hamming weight groups
-            let max_bit_count = groups.keys().max().copied().unwrap_or(0);
-            for bit_count in 0..max_bit_count {
-                if let (Some(group1), Some(group2)) =
-                    (groups.get(&bit_count), groups.get(&(bit_count + 1)))
-                {
-                    let start_time = std::time::Instant::now();
-
-                    // Use SIMD-optimized gray code pair finding: here most of the time is spent
-                    let pairs = E::find_gray_code_pairs(group1, group2, &raw_encodings);
-
-                    if self.logging_on {
-
-                        println!(
-                            "number of pairs found between bit-count {bit_count} and {}: {}; time spend {:?}",
-                            bit_count + 1,
-                            pairs.len(),
-                            start_time.elapsed()
-                        );
-                    }
-                    for (i, j) in pairs {
-                        used[i] = true;
-                        used[j] = true;
-
-                        let raw_i: E::Value = raw_encodings[i];
-                        let raw_j: E::Value = raw_encodings[j];
-                        let raw_combined: E::Value =
-                            Implicant::<E>::replace_complements(raw_i, raw_j, self.variables);
-
-                        let entry = next_level_map.entry(raw_combined).or_insert_with(HashSet::new);
-                        entry.extend(&current_level[i].covered_minterms);
-                        entry.extend(&current_level[j].covered_minterms);
-                    }
-                }
-            }
-
-            // Convert back to Implicants with proper covered_minterms
-            for (raw_value, covered) in next_level_map {
-                let mut combined_imp = Implicant::<E>::from_raw_encoding(raw_value, self.variables);
-                combined_imp.covered_minterms = covered;
-                next_level.push(combined_imp);
-            }
-
-            if self.logging_on {
-                println!(
-                    "Level {order}: next_level size = {}, prime_implicants so far = {}",
-                    next_level.len(),
-                    prime_implicants.len()
-                );
-            }
-
-            #[cfg(debug_assertions)]
-            validate_prime_implicants(&next_level, self.variables);
-
-            for (i, implicant) in current_level.into_iter().enumerate() {
-                if !used[i] {
-                    prime_implicants.push(implicant);
-                }
-            }
-
-            current_level = next_level;
-            order += 1;
+        debug_assert!(
+            current_level.iter().all(|&term| term >> self.variables == E::Value::zero()),
+            "minterm does not fit in {} variables",
+            self.variables
+        );
+
+        PrimeImplicantsIter {
+            qm: self,
+            current_level,
+            finalized: Vec::new().into_iter(),
+            order: 1,
+            found: 0,
+            found_bytes: 0,
+            finished: false,
         }
-
-        self.solution_steps
-            .push(format!("Found {} prime implicants", prime_implicants.len()));
-
-        #[cfg(debug_assertions)]
-        validate_prime_implicants(&prime_implicants, self.variables);
-
-        prime_implicants
     }
 
     /// Find essential prime implicants (those that uniquely cover certain minterms).
@@ -193,40 +188,19 @@ impl<E: MintermEncoding> QuineMcCluskey<E> {
     /// specific minterms. These must be included in any minimal solution.
     /// Returns (all_prime_implicants, essential_prime_implicants)
     pub fn find_essential_prime_implicants(&mut self) -> (Vec<Implicant<E>>, Vec<Implicant<E>>) {
-        let all_pis: Vec<Implicant<E>> = self.find_prime_implicants();
-
-        // Build a coverage map: minterm -> list of prime implicants that cover it
-        let mut coverage_map: std::collections::HashMap<E::Value, Vec<usize>> =
-            std::collections::HashMap::new();
-        
-        for minterm in &self.minterms {
-            for (pi_idx, pi) in all_pis.iter().enumerate() {
-                if pi.covers_minterm(*minterm) {
-                    coverage_map.entry(*minterm).or_default().push(pi_idx);
-                }
-            }
-        }
-
-        // Find essential prime implicants: those that uniquely cover at least one minterm
-        let mut essential_indices = std::collections::HashSet::new();
-        for (_minterm, covering_pis) in &coverage_map {
-            if covering_pis.len() == 1 {
-                // This minterm is only covered by one PI, making it essential
-                essential_indices.insert(covering_pis[0]);
-            }
-        }
+        let (all_pis, essential_pis, _) = self.find_essential_prime_implicants_with_witnesses();
+        (all_pis, essential_pis)
+    }
 
-        let essential_pis: Vec<Implicant<E>> = all_pis
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, pi)| {
-                if essential_indices.contains(&idx) {
-                    Some(pi.clone())
-                } else {
-                    None
-                }
-            })
-            .collect();
+    /// As [`find_essential_prime_implicants`](Self::find_essential_prime_implicants), also
+    /// returning for each essential prime implicant the minterms (ascending) that no other
+    /// prime implicant covers: the reason it is essential
+    #[allow(clippy::type_complexity)]
+    pub fn find_essential_prime_implicants_with_witnesses(
+        &mut self,
+    ) -> (Vec<Implicant<E>>, Vec<Implicant<E>>, Vec<Vec<E::Value>>) {
+        let all_pis: Vec<Implicant<E>> = self.find_prime_implicants();
+        let (essential_pis, witnesses) = essential_with_witnesses(&all_pis, &self.minterms);
 
         self.solution_steps.push(format!(
             "Step {}: Identified {} essential prime implicants (uniquely covering minterms)",
@@ -234,7 +208,7 @@ impl<E: MintermEncoding> QuineMcCluskey<E> {
             essential_pis.len()
         ));
 
-        (all_pis, essential_pis)
+        (all_pis, essential_pis, witnesses)
     }
 
     /// Get the step-by-step description of the minimization process.
@@ -247,13 +221,202 @@ impl<E: MintermEncoding> QuineMcCluskey<E> {
     }
 }
 
+/// Iterator over the prime implicants of a [`QuineMcCluskey`], one merge pass at a time
+///
+/// Created by [`QuineMcCluskey::traced_prime_implicants_iter`]. The closing "Found N prime
+/// implicants" solution step is recorded once the iterator is exhausted.
+///
+/// A pass works on raw encodings only, referring to them by index; an [`Implicant`] with
+/// its covered minterms is built just for the prime implicants. Every point of a merged
+/// cube is a minterm or a don't-care, so the covered minterms are the points of the cube.
+pub struct PrimeImplicantsIter<'a, E: MintermEncoding> {
+    qm: &'a mut QuineMcCluskey<E>,
+    /// Raw encodings of the implicants entering the next pass
+    current_level: Vec<E::Value>,
+    /// Prime implicants of the last pass not yet yielded
+    finalized: std::vec::IntoIter<TracedImplicant<E>>,
+    order: usize,
+    /// Number and tracked bytes of the prime implicants finalized so far
+    found: usize,
+    found_bytes: usize,
+    finished: bool,
+}
+
+impl<E: MintermEncoding> PrimeImplicantsIter<'_, E> {
+    /// Merge `current_level` once, queueing the implicants that found nothing to merge with
+    fn pass(&mut self) {
+        let qm = &mut *self.qm;
+        let order = self.order;
+        let current_level = std::mem::take(&mut self.current_level);
+
+        let msg = format!(
+            "Step {order}: Processing {order}-order implicants (#{})",
+            current_level.len()
+        );
+        if qm.logging_on {
+            log::info!("{msg}");
+        }
+        qm.solution_steps.push(msg);
+
+        let mut next_level: Vec<E::Value> = Vec::new();
+        let mut used = vec![false; current_level.len()];
+
+        // Use Hamming bit-count grouping with fast raw encoding operations
+        // Two implicants can only combine if they differ by exactly 1 bit,
+        // So we only need to compare groups[k] with groups[k+1]
+        use std::collections::HashMap;
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        // Group by Hamming weight using fast pop-count on raw encoding
+        for (idx, &raw_value) in current_level.iter().enumerate() {
+            let data = raw_value & qm.mask;
+            let ones_count = data.count_ones() as usize;
+            groups.entry(ones_count).or_insert_with(Vec::new).push(idx);
+        }
+
+        // Only compare adjacent Hamming weight groups
+        let max_bit_count = groups.keys().max().copied().unwrap_or(0);
+        for bit_count in 0..max_bit_count {
+            if let (Some(group1), Some(group2)) =
+                (groups.get(&bit_count), groups.get(&(bit_count + 1)))
+            {
+                let start_time = if qm.logging_on { start_timer() } else { None };
+
+                // Use SIMD-optimized gray code pair finding: here most of the time is spent
+                let pairs = E::find_gray_code_pairs(group1, group2, &current_level);
+
+                if qm.logging_on {
+
+                    log::info!(
+                        "number of pairs found between bit-count {bit_count} and {}: {}; time spend {:?}",
+                        bit_count + 1,
+                        pairs.len(),
+                        start_time.map(|start| start.elapsed())
+                    );
+                }
+                for (i, j) in pairs {
+                    used[i] = true;
+                    used[j] = true;
+                    next_level.push(Implicant::<E>::replace_complements(
+                        current_level[i],
+                        current_level[j],
+                        qm.variables,
+                    ));
+                }
+            }
+        }
+
+        // A cube with k don't-cares is produced by k merges; sorting also makes the primes
+        // of later passes come out the same on every run
+        next_level.sort_unstable();
+        next_level.dedup();
+
+        if qm.logging_on {
+            log::info!(
+                "Level {order}: next_level size = {}, prime_implicants so far = {}",
+                next_level.len(),
+                self.found
+            );
+        }
+
+        let primes: Vec<Implicant<E>> = current_level
+            .into_iter()
+            .zip(used)
+            .filter(|(_, used)| !used)
+            .map(|(raw, _)| Implicant::from_raw_with_points(raw, qm.variables))
+            .collect();
+
+        #[cfg(debug_assertions)]
+        validate_prime_implicants(&primes, qm.variables);
+
+        self.found += primes.len();
+        self.found_bytes += implicant_bytes(&primes);
+        // Each pass combines only the implicants produced by the pass before it
+        self.finalized = primes
+            .into_iter()
+            .map(|implicant| TracedImplicant {
+                implicant,
+                prime_at_pass: order,
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let tracked = (qm.minterms.len() + qm.dont_cares.len() + next_level.len())
+            * std::mem::size_of::<E::Value>()
+            + self.found_bytes;
+        if let Err(e) = qm
+            .memory
+            .track(&format!("prime implicants, pass {order}"), tracked)
+        {
+            qm.solution_steps.push(e.to_string());
+            qm.memory_error = Some(e);
+            next_level.clear();
+        }
+        self.current_level = next_level;
+        self.order += 1;
+    }
+}
+
+impl<E: MintermEncoding> Iterator for PrimeImplicantsIter<'_, E> {
+    type Item = TracedImplicant<E>;
+
+    fn next(&mut self) -> Option<TracedImplicant<E>> {
+        loop {
+            if let Some(traced) = self.finalized.next() {
+                return Some(traced);
+            }
+            if self.finished {
+                return None;
+            }
+            if self.current_level.is_empty() {
+                self.finished = true;
+                self.qm
+                    .solution_steps
+                    .push(format!("Found {} prime implicants", self.found));
+                return None;
+            }
+            self.pass();
+        }
+    }
+}
+
+/// Essential prime implicants of `pis` (in PI order), each with the minterms it alone covers
+///
+/// A minterm covered by exactly one prime implicant is a witness for that implicant; every
+/// implicant with at least one witness is essential.
+pub fn essential_with_witnesses<E: MintermEncoding>(
+    pis: &[Implicant<E>],
+    minterms: &[E::Value],
+) -> (Vec<Implicant<E>>, Vec<Vec<E::Value>>) {
+    let mut sorted: Vec<E::Value> = minterms.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut witnesses: Vec<Vec<E::Value>> = vec![Vec::new(); pis.len()];
+    for minterm in sorted {
+        let mut covering = pis
+            .iter()
+            .enumerate()
+            .filter(|(_, pi)| pi.covers_minterm(minterm));
+        if let (Some((idx, _)), None) = (covering.next(), covering.next()) {
+            witnesses[idx].push(minterm);
+        }
+    }
+
+    pis.iter()
+        .zip(witnesses)
+        .filter(|(_, witness)| !witness.is_empty())
+        .map(|(pi, witness)| (pi.clone(), witness))
+        .unzip()
+}
+
 /// Validate a list of prime implicants for correctness.
 ///
 /// 1. **Duplicates** — two implicants with the same raw encoding should not appear.
 /// 2. **Don't-care consistency** — every entry in `covered_minterms` must have
 ///    0 in every bit position that is marked as DontCare in the implicant.
 ///
-/// Prints warnings and triggers an `int 3` breakpoint if anything is wrong.
+/// Logs errors and triggers an `int 3` breakpoint if anything is wrong.
 /// Only active in debug builds via `#[cfg(debug_assertions)]` guards on callers.
 #[allow(dead_code)]
 pub fn validate_prime_implicants<E: MintermEncoding>(
@@ -267,7 +430,7 @@ pub fn validate_prime_implicants<E: MintermEncoding>(
         validate_prime_implicant::<E>(&raw, variables);
 
         if let Some(first) = seen.insert(raw, idx) {
-            println!(
+            log::error!(
                 "validate_prime_implicants: duplicate PI {} at index {} matches first at index {}",
                 idx, idx, first
             );
@@ -283,7 +446,7 @@ pub fn validate_prime_implicant<E: MintermEncoding>(raw: &E::Value, variables: u
         let data_bit = raw.get_bit(i);
 
         if dont_know && !data_bit {
-            println!(
+            log::error!(
                 "validate_prime_implicants: DontCare bit is set while data bit is cleared. {:032b}",
                 raw.to_u64()
             );
@@ -291,3 +454,77 @@ pub fn validate_prime_implicant<E: MintermEncoding>(raw: &E::Value, variables: u
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_eq_seeded;
+    use crate::qm::encoding::Enc16;
+    use crate::qm::implicant::BitState;
+    use crate::qm::random::{random_function, test_seed};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    /// Prime implicants of `on_or_dc` as `(values, don't-care mask)`, by testing every cube
+    fn brute_force_primes(n: usize, on_or_dc: &[u64]) -> Vec<(u64, u64)> {
+        let full = (1u64 << n) - 1;
+        let mut member = vec![false; 1 << n];
+        for &m in on_or_dc {
+            member[m as usize] = true;
+        }
+        let inside = |values: u64, dc: u64| {
+            (0..=full)
+                .filter(|m| m & !dc == values)
+                .all(|m| member[m as usize])
+        };
+        let mut primes = Vec::new();
+        for dc in 0..=full {
+            for values in (0..=full).filter(|v| v & dc == 0) {
+                if inside(values, dc)
+                    && !(0..n)
+                        .filter(|i| (dc >> i) & 1 == 0)
+                        .any(|i| inside(values & !(1 << i), dc | (1 << i)))
+                {
+                    primes.push((values, dc));
+                }
+            }
+        }
+        primes.sort_unstable();
+        primes
+    }
+
+    #[test]
+    fn test_prime_implicants_iter_matches_brute_force() {
+        let seed = test_seed(7);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for n in 0..=7usize {
+            for _ in 0..10 {
+                let on_set = random_function(&mut rng, n, 0.4);
+                let mut dont_cares = random_function(&mut rng, n, 0.1);
+                dont_cares.retain(|m| !on_set.contains(m));
+                let mut qm = QuineMcCluskey::<Enc16>::new(n);
+                qm.set_minterms(on_set.iter().map(|&m| m as u32).collect());
+                qm.set_dont_cares(dont_cares.iter().map(|&m| m as u32).collect());
+                let yielded: Vec<Implicant<Enc16>> = qm.prime_implicants_iter().collect();
+                assert_eq_seeded!(
+                    seed,
+                    qm.get_solution_steps().last().unwrap(),
+                    &format!("Found {} prime implicants", yielded.len())
+                );
+
+                let mut cubes: Vec<(u64, u64)> = yielded
+                    .iter()
+                    .map(|pi| {
+                        (0..n).fold((0, 0), |(values, dc), i| match pi.get_bit(i) {
+                            BitState::One => (values | (1 << i), dc),
+                            BitState::Zero => (values, dc),
+                            BitState::DontCare => (values, dc | (1 << i)),
+                        })
+                    })
+                    .collect();
+                cubes.sort_unstable();
+                let on_or_dc: Vec<u64> = on_set.iter().chain(&dont_cares).copied().collect();
+                assert_eq_seeded!(seed, cubes, brute_force_primes(n, &on_or_dc), "n {}", n);
+            }
+        }
+    }
+}
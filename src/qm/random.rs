@@ -1,9 +1,16 @@
 //! Random minterm generation utilities
 //!
 //! This module provides utilities for generating random minterms for testing
-//! and benchmarking the Quine-McCluskey algorithm.
+//! and benchmarking the Quine-McCluskey algorithm, plus a few structured function
+//! families (adders, comparators, symmetric functions, decoders) whose minterms are
+//! far from random.
+//!
+//! Every random generator draws from a caller-supplied `Rng` (or a seed), never from the
+//! OS, so a failure can be replayed from its seed. Randomized tests take their seed from
+//! [`test_seed`] and report it with [`assert_seeded!`](crate::assert_seeded).
 
 use rand::distr::uniform::SampleUniform;
+use rand::seq::index;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -14,13 +21,13 @@ pub trait RandomMinterm: SampleUniform + Ord + Hash + Copy + Sized {
     const MAX_VARS: usize;
 
     /// Generate a random value in range [0, 2^n_variables)
-    fn random_in_range(rng: &mut StdRng, n_variables: usize) -> Self;
+    fn random_in_range<R: Rng + ?Sized>(rng: &mut R, n_variables: usize) -> Self;
 }
 
 impl RandomMinterm for u32 {
     const MAX_VARS: usize = 32;
 
-    fn random_in_range(rng: &mut StdRng, n_variables: usize) -> Self {
+    fn random_in_range<R: Rng + ?Sized>(rng: &mut R, n_variables: usize) -> Self {
         if n_variables >= 32 {
             rng.random_range(0..=u32::MAX)
         } else {
@@ -33,7 +40,7 @@ impl RandomMinterm for u32 {
 impl RandomMinterm for u64 {
     const MAX_VARS: usize = 64;
 
-    fn random_in_range(rng: &mut StdRng, n_variables: usize) -> Self {
+    fn random_in_range<R: Rng + ?Sized>(rng: &mut R, n_variables: usize) -> Self {
         if n_variables >= 64 {
             rng.random_range(0..=u64::MAX)
         } else {
@@ -46,7 +53,7 @@ impl RandomMinterm for u64 {
 impl RandomMinterm for u128 {
     const MAX_VARS: usize = 128;
 
-    fn random_in_range(rng: &mut StdRng, n_variables: usize) -> Self {
+    fn random_in_range<R: Rng + ?Sized>(rng: &mut R, n_variables: usize) -> Self {
         if n_variables >= 128 {
             rng.random_range(0..=u128::MAX)
         } else {
@@ -94,6 +101,18 @@ pub fn generate_random_minterms<T: RandomMinterm>(
     n_variables: usize,
     n_minterms: usize,
     seed: u64,
+) -> Vec<T> {
+    random_minterms(&mut StdRng::seed_from_u64(seed), n_variables, n_minterms)
+}
+
+/// [`generate_random_minterms`] drawing from `rng`
+///
+/// # Panics
+/// Panics if `n_variables` exceeds the type's capacity or is zero
+pub fn random_minterms<T: RandomMinterm, R: Rng + ?Sized>(
+    rng: &mut R,
+    n_variables: usize,
+    n_minterms: usize,
 ) -> Vec<T> {
     assert!(
         n_variables <= T::MAX_VARS,
@@ -103,12 +122,11 @@ pub fn generate_random_minterms<T: RandomMinterm>(
     );
     assert!(n_variables > 0, "Number of variables must be positive");
 
-    let mut rng = StdRng::seed_from_u64(seed);
     let mut minterms = HashSet::new();
 
     // Generate unique random minterms
     while minterms.len() < n_minterms {
-        let minterm = T::random_in_range(&mut rng, n_variables);
+        let minterm = T::random_in_range(rng, n_variables);
         minterms.insert(minterm);
     }
 
@@ -117,6 +135,202 @@ pub fn generate_random_minterms<T: RandomMinterm>(
     result
 }
 
+/// Most variables a structured generator enumerates (2^n candidate minterms)
+pub const MAX_STRUCTURED_VARS: usize = 24;
+
+/// Minterms `m < 2^n_variables` with `f(m)`, ascending
+fn enumerate(n_variables: usize, mut f: impl FnMut(u64) -> bool) -> Vec<u64> {
+    assert!(
+        n_variables <= MAX_STRUCTURED_VARS,
+        "Number of variables ({}) exceeds structured generator limit (max {})",
+        n_variables,
+        MAX_STRUCTURED_VARS
+    );
+    (0..1u64 << n_variables).filter(|&m| f(m)).collect()
+}
+
+/// Carry-out of an `n`-bit adder: `a + b >= 2^n`
+///
+/// Variables `0..n` are `a` (bit 0 least significant), variables `n..2n` are `b`.
+pub fn adder_carry(n: usize) -> Vec<u64> {
+    let mask = (1u64 << n) - 1;
+    enumerate(2 * n, |m| (m & mask) + (m >> n) > mask)
+}
+
+/// Magnitude comparator `a > b` on two `n`-bit operands, laid out as [`adder_carry`]
+pub fn comparator(n: usize) -> Vec<u64> {
+    let mask = (1u64 << n) - 1;
+    enumerate(2 * n, |m| (m & mask) > (m >> n))
+}
+
+/// Odd parity of `n` variables: no two minterms are adjacent, so nothing merges
+pub fn parity(n: usize) -> Vec<u64> {
+    enumerate(n, |m| m.count_ones() % 2 == 1)
+}
+
+/// Symmetric threshold function: at least `k` of the `n` variables are 1
+pub fn threshold(n: usize, k: u32) -> Vec<u64> {
+    enumerate(n, |m| m.count_ones() >= k)
+}
+
+/// Decoder with `n` address variables and an enable (variable `n`), true on `enabled_lines`
+///
+/// # Panics
+/// Panics if a line does not fit in `n` bits
+pub fn decoder(n: usize, enabled_lines: &[u64]) -> Vec<u64> {
+    assert!(
+        enabled_lines.iter().all(|&line| line < 1u64 << n),
+        "Decoder line exceeds {} address bits",
+        n
+    );
+    enumerate(n + 1, |m| {
+        m >> n == 1 && enabled_lines.contains(&(m & ((1u64 << n) - 1)))
+    })
+}
+
+/// Shape of a random on-set drawn by [`random_on_set`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Every minterm independently with probability `density` (in `[0, 1]`)
+    Uniform { density: f64 },
+    /// Every minterm but `⌈(1 - density) · 2^n⌉` random ones; above a density of 0.95 only a
+    /// few off-set rows remain, leaving many large, overlapping prime implicants
+    NearComplete { density: f64 },
+    /// Parity of a random non-empty subset of the variables, possibly inverted: a
+    /// checkerboard in which no two minterms that differ in one of those variables merge
+    Checkerboard,
+    /// The minterms of one random cube with `literals` fixed variables, which is also the
+    /// minimum cover
+    SingleCube { literals: usize },
+}
+
+/// On-set over `n_variables` (at most [`MAX_STRUCTURED_VARS`]) of the given shape, ascending
+pub fn random_on_set<R: Rng + ?Sized>(rng: &mut R, n_variables: usize, shape: Shape) -> Vec<u64> {
+    match shape {
+        Shape::Uniform { density } => random_function(rng, n_variables, density),
+        Shape::NearComplete { density } => near_complete(rng, n_variables, density),
+        Shape::Checkerboard => checkerboard(rng, n_variables),
+        Shape::SingleCube { literals } => single_cube(rng, n_variables, literals),
+    }
+}
+
+/// [`Shape::Uniform`]: each minterm with probability `density`
+///
+/// # Panics
+/// Panics if `density` is outside `[0, 1]`
+pub fn random_function<R: Rng + ?Sized>(rng: &mut R, n_variables: usize, density: f64) -> Vec<u64> {
+    assert!(
+        (0.0..=1.0).contains(&density),
+        "Density {} is outside [0, 1]",
+        density
+    );
+    enumerate(n_variables, |_| rng.random_bool(density))
+}
+
+/// [`Shape::NearComplete`]: all minterms except a random `1 - density` fraction (rounded up)
+///
+/// # Panics
+/// Panics if `density` is outside `[0, 1]`
+pub fn near_complete<R: Rng + ?Sized>(rng: &mut R, n_variables: usize, density: f64) -> Vec<u64> {
+    assert!(
+        (0.0..=1.0).contains(&density),
+        "Density {} is outside [0, 1]",
+        density
+    );
+    let rows = 1usize << n_variables.min(MAX_STRUCTURED_VARS);
+    let missing = (((1.0 - density) * rows as f64).ceil() as usize).min(rows);
+    let off_set: HashSet<u64> = index::sample(rng, rows, missing)
+        .into_iter()
+        .map(|row| row as u64)
+        .collect();
+    enumerate(n_variables, |m| !off_set.contains(&m))
+}
+
+/// [`Shape::Checkerboard`]: (inverted) parity of a random non-empty subset of the variables
+///
+/// # Panics
+/// Panics if `n_variables` is zero
+pub fn checkerboard<R: Rng + ?Sized>(rng: &mut R, n_variables: usize) -> Vec<u64> {
+    assert!(n_variables > 0, "Number of variables must be positive");
+    let mask = rng.random_range(1..1u64 << n_variables.min(MAX_STRUCTURED_VARS));
+    let inverted = rng.random_bool(0.5);
+    enumerate(n_variables, |m| {
+        ((m & mask).count_ones() % 2 == 1) != inverted
+    })
+}
+
+/// [`Shape::SingleCube`]: the minterms of a random cube with `literals` fixed variables
+///
+/// # Panics
+/// Panics if `literals` exceeds `n_variables`
+pub fn single_cube<R: Rng + ?Sized>(rng: &mut R, n_variables: usize, literals: usize) -> Vec<u64> {
+    assert!(
+        literals <= n_variables,
+        "A cube over {} variables has at most {} literals, not {}",
+        n_variables,
+        n_variables,
+        literals
+    );
+    let fixed = index::sample(rng, n_variables, literals)
+        .into_iter()
+        .fold(0u64, |mask, var| mask | 1 << var);
+    let values = rng.random::<u64>() & fixed;
+    enumerate(n_variables, |m| m & fixed == values)
+}
+
+/// Environment variable that overrides the seed of the randomized tests
+pub const SEED_VAR: &str = "QM_TEST_SEED";
+
+/// Seed for a randomized test: the value of [`SEED_VAR`] when set, else `default`
+///
+/// # Panics
+/// Panics if [`SEED_VAR`] is set to something other than a `u64`
+pub fn test_seed(default: u64) -> u64 {
+    match std::env::var(SEED_VAR) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{}={:?} is not a u64 seed", SEED_VAR, value)),
+        Err(_) => default,
+    }
+}
+
+/// Seeds for a test that runs once per seed: only [`SEED_VAR`] when set, else `0..count`
+pub fn test_seeds(count: u64) -> Vec<u64> {
+    match std::env::var_os(SEED_VAR) {
+        Some(_) => vec![test_seed(0)],
+        None => (0..count).collect(),
+    }
+}
+
+/// `assert!` that appends the seed to the failure message, so the test can be replayed
+/// with `QM_TEST_SEED=<seed>`
+#[macro_export]
+macro_rules! assert_seeded {
+    ($seed:expr, $cond:expr $(,)?) => {
+        assert!(
+            $cond,
+            "assertion failed: {} (seed {})",
+            stringify!($cond),
+            $seed
+        )
+    };
+    ($seed:expr, $cond:expr, $($arg:tt)+) => {
+        assert!($cond, "{} (seed {})", format_args!($($arg)+), $seed)
+    };
+}
+
+/// `assert_eq!` that appends the seed to the failure message, as [`assert_seeded!`]
+#[macro_export]
+macro_rules! assert_eq_seeded {
+    ($seed:expr, $left:expr, $right:expr $(,)?) => {
+        assert_eq!($left, $right, "seed {}", $seed)
+    };
+    ($seed:expr, $left:expr, $right:expr, $($arg:tt)+) => {
+        assert_eq!($left, $right, "{} (seed {})", format_args!($($arg)+), $seed)
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +386,82 @@ mod tests {
     fn test_zero_variables() {
         let _: Vec<u32> = generate_random_minterms(0, 10, 42);
     }
+
+    #[test]
+    fn test_structured_families() {
+        // 2-bit carry: a + b >= 4
+        let carry = adder_carry(2);
+        assert!(carry.contains(&0b1111)); // 3 + 3
+        assert!(carry.contains(&0b1110)); // a = 2, b = 3
+        assert!(!carry.contains(&0b0110)); // a = 2, b = 1
+        assert_eq!(carry.len(), 6);
+        assert_eq!(comparator(2).len(), 6);
+        assert_eq!(parity(4).len(), 8);
+        assert_eq!(threshold(4, 3), vec![7, 11, 13, 14, 15]);
+        assert_eq!(decoder(3, &[1, 6]), vec![0b1001, 0b1110]);
+    }
+
+    #[test]
+    fn test_generators_follow_the_rng() {
+        let draw = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let minterms: Vec<u32> = random_minterms(&mut rng, 12, 40);
+            (
+                minterms,
+                random_function(&mut rng, 8, 0.3),
+                near_complete(&mut rng, 8, 0.97),
+                checkerboard(&mut rng, 8),
+                single_cube(&mut rng, 8, 3),
+            )
+        };
+        assert_eq!(draw(5), draw(5));
+        assert_ne!(draw(5), draw(6));
+        assert_eq!(draw(42).0, generate_random_minterms::<u32>(12, 40, 42));
+    }
+
+    #[test]
+    fn test_adversarial_shapes() {
+        use crate::minimize_function;
+
+        let seed = test_seed(9);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for n in 1..=8 {
+            let rows = 1u64 << n;
+
+            let on_set = random_on_set(&mut rng, n, Shape::NearComplete { density: 0.96 });
+            let missing = ((0.04 * rows as f64).ceil()) as u64;
+            crate::assert_eq_seeded!(seed, on_set.len() as u64, rows - missing, "n {}", n);
+
+            // Flipping any variable of the subset leaves the on-set
+            let on_set = random_on_set(&mut rng, n, Shape::Checkerboard);
+            crate::assert_eq_seeded!(seed, on_set.len() as u64, rows / 2, "n {}", n);
+            let subset = (0..n)
+                .filter(|&var| !on_set.contains(&(on_set[0] ^ 1 << var)))
+                .count();
+            let result = minimize_function(&on_set, None, n);
+            crate::assert_eq_seeded!(seed, result.cover.len(), 1 << (subset - 1), "n {}", n);
+
+            let literals = rng.random_range(0..=n);
+            let on_set = random_on_set(&mut rng, n, Shape::SingleCube { literals });
+            crate::assert_eq_seeded!(seed, on_set.len(), 1 << (n - literals), "n {}", n);
+            let result = minimize_function(&on_set, None, n);
+            crate::assert_eq_seeded!(seed, result.cover.len(), 1, "n {}", n);
+            crate::assert_seeded!(
+                seed,
+                result.cover[0].1.count_ones() as usize == n - literals
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "(seed 17)")]
+    fn test_assert_seeded_names_the_seed() {
+        crate::assert_seeded!(17, 1 + 1 == 3, "arithmetic {}", "broke");
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds structured generator limit")]
+    fn test_structured_limit() {
+        let _ = adder_carry(13);
+    }
 }
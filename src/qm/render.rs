@@ -0,0 +1,424 @@
+//! Text renderers for step-by-step Quine-McCluskey solutions
+//!
+//! Produces the tabular artifacts found in textbooks: the grouped-term table of each
+//! combining pass, a combining table showing which pairs merged into which pattern,
+//! and the prime implicant chart with essential prime implicants starred and the passes
+//! that produced each prime implicant as superscripts.
+//!
+//! Terms use the classic encoding (see [`classic`](super::classic)): data bits at
+//! `0..n_variables`, don't-care mask at `E::DK_OFFSET`. Variable `names[i]` is bit `i`,
+//! patterns are printed most significant bit first.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::classic::petrick::{self, PITable1};
+use super::classic::{is_gray_code, minterm_to_string, replace_complements};
+use super::encoding::{BitOps, MintermEncoding};
+
+/// One combining pass of the tabular method
+#[derive(Debug, Clone)]
+pub struct CombiningPass<V> {
+    /// Terms entering this pass (sorted, deduplicated)
+    pub terms: Vec<V>,
+    /// `(left, right, merged)` for every pair that differs in exactly one bit
+    pub merges: Vec<(V, V, V)>,
+}
+
+impl<V: PartialEq> CombiningPass<V> {
+    /// Did `term` take part in at least one merge during this pass?
+    pub fn is_merged(&self, term: &V) -> bool {
+        self.merges
+            .iter()
+            .any(|(left, right, _)| left == term || right == term)
+    }
+}
+
+/// Run the tabular method on `terms` (minterms and don't-cares), recording every pass
+///
+/// Returns the passes and the prime implicants (terms that never merged).
+pub fn combining_passes<E: MintermEncoding>(
+    terms: &[E::Value],
+) -> (Vec<CombiningPass<E::Value>>, Vec<E::Value>) {
+    let mut passes = Vec::new();
+    let mut primes = BTreeSet::new();
+    let mut current: Vec<E::Value> = terms
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    while !current.is_empty() {
+        let mut merged = vec![false; current.len()];
+        let mut merges = Vec::new();
+        let mut next = BTreeSet::new();
+
+        for i in 0..current.len() {
+            for j in (i + 1)..current.len() {
+                if is_gray_code::<E>(current[i], current[j]) {
+                    let new_term = replace_complements::<E>(current[i], current[j]);
+                    merged[i] = true;
+                    merged[j] = true;
+                    merges.push((current[i], current[j], new_term));
+                    next.insert(new_term);
+                }
+            }
+        }
+
+        for (i, &term) in current.iter().enumerate() {
+            if !merged[i] {
+                primes.insert(term);
+            }
+        }
+
+        passes.push(CombiningPass {
+            terms: current,
+            merges,
+        });
+        current = next.into_iter().collect();
+    }
+
+    (passes, primes.into_iter().collect())
+}
+
+/// For each prime implicant of `passes`: the pass that produced it (0 for a minterm or
+/// don't-care) and the pass at which it became prime, numbering passes from 1
+pub fn prime_passes<V: Ord + Copy>(passes: &[CombiningPass<V>]) -> BTreeMap<V, (usize, usize)> {
+    let mut result = BTreeMap::new();
+    for (i, pass) in passes.iter().enumerate() {
+        for term in &pass.terms {
+            if !pass.is_merged(term) {
+                result.insert(*term, (i, i + 1));
+            }
+        }
+    }
+    result
+}
+
+/// `n` in superscript digits, e.g. `¹²`
+fn superscript(n: usize) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .bytes()
+        .map(|digit| DIGITS[(digit - b'0') as usize])
+        .collect()
+}
+
+/// Number of `1` positions in a pattern (don't-care positions excluded)
+fn ones_in_pattern(pattern: &str) -> usize {
+    pattern.chars().filter(|&c| c == '1').count()
+}
+
+/// Product term for a classic-encoded pattern, e.g. `AB'` (`1` for an all-don't-care term)
+pub fn pattern_to_formula<E: MintermEncoding>(
+    term: E::Value,
+    n_variables: usize,
+    names: &[String],
+) -> String {
+    let mut result = String::new();
+    for (i, name) in names.iter().enumerate().take(n_variables) {
+        if !term.get_bit(i + E::DK_OFFSET) {
+            result.push_str(name);
+            if !term.get_bit(i) {
+                result.push('\'');
+            }
+        }
+    }
+    if result.is_empty() {
+        result.push('1');
+    }
+    result
+}
+
+/// Fixed-width table of the terms of one pass, grouped by their number of ones
+///
+/// Terms that merged with another term are checked off with `✓`.
+pub fn render_grouping_table<E: MintermEncoding>(
+    pass: &CombiningPass<E::Value>,
+    n_variables: usize,
+) -> String {
+    let width = n_variables.max("Term".len());
+    let mut rows: Vec<(usize, String, bool)> = pass
+        .terms
+        .iter()
+        .map(|term| {
+            let pattern = minterm_to_string::<E>(n_variables, *term);
+            (ones_in_pattern(&pattern), pattern, pass.is_merged(term))
+        })
+        .collect();
+    rows.sort();
+
+    let mut result = format!("Group | {:<width$} | Used\n", "Term");
+    result.push_str(&format!("------+-{}-+-----\n", "-".repeat(width)));
+
+    let mut previous_group = None;
+    for (group, pattern, used) in rows {
+        let group_label = if previous_group == Some(group) {
+            String::new()
+        } else {
+            group.to_string()
+        };
+        previous_group = Some(group);
+        let row = format!(
+            "{:<5} | {:<width$} | {}",
+            group_label,
+            pattern,
+            if used { "✓" } else { "" }
+        );
+        result.push_str(row.trim_end());
+        result.push('\n');
+    }
+    result
+}
+
+/// Fixed-width table of the pairs that merged during one pass
+pub fn render_combining_table<E: MintermEncoding>(
+    pass: &CombiningPass<E::Value>,
+    n_variables: usize,
+) -> String {
+    if pass.merges.is_empty() {
+        return String::from(
+            "No pairs differ in exactly one bit; all remaining terms are prime.\n",
+        );
+    }
+
+    let width = n_variables.max("Merged".len());
+    let mut result = format!("{:<width$} | {:<width$} | Merged\n", "Left", "Right");
+    result.push_str(&format!(
+        "{}-+-{}-+-{}\n",
+        "-".repeat(width),
+        "-".repeat(width),
+        "-".repeat(width)
+    ));
+    for &(left, right, merged) in &pass.merges {
+        result.push_str(&format!(
+            "{:<width$} | {:<width$} | {}\n",
+            minterm_to_string::<E>(n_variables, left),
+            minterm_to_string::<E>(n_variables, right),
+            minterm_to_string::<E>(n_variables, merged)
+        ));
+    }
+    result
+}
+
+/// Prime implicants that are the only cover of at least one minterm
+pub fn essential_prime_implicants<E: MintermEncoding>(
+    pi_table: &PITable1<E::Value>,
+) -> BTreeSet<E::Value> {
+    essentiality_witnesses::<E>(pi_table).into_keys().collect()
+}
+
+/// Essential prime implicants, each with the minterms (ascending) it alone covers
+pub fn essentiality_witnesses<E: MintermEncoding>(
+    pi_table: &PITable1<E::Value>,
+) -> BTreeMap<E::Value, Vec<E::Value>> {
+    let all_minterms: BTreeSet<E::Value> = pi_table.values().flatten().copied().collect();
+    let mut witnesses: BTreeMap<E::Value, Vec<E::Value>> = BTreeMap::new();
+    for mt in all_minterms {
+        let mut covering = pi_table.iter().filter(|(_, set)| set.contains(&mt));
+        if let (Some((&pi, _)), None) = (covering.next(), covering.next()) {
+            witnesses.entry(pi).or_default().push(mt);
+        }
+    }
+    witnesses
+}
+
+/// Prime implicant chart (via `to_string_pi_table1`) followed by a legend with formulas
+///
+/// A prime implicant in `prime_passes` (see [`prime_passes`]) gets its passes as the
+/// superscript `born⁻prime`, e.g. `0X1X²⁻³`: produced by pass 2, prime at pass 3.
+pub fn render_pi_chart<E: MintermEncoding>(
+    pi_table: &PITable1<E::Value>,
+    n_variables: usize,
+    names: &[String],
+    prime_passes: &BTreeMap<E::Value, (usize, usize)>,
+) -> String {
+    let witnesses = essentiality_witnesses::<E>(pi_table);
+    let passes: BTreeMap<E::Value, String> = pi_table
+        .keys()
+        .filter_map(|pi| {
+            let &(born, prime) = prime_passes.get(pi)?;
+            Some((*pi, format!("{}⁻{}", superscript(born), superscript(prime))))
+        })
+        .collect();
+    let passes_width = passes
+        .values()
+        .map(|p| p.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut result = petrick::to_string_pi_table1::<E>(pi_table, n_variables);
+    if passes.is_empty() {
+        result.push_str("\nPrime implicants (* = essential):\n");
+    } else {
+        result.push_str(
+            "\nPrime implicants (* = essential, ᵇ⁻ᵖ = produced by pass b, prime at pass p):\n",
+        );
+    }
+    for &pi in pi_table.keys() {
+        let pass = passes.get(&pi).map_or("", String::as_str);
+        let line = format!(
+            "{} {}{:<passes_width$} = {}",
+            if witnesses.contains_key(&pi) {
+                '*'
+            } else {
+                ' '
+            },
+            minterm_to_string::<E>(n_variables, pi),
+            pass,
+            pattern_to_formula::<E>(pi, n_variables, names)
+        );
+        match witnesses.get(&pi) {
+            Some(witness) => {
+                let list: Vec<String> = witness.iter().map(|m| m.to_u64().to_string()).collect();
+                result.push_str(&format!(
+                    "{}  (only cover of {} {})\n",
+                    line,
+                    if witness.len() == 1 {
+                        "minterm"
+                    } else {
+                        "minterms"
+                    },
+                    list.join(", ")
+                ));
+            }
+            None => result.push_str(&format!("{}\n", line)),
+        }
+    }
+    result
+}
+
+/// Render all steps: grouping and combining tables per pass, then the PI chart
+pub fn render_steps<E: MintermEncoding>(
+    minterms: &[E::Value],
+    dont_cares: &[E::Value],
+    n_variables: usize,
+    names: &[String],
+) -> String {
+    let all_terms: Vec<E::Value> = minterms.iter().chain(dont_cares).copied().collect();
+    let (passes, primes) = combining_passes::<E>(&all_terms);
+
+    let columns: Vec<&str> = names
+        .iter()
+        .take(n_variables)
+        .rev()
+        .map(String::as_str)
+        .collect();
+    let mut result = format!("Variables (pattern order): {}\n", columns.join(" "));
+
+    for (i, pass) in passes.iter().enumerate() {
+        result.push_str(&format!(
+            "\nPass {}: terms grouped by number of ones\n",
+            i + 1
+        ));
+        result.push_str(&render_grouping_table::<E>(pass, n_variables));
+        result.push_str(&format!("\nPass {}: combining adjacent groups\n", i + 1));
+        result.push_str(&render_combining_table::<E>(pass, n_variables));
+    }
+
+    let pi_table = petrick::create_prime_implicant_table::<E>(&primes, minterms);
+    result.push_str("\nPrime implicant chart (* = minterm covered by a single prime implicant)\n");
+    result.push_str(&render_pi_chart::<E>(
+        &pi_table,
+        n_variables,
+        names,
+        &prime_passes(&passes),
+    ));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::encoding::Enc16;
+
+    #[test]
+    fn test_combining_passes_finds_primes() {
+        // Σ(0,1,2,5,6,7) over 3 variables has six prime implicants of size 2
+        let minterms: Vec<u32> = vec![0, 1, 2, 5, 6, 7];
+        let (passes, primes) = combining_passes::<Enc16>(&minterms);
+
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[0].merges.len(), 6);
+        assert!(passes[1].merges.is_empty());
+
+        let mut patterns: Vec<String> = primes
+            .iter()
+            .map(|&pi| minterm_to_string::<Enc16>(3, pi))
+            .collect();
+        patterns.sort();
+        assert_eq!(patterns, vec!["00X", "0X0", "11X", "1X1", "X01", "X10"]);
+    }
+
+    #[test]
+    fn test_prime_passes_of_textbook_example() {
+        use crate::qm::QuineMcCluskey;
+
+        // Σ(0,1,2,5,6,7,8,9,10,14): the quads 0,1,8,9, 0,2,8,10 and 2,6,10,14 are produced
+        // by pass 2 and prime at pass 3, the pairs 1,5, 5,7 and 6,7 are prime at pass 2
+        let minterms: Vec<u32> = vec![0, 1, 2, 5, 6, 7, 8, 9, 10, 14];
+        let covers = |pi: &[u32], expected: &[u32]| {
+            let mut pi = pi.to_vec();
+            pi.sort_unstable();
+            pi == expected
+        };
+        let expected: [(&[u32], usize, usize); 6] = [
+            (&[0, 1, 8, 9], 2, 3),
+            (&[0, 2, 8, 10], 2, 3),
+            (&[2, 6, 10, 14], 2, 3),
+            (&[1, 5], 1, 2),
+            (&[5, 7], 1, 2),
+            (&[6, 7], 1, 2),
+        ];
+
+        let mut qm = QuineMcCluskey::<Enc16>::new(4);
+        qm.set_minterms(minterms.clone());
+        let traced = qm.find_traced_prime_implicants();
+        assert_eq!(traced.len(), expected.len());
+        for (cube, born, prime) in expected {
+            let pi = traced
+                .iter()
+                .find(|t| {
+                    covers(
+                        &t.implicant
+                            .covered_minterms
+                            .iter()
+                            .copied()
+                            .collect::<Vec<_>>(),
+                        cube,
+                    )
+                })
+                .unwrap_or_else(|| panic!("no prime implicant covers {:?}", cube));
+            assert_eq!((pi.order(), pi.prime_at_pass), (born, prime));
+        }
+
+        // The renderer's passes agree
+        let (passes, primes) = combining_passes::<Enc16>(&minterms);
+        let by_pass = prime_passes(&passes);
+        assert_eq!(by_pass.len(), primes.len());
+        for (cube, born, prime) in expected {
+            let pi = primes
+                .iter()
+                .find(|&&pi| {
+                    let points: Vec<u32> = (0..16)
+                        .filter(|&m| (m ^ pi) & !(pi >> Enc16::DK_OFFSET) & 0b1111 == 0)
+                        .collect();
+                    covers(&points, cube)
+                })
+                .unwrap();
+            assert_eq!(by_pass[pi], (born, prime));
+        }
+        let chart =
+            render_steps::<Enc16>(&minterms, &[], 4, &["A", "B", "C", "D"].map(String::from));
+        assert!(chart.contains("X00X²⁻³ = B'C'"), "{}", chart);
+        assert!(chart.contains("01X1¹⁻² = ACD'"), "{}", chart);
+    }
+
+    #[test]
+    fn test_pattern_to_formula() {
+        let names: Vec<String> = ["A", "B", "C"].iter().map(|s| s.to_string()).collect();
+        // 1X0: C=1, B=don't care, A=0
+        let term = replace_complements::<Enc16>(0b100, 0b110);
+        assert_eq!(pattern_to_formula::<Enc16>(term, 3, &names), "A'C");
+    }
+}
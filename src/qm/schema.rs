@@ -0,0 +1,164 @@
+//! JSON Schema of the serialized [`QMResult`](super::QMResult)
+//!
+//! The CLI's `minimize -f json` output is this object plus the CLI-only fields
+//! `dont_cares`, `minimized_pos`, `best_form` (with `--best-form`), `cost_reduction` and
+//! `truth_table`.
+
+use serde_json::{Value, json};
+
+/// JSON Schema (draft 2020-12) of a serialized [`QMResult`](super::QMResult)
+pub fn result_schema() -> Value {
+    let minterm_list = json!({ "type": "array", "items": { "type": "integer", "minimum": 0 } });
+    let string_list = json!({ "type": "array", "items": { "type": "string" } });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "QMResult",
+        "description": "Result of Quine-McCluskey minimization",
+        "type": "object",
+        "properties": {
+            "minimized_sop": {
+                "type": "string",
+                "description": "Minimized sum-of-products expression, e.g. \"A'B + AC\"; \"0\" for the empty cover"
+            },
+            "prime_implicants": string_list,
+            "essential_prime_implicants": string_list,
+            "steps": {
+                "type": ["array", "null"],
+                "items": { "type": "string" },
+                "description": "Solution steps, null when there are none (not requested)"
+            },
+            "cost_original": { "type": "integer", "minimum": 0 },
+            "cost_minimized": { "type": "integer", "minimum": 0 },
+            "original_minterms": minterm_list,
+            "used_dont_cares": minterm_list,
+            "unused_dont_cares": minterm_list,
+            "n_variables": { "type": "integer", "minimum": 0 },
+            "cover": {
+                "type": "array",
+                "description": "Selected implicants as [values, don't-care mask]; bit i is variable i",
+                "items": {
+                    "type": "array",
+                    "prefixItems": [
+                        { "type": "integer", "minimum": 0 },
+                        { "type": "integer", "minimum": 0 }
+                    ],
+                    "items": false
+                }
+            },
+            "hazard_terms": string_list,
+            "variables_used": string_list,
+            "referenced_variables": {
+                "type": "array",
+                "description": "Bit indices of the input variables that variables_used names",
+                "items": { "type": "integer", "minimum": 0 }
+            },
+            "essential_only_cover": { "type": "boolean" },
+            "essential_cover_fraction": { "type": "number", "minimum": 0, "maximum": 1 },
+            "petrick_invoked": { "type": "boolean" },
+            "petrick_runner_ups": { "type": ["integer", "null"], "minimum": 0 },
+            "petrick_clauses_removed": { "type": "integer", "minimum": 0 },
+            "essentiality_witnesses": {
+                "type": "array",
+                "description": "[index into essential_prime_implicants, minterms only it covers]",
+                "items": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "integer", "minimum": 0 }, minterm_list],
+                    "items": false
+                }
+            },
+            "complemented": { "type": "boolean" },
+            "symmetry_classes": {
+                "type": "array",
+                "description": "Classes of interchangeable variables, as bit indices",
+                "items": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "peak_memory_bytes": {
+                "type": "integer",
+                "minimum": 0,
+                "description": "Most bytes tracked at a phase boundary of the solve"
+            },
+            "duplicates_removed": {
+                "type": "integer",
+                "minimum": 0,
+                "description": "Repeated minterms and don't-cares dropped from the input"
+            }
+        },
+        "required": [
+            "minimized_sop",
+            "prime_implicants",
+            "essential_prime_implicants",
+            "steps",
+            "original_minterms"
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::{Enc16, QMResult, QMSolver};
+
+    /// Does `value` have the JSON type named by `schema["type"]`?
+    fn has_type(value: &Value, schema: &Value) -> bool {
+        // A list of types: any of them
+        if let Some(types) = schema["type"].as_array() {
+            return types.iter().any(|t| {
+                let mut single = schema.clone();
+                single["type"] = t.clone();
+                has_type(value, &single)
+            });
+        }
+        match schema["type"].as_str() {
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_u64() || value.is_i64(),
+            Some("number") => value.is_number(),
+            Some("boolean") => value.is_boolean(),
+            Some("null") => value.is_null(),
+            Some("array") => value.as_array().is_some_and(|items| {
+                items
+                    .iter()
+                    .enumerate()
+                    .all(|(i, item)| match schema["prefixItems"].as_array() {
+                        Some(prefix) => prefix.get(i).is_some_and(|s| has_type(item, s)),
+                        None => has_type(item, &schema["items"]),
+                    })
+            }),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_schema_matches_serialized_result() {
+        let mut solver = QMSolver::<Enc16>::new(4);
+        solver.set_minterms(vec![0, 1, 2, 5, 6, 7, 8, 9, 10, 14]);
+        solver.set_dont_cares(vec![15]);
+        let value = serde_json::to_value(solver.solve()).unwrap();
+
+        let schema = result_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        let fields = value.as_object().unwrap();
+        let mut serialized: Vec<&String> = fields.keys().collect();
+        let mut described: Vec<&String> = properties.keys().collect();
+        serialized.sort();
+        described.sort();
+        assert_eq!(serialized, described);
+
+        for (name, field) in fields {
+            assert!(has_type(field, &properties[name]), "field {}", name);
+        }
+        for required in schema["required"].as_array().unwrap() {
+            assert!(fields.contains_key(required.as_str().unwrap()));
+        }
+
+        // Without steps the field is null, as the CLI has always written it
+        let mut result = solver.solve();
+        result.solution_steps.clear();
+        let value = serde_json::to_value(&result).unwrap();
+        assert!(value["steps"].is_null());
+        assert!(has_type(&value["steps"], &properties["steps"]));
+        assert_eq!(serde_json::from_value::<QMResult>(value).unwrap(), result);
+    }
+}
@@ -3,9 +3,10 @@
 //! Uses bit-plane transposition and AVX-512 to check coverage for 512
 //! minterm-implicant pairs simultaneously.
 
+use super::cubes::cube_contains;
 #[cfg(all(target_arch = "x86_64", feature = "simd"))]
-use super::encoding::{BitOps, MintermEncoding};
-#[cfg(all(target_arch = "x86_64", feature = "simd"))]
+use super::encoding::BitOps;
+use super::encoding::MintermEncoding;
 use super::implicant::Implicant;
 
 /// Bit-packed coverage matrix for memory-efficient storage.
@@ -122,6 +123,163 @@ impl CoverageMatrix {
             }
         }
     }
+
+    /// Number of columns set in `row`: the minterms the prime implicant covers
+    pub fn row_popcount(&self, row: usize) -> usize {
+        let bytes = self.row(row);
+        #[cfg(all(target_arch = "x86_64", feature = "simd"))]
+        if bytes.len() >= AVX2_MIN_ROW_BYTES && is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 support was checked above
+            return unsafe { popcount_avx2(bytes) };
+        }
+        popcount_words(bytes)
+    }
+
+    /// Number of rows set in `col`: the prime implicants covering the minterm
+    pub fn col_popcount(&self, col: usize) -> usize {
+        self.col_words(col)
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if `a & !b` is empty, i.e. row `a` is a subset of row `b`: every
+    /// minterm covered by prime implicant `a` is also covered by prime implicant `b`
+    pub fn row_and_not(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.row(a), self.row(b));
+        #[cfg(all(target_arch = "x86_64", feature = "simd"))]
+        if a.len() >= AVX2_MIN_ROW_BYTES && is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 support was checked above
+            return unsafe { is_subset_avx2(a, b) };
+        }
+        is_subset_words(a, b)
+    }
+
+    /// Returns `true` if column `a` is a subset of column `b`: every prime implicant
+    /// covering minterm `a` also covers minterm `b`
+    pub fn col_subset(&self, a: usize, b: usize) -> bool {
+        self.col_words(a)
+            .zip(self.col_words(b))
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    /// Clear all bits of `row`
+    pub fn clear_row(&mut self, row: usize) {
+        self.row_bytes_mut(row).fill(0);
+    }
+
+    /// Clear all bits of `col`
+    pub fn clear_col(&mut self, col: usize) {
+        debug_assert!(col < self.num_cols);
+        let mask = !(1u8 << (col % 8));
+        for byte in self.data.iter_mut().skip(col / 8).step_by(self.row_bytes) {
+            *byte &= mask;
+        }
+    }
+
+    /// Column `col` with a bit per row, 64 rows per word
+    fn col_words(&self, col: usize) -> impl Iterator<Item = u64> + '_ {
+        debug_assert!(col < self.num_cols);
+        let (offset, shift) = (col / 8, col % 8);
+        self.data.chunks(64 * self.row_bytes).map(move |rows| {
+            rows.iter()
+                .skip(offset)
+                .step_by(self.row_bytes)
+                .enumerate()
+                .fold(0, |word, (i, &byte)| {
+                    word | u64::from((byte >> shift) & 1) << i
+                })
+        })
+    }
+
+    #[inline]
+    fn row(&self, row: usize) -> &[u8] {
+        debug_assert!(row < self.num_rows);
+        let start = row * self.row_bytes;
+        &self.data[start..start + self.row_bytes]
+    }
+}
+
+/// Rows at least this long (256 columns) use the AVX2 row operations
+#[cfg(all(target_arch = "x86_64", feature = "simd"))]
+const AVX2_MIN_ROW_BYTES: usize = 32;
+
+#[inline]
+fn word(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8-byte chunk"))
+}
+
+/// Set bits of `bytes`, 64 bits at a time
+fn popcount_words(bytes: &[u8]) -> usize {
+    let chunks = bytes.chunks_exact(8);
+    let tail = chunks.remainder();
+    let count: usize = chunks.map(|chunk| word(chunk).count_ones() as usize).sum();
+    count + tail.iter().map(|b| b.count_ones() as usize).sum::<usize>()
+}
+
+/// Whether no bit of `a` is missing from `b`, 64 bits at a time
+fn is_subset_words(a: &[u8], b: &[u8]) -> bool {
+    let (a_chunks, b_chunks) = (a.chunks_exact(8), b.chunks_exact(8));
+    let (a_tail, b_tail) = (a_chunks.remainder(), b_chunks.remainder());
+    a_chunks.zip(b_chunks).all(|(x, y)| word(x) & !word(y) == 0)
+        && a_tail.iter().zip(b_tail).all(|(x, y)| x & !y == 0)
+}
+
+/// Set bits of `bytes`, 256 bits at a time with a nibble lookup table
+///
+/// # Safety
+/// Requires AVX2.
+#[cfg(all(target_arch = "x86_64", feature = "simd"))]
+#[target_feature(enable = "avx2")]
+unsafe fn popcount_avx2(bytes: &[u8]) -> usize {
+    use std::arch::x86_64::*;
+
+    let n = bytes.len() / 32 * 32;
+    unsafe {
+        let lookup = _mm256_setr_epi8(
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, //
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        );
+        let low_nibble = _mm256_set1_epi8(0x0f);
+        let mut sums = _mm256_setzero_si256();
+        for i in (0..n).step_by(32) {
+            let v = _mm256_loadu_si256(bytes.as_ptr().add(i) as *const __m256i);
+            let lo = _mm256_and_si256(v, low_nibble);
+            let hi = _mm256_and_si256(_mm256_srli_epi16(v, 4), low_nibble);
+            let counts = _mm256_add_epi8(
+                _mm256_shuffle_epi8(lookup, lo),
+                _mm256_shuffle_epi8(lookup, hi),
+            );
+            // Per-byte counts are at most 8, summed into the four 64-bit lanes
+            sums = _mm256_add_epi64(sums, _mm256_sad_epu8(counts, _mm256_setzero_si256()));
+        }
+        let mut lanes = [0u64; 4];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sums);
+        lanes.iter().sum::<u64>() as usize + popcount_words(&bytes[n..])
+    }
+}
+
+/// Whether no bit of `a` is missing from `b`, 256 bits at a time
+///
+/// # Safety
+/// Requires AVX2.
+#[cfg(all(target_arch = "x86_64", feature = "simd"))]
+#[target_feature(enable = "avx2")]
+unsafe fn is_subset_avx2(a: &[u8], b: &[u8]) -> bool {
+    use std::arch::x86_64::*;
+
+    debug_assert_eq!(a.len(), b.len());
+    let n = a.len() / 32 * 32;
+    unsafe {
+        for i in (0..n).step_by(32) {
+            let va = _mm256_loadu_si256(a.as_ptr().add(i) as *const __m256i);
+            let vb = _mm256_loadu_si256(b.as_ptr().add(i) as *const __m256i);
+            // testc(vb, va) is 1 when !vb & va is zero
+            if _mm256_testc_si256(vb, va) == 0 {
+                return false;
+            }
+        }
+    }
+    is_subset_words(&a[n..], &b[n..])
 }
 
 /// Transpose bits from striped layout (bit-plane) to consecutive layout
@@ -179,6 +337,35 @@ fn transpose_striped_to_consecutive(striped: &[u8; 64], output: &mut [u8], num_b
     }
 }
 
+/// Does implicant `p` cover implicant `q`: is every minterm of `q` a minterm of `p`?
+///
+/// Unlike the minterm kernels, `q` may have don't-cares; this is
+/// [`cube_contains`] on the cubes of the two implicants. Both must be over the same
+/// variables.
+pub fn cube_covers<E: MintermEncoding>(p: &Implicant<E>, q: &Implicant<E>) -> bool {
+    debug_assert_eq!(p.n_variables, q.n_variables);
+    cube_contains::<E>(p.to_cube(), q.to_cube())
+}
+
+/// [`cube_covers`] for every pair: row `i`, column `j` is set when `covering[i]` covers
+/// `covered[j]`, the input of dominance checks between implicants
+pub fn build_cube_coverage_matrix<E: MintermEncoding>(
+    covering: &[Implicant<E>],
+    covered: &[Implicant<E>],
+) -> CoverageMatrix {
+    let columns: Vec<E::Value> = covered.iter().map(Implicant::to_cube).collect();
+    let mut matrix = CoverageMatrix::new(covering.len(), covered.len());
+    for (row, p) in covering.iter().enumerate() {
+        let p = p.to_cube();
+        for (col, &q) in columns.iter().enumerate() {
+            if cube_contains::<E>(p, q) {
+                matrix.set(row, col, true);
+            }
+        }
+    }
+    matrix
+}
+
 /// Threshold for using SIMD optimization
 /// Below this, the bit-plane conversion overhead dominates
 #[cfg(all(target_arch = "x86_64", feature = "simd"))]
@@ -199,9 +386,14 @@ pub fn should_use_simd(num_checks: usize, num_bits: usize) -> bool {
 
     #[cfg(all(target_arch = "x86_64", feature = "simd"))]
     {
-        num_checks >= SIMD_THRESHOLD
-            && is_x86_feature_detected!("avx512f")
-            && is_x86_feature_detected!("gfni")
+        if num_checks < SIMD_THRESHOLD {
+            return false;
+        }
+        let supported = is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("gfni");
+        if !supported {
+            crate::cnf_dnf::optimized_for::debug_scalar_fallback("build_coverage_matrix_simd");
+        }
+        supported
     }
 
     #[cfg(not(all(target_arch = "x86_64", feature = "simd")))]
@@ -532,6 +724,63 @@ mod tests {
         assert_eq!(mask, 0b1010);
     }
 
+    /// A random implicant over `n` variables: each variable is 0, 1 or free
+    fn random_implicant(
+        rng: &mut impl rand::Rng,
+        n: usize,
+    ) -> Implicant<crate::qm::encoding::Enc16> {
+        let states: Vec<BitState> = (0..n)
+            .map(|_| match rng.random_range(0..3) {
+                0 => BitState::Zero,
+                1 => BitState::One,
+                _ => BitState::DontCare,
+            })
+            .collect();
+        Implicant::from_raw_encoding(build_test_bits_raw(n, &states) as u32, n)
+    }
+
+    #[test]
+    fn test_cube_covers_matches_enumeration() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::test_seed;
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let seed = test_seed(11);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for n in 0..=5usize {
+            let implicants: Vec<_> = (0..12).map(|_| random_implicant(&mut rng, n)).collect();
+            // Points of an implicant by enumeration, independent of the mask algebra
+            let points = |imp: &Implicant<crate::qm::encoding::Enc16>| -> Vec<u32> {
+                (0..1u32 << n)
+                    .filter(|&m| {
+                        (0..n).all(|i| match imp.get_bit(i) {
+                            BitState::DontCare => true,
+                            BitState::One => m >> i & 1 == 1,
+                            BitState::Zero => m >> i & 1 == 0,
+                        })
+                    })
+                    .collect()
+            };
+
+            let matrix = build_cube_coverage_matrix(&implicants, &implicants);
+            for (i, p) in implicants.iter().enumerate() {
+                let p_points = points(p);
+                for (j, q) in implicants.iter().enumerate() {
+                    let expected = points(q).iter().all(|m| p_points.contains(m));
+                    assert_eq_seeded!(seed, cube_covers(p, q), expected, "{} covers {}", p, q);
+                    assert_eq_seeded!(seed, matrix.get(i, j), expected, "{} covers {}", p, q);
+                }
+            }
+        }
+
+        // A don't-care of `q` where `p` is fixed is not covered, whatever the data bits say
+        let cube =
+            |pattern: &str| Implicant::<crate::qm::encoding::Enc16>::from_pattern(pattern).unwrap();
+        assert!(cube_covers(&cube("1X"), &cube("11")));
+        assert!(!cube_covers(&cube("11"), &cube("1X")));
+        assert!(cube_covers(&cube("XX"), &cube("0X")));
+    }
+
     #[test]
     fn test_should_use_simd() {
         // Small problem: should not use SIMD
@@ -554,4 +803,75 @@ mod tests {
         // 6-bit problem: not supported
         assert!(!should_use_simd(10000, 6));
     }
+
+    #[test]
+    fn test_row_and_col_operations_match_model() {
+        use crate::assert_eq_seeded;
+        use crate::qm::random::test_seed;
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let seed = test_seed(5);
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Column counts around the 8-byte word and 32-byte AVX2 boundaries; odd ones get
+        // more than 64 rows, for the column words
+        for num_cols in [0, 1, 7, 8, 63, 64, 65, 255, 256, 257, 300, 600] {
+            let num_rows = if num_cols % 2 == 1 {
+                rng.random_range(60..140)
+            } else {
+                rng.random_range(1..6)
+            };
+            let density = rng.random_range(0.5..1.0);
+            let mut model = vec![vec![false; num_cols]; num_rows];
+            let mut matrix = CoverageMatrix::new(num_rows, num_cols);
+            for (row, bits) in model.iter_mut().enumerate() {
+                for (col, bit) in bits.iter_mut().enumerate() {
+                    *bit = rng.random_bool(density);
+                    matrix.set(row, col, *bit);
+                }
+            }
+            if num_cols > 0 && rng.random_bool(0.5) {
+                // A row that is a subset of another one, to hit the `true` case of long rows
+                let (a, b) = (rng.random_range(0..num_rows), rng.random_range(0..num_rows));
+                for col in 0..num_cols {
+                    let bit = model[b][col] && rng.random_bool(0.9);
+                    model[a][col] = bit;
+                    matrix.set(a, col, bit);
+                }
+            }
+
+            for _ in 0..3 {
+                for a in 0..num_rows {
+                    let count = model[a].iter().filter(|&&bit| bit).count();
+                    assert_eq_seeded!(seed, matrix.row_popcount(a), count);
+                    for b in 0..num_rows {
+                        let subset = (0..num_cols).all(|col| !model[a][col] || model[b][col]);
+                        assert_eq_seeded!(seed, matrix.row_and_not(a, b), subset);
+                    }
+                }
+                for a in 0..num_cols {
+                    let count = model.iter().filter(|bits| bits[a]).count();
+                    assert_eq_seeded!(seed, matrix.col_popcount(a), count);
+                    for _ in 0..4 {
+                        let b = rng.random_range(0..num_cols);
+                        let subset = model.iter().all(|bits| !bits[a] || bits[b]);
+                        assert_eq_seeded!(seed, matrix.col_subset(a, b), subset);
+                    }
+                }
+
+                if num_cols > 0 {
+                    let col = rng.random_range(0..num_cols);
+                    matrix.clear_col(col);
+                    model.iter_mut().for_each(|bits| bits[col] = false);
+                }
+                let row = rng.random_range(0..num_rows);
+                matrix.clear_row(row);
+                model[row].fill(false);
+                for (row, bits) in model.iter().enumerate() {
+                    for (col, &bit) in bits.iter().enumerate() {
+                        assert_eq_seeded!(seed, matrix.get(row, col), bit);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,81 @@
+//! Symmetric variables
+//!
+//! Variables `i` and `j` are symmetric when exchanging them leaves the function unchanged:
+//! swapping bits `i` and `j` maps the on-set onto itself and the don't-care set onto
+//! itself. Symmetry is an equivalence relation (a transposition `(i k)` is
+//! `(i j)(j k)(i j)`), so the variables fall into classes of interchangeable inputs; a
+//! function symmetric in a class depends only on how many of its variables are true.
+
+use std::collections::HashSet;
+
+/// Partition of the variables `0..n_vars` into symmetry classes
+///
+/// Classes are sorted, and ordered by their smallest variable; a variable symmetric to no
+/// other one is a class of its own.
+pub fn detect(minterms: &[u64], dont_cares: &[u64], n_vars: usize) -> Vec<Vec<usize>> {
+    let on_set: HashSet<u64> = minterms.iter().copied().collect();
+    let dc_set: HashSet<u64> = dont_cares
+        .iter()
+        .copied()
+        .filter(|m| !on_set.contains(m))
+        .collect();
+
+    let mut classes: Vec<Vec<usize>> = Vec::new();
+    let mut unassigned: Vec<usize> = (0..n_vars).collect();
+    while let Some(&first) = unassigned.first() {
+        let (class, rest) = unassigned.iter().partition(|&&j| {
+            j == first || (is_symmetric(&on_set, first, j) && is_symmetric(&dc_set, first, j))
+        });
+        classes.push(class);
+        unassigned = rest;
+    }
+    classes
+}
+
+/// Does exchanging variables `i` and `j` map `set` onto itself?
+pub fn is_symmetric(set: &HashSet<u64>, i: usize, j: usize) -> bool {
+    set.iter().all(|&m| set.contains(&swap_bits(m, i, j)))
+}
+
+/// `m` with bits `i` and `j` exchanged
+fn swap_bits(m: u64, i: usize, j: usize) -> u64 {
+    let differ = ((m >> i) ^ (m >> j)) & 1;
+    m ^ (differ << i) ^ (differ << j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qm::random::{parity, threshold};
+
+    #[test]
+    fn test_parity_and_majority_are_totally_symmetric() {
+        assert_eq!(detect(&parity(5), &[], 5), vec![vec![0, 1, 2, 3, 4]]);
+        // Majority of 5: at least 3 inputs true
+        assert_eq!(detect(&threshold(5, 3), &[], 5), vec![vec![0, 1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn test_partial_symmetry() {
+        // f = (A + B)C: A and B are interchangeable, C is not
+        let minterms: Vec<u64> = (0..8)
+            .filter(|m| m & 0b100 != 0 && m & 0b011 != 0)
+            .collect();
+        assert_eq!(detect(&minterms, &[], 3), vec![vec![0, 1], vec![2]]);
+
+        // A don't-care on only one side of a swap breaks the symmetry
+        assert_eq!(
+            detect(&minterms, &[0b001], 3),
+            vec![vec![0], vec![1], vec![2]]
+        );
+        // Without variables there is nothing to partition
+        assert!(detect(&[0], &[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_swap_bits() {
+        assert_eq!(swap_bits(0b0001, 0, 3), 0b1000);
+        assert_eq!(swap_bits(0b1001, 0, 3), 0b1001);
+        assert_eq!(swap_bits(0b0110, 1, 2), 0b0110);
+    }
+}
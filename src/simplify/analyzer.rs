@@ -1,37 +1,37 @@
 // Analyzer: Convert branches to truth tables
 
-use super::types::{BoolExpr, BranchSet, TruthTable};
+use super::types::{BoolExpr, BranchSet, OutputKey, TruthTable, VariableType};
+use smallvec::{SmallVec, smallvec};
 use std::collections::{HashMap, HashSet};
 
 /// Extract all variables from a Boolean expression
 pub fn extract_variables(expr: &BoolExpr) -> HashSet<String> {
     let mut vars = HashSet::new();
-    extract_variables_recursive(expr, &mut vars);
-    vars
-}
-
-fn extract_variables_recursive(expr: &BoolExpr, vars: &mut HashSet<String>) {
-    match expr {
-        BoolExpr::Var(name) => {
-            vars.insert(name.clone());
-        }
-        BoolExpr::Not(inner) => {
-            extract_variables_recursive(inner, vars);
-        }
-        BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
-            extract_variables_recursive(left, vars);
-            extract_variables_recursive(right, vars);
-        }
-        // Comparison operators
-        BoolExpr::Equals(var, _)
-        | BoolExpr::NotEquals(var, _)
-        | BoolExpr::LessThan(var, _)
-        | BoolExpr::LessOrEqual(var, _)
-        | BoolExpr::GreaterThan(var, _)
-        | BoolExpr::GreaterOrEqual(var, _) => {
-            vars.insert(var.clone());
+    // An explicit stack: machine-generated conditions can nest deeper than the call stack
+    let mut stack = vec![expr];
+    while let Some(expr) = stack.pop() {
+        match expr {
+            BoolExpr::Const(_) => {}
+            BoolExpr::Var(name) => {
+                vars.insert(name.clone());
+            }
+            BoolExpr::Not(inner) => stack.push(inner),
+            BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
+                stack.push(right);
+                stack.push(left);
+            }
+            // Comparison operators
+            BoolExpr::Equals(var, _)
+            | BoolExpr::NotEquals(var, _)
+            | BoolExpr::LessThan(var, _)
+            | BoolExpr::LessOrEqual(var, _)
+            | BoolExpr::GreaterThan(var, _)
+            | BoolExpr::GreaterOrEqual(var, _) => {
+                vars.insert(var.clone());
+            }
         }
     }
+    vars
 }
 
 /// Evaluate a Boolean expression given variable assignments
@@ -41,111 +41,385 @@ pub fn evaluate(expr: &BoolExpr, assignments: &HashMap<String, bool>) -> bool {
     evaluate_with_ints(expr, assignments, &HashMap::new())
 }
 
+/// Pending work of [`evaluate_with_ints`]
+enum EvalStep<'a> {
+    /// Evaluate the expression
+    Eval(&'a BoolExpr),
+    /// Negate the value just computed
+    Not,
+    /// The left operand of `&&` was just computed; evaluate the right one if it holds
+    And(&'a BoolExpr),
+    /// The left operand of `||` was just computed; evaluate the right one unless it holds
+    Or(&'a BoolExpr),
+}
+
 /// Evaluate with explicit integer assignments
+///
+/// `&&` and `||` short-circuit. The walk uses an explicit stack, so it does not overflow on
+/// deeply nested conditions.
 pub fn evaluate_with_ints(
     expr: &BoolExpr,
     bool_assignments: &HashMap<String, bool>,
     int_assignments: &HashMap<String, i32>,
 ) -> bool {
-    match expr {
-        BoolExpr::Var(name) => *bool_assignments.get(name).unwrap_or(&false),
-        BoolExpr::Not(inner) => !evaluate_with_ints(inner, bool_assignments, int_assignments),
-        BoolExpr::And(left, right) => {
-            evaluate_with_ints(left, bool_assignments, int_assignments)
-                && evaluate_with_ints(right, bool_assignments, int_assignments)
+    let mut stack: SmallVec<[EvalStep; 16]> = smallvec![EvalStep::Eval(expr)];
+    let mut value = false;
+    while let Some(step) = stack.pop() {
+        match step {
+            EvalStep::Eval(expr) => match expr {
+                BoolExpr::Const(constant) => value = *constant,
+                BoolExpr::Var(name) => value = *bool_assignments.get(name).unwrap_or(&false),
+                BoolExpr::Not(inner) => {
+                    stack.push(EvalStep::Not);
+                    stack.push(EvalStep::Eval(inner));
+                }
+                BoolExpr::And(left, right) => {
+                    stack.push(EvalStep::And(right));
+                    stack.push(EvalStep::Eval(left));
+                }
+                BoolExpr::Or(left, right) => {
+                    stack.push(EvalStep::Or(right));
+                    stack.push(EvalStep::Eval(left));
+                }
+                // Comparison operators
+                BoolExpr::Equals(var, n) => value = int_assignments.get(var) == Some(n),
+                BoolExpr::NotEquals(var, n) => value = int_assignments.get(var) != Some(n),
+                BoolExpr::LessThan(var, n) => {
+                    value = int_assignments.get(var).is_some_and(|v| v < n)
+                }
+                BoolExpr::LessOrEqual(var, n) => {
+                    value = int_assignments.get(var).is_some_and(|v| v <= n)
+                }
+                BoolExpr::GreaterThan(var, n) => {
+                    value = int_assignments.get(var).is_some_and(|v| v > n)
+                }
+                BoolExpr::GreaterOrEqual(var, n) => {
+                    value = int_assignments.get(var).is_some_and(|v| v >= n)
+                }
+            },
+            EvalStep::Not => value = !value,
+            EvalStep::And(right) if value => stack.push(EvalStep::Eval(right)),
+            EvalStep::Or(right) if !value => stack.push(EvalStep::Eval(right)),
+            EvalStep::And(_) | EvalStep::Or(_) => {}
         }
-        BoolExpr::Or(left, right) => {
-            evaluate_with_ints(left, bool_assignments, int_assignments)
-                || evaluate_with_ints(right, bool_assignments, int_assignments)
+    }
+    value
+}
+
+/// Reject a branch set with a condition nested deeper than
+/// [`BranchSet::max_condition_depth`]
+///
+/// Run before any analysis: some walks over a condition recurse, one call per level of
+/// nesting.
+pub(crate) fn check_condition_depths(branch_set: &BranchSet) -> Result<(), String> {
+    for (index, branch) in branch_set.branches.iter().enumerate() {
+        let depth = branch.condition.depth();
+        if depth > branch_set.max_condition_depth {
+            return Err(format!(
+                "Condition of branch {} is nested {} levels deep; at most {} are supported",
+                index, depth, branch_set.max_condition_depth
+            ));
         }
-        // Comparison operators
-        BoolExpr::Equals(var, value) => int_assignments.get(var) == Some(value),
-        BoolExpr::NotEquals(var, value) => int_assignments.get(var) != Some(value),
-        BoolExpr::LessThan(var, value) => int_assignments.get(var).is_some_and(|v| v < value),
-        BoolExpr::LessOrEqual(var, value) => int_assignments.get(var).is_some_and(|v| v <= value),
-        BoolExpr::GreaterThan(var, value) => int_assignments.get(var).is_some_and(|v| v > value),
-        BoolExpr::GreaterOrEqual(var, value) => {
-            int_assignments.get(var).is_some_and(|v| v >= value)
+    }
+    Ok(())
+}
+
+/// Pending work of a bottom-up rewrite of a condition
+enum RewriteStep<'a> {
+    /// Rewrite the expression
+    Visit(&'a BoolExpr),
+    /// Combine the rewritten operand just computed into a `!`
+    Not,
+    /// Combine the two rewritten operands just computed into a `&&`
+    And,
+    /// Combine the two rewritten operands just computed into a `||`
+    Or,
+}
+
+/// Rewrite `expr` bottom-up with an explicit stack: leaves are cloned, operators go through
+/// `not` and `binary` (`true` for `&&`) applied to their rewritten operands
+fn rewrite(
+    expr: &BoolExpr,
+    not: impl Fn(BoolExpr) -> BoolExpr,
+    binary: impl Fn(bool, BoolExpr, BoolExpr) -> BoolExpr,
+) -> BoolExpr {
+    let mut stack = vec![RewriteStep::Visit(expr)];
+    let mut rewritten: Vec<BoolExpr> = Vec::new();
+    while let Some(step) = stack.pop() {
+        match step {
+            RewriteStep::Visit(expr) => match expr {
+                BoolExpr::Not(inner) => {
+                    stack.extend([RewriteStep::Not, RewriteStep::Visit(inner)]);
+                }
+                BoolExpr::And(left, right) => {
+                    stack.extend([RewriteStep::And, RewriteStep::Visit(right)]);
+                    stack.push(RewriteStep::Visit(left));
+                }
+                BoolExpr::Or(left, right) => {
+                    stack.extend([RewriteStep::Or, RewriteStep::Visit(right)]);
+                    stack.push(RewriteStep::Visit(left));
+                }
+                _ => rewritten.push(expr.clone()),
+            },
+            RewriteStep::Not => {
+                let inner = rewritten.pop().expect("the operand of `!`");
+                rewritten.push(not(inner));
+            }
+            RewriteStep::And | RewriteStep::Or => {
+                let right = rewritten.pop().expect("the right operand");
+                let left = rewritten.pop().expect("the left operand");
+                let is_and = matches!(step, RewriteStep::And);
+                rewritten.push(binary(is_and, left, right));
+            }
         }
     }
+    rewritten.pop().expect("the rewritten expression")
 }
 
-/// Convert branches to a truth table
+/// Fold constant sub-expressions (`x && false` → `false`, `x || true` → `true`, ...)
 ///
-/// Algorithm:
-/// 1. Extract all unique variables from all branches
-/// 2. For each possible input combination (2^n rows):
-///    - Evaluate each branch condition in order
-///    - First branch that evaluates to true determines the output
-///    - If no branch matches, use default output (or mark as don't care)
-/// 3. Group minterms by their output value
-pub fn build_truth_table(branch_set: &BranchSet) -> Result<TruthTable, String> {
-    // Collect all variables
+/// Comparisons and variables are left untouched; only `Const` operands are simplified away.
+pub fn fold_constants(expr: &BoolExpr) -> BoolExpr {
+    rewrite(
+        expr,
+        |inner| match inner {
+            BoolExpr::Const(value) => BoolExpr::Const(!value),
+            folded => BoolExpr::negate(folded),
+        },
+        |is_and, left, right| match (is_and, left, right) {
+            (true, BoolExpr::Const(false), _) | (true, _, BoolExpr::Const(false)) => {
+                BoolExpr::Const(false)
+            }
+            (true, BoolExpr::Const(true), other) | (true, other, BoolExpr::Const(true)) => other,
+            (true, l, r) => BoolExpr::and(l, r),
+            (false, BoolExpr::Const(true), _) | (false, _, BoolExpr::Const(true)) => {
+                BoolExpr::Const(true)
+            }
+            (false, BoolExpr::Const(false), other) | (false, other, BoolExpr::Const(false)) => {
+                other
+            }
+            (false, l, r) => BoolExpr::or(l, r),
+        },
+    )
+}
+
+/// Structural normal form for comparing conditions
+///
+/// Pushes negations inward ([`BoolExpr::normalize`]), folds constants and flattens `&&` /
+/// `||` chains into a sorted, deduplicated operand list, so `b && a` and `!!a && b`
+/// normalize to the same expression, as do `!(x == 3)` and `x != 3`.
+pub fn normalize(expr: &BoolExpr) -> BoolExpr {
+    rewrite(
+        &fold_constants(&expr.normalize()),
+        |mut inner| match &mut inner {
+            BoolExpr::Not(double) => double.take(),
+            _ => BoolExpr::negate(inner),
+        },
+        normalize_chain,
+    )
+}
+
+/// The normalized `left && right` (or `||`) of two normalized operands
+fn normalize_chain(is_and: bool, left: BoolExpr, right: BoolExpr) -> BoolExpr {
+    let mut operands = Vec::new();
+    let mut stack = vec![right, left];
+    while let Some(mut expr) = stack.pop() {
+        match &mut expr {
+            BoolExpr::And(left, right) if is_and => stack.extend([right.take(), left.take()]),
+            BoolExpr::Or(left, right) if !is_and => stack.extend([right.take(), left.take()]),
+            _ => operands.push(expr),
+        }
+    }
+    operands.sort_by_cached_key(|operand| format!("{:?}", operand));
+    operands.dedup();
+
+    let combine = if is_and { BoolExpr::and } else { BoolExpr::or };
+    operands
+        .into_iter()
+        .reduce(combine)
+        .expect("a chain has at least two operands")
+}
+
+/// Sorted names of all variables used by the branch conditions, and of the declared ones
+/// with [`BranchSet::include_declared_unused`]
+pub(crate) fn collect_variables(branch_set: &BranchSet) -> Vec<String> {
     let mut all_vars = HashSet::new();
     for branch in &branch_set.branches {
-        let vars = extract_variables(&branch.condition);
-        all_vars.extend(vars);
+        all_vars.extend(extract_variables(&branch.condition));
+    }
+    if branch_set.include_declared_unused {
+        all_vars.extend(branch_set.variable_types.keys().cloned());
     }
-
     let mut variables: Vec<String> = all_vars.into_iter().collect();
     variables.sort(); // Deterministic ordering
+    variables
+}
 
-    let var_count = variables.len();
-    if var_count == 0 {
-        return Err("No variables found in conditions".to_string());
-    }
-    if var_count > 16 {
-        return Err(format!(
-            "Too many variables ({}). Maximum supported: 64",
-            var_count
-        ));
-    }
+/// Every branch condition evaluated on every input combination, in one enumeration
+///
+/// Rows are numbered by the mixed-radix index of the assignment (the first variable is the
+/// least significant digit); with boolean variables only, row `m` is the minterm with
+/// variable `i` at bit `i`.
+pub(crate) struct BranchEvaluation {
+    /// Output of the first branch that holds on each row (else the default or a don't-care)
+    pub table: TruthTable,
+    /// Rows on which each branch's condition holds, ascending
+    pub branch_rows: Vec<Vec<u64>>,
+    /// Per branch, the earlier branches that also hold on one of its rows, in order of discovery
+    pub overlaps: Vec<Vec<usize>>,
+    /// Per branch, the number of rows on which it is the first branch that holds
+    pub first_match_rows: Vec<usize>,
+    /// Rows on which no branch holds
+    pub unmatched: Vec<u64>,
+}
 
-    let total_rows = 1u64 << var_count;
-    let mut output_groups: HashMap<String, Vec<u64>> = HashMap::new();
-    let mut dont_cares = Vec::new();
+/// Enumerate the `total_rows` assignments of `variables` and evaluate the branches on each
+///
+/// Branches flagged in `skip` are known never to hold and are not evaluated. With
+/// `first_match_only` evaluation stops at the first branch that holds, so only `table`,
+/// `first_match_rows` and `unmatched` are filled in.
+pub(crate) fn evaluate_branches(
+    branch_set: &BranchSet,
+    variables: Vec<String>,
+    var_types: &HashMap<String, VariableType>,
+    total_rows: u64,
+    skip: &[bool],
+    first_match_only: bool,
+) -> BranchEvaluation {
+    // Push negations inward and fold constants once up front so contradictory branches
+    // never match
+    let conditions: Vec<BoolExpr> = branch_set
+        .branches
+        .iter()
+        .map(|branch| fold_constants(&branch.condition.normalize()))
+        .collect();
 
-    // Evaluate each possible input combination
-    for minterm in 0..total_rows {
-        // Build variable assignment map for this minterm
-        let mut assignments = HashMap::new();
-        for (i, var) in variables.iter().enumerate() {
-            let bit_value = (minterm >> i) & 1;
-            assignments.insert(var.clone(), bit_value == 1);
+    // Grouping key and emitted spelling of each branch's output and of the default
+    let identities: Vec<(OutputKey, String)> = branch_set
+        .branches
+        .iter()
+        .map(|branch| branch_set.output_identity(&branch.output))
+        .collect();
+    let default_identity = branch_set
+        .default_output
+        .as_deref()
+        .map(|default| branch_set.output_identity(default));
+
+    // One assignment map entry per variable, updated in place for every row
+    let types: Vec<VariableType> = variables.iter().map(|v| var_types[v].clone()).collect();
+    let mut values: Vec<i32> = types.iter().map(VariableType::min_value).collect();
+    let mut bool_assignments: HashMap<String, bool> = HashMap::new();
+    let mut int_assignments: HashMap<String, i32> = HashMap::new();
+    for (var, var_type) in variables.iter().zip(&types) {
+        match var_type {
+            VariableType::Boolean => {
+                bool_assignments.insert(var.clone(), false);
+            }
+            VariableType::Integer { .. } => {
+                int_assignments.insert(var.clone(), 0);
+            }
         }
+    }
 
-        // Find first matching branch
-        let mut output = None;
-        for branch in &branch_set.branches {
-            if evaluate(&branch.condition, &assignments) {
-                output = Some(branch.output.clone());
-                break;
+    let n_branches = conditions.len();
+    let mut evaluation = BranchEvaluation {
+        table: TruthTable::new(variables),
+        branch_rows: vec![Vec::new(); n_branches],
+        overlaps: vec![Vec::new(); n_branches],
+        first_match_rows: vec![0; n_branches],
+        unmatched: Vec::new(),
+    };
+    let mut matched: Vec<usize> = Vec::with_capacity(n_branches);
+
+    for row in 0..total_rows {
+        for ((var, var_type), &value) in evaluation.table.variables.iter().zip(&types).zip(&values)
+        {
+            match var_type {
+                VariableType::Boolean => *bool_assignments.get_mut(var).unwrap() = value != 0,
+                VariableType::Integer { .. } => *int_assignments.get_mut(var).unwrap() = value,
             }
         }
 
-        // If no branch matched, use default or mark as don't care
-        match output {
-            Some(out) => {
-                output_groups.entry(out).or_default().push(minterm);
+        matched.clear();
+        for (branch_idx, condition) in conditions.iter().enumerate() {
+            if !skip[branch_idx]
+                && evaluate_with_ints(condition, &bool_assignments, &int_assignments)
+            {
+                matched.push(branch_idx);
+                if first_match_only {
+                    break;
+                }
             }
-            None => {
-                if let Some(ref default) = branch_set.default_output {
-                    output_groups
-                        .entry(default.clone())
-                        .or_default()
-                        .push(minterm);
-                } else {
-                    dont_cares.push(minterm);
+        }
+
+        if !first_match_only {
+            for (k, &branch_idx) in matched.iter().enumerate() {
+                evaluation.branch_rows[branch_idx].push(row);
+                for &earlier in &matched[..k] {
+                    if !evaluation.overlaps[branch_idx].contains(&earlier) {
+                        evaluation.overlaps[branch_idx].push(earlier);
+                    }
                 }
             }
         }
+
+        // First matching branch, else the default, else a don't-care
+        let first = matched.first().copied();
+        match first {
+            Some(branch_idx) => evaluation.first_match_rows[branch_idx] += 1,
+            None => evaluation.unmatched.push(row),
+        }
+        match first.map(|b| &identities[b]).or(default_identity.as_ref()) {
+            Some((key, display)) => evaluation.table.add_minterm(key.clone(), display, row),
+            None => evaluation.table.dont_cares.push(row),
+        }
+
+        // Increment to next combination (like odometer)
+        for (value, var_type) in values.iter_mut().zip(&types) {
+            *value += 1;
+            if *value > var_type.max_value() {
+                *value = var_type.min_value();
+            } else {
+                break;
+            }
+        }
     }
 
-    Ok(TruthTable {
-        variables,
-        output_groups,
-        dont_cares,
-    })
+    evaluation
+}
+
+/// Convert branches to a truth table
+///
+/// Algorithm:
+/// 1. Extract all unique variables from all branches
+/// 2. For each possible input combination (2^n rows):
+///    - Evaluate each branch condition in order
+///    - First branch that evaluates to true determines the output
+///    - If no branch matches, use default output (or mark as don't care)
+/// 3. Group minterms by their output value
+pub fn build_truth_table(branch_set: &BranchSet) -> Result<TruthTable, String> {
+    let variables = boolean_variables(branch_set)?;
+    let var_types = variables
+        .iter()
+        .map(|var| (var.clone(), VariableType::Boolean))
+        .collect();
+    let total_rows = 1u64 << variables.len();
+    let skip = vec![false; branch_set.branches.len()];
+    Ok(evaluate_branches(branch_set, variables, &var_types, total_rows, &skip, true).table)
+}
+
+/// Variables of a boolean-only truth table, at most 16
+fn boolean_variables(branch_set: &BranchSet) -> Result<Vec<String>, String> {
+    check_condition_depths(branch_set)?;
+    let variables = collect_variables(branch_set);
+    let var_count = variables.len();
+    if var_count > 16 {
+        return Err(format!(
+            "Too many variables ({}). Maximum supported: 16",
+            var_count
+        ));
+    }
+    Ok(variables)
 }
 
 #[cfg(test)]
@@ -162,6 +436,29 @@ mod tests {
         assert!(vars.contains("b"));
     }
 
+    #[test]
+    fn test_normalize_ignores_operand_order() {
+        let a = BoolExpr::var("a");
+        let b = BoolExpr::var("b");
+        let c = BoolExpr::var("c");
+        // (b && a) && c  vs  c && (!!a && b)
+        let left = BoolExpr::and(BoolExpr::and(b.clone(), a.clone()), c.clone());
+        let right = BoolExpr::and(
+            c.clone(),
+            BoolExpr::and(BoolExpr::negate(BoolExpr::negate(a.clone())), b.clone()),
+        );
+        assert_eq!(normalize(&left), normalize(&right));
+        assert_eq!(normalize(&BoolExpr::or(a.clone(), a.clone())), a);
+        assert_eq!(
+            normalize(&BoolExpr::negate(BoolExpr::equals("x", 3))),
+            normalize(&BoolExpr::not_equals("x", 3))
+        );
+        assert_ne!(
+            normalize(&BoolExpr::and(a.clone(), b.clone())),
+            normalize(&BoolExpr::or(a, b))
+        );
+    }
+
     #[test]
     fn test_evaluate_simple() {
         let expr = BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b"));
@@ -174,6 +471,88 @@ mod tests {
         assert!(!evaluate(&expr, &assignments));
     }
 
+    #[test]
+    fn test_truth_table_csv() {
+        // if a && !b { return "1" } else { return "0" }
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::negate(BoolExpr::var("b"))),
+            "1",
+        );
+        branch_set.set_default("0");
+
+        let table = build_truth_table(&branch_set).unwrap();
+        assert_eq!(
+            table.sorted_output_groups(),
+            vec![("0".to_string(), vec![0, 2, 3]), ("1".to_string(), vec![1])]
+        );
+        assert_eq!(table.to_csv(), "a,b,output\n0,0,0\n1,0,1\n0,1,0\n1,1,0\n");
+        // The text table writes each minterm most significant bit first
+        assert_eq!(
+            table.to_text(),
+            "a b | F\n--------\n0 0 | 0\n0 1 | 1\n1 0 | 0\n1 1 | 0\n"
+        );
+    }
+
+    #[test]
+    fn test_truth_table_without_variables() {
+        // if false { return "x" } else if true { return "1" }: one row, no columns
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::constant(false), "x");
+        branch_set.add_branch(BoolExpr::constant(true), "1");
+
+        let table = build_truth_table(&branch_set).unwrap();
+        assert!(table.variables.is_empty());
+        assert_eq!(
+            table.sorted_output_groups(),
+            vec![("1".to_string(), vec![0])]
+        );
+        assert_eq!(table.to_csv(), "output\n1\n");
+    }
+
+    #[test]
+    fn test_truth_table_row_limit() {
+        let names = |n: usize| (0..n).map(|i| format!("x{}", i)).collect::<Vec<_>>();
+
+        // 12 variables: exactly the default limit, so every row
+        let table = TruthTable::from_function_truncated(names(12), &[1, 4095], &[2], 4096).unwrap();
+        assert_eq!(table.omitted_rows, 0);
+        assert_eq!(table.truncation_notice(), None);
+        assert_eq!(table.group("1").unwrap().minterms, vec![1, 4095]);
+        assert_eq!(table.to_text().lines().count(), 2 + 4096);
+
+        // 20 variables: the first 4096 rows, minterms past them dropped
+        let table = TruthTable::from_function_truncated(names(20), &[1, 4096], &[], 4096).unwrap();
+        assert_eq!(table.omitted_rows, (1 << 20) - 4096);
+        assert_eq!(table.group("1").unwrap().minterms, vec![1]);
+        let text = table.to_text();
+        assert_eq!(text.lines().count(), 2 + 4096 + 1);
+        assert!(text.ends_with("... 1044480 more rows not shown (1048576 in total)\n"));
+        assert!(table.to_markdown().contains("\n\n... 1044480 more rows"));
+        assert!(!table.to_csv().contains("more rows"));
+
+        assert_eq!(TruthTable::row_count(63), Ok(1 << 63));
+        assert!(
+            TruthTable::row_count(64)
+                .unwrap_err()
+                .contains("64 variables")
+        );
+        assert!(TruthTable::from_function_truncated(names(64), &[], &[], 10).is_err());
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let a = BoolExpr::var("a");
+        let expr = BoolExpr::and(a.clone(), BoolExpr::constant(false));
+        assert_eq!(fold_constants(&expr), BoolExpr::constant(false));
+
+        let expr = BoolExpr::or(a.clone(), BoolExpr::constant(true));
+        assert_eq!(fold_constants(&expr), BoolExpr::constant(true));
+
+        let expr = BoolExpr::and(a.clone(), BoolExpr::negate(BoolExpr::constant(false)));
+        assert_eq!(fold_constants(&expr), a);
+    }
+
     #[test]
     fn test_build_truth_table_simple() {
         // if a && b { return "1" } else { return "0" }
@@ -188,12 +567,12 @@ mod tests {
         assert!(table.variables.contains(&"b".to_string()));
 
         // With a, b ordering: minterm 3 = (a=1, b=1) should output "1"
-        let ones = table.output_groups.get("1").unwrap();
+        let ones = &table.group("1").unwrap().minterms;
         assert_eq!(ones.len(), 1);
         // The exact minterm depends on variable ordering, but there should be exactly 1
         assert!(ones.contains(&3) || ones.contains(&3)); // a=1, b=1
 
-        let zeros = table.output_groups.get("0").unwrap();
+        let zeros = &table.group("0").unwrap().minterms;
         assert_eq!(zeros.len(), 3); // All other combinations
     }
 
@@ -212,9 +591,104 @@ mod tests {
 
         let table = build_truth_table(&branch_set).unwrap();
 
-        let ones = table.output_groups.get("1").unwrap();
+        let ones = &table.group("1").unwrap().minterms;
         // Should have 2 minterms: a && b, and a && !b
         // This simplifies to just "a"
         assert_eq!(ones.len(), 2);
     }
+
+    #[test]
+    fn test_declared_unused_variables_keep_indices_stable() {
+        let declared = |condition: BoolExpr| {
+            let mut branch_set = BranchSet::new();
+            for name in ["c", "b", "a"] {
+                branch_set.declare_bool(name);
+            }
+            branch_set.add_branch(condition, "1");
+            branch_set.set_default("0");
+            branch_set
+        };
+        let mut uses_a_c = declared(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("c")));
+        let mut uses_b = declared(BoolExpr::var("b"));
+
+        // Only the referenced variables: row 1 is a=1 in one table and b=1 in the other
+        assert_eq!(build_truth_table(&uses_a_c).unwrap().variables, ["a", "c"]);
+        assert_eq!(build_truth_table(&uses_b).unwrap().variables, ["b"]);
+
+        uses_a_c.set_include_declared_unused(true);
+        uses_b.set_include_declared_unused(true);
+        let table_a_c = build_truth_table(&uses_a_c).unwrap();
+        let table_b = build_truth_table(&uses_b).unwrap();
+        assert_eq!(table_a_c.variables, ["a", "b", "c"]);
+        assert_eq!(table_b.variables, table_a_c.variables);
+        assert_eq!(table_a_c.group("1").unwrap().minterms, [5, 7]);
+        assert_eq!(table_b.group("1").unwrap().minterms, [2, 3, 6, 7]);
+
+        // The extra column is free, so the simplified condition does not mention it
+        let result = crate::simplify::simplify_branches(&uses_a_c).unwrap();
+        assert_eq!(result.variables, ["a", "b", "c"]);
+        let (condition, output) = &result.simplified_conditions[0];
+        assert_eq!(output, "1");
+        assert!(!extract_variables(condition).contains("b"));
+    }
+
+    #[test]
+    fn test_deep_condition() {
+        use crate::simplify::format_bool_expr;
+        use std::time::{Duration, Instant};
+
+        // A machine-generated v0 && v1 && ... as a left-leaning chain of 100k nodes
+        let n = 50_000;
+        let chain = (1..n).fold(BoolExpr::var("v0"), |chain, i| {
+            BoolExpr::and(chain, BoolExpr::var(&format!("v{}", i % 8)))
+        });
+        assert_eq!(chain.depth(), n);
+
+        let start = Instant::now();
+        let mut assignments: HashMap<String, bool> =
+            (0..8).map(|i| (format!("v{}", i), true)).collect();
+        assert!(evaluate(&chain, &assignments));
+        assignments.insert("v3".to_string(), false);
+        assert!(!evaluate(&chain, &assignments));
+        assert_eq!(extract_variables(&chain).len(), 8);
+        assert!(format_bool_expr(&chain).ends_with(") && v7"));
+        assert_eq!(chain.operator_count(), n - 1);
+        assert!(!chain.has_comparisons());
+        assert_eq!(fold_constants(&chain).depth(), n);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Analysis rejects it before walking it recursively; dropping it does not recurse
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(chain, "1");
+        let error = build_truth_table(&branch_set).unwrap_err();
+        assert!(
+            error.contains("nested 50000 levels deep; at most 1024"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_wide_shallow_condition() {
+        // A generated DNF of 100 terms over 8 variables: over 1500 nodes, but 108 levels deep
+        let terms: Vec<String> = (0..100)
+            .map(|term: u32| {
+                let literals: Vec<String> = (0..8)
+                    .map(|i| {
+                        let negation = if (term >> i) & 1 == 1 { "" } else { "!" };
+                        format!("{}v{}", negation, i)
+                    })
+                    .collect();
+                format!("({})", literals.join(" && "))
+            })
+            .collect();
+        let condition = crate::simplify::parse_bool_expr(&terms.join(" || ")).unwrap();
+        assert_eq!(condition.depth(), 108);
+
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(condition, "1");
+        branch_set.set_default("0");
+        let table = build_truth_table(&branch_set).unwrap();
+        assert_eq!(table.group("1").unwrap().minterms.len(), 100);
+    }
 }
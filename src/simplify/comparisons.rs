@@ -1,8 +1,8 @@
 // Comparison operator support for if-then-else simplification
 
-use super::analyzer::{evaluate_with_ints, extract_variables};
-use super::types::{BranchSet, TruthTable, VariableType};
-use std::collections::{HashMap, HashSet};
+use super::analyzer::{check_condition_depths, collect_variables, evaluate_branches};
+use super::types::{BoolExpr, BranchSet, OutputGroup, TruthTable, VariableType};
+use std::collections::HashMap;
 
 /// Build truth table with support for integer variables and comparisons
 ///
@@ -17,19 +17,10 @@ use std::collections::{HashMap, HashSet};
 /// 3. For each combination, evaluate all branches in order
 /// 4. Map to output groups
 pub fn build_truth_table_with_comparisons(branch_set: &BranchSet) -> Result<TruthTable, String> {
-    // Collect all variables and infer types if not declared
-    let mut all_vars = HashSet::new();
-    for branch in &branch_set.branches {
-        let vars = extract_variables(&branch.condition);
-        all_vars.extend(vars);
-    }
+    check_condition_depths(branch_set)?;
 
-    let mut variables: Vec<String> = all_vars.into_iter().collect();
-    variables.sort();
-
-    if variables.is_empty() {
-        return Err("No variables found in conditions".to_string());
-    }
+    // Collect all variables and infer types if not declared
+    let variables = collect_variables(branch_set);
 
     // Get or infer variable types
     let mut var_types: HashMap<String, VariableType> = HashMap::new();
@@ -59,82 +50,418 @@ pub fn build_truth_table_with_comparisons(branch_set: &BranchSet) -> Result<Trut
         ));
     }
 
-    let mut output_groups: HashMap<String, Vec<u64>> = HashMap::new();
-    let mut dont_cares = Vec::new();
+    let skip = vec![false; branch_set.branches.len()];
+    Ok(evaluate_branches(
+        branch_set,
+        variables,
+        &var_types,
+        total_combinations as u64,
+        &skip,
+        true,
+    )
+    .table)
+}
 
-    // Enumerate all combinations
-    let mut assignments: Vec<i32> = variables.iter().map(|v| var_types[v].min_value()).collect();
+/// A source variable mapped onto consecutive bits of a [`BitBlastedTable`]
+#[derive(Debug, Clone)]
+pub struct BlastedVariable {
+    pub name: String,
+    pub var_type: VariableType,
+    pub first_bit: usize,
+    pub bit_count: usize,
+}
 
-    for minterm_idx in 0..total_combinations as u64 {
-        // Build assignment maps
-        let mut bool_assignments = HashMap::new();
-        let mut int_assignments = HashMap::new();
+impl BlastedVariable {
+    /// Code (`value - min`) of this variable in a bit-blasted minterm
+    fn code(&self, minterm: u64) -> u64 {
+        (minterm >> self.first_bit) & ((1u64 << self.bit_count) - 1)
+    }
+}
 
-        for (i, var) in variables.iter().enumerate() {
-            let value = assignments[i];
-            match &var_types[var] {
-                VariableType::Boolean => {
-                    bool_assignments.insert(var.clone(), value != 0);
-                }
-                VariableType::Integer { .. } => {
-                    int_assignments.insert(var.clone(), value);
-                }
+/// Boolean truth table in which every integer variable is encoded in binary
+///
+/// Boolean variables keep their name; bit `i` of integer `x` (of `x - min`) is named
+/// `x[i]`. Codes outside a variable's domain never occur and are don't-cares.
+#[derive(Debug)]
+pub struct BitBlastedTable {
+    pub table: TruthTable,
+    pub layout: Vec<BlastedVariable>,
+}
+
+/// Maximum number of bits of a bit-blasted table
+const MAX_BLASTED_BITS: usize = 24;
+
+/// Build the bit-blasted truth table of a branch set with integer variables
+pub fn build_bit_blasted_table(branch_set: &BranchSet) -> Result<BitBlastedTable, String> {
+    let mixed = build_truth_table_with_comparisons(branch_set)?;
+
+    let mut layout = Vec::new();
+    let mut bit_names = Vec::new();
+    for var in &mixed.variables {
+        let var_type = branch_set
+            .variable_types
+            .get(var)
+            .cloned()
+            .unwrap_or(VariableType::Boolean);
+        let bit_count = var_type.bit_count();
+        let first_bit = bit_names.len();
+        match var_type {
+            VariableType::Boolean => bit_names.push(var.clone()),
+            VariableType::Integer { .. } => {
+                bit_names.extend((0..bit_count).map(|i| format!("{}[{}]", var, i)))
             }
         }
+        layout.push(BlastedVariable {
+            name: var.clone(),
+            var_type,
+            first_bit,
+            bit_count,
+        });
+    }
+
+    if bit_names.len() > MAX_BLASTED_BITS {
+        return Err(format!(
+            "Too many bits after encoding integers ({}). Maximum: {}",
+            bit_names.len(),
+            MAX_BLASTED_BITS
+        ));
+    }
+
+    // Mixed-radix index (first variable is the least significant digit) to binary codes
+    let encode = |mut index: u64| -> u64 {
+        let mut minterm = 0;
+        for var in &layout {
+            let range = (var.var_type.max_value() - var.var_type.min_value() + 1) as u64;
+            minterm |= (index % range) << var.first_bit;
+            index /= range;
+        }
+        minterm
+    };
+
+    let mut table = TruthTable::new(bit_names);
+    for (key, group) in &mixed.output_groups {
+        let encoded = group.minterms.iter().map(|&m| encode(m)).collect();
+        table.output_groups.insert(
+            key.clone(),
+            OutputGroup {
+                display: group.display.clone(),
+                minterms: encoded,
+            },
+        );
+    }
+    table.dont_cares = mixed.dont_cares.iter().map(|&m| encode(m)).collect();
+
+    for minterm in 0..1u64 << table.variable_count() {
+        let out_of_domain = layout.iter().any(|var| {
+            let largest_code = (var.var_type.max_value() - var.var_type.min_value()) as u64;
+            var.code(minterm) > largest_code
+        });
+        if out_of_domain {
+            table.dont_cares.push(minterm);
+        }
+    }
+
+    Ok(BitBlastedTable { table, layout })
+}
+
+/// Values of `var` selected by a product term, given as `(bit, positive)` literals
+pub fn covered_values(var: &BlastedVariable, literals: &[(usize, bool)]) -> Vec<i32> {
+    let min = var.var_type.min_value();
+    (min..=var.var_type.max_value())
+        .filter(|&value| {
+            let code = (value - min) as u64;
+            literals.iter().all(|&(bit, positive)| {
+                !(var.first_bit..var.first_bit + var.bit_count).contains(&bit)
+                    || ((code >> (bit - var.first_bit)) & 1 == 1) == positive
+            })
+        })
+        .collect()
+}
+
+/// Cleanest comparison for "`var` is one of `values`" over the domain `min..=max`
+///
+/// A contiguous range becomes `==`, `<`, `>=` or `>= && <=`, the domain minus a single value
+/// becomes `!=`, and anything else an OR of equalities. Returns `None` when `values` is
+/// the whole domain, as no comparison is needed.
+pub fn recover_comparison(var: &str, values: &[i32], min: i32, max: i32) -> Option<BoolExpr> {
+    let mut values: Vec<i32> = values
+        .iter()
+        .copied()
+        .filter(|v| (min..=max).contains(v))
+        .collect();
+    values.sort_unstable();
+    values.dedup();
+
+    let domain_size = (max - min + 1) as usize;
+    let (first, last) = match (values.first(), values.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Some(BoolExpr::Const(false)),
+    };
+    let contiguous = (last - first + 1) as usize == values.len();
+
+    if values.len() == domain_size {
+        return None;
+    }
+    if values.len() == 1 {
+        return Some(BoolExpr::equals(var, first));
+    }
+    if values.len() + 1 == domain_size && domain_size > 2 && !contiguous {
+        let missing = (min..=max).find(|v| !values.contains(v)).unwrap();
+        return Some(BoolExpr::not_equals(var, missing));
+    }
+    if contiguous {
+        return Some(if first == min {
+            BoolExpr::less_than(var, last + 1)
+        } else if last == max {
+            BoolExpr::greater_or_equal(var, first)
+        } else {
+            BoolExpr::and(
+                BoolExpr::greater_or_equal(var, first),
+                BoolExpr::less_or_equal(var, last),
+            )
+        });
+    }
+
+    values
+        .iter()
+        .map(|&v| BoolExpr::equals(var, v))
+        .reduce(BoolExpr::or)
+}
+
+/// Values of every layout variable selected by a bit-blasted product term
+pub fn term_values(layout: &[BlastedVariable], literals: &[(usize, bool)]) -> Vec<Vec<i32>> {
+    layout
+        .iter()
+        .map(|var| covered_values(var, literals))
+        .collect()
+}
 
-        // Find first matching branch
-        let mut output = None;
-        for branch in &branch_set.branches {
-            if evaluate_with_ints(&branch.condition, &bool_assignments, &int_assignments) {
-                output = Some(branch.output.clone());
-                break;
+/// Merge terms that select the same values for all variables but one
+///
+/// Binary cubes split ranges at power-of-two boundaries (`{2,3,4}` is `{2,3} | {4}`);
+/// merging their value sets first lets [`recover_term`] emit the whole range at once.
+pub fn merge_terms(mut terms: Vec<Vec<Vec<i32>>>) -> Vec<Vec<Vec<i32>>> {
+    'restart: loop {
+        for i in 0..terms.len() {
+            for j in (i + 1)..terms.len() {
+                let mut differing = (0..terms[i].len()).filter(|&v| terms[i][v] != terms[j][v]);
+                let merge_at = match (differing.next(), differing.next()) {
+                    (None, _) => None,
+                    (Some(v), None) => Some(v),
+                    _ => continue,
+                };
+                let other = terms.remove(j);
+                if let Some(v) = merge_at {
+                    terms[i][v].extend_from_slice(&other[v]);
+                    terms[i][v].sort_unstable();
+                    terms[i][v].dedup();
+                }
+                continue 'restart;
             }
         }
+        return terms;
+    }
+}
 
-        // Record output
-        match output {
-            Some(out) => {
-                output_groups.entry(out).or_default().push(minterm_idx);
+/// Rebuild a product term from the values it selects per variable (see [`term_values`])
+///
+/// Booleans become literals, integers comparisons; returns `Const(true)` when the term
+/// constrains nothing.
+pub fn recover_term(layout: &[BlastedVariable], values: &[Vec<i32>]) -> BoolExpr {
+    let mut parts = Vec::new();
+    for (var, values) in layout.iter().zip(values) {
+        match var.var_type {
+            VariableType::Boolean => match values.as_slice() {
+                [1] => parts.push(BoolExpr::var(&var.name)),
+                [0] => parts.push(BoolExpr::negate(BoolExpr::var(&var.name))),
+                _ => {}
+            },
+            VariableType::Integer { min, max } => {
+                parts.extend(recover_comparison(&var.name, values, min, max));
             }
+        }
+    }
+    parts
+        .into_iter()
+        .reduce(BoolExpr::and)
+        .unwrap_or(BoolExpr::Const(true))
+}
+
+/// Bounds on one integer variable, each with the comparison that set it (`None`: domain)
+struct Interval<'a> {
+    lo: (i32, Option<(usize, &'a BoolExpr)>),
+    hi: (i32, Option<(usize, &'a BoolExpr)>),
+}
+
+/// Explain why a conjunction of comparisons can never hold, e.g. `x < 3 && x > 7`
+///
+/// Symbolic pre-pass: the top-level `&&` operands that compare an integer variable with a
+/// constant are intersected per variable (starting from its domain). Returns a message
+/// naming the conflicting comparisons when some interval is empty, `None` otherwise
+/// (including every condition this does not understand).
+pub fn conflicting_comparisons(
+    condition: &BoolExpr,
+    var_types: &HashMap<String, VariableType>,
+) -> Option<String> {
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(condition, &mut conjuncts);
+
+    let mut intervals: Vec<(&str, Interval)> = Vec::new();
+    for (index, &conjunct) in conjuncts.iter().enumerate() {
+        let (var, lo, hi) = match conjunct {
+            BoolExpr::Equals(v, n) => (v, Some(*n), Some(*n)),
+            BoolExpr::LessThan(v, n) => (v, None, n.checked_sub(1)),
+            BoolExpr::LessOrEqual(v, n) => (v, None, Some(*n)),
+            BoolExpr::GreaterThan(v, n) => (v, n.checked_add(1), None),
+            BoolExpr::GreaterOrEqual(v, n) => (v, Some(*n), None),
+            _ => continue,
+        };
+        let Some(&VariableType::Integer { min, max }) = var_types.get(var) else {
+            continue;
+        };
+        let position = match intervals.iter().position(|(name, _)| name == var) {
+            Some(position) => position,
             None => {
-                if let Some(ref default) = branch_set.default_output {
-                    output_groups
-                        .entry(default.clone())
-                        .or_default()
-                        .push(minterm_idx);
-                } else {
-                    dont_cares.push(minterm_idx);
-                }
+                intervals.push((
+                    var,
+                    Interval {
+                        lo: (min, None),
+                        hi: (max, None),
+                    },
+                ));
+                intervals.len() - 1
             }
+        };
+        let interval = &mut intervals[position].1;
+        let source = Some((index, conjunct));
+        match lo {
+            Some(lo) if lo > interval.lo.0 => interval.lo = (lo, source),
+            _ => {}
+        }
+        match hi {
+            Some(hi) if hi < interval.hi.0 => interval.hi = (hi, source),
+            _ => {}
         }
+    }
 
-        // Increment to next combination (like odometer)
-        let mut carry = true;
-        for i in 0..variables.len() {
-            if carry {
-                assignments[i] += 1;
-                let var_type = &var_types[&variables[i]];
-                if assignments[i] > var_type.max_value() {
-                    assignments[i] = var_type.min_value();
-                } else {
-                    carry = false;
-                }
-            }
+    let (var, interval) = intervals
+        .iter()
+        .find(|(_, interval)| interval.lo.0 > interval.hi.0)?;
+    let format = |(_, expr): (usize, &BoolExpr)| super::optimizer::format_bool_expr(expr);
+    let domain = || {
+        let var_type = &var_types[*var];
+        format!(
+            "outside the domain of {} ({}..={})",
+            var,
+            var_type.min_value(),
+            var_type.max_value()
+        )
+    };
+    match (interval.lo.1, interval.hi.1) {
+        (Some(lo), Some(hi)) if lo.0 != hi.0 => {
+            // Name them in source order
+            let (first, second) = if lo.0 < hi.0 { (lo, hi) } else { (hi, lo) };
+            Some(format!("{} contradicts {}", format(second), format(first)))
+        }
+        (Some(source), _) | (_, Some(source)) => {
+            Some(format!("{} is {}", format(source), domain()))
         }
+        // An empty declared domain; nothing to blame on the condition
+        (None, None) => None,
     }
+}
 
-    Ok(TruthTable {
-        variables,
-        output_groups,
-        dont_cares,
-    })
+/// Operands of a (nested) `&&` chain, left to right
+fn collect_conjuncts<'a>(expr: &'a BoolExpr, conjuncts: &mut Vec<&'a BoolExpr>) {
+    match expr {
+        BoolExpr::And(left, right) => {
+            collect_conjuncts(left, conjuncts);
+            collect_conjuncts(right, conjuncts);
+        }
+        _ => conjuncts.push(expr),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simplify::optimizer::format_bool_expr;
     use crate::simplify::types::{BoolExpr, BranchSet};
 
+    #[test]
+    fn test_recover_comparison_ranges() {
+        let show =
+            |values: &[i32]| recover_comparison("x", values, 0, 5).map(|e| format_bool_expr(&e));
+
+        assert_eq!(show(&[2, 3, 4]), Some("(x >= 2) && (x <= 4)".to_string()));
+        assert_eq!(show(&[0, 1]), Some("x < 2".to_string()));
+        assert_eq!(show(&[3, 4, 5]), Some("x >= 3".to_string()));
+        assert_eq!(show(&[3]), Some("x == 3".to_string()));
+        assert_eq!(show(&[0, 1, 2, 4, 5]), Some("x != 3".to_string()));
+        assert_eq!(show(&[1, 4]), Some("x == 1 || x == 4".to_string()));
+        assert_eq!(show(&[0, 1, 2, 3, 4, 5]), None);
+        assert_eq!(show(&[]), Some("false".to_string()));
+    }
+
+    #[test]
+    fn test_bit_blasted_term_recovery() {
+        // if x >= 2 && x <= 4 { "mid" } else { "other" } with x in 0..=5
+        let mut branches = BranchSet::new();
+        branches.declare_int("x", 0, 5);
+        branches.add_branch(
+            BoolExpr::and(
+                BoolExpr::greater_or_equal("x", 2),
+                BoolExpr::less_or_equal("x", 4),
+            ),
+            "mid",
+        );
+        branches.set_default("other");
+
+        let blasted = build_bit_blasted_table(&branches).unwrap();
+        assert_eq!(blasted.table.variables, vec!["x[0]", "x[1]", "x[2]"]);
+        // Codes 6 and 7 do not exist
+        assert!(blasted.table.dont_cares.contains(&6));
+        assert!(blasted.table.dont_cares.contains(&7));
+
+        let mut mid = blasted.table.group("mid").unwrap().minterms.clone();
+        mid.sort_unstable();
+        assert_eq!(mid, vec![2, 3, 4]);
+
+        let recover = |literals: &[(usize, bool)]| {
+            format_bool_expr(&recover_term(
+                &blasted.layout,
+                &term_values(&blasted.layout, literals),
+            ))
+        };
+        // x[1] alone selects {2, 3} (6 and 7 are out of domain)
+        assert_eq!(recover(&[(1, true)]), "(x >= 2) && (x <= 3)");
+        // x[0]' x[1] is x == 2
+        assert_eq!(recover(&[(0, false), (1, true)]), "x == 2");
+        assert_eq!(recover(&[]), "true");
+
+        // {2, 3} | {4} merges into the full range
+        let merged = merge_terms(vec![
+            term_values(&blasted.layout, &[(1, true)]),
+            term_values(&blasted.layout, &[(0, false), (2, true)]),
+        ]);
+        assert_eq!(merged, vec![vec![vec![2, 3, 4]]]);
+    }
+
+    #[test]
+    fn test_merge_terms_with_booleans() {
+        // a && x in {0,1}  |  a && x in {4}  |  !a && x in {4}
+        let terms = vec![
+            vec![vec![1], vec![0, 1]],
+            vec![vec![1], vec![4]],
+            vec![vec![0], vec![4]],
+        ];
+        let merged = merge_terms(terms);
+        assert_eq!(
+            merged,
+            vec![vec![vec![1], vec![0, 1, 4]], vec![vec![0], vec![4]]]
+        );
+    }
+
     #[test]
     fn test_comparison_equals() {
         // if x == 2 { return "A" } else { return "B" }
@@ -147,10 +474,10 @@ mod tests {
         let table = build_truth_table_with_comparisons(&branches).unwrap();
 
         // Should have 4 combinations (x = 0, 1, 2, 3)
-        let a_minterms = table.output_groups.get("A").unwrap();
+        let a_minterms = &table.group("A").unwrap().minterms;
         assert_eq!(a_minterms.len(), 1); // Only x=2
 
-        let b_minterms = table.output_groups.get("B").unwrap();
+        let b_minterms = &table.group("B").unwrap().minterms;
         assert_eq!(b_minterms.len(), 3); // x=0,1,3
     }
 
@@ -165,10 +492,10 @@ mod tests {
 
         let table = build_truth_table_with_comparisons(&branches).unwrap();
 
-        let small = table.output_groups.get("small").unwrap();
+        let small = &table.group("small").unwrap().minterms;
         assert_eq!(small.len(), 2); // x=0,1
 
-        let big = table.output_groups.get("big").unwrap();
+        let big = &table.group("big").unwrap().minterms;
         assert_eq!(big.len(), 2); // x=2,3
     }
 
@@ -190,10 +517,45 @@ mod tests {
 
         // Total combinations: 2 (for a) * 4 (for x) = 8
         // "1" when a=true AND x>1: (a=1, x=2), (a=1, x=3) = 2 combinations
-        let ones = table.output_groups.get("1").unwrap();
+        let ones = &table.group("1").unwrap().minterms;
         assert_eq!(ones.len(), 2);
 
-        let zeros = table.output_groups.get("0").unwrap();
+        let zeros = &table.group("0").unwrap().minterms;
         assert_eq!(zeros.len(), 6);
     }
+
+    #[test]
+    fn test_conflicting_comparisons() {
+        let types: HashMap<String, VariableType> =
+            [("x".to_string(), VariableType::Integer { min: 0, max: 9 })].into();
+        let both = |a, b| conflicting_comparisons(&BoolExpr::and(a, b), &types);
+
+        assert_eq!(
+            both(BoolExpr::less_than("x", 3), BoolExpr::greater_than("x", 7)),
+            Some("x > 7 contradicts x < 3".to_string())
+        );
+        assert_eq!(
+            both(
+                BoolExpr::greater_or_equal("x", 2),
+                BoolExpr::less_or_equal("x", 5)
+            ),
+            None
+        );
+        assert_eq!(
+            both(BoolExpr::equals("x", 2), BoolExpr::equals("x", 5)),
+            Some("x == 5 contradicts x == 2".to_string())
+        );
+        assert_eq!(
+            conflicting_comparisons(&BoolExpr::greater_than("x", 9), &types),
+            Some("x > 9 is outside the domain of x (0..=9)".to_string())
+        );
+        // Only top-level conjunctions are considered
+        assert_eq!(
+            conflicting_comparisons(
+                &BoolExpr::or(BoolExpr::less_than("x", 3), BoolExpr::greater_than("x", 7)),
+                &types
+            ),
+            None
+        );
+    }
 }
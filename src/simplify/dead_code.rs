@@ -1,11 +1,20 @@
 // Dead code detection and coverage analysis
 
-use super::analyzer::{evaluate_with_ints, extract_variables};
+use super::analyzer::{
+    check_condition_depths, collect_variables, evaluate_branches, evaluate_with_ints,
+    extract_variables, fold_constants, normalize,
+};
+use super::comparisons::conflicting_comparisons;
 use super::types::{
-    BranchCoverage, BranchSet, DeadBranch, DeadCodeReason, SimplificationAnalysis, VariableType,
+    BoolExpr, BranchCoverage, BranchSet, DeadBranch, DeadCodeReason, SimplificationAnalysis,
+    SymbolicAnalysis, TruthTable, VariableType,
 };
 use std::collections::{HashMap, HashSet};
 
+/// Most input combinations [`analyze_branches_symbolic`] enumerates for one group of
+/// conditions that share variables
+pub const MAX_SYMBOLIC_COMBINATIONS: u64 = 1 << 20;
+
 /// Analyze branch coverage and detect dead code
 ///
 /// This function evaluates each branch in order and tracks:
@@ -14,20 +23,22 @@ use std::collections::{HashMap, HashSet};
 /// - Overlapping conditions between branches
 /// - Uncovered input combinations
 pub fn analyze_branches(branch_set: &BranchSet) -> Result<SimplificationAnalysis, String> {
-    // Collect all variables
-    let mut all_vars = HashSet::new();
-    for branch in &branch_set.branches {
-        let vars = extract_variables(&branch.condition);
-        all_vars.extend(vars);
-    }
+    analyze(branch_set).map(|(analysis, _)| analysis)
+}
 
-    let mut variables: Vec<String> = all_vars.into_iter().collect();
-    variables.sort();
+/// [`analyze_branches`] together with the
+/// [`build_truth_table`](super::analyzer::build_truth_table) table of the branch set, from a
+/// single enumeration; with integer variables a row per combination of their values
+pub fn analyze_with_truth_table(
+    branch_set: &BranchSet,
+) -> Result<(SimplificationAnalysis, TruthTable), String> {
+    analyze(branch_set)
+}
 
+fn analyze(branch_set: &BranchSet) -> Result<(SimplificationAnalysis, TruthTable), String> {
+    check_condition_depths(branch_set)?;
+    let variables = collect_variables(branch_set);
     let var_count = variables.len();
-    if var_count == 0 {
-        return Err("No variables found in conditions".to_string());
-    }
     if var_count > 16 {
         return Err(format!(
             "Too many variables ({}). Maximum supported: 16",
@@ -55,134 +66,338 @@ pub fn analyze_branches(branch_set: &BranchSet) -> Result<SimplificationAnalysis
             .checked_mul(range)
             .ok_or_else(|| "Too many variable combinations".to_string())?;
     }
-
     let total_rows = total_combinations as u32;
-    let mut covered_minterms = HashSet::new();
-    let mut branch_coverage: Vec<BranchCoverage> = Vec::new();
-    let mut dead_branches = Vec::new();
 
-    // Initialize assignments
-    let mut assignments: Vec<i32> = variables.iter().map(|v| var_types[v].min_value()).collect();
+    // Comparisons with an empty intersection cover no row; no need to evaluate them
+    let conflicts: Vec<Option<String>> = branch_set
+        .branches
+        .iter()
+        .map(|branch| conflicting_comparisons(&fold_constants(&branch.condition), &var_types))
+        .collect();
+    let skip: Vec<bool> = conflicts.iter().map(Option::is_some).collect();
 
-    // Analyze each branch in order
-    for (branch_idx, branch) in branch_set.branches.iter().enumerate() {
-        let mut minterms_for_this_branch = Vec::new();
-        let mut overlaps_with = Vec::new();
+    let evaluation = evaluate_branches(
+        branch_set,
+        variables,
+        &var_types,
+        u64::from(total_rows),
+        &skip,
+        false,
+    );
 
-        // Reset assignments for enumeration
-        for i in 0..variables.len() {
-            assignments[i] = var_types[&variables[i]].min_value();
-        }
+    // Normalized conditions to recognize a branch that repeats an earlier condition
+    let normalized: Vec<_> = branch_set
+        .branches
+        .iter()
+        .map(|branch| normalize(&branch.condition))
+        .collect();
 
-        // Evaluate which minterms this branch covers
-        for minterm_idx in 0..total_rows {
-            // Build assignment maps
-            let mut bool_assignments = HashMap::new();
-            let mut int_assignments = HashMap::new();
-
-            for (i, var) in variables.iter().enumerate() {
-                let value = assignments[i];
-                match &var_types[var] {
-                    VariableType::Boolean => {
-                        bool_assignments.insert(var.clone(), value != 0);
-                    }
-                    VariableType::Integer { .. } => {
-                        int_assignments.insert(var.clone(), value);
-                    }
-                }
-            }
-
-            if evaluate_with_ints(&branch.condition, &bool_assignments, &int_assignments) {
-                minterms_for_this_branch.push(minterm_idx);
-
-                // Check if this minterm was already covered by an earlier branch
-                if covered_minterms.contains(&minterm_idx) {
-                    // Find which branch(es) already covered this
-                    for (prev_idx, prev_coverage) in branch_coverage.iter().enumerate() {
-                        if prev_coverage.minterms_covered.contains(&minterm_idx)
-                            && !overlaps_with.contains(&prev_idx)
-                        {
-                            overlaps_with.push(prev_idx);
-                        }
-                    }
-                }
-            }
-
-            // Increment to next combination (like odometer)
-            let mut carry = true;
-            for i in 0..variables.len() {
-                if carry {
-                    assignments[i] += 1;
-                    let var_type = &var_types[&variables[i]];
-                    if assignments[i] > var_type.max_value() {
-                        assignments[i] = var_type.min_value();
-                    } else {
-                        carry = false;
-                    }
-                }
-            }
-        }
-
-        // Check if this branch is dead code
-        let new_coverage: HashSet<u32> = minterms_for_this_branch
-            .iter()
-            .filter(|&&m| !covered_minterms.contains(&m))
-            .copied()
-            .collect();
+    let mut branch_coverage: Vec<BranchCoverage> = Vec::new();
+    let mut dead_branches = Vec::new();
+    let rows_and_overlaps = evaluation.branch_rows.iter().zip(&evaluation.overlaps);
+    for (branch_idx, ((branch, conflict), (rows, overlaps_with))) in branch_set
+        .branches
+        .iter()
+        .zip(conflicts)
+        .zip(rows_and_overlaps)
+        .enumerate()
+    {
+        // Rows on which this branch is the first to hold
+        let new_coverage = evaluation.first_match_rows[branch_idx];
 
-        if new_coverage.is_empty() && !minterms_for_this_branch.is_empty() {
+        if new_coverage == 0 {
             // This branch covers no new minterms - it's dead code
-            let reason = if minterms_for_this_branch.is_empty() {
-                DeadCodeReason::Contradiction
-            } else {
-                DeadCodeReason::FullyCovered
+            let duplicate_of = (0..branch_idx).find(|&j| normalized[j] == normalized[branch_idx]);
+            let (reason, covered_by) = match duplicate_of {
+                _ if rows.is_empty() => (DeadCodeReason::Contradiction, overlaps_with.clone()),
+                // An exact repeat with another output usually means the extraction dropped
+                // the variable that tells the two apart
+                Some(j)
+                    if branch_set.output_key(&branch_set.branches[j].output)
+                        != branch_set.output_key(&branch.output) =>
+                {
+                    (DeadCodeReason::ConflictingDuplicate, vec![j])
+                }
+                Some(j) => (DeadCodeReason::Redundant, vec![j]),
+                None => (DeadCodeReason::FullyCovered, overlaps_with.clone()),
             };
 
             dead_branches.push(DeadBranch {
                 branch_index: branch_idx,
                 reason,
-                covered_by: overlaps_with.clone(),
+                covered_by,
+                protected: branch.protected,
+                detail: conflict,
             });
         }
 
-        // Add to covered set
-        for &minterm in &minterms_for_this_branch {
-            covered_minterms.insert(minterm);
-        }
-
         // Record coverage for this branch
         branch_coverage.push(BranchCoverage {
             branch_index: branch_idx,
-            minterms_covered: minterms_for_this_branch,
-            coverage_count: new_coverage.len(),
-            overlaps_with,
+            minterms_covered: rows.iter().map(|&row| row as u32).collect(),
+            coverage_count: new_coverage,
+            overlaps_with: overlaps_with.clone(),
         });
     }
 
     // Check for uncovered minterms (only if there's no default)
-    let mut uncovered_minterms = Vec::new();
-    if branch_set.default_output.is_none() {
-        for minterm in 0..total_rows {
-            if !covered_minterms.contains(&minterm) {
-                uncovered_minterms.push(minterm);
-            }
-        }
-    }
+    let uncovered_minterms = if branch_set.default_output.is_none() {
+        evaluation.unmatched.iter().map(|&row| row as u32).collect()
+    } else {
+        Vec::new()
+    };
 
+    let covered = total_rows as usize - evaluation.unmatched.len();
     let total_coverage_percent = if total_rows > 0 {
-        (covered_minterms.len() as f64 / total_rows as f64) * 100.0
+        (covered as f64 / total_rows as f64) * 100.0
     } else {
         0.0
     };
 
-    Ok(SimplificationAnalysis {
+    let redundant_with_default = redundant_with_default(
+        branch_set,
+        &evaluation.branch_rows,
+        &evaluation.first_match_rows,
+    );
+
+    let analysis = SimplificationAnalysis {
         branch_coverage,
         dead_branches,
         uncovered_minterms,
         total_coverage_percent,
+        redundant_with_default,
+        notes: Vec::new(),
+    };
+    Ok((analysis, evaluation.table))
+}
+
+/// Live, unprotected branches that return the default output and can be dropped together
+///
+/// Dropping a branch hands its rows to the next branch that holds there, or to the default.
+/// That is only harmless if the next one returns the default as well and is not protected
+/// (a protected branch runs its side effects); `if a { 0 } else if b { 1 } else { 0 }` can
+/// not lose its first branch, as `a && b` would then return 1. Candidates are tried in
+/// order, each together with those accepted before it.
+fn redundant_with_default(
+    branch_set: &BranchSet,
+    branch_rows: &[Vec<u64>],
+    first_match_rows: &[usize],
+) -> Vec<usize> {
+    let Some(default) = &branch_set.default_output else {
+        return Vec::new();
+    };
+    let default_key = branch_set.output_key(default);
+    let branches = &branch_set.branches;
+    let returns_default = |j: usize| branch_set.output_key(&branches[j].output) == default_key;
+    let holds = |j: usize, row: u64| branch_rows[j].binary_search(&row).is_ok();
+    let first_holding = |row: u64, dropped: &[usize]| {
+        (0..branches.len()).find(|&j| !dropped.contains(&j) && holds(j, row))
+    };
+
+    let mut dropped: Vec<usize> = Vec::new();
+    for index in 0..branches.len() {
+        if branches[index].protected || first_match_rows[index] == 0 || !returns_default(index) {
+            continue;
+        }
+        dropped.push(index);
+        let harmless = dropped
+            .iter()
+            .flat_map(|&j| &branch_rows[j])
+            .filter(|&&row| first_holding(row, &[]).is_some_and(|j| dropped.contains(&j)))
+            .all(|&row| {
+                first_holding(row, &dropped)
+                    .is_none_or(|next| !branches[next].protected && returns_default(next))
+            });
+        if !harmless {
+            dropped.pop();
+        }
+    }
+    dropped
+}
+
+/// Dead-code detection without enumerating all inputs, for branch sets with many variables
+///
+/// Branch `k` is dead when its condition and the negations of all earlier conditions can
+/// not hold together. That conjunction is split into groups of operands that share
+/// variables, and each group is checked by enumerating only its own variables (at most
+/// [`MAX_SYMBOLIC_COMBINATIONS`] combinations). Dead branches are reported as by
+/// [`analyze_branches`]; coverage is not computed.
+pub fn analyze_branches_symbolic(branch_set: &BranchSet) -> Result<SymbolicAnalysis, String> {
+    check_condition_depths(branch_set)?;
+    let var_types = |name: &str| {
+        branch_set
+            .variable_types
+            .get(name)
+            .cloned()
+            .unwrap_or(VariableType::Boolean)
+    };
+    let conditions: Vec<BoolExpr> = branch_set
+        .branches
+        .iter()
+        .map(|branch| fold_constants(&branch.condition))
+        .collect();
+    let negations: Vec<BoolExpr> = conditions.iter().cloned().map(BoolExpr::negate).collect();
+    let normalized: Vec<_> = conditions.iter().map(normalize).collect();
+    let all_types: HashMap<String, VariableType> = collect_variables(branch_set)
+        .into_iter()
+        .map(|name| {
+            let var_type = var_types(&name);
+            (name, var_type)
+        })
+        .collect();
+
+    let mut dead_branches = Vec::new();
+    for (k, branch) in branch_set.branches.iter().enumerate() {
+        let condition = &conditions[k];
+        let mut reachable: Vec<&BoolExpr> = vec![condition];
+        reachable.extend(&negations[..k]);
+        if satisfiable(&reachable, &var_types)? {
+            continue;
+        }
+
+        let duplicate_of = (0..k).find(|&j| normalized[j] == normalized[k]);
+        let (reason, covered_by) = if !satisfiable(&[condition], &var_types)? {
+            (DeadCodeReason::Contradiction, Vec::new())
+        } else {
+            match duplicate_of {
+                Some(j)
+                    if branch_set.output_key(&branch_set.branches[j].output)
+                        != branch_set.output_key(&branch.output) =>
+                {
+                    (DeadCodeReason::ConflictingDuplicate, vec![j])
+                }
+                Some(j) => (DeadCodeReason::Redundant, vec![j]),
+                None => {
+                    let mut overlapping = Vec::new();
+                    for (j, earlier) in conditions[..k].iter().enumerate() {
+                        if satisfiable(&[earlier, condition], &var_types)? {
+                            overlapping.push(j);
+                        }
+                    }
+                    (DeadCodeReason::FullyCovered, overlapping)
+                }
+            }
+        };
+        dead_branches.push(DeadBranch {
+            branch_index: k,
+            reason,
+            covered_by,
+            protected: branch.protected,
+            detail: conflicting_comparisons(condition, &all_types),
+        });
+    }
+
+    let none_holds: Vec<&BoolExpr> = negations.iter().collect();
+    Ok(SymbolicAnalysis {
+        dead_branches,
+        has_unmatched_inputs: satisfiable(&none_holds, &var_types)?,
     })
 }
 
+/// Can all `operands` hold at once?
+///
+/// Operands that share no variable (directly or through other operands) are independent,
+/// so each connected group is enumerated on its own.
+fn satisfiable(
+    operands: &[&BoolExpr],
+    var_types: &dyn Fn(&str) -> VariableType,
+) -> Result<bool, String> {
+    // Groups of (variables, operands), merged whenever an operand touches several groups
+    let mut groups: Vec<(HashSet<String>, Vec<&BoolExpr>)> = Vec::new();
+    for &operand in operands {
+        let mut variables = extract_variables(operand);
+        let mut members = vec![operand];
+        let mut i = 0;
+        while i < groups.len() {
+            if groups[i].0.is_disjoint(&variables) {
+                i += 1;
+            } else {
+                let (group_vars, group_members) = groups.swap_remove(i);
+                variables.extend(group_vars);
+                members.extend(group_members);
+            }
+        }
+        groups.push((variables, members));
+    }
+
+    for (variables, members) in &groups {
+        let mut variables: Vec<&String> = variables.iter().collect();
+        variables.sort();
+        if !group_satisfiable(&variables, members, var_types)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Is there an assignment of `variables` on which every operand holds?
+fn group_satisfiable(
+    variables: &[&String],
+    operands: &[&BoolExpr],
+    var_types: &dyn Fn(&str) -> VariableType,
+) -> Result<bool, String> {
+    let domains: Vec<(i32, i32, bool)> = variables
+        .iter()
+        .map(|name| match var_types(name) {
+            VariableType::Boolean => (0, 1, true),
+            VariableType::Integer { min, max } => (min, max, false),
+        })
+        .collect();
+    let combinations = domains.iter().try_fold(1u64, |total, &(min, max, _)| {
+        let range = (i64::from(max) - i64::from(min) + 1).max(0) as u64;
+        total
+            .checked_mul(range)
+            .filter(|&total| total <= MAX_SYMBOLIC_COMBINATIONS)
+    });
+    if combinations.is_none() {
+        return Err(format!(
+            "Conditions over {{{}}} have more than {} input combinations",
+            variables
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            MAX_SYMBOLIC_COMBINATIONS
+        ));
+    }
+    if domains.iter().any(|&(min, max, _)| min > max) {
+        return Ok(false);
+    }
+
+    // Odometer over the domains, the first variable fastest
+    let mut values: Vec<i32> = domains.iter().map(|&(min, _, _)| min).collect();
+    let mut bool_assignments: HashMap<String, bool> = HashMap::new();
+    let mut int_assignments: HashMap<String, i32> = HashMap::new();
+    loop {
+        for ((name, &value), &(_, _, is_bool)) in variables.iter().zip(&values).zip(&domains) {
+            if is_bool {
+                bool_assignments.insert(name.to_string(), value == 1);
+            } else {
+                int_assignments.insert(name.to_string(), value);
+            }
+        }
+        if operands
+            .iter()
+            .all(|operand| evaluate_with_ints(operand, &bool_assignments, &int_assignments))
+        {
+            return Ok(true);
+        }
+
+        let mut digit = 0;
+        loop {
+            if digit == values.len() {
+                return Ok(false);
+            }
+            if values[digit] < domains[digit].1 {
+                values[digit] += 1;
+                break;
+            }
+            values[digit] = domains[digit].0;
+            digit += 1;
+        }
+    }
+}
+
 /// Format a minterm as variable assignments
 pub fn format_minterm(minterm: u32, variables: &[String]) -> String {
     let mut parts = Vec::new();
@@ -200,7 +415,14 @@ pub fn format_minterm(minterm: u32, variables: &[String]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::qm::random::test_seed;
+    use crate::simplify::analyzer::{
+        build_truth_table, evaluate, evaluate_with_ints, extract_variables,
+    };
     use crate::simplify::types::{BoolExpr, BranchSet};
+    use crate::{assert_eq_seeded, assert_seeded};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use std::collections::HashSet;
 
     #[test]
     fn test_detect_fully_covered() {
@@ -259,6 +481,539 @@ mod tests {
         assert!(analysis.uncovered_minterms.contains(&2)); // !a && b
     }
 
+    #[test]
+    fn test_detect_constant_false() {
+        // if a { return "1" }
+        // elif false { return "2" }  // Never taken - CONTRADICTION
+        // else { return "0" }
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::var("a"), "1");
+        branches.add_branch(BoolExpr::constant(false), "2");
+        branches.set_default("0");
+
+        let analysis = analyze_branches(&branches).unwrap();
+
+        assert!(analysis.branch_coverage[1].minterms_covered.is_empty());
+        assert_eq!(analysis.dead_branches.len(), 1);
+        assert_eq!(analysis.dead_branches[0].branch_index, 1);
+        assert_eq!(
+            analysis.dead_branches[0].reason,
+            DeadCodeReason::Contradiction
+        );
+    }
+
+    #[test]
+    fn test_constant_true_shadows_later_branches() {
+        // if a || true { return "1" }  // Folds to true - covers everything
+        // elif b { return "2" }        // Shadowed - DEAD CODE
+        let mut branches = BranchSet::new();
+        branches.add_branch(
+            BoolExpr::or(BoolExpr::var("a"), BoolExpr::constant(true)),
+            "1",
+        );
+        branches.add_branch(BoolExpr::var("b"), "2");
+
+        let analysis = analyze_branches(&branches).unwrap();
+
+        assert_eq!(analysis.branch_coverage[0].minterms_covered.len(), 4);
+        assert_eq!(analysis.total_coverage_percent, 100.0);
+        assert_eq!(analysis.dead_branches.len(), 1);
+        assert_eq!(analysis.dead_branches[0].branch_index, 1);
+        assert_eq!(
+            analysis.dead_branches[0].reason,
+            DeadCodeReason::FullyCovered
+        );
+        assert_eq!(analysis.dead_branches[0].covered_by, vec![0]);
+    }
+
+    #[test]
+    fn test_duplicate_condition_same_output_is_redundant() {
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branches.add_branch(BoolExpr::var("c"), "2");
+        branches.add_branch(BoolExpr::and(BoolExpr::var("b"), BoolExpr::var("a")), "1");
+
+        let analysis = analyze_branches(&branches).unwrap();
+
+        assert_eq!(analysis.dead_branches.len(), 1);
+        assert_eq!(analysis.dead_branches[0].branch_index, 2);
+        assert_eq!(analysis.dead_branches[0].reason, DeadCodeReason::Redundant);
+        assert_eq!(analysis.dead_branches[0].covered_by, vec![0]);
+    }
+
+    #[test]
+    fn test_duplicate_condition_different_output() {
+        // if a && b { return "1" }
+        // elif a && b { return "2" }  // Same condition, other output: a variable went missing?
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branches.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "2");
+
+        let analysis = analyze_branches(&branches).unwrap();
+
+        assert_eq!(analysis.dead_branches.len(), 1);
+        assert_eq!(
+            analysis.dead_branches[0].reason,
+            DeadCodeReason::ConflictingDuplicate
+        );
+        assert_eq!(analysis.dead_branches[0].covered_by, vec![0]);
+    }
+
+    #[test]
+    fn test_integer_interval_restricts_domain() {
+        // if x >= 2 && x <= 5 { ... }  // x in 0..=9: rows 2..=5
+        let mut branches = BranchSet::new();
+        branches.declare_int("x", 0, 9);
+        branches.add_branch(
+            BoolExpr::and(
+                BoolExpr::greater_or_equal("x", 2),
+                BoolExpr::less_or_equal("x", 5),
+            ),
+            "mid",
+        );
+
+        let analysis = analyze_branches(&branches).unwrap();
+        assert!(analysis.dead_branches.is_empty());
+        assert_eq!(
+            analysis.branch_coverage[0].minterms_covered,
+            vec![2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_empty_integer_interval_is_contradiction() {
+        // if x < 3 && x > 7 { ... }  // no x satisfies both
+        let mut branches = BranchSet::new();
+        branches.declare_int("x", 0, 9);
+        branches.add_branch(BoolExpr::equals("x", 1), "one");
+        branches.add_branch(
+            BoolExpr::and(BoolExpr::less_than("x", 3), BoolExpr::greater_than("x", 7)),
+            "never",
+        );
+
+        let analysis = analyze_branches(&branches).unwrap();
+        assert_eq!(analysis.dead_branches.len(), 1);
+        let dead = &analysis.dead_branches[0];
+        assert_eq!(dead.branch_index, 1);
+        assert_eq!(dead.reason, DeadCodeReason::Contradiction);
+        assert_eq!(dead.detail.as_deref(), Some("x > 7 contradicts x < 3"));
+        assert!(analysis.branch_coverage[1].minterms_covered.is_empty());
+    }
+
+    /// `analyze_branches` as it was before the single-pass evaluation
+    fn analyze_branches_reference(
+        branch_set: &BranchSet,
+    ) -> Result<SimplificationAnalysis, String> {
+        // Collect all variables
+        let mut all_vars = HashSet::new();
+        for branch in &branch_set.branches {
+            let vars = extract_variables(&branch.condition);
+            all_vars.extend(vars);
+        }
+
+        let mut variables: Vec<String> = all_vars.into_iter().collect();
+        variables.sort();
+
+        let var_count = variables.len();
+        if var_count > 16 {
+            return Err(format!(
+                "Too many variables ({}). Maximum supported: 16",
+                var_count
+            ));
+        }
+
+        // Get or infer variable types
+        let mut var_types: HashMap<String, VariableType> = HashMap::new();
+        for var in &variables {
+            let var_type = branch_set
+                .variable_types
+                .get(var)
+                .cloned()
+                .unwrap_or(VariableType::Boolean);
+            var_types.insert(var.clone(), var_type);
+        }
+
+        // Calculate total combinations
+        let mut total_combinations = 1usize;
+        for var in &variables {
+            let var_type = &var_types[var];
+            let range = (var_type.max_value() - var_type.min_value() + 1) as usize;
+            total_combinations = total_combinations
+                .checked_mul(range)
+                .ok_or_else(|| "Too many variable combinations".to_string())?;
+        }
+
+        let total_rows = total_combinations as u32;
+        let mut covered_minterms = HashSet::new();
+        let mut branch_coverage: Vec<BranchCoverage> = Vec::new();
+        let mut dead_branches = Vec::new();
+
+        // Initialize assignments
+        let mut assignments: Vec<i32> =
+            variables.iter().map(|v| var_types[v].min_value()).collect();
+
+        // Normalized conditions to recognize a branch that repeats an earlier condition
+        let normalized: Vec<_> = branch_set
+            .branches
+            .iter()
+            .map(|branch| normalize(&branch.condition))
+            .collect();
+
+        // Analyze each branch in order
+        for (branch_idx, branch) in branch_set.branches.iter().enumerate() {
+            let condition = fold_constants(&branch.condition);
+            let mut minterms_for_this_branch = Vec::new();
+            let mut overlaps_with = Vec::new();
+
+            // Reset assignments for enumeration
+            for i in 0..variables.len() {
+                assignments[i] = var_types[&variables[i]].min_value();
+            }
+
+            // Comparisons with an empty intersection cover no row; no need to enumerate
+            let conflict = conflicting_comparisons(&condition, &var_types);
+            let rows = if conflict.is_some() { 0 } else { total_rows };
+
+            // Evaluate which minterms this branch covers
+            for minterm_idx in 0..rows {
+                // Build assignment maps
+                let mut bool_assignments = HashMap::new();
+                let mut int_assignments = HashMap::new();
+
+                for (i, var) in variables.iter().enumerate() {
+                    let value = assignments[i];
+                    match &var_types[var] {
+                        VariableType::Boolean => {
+                            bool_assignments.insert(var.clone(), value != 0);
+                        }
+                        VariableType::Integer { .. } => {
+                            int_assignments.insert(var.clone(), value);
+                        }
+                    }
+                }
+
+                if evaluate_with_ints(&condition, &bool_assignments, &int_assignments) {
+                    minterms_for_this_branch.push(minterm_idx);
+
+                    // Check if this minterm was already covered by an earlier branch
+                    if covered_minterms.contains(&minterm_idx) {
+                        // Find which branch(es) already covered this
+                        for (prev_idx, prev_coverage) in branch_coverage.iter().enumerate() {
+                            if prev_coverage.minterms_covered.contains(&minterm_idx)
+                                && !overlaps_with.contains(&prev_idx)
+                            {
+                                overlaps_with.push(prev_idx);
+                            }
+                        }
+                    }
+                }
+
+                // Increment to next combination (like odometer)
+                let mut carry = true;
+                for i in 0..variables.len() {
+                    if carry {
+                        assignments[i] += 1;
+                        let var_type = &var_types[&variables[i]];
+                        if assignments[i] > var_type.max_value() {
+                            assignments[i] = var_type.min_value();
+                        } else {
+                            carry = false;
+                        }
+                    }
+                }
+            }
+
+            // Check if this branch is dead code
+            let new_coverage: HashSet<u32> = minterms_for_this_branch
+                .iter()
+                .filter(|&&m| !covered_minterms.contains(&m))
+                .copied()
+                .collect();
+
+            if new_coverage.is_empty() {
+                // This branch covers no new minterms - it's dead code
+                let duplicate_of =
+                    (0..branch_idx).find(|&j| normalized[j] == normalized[branch_idx]);
+                let (reason, covered_by) = match duplicate_of {
+                    _ if minterms_for_this_branch.is_empty() => {
+                        (DeadCodeReason::Contradiction, overlaps_with.clone())
+                    }
+                    // An exact repeat with another output usually means the extraction dropped
+                    // the variable that tells the two apart
+                    Some(j)
+                        if branch_set.output_key(&branch_set.branches[j].output)
+                            != branch_set.output_key(&branch.output) =>
+                    {
+                        (DeadCodeReason::ConflictingDuplicate, vec![j])
+                    }
+                    Some(j) => (DeadCodeReason::Redundant, vec![j]),
+                    None => (DeadCodeReason::FullyCovered, overlaps_with.clone()),
+                };
+
+                dead_branches.push(DeadBranch {
+                    branch_index: branch_idx,
+                    reason,
+                    covered_by,
+                    protected: branch.protected,
+                    detail: conflict,
+                });
+            }
+
+            // Add to covered set
+            for &minterm in &minterms_for_this_branch {
+                covered_minterms.insert(minterm);
+            }
+
+            // Record coverage for this branch
+            branch_coverage.push(BranchCoverage {
+                branch_index: branch_idx,
+                minterms_covered: minterms_for_this_branch,
+                coverage_count: new_coverage.len(),
+                overlaps_with,
+            });
+        }
+
+        // Check for uncovered minterms (only if there's no default)
+        let mut uncovered_minterms = Vec::new();
+        if branch_set.default_output.is_none() {
+            for minterm in 0..total_rows {
+                if !covered_minterms.contains(&minterm) {
+                    uncovered_minterms.push(minterm);
+                }
+            }
+        }
+
+        let total_coverage_percent = if total_rows > 0 {
+            (covered_minterms.len() as f64 / total_rows as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(SimplificationAnalysis {
+            branch_coverage,
+            dead_branches,
+            uncovered_minterms,
+            total_coverage_percent,
+            redundant_with_default: Vec::new(),
+            notes: Vec::new(),
+        })
+    }
+
+    /// `build_truth_table` as it was before the single-pass evaluation
+    fn build_truth_table_reference(branch_set: &BranchSet) -> Vec<(String, Vec<u64>)> {
+        let mut variables: Vec<String> = branch_set
+            .branches
+            .iter()
+            .flat_map(|branch| extract_variables(&branch.condition))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        variables.sort();
+        let mut groups: HashMap<String, Vec<u64>> = HashMap::new();
+        for minterm in 0..1u64 << variables.len() {
+            let assignments: HashMap<String, bool> = variables
+                .iter()
+                .enumerate()
+                .map(|(i, var)| (var.clone(), (minterm >> i) & 1 == 1))
+                .collect();
+            let output = branch_set
+                .branches
+                .iter()
+                .find(|branch| evaluate(&fold_constants(&branch.condition), &assignments))
+                .map(|branch| branch.output.as_str())
+                .or(branch_set.default_output.as_deref())
+                .unwrap_or("-");
+            groups.entry(output.to_string()).or_default().push(minterm);
+        }
+        let mut groups: Vec<(String, Vec<u64>)> = groups.into_iter().collect();
+        groups.sort();
+        groups
+    }
+
+    /// Random branch set over booleans `a`..`e` and, with `with_int`, an integer `x` in 0..=5
+    fn random_branch_set(rng: &mut StdRng, with_int: bool) -> BranchSet {
+        fn expr(rng: &mut StdRng, depth: u32, with_int: bool) -> BoolExpr {
+            let var = |rng: &mut StdRng| ["a", "b", "c", "d", "e"][rng.random_range(0..5)];
+            let leaves = if with_int { 3 } else { 2 };
+            match rng.random_range(0..if depth == 0 { leaves } else { 6 }) {
+                0 if rng.random_bool(0.1) => BoolExpr::constant(rng.random_bool(0.5)),
+                0 | 1 => BoolExpr::var(var(rng)),
+                2 if with_int => match rng.random_range(0..3) {
+                    0 => BoolExpr::equals("x", rng.random_range(0..6)),
+                    1 => BoolExpr::less_than("x", rng.random_range(0..6)),
+                    _ => BoolExpr::greater_than("x", rng.random_range(0..6)),
+                },
+                2 | 3 => BoolExpr::negate(expr(rng, depth - 1, with_int)),
+                4 => BoolExpr::and(
+                    expr(rng, depth - 1, with_int),
+                    expr(rng, depth - 1, with_int),
+                ),
+                _ => BoolExpr::or(
+                    expr(rng, depth - 1, with_int),
+                    expr(rng, depth - 1, with_int),
+                ),
+            }
+        }
+
+        let mut branch_set = BranchSet::new();
+        if with_int {
+            branch_set.declare_int("x", 0, 5);
+        }
+        for _ in 0..rng.random_range(1..7) {
+            let condition = match branch_set.branches.last() {
+                // Repeat the previous condition now and then (redundant or conflicting)
+                Some(previous) if rng.random_bool(0.15) => previous.condition.clone(),
+                _ => expr(rng, 3, with_int),
+            };
+            branch_set.add_branch(condition, ["1", "2", "3"][rng.random_range(0..3)]);
+        }
+        if rng.random_bool(0.5) {
+            branch_set.set_default("0");
+        }
+        branch_set
+    }
+
+    #[test]
+    fn test_symbolic_40_variables() {
+        // Chains over x0..x39; branch 2 is shadowed by branch 0, branch 3 is a contradiction
+        let chain = |vars: std::ops::Range<usize>| {
+            vars.map(|i| BoolExpr::var(&format!("x{}", i)))
+                .reduce(BoolExpr::and)
+                .unwrap()
+        };
+        let mut branches = BranchSet::new();
+        branches.add_branch(chain(0..2), "1");
+        branches.add_branch(chain(2..12), "2");
+        branches.add_branch(BoolExpr::and(chain(0..2), chain(25..28)), "3");
+        branches.add_branch(
+            BoolExpr::and(BoolExpr::var("x5"), BoolExpr::negate(BoolExpr::var("x5"))),
+            "4",
+        );
+        branches.add_branch(chain(12..25), "5");
+        branches.add_branch(chain(28..40), "6");
+        assert_eq!(collect_variables(&branches).len(), 40);
+        assert!(analyze_branches(&branches).is_err());
+
+        let analysis = analyze_branches_symbolic(&branches).unwrap();
+        let dead: Vec<(usize, DeadCodeReason, Vec<usize>)> = analysis
+            .dead_branches
+            .iter()
+            .map(|d| (d.branch_index, d.reason.clone(), d.covered_by.clone()))
+            .collect();
+        assert_eq!(
+            dead,
+            vec![
+                (2, DeadCodeReason::FullyCovered, vec![0, 1]),
+                (3, DeadCodeReason::Contradiction, vec![])
+            ]
+        );
+        assert!(analysis.has_unmatched_inputs);
+
+        // A tautology shadows everything after it
+        branches.add_branch(
+            BoolExpr::or(BoolExpr::var("x0"), BoolExpr::negate(BoolExpr::var("x0"))),
+            "6",
+        );
+        branches.add_branch(BoolExpr::var("x39"), "7");
+        let analysis = analyze_branches_symbolic(&branches).unwrap();
+        assert_eq!(analysis.dead_branches.last().unwrap().branch_index, 7);
+        assert!(!analysis.has_unmatched_inputs);
+    }
+
+    #[test]
+    fn test_symbolic_matches_enumeration() {
+        let seed = test_seed(11);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for round in 0..300 {
+            let branch_set = random_branch_set(&mut rng, round % 3 == 0);
+            let Ok(expected) = analyze_branches(&branch_set) else {
+                continue;
+            };
+            let actual = analyze_branches_symbolic(&branch_set).unwrap();
+            let summary = |dead: &[DeadBranch]| -> Vec<String> {
+                dead.iter()
+                    .map(|d| {
+                        let mut covered_by = d.covered_by.clone();
+                        covered_by.sort_unstable();
+                        format!(
+                            "{} {:?} {:?} {:?}",
+                            d.branch_index, d.reason, covered_by, d.detail
+                        )
+                    })
+                    .collect()
+            };
+            assert_eq_seeded!(
+                seed,
+                summary(&actual.dead_branches),
+                summary(&expected.dead_branches),
+                "round {}",
+                round
+            );
+            // With boolean variables only, every row is a first match of at most one branch
+            if round % 3 != 0 {
+                let first_matches: usize = expected
+                    .branch_coverage
+                    .iter()
+                    .map(|c| c.coverage_count)
+                    .sum();
+                let rows = 1usize << collect_variables(&branch_set).len();
+                assert_eq_seeded!(
+                    seed,
+                    actual.has_unmatched_inputs,
+                    first_matches < rows,
+                    "round {}",
+                    round
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_pass_matches_reference() {
+        let seed = test_seed(5);
+        let mut rng = StdRng::seed_from_u64(seed);
+        for round in 0..300 {
+            let branch_set = random_branch_set(&mut rng, round % 3 == 0);
+            let expected = analyze_branches_reference(&branch_set);
+            let actual = analyze_branches(&branch_set);
+            assert_eq_seeded!(
+                seed,
+                format!("{:?}", actual),
+                format!("{:?}", expected),
+                "round {}",
+                round
+            );
+
+            if round % 3 != 0 && expected.is_ok() {
+                let (analysis, table) = analyze_with_truth_table(&branch_set).unwrap();
+                assert_eq_seeded!(
+                    seed,
+                    format!("{:?}", Ok::<_, String>(analysis)),
+                    format!("{:?}", expected)
+                );
+                let mut groups = table.sorted_output_groups();
+                if !table.dont_cares.is_empty() {
+                    groups.push(("-".to_string(), table.dont_cares.clone()));
+                    groups.sort();
+                }
+                assert_eq_seeded!(
+                    seed,
+                    groups,
+                    build_truth_table_reference(&branch_set),
+                    "round {}",
+                    round
+                );
+                assert_eq_seeded!(
+                    seed,
+                    build_truth_table(&branch_set)
+                        .unwrap()
+                        .sorted_output_groups(),
+                    table.sorted_output_groups()
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_format_minterm() {
         let vars = vec!["a".to_string(), "b".to_string()];
@@ -272,4 +1027,123 @@ mod tests {
         // minterm 3: a=1, b=1
         assert_eq!(format_minterm(3, &vars), "a && b");
     }
+
+    #[test]
+    fn test_redundant_with_default() {
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+
+        // if a && !b { 0 } elif b { 1 } else { 0 }: nothing after the first branch holds
+        // on its rows, so the default takes them over unchanged
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::and(a.clone(), BoolExpr::negate(b.clone())), "0");
+        branches.add_branch(b.clone(), "1");
+        branches.set_default("0");
+        assert_eq!(
+            analyze_branches(&branches).unwrap().redundant_with_default,
+            vec![0]
+        );
+
+        // if a { 0 } elif b { 1 } else { 0 }: without the first branch, a && b returns 1
+        let mut branches = BranchSet::new();
+        branches.add_branch(a.clone(), "0");
+        branches.add_branch(b.clone(), "1");
+        branches.set_default("0");
+        assert!(
+            analyze_branches(&branches)
+                .unwrap()
+                .redundant_with_default
+                .is_empty()
+        );
+
+        // The same with a protected branch after it: its side effects would start to run
+        let mut branches = BranchSet::new();
+        branches.add_branch(a.clone(), "0");
+        branches.add_protected_branch(a.clone(), "0");
+        branches.set_default("0");
+        assert!(
+            analyze_branches(&branches)
+                .unwrap()
+                .redundant_with_default
+                .is_empty()
+        );
+
+        // Without a default nothing is redundant with it
+        let mut branches = BranchSet::new();
+        branches.add_branch(a.clone(), "0");
+        assert!(
+            analyze_branches(&branches)
+                .unwrap()
+                .redundant_with_default
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_redundant_with_default_dropped_together() {
+        // if a { 0 } elif a || b { 0 } elif a { 1 } else { 0 }: either of the first two can
+        // go, but without both `a` reaches the (dead) third branch
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+        let mut branches = BranchSet::new();
+        branches.add_branch(a.clone(), "0");
+        branches.add_branch(BoolExpr::or(a.clone(), b), "0");
+        branches.add_branch(a, "1");
+        branches.set_default("0");
+        assert_eq!(
+            analyze_branches(&branches).unwrap().redundant_with_default,
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn test_dropping_redundant_branches_keeps_the_table() {
+        let seed = test_seed(17);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut dropped_any = false;
+        for round in 0..300 {
+            let mut branch_set = random_branch_set(&mut rng, false);
+            for branch in &mut branch_set.branches {
+                if rng.random_bool(0.4) {
+                    branch.output = "0".to_string();
+                }
+            }
+            branch_set.set_default("0");
+
+            let redundant = analyze_branches(&branch_set)
+                .unwrap()
+                .redundant_with_default;
+            dropped_any |= !redundant.is_empty();
+            let mut reduced = branch_set.clone();
+            reduced.branches = (0..branch_set.branches.len())
+                .filter(|i| !redundant.contains(i))
+                .map(|i| branch_set.branches[i].clone())
+                .collect();
+
+            // Variables that only the dropped branches read do not matter to the decisions
+            let outputs = |set: &BranchSet| -> Vec<String> {
+                let variables = collect_variables(&branch_set);
+                (0..1u64 << variables.len())
+                    .map(|row| {
+                        let assignments: HashMap<String, bool> = variables
+                            .iter()
+                            .enumerate()
+                            .map(|(i, var)| (var.clone(), (row >> i) & 1 == 1))
+                            .collect();
+                        set.branches
+                            .iter()
+                            .find(|b| evaluate(&fold_constants(&b.condition), &assignments))
+                            .map_or("0", |b| b.output.as_str())
+                            .to_string()
+                    })
+                    .collect()
+            };
+            assert_eq_seeded!(
+                seed,
+                outputs(&reduced),
+                outputs(&branch_set),
+                "round {}",
+                round
+            );
+        }
+        assert_seeded!(seed, dropped_any);
+    }
 }
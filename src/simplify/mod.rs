@@ -11,7 +11,8 @@
 //!
 //! # Example
 //! ```
-//! use qm_agent::simplify::{BoolExpr, BranchSet, simplify_branches, format_result};
+//! use qm_agent::prelude::*;
+//! use qm_agent::simplify::format_result;
 //!
 //! // Create branches: if a && b { 1 } elif a && !b { 1 } else { 0 }
 //! let mut branches = BranchSet::new();
@@ -39,15 +40,18 @@ pub mod dead_code;
 pub mod optimizer;
 pub mod parser;
 pub mod types;
+pub mod visualize;
 
 // Re-export main types and functions
 pub use comparisons::build_truth_table_with_comparisons;
-pub use dead_code::{analyze_branches, format_minterm};
-pub use optimizer::{format_bool_expr, simplify_branches};
-pub use parser::parse_bool_expr;
+pub use dead_code::{analyze_branches, analyze_branches_symbolic, format_minterm};
+pub use optimizer::{format_bool_expr, format_bool_expr_python, simplify_branches};
+pub use parser::{DEFAULT_MAX_DEPTH, parse_bool_expr, parse_bool_expr_with_max_depth};
 pub use types::{
-    BoolExpr, Branch, BranchCoverage, BranchSet, DeadBranch, DeadCodeReason,
-    SimplificationAnalysis, SimplificationResult, VariableType,
+    BoolExpr, Branch, BranchCoverage, BranchOrder, BranchSet, BranchSetBuilder,
+    DEFAULT_MAX_CONDITION_DEPTH, DEFAULT_MAX_CONDITION_OPERATORS, DEFAULT_MAX_TRUTH_TABLE_ROWS,
+    DeadBranch, DeadCodeReason, OutputComparison, OutputGroup, OutputKey, SimplificationAnalysis,
+    SimplificationResult, SymbolicAnalysis, TruthTable, VariableType, VariableTypes,
 };
 
 /// Format simplification result as human-readable text
@@ -79,7 +83,15 @@ pub fn format_result(result: &SimplificationResult) -> String {
                 "  Branch {} is unreachable (reason: {:?})\n",
                 dead.branch_index, dead.reason
             ));
-            if !dead.covered_by.is_empty() {
+            if let Some(detail) = &dead.detail {
+                output.push_str(&format!("    {}\n", detail));
+            }
+            if dead.reason == DeadCodeReason::ConflictingDuplicate {
+                output.push_str(&format!(
+                    "    Same condition as branch {:?} with a different output; a distinguishing variable may be missing\n",
+                    dead.covered_by
+                ));
+            } else if !dead.covered_by.is_empty() {
                 output.push_str(&format!(
                     "    Already covered by branches: {:?}\n",
                     dead.covered_by
@@ -89,6 +101,36 @@ pub fn format_result(result: &SimplificationResult) -> String {
         output.push('\n');
     }
 
+    if !result.analysis.redundant_with_default.is_empty() {
+        output.push_str("ℹ️  REDUNDANT WITH DEFAULT:\n");
+        for index in &result.analysis.redundant_with_default {
+            output.push_str(&format!(
+                "  Branch {} returns the default output; folded into the default\n",
+                index
+            ));
+        }
+        output.push('\n');
+    }
+
+    if !result.unreferenced_variables.is_empty() {
+        output.push_str("ℹ️  UNUSED INPUTS:\n");
+        for var in &result.unreferenced_variables {
+            output.push_str(&format!(
+                "  Input '{}' does not affect the outcome and can be removed\n",
+                var
+            ));
+        }
+        output.push('\n');
+    }
+
+    if !result.analysis.notes.is_empty() {
+        output.push_str("ℹ️  NOTES:\n");
+        for note in &result.analysis.notes {
+            output.push_str(&format!("  {}\n", note));
+        }
+        output.push('\n');
+    }
+
     // Show coverage gaps
     if result.analysis.has_coverage_gaps() {
         output.push_str("⚠️  COVERAGE GAPS (missing test cases):\n");
@@ -119,8 +161,15 @@ pub fn format_result(result: &SimplificationResult) -> String {
             output.push_str("elif ");
         }
 
-        output.push_str(&format_bool_expr(condition));
-        output.push_str(&format!(" {{ return {}; }}\n", out));
+        output.push_str(&format_bool_expr(&condition.normalize()));
+        output.push_str(&format!(" {{ return {}; }}", out));
+        if let Some(covered) = result.combinations_covered.get(i) {
+            output.push_str(&format!(
+                " (covers {}/{} input combinations)",
+                covered, result.total_combinations
+            ));
+        }
+        output.push('\n');
     }
 
     output
@@ -166,4 +215,22 @@ mod tests {
         let result = simplify_branches(&branches).unwrap();
         assert_eq!(result.simplified_branch_count, 2);
     }
+
+    #[test]
+    fn test_simplify_zero_and_one_variables() {
+        // Only constant conditions: the first true branch decides everything
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::constant(false), "x");
+        branches.add_branch(BoolExpr::constant(true), "y");
+        let result = simplify_branches(&branches).unwrap();
+        assert_eq!(result.simplified_branch_count, 1);
+        assert_eq!(result.simplified_conditions[0].1, "y");
+
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::negate(BoolExpr::var("a")), "x");
+        branches.add_branch(BoolExpr::var("a"), "y");
+        let result = simplify_branches(&branches).unwrap();
+        assert_eq!(result.simplified_branch_count, 2);
+        assert_eq!(result.variables, vec!["a"]);
+    }
 }
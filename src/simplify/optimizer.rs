@@ -1,58 +1,535 @@
 // Optimizer: Apply QM minimization and generate simplified conditions
 
-use super::types::{BoolExpr, BranchSet, SimplificationResult, TruthTable};
-use crate::qm::QMSolver;
+use super::analyzer::{collect_variables, evaluate_with_ints, extract_variables};
+use super::comparisons::{
+    BitBlastedTable, build_bit_blasted_table, merge_terms, recover_term, term_values,
+};
+use super::types::{
+    BoolExpr, BranchOrder, BranchSet, SimplificationAnalysis, SimplificationResult, TruthTable,
+    VariableType,
+};
+use crate::qm::{QMSolver, SopExpression, parse_sop};
 use crate::{Enc16, Enc32, Enc64};
+use std::collections::{HashMap, HashSet};
 
 /// Simplify a set of branches using Quine-McCluskey minimization
+///
+/// When branches carry weights, outputs are ordered heaviest first. Around protected
+/// branches, whose position is fixed, weights are ignored; with integer variables they are
+/// rejected, as the original conditions are kept there.
 pub fn simplify_branches(branch_set: &BranchSet) -> Result<SimplificationResult, String> {
-    // Analyze for dead code first
-    let analysis = super::dead_code::analyze_branches(branch_set)?;
+    let (mut result, table) = simplify_unmeasured(branch_set)?;
+    let (covered, total) = count_combinations(branch_set, &table, &result.simplified_conditions);
+    result.combinations_covered = covered;
+    result.total_combinations = total;
+    result.unreferenced_variables = unreferenced_variables(
+        branch_set,
+        &table,
+        &result.simplified_conditions,
+        &result.analysis.uncovered_minterms,
+    );
+    Ok(result)
+}
+
+/// Variables of `branch_set`, declared or used by a condition, that none of `conditions`
+/// reads: the outcome does not depend on them (sorted)
+///
+/// The rows in `uncovered` (no branch and no default) are minimized as don't-cares, so a
+/// variable that only decides whether an input falls into such a gap is not read by any
+/// condition either; it still matters and is not reported.
+fn unreferenced_variables(
+    branch_set: &BranchSet,
+    table: &TruthTable,
+    conditions: &[(BoolExpr, String)],
+    uncovered: &[u32],
+) -> Vec<String> {
+    let mut referenced: HashSet<String> = conditions
+        .iter()
+        .flat_map(|(condition, _)| extract_variables(condition))
+        .collect();
+    referenced.extend(gap_variables(branch_set, table, uncovered));
+    let mut variables: Vec<String> = collect_variables(branch_set)
+        .into_iter()
+        .chain(branch_set.variable_types.keys().cloned())
+        .filter(|var| !referenced.contains(var))
+        .collect();
+    variables.sort();
+    variables.dedup();
+    variables
+}
+
+/// Variables of `table` on which membership of the rows in `uncovered` depends
+///
+/// Rows count in mixed radix over the variables' domains, the first variable varying
+/// fastest. A variable is independent of the gaps when, with the other variables fixed,
+/// either all of its values or none give an uncovered row.
+fn gap_variables(branch_set: &BranchSet, table: &TruthTable, uncovered: &[u32]) -> Vec<String> {
+    let mut stride = 1u64;
+    let mut deciding = Vec::new();
+    for var in &table.variables {
+        let var_type = branch_set.variable_types.get(var).cloned();
+        let var_type = var_type.unwrap_or(VariableType::Boolean);
+        let range = (var_type.max_value() - var_type.min_value() + 1) as u64;
+        // Uncovered rows per assignment of the other variables
+        let mut counts: HashMap<u64, u64> = HashMap::new();
+        for &row in uncovered {
+            let row = u64::from(row);
+            let others = row % stride + row / (stride * range) * stride;
+            *counts.entry(others).or_default() += 1;
+        }
+        if counts.values().any(|&count| count < range) {
+            deciding.push(var.clone());
+        }
+        stride *= range;
+    }
+    deciding
+}
+
+/// Per simplified condition, the input combinations of `branch_set`'s variables on which it
+/// is the first condition to hold; and the number of input combinations
+///
+/// `table` has a row per input combination (integers by their domain, the first variable
+/// varying fastest). The simplified conditions return the output of every row that has
+/// one, so an output with a single condition takes its whole group; only the rows of an
+/// output with several conditions, and the don't-care rows, are evaluated.
+fn count_combinations(
+    branch_set: &BranchSet,
+    table: &TruthTable,
+    conditions: &[(BoolExpr, String)],
+) -> (Vec<u64>, u64) {
+    let var_types: Vec<VariableType> = table
+        .variables
+        .iter()
+        .map(|var| {
+            let var_type = branch_set.variable_types.get(var).cloned();
+            var_type.unwrap_or(VariableType::Boolean)
+        })
+        .collect();
+    let total: u64 = var_types
+        .iter()
+        .map(|var_type| (var_type.max_value() - var_type.min_value() + 1) as u64)
+        .product();
+
+    let mut bool_assignments: HashMap<String, bool> = HashMap::new();
+    let mut int_assignments: HashMap<String, i32> = HashMap::new();
+    let mut first_holding = |mut row: u64, positions: &[usize]| {
+        for (var, var_type) in table.variables.iter().zip(&var_types) {
+            let range = (var_type.max_value() - var_type.min_value() + 1) as u64;
+            let value = var_type.min_value() + (row % range) as i32;
+            row /= range;
+            match var_type {
+                VariableType::Boolean => {
+                    bool_assignments.insert(var.clone(), value != 0);
+                }
+                VariableType::Integer { .. } => {
+                    int_assignments.insert(var.clone(), value);
+                }
+            }
+        }
+        positions
+            .iter()
+            .copied()
+            .find(|&i| evaluate_with_ints(&conditions[i].0, &bool_assignments, &int_assignments))
+    };
+
+    let mut covered = vec![0u64; conditions.len()];
+    for (key, group) in &table.output_groups {
+        let positions: Vec<usize> = (0..conditions.len())
+            .filter(|&i| branch_set.output_key(&conditions[i].1) == *key)
+            .collect();
+        if let [position] = positions[..] {
+            covered[position] += group.minterms.len() as u64;
+            continue;
+        }
+        for &row in &group.minterms {
+            if let Some(position) = first_holding(row, &positions) {
+                covered[position] += 1;
+            }
+        }
+    }
+    let all: Vec<usize> = (0..conditions.len()).collect();
+    for &row in &table.dont_cares {
+        if let Some(position) = first_holding(row, &all) {
+            covered[position] += 1;
+        }
+    }
+    (covered, total)
+}
 
+/// [`simplify_branches`] without the coverage figures; and the table of the branch set over
+/// its variables (integers by their domain)
+fn simplify_unmeasured(
+    branch_set: &BranchSet,
+) -> Result<(SimplificationResult, TruthTable), String> {
     // Check if we have integer variables
     let has_int_vars = branch_set
         .variable_types
         .values()
-        .any(|t| matches!(t, super::types::VariableType::Integer { .. }));
+        .any(|t| matches!(t, VariableType::Integer { .. }));
 
-    // For integer variables, skip QM minimization and keep original conditions
-    // (QM doesn't work well with integer comparisons as they're already minimal)
+    // Integer variables are minimized in binary and turned back into comparisons;
+    // with protected branches the original conditions are kept
     if has_int_vars {
-        return simplify_with_integer_vars(branch_set, analysis);
+        // Analyze for dead code first
+        let (analysis, table) = super::dead_code::analyze_with_truth_table(branch_set)?;
+        let result = if branch_set.branches.iter().any(|b| b.protected) {
+            simplify_with_integer_vars(branch_set, analysis)?
+        } else {
+            simplify_with_bit_blasting(branch_set, analysis)?
+        };
+        let result = keep_oversized_outputs(branch_set, result, simplify_with_integer_vars)?;
+        return Ok((result, table));
     }
 
-    // Build truth table from branches (boolean-only)
-    let table = super::analyzer::build_truth_table(branch_set)?;
+    // Dead code analysis and truth table from the same enumeration (boolean-only)
+    let (analysis, table) = super::dead_code::analyze_with_truth_table(branch_set)?;
 
+    let result = if branch_set.branches.iter().any(|b| b.protected) {
+        simplify_around_protected(branch_set, &table, analysis)?
+    } else {
+        simplify_by_output(branch_set, &table, analysis)?
+    };
+    let result = keep_oversized_outputs(branch_set, result, |kept, analysis| {
+        simplify_around_protected(kept, &table, analysis)
+    })?;
+    Ok((result, table))
+}
+
+/// Minimize every output group of a boolean-only branch set without protected branches
+///
+/// The outputs form a first-match chain, see [`minimize_chain`].
+fn simplify_by_output(
+    branch_set: &BranchSet,
+    table: &TruthTable,
+    analysis: SimplificationAnalysis,
+) -> Result<SimplificationResult, String> {
     let original_count = branch_set.branches.len();
-    let mut simplified_conditions = Vec::new();
 
-    // For each unique output value, run QM minimization
-    for (output, minterms) in &table.output_groups {
-        let minimized_expr = minimize_for_output(&table, minterms, &table.dont_cares)?;
-        simplified_conditions.push((minimized_expr, output.clone()));
+    let (mut simplified_conditions, weighted_default) = output_chain(branch_set, &analysis, table);
+    let n_minimized = simplified_conditions.len() - usize::from(weighted_default.is_some());
+    let outputs: Vec<String> = simplified_conditions
+        .iter()
+        .map(|(_, output)| output.clone())
+        .collect();
+    let expressions = minimize_chain(table, &outputs, n_minimized)?;
+    for (position, expression) in expressions.iter().enumerate() {
+        simplified_conditions[position].0 = sop_condition(expression, &table.variables)?;
     }
 
-    // Sort by output for deterministic results
-    simplified_conditions.sort_by(|a, b| a.1.cmp(&b.1));
-
     let simplified_count = simplified_conditions.len();
 
     Ok(SimplificationResult {
         variables: table.variables.clone(),
         simplified_conditions,
+        protected_branches: Vec::new(),
         original_branch_count: original_count,
         simplified_branch_count: simplified_count,
         analysis,
+        weighted_default,
+        combinations_covered: Vec::new(),
+        total_combinations: 0,
+        unreferenced_variables: Vec::new(),
+    })
+}
+
+/// The outputs of `table`, each with condition `true`, in source order (or alphabetical)
+/// with the default output last and then by weight ([`apply_weights`]); and the weighted
+/// default, if any
+fn output_chain(
+    branch_set: &BranchSet,
+    analysis: &SimplificationAnalysis,
+    table: &TruthTable,
+) -> (Vec<(BoolExpr, String)>, Option<String>) {
+    let mut chain: Vec<(BoolExpr, String)> = table
+        .output_groups
+        .values()
+        .map(|group| (BoolExpr::Const(true), group.display.clone()))
+        .collect();
+    let sort_key = |output: &str| output_sort_key(branch_set, analysis, output);
+    chain.sort_by(|a, b| sort_key(&a.1).cmp(&sort_key(&b.1)).then(a.1.cmp(&b.1)));
+    let weighted_default = apply_weights(branch_set, analysis, &mut chain);
+    (chain, weighted_default)
+}
+
+/// Minimized SOP expression of each of the first `n_minimized` of `outputs`, the outputs of
+/// a first-match chain in order; the others are the else branch
+///
+/// An input combination of an output that comes earlier in the chain never reaches a later
+/// condition, so when minimizing an output the minterms of its earlier siblings are
+/// don't-cares; the conditions may then overlap, and the order decides. The chain is
+/// checked under first-match semantics: an output whose condition takes an input
+/// combination of another output is minimized again without sibling don't-cares.
+fn minimize_chain(
+    table: &TruthTable,
+    outputs: &[String],
+    n_minimized: usize,
+) -> Result<Vec<String>, String> {
+    let minterms: Vec<&[u64]> = outputs[..n_minimized]
+        .iter()
+        .map(|output| {
+            table
+                .group(output)
+                .map_or(&[][..], |g| g.minterms.as_slice())
+        })
+        .collect();
+    let mut expressions = Vec::with_capacity(n_minimized);
+    let mut earlier = table.dont_cares.clone();
+    for &own in &minterms {
+        expressions.push(solve_for_output(table, own, &earlier));
+        earlier.extend_from_slice(own);
+    }
+
+    let mut strict = vec![false; n_minimized];
+    loop {
+        let chain = outputs
+            .iter()
+            .enumerate()
+            .map(|(position, output)| {
+                let expression = expressions.get(position).map_or("1", String::as_str);
+                let sop = parse_sop(expression, &table.variables).map_err(|e| e.to_string())?;
+                Ok((sop, output.as_str()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let mismatches = first_match_mismatches(table, &chain);
+        if mismatches.is_empty() {
+            return Ok(expressions);
+        }
+        for position in mismatches {
+            if position >= n_minimized || strict[position] {
+                return Err(format!(
+                    "Simplified condition for '{}' is not equivalent to the original branches",
+                    outputs[position]
+                ));
+            }
+            strict[position] = true;
+            expressions[position] = solve_for_output(table, minterms[position], &table.dont_cares);
+        }
+    }
+}
+
+/// Positions in `chain` whose condition is the first to hold on a minterm of another output
+///
+/// Every minterm of `table` is tried against the conditions in order, with bit `i` as the
+/// value of variable `i`; its own output's position is reported when no condition holds.
+/// Don't-cares are not tried.
+fn first_match_mismatches(table: &TruthTable, chain: &[(SopExpression, &str)]) -> Vec<usize> {
+    let mut mismatches = Vec::new();
+    for group in table.output_groups.values() {
+        for &minterm in &group.minterms {
+            let first = chain
+                .iter()
+                .position(|(condition, _)| condition.evaluate(minterm));
+            let mismatch = match first {
+                Some(position) if chain[position].1 == group.display => None,
+                Some(position) => Some(position),
+                None => chain
+                    .iter()
+                    .position(|&(_, output)| output == group.display),
+            };
+            if let Some(position) = mismatch.filter(|p| !mismatches.contains(p)) {
+                mismatches.push(position);
+            }
+        }
+    }
+    mismatches.sort_unstable();
+    mismatches
+}
+
+/// Fall back to the original branches of outputs whose minimized condition is too large
+///
+/// A condition is too large when it has more operators than
+/// [`BranchSet::max_condition_operators`], or more than the original conditions of its
+/// output written out on their own: each with the conditions of the earlier branches
+/// negated, as the first-match order implies. The live branches of such outputs are then
+/// marked protected and the branch set is simplified again by `simplify_protected`, which
+/// keeps them verbatim at their position; a note is added to the analysis. With integer
+/// variables that is [`simplify_with_integer_vars`], which keeps every original branch.
+fn keep_oversized_outputs(
+    branch_set: &BranchSet,
+    result: SimplificationResult,
+    simplify_protected: impl FnOnce(
+        &BranchSet,
+        SimplificationAnalysis,
+    ) -> Result<SimplificationResult, String>,
+) -> Result<SimplificationResult, String> {
+    let analysis = &result.analysis;
+    let is_default = |output: &str| {
+        branch_set
+            .default_output
+            .as_deref()
+            .is_some_and(|default| branch_set.output_key(default) == branch_set.output_key(output))
+    };
+    let is_protected = |position: usize| {
+        result
+            .protected_branches
+            .iter()
+            .any(|&(p, _)| p == position)
+    };
+
+    // Operators of each branch's condition standing alone: `!c0 && !c1 && ... && ci`
+    let mut negated_earlier = 0;
+    let standalone: Vec<usize> = branch_set
+        .branches
+        .iter()
+        .map(|branch| {
+            let operators = branch.condition.operator_count();
+            let size = negated_earlier + operators;
+            negated_earlier += operators + 2;
+            size
+        })
+        .collect();
+
+    // Live, unprotected branches per oversized output
+    let mut oversized: Vec<(String, Vec<usize>)> = Vec::new();
+    for (position, (_, output)) in result.simplified_conditions.iter().enumerate() {
+        let key = branch_set.output_key(output);
+        if is_default(output)
+            || is_protected(position)
+            || oversized
+                .iter()
+                .any(|(o, _)| branch_set.output_key(o) == key)
+        {
+            continue;
+        }
+        let minimized: usize = result
+            .simplified_conditions
+            .iter()
+            .enumerate()
+            .filter(|&(p, (_, other))| branch_set.output_key(other) == key && !is_protected(p))
+            .map(|(_, (condition, _))| condition.operator_count())
+            .sum();
+        let originals: Vec<usize> = (0..branch_set.branches.len())
+            .filter(|&idx| {
+                let branch = &branch_set.branches[idx];
+                !branch.protected
+                    && branch_set.output_key(&branch.output) == key
+                    && analysis.branch_coverage[idx].coverage_count > 0
+                    && !analysis.redundant_with_default.contains(&idx)
+            })
+            .collect();
+        if originals.is_empty() {
+            continue;
+        }
+        // Joined by `||`
+        let original =
+            originals.iter().map(|&idx| standalone[idx]).sum::<usize>() + originals.len() - 1;
+        if minimized > branch_set.max_condition_operators || minimized > original {
+            oversized.push((output.clone(), originals));
+        }
+    }
+    if oversized.is_empty() {
+        return Ok(result);
+    }
+
+    let mut kept = branch_set.clone();
+    for &idx in oversized.iter().flat_map(|(_, originals)| originals) {
+        kept.branches[idx].protected = true;
+    }
+    let mut fallback = simplify_protected(&kept, result.analysis)?;
+    fallback
+        .protected_branches
+        .retain(|&(_, idx)| branch_set.branches[idx].protected);
+    for (output, _) in oversized {
+        fallback.analysis.notes.push(format!(
+            "output '{}' left unsimplified: minimized form larger",
+            output
+        ));
+    }
+    Ok(fallback)
+}
+
+/// Simplify branches with integer variables by minimizing their binary encoding
+///
+/// The outputs form a first-match chain over the encoding, see [`minimize_chain`]. Each
+/// product term of the minimized cover is rebuilt with [`recover_term`], so bit patterns
+/// like `x[0]' x[1]` come back as `x == 2`. Sibling don't-cares can spread the values a
+/// condition selects over the domain, which reads as a list of equalities rather than a
+/// range; an output keeps the condition minimized without them when that one is smaller.
+fn simplify_with_bit_blasting(
+    branch_set: &BranchSet,
+    analysis: SimplificationAnalysis,
+) -> Result<SimplificationResult, String> {
+    let blasted = build_bit_blasted_table(branch_set)?;
+    let table = &blasted.table;
+
+    let (mut simplified_conditions, weighted_default) = output_chain(branch_set, &analysis, table);
+    let n_minimized = simplified_conditions.len() - usize::from(weighted_default.is_some());
+    let outputs: Vec<String> = simplified_conditions
+        .iter()
+        .map(|(_, output)| output.clone())
+        .collect();
+    let expressions = minimize_chain(table, &outputs, n_minimized)?;
+    for (position, expression) in expressions.iter().enumerate() {
+        let own = table
+            .group(&outputs[position])
+            .map_or(&[][..], |g| g.minterms.as_slice());
+        let strict = solve_for_output(table, own, &table.dont_cares);
+        let (chained, strict) = (
+            recover_condition(&blasted, expression)?,
+            recover_condition(&blasted, &strict)?,
+        );
+        simplified_conditions[position].0 = if strict.operator_count() < chained.operator_count() {
+            strict
+        } else {
+            chained
+        };
+    }
+
+    let simplified_count = simplified_conditions.len();
+
+    Ok(SimplificationResult {
+        variables: blasted.layout.iter().map(|var| var.name.clone()).collect(),
+        simplified_conditions,
+        protected_branches: Vec::new(),
+        original_branch_count: branch_set.branches.len(),
+        simplified_branch_count: simplified_count,
+        analysis,
+        weighted_default,
+        combinations_covered: Vec::new(),
+        total_combinations: 0,
+        unreferenced_variables: Vec::new(),
+    })
+}
+
+/// Condition of a minimized SOP expression over the bits of `blasted`, with comparisons
+/// recovered from the bit patterns
+fn recover_condition(blasted: &BitBlastedTable, expression: &str) -> Result<BoolExpr, String> {
+    let table = &blasted.table;
+    let sop = parse_sop(expression, &table.variables).map_err(|e| e.to_string())?;
+    if sop.terms.is_empty() {
+        return Err("Contradiction: no valid conditions".to_string());
+    }
+
+    let value_terms = sop
+        .terms
+        .iter()
+        .map(|literals| term_values(&blasted.layout, literals))
+        .collect();
+    let terms: Vec<BoolExpr> = merge_terms(value_terms)
+        .iter()
+        .map(|values| recover_term(&blasted.layout, values))
+        .collect();
+    Ok(if terms.contains(&BoolExpr::Const(true)) {
+        BoolExpr::Const(true)
+    } else {
+        terms.into_iter().reduce(BoolExpr::or).unwrap()
     })
 }
 
 /// Simplify branches with integer variables (skip QM, keep original conditions)
+///
+/// The original conditions may overlap, so their order cannot follow the branch weights;
+/// weights are rejected here rather than ignored.
 fn simplify_with_integer_vars(
     branch_set: &BranchSet,
-    analysis: super::types::SimplificationAnalysis,
+    analysis: SimplificationAnalysis,
 ) -> Result<SimplificationResult, String> {
-    use std::collections::HashSet;
+    if branch_set.branches.iter().any(|b| b.weight.is_some()) {
+        return Err(
+            "Branch weights are not supported with protected branches on integer variables"
+                .to_string(),
+        );
+    }
 
     // Extract all variables
     let mut all_vars = HashSet::new();
@@ -70,22 +547,25 @@ fn simplify_with_integer_vars(
         .map(|db| db.branch_index)
         .collect();
 
-    // Keep non-dead branches in original order
+    // Keep non-dead (and protected) branches in original order; those the default already
+    // handles are dropped as well
     let mut simplified_conditions = Vec::new();
+    let mut protected_branches = Vec::new();
     for (idx, branch) in branch_set.branches.iter().enumerate() {
-        if !dead_indices.contains(&idx) {
+        if branch.protected {
+            protected_branches.push((simplified_conditions.len(), idx));
+        }
+        if branch.protected
+            || !(dead_indices.contains(&idx) || analysis.redundant_with_default.contains(&idx))
+        {
             simplified_conditions.push((branch.condition.clone(), branch.output.clone()));
         }
     }
 
     // Add default if present
     if let Some(ref default) = branch_set.default_output {
-        // Use tautology to represent else clause
-        let else_condition = BoolExpr::or(
-            BoolExpr::var(&variables[0]),
-            BoolExpr::negate(BoolExpr::var(&variables[0])),
-        );
-        simplified_conditions.push((else_condition, default.clone()));
+        // `true` is the else clause
+        simplified_conditions.push((BoolExpr::Const(true), default.clone()));
     }
 
     let simplified_count = simplified_conditions.len();
@@ -93,18 +573,233 @@ fn simplify_with_integer_vars(
     Ok(SimplificationResult {
         variables,
         simplified_conditions,
+        protected_branches,
+        original_branch_count: branch_set.branches.len(),
+        simplified_branch_count: simplified_count,
+        analysis,
+        weighted_default: None,
+        combinations_covered: Vec::new(),
+        total_combinations: 0,
+        unreferenced_variables: Vec::new(),
+    })
+}
+
+/// Simplify the unprotected branches between protected ones (boolean-only)
+///
+/// Protected branches are emitted verbatim at their original position. The unprotected
+/// branches between two protected ones form a segment that is minimized per output;
+/// minterms claimed by any earlier branch can never reach a later segment, so they are
+/// don't-cares there. Branches that are redundant with the default claim nothing: their
+/// minterms go to the default at the end instead of growing an early segment.
+fn simplify_around_protected(
+    branch_set: &BranchSet,
+    table: &TruthTable,
+    analysis: SimplificationAnalysis,
+) -> Result<SimplificationResult, String> {
+    let mut simplified_conditions = Vec::new();
+    let mut protected_branches = Vec::new();
+
+    let mut claimed: HashSet<u64> = HashSet::new();
+    let mut resolved: Vec<u64> = table.dont_cares.clone(); // don't-cares for the current segment
+    let mut segment: HashMap<String, Vec<u64>> = HashMap::new();
+    let sort_key = |output: &str| output_sort_key(branch_set, &analysis, output);
+
+    for (idx, branch) in branch_set.branches.iter().enumerate() {
+        if analysis.redundant_with_default.contains(&idx) {
+            continue;
+        }
+        let new_minterms: Vec<u64> = analysis.branch_coverage[idx]
+            .minterms_covered
+            .iter()
+            .map(|&m| m as u64)
+            .filter(|&m| claimed.insert(m))
+            .collect();
+
+        if branch.protected {
+            flush_segment(
+                table,
+                &mut segment,
+                &mut resolved,
+                &mut simplified_conditions,
+                &sort_key,
+            )?;
+            protected_branches.push((simplified_conditions.len(), idx));
+            simplified_conditions.push((branch.condition.clone(), branch.output.clone()));
+            resolved.extend(new_minterms);
+        } else {
+            let (_, display) = branch_set.output_identity(&branch.output);
+            segment.entry(display).or_default().extend(new_minterms);
+        }
+    }
+
+    // Whatever no branch claimed falls through to the default output
+    for group in table.output_groups.values() {
+        let unclaimed = group.minterms.iter().copied().filter(|m| !claimed.contains(m));
+        segment
+            .entry(group.display.clone())
+            .or_default()
+            .extend(unclaimed);
+    }
+    flush_segment(
+        table,
+        &mut segment,
+        &mut resolved,
+        &mut simplified_conditions,
+        &sort_key,
+    )?;
+
+    let simplified_count = simplified_conditions.len();
+
+    Ok(SimplificationResult {
+        variables: table.variables.clone(),
+        simplified_conditions,
+        protected_branches,
         original_branch_count: branch_set.branches.len(),
         simplified_branch_count: simplified_count,
         analysis,
+        weighted_default: None,
+        combinations_covered: Vec::new(),
+        total_combinations: 0,
+        unreferenced_variables: Vec::new(),
     })
 }
 
+/// Minimize every output group of a segment, then mark its minterms as resolved
+fn flush_segment(
+    table: &TruthTable,
+    segment: &mut HashMap<String, Vec<u64>>,
+    resolved: &mut Vec<u64>,
+    simplified_conditions: &mut Vec<(BoolExpr, String)>,
+    sort_key: &impl Fn(&str) -> (bool, usize),
+) -> Result<(), String> {
+    let mut groups: Vec<(String, Vec<u64>)> = segment
+        .drain()
+        .filter(|(_, minterms)| !minterms.is_empty())
+        .collect();
+
+    groups.sort_by(|a, b| sort_key(&a.0).cmp(&sort_key(&b.0)).then(a.0.cmp(&b.0)));
+
+    for (output, minterms) in &groups {
+        let minimized_expr = minimize_for_output(table, minterms, resolved)?;
+        simplified_conditions.push((minimized_expr, output.clone()));
+    }
+    for (_, minterms) in groups {
+        resolved.extend(minterms);
+    }
+    Ok(())
+}
+
+/// Reorder simplified branches by descending output weight (see [`BranchSet::output_weight`])
+///
+/// The default output stays last and unweighted outputs keep their order after the weighted
+/// ones. Without a default, but with every input covered, the heaviest output becomes the
+/// else branch so its (most frequent) case is reached without evaluating a condition; that
+/// output is returned. Does nothing when no branch has a weight.
+fn apply_weights(
+    branch_set: &BranchSet,
+    analysis: &SimplificationAnalysis,
+    simplified_conditions: &mut Vec<(BoolExpr, String)>,
+) -> Option<String> {
+    if branch_set.branches.iter().all(|b| b.weight.is_none()) {
+        return None;
+    }
+    let is_default = |output: &str| {
+        branch_set
+            .default_output
+            .as_deref()
+            .is_some_and(|default| branch_set.output_key(default) == branch_set.output_key(output))
+    };
+    let weight = |output: &str| {
+        branch_set
+            .output_weight(output)
+            .unwrap_or(f64::NEG_INFINITY)
+    };
+    simplified_conditions.sort_by(|a, b| {
+        is_default(&a.1)
+            .cmp(&is_default(&b.1))
+            .then(weight(&b.1).total_cmp(&weight(&a.1)))
+    });
+
+    let total_coverage =
+        branch_set.default_output.is_none() && analysis.uncovered_minterms.is_empty();
+    if !total_coverage || simplified_conditions.len() < 2 {
+        return None;
+    }
+    // Output groups are disjoint when every input is covered, so any of them can be the else
+    branch_set.output_weight(&simplified_conditions[0].1)?;
+    let (_, heaviest) = simplified_conditions.remove(0);
+    simplified_conditions.push((BoolExpr::Const(true), heaviest.clone()));
+    Some(heaviest)
+}
+
+/// Sort key for an output group: `(is_default, position)`
+///
+/// With `BranchOrder::SourceOrder` the position is the smallest index of an original branch
+/// that contributed minterms to this output; with `Alphabetical` it is constant so the
+/// output string alone decides. The default output always sorts last.
+fn output_sort_key(
+    branch_set: &BranchSet,
+    analysis: &SimplificationAnalysis,
+    output: &str,
+) -> (bool, usize) {
+    let key = branch_set.output_key(output);
+    let is_default = branch_set
+        .default_output
+        .as_deref()
+        .is_some_and(|default| branch_set.output_key(default) == key);
+    let position = match branch_set.branch_order {
+        BranchOrder::Alphabetical => 0,
+        BranchOrder::SourceOrder => analysis
+            .branch_coverage
+            .iter()
+            .find(|bc| {
+                bc.coverage_count > 0
+                    && branch_set.output_key(&branch_set.branches[bc.branch_index].output) == key
+            })
+            .map_or(usize::MAX, |bc| bc.branch_index),
+    };
+    (is_default, position)
+}
+
 /// Apply QM minimization for a single output value
 fn minimize_for_output(
     table: &TruthTable,
     minterms: &[u64],
     dont_cares: &[u64],
 ) -> Result<BoolExpr, String> {
+    sop_condition(
+        &solve_for_output(table, minterms, dont_cares),
+        &table.variables,
+    )
+}
+
+/// Condition of a minimized SOP expression over `variables`
+fn sop_condition(minimized_expression: &str, variables: &[String]) -> Result<BoolExpr, String> {
+    // Convert minimal cover to BoolExpr
+    if minimized_expression == "0" {
+        return Err("Contradiction: no valid conditions".to_string());
+    }
+
+    if minimized_expression == "1" {
+        // Tautology - always true
+        return Ok(BoolExpr::Const(true));
+    }
+
+    parse_qm_result(minimized_expression, variables)
+}
+
+/// Run QM for a single output value and return the minimized SOP expression
+///
+/// The solver lists the terms in no particular order; they are sorted so the same branch
+/// set always gives the same conditions (and generated code).
+fn solve_for_output(table: &TruthTable, minterms: &[u64], dont_cares: &[u64]) -> String {
+    let expression = solve_unordered(table, minterms, dont_cares);
+    let mut terms: Vec<&str> = expression.split(" + ").collect();
+    terms.sort_unstable();
+    terms.join(" + ")
+}
+
+fn solve_unordered(table: &TruthTable, minterms: &[u64], dont_cares: &[u64]) -> String {
     let var_count = table.variable_count();
 
     // Choose encoding based on variable count to avoid unnecessary conversions
@@ -116,66 +811,21 @@ fn minimize_for_output(
         solver.set_minterms(minterms.iter().map(|&x| x as u32).collect());
         solver.set_dont_cares(dont_cares.iter().map(|&x| x as u32).collect());
 
-        let result = solver.solve();
-
-        // Convert minimal cover to BoolExpr
-        if result.minimized_expression == "0" {
-            return Err("Contradiction: no valid conditions".to_string());
-        }
-
-        if result.minimized_expression == "1" {
-            // Tautology - always true
-            return Ok(BoolExpr::or(
-                BoolExpr::var(&table.variables[0]),
-                BoolExpr::negate(BoolExpr::var(&table.variables[0])),
-            ));
-        }
-
-        parse_qm_result(&result.minimized_expression, &table.variables)
+        solver.solve().minimized_expression
     } else if var_count <= 32 {
         let mut solver = QMSolver::<Enc32>::new_with_variable_names(var_count, table.variables.clone());
 
         solver.set_minterms(minterms.to_vec());
         solver.set_dont_cares(dont_cares.to_vec());
 
-        let result = solver.solve();
-
-        // Convert minimal cover to BoolExpr
-        if result.minimized_expression == "0" {
-            return Err("Contradiction: no valid conditions".to_string());
-        }
-
-        if result.minimized_expression == "1" {
-            // Tautology - always true
-            return Ok(BoolExpr::or(
-                BoolExpr::var(&table.variables[0]),
-                BoolExpr::negate(BoolExpr::var(&table.variables[0])),
-            ));
-        }
-
-        parse_qm_result(&result.minimized_expression, &table.variables)
+        solver.solve().minimized_expression
     } else {
         let mut solver = QMSolver::<Enc64>::new_with_variable_names(var_count, table.variables.clone());
 
         solver.set_minterms(minterms.iter().map(|&x| x as u128).collect());
         solver.set_dont_cares(dont_cares.iter().map(|&x| x as u128).collect());
 
-        let result = solver.solve();
-
-        // Convert minimal cover to BoolExpr
-        if result.minimized_expression == "0" {
-            return Err("Contradiction: no valid conditions".to_string());
-        }
-
-        if result.minimized_expression == "1" {
-            // Tautology - always true
-            return Ok(BoolExpr::or(
-                BoolExpr::var(&table.variables[0]),
-                BoolExpr::negate(BoolExpr::var(&table.variables[0])),
-            ));
-        }
-
-        parse_qm_result(&result.minimized_expression, &table.variables)
+        solver.solve().minimized_expression
     }
 }
 
@@ -284,42 +934,99 @@ fn format_comparison(expr: &BoolExpr) -> String {
     }
 }
 
+/// Operator and constant spellings of a target language
+struct Spelling {
+    not: &'static str,
+    and: &'static str,
+    or: &'static str,
+    constants: [&'static str; 2],
+}
+
+const C_SPELLING: Spelling = Spelling {
+    not: "!",
+    and: " && ",
+    or: " || ",
+    constants: ["false", "true"],
+};
+
+const PYTHON_SPELLING: Spelling = Spelling {
+    not: "not ",
+    and: " and ",
+    or: " or ",
+    constants: ["False", "True"],
+};
+
 /// Format a BoolExpr as a human-readable string
+///
+/// Operands of `!` and `&&` other than variables, constants and negations are
+/// parenthesized, as are `||` operands of `||`. The walk uses an explicit stack, so it does
+/// not overflow on deeply nested conditions.
 pub fn format_bool_expr(expr: &BoolExpr) -> String {
-    match expr {
-        BoolExpr::Var(name) => name.clone(),
-        BoolExpr::Not(inner) => format!("!{}", format_bool_expr_with_parens(inner)),
-        BoolExpr::And(left, right) => format!(
-            "{} && {}",
-            format_bool_expr_with_parens(left),
-            format_bool_expr_with_parens(right)
-        ),
-        BoolExpr::Or(left, right) => {
-            format!("{} || {}", format_and_expr(left), format_and_expr(right))
-        }
-        // Comparison operators
-        _ => format_comparison(expr),
-    }
+    format_with(expr, &C_SPELLING)
 }
 
-fn format_bool_expr_with_parens(expr: &BoolExpr) -> String {
-    match expr {
-        BoolExpr::Var(_) | BoolExpr::Not(_) => format_bool_expr(expr),
-        _ => format!("({})", format_bool_expr(expr)),
-    }
+/// [`format_bool_expr`] with Python's `not`, `and`, `or`, `True` and `False`
+///
+/// Python ranks the operators as C does (`not` above `and` above `or`), so the same
+/// parentheses apply.
+pub fn format_bool_expr_python(expr: &BoolExpr) -> String {
+    format_with(expr, &PYTHON_SPELLING)
 }
 
-fn format_and_expr(expr: &BoolExpr) -> String {
-    match expr {
-        BoolExpr::Or(_, _) => format!("({})", format_bool_expr(expr)),
-        _ => format_bool_expr(expr),
+fn format_with(expr: &BoolExpr, spelling: &Spelling) -> String {
+    enum Piece<'a> {
+        Expr(&'a BoolExpr),
+        Text(&'static str),
+    }
+    /// Push `operand` to be written next, in parentheses when `parens`
+    fn push_operand<'a>(stack: &mut Vec<Piece<'a>>, operand: &'a BoolExpr, parens: bool) {
+        if parens {
+            stack.extend([Piece::Text(")"), Piece::Expr(operand), Piece::Text("(")]);
+        } else {
+            stack.push(Piece::Expr(operand));
+        }
+    }
+    fn compound(expr: &BoolExpr) -> bool {
+        !matches!(
+            expr,
+            BoolExpr::Const(_) | BoolExpr::Var(_) | BoolExpr::Not(_)
+        )
+    }
+
+    let mut text = String::new();
+    let mut stack = vec![Piece::Expr(expr)];
+    while let Some(piece) = stack.pop() {
+        match piece {
+            Piece::Text(t) => text.push_str(t),
+            Piece::Expr(expr) => match expr {
+                BoolExpr::Const(value) => text.push_str(spelling.constants[*value as usize]),
+                BoolExpr::Var(name) => text.push_str(name),
+                BoolExpr::Not(inner) => {
+                    text.push_str(spelling.not);
+                    push_operand(&mut stack, inner, compound(inner));
+                }
+                BoolExpr::And(left, right) => {
+                    push_operand(&mut stack, right, compound(right));
+                    stack.push(Piece::Text(spelling.and));
+                    push_operand(&mut stack, left, compound(left));
+                }
+                BoolExpr::Or(left, right) => {
+                    push_operand(&mut stack, right, matches!(**right, BoolExpr::Or(..)));
+                    stack.push(Piece::Text(spelling.or));
+                    push_operand(&mut stack, left, matches!(**left, BoolExpr::Or(..)));
+                }
+                // Comparison operators
+                _ => text.push_str(&format_comparison(expr)),
+            },
+        }
     }
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::simplify::types::BranchSet;
+    use crate::simplify::types::{BranchSet, DEFAULT_MAX_CONDITION_OPERATORS, OutputComparison};
 
     #[test]
     fn test_simplify_basic_and() {
@@ -353,6 +1060,527 @@ mod tests {
         // The simplified condition should be simpler (no compound AND)
         // It should be a single variable (either a or b depending on QM output)
         assert!(!formatted.contains("&&"), "Should not contain compound AND");
+
+        // Source order: "1" first, the default "0" last
+        assert_eq!(result.simplified_conditions[0].1, "1");
+        assert_eq!(result.simplified_conditions[1].1, "0");
+    }
+
+    #[test]
+    fn test_outputs_equal_modulo_whitespace_are_merged() {
+        // if a && b { handleA() } elif a && !b { handleA( ) } else { other() }
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")),
+            "handleA(x, y)",
+        );
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::negate(BoolExpr::var("b"))),
+            "handleA(x,\n    y) ",
+        );
+        branch_set.set_default("other()");
+
+        // Compared exactly, the two spellings stay separate outputs
+        let exact = simplify_branches(&branch_set).unwrap();
+        assert_eq!(exact.simplified_branch_count, 3);
+
+        branch_set.set_output_comparison(OutputComparison::IgnoreWhitespace);
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.simplified_branch_count, 2);
+        // The first-seen spelling is emitted
+        assert_eq!(
+            result.simplified_conditions[0],
+            (BoolExpr::var("a"), "handleA(x, y)".to_string())
+        );
+        assert_eq!(result.simplified_conditions[1].1, "other()");
+
+        // Whitespace between tokens is not dropped
+        let key = |output| OutputComparison::IgnoreWhitespace.key(output);
+        assert_eq!(key(" return  x\t"), key("return x"));
+        assert_ne!(key("return x"), key("returnx"));
+    }
+
+    #[test]
+    fn test_source_order_preserved() {
+        // if a && b { return "zeta" }
+        // elif a { return "alpha" }
+        // elif b { return "mid" }
+        // else { return "none" }
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")),
+            "zeta",
+        );
+        branch_set.add_branch(BoolExpr::var("a"), "alpha");
+        branch_set.add_branch(BoolExpr::var("b"), "mid");
+        branch_set.set_default("none");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        let outputs: Vec<&str> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, out)| out.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["zeta", "alpha", "mid", "none"]);
+
+        // Alphabetical on request, default still last
+        branch_set.set_branch_order(BranchOrder::Alphabetical);
+        let result = simplify_branches(&branch_set).unwrap();
+        let outputs: Vec<&str> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, out)| out.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["alpha", "mid", "zeta", "none"]);
+    }
+
+    #[test]
+    fn test_weights_order_branches() {
+        // Same branches as above; "mid" is the hottest, the default stays last
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")),
+            "zeta",
+        );
+        branch_set.add_branch(BoolExpr::var("a"), "alpha");
+        branch_set.add_branch(BoolExpr::var("b"), "mid");
+        branch_set.set_default("none");
+        branch_set.set_branch_weight(1, 5.0);
+        branch_set.set_branch_weight(2, 20.0);
+
+        let result = simplify_branches(&branch_set).unwrap();
+        let outputs: Vec<&str> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, out)| out.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["mid", "alpha", "zeta", "none"]);
+        // An explicit default is never replaced
+        assert_eq!(result.weighted_default, None);
+    }
+
+    #[test]
+    fn test_earlier_outputs_are_dont_cares() {
+        // if a && b { zeta } elif a { alpha } elif b { mid } else { none }: `a` also holds on
+        // the input of "zeta", which is decided before "alpha" is tested
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(a.clone(), b.clone()), "zeta");
+        branch_set.add_branch(a.clone(), "alpha");
+        branch_set.add_branch(b.clone(), "mid");
+        branch_set.set_default("none");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(
+            result.simplified_conditions,
+            vec![
+                (BoolExpr::and(a.clone(), b.clone()), "zeta".to_string()),
+                (a, "alpha".to_string()),
+                (b, "mid".to_string()),
+                (BoolExpr::Const(true), "none".to_string()),
+            ]
+        );
+        // The overlapping conditions still decide every input as the original branches do
+        assert_eq!(result.combinations_covered, vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_first_match_mismatches() {
+        // Output "1" on a && b, "0" elsewhere
+        let table = TruthTable::from_function(vec!["a".to_string(), "b".to_string()], &[3], &[]);
+        let chain = |conditions: Vec<(&str, &'static str)>| -> Vec<(SopExpression, &'static str)> {
+            conditions
+                .into_iter()
+                .map(|(sop, output)| (parse_sop(sop, &table.variables).unwrap(), output))
+                .collect()
+        };
+
+        let exact = chain(vec![("ab", "1"), ("1", "0")]);
+        assert!(first_match_mismatches(&table, &exact).is_empty());
+
+        // "0" on `a` comes first and takes the input of "1"
+        let stolen = chain(vec![("a", "0"), ("ab", "1"), ("1", "0")]);
+        assert_eq!(first_match_mismatches(&table, &stolen), vec![0]);
+
+        // Nothing holds on the inputs with !a: blamed on "0"
+        let missing = chain(vec![("ab", "1"), ("a", "0")]);
+        assert_eq!(first_match_mismatches(&table, &missing), vec![1]);
+    }
+
+    #[test]
+    fn test_integer_conditions_recovered() {
+        // x in 0..=5: x == 2 || x == 3 || x == 4 merges into a single range
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_int("x", 0, 5);
+        branch_set.add_branch(BoolExpr::equals("x", 2), "mid");
+        branch_set.add_branch(BoolExpr::equals("x", 3), "mid");
+        branch_set.add_branch(BoolExpr::equals("x", 4), "mid");
+        branch_set.set_default("other");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.variables, vec!["x"]);
+        assert_eq!(result.simplified_conditions.len(), 2);
+
+        let (condition, output) = &result.simplified_conditions[0];
+        assert_eq!(output, "mid");
+        assert_eq!(format_bool_expr(condition), "(x >= 2) && (x <= 4)");
+        assert_eq!(result.simplified_conditions[1].1, "other");
+    }
+
+    #[test]
+    fn test_integer_conditions_use_earlier_branches() {
+        // x in 0..=7: "mid" only has to hold on 3 and 4 once "low" has taken 0..=2
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_int("x", 0, 7);
+        branch_set.add_branch(BoolExpr::less_than("x", 3), "low");
+        branch_set.add_branch(
+            BoolExpr::and(
+                BoolExpr::negate(BoolExpr::less_than("x", 3)),
+                BoolExpr::less_than("x", 5),
+            ),
+            "mid",
+        );
+        branch_set.set_default("high");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        let conditions: Vec<(String, &str)> = result
+            .simplified_conditions
+            .iter()
+            .map(|(condition, output)| (format_bool_expr(condition), output.as_str()))
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                ("x < 3".to_string(), "low"),
+                ("x < 5".to_string(), "mid"),
+                ("true".to_string(), "high"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integer_weights_order_branches() {
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_int("x", 0, 3);
+        branch_set.add_branch(BoolExpr::equals("x", 0), "zero");
+        branch_set.add_branch(BoolExpr::equals("x", 1), "one");
+        branch_set.set_default("other");
+        branch_set.set_branch_weight(1, 10.0);
+
+        let result = simplify_branches(&branch_set).unwrap();
+        let outputs: Vec<&str> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, out)| out.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["one", "zero", "other"]);
+
+        // Protected branches keep their original order: weights cannot be honoured
+        branch_set.add_protected_branch(BoolExpr::equals("x", 2), "log");
+        let error = simplify_branches(&branch_set).unwrap_err();
+        assert!(
+            error.starts_with("Branch weights are not supported"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_integer_default_is_constant_true() {
+        // Protected integer branches keep their conditions; the else clause is plain `true`
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_int("x", 0, 5);
+        branch_set.add_protected_branch(BoolExpr::equals("x", 2), "log");
+        branch_set.add_branch(BoolExpr::less_than("x", 2), "low");
+        branch_set.set_default("other");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        let (condition, output) = result.simplified_conditions.last().unwrap();
+        assert_eq!(output, "other");
+        assert_eq!(*condition, BoolExpr::Const(true));
+        assert_eq!(format_bool_expr(condition), "true");
+    }
+
+    #[test]
+    fn test_protected_dead_branch_is_kept() {
+        // if a && b { return "1" }
+        // elif a && !b { return "1" }
+        // elif a { return "log" }  // Dead, but protected
+        // else { return "0" }
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::negate(BoolExpr::var("b"))),
+            "1",
+        );
+        branch_set.add_protected_branch(BoolExpr::var("a"), "log");
+        branch_set.set_default("0");
+
+        let result = simplify_branches(&branch_set).unwrap();
+
+        // Still reported as dead, for information
+        assert_eq!(result.analysis.dead_branches.len(), 1);
+        assert!(result.analysis.dead_branches[0].protected);
+
+        // "1" branches merge into `a`, the protected branch stays verbatim in second place
+        let outputs: Vec<&str> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, out)| out.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["1", "log", "0"]);
+        assert_eq!(result.simplified_conditions[0].0, BoolExpr::var("a"));
+        assert_eq!(result.simplified_conditions[1].0, BoolExpr::var("a"));
+        assert_eq!(result.protected_branches, vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_branch_returning_default_folds_into_it() {
+        // if a && !b { return "0" }  // The default already returns "0" here
+        // elif b && c { return "log" }  // Protected
+        // elif b { return "1" }
+        // else { return "0" }
+        let (a, b, c) = (BoolExpr::var("a"), BoolExpr::var("b"), BoolExpr::var("c"));
+        let branch_set = |protected: BoolExpr| {
+            let mut branch_set = BranchSet::new();
+            branch_set.add_branch(BoolExpr::and(a.clone(), BoolExpr::negate(b.clone())), "0");
+            branch_set.add_protected_branch(protected, "log");
+            branch_set.add_branch(b.clone(), "1");
+            branch_set.set_default("0");
+            branch_set
+        };
+        let outputs = |result: &SimplificationResult| -> Vec<String> {
+            result
+                .simplified_conditions
+                .iter()
+                .map(|(_, out)| out.clone())
+                .collect()
+        };
+
+        // No segment of its own before the protected branch
+        let result = simplify_branches(&branch_set(BoolExpr::and(b.clone(), c.clone()))).unwrap();
+        assert_eq!(result.analysis.redundant_with_default, vec![0]);
+        assert_eq!(outputs(&result), vec!["log", "1", "0"]);
+
+        // With the protected branch on `c`, a && !b && c would start to log: kept
+        let result = simplify_branches(&branch_set(c.clone())).unwrap();
+        assert!(result.analysis.redundant_with_default.is_empty());
+        assert_eq!(outputs(&result), vec!["0", "log", "1", "0"]);
+        assert_eq!(
+            result.simplified_conditions[0].0,
+            BoolExpr::and(a.clone(), BoolExpr::negate(b.clone()))
+        );
+    }
+
+    #[test]
+    fn test_scattered_output_keeps_original_branch() {
+        // if (a ^ b) ^ (c ^ d) { return "1" } else { return "0" }: the minimized form lists
+        // all eight odd-parity minterms
+        let xor = |x: BoolExpr, y: BoolExpr| {
+            BoolExpr::or(
+                BoolExpr::and(x.clone(), BoolExpr::negate(y.clone())),
+                BoolExpr::and(BoolExpr::negate(x), y),
+            )
+        };
+        let parity = xor(
+            xor(BoolExpr::var("a"), BoolExpr::var("b")),
+            xor(BoolExpr::var("c"), BoolExpr::var("d")),
+        );
+        assert_eq!(parity.operator_count(), 25);
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(parity.clone(), "1");
+        branch_set.set_default("0");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(
+            result.simplified_conditions,
+            vec![
+                (parity, "1".to_string()),
+                (BoolExpr::Const(true), "0".to_string())
+            ]
+        );
+        assert!(result.protected_branches.is_empty());
+        assert_eq!(
+            result.analysis.notes,
+            vec!["output '1' left unsimplified: minimized form larger"]
+        );
+
+        // A structured on-set is simplified as usual ...
+        let mut branch_set = BranchSet::new();
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+        branch_set.add_branch(BoolExpr::and(a.clone(), b.clone()), "1");
+        branch_set.add_branch(BoolExpr::and(a.clone(), BoolExpr::negate(b.clone())), "1");
+        branch_set.set_default("0");
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.simplified_conditions[0].0, a);
+        assert!(result.analysis.notes.is_empty());
+
+        // ... unless the limit is lower than even the minimized form
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(a.clone(), b.clone()), "1");
+        branch_set.set_default("0");
+        branch_set.set_max_condition_operators(0);
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.simplified_conditions[0].0, BoolExpr::and(a, b));
+        assert_eq!(result.analysis.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_oversized_output_with_integers_keeps_original_branch() {
+        // The bit-blasted x == 1 && flag has an operator, more than the limit allows
+        let build = |max: usize| {
+            BranchSet::builder()
+                .int_var("x", 0, 3)
+                .bool_var("flag")
+                .branch("x == 1 && flag", "1")
+                .default("0")
+                .max_condition_operators(max)
+                .build()
+                .unwrap()
+        };
+        let result = simplify_branches(&build(DEFAULT_MAX_CONDITION_OPERATORS)).unwrap();
+        assert!(result.analysis.notes.is_empty());
+
+        let branch_set = build(0);
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(
+            result.analysis.notes,
+            vec!["output '1' left unsimplified: minimized form larger"]
+        );
+        assert_eq!(
+            result.simplified_conditions[0],
+            (branch_set.branches[0].condition.clone(), "1".to_string())
+        );
+        assert!(result.protected_branches.is_empty());
+    }
+
+    #[test]
+    fn test_oversized_output_with_protected_integer_branches() {
+        // The original conditions are kept around a protected branch; the limit still
+        // reports the output whose condition is larger
+        let branch_set = BranchSet::builder()
+            .int_var("x", 0, 3)
+            .bool_var("flag")
+            .protected_branch("x == 0", "log()")
+            .branch("flag && x >= 2", "1")
+            .default("0")
+            .max_condition_operators(0)
+            .build()
+            .unwrap();
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(
+            result.analysis.notes,
+            vec!["output '1' left unsimplified: minimized form larger"]
+        );
+        assert_eq!(result.protected_branches, vec![(0, 0)]);
+        let conditions: Vec<&BoolExpr> = result
+            .simplified_conditions
+            .iter()
+            .map(|(condition, _)| condition)
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                &branch_set.branches[0].condition,
+                &branch_set.branches[1].condition,
+                &BoolExpr::Const(true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreferenced_variables() {
+        // if a && b { 1 } elif a && !b { 1 } else { 0 }: b never matters, c is declared only
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_variable("c", VariableType::Boolean);
+        branch_set.add_branch(BoolExpr::and(a.clone(), b.clone()), "1");
+        branch_set.add_branch(BoolExpr::and(a, BoolExpr::negate(b)), "1");
+        branch_set.set_default("0");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.unreferenced_variables, vec!["b", "c"]);
+        assert!(
+            crate::simplify::format_result(&result)
+                .contains("Input 'b' does not affect the outcome and can be removed")
+        );
+
+        // Both inputs decide
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branch_set.set_default("0");
+        let result = simplify_branches(&branch_set).unwrap();
+        assert!(result.unreferenced_variables.is_empty());
+
+        // No default: c only decides the gap a && b && !c, which no condition has to read
+        let (a, b, c) = (BoolExpr::var("a"), BoolExpr::var("b"), BoolExpr::var("c"));
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(a.clone(), BoolExpr::negate(b.clone())), "1");
+        branch_set.add_branch(BoolExpr::and(BoolExpr::and(a.clone(), b), c), "2");
+        branch_set.add_branch(BoolExpr::negate(a), "0");
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.analysis.uncovered_minterms.len(), 1);
+        assert!(result.unreferenced_variables.is_empty());
+    }
+
+    #[test]
+    fn test_combinations_covered_boolean() {
+        // if a && b { 1 } elif c { 2 } else { 0 }: 2, 3 and 3 of the 8 inputs
+        let mut branch_set = BranchSet::new();
+        branch_set.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branch_set.add_branch(BoolExpr::var("c"), "2");
+        branch_set.set_default("0");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.total_combinations, 8);
+        assert_eq!(result.combinations_covered, vec![2, 3, 3]);
+        assert!(
+            crate::simplify::format_result(&result)
+                .contains("{ return 1; } (covers 2/8 input combinations)")
+        );
+    }
+
+    #[test]
+    fn test_combinations_covered_with_integers() {
+        // flag in {0, 1} and x in 0..=4: 10 inputs, of which flag && x >= 3 holds on 2
+        let mut branch_set = BranchSet::new();
+        branch_set
+            .variable_types
+            .insert("x".to_string(), VariableType::Integer { min: 0, max: 4 });
+        branch_set.add_branch(
+            BoolExpr::and(BoolExpr::var("flag"), BoolExpr::greater_or_equal("x", 3)),
+            "hi",
+        );
+        branch_set.add_branch(BoolExpr::equals("x", 0), "zero");
+        branch_set.set_default("lo");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.total_combinations, 10);
+        let covered: HashMap<&str, u64> = result
+            .simplified_conditions
+            .iter()
+            .map(|(_, output)| output.as_str())
+            .zip(result.combinations_covered.iter().copied())
+            .collect();
+        assert_eq!(covered["hi"], 2);
+        assert_eq!(covered["zero"], 2);
+        assert_eq!(covered["lo"], 6);
+    }
+
+    #[test]
+    fn test_combinations_covered_repeated_output() {
+        // Protected integer branches are kept as written, so "log" has two conditions
+        let mut branch_set = BranchSet::new();
+        branch_set.declare_int("x", 0, 5);
+        branch_set.add_protected_branch(BoolExpr::equals("x", 2), "log");
+        branch_set.add_branch(BoolExpr::less_than("x", 2), "low");
+        branch_set.add_branch(BoolExpr::equals("x", 3), "log");
+        branch_set.set_default("other");
+
+        let result = simplify_branches(&branch_set).unwrap();
+        assert_eq!(result.simplified_conditions.len(), 4);
+        assert_eq!(result.total_combinations, 6);
+        assert_eq!(result.combinations_covered, vec![1, 2, 1, 2]);
     }
 
     #[test]
@@ -404,4 +1632,22 @@ mod tests {
         let expr3 = BoolExpr::negate(BoolExpr::var("a"));
         assert_eq!(format_bool_expr(&expr3), "!a");
     }
+
+    #[test]
+    fn test_format_bool_expr_python() {
+        let expr = BoolExpr::or(
+            BoolExpr::and(
+                BoolExpr::negate(BoolExpr::var("a")),
+                BoolExpr::negate(BoolExpr::equals("x", 2)),
+            ),
+            BoolExpr::and(BoolExpr::var("b"), BoolExpr::constant(true)),
+        );
+        assert_eq!(format_bool_expr(&expr), "!a && !(x == 2) || b && true");
+        assert_eq!(
+            format_bool_expr_python(&expr),
+            "not a and not (x == 2) or b and True"
+        );
+        let nested = BoolExpr::negate(BoolExpr::or(BoolExpr::var("a"), BoolExpr::var("b")));
+        assert_eq!(format_bool_expr_python(&nested), "not (a or b)");
+    }
 }
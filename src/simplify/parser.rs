@@ -3,22 +3,45 @@
 use super::types::BoolExpr;
 
 /// Parse a simple Boolean expression string
-/// Supports: variables (a-z), &&, ||, !, parentheses
+/// Supports: variables (a-z), constants (true/false, 1/0), &&, ||, !, parentheses, and
+/// comparisons of a variable with an integer (==, !=, <, <=, >, >=). The Python spellings
+/// `and`, `or`, `not`, `True` and `False` are accepted too, so these are not variable names.
 ///
 /// Examples:
 /// - "a" → Var("a")
+/// - "true" → Const(true)
 /// - "!a" → Not(Var("a"))
 /// - "a && b" → And(Var("a"), Var("b"))
 /// - "a || b && c" → Or(Var("a"), And(Var("b"), Var("c")))
+/// - "x >= 2" → GreaterOrEqual("x", 2)
+/// - "not a and b" → And(Not(Var("a")), Var("b"))
 pub fn parse_bool_expr(input: &str) -> Result<BoolExpr, String> {
+    parse_bool_expr_with_max_depth(input, DEFAULT_MAX_DEPTH)
+}
+
+/// Default nesting limit of [`parse_bool_expr`]
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// [`parse_bool_expr`] with a limit on the nesting of parentheses and `!`
+///
+/// The operands of a flat `&&` / `||` chain do not nest: `a && b && c` has depth 0.
+/// Deeper input is rejected with an error instead of overflowing the stack.
+pub fn parse_bool_expr_with_max_depth(input: &str, max_depth: usize) -> Result<BoolExpr, String> {
     let tokens = tokenize(input)?;
-    let mut parser = Parser::new(tokens);
-    parser.parse_or()
+    let mut parser = Parser::new(tokens, max_depth);
+    let expr = parser.parse_or()?;
+    match parser.current() {
+        None => Ok(expr),
+        Some(token) => Err(format!("Unexpected token: {:?}", token)),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 enum Token {
     Var(String),
+    Const(bool),
+    Int(i32),
+    Cmp(&'static str),
     And,
     Or,
     Not,
@@ -44,8 +67,35 @@ fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                 chars.next();
             }
             '!' => {
-                tokens.push(Token::Not);
                 chars.next();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push(Token::Cmp("!="));
+                } else {
+                    tokens.push(Token::Not);
+                }
+            }
+            '=' => {
+                chars.next();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push(Token::Cmp("=="));
+                } else {
+                    return Err("Expected '==', found single '='".to_string());
+                }
+            }
+            '<' | '>' => {
+                chars.next();
+                let op = match (ch, chars.peek() == Some(&'=')) {
+                    ('<', true) => "<=",
+                    ('<', false) => "<",
+                    (_, true) => ">=",
+                    (_, false) => ">",
+                };
+                if op.len() == 2 {
+                    chars.next();
+                }
+                tokens.push(Token::Cmp(op));
             }
             '&' => {
                 chars.next();
@@ -75,7 +125,38 @@ fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                         break;
                     }
                 }
-                tokens.push(Token::Var(var_name));
+                match var_name.as_str() {
+                    "true" | "True" => tokens.push(Token::Const(true)),
+                    "false" | "False" => tokens.push(Token::Const(false)),
+                    "and" => tokens.push(Token::And),
+                    "or" => tokens.push(Token::Or),
+                    "not" => tokens.push(Token::Not),
+                    _ => tokens.push(Token::Var(var_name)),
+                }
+            }
+            '0'..='9' | '-' => {
+                let mut literal = String::new();
+                if ch == '-' {
+                    literal.push(ch);
+                    chars.next();
+                }
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_alphanumeric() || ch == '_' {
+                        literal.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match literal.as_str() {
+                    "1" => tokens.push(Token::Const(true)),
+                    "0" => tokens.push(Token::Const(false)),
+                    _ => match literal.parse::<i32>() {
+                        // Only meaningful as the right-hand side of a comparison
+                        Ok(value) => tokens.push(Token::Int(value)),
+                        Err(_) => return Err(format!("Unexpected literal: '{}'", literal)),
+                    },
+                }
             }
             _ => {
                 return Err(format!("Unexpected character: '{}'", ch));
@@ -89,11 +170,30 @@ fn tokenize(input: &str) -> Result<Vec<Token>, String> {
 struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl Parser {
-    fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0 }
+    fn new(tokens: Vec<Token>, max_depth: usize) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            depth: 0,
+            max_depth,
+        }
+    }
+
+    /// Enter one level of `!` or parentheses
+    fn descend(&mut self) -> Result<(), String> {
+        if self.depth >= self.max_depth {
+            return Err(format!(
+                "Expression nested deeper than {} levels",
+                self.max_depth
+            ));
+        }
+        self.depth += 1;
+        Ok(())
     }
 
     fn current(&self) -> Option<&Token> {
@@ -131,7 +231,9 @@ impl Parser {
     fn parse_not(&mut self) -> Result<BoolExpr, String> {
         if let Some(Token::Not) = self.current() {
             self.advance();
+            self.descend()?;
             let expr = self.parse_not()?;
+            self.depth -= 1;
             Ok(BoolExpr::negate(expr))
         } else {
             self.parse_primary()
@@ -141,13 +243,26 @@ impl Parser {
     fn parse_primary(&mut self) -> Result<BoolExpr, String> {
         match self.current() {
             Some(Token::Var(name)) => {
-                let expr = BoolExpr::var(name);
+                let name = name.clone();
+                self.advance();
+                match self.current() {
+                    Some(&Token::Cmp(op)) => {
+                        self.advance();
+                        self.parse_comparison(&name, op)
+                    }
+                    _ => Ok(BoolExpr::Var(name)),
+                }
+            }
+            Some(Token::Const(value)) => {
+                let expr = BoolExpr::constant(*value);
                 self.advance();
                 Ok(expr)
             }
             Some(Token::LParen) => {
                 self.advance();
+                self.descend()?;
                 let expr = self.parse_or()?;
+                self.depth -= 1;
                 if let Some(Token::RParen) = self.current() {
                     self.advance();
                     Ok(expr)
@@ -159,6 +274,24 @@ impl Parser {
             None => Err("Unexpected end of input".to_string()),
         }
     }
+
+    /// Right-hand side of `name op value`; `0` and `1` arrive as constants
+    fn parse_comparison(&mut self, name: &str, op: &str) -> Result<BoolExpr, String> {
+        let value = match self.current() {
+            Some(&Token::Int(value)) => value,
+            Some(&Token::Const(value)) => value as i32,
+            _ => return Err(format!("Expected an integer after '{} {}'", name, op)),
+        };
+        self.advance();
+        Ok(match op {
+            "==" => BoolExpr::equals(name, value),
+            "!=" => BoolExpr::not_equals(name, value),
+            "<" => BoolExpr::less_than(name, value),
+            "<=" => BoolExpr::less_or_equal(name, value),
+            ">" => BoolExpr::greater_than(name, value),
+            _ => BoolExpr::greater_or_equal(name, value),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +348,108 @@ mod tests {
         let expr = parse_bool_expr("!!a").unwrap();
         assert_eq!(expr, BoolExpr::negate(BoolExpr::negate(BoolExpr::var("a"))));
     }
+
+    #[test]
+    fn test_parse_constants() {
+        assert_eq!(parse_bool_expr("true").unwrap(), BoolExpr::constant(true));
+        assert_eq!(parse_bool_expr("0").unwrap(), BoolExpr::constant(false));
+
+        let expr = parse_bool_expr("a && false").unwrap();
+        assert_eq!(
+            expr,
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::constant(false))
+        );
+
+        // Identifiers that merely start with a keyword are still variables
+        assert_eq!(
+            parse_bool_expr("trueish").unwrap(),
+            BoolExpr::var("trueish")
+        );
+        assert!(parse_bool_expr("2").is_err());
+    }
+
+    #[test]
+    fn test_parse_comparisons() {
+        let expr = parse_bool_expr("x >= 2 && x <= 5").unwrap();
+        assert_eq!(
+            expr,
+            BoolExpr::and(
+                BoolExpr::greater_or_equal("x", 2),
+                BoolExpr::less_or_equal("x", 5)
+            )
+        );
+        assert_eq!(
+            parse_bool_expr("x != 0").unwrap(),
+            BoolExpr::not_equals("x", 0)
+        );
+        assert_eq!(
+            parse_bool_expr("x<-3").unwrap(),
+            BoolExpr::less_than("x", -3)
+        );
+        assert_eq!(parse_bool_expr("x == 1").unwrap(), BoolExpr::equals("x", 1));
+
+        assert!(parse_bool_expr("x = 2").is_err());
+        assert!(parse_bool_expr("x < y").is_err());
+    }
+
+    #[test]
+    fn test_parse_python_keywords() {
+        assert_eq!(
+            parse_bool_expr("not a and b or c").unwrap(),
+            parse_bool_expr("!a && b || c").unwrap()
+        );
+        assert_eq!(
+            parse_bool_expr("(a or False) and not (b or True)").unwrap(),
+            parse_bool_expr("(a || false) && !(b || true)").unwrap()
+        );
+        // Keywords only match whole words
+        assert_eq!(
+            parse_bool_expr("android || order").unwrap(),
+            BoolExpr::or(BoolExpr::var("android"), BoolExpr::var("order"))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_trailing_tokens() {
+        assert!(parse_bool_expr("a b").is_err());
+        assert!(parse_bool_expr("(a || b) c").is_err());
+        assert!(parse_bool_expr("a)").is_err());
+    }
+
+    #[test]
+    fn test_parse_depth_limit() {
+        // Regression inputs from fuzzing: used to overflow the stack
+        let parens = format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(
+            parse_bool_expr(&parens)
+                .unwrap_err()
+                .contains("deeper than 256")
+        );
+        let nots = format!("{}a", "!".repeat(100_000));
+        assert!(parse_bool_expr(&nots).is_err());
+
+        // Only parentheses and `!` nest; the operands of a flat chain do not
+        let chain = format!("{}a", "a && ".repeat(1000));
+        assert!(parse_bool_expr_with_max_depth(&chain, 0).is_ok());
+        let chain = format!("{}a", "a || b && ".repeat(1000));
+        assert!(parse_bool_expr_with_max_depth(&chain, 0).is_ok());
+        assert!(parse_bool_expr_with_max_depth("(a && b) || (c && d)", 1).is_ok());
+        assert!(parse_bool_expr_with_max_depth("a && (b || (c && d))", 1).is_err());
+
+        let nested = format!("{}a{}", "(".repeat(256), ")".repeat(256));
+        assert_eq!(parse_bool_expr(&nested).unwrap(), BoolExpr::var("a"));
+        assert!(parse_bool_expr_with_max_depth(&nested, 255).is_err());
+        assert!(parse_bool_expr_with_max_depth("!(!a)", 3).is_ok());
+        assert!(parse_bool_expr_with_max_depth("!(!a)", 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_huge_literals() {
+        assert!(parse_bool_expr("x < 99999999999999999999").is_err());
+        assert!(parse_bool_expr("x < -2147483649").is_err());
+        assert_eq!(
+            parse_bool_expr("x >= -2147483648").unwrap(),
+            BoolExpr::greater_or_equal("x", i32::MIN)
+        );
+    }
 }
@@ -1,10 +1,16 @@
 // Types for if-then-else simplification
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::optimizer::format_bool_expr;
+use super::parser::parse_bool_expr;
 
 /// A simple Boolean expression
 #[derive(Debug, Clone, PartialEq)]
 pub enum BoolExpr {
+    Const(bool),                       // true, false
     Var(String),                       // a, b, flag
     Not(Box<BoolExpr>),                // !a
     And(Box<BoolExpr>, Box<BoolExpr>), // a && b
@@ -20,6 +26,10 @@ pub enum BoolExpr {
 }
 
 impl BoolExpr {
+    pub fn constant(value: bool) -> Self {
+        BoolExpr::Const(value)
+    }
+
     pub fn var(name: &str) -> Self {
         BoolExpr::Var(name.to_string())
     }
@@ -60,13 +70,249 @@ impl BoolExpr {
     pub fn greater_or_equal(var: &str, value: i32) -> Self {
         BoolExpr::GreaterOrEqual(var.to_string(), value)
     }
+
+    /// Number of operators: `!`, `&&`, `||` and comparisons each count one
+    pub fn operator_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(expr) = stack.pop() {
+            match expr {
+                BoolExpr::Const(_) | BoolExpr::Var(_) => {}
+                BoolExpr::Not(inner) => {
+                    count += 1;
+                    stack.push(inner);
+                }
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
+                    count += 1;
+                    stack.push(left);
+                    stack.push(right);
+                }
+                _ => count += 1,
+            }
+        }
+        count
+    }
+
+    /// Nodes on the longest path from the root to a leaf: `a` has depth 1, `a && !b` depth 3
+    ///
+    /// Measured with an explicit stack, so any expression can be measured.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((expr, level)) = stack.pop() {
+            depth = depth.max(level);
+            match expr {
+                BoolExpr::Not(inner) => stack.push((inner, level + 1)),
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
+                    stack.push((left, level + 1));
+                    stack.push((right, level + 1));
+                }
+                _ => {}
+            }
+        }
+        depth
+    }
+
+    /// Does the expression compare a variable with an integer anywhere?
+    pub fn has_comparisons(&self) -> bool {
+        let mut stack = vec![self];
+        while let Some(expr) = stack.pop() {
+            match expr {
+                BoolExpr::Const(_) | BoolExpr::Var(_) => {}
+                BoolExpr::Not(inner) => stack.push(inner),
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                _ => return true,
+            }
+        }
+        false
+    }
+
+    /// Move the expression out, leaving `false` in its place
+    pub(crate) fn take(&mut self) -> BoolExpr {
+        std::mem::replace(self, BoolExpr::Const(false))
+    }
+
+    /// Equivalent expression with negations pushed inward
+    ///
+    /// A negated comparison becomes the complementary comparison (`!(x == 3)` is `x != 3`,
+    /// `!(x < 2)` is `x >= 2`), `!!a` becomes `a` and `!true` becomes `false`. A negated
+    /// `&&` or `||` is rewritten by De Morgan when that takes no more operators: `!(!a && x < 2)`
+    /// becomes `a || x >= 2`, but `!(a && b)` stays.
+    ///
+    /// Linear in the size of the expression: the operator counts of both polarities of
+    /// every `&&` and `||` are computed bottom-up first, then the chosen forms are built.
+    pub fn normalize(&self) -> BoolExpr {
+        let pushed = self.de_morgan_choices();
+
+        enum Step<'a> {
+            Visit(&'a BoolExpr, bool),
+            Not,
+            And,
+            Or,
+        }
+        let mut done: Vec<BoolExpr> = Vec::new();
+        let mut stack = vec![Step::Visit(self, false)];
+        while let Some(step) = stack.pop() {
+            let (expr, negated) = match step {
+                Step::Visit(expr, negated) => (expr, negated),
+                Step::Not => {
+                    let inner = done.pop().unwrap();
+                    done.push(BoolExpr::negate(inner));
+                    continue;
+                }
+                Step::And | Step::Or => {
+                    let right = done.pop().unwrap();
+                    let left = done.pop().unwrap();
+                    done.push(if matches!(step, Step::And) {
+                        BoolExpr::and(left, right)
+                    } else {
+                        BoolExpr::or(left, right)
+                    });
+                    continue;
+                }
+            };
+            match expr {
+                BoolExpr::Not(inner) => stack.push(Step::Visit(inner, !negated)),
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) => {
+                    let is_and = matches!(expr, BoolExpr::And(..));
+                    if !negated {
+                        stack.push(if is_and { Step::And } else { Step::Or });
+                    } else if pushed.contains(&(expr as *const BoolExpr)) {
+                        stack.push(if is_and { Step::Or } else { Step::And });
+                    } else {
+                        stack.push(Step::Not);
+                        stack.push(Step::Visit(expr, false));
+                        continue;
+                    }
+                    stack.push(Step::Visit(right, negated));
+                    stack.push(Step::Visit(left, negated));
+                }
+                leaf if negated => done.push(leaf.negated_leaf()),
+                leaf => done.push(leaf.clone()),
+            }
+        }
+        done.pop().unwrap()
+    }
+
+    /// The `&&` and `||` nodes whose negation [`normalize`](Self::normalize) rewrites by
+    /// De Morgan, found from the operator counts of both polarities of every node
+    fn de_morgan_choices(&self) -> HashSet<*const BoolExpr> {
+        let mut pushed = HashSet::new();
+        // Operator counts of the normalized node and of its normalized negation
+        let mut counts: Vec<(usize, usize)> = Vec::new();
+        let mut stack = vec![(self, false)];
+        while let Some((expr, expanded)) = stack.pop() {
+            match expr {
+                BoolExpr::Not(inner) if !expanded => {
+                    stack.push((expr, true));
+                    stack.push((inner, false));
+                }
+                BoolExpr::Not(_) => {
+                    let (positive, negated) = counts.pop().unwrap();
+                    counts.push((negated, positive));
+                }
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) if !expanded => {
+                    stack.push((expr, true));
+                    stack.push((right, false));
+                    stack.push((left, false));
+                }
+                BoolExpr::And(..) | BoolExpr::Or(..) => {
+                    let (right_positive, right_negated) = counts.pop().unwrap();
+                    let (left_positive, left_negated) = counts.pop().unwrap();
+                    let positive = left_positive + right_positive + 1;
+                    let by_de_morgan = left_negated + right_negated + 1;
+                    let negated = if by_de_morgan <= positive + 1 {
+                        pushed.insert(expr as *const BoolExpr);
+                        by_de_morgan
+                    } else {
+                        positive + 1
+                    };
+                    counts.push((positive, negated));
+                }
+                BoolExpr::Const(_) => counts.push((0, 0)),
+                BoolExpr::Var(_) => counts.push((0, 1)),
+                _ => counts.push((1, 1)),
+            }
+        }
+        pushed
+    }
+
+    /// `!self` for a constant, variable or comparison, with the negation applied
+    fn negated_leaf(&self) -> BoolExpr {
+        match self {
+            BoolExpr::Const(value) => BoolExpr::Const(!value),
+            BoolExpr::Equals(var, value) => BoolExpr::NotEquals(var.clone(), *value),
+            BoolExpr::NotEquals(var, value) => BoolExpr::Equals(var.clone(), *value),
+            BoolExpr::LessThan(var, value) => BoolExpr::GreaterOrEqual(var.clone(), *value),
+            BoolExpr::LessOrEqual(var, value) => BoolExpr::GreaterThan(var.clone(), *value),
+            BoolExpr::GreaterThan(var, value) => BoolExpr::LessOrEqual(var.clone(), *value),
+            BoolExpr::GreaterOrEqual(var, value) => BoolExpr::LessThan(var.clone(), *value),
+            _ => BoolExpr::negate(self.clone()),
+        }
+    }
+}
+
+/// Dropped with an explicit stack: the derived drop glue recurses once per level of nesting
+/// and overflows on machine-generated conditions
+impl Drop for BoolExpr {
+    fn drop(&mut self) {
+        /// Move the operands of `expr` that have operands themselves onto `stack`
+        fn take_operands(expr: &mut BoolExpr, stack: &mut Vec<BoolExpr>) {
+            let operands = match expr {
+                BoolExpr::Not(inner) => [Some(inner), None],
+                BoolExpr::And(left, right) | BoolExpr::Or(left, right) => [Some(left), Some(right)],
+                _ => return,
+            };
+            for operand in operands.into_iter().flatten() {
+                if matches!(
+                    **operand,
+                    BoolExpr::Not(_) | BoolExpr::And(..) | BoolExpr::Or(..)
+                ) {
+                    stack.push(operand.take());
+                }
+            }
+        }
+
+        let mut stack = Vec::new();
+        take_operands(self, &mut stack);
+        // Each popped expression is dropped with leaves as operands only
+        while let Some(mut expr) = stack.pop() {
+            take_operands(&mut expr, &mut stack);
+        }
+    }
 }
 
 /// A single branch in an if-then-else chain
-#[derive(Debug, Clone)]
+///
+/// Serialized with the condition as a string such as `"a && (x < 2)"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Branch {
+    #[serde(
+        serialize_with = "serialize_condition",
+        deserialize_with = "deserialize_condition"
+    )]
     pub condition: BoolExpr,
-    pub output: String, // For mini-MVP: just a string like "1" or "return true"
+    pub output: String,  // For mini-MVP: just a string like "1" or "return true"
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool, // Must survive simplification verbatim (logging, metrics, rollout)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>, // Expected execution frequency, used to order simplified branches
+}
+
+fn serialize_condition<S: Serializer>(
+    condition: &BoolExpr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_bool_expr(condition))
+}
+
+fn deserialize_condition<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BoolExpr, D::Error> {
+    let condition = String::deserialize(deserializer)?;
+    parse_bool_expr(&condition)
+        .map_err(|e| serde::de::Error::custom(format!("Failed to parse '{}': {}", condition, e)))
 }
 
 impl Branch {
@@ -74,25 +320,38 @@ impl Branch {
         Self {
             condition,
             output: output.to_string(),
+            protected: false,
+            weight: None,
+        }
+    }
+
+    /// Create a branch the optimizer must neither remove nor merge
+    pub fn protected(condition: BoolExpr, output: &str) -> Self {
+        Self {
+            protected: true,
+            ..Self::new(condition, output)
         }
     }
 }
 
 /// Variable type with domain information
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serialized as `{"type": "boolean"}` or `{"type": "integer", "min": 0, "max": 3}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum VariableType {
     Boolean,                        // True boolean variable
     Integer { min: i32, max: i32 }, // Integer with bounded domain
 }
 
 impl VariableType {
-    /// Get the number of bits needed to represent this variable
+    /// Get the number of bits needed to represent this variable (as `value - min`)
     pub fn bit_count(&self) -> usize {
         match self {
             VariableType::Boolean => 1,
             VariableType::Integer { min, max } => {
-                let range = (*max - *min + 1) as u32;
-                (32 - range.leading_zeros()) as usize
+                let largest_code = (*max - *min) as u32;
+                ((32 - largest_code.leading_zeros()) as usize).max(1)
             }
         }
     }
@@ -114,12 +373,229 @@ impl VariableType {
     }
 }
 
+/// Order in which simplified branches are emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchOrder {
+    #[default]
+    SourceOrder, // By the first original branch that contributed to each output
+    Alphabetical, // By output string, independent of the source layout
+}
+
+/// How branch outputs are compared when grouping them into one simplified branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputComparison {
+    #[default]
+    Exact, // Byte for byte
+    IgnoreWhitespace, // Runs of whitespace count as one space, leading and trailing as none
+    IgnoreCase,       // Case-insensitive (for languages where spelling case does not matter)
+}
+
+impl OutputComparison {
+    /// Grouping key of `output`: outputs with equal keys are the same output
+    pub fn key(self, output: &str) -> OutputKey {
+        OutputKey(match self {
+            OutputComparison::Exact => output.to_string(),
+            // Whitespace can separate tokens (`return x`), so it is collapsed, not removed
+            OutputComparison::IgnoreWhitespace => {
+                output.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            OutputComparison::IgnoreCase => output.to_lowercase(),
+        })
+    }
+}
+
+/// Grouping key of an output, see [`OutputComparison::key`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OutputKey(String);
+
+impl OutputKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The minterms of one output, and the spelling emitted for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputGroup {
+    pub display: String,    // First-seen original text of the output
+    pub minterms: Vec<u64>, // Minterms that produce this output
+}
+
+impl OutputGroup {
+    pub fn new(display: &str) -> Self {
+        Self {
+            display: display.to_string(),
+            minterms: Vec::new(),
+        }
+    }
+}
+
 /// Collection of branches to simplify
-#[derive(Debug, Clone)]
+///
+/// Serializes to JSON in the shape of an agent API request, variables in declaration order:
+///
+/// ```json
+/// {
+///   "variables": {"a": {"type": "boolean"}, "x": {"type": "integer", "min": 0, "max": 3}},
+///   "branches": [{"condition": "a && (x < 2)", "output": "1"}],
+///   "default": "0",
+///   "branch_order": "source_order",
+///   "output_comparison": "exact"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BranchSet {
+    #[serde(default)]
     pub branches: Vec<Branch>,
+    #[serde(rename = "default", default)]
     pub default_output: Option<String>,
-    pub variable_types: HashMap<String, VariableType>, // Variable domains
+    #[serde(rename = "variables", default)]
+    pub variable_types: VariableTypes, // Variable domains
+    #[serde(default)]
+    pub branch_order: BranchOrder, // Ordering of simplified branches
+    #[serde(default)]
+    pub output_comparison: OutputComparison, // When two outputs are the same output
+    /// Also give declared variables that no condition references a (don't-care) column, so
+    /// row indices stay the same across branch sets sharing the declarations
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_declared_unused: bool,
+    /// Larger minimized conditions are replaced by the original branches of their output
+    #[serde(
+        default = "default_max_condition_operators",
+        skip_serializing_if = "is_default_max_condition_operators"
+    )]
+    pub max_condition_operators: usize,
+    /// Branch sets with a deeper condition ([`BoolExpr::depth`]) are rejected
+    #[serde(
+        default = "default_max_condition_depth",
+        skip_serializing_if = "is_default_max_condition_depth"
+    )]
+    pub max_condition_depth: usize,
+}
+
+/// Operators ([`BoolExpr::operator_count`]) a minimized condition may have before
+/// [`simplify_branches`](super::simplify_branches) keeps the original branches instead
+pub const DEFAULT_MAX_CONDITION_OPERATORS: usize = 24;
+
+fn default_max_condition_operators() -> usize {
+    DEFAULT_MAX_CONDITION_OPERATORS
+}
+
+fn is_default_max_condition_operators(max: &usize) -> bool {
+    *max == DEFAULT_MAX_CONDITION_OPERATORS
+}
+
+/// Depth ([`BoolExpr::depth`]) a branch condition may have; deeper ones are rejected before
+/// analysis, some of whose walks recurse once per level of nesting
+///
+/// A parsed `a && b && c` chain nests one level per operand, so this is well above the
+/// parser's [`DEFAULT_MAX_DEPTH`](super::DEFAULT_MAX_DEPTH) of parenthesized nesting.
+pub const DEFAULT_MAX_CONDITION_DEPTH: usize = 1024;
+
+fn default_max_condition_depth() -> usize {
+    DEFAULT_MAX_CONDITION_DEPTH
+}
+
+fn is_default_max_condition_depth(max: &usize) -> bool {
+    *max == DEFAULT_MAX_CONDITION_DEPTH
+}
+
+/// Declared variable domains, in declaration order
+///
+/// A JSON object keeps the order of its keys both ways; redeclaring a variable replaces its
+/// type in place. Lookups are linear, there are few variables.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariableTypes {
+    entries: Vec<(String, VariableType)>,
+}
+
+impl VariableTypes {
+    /// Declare `name`, returning the type it had
+    pub fn insert(&mut self, name: String, var_type: VariableType) -> Option<VariableType> {
+        let declared = self.entries.iter().position(|(n, _)| *n == name);
+        match declared {
+            Some(i) => Some(std::mem::replace(&mut self.entries[i].1, var_type)),
+            None => {
+                self.entries.push((name, var_type));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&VariableType> {
+        self.entries
+            .iter()
+            .find(|(declared, _)| declared == name)
+            .map(|(_, var_type)| var_type)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &VariableType)> {
+        self.entries.iter().map(|(name, var_type)| (name, var_type))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(name, _)| name)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &VariableType> {
+        self.entries.iter().map(|(_, var_type)| var_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl std::ops::Index<&str> for VariableTypes {
+    type Output = VariableType;
+
+    fn index(&self, name: &str) -> &VariableType {
+        self.get(name)
+            .unwrap_or_else(|| panic!("Variable '{}' is not declared", name))
+    }
+}
+
+impl Serialize for VariableTypes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for VariableTypes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct InOrder;
+
+        impl<'de> serde::de::Visitor<'de> for InOrder {
+            type Value = VariableTypes;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a map from variable names to types")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<VariableTypes, A::Error> {
+                let mut types = VariableTypes::default();
+                while let Some((name, var_type)) = map.next_entry()? {
+                    types.insert(name, var_type);
+                }
+                Ok(types)
+            }
+        }
+
+        deserializer.deserialize_map(InOrder)
+    }
 }
 
 impl Default for BranchSet {
@@ -129,11 +605,21 @@ impl Default for BranchSet {
 }
 
 impl BranchSet {
+    /// Start a [`BranchSetBuilder`]
+    pub fn builder() -> BranchSetBuilder {
+        Default::default()
+    }
+
     pub fn new() -> Self {
         Self {
             branches: Vec::new(),
             default_output: None,
-            variable_types: HashMap::new(),
+            variable_types: VariableTypes::default(),
+            branch_order: BranchOrder::default(),
+            output_comparison: OutputComparison::default(),
+            include_declared_unused: false,
+            max_condition_operators: DEFAULT_MAX_CONDITION_OPERATORS,
+            max_condition_depth: DEFAULT_MAX_CONDITION_DEPTH,
         }
     }
 
@@ -141,10 +627,80 @@ impl BranchSet {
         self.branches.push(Branch::new(condition, output));
     }
 
+    /// Add a branch that is kept verbatim, even when it is logically redundant
+    pub fn add_protected_branch(&mut self, condition: BoolExpr, output: &str) {
+        self.branches.push(Branch::protected(condition, output));
+    }
+
+    /// Set the expected execution frequency of branch `index`
+    ///
+    /// Weighted outputs are emitted heaviest first; see [`simplify_branches`](super::simplify_branches).
+    pub fn set_branch_weight(&mut self, index: usize, weight: f64) {
+        self.branches[index].weight = Some(weight);
+    }
+
+    /// Summed weight of the branches with `output`, or `None` when none of them has a weight
+    pub fn output_weight(&self, output: &str) -> Option<f64> {
+        let key = self.output_key(output);
+        self.branches
+            .iter()
+            .filter(|b| self.output_key(&b.output) == key)
+            .filter_map(|b| b.weight)
+            .reduce(|a, b| a + b)
+    }
+
     pub fn set_default(&mut self, output: &str) {
         self.default_output = Some(output.to_string());
     }
 
+    /// Choose how simplified branches are ordered (the default output always comes last)
+    pub fn set_branch_order(&mut self, order: BranchOrder) {
+        self.branch_order = order;
+    }
+
+    /// Choose when two branch outputs count as the same output
+    pub fn set_output_comparison(&mut self, comparison: OutputComparison) {
+        self.output_comparison = comparison;
+    }
+
+    /// Choose whether declared but unreferenced variables get a column in the truth table
+    /// (see [`include_declared_unused`](Self::include_declared_unused))
+    pub fn set_include_declared_unused(&mut self, include: bool) {
+        self.include_declared_unused = include;
+    }
+
+    /// Set the operator limit of a minimized condition (see
+    /// [`max_condition_operators`](Self::max_condition_operators))
+    pub fn set_max_condition_operators(&mut self, max: usize) {
+        self.max_condition_operators = max;
+    }
+
+    /// Set the depth limit of a branch condition (see
+    /// [`max_condition_depth`](Self::max_condition_depth))
+    pub fn set_max_condition_depth(&mut self, max: usize) {
+        self.max_condition_depth = max;
+    }
+
+    /// Grouping key of `output` under this set's [`OutputComparison`]
+    pub fn output_key(&self, output: &str) -> OutputKey {
+        self.output_comparison.key(output)
+    }
+
+    /// Key and emitted text of `output`: the spelling of the first branch (or else the
+    /// default) with the same key
+    pub fn output_identity(&self, output: &str) -> (OutputKey, String) {
+        let key = self.output_key(output);
+        let display = self
+            .branches
+            .iter()
+            .map(|b| b.output.as_str())
+            .chain(self.default_output.as_deref())
+            .find(|&other| self.output_key(other) == key)
+            .unwrap_or(output)
+            .to_string();
+        (key, display)
+    }
+
     /// Declare a variable type (needed for comparisons)
     pub fn declare_variable(&mut self, name: &str, var_type: VariableType) {
         self.variable_types.insert(name.to_string(), var_type);
@@ -161,12 +717,167 @@ impl BranchSet {
     }
 }
 
+/// A branch of a [`BranchSetBuilder`], with its condition still unparsed
+#[derive(Debug, Clone)]
+struct PendingBranch {
+    condition: String,
+    output: String,
+    protected: bool,
+    weight: Option<f64>,
+}
+
+/// Fluent construction of a [`BranchSet`] from condition strings
+///
+/// ```
+/// use qm_agent::simplify::BranchSet;
+///
+/// let branches = BranchSet::builder()
+///     .bool_var("a")
+///     .int_var("x", 0, 3)
+///     .branch("a && x < 2", "1")
+///     .default("0")
+///     .build()
+///     .unwrap();
+/// assert_eq!(branches.branches.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BranchSetBuilder {
+    variables: Vec<(String, VariableType)>,
+    branches: Vec<PendingBranch>,
+    default_output: Option<String>,
+    branch_order: BranchOrder,
+    output_comparison: OutputComparison,
+    include_declared_unused: bool,
+    max_condition_operators: Option<usize>,
+    max_condition_depth: Option<usize>,
+}
+
+impl BranchSetBuilder {
+    pub fn variable(mut self, name: &str, var_type: VariableType) -> Self {
+        self.variables.push((name.to_string(), var_type));
+        self
+    }
+
+    pub fn bool_var(self, name: &str) -> Self {
+        self.variable(name, VariableType::Boolean)
+    }
+
+    pub fn int_var(self, name: &str, min: i32, max: i32) -> Self {
+        self.variable(name, VariableType::Integer { min, max })
+    }
+
+    /// Add a branch; `condition` is parsed by [`build`](Self::build)
+    pub fn branch(mut self, condition: &str, output: &str) -> Self {
+        self.branches.push(PendingBranch {
+            condition: condition.to_string(),
+            output: output.to_string(),
+            protected: false,
+            weight: None,
+        });
+        self
+    }
+
+    /// Add a branch that is kept verbatim (see [`BranchSet::add_protected_branch`])
+    pub fn protected_branch(mut self, condition: &str, output: &str) -> Self {
+        self = self.branch(condition, output);
+        if let Some(branch) = self.branches.last_mut() {
+            branch.protected = true;
+        }
+        self
+    }
+
+    /// Set the expected execution frequency of the branch added last
+    ///
+    /// # Panics
+    /// Panics if no branch has been added yet
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.branches
+            .last_mut()
+            .expect("weight() needs a branch to apply to")
+            .weight = Some(weight);
+        self
+    }
+
+    pub fn default(mut self, output: &str) -> Self {
+        self.default_output = Some(output.to_string());
+        self
+    }
+
+    pub fn branch_order(mut self, order: BranchOrder) -> Self {
+        self.branch_order = order;
+        self
+    }
+
+    pub fn output_comparison(mut self, comparison: OutputComparison) -> Self {
+        self.output_comparison = comparison;
+        self
+    }
+
+    pub fn include_declared_unused(mut self, include: bool) -> Self {
+        self.include_declared_unused = include;
+        self
+    }
+
+    pub fn max_condition_operators(mut self, max: usize) -> Self {
+        self.max_condition_operators = Some(max);
+        self
+    }
+
+    pub fn max_condition_depth(mut self, max: usize) -> Self {
+        self.max_condition_depth = Some(max);
+        self
+    }
+
+    /// Parse the conditions and check the weights
+    pub fn build(self) -> Result<BranchSet, String> {
+        let mut branch_set = BranchSet::new();
+        for (name, var_type) in self.variables {
+            branch_set.declare_variable(&name, var_type);
+        }
+        for (index, pending) in self.branches.iter().enumerate() {
+            let condition = parse_bool_expr(&pending.condition)
+                .map_err(|e| format!("Failed to parse '{}': {}", pending.condition, e))?;
+            if pending.protected {
+                branch_set.add_protected_branch(condition, &pending.output);
+            } else {
+                branch_set.add_branch(condition, &pending.output);
+            }
+            if let Some(weight) = pending.weight {
+                if !weight.is_finite() || weight < 0.0 {
+                    return Err(format!(
+                        "Invalid weight {} for branch '{}': must be a non-negative number",
+                        weight, pending.condition
+                    ));
+                }
+                branch_set.set_branch_weight(index, weight);
+            }
+        }
+        if let Some(default) = &self.default_output {
+            branch_set.set_default(default);
+        }
+        branch_set.set_branch_order(self.branch_order);
+        branch_set.set_output_comparison(self.output_comparison);
+        branch_set.set_include_declared_unused(self.include_declared_unused);
+        if let Some(max) = self.max_condition_operators {
+            branch_set.set_max_condition_operators(max);
+        }
+        if let Some(max) = self.max_condition_depth {
+            branch_set.set_max_condition_depth(max);
+        }
+        Ok(branch_set)
+    }
+}
+
+/// Rows [`TruthTable::from_function_truncated`] keeps unless told otherwise
+pub const DEFAULT_MAX_TRUTH_TABLE_ROWS: u64 = 4096;
+
 /// Truth table representation for simplification
 #[derive(Debug)]
 pub struct TruthTable {
     pub variables: Vec<String>,
-    pub output_groups: HashMap<String, Vec<u64>>, // output -> list of minterms
+    pub output_groups: HashMap<OutputKey, OutputGroup>, // output key -> spelling and minterms
     pub dont_cares: Vec<u64>,
+    pub omitted_rows: u64, // Rows past the last minterm of the table, left out to bound its size
 }
 
 impl TruthTable {
@@ -175,12 +886,205 @@ impl TruthTable {
             variables,
             output_groups: HashMap::new(),
             dont_cares: Vec::new(),
+            omitted_rows: 0,
         }
     }
 
+    /// Rows of a table over `n_variables` variables; an error from 64 variables on, where
+    /// the count no longer fits in a `u64`
+    pub fn row_count(n_variables: usize) -> Result<u64, String> {
+        u32::try_from(n_variables)
+            .ok()
+            .and_then(|n| 1u64.checked_shl(n))
+            .ok_or_else(|| {
+                format!(
+                    "A truth table of {} variables has 2^{} rows, too many to enumerate (maximum 63 variables)",
+                    n_variables, n_variables
+                )
+            })
+    }
+
     pub fn variable_count(&self) -> usize {
         self.variables.len()
     }
+
+    /// Add `minterm` to the group of `key`, created with spelling `display` if new
+    pub fn add_minterm(&mut self, key: OutputKey, display: &str, minterm: u64) {
+        self.output_groups
+            .entry(key)
+            .or_insert_with(|| OutputGroup::new(display))
+            .minterms
+            .push(minterm);
+    }
+
+    /// The group emitted as `display`
+    pub fn group(&self, display: &str) -> Option<&OutputGroup> {
+        self.output_groups.values().find(|g| g.display == display)
+    }
+
+    /// Output groups `(display, minterms)` sorted by display, minterms ascending
+    pub fn sorted_output_groups(&self) -> Vec<(String, Vec<u64>)> {
+        let mut groups: Vec<(String, Vec<u64>)> = self
+            .output_groups
+            .values()
+            .map(|group| {
+                let mut minterms = group.minterms.clone();
+                minterms.sort_unstable();
+                (group.display.clone(), minterms)
+            })
+            .collect();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        groups
+    }
+
+    /// Table of a single Boolean function: output `1` on `minterms`, `0` elsewhere
+    ///
+    /// Minterms that are also don't-cares count as minterms. Every row is included: see
+    /// [`from_function_truncated`](Self::from_function_truncated) to bound the size.
+    ///
+    /// # Panics
+    ///
+    /// With 64 or more variables (see [`row_count`](Self::row_count)).
+    pub fn from_function(variables: Vec<String>, minterms: &[u64], dont_cares: &[u64]) -> Self {
+        Self::from_function_truncated(variables, minterms, dont_cares, u64::MAX)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// As [`from_function`](Self::from_function), keeping only the first `max_rows` rows;
+    /// the others are counted in [`omitted_rows`](Self::omitted_rows)
+    pub fn from_function_truncated(
+        variables: Vec<String>,
+        minterms: &[u64],
+        dont_cares: &[u64],
+        max_rows: u64,
+    ) -> Result<Self, String> {
+        let rows = Self::row_count(variables.len())?;
+        let mut table = Self::new(variables);
+        table.omitted_rows = rows.saturating_sub(max_rows);
+        let on_set: HashSet<u64> = minterms.iter().copied().collect();
+        let dc_set: HashSet<u64> = dont_cares.iter().copied().collect();
+        for m in 0..rows - table.omitted_rows {
+            if on_set.contains(&m) {
+                table.add_minterm(OutputKey("1".to_string()), "1", m);
+            } else if dc_set.contains(&m) {
+                table.dont_cares.push(m);
+            } else {
+                table.add_minterm(OutputKey("0".to_string()), "0", m);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Rows in the table
+    fn row_len(&self) -> u64 {
+        let minterms: usize = self.output_groups.values().map(|g| g.minterms.len()).sum();
+        (minterms + self.dont_cares.len()) as u64
+    }
+
+    /// Line saying how many rows were left out, if any
+    pub fn truncation_notice(&self) -> Option<String> {
+        (self.omitted_rows > 0).then(|| {
+            format!(
+                "... {} more rows not shown ({} in total)",
+                self.omitted_rows,
+                self.omitted_rows + self.row_len()
+            )
+        })
+    }
+
+    /// `(minterm, output)` for every row, ascending; don't-care rows have output `dont_care`
+    fn rows<'a>(&'a self, dont_care: &'a str) -> Vec<(u64, &'a str)> {
+        let mut rows: Vec<(u64, &str)> = self
+            .output_groups
+            .values()
+            .flat_map(|group| {
+                group
+                    .minterms
+                    .iter()
+                    .map(move |&m| (m, group.display.as_str()))
+            })
+            .chain(self.dont_cares.iter().map(|&m| (m, dont_care)))
+            .collect();
+        rows.sort_unstable();
+        rows
+    }
+
+    /// Export as CSV: one row per minterm, one column per variable plus `output`
+    ///
+    /// Column `i` holds bit `i` of the minterm; don't-care rows have output `-`. Omitted
+    /// rows are not mentioned, which keeps the output plain CSV.
+    pub fn to_csv(&self) -> String {
+        let mut csv: String = self
+            .variables
+            .iter()
+            .map(|name| format!("{},", name))
+            .collect();
+        csv.push_str("output\n");
+        for (minterm, output) in self.rows("-") {
+            for i in 0..self.variables.len() {
+                csv.push_str(if (minterm >> i) & 1 == 1 { "1," } else { "0," });
+            }
+            csv.push_str(output);
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Plain-text table with an `F` column; don't-care rows have output `X`
+    ///
+    /// Each row writes its minterm in binary, most significant bit first. The
+    /// [`truncation_notice`](Self::truncation_notice) ends the table.
+    pub fn to_text(&self) -> String {
+        let mut table = String::new();
+        for name in &self.variables {
+            table.push_str(name);
+            table.push(' ');
+        }
+        table.push_str("| F\n");
+        let width: usize = self.variables.iter().map(|v| v.chars().count() + 1).sum();
+        table.push_str(&"-".repeat(width + 4));
+        table.push('\n');
+
+        for (minterm, output) in self.rows("X") {
+            let n = self.variables.len();
+            for (i, name) in self.variables.iter().enumerate() {
+                let bit = (minterm >> (n - 1 - i)) & 1;
+                table.push_str(&format!("{:<width$} ", bit, width = name.chars().count()));
+            }
+            table.push_str("| ");
+            table.push_str(output);
+            table.push('\n');
+        }
+        if let Some(notice) = self.truncation_notice() {
+            table.push_str(&notice);
+            table.push('\n');
+        }
+        table
+    }
+
+    /// Markdown table with an `F` column; don't-care rows have output `X`, followed by the
+    /// [`truncation_notice`](Self::truncation_notice) as a paragraph
+    pub fn to_markdown(&self) -> String {
+        let mut table = String::from("|");
+        for name in &self.variables {
+            table.push_str(&format!(" {} |", name));
+        }
+        table.push_str(" F |\n|");
+        table.push_str(&"---|".repeat(self.variables.len() + 1));
+        table.push('\n');
+
+        for (minterm, output) in self.rows("X") {
+            table.push('|');
+            for i in 0..self.variables.len() {
+                table.push_str(&format!(" {} |", (minterm >> i) & 1));
+            }
+            table.push_str(&format!(" {} |\n", output));
+        }
+        if let Some(notice) = self.truncation_notice() {
+            table.push_str(&format!("\n{}\n", notice));
+        }
+        table
+    }
 }
 
 /// Result of simplification
@@ -188,9 +1092,14 @@ impl TruthTable {
 pub struct SimplificationResult {
     pub variables: Vec<String>,
     pub simplified_conditions: Vec<(BoolExpr, String)>, // (condition, output)
+    pub protected_branches: Vec<(usize, usize)>, // (index in simplified_conditions, original branch index)
     pub original_branch_count: usize,
     pub simplified_branch_count: usize,
     pub analysis: SimplificationAnalysis,
+    pub weighted_default: Option<String>, // Output made the else branch because it is the heaviest
+    pub combinations_covered: Vec<u64>, // Per simplified condition, the input combinations on which it is the first to hold
+    pub total_combinations: u64,        // Input combinations, integers counted by their domain size
+    pub unreferenced_variables: Vec<String>, // Declared or used variables no simplified condition reads (sorted)
 }
 
 impl SimplificationResult {
@@ -214,6 +1123,8 @@ pub struct SimplificationAnalysis {
     pub dead_branches: Vec<DeadBranch>,
     pub uncovered_minterms: Vec<u32>,
     pub total_coverage_percent: f64,
+    pub redundant_with_default: Vec<usize>, // Live branches returning the default that can go without changing any decision
+    pub notes: Vec<String>, // Simplification decisions worth reporting, e.g. outputs left unsimplified
 }
 
 impl Default for SimplificationAnalysis {
@@ -229,6 +1140,8 @@ impl SimplificationAnalysis {
             dead_branches: Vec::new(),
             uncovered_minterms: Vec::new(),
             total_coverage_percent: 0.0,
+            redundant_with_default: Vec::new(),
+            notes: Vec::new(),
         }
     }
 
@@ -241,6 +1154,23 @@ impl SimplificationAnalysis {
     }
 }
 
+/// Dead-code analysis of [`analyze_branches_symbolic`](super::dead_code::analyze_branches_symbolic)
+///
+/// Only reachability is decided: there is no per-branch coverage, no list of uncovered
+/// minterms and no coverage percentage.
+#[derive(Debug, Clone)]
+pub struct SymbolicAnalysis {
+    pub dead_branches: Vec<DeadBranch>,
+    /// Some input reaches no branch (whether or not there is a default)
+    pub has_unmatched_inputs: bool,
+}
+
+impl SymbolicAnalysis {
+    pub fn has_dead_code(&self) -> bool {
+        !self.dead_branches.is_empty()
+    }
+}
+
 /// Coverage information for a single branch
 #[derive(Debug, Clone)]
 pub struct BranchCoverage {
@@ -255,12 +1185,165 @@ pub struct BranchCoverage {
 pub struct DeadBranch {
     pub branch_index: usize,
     pub reason: DeadCodeReason,
-    pub covered_by: Vec<usize>, // Which earlier branches make this unreachable
+    pub covered_by: Vec<usize>, // Which earlier branches make this unreachable (for a duplicate: the first identical branch)
+    pub protected: bool,        // Kept anyway; reported for information only
+    pub detail: Option<String>, // Why, when known (e.g. the conflicting comparisons of a contradiction)
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeadCodeReason {
-    FullyCovered,  // All conditions already handled by earlier branches
-    Contradiction, // Condition is logically impossible
-    Redundant,     // Identical to an earlier branch
+    FullyCovered,         // All conditions already handled by earlier branches
+    Contradiction,        // Condition is logically impossible
+    Redundant,            // Identical to an earlier branch
+    ConflictingDuplicate, // Same condition as an earlier branch, but a different output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_negated_comparisons() {
+        let cases = [
+            ("!(x == 3)", "x != 3"),
+            ("!(x != 3)", "x == 3"),
+            ("!(x < 2)", "x >= 2"),
+            ("!(x <= 2)", "x > 2"),
+            ("!(x > 2)", "x <= 2"),
+            ("!(x >= 2)", "x < 2"),
+            ("!!a", "a"),
+            ("!!!(x == 3)", "x != 3"),
+            ("!true", "false"),
+            ("!a", "!a"),
+        ];
+        for (condition, expected) in cases {
+            let normalized = parse_bool_expr(condition).unwrap().normalize();
+            assert_eq!(format_bool_expr(&normalized), expected, "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_normalize_de_morgan() {
+        let normalize =
+            |condition: &str| format_bool_expr(&parse_bool_expr(condition).unwrap().normalize());
+        assert_eq!(
+            normalize("!(!(x == 3) && (a || x < 2))"),
+            "x == 3 || !a && (x >= 2)"
+        );
+        assert_eq!(normalize("!(!a && !(x < 2)) && b"), "(a || x < 2) && b");
+        assert_eq!(normalize("!(x == 3 || !b)"), "(x != 3) && b");
+        // Pushing the negation would add operators
+        assert_eq!(normalize("!(a && b)"), "!(a && b)");
+    }
+
+    #[test]
+    fn test_normalize_nested_negations() {
+        use crate::simplify::analyzer::evaluate;
+
+        // !(!(...!(a && b)...) && b) && b: each level weighs both polarities of the one below
+        let (a, b) = (BoolExpr::var("a"), BoolExpr::var("b"));
+        let mut condition = BoolExpr::and(a, b.clone());
+        for _ in 0..100 {
+            condition = BoolExpr::and(BoolExpr::negate(condition), b.clone());
+        }
+        let normalized = condition.normalize();
+        assert!(normalized.operator_count() <= condition.operator_count());
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            let assignments = HashMap::from([("a".to_string(), a), ("b".to_string(), b)]);
+            assert_eq!(
+                evaluate(&normalized, &assignments),
+                evaluate(&condition, &assignments)
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_matches_manual_construction() {
+        let built = BranchSet::builder()
+            .bool_var("a")
+            .int_var("x", 0, 3)
+            .branch("a && x < 2", "1")
+            .protected_branch("!a", "log()")
+            .weight(2.5)
+            .default("0")
+            .build()
+            .unwrap();
+
+        let mut manual = BranchSet::new();
+        manual.declare_bool("a");
+        manual.declare_int("x", 0, 3);
+        manual.add_branch(
+            BoolExpr::and(BoolExpr::var("a"), BoolExpr::less_than("x", 2)),
+            "1",
+        );
+        manual.add_protected_branch(BoolExpr::negate(BoolExpr::var("a")), "log()");
+        manual.set_branch_weight(1, 2.5);
+        manual.set_default("0");
+        assert_eq!(built, manual);
+
+        let error = BranchSet::builder()
+            .branch("a &&", "1")
+            .build()
+            .unwrap_err();
+        assert!(error.starts_with("Failed to parse 'a &&'"), "{}", error);
+        let error = BranchSet::builder()
+            .branch("a", "1")
+            .weight(-1.0)
+            .build()
+            .unwrap_err();
+        assert!(error.starts_with("Invalid weight -1"), "{}", error);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let json = r#"{
+            "branches": [
+                {"condition": "a && (x < 2)", "output": "1"},
+                {"condition": "!a || x == 3", "output": "log()", "protected": true, "weight": 0.5}
+            ],
+            "default": "0",
+            "variables": {
+                "x": {"type": "integer", "min": -1, "max": 3},
+                "b": {"type": "boolean"},
+                "a": {"type": "boolean"}
+            },
+            "branch_order": "alphabetical",
+            "output_comparison": "ignore_whitespace"
+        }"#;
+        let branch_set: BranchSet = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            branch_set.variable_types["x"],
+            VariableType::Integer { min: -1, max: 3 }
+        );
+        assert_eq!(branch_set.branch_order, BranchOrder::Alphabetical);
+        let outputs: Vec<&str> = branch_set
+            .branches
+            .iter()
+            .map(|b| b.output.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["1", "log()"]);
+
+        let serialized = serde_json::to_string_pretty(&branch_set).unwrap();
+        let value = |text: &str| serde_json::from_str::<serde_json::Value>(text).unwrap();
+        assert_eq!(value(&serialized), value(json));
+        // Variables come out in declaration order, not sorted
+        let names: Vec<usize> = ["\"x\"", "\"b\"", "\"a\""]
+            .iter()
+            .map(|name| serialized.find(name).unwrap())
+            .collect();
+        assert!(names.is_sorted(), "{}", serialized);
+        let declared: Vec<&String> = branch_set.variable_types.keys().collect();
+        assert_eq!(declared, ["x", "b", "a"]);
+        let reloaded: BranchSet = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(reloaded, branch_set);
+        assert_eq!(serde_json::to_string_pretty(&reloaded).unwrap(), serialized);
+
+        // Sparse fixtures fall back to the defaults
+        let sparse: BranchSet =
+            serde_json::from_str(r#"{"branches": [{"condition": "a", "output": "1"}]}"#).unwrap();
+        assert_eq!(sparse.default_output, None);
+        assert_eq!(sparse.output_comparison, OutputComparison::Exact);
+        let malformed = r#"{"branches": [{"condition": "a ||", "output": "1"}]}"#;
+        assert!(serde_json::from_str::<BranchSet>(malformed).is_err());
+    }
 }
@@ -0,0 +1,269 @@
+//! Coverage diagrams of a branch analysis, as Graphviz DOT or Mermaid
+//!
+//! Every branch is a node (`b0`, `b1`, ... by branch index) in a first-match chain ending
+//! in the default (`default`). A dashed edge from an earlier to a later branch counts the
+//! input combinations on which both conditions hold; dead branches are filled red; a side
+//! node `uncovered` lists the input combinations no branch handles.
+
+use super::types::SimplificationAnalysis;
+use std::collections::HashSet;
+
+/// Uncovered input combinations listed on the side node; the rest are counted
+const MAX_LISTED_UNCOVERED: usize = 8;
+
+/// What the diagrams show, independent of the output syntax
+struct Diagram {
+    /// `(id, label, dead)`
+    nodes: Vec<(String, String, bool)>,
+    /// Branch ids in first-match order, then `default` when there is one
+    chain: Vec<String>,
+    /// `(earlier id, later id, shared input combinations)`
+    overlaps: Vec<(String, String, usize)>,
+    uncovered: Option<String>,
+}
+
+impl SimplificationAnalysis {
+    /// The branch coverage as a Graphviz `digraph`
+    ///
+    /// `branch_labels[i]` labels branch `i` (`branch i` when missing); one label past the
+    /// last branch labels the default, which is left out without it.
+    pub fn to_dot(&self, branch_labels: &[String]) -> String {
+        let diagram = self.diagram(branch_labels);
+        let mut dot = String::from("digraph coverage {\n    node [shape=box];\n");
+        for (id, label, dead) in &diagram.nodes {
+            let style = if *dead {
+                ", style=filled, fillcolor=\"#f4cccc\", color=\"#cc0000\""
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    {} [label=\"{}\"{}];\n",
+                id,
+                escape_dot(label),
+                style
+            ));
+        }
+        if let Some(uncovered) = &diagram.uncovered {
+            dot.push_str(&format!(
+                "    uncovered [label=\"{}\", shape=note];\n",
+                escape_dot(uncovered)
+            ));
+        }
+        for pair in diagram.chain.windows(2) {
+            dot.push_str(&format!("    {} -> {};\n", pair[0], pair[1]));
+        }
+        for (from, to, shared) in &diagram.overlaps {
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{} overlapping\", style=dashed, color=\"#cc0000\"];\n",
+                from, to, shared
+            ));
+        }
+        if let (Some(_), Some(last)) = (&diagram.uncovered, diagram.chain.last()) {
+            dot.push_str(&format!("    {} -> uncovered [style=dotted];\n", last));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The branch coverage as a Mermaid flowchart, with the nodes of [`to_dot`](Self::to_dot)
+    pub fn to_mermaid(&self, branch_labels: &[String]) -> String {
+        let diagram = self.diagram(branch_labels);
+        let mut mermaid = String::from("flowchart TD\n");
+        for (id, label, _) in &diagram.nodes {
+            mermaid.push_str(&format!("    {}[\"{}\"]\n", id, escape_mermaid(label)));
+        }
+        if let Some(uncovered) = &diagram.uncovered {
+            mermaid.push_str(&format!(
+                "    uncovered>\"{}\"]\n",
+                escape_mermaid(uncovered)
+            ));
+        }
+        for pair in diagram.chain.windows(2) {
+            mermaid.push_str(&format!("    {} --> {}\n", pair[0], pair[1]));
+        }
+        for (from, to, shared) in &diagram.overlaps {
+            mermaid.push_str(&format!(
+                "    {} -.->|{} overlapping| {}\n",
+                from, shared, to
+            ));
+        }
+        if let (Some(_), Some(last)) = (&diagram.uncovered, diagram.chain.last()) {
+            mermaid.push_str(&format!("    {} -.-> uncovered\n", last));
+        }
+        let dead: Vec<&str> = diagram
+            .nodes
+            .iter()
+            .filter(|(_, _, dead)| *dead)
+            .map(|(id, _, _)| id.as_str())
+            .collect();
+        if !dead.is_empty() {
+            mermaid.push_str("    classDef dead fill:#f4cccc,stroke:#cc0000\n");
+            mermaid.push_str(&format!("    class {} dead\n", dead.join(",")));
+        }
+        mermaid
+    }
+
+    fn diagram(&self, branch_labels: &[String]) -> Diagram {
+        let n_branches = self.branch_coverage.len();
+        let dead: HashSet<usize> = self
+            .dead_branches
+            .iter()
+            .map(|db| db.branch_index)
+            .collect();
+
+        let mut nodes = Vec::new();
+        let mut chain = Vec::new();
+        let mut overlaps = Vec::new();
+        for (i, coverage) in self.branch_coverage.iter().enumerate() {
+            let id = format!("b{}", i);
+            let label = branch_labels
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| format!("branch {}", i));
+            let label = if dead.contains(&i) {
+                format!("{}\nunreachable", label)
+            } else {
+                format!("{}\nfirst match on {}", label, coverage.coverage_count)
+            };
+            nodes.push((id.clone(), label, dead.contains(&i)));
+            chain.push(id.clone());
+
+            let own: HashSet<u32> = coverage.minterms_covered.iter().copied().collect();
+            for &j in &coverage.overlaps_with {
+                let shared = self.branch_coverage[j]
+                    .minterms_covered
+                    .iter()
+                    .filter(|m| own.contains(m))
+                    .count();
+                overlaps.push((format!("b{}", j), id.clone(), shared));
+            }
+        }
+        if let Some(label) = branch_labels.get(n_branches) {
+            nodes.push(("default".to_string(), label.clone(), false));
+            chain.push("default".to_string());
+        }
+
+        let uncovered = (!self.uncovered_minterms.is_empty()).then(|| {
+            let listed: Vec<String> = self
+                .uncovered_minterms
+                .iter()
+                .take(MAX_LISTED_UNCOVERED)
+                .map(|m| m.to_string())
+                .collect();
+            let more = self.uncovered_minterms.len() - listed.len();
+            let mut text = format!(
+                "{} uncovered input combinations\n{}",
+                self.uncovered_minterms.len(),
+                listed.join(", ")
+            );
+            if more > 0 {
+                text.push_str(&format!(" and {} more", more));
+            }
+            text
+        });
+
+        Diagram {
+            nodes,
+            chain,
+            overlaps,
+            uncovered,
+        }
+    }
+}
+
+/// `text` inside a double-quoted DOT string
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// `text` inside a double-quoted Mermaid label: entity codes for the characters Mermaid
+/// would read as syntax, `<br>` for line breaks
+fn escape_mermaid(text: &str) -> String {
+    text.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplify::dead_code::analyze_branches;
+    use crate::simplify::types::{BoolExpr, BranchSet};
+
+    fn labels(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// if a || b { 1 } elif a && b { 2 } else { 0 }: branch 1 is fully covered
+    fn fully_covered() -> SimplificationAnalysis {
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::or(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        branches.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "2");
+        branches.set_default("0");
+        analyze_branches(&branches).unwrap()
+    }
+
+    #[test]
+    fn test_to_dot_fully_covered_branch() {
+        let dot = fully_covered().to_dot(&labels(&["a || b => 1", "a && b => 2", "else => 0"]));
+        assert!(dot.starts_with("digraph coverage {\n"), "{}", dot);
+        assert!(dot.contains("    b0 [label=\"a || b => 1\\nfirst match on 3\"];\n"));
+        assert!(dot.contains(
+            "    b1 [label=\"a && b => 2\\nunreachable\", style=filled, fillcolor=\"#f4cccc\", color=\"#cc0000\"];\n"
+        ));
+        assert!(dot.contains("    default [label=\"else => 0\"];\n"));
+        assert!(dot.contains("    b0 -> b1;\n    b1 -> default;\n"));
+        assert!(dot.contains("    b0 -> b1 [label=\"1 overlapping\""));
+        assert!(!dot.contains("uncovered"));
+    }
+
+    #[test]
+    fn test_to_mermaid_fully_covered_branch() {
+        let mermaid = fully_covered().to_mermaid(&labels(&["a || b => 1", "a && b => 2"]));
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("    b0[\"a || b =#gt; 1<br>first match on 3\"]\n"));
+        assert!(mermaid.contains("    b0 --> b1\n"));
+        assert!(mermaid.contains("    b0 -.->|1 overlapping| b1\n"));
+        assert!(mermaid.contains("    class b1 dead\n"));
+        // No label for the default: no default node
+        assert!(!mermaid.contains("default"));
+    }
+
+    #[test]
+    fn test_uncovered_side_node() {
+        // if a && b { 1 }, no default: three input combinations are unhandled
+        let mut branches = BranchSet::new();
+        branches.add_branch(BoolExpr::and(BoolExpr::var("a"), BoolExpr::var("b")), "1");
+        let analysis = analyze_branches(&branches).unwrap();
+
+        let dot = analysis.to_dot(&[]);
+        assert!(dot.contains("    b0 [label=\"branch 0\\nfirst match on 1\"];\n"));
+        assert!(dot.contains(
+            "    uncovered [label=\"3 uncovered input combinations\\n0, 1, 2\", shape=note];\n"
+        ));
+        assert!(dot.contains("    b0 -> uncovered [style=dotted];\n"));
+        let mermaid = analysis.to_mermaid(&[]);
+        assert!(mermaid.contains("    uncovered>\"3 uncovered input combinations<br>0, 1, 2\"]\n"));
+        assert!(mermaid.contains("    b0 -.-> uncovered\n"));
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let analysis = fully_covered();
+        let label = labels(&[r#"s == "a\b" # x"#]);
+        assert!(
+            analysis
+                .to_dot(&label)
+                .contains(r#"b0 [label="s == \"a\\b\" # x\nfirst match on 3"];"#)
+        );
+        assert!(
+            analysis
+                .to_mermaid(&label)
+                .contains("b0[\"s == #quot;a\\b#quot; #35; x<br>first match on 3\"]")
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! JavaScript bindings for the browser (feature `wasm`)
+//!
+//! Build for `wasm32-unknown-unknown` without the default (x86-only) features:
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --no-default-features
+//! --features wasm --crate-type cdylib`, then run `wasm-bindgen` on the `.wasm` file.
+
+use wasm_bindgen::prelude::*;
+
+/// Minimize the function given by `minterms` and `dont_cares` over `variables` inputs
+///
+/// Returns the [`QMResult`](crate::QMResult) as a JavaScript object with the field names of
+/// the JSON output (`minimized_sop`, `prime_implicants`, ...). Minterms are numbers, so
+/// at most 53 variables can be addressed exactly.
+#[wasm_bindgen(js_name = minimize)]
+pub fn minimize(
+    minterms: Vec<f64>,
+    dont_cares: Vec<f64>,
+    variables: usize,
+) -> Result<JsValue, JsError> {
+    if variables > 64 {
+        return Err(JsError::new(&format!(
+            "too many variables ({}); maximum supported is 64",
+            variables
+        )));
+    }
+    let minterms = to_minterms(&minterms, variables)?;
+    let dont_cares = to_minterms(&dont_cares, variables)?;
+    let result = crate::minimize_function(&minterms, Some(&dont_cares), variables);
+    let json = serde_json::to_string(&result).map_err(|e| JsError::new(&e.to_string()))?;
+    js_sys::JSON::parse(&json).map_err(|_| JsError::new("JSON parse error"))
+}
+
+/// Run [`simplify_from_json`](crate::agent_api::simplify_from_json): the request and the
+/// response are the JSON strings of `qm-agent simplify`
+#[wasm_bindgen(js_name = simplifyJson)]
+pub fn simplify_json(request: &str) -> Result<String, JsError> {
+    crate::agent_api::simplify_from_json(request).map_err(|e| JsError::new(&e))
+}
+
+/// Minterms from JavaScript numbers, which must be integers below `2^variables`
+fn to_minterms(values: &[f64], variables: usize) -> Result<Vec<u64>, JsError> {
+    values
+        .iter()
+        .map(|&value| {
+            let fits = value >= 0.0
+                && value.fract() == 0.0
+                && (variables >= 64 || value < (1u64 << variables) as f64);
+            if fits {
+                Ok(value as u64)
+            } else {
+                Err(JsError::new(&format!(
+                    "minterm {} is not an integer in 0..2^{}",
+                    value, variables
+                )))
+            }
+        })
+        .collect()
+}
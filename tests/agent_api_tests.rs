@@ -64,8 +64,6 @@ fn test_dead_code_detection() {
 }
 
 #[test]
-#[ignore] // TODO: Parser doesn't support comparison operators yet (< > ==)
-// These work via programmatic API but not via string parsing
 fn test_integer_variables() {
     let input = r#"{
         "variables": {
@@ -138,6 +136,52 @@ fn test_language_code_generation() {
     assert!(suggestions.is_array());
 }
 
+#[test]
+fn test_default_branch_is_plain_else() {
+    // After the protected branch only "0" remains, which minimizes to `true`
+    for (language, else_keyword) in [("rust", "else {"), ("python", "else:")] {
+        let input = format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a && b", "output": "log()", "metadata": {{"protected": true}}}},
+                    {{"condition": "a && !b", "output": "0"}},
+                    {{"condition": "!a", "output": "0"}}
+                ],
+                "context": {{"language": "{}"}}
+            }}"#,
+            language
+        );
+
+        let result = agent_api::simplify_from_json(&input).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+
+        let last = json["simplified_branches"]
+            .as_array()
+            .unwrap()
+            .last()
+            .unwrap();
+        assert_eq!(last["condition"], "true");
+        assert_eq!(last["is_default"], true);
+
+        let code = json["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["kind"] == "simplification")
+            .and_then(|s| s["code"].as_str())
+            .expect("generated code")
+            .to_string();
+        assert!(code.contains(else_keyword), "{}", code);
+        // Conditions are written in the target language, protected ones too
+        if language == "python" {
+            assert!(code.starts_with("if a and b:\n"), "{}", code);
+        }
+        assert!(!code.contains("||"), "tautology in {}", code);
+        assert!(!code.contains("true"), "{}", code);
+    }
+}
+
 #[test]
 fn test_metadata_preservation() {
     let input = r#"{
@@ -164,6 +208,270 @@ fn test_metadata_preservation() {
     assert!(json["simplified_branches"].is_array());
 }
 
+#[test]
+fn test_protected_dead_branch_survives_in_go_code() {
+    let input = r#"{
+        "variables": {
+            "a": "boolean",
+            "b": "boolean"
+        },
+        "branches": [
+            {"condition": "a && b", "output": "return 1", "metadata": {"line": 10}},
+            {"condition": "a && !b", "output": "return 1", "metadata": {"line": 12}},
+            {"condition": "a", "output": "metrics.Inc()", "metadata": {"line": 14, "protected": true}},
+            {"condition": "!a && b", "output": "return 0", "metadata": {"line": 16}}
+        ],
+        "default": "return 0",
+        "context": {"language": "go"}
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    // Still reported as redundant, but flagged as protected
+    let dead_code = json["analysis"]["dead_code"].as_array().unwrap();
+    assert_eq!(dead_code.len(), 1);
+    assert_eq!(dead_code[0]["branch_index"], 2);
+    assert_eq!(dead_code[0]["protected"], true);
+
+    // Kept verbatim, right after the merged "return 1" branch
+    let branches = json["simplified_branches"].as_array().unwrap();
+    assert_eq!(branches[1]["condition"], "a");
+    assert_eq!(branches[1]["output"], "metrics.Inc()");
+    assert_eq!(branches[1]["protected"], true);
+
+    let code = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["kind"] == "simplification")
+        .and_then(|s| s["code"].as_str())
+        .expect("Should generate simplified code");
+    assert!(code.contains("if a {\n\treturn 1\n}"));
+    assert!(code.contains("else if a {\n\tmetrics.Inc()\n}"));
+}
+
+#[test]
+fn test_side_effect_branch_is_not_merged() {
+    let input = r#"{
+        "variables": {
+            "a": "boolean",
+            "b": "boolean"
+        },
+        "branches": [
+            {"condition": "a && b", "output": "audit_log()", "metadata": {"line": 10}},
+            {"condition": "a && !b", "output": "audit_log()", "metadata": {"line": 12, "has_side_effects": true}}
+        ],
+        "default": "skip()"
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    // Same output string, but the flagged branch stays on its own
+    let branches = json["simplified_branches"].as_array().unwrap();
+    let audit: Vec<&Value> = branches
+        .iter()
+        .filter(|b| b["output"] == "audit_log()")
+        .collect();
+    assert_eq!(audit.len(), 2);
+    assert_eq!(audit[1]["condition"], "a && !b");
+    assert_eq!(audit[1]["original_lines"], serde_json::json!([12]));
+    assert_eq!(audit[0]["original_lines"], serde_json::json!([10]));
+
+    let suggestions = json["suggestions"].as_array().unwrap();
+    let note = suggestions
+        .iter()
+        .find(|s| s["kind"] == "side_effects")
+        .expect("Should note the side-effecting branch");
+    assert_eq!(note["lines"], serde_json::json!([12]));
+}
+
+#[test]
+fn test_dead_side_effect_branch_is_kept() {
+    let input = r#"{
+        "variables": {
+            "a": "boolean",
+            "b": "boolean"
+        },
+        "branches": [
+            {"condition": "a", "output": "notify()", "metadata": {"line": 3}},
+            {"condition": "a && b", "output": "notify()", "metadata": {"line": 5, "has_side_effects": true}}
+        ]
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    // Unreachable, but not removable
+    let dead_code = json["analysis"]["dead_code"].as_array().unwrap();
+    assert_eq!(dead_code.len(), 1);
+    assert_eq!(dead_code[0]["protected"], true);
+
+    let branches = json["simplified_branches"].as_array().unwrap();
+    assert!(branches.iter().any(|b| b["condition"] == "a && b"));
+
+    let kinds: Vec<&str> = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|s| s["kind"].as_str())
+        .collect();
+    assert!(kinds.contains(&"side_effects"));
+    assert!(!kinds.contains(&"dead_code"));
+}
+
+#[test]
+fn test_duplicate_condition_with_different_output_is_flagged() {
+    let input = r#"{
+        "variables": {
+            "a": "boolean",
+            "b": "boolean"
+        },
+        "branches": [
+            {"condition": "a && b", "output": "grant()", "metadata": {"line": 3}},
+            {"condition": "b && a", "output": "deny()", "metadata": {"line": 5}}
+        ],
+        "default": "skip()"
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    let dead_code = json["analysis"]["dead_code"].as_array().unwrap();
+    assert_eq!(dead_code.len(), 1);
+    assert_eq!(dead_code[0]["reason"], "ConflictingDuplicate");
+    assert_eq!(dead_code[0]["covered_by"][0], 0);
+
+    let suggestion = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["kind"] == "duplicate_condition_different_output")
+        .expect("duplicate condition suggestion");
+    let message = suggestion["message"].as_str().unwrap();
+    assert!(message.contains("grant()") && message.contains("deny()"));
+    assert_eq!(suggestion["lines"], serde_json::json!([3, 5]));
+}
+
+#[test]
+fn test_contradictory_integer_comparisons_are_explained() {
+    let input = r#"{
+        "variables": {
+            "x": {"type": "integer", "min": 0, "max": 9}
+        },
+        "branches": [
+            {"condition": "x >= 2 && x <= 5", "output": "mid", "metadata": {"line": 2}},
+            {"condition": "x < 3 && x > 7", "output": "never", "metadata": {"line": 4}}
+        ],
+        "default": "other"
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    let dead_code = json["analysis"]["dead_code"].as_array().unwrap();
+    assert_eq!(dead_code.len(), 1);
+    assert_eq!(dead_code[0]["branch_index"], 1);
+    assert_eq!(dead_code[0]["reason"], "Contradiction");
+    assert_eq!(dead_code[0]["detail"], "x > 7 contradicts x < 3");
+
+    let suggestion = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["kind"] == "dead_code")
+        .expect("dead code suggestion");
+    assert_eq!(
+        suggestion["message"],
+        "Branch at line Some(4) is unreachable (Contradiction: x > 7 contradicts x < 3)"
+    );
+}
+
+const VERSION_TEST_BRANCHES: &str = r#"
+    "variables": {"a": "boolean", "b": "boolean"},
+    "branches": [
+        {"condition": "a && b", "output": "1"},
+        {"condition": "a && !b", "output": "1"}
+    ],
+    "default": "0"
+"#;
+
+#[test]
+fn test_unversioned_request_gets_v1_response() {
+    let input = format!("{{{}}}", VERSION_TEST_BRANCHES);
+    let result = agent_api::simplify_from_json(&input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+
+    assert!(json.get("api_version").is_none());
+    assert!(json.get("capabilities").is_none());
+    assert!(json["simplified_branches"].is_array());
+}
+
+#[test]
+fn test_versioned_request_gets_v2_envelope() {
+    for version_field in [r#""api_version": 2"#, r#""min_api_version": 1"#] {
+        let input = format!("{{{}, {}}}", version_field, VERSION_TEST_BRANCHES);
+        let result = agent_api::simplify_from_json(&input).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(json["api_version"], agent_api::API_VERSION);
+        let capabilities = json["capabilities"].as_array().unwrap();
+        assert!(capabilities.iter().any(|c| c == "integer_comparisons"));
+        assert!(json["simplified_branches"].is_array());
+    }
+}
+
+#[test]
+fn test_min_api_version_too_new_is_rejected() {
+    let input = format!(r#"{{"min_api_version": 99, {}}}"#, VERSION_TEST_BRANCHES);
+    let error = agent_api::simplify_from_json(&input).unwrap_err();
+    let json: Value = serde_json::from_str(&error).expect("error should be structured JSON");
+
+    assert_eq!(json["error"], "unsupported_api_version");
+    assert_eq!(json["requested_min_api_version"], 99);
+    assert_eq!(json["api_version"], agent_api::API_VERSION);
+    assert!(json["capabilities"].is_array());
+}
+
+#[test]
+fn test_debug_truth_table() {
+    let request = |context: &str| {
+        format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a && !b", "output": "1"}},
+                    {{"condition": "b", "output": "2"}}
+                ],
+                "context": {context}
+            }}"#
+        )
+    };
+
+    // Absent by default
+    let result = agent_api::simplify_from_json(&request("{}")).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    assert!(json.get("truth_table").is_none());
+
+    // Present when debug is on: minterm bit i is variables[i]
+    let result = agent_api::simplify_from_json(&request(r#"{"debug": true}"#)).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    let table = &json["truth_table"];
+    assert_eq!(table["variables"], serde_json::json!(["a", "b"]));
+    assert_eq!(table["outputs"]["1"], serde_json::json!([1]));
+    assert_eq!(table["outputs"]["2"], serde_json::json!([2, 3]));
+    assert_eq!(table["dont_cares"], serde_json::json!([0]));
+    assert_eq!(table["truncated"], false);
+
+    // Capped lists are marked as truncated
+    let result =
+        agent_api::simplify_from_json(&request(r#"{"debug": true, "debug_max_rows": 1}"#)).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(json["truth_table"]["outputs"]["2"], serde_json::json!([2]));
+    assert_eq!(json["truth_table"]["truncated"], true);
+}
+
 #[test]
 fn test_error_handling_invalid_json() {
     let input = "not valid json";
@@ -198,3 +506,469 @@ fn test_error_handling_unknown_type() {
     let result = agent_api::simplify_from_json(input);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_output_comparison_ignores_whitespace() {
+    let input = r#"{
+        "variables": {"a": "boolean", "b": "boolean"},
+        "branches": [
+            {"condition": "a && b", "output": "handleA(x, y)", "metadata": {"line": 1}},
+            {"condition": "a && !b", "output": "handleA(x,\n    y) ", "metadata": {"line": 3}}
+        ],
+        "default": "other()",
+        "context": {"output_comparison": "ignore_whitespace"}
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    let branches = json["simplified_branches"].as_array().unwrap();
+
+    assert_eq!(branches.len(), 2);
+    assert_eq!(branches[0]["condition"], "a");
+    assert_eq!(branches[0]["output"], "handleA(x, y)");
+    assert_eq!(branches[0]["original_lines"], serde_json::json!([1, 3]));
+
+    // Whitespace between tokens still separates outputs
+    let glued = input.replace(r#""handleA(x,\n    y) ""#, r#""handleA(x,y)""#);
+    let result = agent_api::simplify_from_json(&glued).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(json["simplified_branches"].as_array().unwrap().len(), 3);
+
+    let unknown = input.replace("ignore_whitespace", "fuzzy");
+    assert!(
+        agent_api::simplify_from_json(&unknown)
+            .unwrap_err()
+            .contains("Unknown output comparison")
+    );
+}
+
+#[test]
+fn test_heaviest_output_becomes_else() {
+    let input = r#"{
+        "variables": {"a": "boolean", "b": "boolean"},
+        "branches": [
+            {"condition": "a && b", "output": "return ERR", "weight": 1},
+            {"condition": "!a && b", "output": "return OK", "weight": 50},
+            {"condition": "a && !b", "output": "return RETRY", "weight": 4},
+            {"condition": "!a && !b", "output": "return OK", "weight": 45}
+        ],
+        "context": {"language": "go"}
+    }"#;
+
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    let branches = json["simplified_branches"].as_array().unwrap();
+
+    let outputs: Vec<&str> = branches
+        .iter()
+        .map(|b| b["output"].as_str().unwrap())
+        .collect();
+    assert_eq!(outputs, vec!["return RETRY", "return ERR", "return OK"]);
+    assert_eq!(branches[2]["is_default"], true);
+
+    let suggestions = json["suggestions"].as_array().unwrap();
+    let decision = suggestions
+        .iter()
+        .find(|s| s["kind"] == "weighted_default")
+        .expect("weighted_default suggestion");
+    assert_eq!(
+        decision["message"],
+        "Made 'return OK' the default branch based on weights"
+    );
+    let code = suggestions
+        .iter()
+        .find(|s| s["kind"] == "simplification")
+        .and_then(|s| s["code"].as_str())
+        .unwrap();
+    assert!(code.ends_with("} else {\n\treturn OK\n}\n"), "{}", code);
+
+    let negative = input.replace("\"weight\": 1}", "\"weight\": -1}");
+    assert!(
+        agent_api::simplify_from_json(&negative)
+            .unwrap_err()
+            .contains("Invalid weight")
+    );
+}
+
+#[test]
+fn test_branch_redundant_with_default() {
+    let request = |second: &str| {
+        format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a && !b", "output": "0", "metadata": {{"line": 3}}}},
+                    {{"condition": "{}", "output": "1", "metadata": {{"line": 4}}}}
+                ],
+                "default": "0"
+            }}"#,
+            second
+        )
+    };
+    let redundancies = |input: &str| -> Vec<String> {
+        let result = agent_api::simplify_from_json(input).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+        json["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|s| s["kind"] == "redundant_with_default")
+            .map(|s| s["message"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        redundancies(&request("b")),
+        vec!["Branch at line 3 is redundant with the default"]
+    );
+    // `a` would reach the second branch once the first is gone
+    assert!(redundancies(&request("a || b")).is_empty());
+}
+
+#[test]
+fn test_branch_combination_counts() {
+    let input = r#"{
+        "variables": {"flag": "boolean", "x": {"type": "integer", "min": 0, "max": 4}},
+        "branches": [{"condition": "flag && x >= 3", "output": "hi"}],
+        "default": "lo"
+    }"#;
+    let result = agent_api::simplify_from_json(input).unwrap();
+    let json: Value = serde_json::from_str(&result).unwrap();
+    let counts: Vec<(String, u64, f64)> = json["simplified_branches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| {
+            (
+                b["output"].as_str().unwrap().to_string(),
+                b["combinations_covered"].as_u64().unwrap(),
+                b["coverage_fraction"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("hi".to_string(), 2, 0.2), ("lo".to_string(), 8, 0.8)]
+    );
+}
+
+/// Apply a unified diff to `original`, checking the hunk headers against the hunk bodies
+fn apply_unified_diff(original: &str, patch: &str, first_line: usize) -> String {
+    let old: Vec<&str> = original.lines().collect();
+    let mut lines = patch.lines().peekable();
+    assert_eq!(lines.next(), Some("--- original"));
+    assert_eq!(lines.next(), Some("+++ simplified"));
+
+    let mut new = Vec::new();
+    let mut next_old = 0;
+    while let Some(header) = lines.next() {
+        let ranges = header
+            .strip_prefix("@@ -")
+            .and_then(|h| h.strip_suffix(" @@"))
+            .unwrap_or_else(|| panic!("bad hunk header: {}", header));
+        let (old_range, new_range) = ranges.split_once(" +").unwrap();
+        let parse = |range: &str| -> (usize, usize) {
+            let (start, len) = range.split_once(',').unwrap();
+            (start.parse().unwrap(), len.parse().unwrap())
+        };
+        let ((old_start, old_len), (_, new_len)) = (parse(old_range), parse(new_range));
+
+        let start = old_start - first_line;
+        new.extend_from_slice(&old[next_old..start]);
+        next_old = start;
+        let (mut seen_old, mut seen_new) = (0, 0);
+        while let Some(line) = lines.next_if(|line| !line.starts_with("@@")) {
+            let (marker, text) = line.split_at(1);
+            match marker {
+                " " => {
+                    assert_eq!(old[next_old], text);
+                    new.push(text);
+                    next_old += 1;
+                    seen_old += 1;
+                    seen_new += 1;
+                }
+                "-" => {
+                    assert_eq!(old[next_old], text);
+                    next_old += 1;
+                    seen_old += 1;
+                }
+                "+" => {
+                    new.push(text);
+                    seen_new += 1;
+                }
+                _ => panic!("bad hunk line: {}", line),
+            }
+        }
+        assert_eq!((seen_old, seen_new), (old_len, new_len), "{}", header);
+    }
+    new.extend_from_slice(&old[next_old..]);
+    new.join("\n")
+}
+
+#[test]
+fn test_patch_for_go_ladder() {
+    let original = "\tif a {\n\t\treturn 1\n\t} else if a && b {\n\t\treturn 2\n\t} else if !a {\n\t\treturn 0\n\t}";
+    let request = |line_of_dead_branch: &str| {
+        format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a", "output": "return 1", "metadata": {{"line": 10}}}},
+                    {{"condition": "a && b", "output": "return 2", "metadata": {{{}}}}},
+                    {{"condition": "!a", "output": "return 0", "metadata": {{"line": 14}}}}
+                ],
+                "context": {{"language": "go", "original_code": {}}}
+            }}"#,
+            line_of_dead_branch,
+            serde_json::to_string(original).unwrap()
+        )
+    };
+    let simplification = |input: &str| {
+        let json: Value =
+            serde_json::from_str(&agent_api::simplify_from_json(input).unwrap()).unwrap();
+        assert_eq!(json["simplified_branches"].as_array().unwrap().len(), 2);
+        json["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["kind"] == "simplification")
+            .cloned()
+            .expect("Should suggest a simplification")
+    };
+
+    let suggestion = simplification(&request(r#""line": 12"#));
+    let patch = suggestion["patch"]
+        .as_str()
+        .expect("Should produce a patch");
+    assert!(patch.contains("\n@@ -10,7 +10,8 @@\n"), "{}", patch);
+    assert!(
+        patch.contains("\n-\t} else if a && b {\n-\t\treturn 2\n"),
+        "{}",
+        patch
+    );
+    assert_eq!(
+        apply_unified_diff(original, patch, 10),
+        "\tif a {\n\t\treturn 1\n\t} else {\n\t\treturn 0\n\t}\n\
+         \t// QM-AGENT-DEAD: line 12\n\t// } else if a && b {\n\t// \treturn 2"
+    );
+
+    // Without the line of every branch the whole-construct code stays the only output
+    let suggestion = simplification(&request(""));
+    assert!(suggestion.get("patch").is_none());
+    assert!(
+        suggestion["code"]
+            .as_str()
+            .unwrap()
+            .contains("QM-AGENT-SIMPLIFIED")
+    );
+}
+
+#[test]
+fn test_patch_leaves_code_after_the_ladder() {
+    let ladder = "\tif a {\n\t\treturn 1\n\t} else if a && b {\n\t\treturn 2\n\t} else if !a {\n\t\treturn 0\n\t}";
+    for rest in ["\n\treturn -1\n}", "\n\n\tlog(a)\n\treturn -1\n}"] {
+        let original = format!("{}{}", ladder, rest);
+        let input = format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a", "output": "return 1", "metadata": {{"line": 10}}}},
+                    {{"condition": "a && b", "output": "return 2", "metadata": {{"line": 12}}}},
+                    {{"condition": "!a", "output": "return 0", "metadata": {{"line": 14}}}}
+                ],
+                "context": {{"language": "go", "original_code": {}}}
+            }}"#,
+            serde_json::to_string(&original).unwrap()
+        );
+        let json: Value =
+            serde_json::from_str(&agent_api::simplify_from_json(&input).unwrap()).unwrap();
+        let suggestion = json["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["kind"] == "simplification")
+            .cloned()
+            .expect("Should suggest a simplification");
+        let patch = suggestion["patch"]
+            .as_str()
+            .expect("Should produce a patch");
+
+        assert!(!patch.contains("return -1"), "{}", patch);
+        assert_eq!(
+            apply_unified_diff(&original, patch, 10),
+            format!(
+                "\tif a {{\n\t\treturn 1\n\t}} else {{\n\t\treturn 0\n\t}}\n\
+                 \t// QM-AGENT-DEAD: line 12\n\t// }} else if a && b {{\n\t// \treturn 2{}",
+                rest
+            )
+        );
+    }
+}
+
+#[test]
+fn test_merged_branch_comments_in_generated_code() {
+    let cases = [
+        (
+            "rust",
+            "if a && b { return 1 } // fast path",
+            "if a {\n\t// fast path\n\t// retry exhausted\n\treturn 1\n} else {\n\treturn 0\n}\n",
+        ),
+        (
+            "python",
+            "if a and b:  # fast path",
+            "if a:\n\t# fast path\n\t# retry exhausted\n\treturn 1\nelse:\n\treturn 0\n",
+        ),
+    ];
+    for (language, source, expected) in cases {
+        let input = format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a && b", "output": "return 1", "metadata": {{"source": {}}}}},
+                    {{"condition": "a && !b", "output": "return 1", "metadata": {{"comment": "retry exhausted"}}}},
+                    {{"condition": "a && !b", "output": "return 1", "metadata": {{"comment": "fast path"}}}}
+                ],
+                "default": "return 0",
+                "context": {{"language": "{}"}}
+            }}"#,
+            serde_json::to_string(source).unwrap(),
+            language
+        );
+        let json: Value =
+            serde_json::from_str(&agent_api::simplify_from_json(&input).unwrap()).unwrap();
+        assert_eq!(
+            json["simplified_branches"][0]["comment"],
+            "fast path\nretry exhausted"
+        );
+        let code = json["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["kind"] == "simplification")
+            .and_then(|s| s["code"].as_str())
+            .expect("generated code");
+        assert_eq!(code, expected, "{}", language);
+    }
+}
+
+#[test]
+fn test_unused_input_is_reported() {
+    let input = r#"{
+        "variables": {"enabled": "boolean", "debug_mode": "boolean"},
+        "branches": [
+            {"condition": "enabled && debug_mode", "output": "return 1"},
+            {"condition": "enabled && !debug_mode", "output": "return 1"}
+        ],
+        "default": "return 0"
+    }"#;
+    let json: Value = serde_json::from_str(&agent_api::simplify_from_json(input).unwrap()).unwrap();
+    let messages: Vec<&str> = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|s| s["kind"] == "unused_variable")
+        .filter_map(|s| s["message"].as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["Input 'debug_mode' does not affect the outcome and can be removed"]
+    );
+}
+
+#[test]
+fn test_visualization_on_request() {
+    let input = |visualize: bool| {
+        format!(
+            r#"{{
+                "variables": {{"a": "boolean", "b": "boolean"}},
+                "branches": [
+                    {{"condition": "a || b", "output": "return 1"}},
+                    {{"condition": "a && b", "output": "return 2"}}
+                ],
+                "default": "return 0",
+                "context": {{"visualize": {}}}
+            }}"#,
+            visualize
+        )
+    };
+    let json: Value =
+        serde_json::from_str(&agent_api::simplify_from_json(&input(false)).unwrap()).unwrap();
+    assert!(json.get("visualization").is_none());
+
+    let json: Value =
+        serde_json::from_str(&agent_api::simplify_from_json(&input(true)).unwrap()).unwrap();
+    let dot = json["visualization"]["dot"].as_str().unwrap();
+    assert!(
+        dot.contains("b0 [label=\"a || b => return 1\\nfirst match on 3\"];"),
+        "{}",
+        dot
+    );
+    assert!(
+        dot.contains("default [label=\"else => return 0\"];"),
+        "{}",
+        dot
+    );
+    assert!(dot.contains("b0 -> b1 [label=\"1 overlapping\""), "{}", dot);
+    let mermaid = json["visualization"]["mermaid"].as_str().unwrap();
+    assert!(mermaid.contains("class b1 dead"), "{}", mermaid);
+}
+
+#[test]
+fn test_negated_comparisons_are_normalized() {
+    // The side-effecting branch keeps its condition as written; the others are normalized
+    let input = r#"{
+        "variables": {
+            "x": {"type": "integer", "min": 0, "max": 7},
+            "ready": "boolean"
+        },
+        "branches": [
+            {"condition": "!(x < 2) && ready", "output": "start()"},
+            {"condition": "!(x == 3)", "output": "audit_log()", "metadata": {"has_side_effects": true}}
+        ],
+        "default": "wait()"
+    }"#;
+
+    let json: Value = serde_json::from_str(&agent_api::simplify_from_json(input).unwrap()).unwrap();
+    let conditions: Vec<&str> = json["simplified_branches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|b| b["condition"].as_str())
+        .collect();
+    assert_eq!(conditions[0], "(x >= 2) && ready");
+    assert_eq!(conditions[1], "!(x == 3)");
+    // x == 3 && !ready falls through to the default: 15 of 16 rows are covered
+    assert_eq!(json["analysis"]["coverage_percent"], 93.75);
+}
+
+#[test]
+fn test_already_analyzed_echoes_request_metrics() {
+    let input = r#"{
+        "variables": {"a": "boolean", "b": "boolean"},
+        "branches": [
+            {"condition": "a || b", "output": "return 1"},
+            {"condition": "a && b", "output": "return 2"},
+            {"condition": "!a && c", "output": "return 3"}
+        ],
+        "default": "return 0",
+        "context": {"already_analyzed": true}
+    }"#;
+    let json: Value = serde_json::from_str(&agent_api::simplify_from_json(input).unwrap()).unwrap();
+
+    assert_eq!(json["metrics"]["original_branches"], 3);
+    assert_eq!(
+        json["metrics"]["variables_used"],
+        serde_json::json!(["a", "b", "c"])
+    );
+    assert!(json["metrics"].get("simplified_branches").is_none());
+    assert!(json["metrics"].get("complexity_reduction").is_none());
+    // Branch 1 is dead, but nothing was analyzed
+    assert_eq!(json["analysis"]["dead_code"], serde_json::json!([]));
+    assert!(json["analysis"].get("coverage_percent").is_none());
+    assert_eq!(json["simplified_branches"], serde_json::json!([]));
+    assert_eq!(json["suggestions"][0]["kind"], "skipped_reanalysis");
+
+    // The branches are still parsed
+    let invalid = input.replace("!a && c", "!a &&");
+    assert!(agent_api::simplify_from_json(&invalid).is_err());
+}
@@ -6,7 +6,9 @@
 //
 // Run with: cargo test --test cnf_dnf_consistency_tests -- --ignored --nocapture
 
+use qm_agent::assert_seeded;
 use qm_agent::cnf_dnf::{self, OptimizedFor};
+use qm_agent::qm::random::test_seed;
 use qm_agent::qm::{Enc16, Enc32, Enc64};
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::time::Instant;
@@ -26,7 +28,10 @@ fn dnf_equal(dnf1: &[u64], dnf2: &[u64]) -> bool {
 #[test]
 #[ignore] // Run explicitly with: cargo test equality_test -- --ignored --nocapture
 fn equality_test() {
-    let mut rng = StdRng::from_os_rng();
+    // A fresh seed per run unless QM_TEST_SEED is set; a failure reports it for a replay
+    let seed = test_seed(rand::random());
+    println!("seed {seed}");
+    let mut rng = StdRng::seed_from_u64(seed);
     const MAX_EXPERIMENTS: usize = 100_000;
 
     for experiment in 0..MAX_EXPERIMENTS {
@@ -64,7 +69,9 @@ fn equality_test() {
                 println!(
                     " Experiment {experiment}: Enc64/X64 != Enc32/AutoDetect with {n_variables} variables"
                 );
-                panic!("DNF mismatch: Enc64/X64 vs Enc32/AutoDetect with {n_variables} variables");
+                panic!(
+                    "DNF mismatch: Enc64/X64 vs Enc32/AutoDetect with {n_variables} variables (seed {seed})"
+                );
             }
         }
 
@@ -76,7 +83,9 @@ fn equality_test() {
                 println!(
                     " Experiment {experiment}: Enc64/X64 != Enc16/AutoDetect with {n_variables} variables"
                 );
-                panic!("DNF mismatch: Enc64/X64 vs Enc16/AutoDetect with {n_variables} variables");
+                panic!(
+                    "DNF mismatch: Enc64/X64 vs Enc16/AutoDetect with {n_variables} variables (seed {seed})"
+                );
             }
         }
 
@@ -98,7 +107,10 @@ fn equality_test() {
 #[test]
 #[ignore] // Run explicitly with: cargo test equality_test_minimal -- --ignored --nocapture
 fn equality_test_minimal() {
-    let mut rng = StdRng::from_os_rng();
+    // A fresh seed per run unless QM_TEST_SEED is set; a failure reports it for a replay
+    let seed = test_seed(rand::random());
+    println!("seed {seed}");
+    let mut rng = StdRng::seed_from_u64(seed);
     const MAX_EXPERIMENTS: usize = 100_000;
 
     for experiment in 0..MAX_EXPERIMENTS {
@@ -170,7 +182,7 @@ fn equality_test_minimal() {
             println!("DNF_A (run 1): {dnf_a:?}");
             println!("DNF_B (run 2): {dnf_b:?}");
             panic!(
-                "Minimal DNF mismatch: {encoding_name}/AutoDetect produced inconsistent results with {n_variables} variables"
+                "Minimal DNF mismatch: {encoding_name}/AutoDetect produced inconsistent results with {n_variables} variables (seed {seed})"
             );
         }
 
@@ -192,7 +204,8 @@ fn equality_test_minimal() {
 #[test]
 fn quick_equality_smoke_test_cnf_dnf() {
     // Quick smoke test (runs as part of the normal test suite)
-    let mut rng = StdRng::seed_from_u64(42);
+    let seed = test_seed(42);
+    let mut rng = StdRng::seed_from_u64(seed);
 
     const N_EXPERIMENTS: usize = 10;
 
@@ -219,7 +232,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                 let dnf_64_a =
                     cnf_dnf::cnf_to_dnf::<Enc64>(&cnf, n_variables, OptimizedFor::Avx512_64bits)
                         .unwrap();
-                assert!(
+                assert_seeded!(
+                    seed,
                     dnf_equal(&dnf_64, &dnf_64_a),
                     "DNF mismatch: Enc64/X64 vs Enc64/Avx512_64bits with {} variables",
                     n_variables
@@ -235,7 +249,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                         OptimizedFor::Avx512_64bits,
                     )
                     .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc32/Avx512_64bits with {} variables",
                         n_variables
@@ -248,7 +263,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                         OptimizedFor::Avx512_32bits,
                     )
                     .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc32/Avx512_32bits with {} variables",
                         n_variables
@@ -264,7 +280,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                         OptimizedFor::Avx512_64bits,
                     )
                     .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc16/Avx512_64bits with {} variables",
                         n_variables
@@ -277,7 +294,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                         OptimizedFor::Avx512_32bits,
                     )
                     .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc16/Avx512_32bits with {} variables",
                         n_variables
@@ -290,7 +308,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                         OptimizedFor::Avx512_16bits,
                     )
                     .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc16/Avx512_16bits with {} variables",
                         n_variables
@@ -303,7 +322,8 @@ fn quick_equality_smoke_test_cnf_dnf() {
                     let dnf_x =
                         cnf_dnf::cnf_to_dnf::<Enc16>(&cnf, n_variables, OptimizedFor::Avx512_8bits)
                             .unwrap();
-                    assert!(
+                    assert_seeded!(
+                        seed,
                         dnf_equal(&dnf_64, &dnf_x),
                         "DNF mismatch: Enc64/X64 vs Enc16/Avx512_8bits with {} variables",
                         n_variables
@@ -0,0 +1,101 @@
+// CNF to DNF Determinism Tests
+//
+// All conversion functions return their products in canonical order (ascending number
+// of literals, then ascending value), independent of run and optimization level.
+
+use qm_agent::Enc16;
+use qm_agent::cnf_dnf::{
+    OptimizedFor, cnf_to_dnf, cnf_to_dnf_minimal, cnf_to_dnf_minimal_reference,
+    cnf_to_dnf_with_names,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+const N_RUNS: usize = 50;
+const N_VARIABLES: usize = 12;
+
+fn random_cnf(seed: u64) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..6)
+        .map(|_| {
+            let mut clause = 0u64;
+            for _ in 0..3 {
+                clause |= 1u64 << rng.random_range(0..N_VARIABLES);
+            }
+            clause
+        })
+        .collect()
+}
+
+fn is_canonical(dnf: &[u64]) -> bool {
+    dnf.windows(2)
+        .all(|w| (w[0].count_ones(), w[0]) < (w[1].count_ones(), w[1]))
+}
+
+fn assert_deterministic(name: &str, f: impl Fn(&[u64]) -> Vec<u64>) {
+    for seed in 0..5 {
+        let cnf = random_cnf(seed);
+        let first = f(&cnf);
+        assert!(
+            is_canonical(&first),
+            "{}: not canonical for seed {}",
+            name,
+            seed
+        );
+        for _ in 1..N_RUNS {
+            assert_eq!(
+                f(&cnf),
+                first,
+                "{}: differs between runs for seed {}",
+                name,
+                seed
+            );
+        }
+
+        // Clause order must not matter either
+        let mut reversed = cnf.clone();
+        reversed.reverse();
+        assert_eq!(f(&reversed), first, "{}: depends on clause order", name);
+    }
+}
+
+#[test]
+fn test_cnf_to_dnf_deterministic() {
+    assert_deterministic("cnf_to_dnf", |cnf| {
+        cnf_to_dnf::<Enc16>(cnf, N_VARIABLES, OptimizedFor::AutoDetect).unwrap()
+    });
+    assert_deterministic("cnf_to_dnf X64", |cnf| {
+        cnf_to_dnf::<Enc16>(cnf, N_VARIABLES, OptimizedFor::X64).unwrap()
+    });
+}
+
+#[test]
+fn test_cnf_to_dnf_minimal_deterministic() {
+    assert_deterministic("cnf_to_dnf_minimal", |cnf| {
+        cnf_to_dnf_minimal::<Enc16>(cnf, N_VARIABLES, OptimizedFor::AutoDetect).unwrap()
+    });
+    assert_deterministic("cnf_to_dnf_minimal_reference", |cnf| {
+        cnf_to_dnf_minimal_reference::<Enc16>(cnf, N_VARIABLES, OptimizedFor::AutoDetect).unwrap()
+    });
+}
+
+#[test]
+fn test_cnf_to_dnf_with_names_deterministic() {
+    let cnf: Vec<Vec<String>> = random_cnf(7)
+        .iter()
+        .map(|&clause| {
+            (0..N_VARIABLES)
+                .filter(|&i| (clause >> i) & 1 == 1)
+                .map(|i| format!("v{}", i))
+                .collect()
+        })
+        .collect();
+
+    let first = cnf_to_dnf_with_names(&cnf).unwrap();
+    for _ in 1..N_RUNS {
+        assert_eq!(cnf_to_dnf_with_names(&cnf).unwrap(), first);
+    }
+    assert!(
+        first.windows(2).all(|w| w[0].len() <= w[1].len()),
+        "products not ordered by size"
+    );
+}
@@ -5,7 +5,7 @@
 use std::collections::HashSet;
 
 use qm_agent::qm::{Enc16, Enc64};
-use qm_agent::{QMSolver, SolveMethod};
+use qm_agent::{QMResult, QMSolver, SolveMethod};
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -29,50 +29,11 @@ fn gen_random(n_vars: u8, density: f64, seed: u64) -> Vec<u64> {
         .collect()
 }
 
-/// Evaluate an SOP expression against all 2^n minterms; return a bitmask.
-fn eval_expr(expr: &str, n_vars: usize) -> u64 {
-    let mut cover = 0u64;
-    for mt in 0..(1u64 << n_vars) {
-        if eval_term(expr, mt) {
-            cover |= 1u64 << mt;
-        }
-    }
-    cover
-}
-
-fn eval_term(expr: &str, mt: u64) -> bool {
-    for part in expr.split('+').map(|s| s.trim()) {
-        if part.is_empty() {
-            continue;
-        }
-        if eval_cube(part, mt) {
-            return true;
-        }
-    }
-    false
-}
-
-fn eval_cube(cube: &str, mt: u64) -> bool {
-    let mut one = 0u64;
-    let mut zero = 0u64;
-    let chars: Vec<char> = cube.chars().collect();
-    let mut i = 0;
-    while i < chars.len() {
-        let c = chars[i];
-        if !c.is_alphabetic() {
-            i += 1;
-            continue;
-        }
-        let var = (c as u8 - b'A') as usize;
-        i += 1;
-        if i < chars.len() && chars[i] == '\'' {
-            zero |= 1u64 << var;
-            i += 1;
-        } else {
-            one |= 1u64 << var;
-        }
-    }
-    (mt & one) == one && (mt & zero) == 0
+/// Evaluate the minimized function against all 2^n minterms; return a bitmask.
+fn eval_result(result: &QMResult, n_vars: usize) -> u64 {
+    (0..1u64 << n_vars)
+        .filter(|&mt| result.evaluate(mt))
+        .fold(0, |cover, mt| cover | 1u64 << mt)
 }
 
 /// Test Classic vs min-cubes with u32 encoding (≤16 vars).
@@ -94,7 +55,8 @@ fn check_16(n_vars: usize, minterms: Vec<u32>, dont_cares: Option<Vec<u32>>) {
     if let Some(ref dc) = dont_cares {
         solver1.set_dont_cares(dc.clone());
     }
-    let expr1 = solver1.solve().minimized_expression;
+    let result1 = solver1.solve();
+    let expr1 = &result1.minimized_expression;
     assert!(!expr1.is_empty() && expr1 != "0", "n={} expr1 empty minterms={:?} dc={:?}", n_vars, minterms, dont_cares);
 
     let mut solver2 = QMSolver::<Enc16>::new(n_vars);
@@ -103,11 +65,12 @@ fn check_16(n_vars: usize, minterms: Vec<u32>, dont_cares: Option<Vec<u32>>) {
         solver2.set_dont_cares(dc.clone());
     }
     solver2.set_method(SolveMethod::MinCubes);
-    let expr2 = solver2.solve().minimized_expression;
+    let result2 = solver2.solve();
+    let expr2 = &result2.minimized_expression;
     assert!(!expr2.is_empty() && expr2 != "0", "n={} expr2 empty", n_vars);
 
-    let c1 = eval_expr(&expr1, n_vars);
-    let c2 = eval_expr(&expr2, n_vars);
+    let c1 = eval_result(&result1, n_vars);
+    let c2 = eval_result(&result2, n_vars);
 
     // Both must cover all required minterms
     for &m in &cover_expected {
@@ -145,19 +108,21 @@ fn check_128(n_vars: usize, minterms: Vec<u128>) {
 
     let mut solver1 = QMSolver::<Enc64>::new(n_vars);
     solver1.set_minterms(minterms.clone());
-    let expr1 = solver1.solve().minimized_expression;
+    let result1 = solver1.solve();
+    let expr1 = &result1.minimized_expression;
     assert!(!expr1.is_empty() && expr1 != "0", "n={} expr1 empty mt=[{:?}...]", n_vars, &minterms[..3.min(minterms.len())]);
 
     let mut solver2 = QMSolver::<Enc64>::new(n_vars);
     solver2.set_minterms(minterms);
     solver2.set_method(SolveMethod::MinCubes);
-    let expr2 = solver2.solve().minimized_expression;
+    let result2 = solver2.solve();
+    let expr2 = &result2.minimized_expression;
     assert!(!expr2.is_empty() && expr2 != "0", "n={} expr2 empty", n_vars);
 
     // Both must cover expected minterms (up to 64-bit bitmask)
     for &m in &cover_expected {
-        let c1 = eval_expr(&expr1, n_vars);
-        let c2 = eval_expr(&expr2, n_vars);
+        let c1 = eval_result(&result1, n_vars);
+        let c2 = eval_result(&result2, n_vars);
         assert!(
             c1 & (1u64 << m) != 0,
             "{} classic missed minterm {}", n_vars, m
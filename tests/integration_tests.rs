@@ -29,6 +29,90 @@ fn test_minimize_simple_format() {
         .stdout(predicate::str::contains("Prime Implicants"));
 }
 
+#[test]
+fn test_minimize_rust_fn_format() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg(r#"{"minterms": [1, 3], "variables": 2}"#)
+        .arg("-f")
+        .arg("rust-fn");
+
+    cmd.assert()
+        .success()
+        .stdout("pub fn f(a: bool, b: bool) -> bool {\n    a\n}\n");
+
+    // Named variables become snake-case parameters; a name that is no identifier fails
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(Ready, Busy) = Σ(1)")
+        .arg("-f")
+        .arg("rust-fn");
+    cmd.assert()
+        .success()
+        .stdout("pub fn f(ready: bool, busy: bool) -> bool {\n    ready && !busy\n}\n");
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(größe, b) = Σ(1)")
+        .arg("-f")
+        .arg("rust-fn");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("not an ASCII identifier"));
+}
+
+#[test]
+fn test_minimize_hex_and_binary_minterms() {
+    // Σ(1,3) + d(5,7) written in three radixes
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(A,B,C) = Σ(0b001, 0x3) + d(5, 0b1_11)")
+        .arg("-f")
+        .arg("json");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""minimized_sop": "A""#));
+}
+
+#[test]
+fn test_minimize_rejects_minterm_beyond_variable_count() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("minimize minterms 0x1, 0xFFFF_FFFF with 8 variables");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Minterm 4294967295 (0xffffffff) is out of range for 8 variables",
+    ));
+}
+
+#[test]
+fn test_minimize_rejects_invalid_variable_names() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg(r#"{"minterms": [1, 3], "variables": 3, "variable_names": ["A", "A'"]}"#);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid variable names: 2 names given for 3 variables; name 1 'A'' contains ' or !",
+    ));
+}
+
+#[test]
+fn test_minimize_reports_invalid_minterm_token() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize").arg("-i").arg("f(A,B) = Σ(1, 0b12)");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("'0b12' at index 1"));
+}
+
 #[test]
 fn test_minimize_truth_table() {
     let mut cmd = Command::cargo_bin("qm-agent").unwrap();
@@ -39,6 +123,82 @@ fn test_minimize_truth_table() {
         .stdout(predicate::str::contains("Minimized Expression"));
 }
 
+#[test]
+fn test_minimize_leaves_out_large_truth_tables() {
+    let json = |variables: usize, extra: &[&str]| -> serde_json::Value {
+        let input = format!("minimize minterms 1,3 with {} variables", variables);
+        let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+        let output = cmd
+            .args(["minimize", "-i", &input, "-f", "json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    // 2^12 rows is within the default limit of 4096, 2^20 is not
+    let rows = |json: &serde_json::Value| json["truth_table"].as_str().map(|t| t.lines().count());
+    assert_eq!(rows(&json(12, &[])), Some(2 + 4096));
+    assert_eq!(rows(&json(20, &[])), None);
+    assert_eq!(rows(&json(12, &["--max-rows", "100"])), None);
+    assert_eq!(rows(&json(14, &[])), None);
+    assert_eq!(rows(&json(14, &["--full-table"])), Some(2 + (1 << 14)));
+}
+
+#[test]
+fn test_truth_table_command_truncates() {
+    let spec = "minimize minterms 1,3 with 20 variables";
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["truth-table", "-i", spec])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "... 1044480 more rows not shown (1048576 in total)\n",
+        ));
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args([
+        "truth-table",
+        "-i",
+        "A'B + AC",
+        "--max-rows",
+        "2",
+        "-f",
+        "csv",
+    ])
+    .assert()
+    .success()
+    .stdout("A,B,C,output\n0,0,0,0\n1,0,0,0\n")
+    .stderr(predicate::str::contains("6 more rows not shown"));
+}
+
+#[test]
+fn test_minimize_small_truth_tables() {
+    for (table, expression) in [
+        ("01", "F = A"),
+        ("10", "F = A'"),
+        ("1", "F = 1"),
+        ("0", "F = 0"),
+    ] {
+        let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+        cmd.arg("minimize")
+            .arg("-i")
+            .arg(format!("truth table: {}", table));
+
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(expression));
+    }
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize").arg("-i").arg("truth table: 011");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a power of two"));
+}
+
 #[test]
 fn test_minimize_with_steps() {
     let mut cmd = Command::cargo_bin("qm-agent").unwrap();
@@ -52,6 +212,113 @@ fn test_minimize_with_steps() {
         .stdout(predicate::str::contains("Solution Steps"));
 }
 
+#[test]
+fn test_minimize_steps_format_renders_tables() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(A,B,C) = Σ(1,3) + d(5,7)")
+        .arg("-f")
+        .arg("steps");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Pass 1: terms grouped by number of ones",
+        ))
+        .stdout(predicate::str::contains("001    | 011    | 0X1"))
+        .stdout(predicate::str::contains("* XX1²⁻³ = A"));
+}
+
+#[test]
+fn test_minimize_stats_shows_iterations() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("minimize minterms 0,1,2,3 with 2 variables")
+        .arg("--stats");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Iterations: 3"))
+        .stderr(predicate::str::contains(
+            "pass 1: 4 -> 4 terms (4 pair comparisons, classic)",
+        ));
+}
+
+#[test]
+fn test_minimize_mentions_essential_only_cover() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("minimize minterms 1,3 with 2 variables");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "cover completed by essential implicants alone",
+    ));
+
+    // Cyclic core: there are no essentials, Petrick's method picks the cover
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("minimize minterms 0,1,5,7,8,10,14,15 with 4 variables");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("essential implicants alone").not());
+}
+
+#[test]
+fn test_minimize_reports_used_dont_cares() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(A,B,C) = Σ(1,3) + d(5,6,7)");
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Don't-cares assigned to 1: 2 of 3",
+    ));
+}
+
+#[test]
+fn test_minimize_reports_removed_duplicates() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("minimize minterms 3,1,3,7 with 3 variables");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Minterms: [1, 3, 7]"))
+        .stdout(predicate::str::contains("Duplicates removed: 1"));
+}
+
+#[test]
+fn test_equivalent_command() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("equivalent")
+        .arg("-a")
+        .arg("A'B + AB")
+        .arg("-b")
+        .arg("B");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Expressions are equivalent"));
+}
+
+#[test]
+fn test_equivalent_command_reports_counterexample() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("equivalent")
+        .arg("-a")
+        .arg("A + B")
+        .arg("-b")
+        .arg("B");
+
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Counterexample: A=1, B=0"));
+}
+
 #[test]
 fn test_minimize_table_format() {
     let mut cmd = Command::cargo_bin("qm-agent").unwrap();
@@ -81,6 +348,192 @@ fn test_minimize_from_file() {
         .stdout(predicate::str::contains("Minimized Expression"));
 }
 
+#[test]
+fn test_minimize_c_style_expression() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("(!ready && valid) || force");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("F = ready'valid + force"));
+}
+
+#[test]
+fn test_minimize_python_style_expression() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("not ready and valid or force")
+        .arg("-f")
+        .arg("json");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"minimized_sop\": \"ready'valid + force\"",
+    ));
+}
+
+#[test]
+fn test_minimize_from_stdin() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", "-", "-f", "json"])
+        .write_stdin(r#"{"minterms": [1, 3, 7], "variables": 3}"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("minimized_sop"));
+}
+
+#[test]
+fn test_minimize_writes_output_file() {
+    let output = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", "f(A,B,C) = Σ(1,3,7)", "-f", "json", "-o"])
+        .arg(output.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let written = std::fs::read_to_string(output.path()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(json["original_minterms"], serde_json::json!([1, 3, 7]));
+}
+
+#[test]
+fn test_minimize_exports_cover_dimacs() {
+    let dimacs = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args([
+        "minimize",
+        "-i",
+        "f(A,B,C) = Σ(0,1,2,5,6,7)",
+        "--export-cover-dimacs",
+    ])
+    .arg(dimacs.path())
+    .assert()
+    .success();
+
+    // Six prime implicants of two minterms each: every minterm is a clause of two
+    let written = std::fs::read_to_string(dimacs.path()).unwrap();
+    assert!(written.contains("p cnf 6 6\n"), "{}", written);
+    assert_eq!(written.lines().filter(|l| l.starts_with("c ")).count(), 6);
+    assert!(
+        written
+            .lines()
+            .skip_while(|l| !l.starts_with("p "))
+            .skip(1)
+            .all(|l| l.split(' ').count() == 3 && l.ends_with(" 0"))
+    );
+}
+
+#[test]
+fn test_minimize_human_report_goes_to_stderr_with_output_file() {
+    let output = NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", "f(A,B) = Σ(1,3)", "-o"])
+        .arg(output.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("Minimized Expression"));
+
+    let written = std::fs::read_to_string(output.path()).unwrap();
+    assert_eq!(written.trim(), "A");
+}
+
+const REDUNDANT_PLA: &str = "\
+.i 3
+.o 1
+000 1
+001 1
+00- 1
+1-1 1
+111 1
+.e
+";
+
+#[test]
+fn test_minimize_pla_prints_espresso_stats() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(REDUNDANT_PLA.as_bytes()).unwrap();
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i"])
+        .arg(temp_file.path())
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("F = A'B' + AC").or(predicate::str::contains("F = AC + A'B'")))
+        .stderr(predicate::str::contains(
+            "PLA: in 5 terms, 13 literals -> out 2 terms, 4 literals (-60.0% terms, -69.2% literals)",
+        ))
+        .stderr(predicate::str::contains("equivalent to the input cover"));
+}
+
+#[test]
+fn test_minimize_check_needs_pla_input() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", "f(A,B) = Σ(1,3)", "--check"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--check needs PLA input"));
+}
+
+const SHARED_TERM_PLA: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/shared_term.pla"
+);
+
+#[test]
+fn test_minimize_pla_outputs() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    let output = cmd
+        .args(["minimize", "-i", SHARED_TERM_PLA, "-f", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let outputs = json["outputs"].as_array().unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0]["name"], "f0");
+    assert_eq!(outputs[1]["name"], "f1");
+    assert_eq!(
+        outputs[1]["original_minterms"],
+        serde_json::json!([0, 2, 3, 7])
+    );
+    assert_eq!(
+        json["shared_terms"],
+        serde_json::json!([{"term": "AB", "outputs": ["f0", "f1"]}])
+    );
+}
+
+#[test]
+fn test_minimize_selected_pla_output() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", SHARED_TERM_PLA, "--outputs", "f1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("f1 = "))
+        .stdout(predicate::str::contains("f0 = ").not())
+        .stdout(predicate::str::contains("• None"));
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", SHARED_TERM_PLA, "--outputs", "carry"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no output 'carry'; the outputs are f0, f1",
+        ));
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.args(["minimize", "-i", "f(A,B) = Σ(1,3)", "--outputs", "all"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--outputs needs PLA input"));
+}
+
 #[test]
 fn test_examples_command() {
     let mut cmd = Command::cargo_bin("qm-agent").unwrap();
@@ -120,3 +573,108 @@ fn test_minimize_help() {
         .success()
         .stdout(predicate::str::contains("Minimize a Boolean function"));
 }
+
+#[test]
+fn test_json_output_keeps_field_names() {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg("f(A,B,C) = Σ(1,3,7) + d(2,4)")
+        .arg("-f")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let fixture: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/minimize_response_v0.json")).unwrap();
+    for key in fixture.as_object().unwrap().keys() {
+        assert!(json.get(key).is_some(), "missing key {}", key);
+    }
+    // Steps were not requested
+    assert!(json["steps"].is_null());
+}
+
+#[test]
+fn test_old_json_output_deserializes() {
+    let old: qm_agent::QMResult =
+        serde_json::from_str(include_str!("fixtures/minimize_response_v0.json")).unwrap();
+    assert_eq!(old.minimized_expression, "AC' + AB");
+    assert_eq!(old.minterms, vec![1, 3, 7]);
+    assert!(old.solution_steps.is_empty());
+    // Fields added later take their defaults
+    assert!(old.used_dont_cares.is_empty() && old.unused_dont_cares.is_empty());
+    assert!(!old.essential_only_cover);
+    assert!(old.cover.is_empty());
+    assert!(!old.complemented);
+
+    let with_steps: qm_agent::QMResult =
+        serde_json::from_str(include_str!("fixtures/minimize_response_v0_steps.json")).unwrap();
+    assert!(!with_steps.solution_steps.is_empty());
+}
+
+/// Write the JSON output of `minimize -i input` to a temporary file
+fn minimize_to_file(input: &str) -> NamedTempFile {
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("minimize")
+        .arg("-i")
+        .arg(input)
+        .arg("-f")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(&output).unwrap();
+    file
+}
+
+#[test]
+fn test_diff_command_equivalent_covers() {
+    // Turning minterm 4 into a don't-care changes the cover, not the function
+    let a = minimize_to_file("f(A,B,C) = Σ(1,3,4,6,7)");
+    let b = minimize_to_file("f(A,B,C) = Σ(1,3,6,7) + d(4)");
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("diff")
+        .arg("-a")
+        .arg(a.path())
+        .arg("-b")
+        .arg(b.path());
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Covers are equivalent"));
+}
+
+#[test]
+fn test_diff_command_different_covers() {
+    let a = minimize_to_file("f(A,B,C) = Σ(1,3,4,7)");
+    let b = minimize_to_file("f(A,B,C) = Σ(1,3,7)");
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("diff")
+        .arg("-a")
+        .arg(a.path())
+        .arg("-b")
+        .arg(b.path());
+
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("only in a"))
+        .stdout(predicate::str::contains(
+            "Covers implement different functions",
+        ));
+}
+
+#[test]
+fn test_diff_command_rejects_result_without_cover() {
+    let a = minimize_to_file("f(A,B,C) = Σ(1,3,7) + d(2,4)");
+    let old = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/minimize_response_v0.json"
+    );
+
+    let mut cmd = Command::cargo_bin("qm-agent").unwrap();
+    cmd.arg("diff").arg("-a").arg(a.path()).arg("-b").arg(old);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("result b has no cover"));
+}
@@ -0,0 +1,84 @@
+// Heap use of the prime implicant search, measured with a counting global allocator
+//
+// The only test in this binary, so no other thread allocates while it measures.
+
+use qm_agent::{Enc16, Implicant, QuineMcCluskey};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: forwarded unchanged to the system allocator
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: forwarded unchanged to the system allocator
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Most bytes allocated at once by `f` on top of what was live before, and its result
+fn peak_heap<T>(f: impl FnOnce() -> T) -> (usize, T) {
+    let before = LIVE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let result = f();
+    (PEAK.load(Ordering::Relaxed) - before, result)
+}
+
+#[test]
+fn test_prime_implicant_search_heap() {
+    const N: usize = 12;
+
+    // A yardstick, not the old code path: the 924·64 cubes with six don't-cares that
+    // merging the full function passes through, each owning the set of minterms it covers
+    let (owned_level, level) = peak_heap(|| {
+        let mut level = Vec::new();
+        for free in (0u32..1 << N).filter(|free| free.count_ones() == 6) {
+            for fixed in (0u32..1 << N).filter(|fixed| fixed & free == 0) {
+                let pattern: String = (0..N)
+                    .rev()
+                    .map(|i| match (free >> i & 1, fixed >> i & 1) {
+                        (1, _) => 'X',
+                        (_, 1) => '1',
+                        _ => '0',
+                    })
+                    .collect();
+                level.push(Implicant::<Enc16>::from_pattern(&pattern).unwrap());
+            }
+        }
+        level
+    });
+    assert_eq!(level.len(), 924 * 64);
+    drop(level);
+
+    // Passes work on raw encodings and only the prime implicants own their sets, so the
+    // whole search stays below holding that one pass as owned implicants
+    let (search, (primes, _)) = peak_heap(|| {
+        let mut qm = QuineMcCluskey::<Enc16>::new(N);
+        qm.set_minterms((0..1 << N).collect());
+        qm.find_essential_prime_implicants()
+    });
+    assert_eq!(primes.len(), 1);
+    assert_eq!(primes[0].covered_minterms.len(), 1 << N);
+    assert!(
+        search < owned_level,
+        "search peaked at {} bytes, one pass of owned implicants takes {}",
+        search,
+        owned_level
+    );
+}
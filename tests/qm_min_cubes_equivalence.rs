@@ -34,53 +34,12 @@ fn gen_random_density(n: u8, density: f64, seed: u64) -> Vec<u64> {
     out
 }
 
-/// Parse and evaluate the expression string against all 2^n minterms.
+/// Evaluate the minimized function against all 2^n minterms.
 /// Returns a bitmask of covered minterms.
-fn eval_expression(expr: &str, n_vars: usize) -> u64 {
-    let mut covered = 0u64;
-    let limit = 1u64 << n_vars;
-    for minterm in 0..limit {
-        if evaluate_term(expr, minterm) {
-            covered |= 1u64 << minterm;
-        }
-    }
-    covered
-}
-
-fn evaluate_term(expr: &str, minterm: u64) -> bool {
-    for term in expr.split('+') {
-        let term = term.trim();
-        if term.is_empty() {
-            continue;
-        }
-        if evaluate_product_term(term, minterm) {
-            return true;
-        }
-    }
-    false
-}
-
-fn evaluate_product_term(term: &str, minterm: u64) -> bool {
-    let mut zero_bits = 0u64;
-    let mut one_bits = 0u64;
-    let chars: Vec<char> = term.chars().collect();
-    let mut i = 0;
-    while i < chars.len() {
-        let c = chars[i];
-        if !c.is_alphabetic() {
-            i += 1;
-            continue;
-        }
-        let var_idx = (c as u8 - b'A') as usize;
-        i += 1;
-        if i < chars.len() && chars[i] == '\'' {
-            zero_bits |= 1u64 << var_idx;
-            i += 1;
-        } else {
-            one_bits |= 1u64 << var_idx;
-        }
-    }
-    (minterm & one_bits) == one_bits && (minterm & zero_bits) == 0
+fn eval_result(result: &qm_agent::QMResult, n_vars: usize) -> u64 {
+    (0..1u64 << n_vars)
+        .filter(|&minterm| result.evaluate(minterm))
+        .fold(0, |covered, minterm| covered | 1u64 << minterm)
 }
 
 // ---- Small n (Enc32::Value = u64) ----
@@ -93,8 +52,8 @@ fn eq_test_and_3() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 3);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 3);
+    let qm_covered = eval_result(&qm_result, 3);
+    let mc_covered = eval_result(&mc_result, 3);
 
     assert_eq!(
         qm_covered, mc_covered,
@@ -120,8 +79,8 @@ fn eq_test_or_3() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 3);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 3);
+    let qm_covered = eval_result(&qm_result, 3);
+    let mc_covered = eval_result(&mc_result, 3);
 
     assert_eq!(
         qm_covered, mc_covered,
@@ -138,8 +97,8 @@ fn eq_test_xor_3() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 3);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 3);
+    let qm_covered = eval_result(&qm_result, 3);
+    let mc_covered = eval_result(&mc_result, 3);
 
     assert_eq!(
         qm_covered, mc_covered,
@@ -156,8 +115,8 @@ fn eq_test_xor_4() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 4);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 4);
+    let qm_covered = eval_result(&qm_result, 4);
+    let mc_covered = eval_result(&mc_result, 4);
 
     assert_eq!(
         qm_covered, mc_covered,
@@ -177,8 +136,8 @@ fn eq_test_with_dontcares() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let _qm_covered = eval_expression(&qm_result.minimized_expression, 3);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 3);
+    let _qm_covered = eval_result(&qm_result, 3);
+    let mc_covered = eval_result(&mc_result, 3);
 
     // QM greedy is known to diverge from exact B&B on dont-care cases.
     // min-cubes B&B gives correct B' coverage (minterms 0,1,4,5).
@@ -203,8 +162,8 @@ fn eq_test_maj_5() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 5);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 5);
+    let qm_covered = eval_result(&qm_result, 5);
+    let mc_covered = eval_result(&mc_result, 5);
 
     assert_eq!(
         qm_covered,
@@ -226,8 +185,8 @@ fn eq_test_dense_5() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 5);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 5);
+    let qm_covered = eval_result(&qm_result, 5);
+    let mc_covered = eval_result(&mc_result, 5);
 
     // QM greedy may diverge from exact B&B on dense problems.
     assert!(
@@ -252,8 +211,8 @@ fn eq_test_sparse_6() {
     let qm_result = solver.solve();
     let mc_result = solver.solve_min_cubes();
 
-    let qm_covered = eval_expression(&qm_result.minimized_expression, 6);
-    let mc_covered = eval_expression(&mc_result.minimized_expression, 6);
+    let qm_covered = eval_result(&qm_result, 6);
+    let mc_covered = eval_result(&mc_result, 6);
 
     assert!(
         mc_covered != 0,
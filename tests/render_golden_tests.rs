@@ -0,0 +1,75 @@
+// Golden-file tests for the step-by-step renderers and the `truth-table` command
+//
+// Regenerate with: UPDATE_GOLDEN=1 cargo test --test render_golden_tests
+
+use assert_cmd::Command;
+use qm_agent::Enc32;
+use qm_agent::qm::render::render_steps;
+use std::path::Path;
+
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
+
+fn names(n: usize) -> Vec<String> {
+    (0..n)
+        .map(|i| ((b'A' + i as u8) as char).to_string())
+        .collect()
+}
+
+#[test]
+fn test_steps_classic_4var() {
+    // f(A,B,C,D) = Σ(0,1,2,5,6,7)
+    let minterms: Vec<u64> = vec![0, 1, 2, 5, 6, 7];
+    let rendered = render_steps::<Enc32>(&minterms, &[], 4, &names(4));
+    check_golden("steps_sum_0_1_2_5_6_7.txt", &rendered);
+}
+
+#[test]
+fn test_steps_with_dont_cares() {
+    // f(A,B,C) = Σ(1,3) + d(5,7)
+    let minterms: Vec<u64> = vec![1, 3];
+    let dont_cares: Vec<u64> = vec![5, 7];
+    let rendered = render_steps::<Enc32>(&minterms, &dont_cares, 3, &names(3));
+    check_golden("steps_sum_1_3_dc_5_7.txt", &rendered);
+}
+
+fn truth_table_command(args: &[&str]) -> String {
+    let output = Command::cargo_bin("qm-agent")
+        .unwrap()
+        .arg("truth-table")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_truth_table_sum_with_dont_cares() {
+    let spec = "f(A,B,C) = Σ(1,3,7) + d(2)";
+    check_golden(
+        "truth_table_sum_1_3_7_dc_2.txt",
+        &truth_table_command(&["-i", spec]),
+    );
+    check_golden(
+        "truth_table_sum_1_3_7_dc_2.md",
+        &truth_table_command(&["-i", spec, "--format", "markdown"]),
+    );
+}
+
+#[test]
+fn test_truth_table_expression() {
+    check_golden(
+        "truth_table_expr_a_b_ac.txt",
+        &truth_table_command(&["-i", "f(A,B,C) = A'B + AC"]),
+    );
+}
@@ -0,0 +1,210 @@
+// Golden corpus for `simplify_from_json`: realistic branch sets in tests/simplify_corpus
+//
+// Each `NAME.request.json` is simplified; the response must make the same first-match
+// decision as the request for every input, and must equal `NAME.response.json`.
+// Regenerate the responses with: UPDATE_GOLDEN=1 cargo test --test simplify_corpus_tests
+
+use qm_agent::agent_api::simplify_from_json;
+use qm_agent::simplify::analyzer::evaluate_with_ints;
+use qm_agent::simplify::{BoolExpr, parse_bool_expr};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A branch chain: `(condition, output)` pairs tried in order, then the default
+struct Chain {
+    branches: Vec<(BoolExpr, String)>,
+    default: Option<String>,
+}
+
+impl Chain {
+    fn decide(&self, bools: &HashMap<String, bool>, ints: &HashMap<String, i32>) -> Option<&str> {
+        self.branches
+            .iter()
+            .find(|(condition, _)| evaluate_with_ints(condition, bools, ints))
+            .map(|(_, output)| output.as_str())
+            .or(self.default.as_deref())
+    }
+}
+
+/// Variable name with its domain; booleans are `(0, 1)`
+fn domains(request: &Value) -> Vec<(String, bool, i32, i32)> {
+    let mut domains: Vec<_> = request["variables"]
+        .as_object()
+        .expect("variables object")
+        .iter()
+        .map(|(name, spec)| match spec {
+            Value::String(_) => (name.clone(), true, 0, 1),
+            _ if spec["type"] == "boolean" || spec["type"] == "bool" => (name.clone(), true, 0, 1),
+            _ => (
+                name.clone(),
+                false,
+                spec["min"].as_i64().unwrap_or(0) as i32,
+                spec["max"].as_i64().expect("integer max") as i32,
+            ),
+        })
+        .collect();
+    domains.sort();
+    domains
+}
+
+fn parse(condition: &Value) -> BoolExpr {
+    let condition = condition.as_str().expect("condition string");
+    parse_bool_expr(condition).unwrap_or_else(|e| panic!("cannot parse '{}': {}", condition, e))
+}
+
+fn request_chain(request: &Value) -> Chain {
+    Chain {
+        branches: request["branches"]
+            .as_array()
+            .expect("branches array")
+            .iter()
+            .map(|b| {
+                (
+                    parse(&b["condition"]),
+                    b["output"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect(),
+        default: request["default"].as_str().map(str::to_string),
+    }
+}
+
+fn response_chain(response: &Value) -> Chain {
+    Chain {
+        branches: response["simplified_branches"]
+            .as_array()
+            .expect("simplified_branches array")
+            .iter()
+            .map(|b| {
+                let condition = if b["is_default"] == true {
+                    BoolExpr::Const(true)
+                } else {
+                    parse(&b["condition"])
+                };
+                (condition, b["output"].as_str().unwrap().to_string())
+            })
+            .collect(),
+        default: None,
+    }
+}
+
+/// First input on which the request and the simplified chain decide differently
+///
+/// Inputs the request leaves undecided (no branch and no default) are don't-cares to the
+/// simplifier, so any decision is accepted there.
+fn find_mismatch(request: &Value, response: &Value) -> Option<String> {
+    let domains = domains(request);
+    let (original, simplified) = (request_chain(request), response_chain(response));
+
+    let mut values: Vec<i32> = domains.iter().map(|d| d.2).collect();
+    loop {
+        let mut bools = HashMap::new();
+        let mut ints = HashMap::new();
+        for ((name, is_bool, _, _), &value) in domains.iter().zip(&values) {
+            if *is_bool {
+                bools.insert(name.clone(), value == 1);
+            } else {
+                ints.insert(name.clone(), value);
+            }
+        }
+        let (expected, actual) = (
+            original.decide(&bools, &ints),
+            simplified.decide(&bools, &ints),
+        );
+        if expected.is_some() && expected != actual {
+            return Some(format!(
+                "bools {:?} ints {:?}: request gives {:?}, response {:?}",
+                bools, ints, expected, actual
+            ));
+        }
+
+        // Next assignment, odometer style
+        let position = (0..domains.len()).find(|&i| values[i] < domains[i].3)?;
+        values[position] += 1;
+        for i in 0..position {
+            values[i] = domains[i].2;
+        }
+    }
+}
+
+/// The response pretty-printed with sorted keys; it carries no timings or other fields
+/// that change from run to run, and the conditions come out in a fixed order
+fn normalize(response: &str) -> String {
+    let value: Value = serde_json::from_str(response).expect("response is JSON");
+    serde_json::to_string_pretty(&value).unwrap() + "\n"
+}
+
+fn corpus() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/simplify_corpus");
+    let mut requests: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with(".request.json"))
+        .collect();
+    requests.sort();
+    requests
+}
+
+#[test]
+fn test_simplify_corpus() {
+    let requests = corpus();
+    assert!(
+        requests.len() >= 12,
+        "corpus has {} fixtures",
+        requests.len()
+    );
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+
+    let mut failures = Vec::new();
+    for request_path in &requests {
+        let name = request_path.file_name().unwrap().to_string_lossy();
+        let name = name.trim_end_matches(".request.json").to_string();
+        let input = std::fs::read_to_string(request_path).unwrap();
+        let output = simplify_from_json(&input)
+            .unwrap_or_else(|e| panic!("{}: simplify failed: {}", name, e));
+        let actual = normalize(&output);
+
+        let request: Value = serde_json::from_str(&input).unwrap();
+        let response: Value = serde_json::from_str(&actual).unwrap();
+        if let Some(mismatch) = find_mismatch(&request, &response) {
+            failures.push(format!("{}: not equivalent: {}", name, mismatch));
+        }
+
+        let golden = request_path.with_file_name(format!("{}.response.json", name));
+        if update {
+            std::fs::write(&golden, &actual).unwrap();
+        }
+        match std::fs::read_to_string(&golden) {
+            Ok(expected) if expected == actual => {}
+            Ok(_) => failures.push(format!(
+                "{}: response differs from {}",
+                name,
+                golden.display()
+            )),
+            Err(e) => failures.push(format!("{}: missing {}: {}", name, golden.display(), e)),
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_mismatch_is_detected() {
+    let request: Value = serde_json::from_str(
+        r#"{"variables": {"a": "boolean", "n": {"type": "integer", "min": 0, "max": 3}},
+            "branches": [{"condition": "a && n >= 2", "output": "x"}], "default": "y"}"#,
+    )
+    .unwrap();
+    let response = |condition: &str| -> Value {
+        serde_json::from_str(&format!(
+            r#"{{"simplified_branches": [
+                {{"condition": "{}", "output": "x", "is_default": false}},
+                {{"condition": "true", "output": "y", "is_default": true}}]}}"#,
+            condition
+        ))
+        .unwrap()
+    };
+    assert_eq!(find_mismatch(&request, &response("n >= 2 && a")), None);
+    let mismatch = find_mismatch(&request, &response("a && n >= 1")).unwrap();
+    assert!(mismatch.contains("\"n\": 1"), "{}", mismatch);
+}
@@ -0,0 +1,34 @@
+//! Tests of the JavaScript bindings, run in a wasm runtime:
+//! `wasm-pack test --node -- --no-default-features --features wasm --test wasm`
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use qm_agent::wasm::{minimize, simplify_json};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_minimize_3_variables() {
+    let result = minimize(vec![1.0, 3.0, 7.0], vec![2.0, 4.0], 3).unwrap();
+    let sop = js_sys::Reflect::get(&result, &JsValue::from_str("minimized_sop")).unwrap();
+    assert_eq!(sop.as_string().unwrap(), "AC' + AB");
+
+    assert!(minimize(vec![8.0], vec![], 3).is_err());
+    assert!(minimize(vec![1.5], vec![], 3).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_simplify_request() {
+    let request = r#"{
+        "variables": {"a": "boolean", "b": "boolean"},
+        "branches": [
+            {"condition": "a && b", "output": "1"},
+            {"condition": "a && !b", "output": "1"}
+        ],
+        "default": "0"
+    }"#;
+    let response: serde_json::Value =
+        serde_json::from_str(&simplify_json(request).unwrap()).unwrap();
+    assert_eq!(response["simplified_branches"][0]["condition"], "a");
+
+    assert!(simplify_json("{").is_err());
+}